@@ -0,0 +1,816 @@
+"""Type stubs for the `_simulor_rust` native extension.
+
+Declares the classes the `simulor` package imports from the extension;
+any other name resolves to `Any`.
+"""
+
+from collections.abc import Iterable, Iterator, Sequence
+from datetime import date, datetime, time, timedelta, tzinfo
+from decimal import Decimal
+from os import PathLike
+from pathlib import Path
+from types import TracebackType
+from typing import Any, ClassVar, Literal, Self, TypeVar, overload
+
+import numpy as np
+from numpy.typing import ArrayLike, NDArray
+
+from simulor.core.events import MarketEvent
+from simulor.data.providers.base import DataProvider
+from simulor.types import (
+    Instrument,
+    MarketData,
+    OptionType,
+    OrderSide,
+    OrderSpec,
+    QuoteBar,
+    QuoteTick,
+    Resolution,
+    TradeBar,
+)
+
+__version__: str
+
+def __getattr__(name: str) -> Any: ...
+
+# Decimal values, also accepted as int, float or str
+_Value = Decimal | int | float | str
+# For annotations in classes with a `date` attribute, which shadows the type
+_Date = date
+_Record = TypeVar("_Record", bound=MarketData)
+# `(price, size)` of a book level
+_Level = tuple[Decimal, Decimal]
+# Option model results: a float for scalar inputs, an array of their shape otherwise
+_Result = float | NDArray[np.float64]
+# An `OptionType` or its value, or a sequence of them
+_OptionType = OptionType | str | Sequence[OptionType | str]
+
+# Data providers
+class FileDataProvider(DataProvider):
+    @property
+    def data_path(self) -> Path: ...
+    @property
+    def resolution(self) -> Resolution: ...
+    @property
+    def date_column(self) -> str: ...
+    @property
+    def symbol_column(self) -> str: ...
+    @property
+    def instrument_type_column(self) -> str: ...
+    @property
+    def timezone_info(self) -> tzinfo: ...
+    @property
+    def files(self) -> list[Path]: ...
+
+class CSVDataProvider(FileDataProvider):
+    def __init__(
+        self,
+        path: str | PathLike[str],
+        resolution: Resolution,
+        date_column: str = "timestamp",
+        symbol_column: str = "symbol",
+        instrument_type_column: str = "instrument_type",
+        timezone: str = "UTC",
+        validate: bool = True,
+    ) -> None: ...
+    def __iter__(self) -> Iterator[MarketEvent]: ...
+
+class ParquetDataProvider(FileDataProvider):
+    def __init__(
+        self,
+        path: str | PathLike[str],
+        resolution: Resolution,
+        date_column: str = "timestamp",
+        symbol_column: str = "symbol",
+        instrument_type_column: str = "instrument_type",
+        timezone: str = "UTC",
+        start: datetime | None = None,
+        end: datetime | None = None,
+        symbols: Iterable[str] | None = None,
+        validate: bool = True,
+    ) -> None: ...
+    def __iter__(self) -> Iterator[MarketEvent]: ...
+
+class ArrowIpcDataProvider(FileDataProvider):
+    def __init__(
+        self,
+        path: str | PathLike[str],
+        resolution: Resolution,
+        date_column: str = "timestamp",
+        symbol_column: str = "symbol",
+        instrument_type_column: str = "instrument_type",
+        timezone: str = "UTC",
+        start: datetime | None = None,
+        end: datetime | None = None,
+        symbols: Iterable[str] | None = None,
+        validate: bool = True,
+    ) -> None: ...
+    def __iter__(self) -> Iterator[MarketEvent]: ...
+
+# Tick store
+class TickStoreDataProvider(DataProvider):
+    def __init__(
+        self,
+        path: str | PathLike[str],
+        timezone: str = "UTC",
+        start: datetime | None = None,
+        end: datetime | None = None,
+        symbols: Iterable[str] | None = None,
+    ) -> None: ...
+    @property
+    def data_path(self) -> Path: ...
+    @property
+    def timezone_info(self) -> tzinfo: ...
+    @property
+    def instruments(self) -> list[Instrument]: ...
+    @property
+    def row_count(self) -> int: ...
+    @property
+    def chunk_count(self) -> int: ...
+    @property
+    def start(self) -> datetime | None: ...
+    @property
+    def end(self) -> datetime | None: ...
+    def __iter__(self) -> Iterator[MarketEvent]: ...
+
+class TickStoreWriter:
+    def __init__(self, path: str | PathLike[str]) -> None: ...
+    def add(self, record: MarketData) -> None: ...
+    def add_event(self, event: MarketEvent) -> None: ...
+    def write(self, provider: Iterable[MarketEvent]) -> int: ...
+    def close(self) -> None: ...
+    def abort(self) -> None: ...
+    @property
+    def closed(self) -> bool: ...
+    @property
+    def rows_written(self) -> int: ...
+    def __enter__(self) -> Self: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_value: BaseException | None = None,
+        traceback: TracebackType | None = None,
+    ) -> bool: ...
+
+# Bar consolidators
+class BarConsolidator:
+    def update(self, record: MarketData) -> list[TradeBar | QuoteBar]: ...
+    def advance(self, time: datetime) -> list[TradeBar | QuoteBar]: ...
+    def flush(self) -> list[TradeBar | QuoteBar]: ...
+    def reset(self) -> None: ...
+
+class TimeBarConsolidator(BarConsolidator):
+    def __init__(
+        self,
+        resolution: Resolution,
+        timezone: str | None = None,
+        session_start: time | str | None = None,
+        session_end: time | str | None = None,
+        fill_forward: bool = False,
+        calendar: ExchangeCalendar | str | None = None,
+    ) -> None: ...
+    @property
+    def resolution(self) -> Resolution: ...
+    @property
+    def timezone_info(self) -> tzinfo: ...
+    @property
+    def fill_forward(self) -> bool: ...
+    @property
+    def calendar(self) -> ExchangeCalendar | None: ...
+
+class TickBarConsolidator(BarConsolidator):
+    def __init__(self, threshold: int) -> None: ...
+    @property
+    def threshold(self) -> int: ...
+
+class VolumeBarConsolidator(BarConsolidator):
+    def __init__(self, threshold: _Value) -> None: ...
+    @property
+    def threshold(self) -> Decimal: ...
+
+class DollarBarConsolidator(BarConsolidator):
+    def __init__(self, threshold: _Value) -> None: ...
+    @property
+    def threshold(self) -> Decimal: ...
+
+class ImbalanceBarConsolidator(BarConsolidator):
+    def __init__(
+        self,
+        metric: Literal["tick", "volume", "dollar"] = "tick",
+        expected_ticks: int = 100,
+        bar_span: int = 20,
+        tick_span: int = 1000,
+        adaptive: bool = True,
+    ) -> None: ...
+    @property
+    def metric(self) -> Literal["tick", "volume", "dollar"]: ...
+    @property
+    def expected_ticks(self) -> int: ...
+
+class RunBarConsolidator(BarConsolidator):
+    def __init__(
+        self,
+        metric: Literal["tick", "volume", "dollar"] = "tick",
+        expected_ticks: int = 100,
+        bar_span: int = 20,
+        tick_span: int = 1000,
+        adaptive: bool = True,
+    ) -> None: ...
+    @property
+    def metric(self) -> Literal["tick", "volume", "dollar"]: ...
+    @property
+    def expected_ticks(self) -> int: ...
+
+class ConsolidatingDataProvider(DataProvider):
+    def __init__(
+        self,
+        source: Iterable[MarketEvent],
+        consolidators: BarConsolidator | Iterable[BarConsolidator],
+        include_source: bool = True,
+    ) -> None: ...
+    @property
+    def source(self) -> Iterable[MarketEvent]: ...
+    def __iter__(self) -> Iterator[MarketEvent]: ...
+
+# Exchange calendars
+class TradingSession:
+    @property
+    def date(self) -> _Date: ...
+    @property
+    def pre_open(self) -> datetime: ...
+    @property
+    def open(self) -> datetime: ...
+    @property
+    def break_start(self) -> datetime | None: ...
+    @property
+    def break_end(self) -> datetime | None: ...
+    @property
+    def close(self) -> datetime: ...
+    @property
+    def post_close(self) -> datetime: ...
+    @property
+    def early_close(self) -> bool: ...
+    def is_open(self, dt: datetime, extended: bool = False) -> bool: ...
+
+class ExchangeCalendar:
+    def __init__(self, name: str) -> None: ...
+    @staticmethod
+    def names() -> list[str]: ...
+    @property
+    def name(self) -> str: ...
+    @property
+    def timezone_info(self) -> tzinfo: ...
+    def is_session(self, date: date) -> bool: ...
+    def is_early_close(self, date: date) -> bool: ...
+    def holidays(self, year: int) -> dict[date, str]: ...
+    def session(self, date: date) -> TradingSession | None: ...
+    def sessions(self, start: date, end: date) -> list[TradingSession]: ...
+    def is_open(self, dt: datetime, extended: bool = False) -> bool: ...
+    def session_date(self, dt: datetime) -> date | None: ...
+    def session_at(self, dt: datetime, extended: bool = False) -> TradingSession | None: ...
+    def next_session(self, dt: datetime, extended: bool = False) -> TradingSession | None: ...
+    def previous_session(self, dt: datetime, extended: bool = False) -> TradingSession | None: ...
+    def next_open(self, dt: datetime, extended: bool = False) -> datetime | None: ...
+    def next_close(self, dt: datetime, extended: bool = False) -> datetime | None: ...
+    def previous_open(self, dt: datetime, extended: bool = False) -> datetime | None: ...
+    def previous_close(self, dt: datetime, extended: bool = False) -> datetime | None: ...
+    def filter_event(self, event: MarketEvent, extended: bool = False) -> MarketEvent | None: ...
+
+# Corporate actions
+class CorporateActionType:
+    SPLIT: ClassVar[CorporateActionType]
+    DIVIDEND: ClassVar[CorporateActionType]
+    SYMBOL_CHANGE: ClassVar[CorporateActionType]
+
+class PriceAdjustment:
+    RAW: ClassVar[PriceAdjustment]
+    BACK_ADJUSTED: ClassVar[PriceAdjustment]
+    TOTAL_RETURN: ClassVar[PriceAdjustment]
+
+class CorporateAction:
+    @staticmethod
+    def split(instrument: Instrument, ex_date: date, ratio: _Value) -> CorporateAction: ...
+    @staticmethod
+    def dividend(instrument: Instrument, ex_date: date, amount: _Value) -> CorporateAction: ...
+    @staticmethod
+    def symbol_change(instrument: Instrument, ex_date: date, new_instrument: Instrument) -> CorporateAction: ...
+    @property
+    def kind(self) -> CorporateActionType: ...
+    @property
+    def instrument(self) -> Instrument: ...
+    @property
+    def ex_date(self) -> date: ...
+    @property
+    def ratio(self) -> Decimal | None: ...
+    @property
+    def amount(self) -> Decimal | None: ...
+    @property
+    def new_instrument(self) -> Instrument | None: ...
+
+class CorporateActionStore:
+    def __init__(self, actions: Iterable[CorporateAction] | None = None, timezone: str = "UTC") -> None: ...
+    @staticmethod
+    def from_csv(path: str | PathLike[str], timezone: str = "UTC") -> CorporateActionStore: ...
+    @property
+    def timezone_info(self) -> tzinfo: ...
+    def add(self, action: CorporateAction) -> None: ...
+    def actions(self, instrument: Instrument | None = None) -> list[CorporateAction]: ...
+    def between(self, start: datetime | None = None, end: datetime | None = None) -> list[CorporateAction]: ...
+    def count(self, instrument: Instrument, as_of: datetime | None = None) -> int: ...
+    def adjust(
+        self,
+        records: Sequence[_Record],
+        as_of: datetime | None = None,
+        adjustment: PriceAdjustment = ...,
+        instrument: Instrument | None = None,
+    ) -> list[_Record]: ...
+    def __len__(self) -> int: ...
+
+# Data quality
+class QualityIssue:
+    CROSSED_QUOTE: ClassVar[QualityIssue]
+    LOCKED_QUOTE: ClassVar[QualityIssue]
+    NON_POSITIVE_PRICE: ClassVar[QualityIssue]
+    OHLC_INCONSISTENT: ClassVar[QualityIssue]
+    DUPLICATE_TIMESTAMP: ClassVar[QualityIssue]
+    OUT_OF_ORDER: ClassVar[QualityIssue]
+    STALE_QUOTE: ClassVar[QualityIssue]
+    PRICE_SPIKE: ClassVar[QualityIssue]
+    @property
+    def name(self) -> str: ...
+    def __lt__(self, value: QualityIssue, /) -> bool: ...
+
+class QualityAction:
+    DROP: ClassVar[QualityAction]
+    REPAIR: ClassVar[QualityAction]
+    FLAG: ClassVar[QualityAction]
+    def __lt__(self, value: QualityAction, /) -> bool: ...
+
+class SpikeMethod:
+    MAD: ClassVar[SpikeMethod]
+    Z_SCORE: ClassVar[SpikeMethod]
+    def __lt__(self, value: SpikeMethod, /) -> bool: ...
+
+class DataQualityPolicy:
+    def __init__(
+        self,
+        default: QualityAction = ...,
+        actions: dict[QualityIssue, QualityAction] | None = None,
+        spike_method: SpikeMethod = ...,
+        spike_threshold: float | None = 10.0,
+        spike_window: int = 50,
+        stale_after: timedelta | None = None,
+    ) -> None: ...
+    def action(self, issue: QualityIssue) -> QualityAction: ...
+    @property
+    def default(self) -> QualityAction: ...
+    @property
+    def actions(self) -> dict[QualityIssue, QualityAction]: ...
+    @property
+    def spike_method(self) -> SpikeMethod: ...
+    @property
+    def spike_threshold(self) -> float | None: ...
+    @property
+    def spike_window(self) -> int: ...
+    @property
+    def stale_after(self) -> timedelta | None: ...
+
+class InstrumentQuality:
+    @property
+    def instrument(self) -> Instrument: ...
+    @property
+    def records(self) -> int: ...
+    @property
+    def dropped(self) -> int: ...
+    @property
+    def repaired(self) -> int: ...
+    @property
+    def flagged(self) -> int: ...
+    @property
+    def issues(self) -> dict[QualityIssue, int]: ...
+
+class DataQualityReport:
+    @property
+    def instruments(self) -> list[Instrument]: ...
+    @property
+    def records(self) -> int: ...
+    @property
+    def dropped(self) -> int: ...
+    @property
+    def repaired(self) -> int: ...
+    @property
+    def flagged(self) -> int: ...
+    @property
+    def issues(self) -> dict[QualityIssue, int]: ...
+    def __getitem__(self, key: Instrument, /) -> InstrumentQuality: ...
+    def __contains__(self, key: object, /) -> bool: ...
+    def __len__(self) -> int: ...
+    def __iter__(self) -> Iterator[InstrumentQuality]: ...
+
+class DataQualityProvider(DataProvider):
+    def __init__(self, source: Iterable[MarketEvent], policy: DataQualityPolicy | None = None) -> None: ...
+    @property
+    def source(self) -> Iterable[MarketEvent]: ...
+    @property
+    def policy(self) -> DataQualityPolicy: ...
+    @property
+    def report(self) -> DataQualityReport: ...
+    def __iter__(self) -> Iterator[MarketEvent]: ...
+
+# Look-ahead guard
+class LookAheadError(RuntimeError): ...
+
+class LookAheadGuard:
+    def __init__(
+        self, delays: dict[Resolution, timedelta] | None = None, bar_end: bool = True, strict: bool = False
+    ) -> None: ...
+    @property
+    def delays(self) -> dict[Resolution, timedelta]: ...
+    @property
+    def bar_end(self) -> bool: ...
+    @property
+    def strict(self) -> bool: ...
+    def lag(self, resolution: Resolution) -> timedelta: ...
+
+class SeriesView:
+    def __len__(self) -> int: ...
+    @overload
+    def __getitem__(self, key: int, /) -> Any: ...
+    @overload
+    def __getitem__(self, key: slice, /) -> list[Any]: ...
+    def __iter__(self) -> Iterator[Any]: ...
+    def count(self, value: object) -> int: ...
+    def index(self, value: object, start: int = 0, stop: int | None = None) -> int: ...
+
+class TimeFencedStore:
+    def __init__(self, guard: LookAheadGuard | None = None) -> None: ...
+    @property
+    def guard(self) -> LookAheadGuard | None: ...
+    @property
+    def now(self) -> datetime | None: ...
+    def advance(self, time: datetime) -> None: ...
+    def update(self, market_event: MarketEvent) -> None: ...
+    def trade_ticks(self, instrument: Instrument) -> SeriesView | None: ...
+    def quote_ticks(self, instrument: Instrument) -> SeriesView | None: ...
+    def trade_bars(self, instrument: Instrument, resolution: Resolution) -> SeriesView | None: ...
+    def quote_bars(self, instrument: Instrument, resolution: Resolution) -> SeriesView | None: ...
+    def book_updates(self, instrument: Instrument) -> SeriesView | None: ...
+    def order_book(self, instrument: Instrument) -> OrderBookL2 | None: ...
+    def latest(self, instrument: Instrument) -> MarketData | None: ...
+    def instruments(self) -> list[Instrument]: ...
+    def rename(self, old: Instrument, new: Instrument) -> None: ...
+
+# Index membership
+class MembershipStore:
+    def __init__(
+        self,
+        intervals: Iterable[tuple[Instrument | str, date | str, date | str | None]] | None = None,
+        timezone: str = "UTC",
+    ) -> None: ...
+    @staticmethod
+    def from_csv(path: str | PathLike[str], timezone: str = "UTC") -> MembershipStore: ...
+    @staticmethod
+    def from_parquet(path: str | PathLike[str], timezone: str = "UTC") -> MembershipStore: ...
+    @property
+    def timezone_info(self) -> tzinfo: ...
+    def add(self, instrument: Instrument | str, start: date | str, end: date | str | None = None) -> None: ...
+    def members(self, as_of: datetime) -> list[Instrument]: ...
+    def is_member(self, instrument: Instrument | str, as_of: datetime) -> bool: ...
+    def instruments(self) -> list[Instrument]: ...
+    def intervals(self, instrument: Instrument | str | None = None) -> list[tuple[Instrument, date, date | None]]: ...
+    def __len__(self) -> int: ...
+
+# Order books
+class SequenceGapError(RuntimeError): ...
+
+class BookUpdate(MarketData):
+    def __init__(
+        self,
+        timestamp: datetime,
+        instrument: Instrument,
+        resolution: Resolution,
+        bids: Iterable[tuple[_Value, _Value]] | None = None,
+        asks: Iterable[tuple[_Value, _Value]] | None = None,
+        sequence: int | None = None,
+        snapshot: bool = False,
+    ) -> None: ...
+    @property
+    def bids(self) -> tuple[_Level, ...]: ...
+    @property
+    def asks(self) -> tuple[_Level, ...]: ...
+    @property
+    def sequence(self) -> int | None: ...
+    @property
+    def snapshot(self) -> bool: ...
+
+class OrderBookL2:
+    def __init__(self, instrument: Instrument, strict: bool = False) -> None: ...
+    def apply(self, update: BookUpdate) -> bool: ...
+    def clear(self) -> None: ...
+    def copy(self) -> OrderBookL2: ...
+    @property
+    def instrument(self) -> Instrument: ...
+    @property
+    def timestamp(self) -> datetime | None: ...
+    @property
+    def sequence(self) -> int | None: ...
+    @property
+    def synced(self) -> bool: ...
+    @property
+    def gaps(self) -> int: ...
+    def bids(self, depth: int | None = None) -> list[_Level]: ...
+    def asks(self, depth: int | None = None) -> list[_Level]: ...
+    def cumulative_bids(self, depth: int | None = None) -> list[_Level]: ...
+    def cumulative_asks(self, depth: int | None = None) -> list[_Level]: ...
+    @property
+    def best_bid(self) -> _Level | None: ...
+    @property
+    def best_ask(self) -> _Level | None: ...
+    @property
+    def spread(self) -> Decimal | None: ...
+    @property
+    def mid_price(self) -> Decimal | None: ...
+    @property
+    def microprice(self) -> Decimal | None: ...
+    def imbalance(self, depth: int = 1) -> Decimal | None: ...
+    def to_quote_tick(self) -> QuoteTick | None: ...
+
+class OrderBookL3:
+    def __init__(self, instrument: Instrument) -> None: ...
+    def add(self, order_id: int, side: OrderSide | str, price: _Value, size: _Value) -> None: ...
+    def execute(self, order_id: int, size: _Value) -> Decimal: ...
+    def cancel(self, order_id: int, size: _Value) -> Decimal: ...
+    def delete(self, order_id: int) -> None: ...
+    def replace(self, order_id: int, new_order_id: int, price: _Value, size: _Value) -> None: ...
+    def order(self, order_id: int) -> tuple[OrderSide, Decimal, Decimal] | None: ...
+    def queue_position(self, order_id: int) -> tuple[int, Decimal] | None: ...
+    def queue(self, side: OrderSide | str, price: _Value) -> list[tuple[int, Decimal]]: ...
+    def bids(self, depth: int | None = None) -> list[_Level]: ...
+    def asks(self, depth: int | None = None) -> list[_Level]: ...
+    @property
+    def best_bid(self) -> _Level | None: ...
+    @property
+    def best_ask(self) -> _Level | None: ...
+    @property
+    def instrument(self) -> Instrument: ...
+    @property
+    def timestamp(self) -> datetime | None: ...
+    def to_order_book_l2(self) -> OrderBookL2: ...
+    def clear(self) -> None: ...
+    def copy(self) -> OrderBookL3: ...
+    def __len__(self) -> int: ...
+    def __contains__(self, key: int, /) -> bool: ...
+
+# NASDAQ TotalView-ITCH
+class ItchDataProvider(DataProvider):
+    def __init__(
+        self,
+        path: str | PathLike[str],
+        date: date | str | None = None,
+        timezone: str = "America/New_York",
+        start: datetime | None = None,
+        end: datetime | None = None,
+        symbols: Iterable[str] | None = None,
+    ) -> None: ...
+    @property
+    def data_path(self) -> Path: ...
+    @property
+    def timezone_info(self) -> tzinfo: ...
+    @property
+    def files(self) -> list[Path]: ...
+    def __iter__(self) -> Iterator[MarketEvent]: ...
+
+# Databento DBN
+class DbnDataProvider(DataProvider):
+    def __init__(
+        self,
+        path: str | PathLike[str],
+        timezone: str = "UTC",
+        start: datetime | None = None,
+        end: datetime | None = None,
+        symbols: Iterable[str] | None = None,
+    ) -> None: ...
+    @property
+    def data_path(self) -> Path: ...
+    @property
+    def timezone_info(self) -> tzinfo: ...
+    @property
+    def files(self) -> list[Path]: ...
+    @property
+    def metadata(self) -> list[dict[str, Any]]: ...
+    def __iter__(self) -> Iterator[MarketEvent]: ...
+
+# Continuous futures
+class RollRule:
+    CALENDAR: ClassVar[RollRule]
+    VOLUME: ClassVar[RollRule]
+    OPEN_INTEREST: ClassVar[RollRule]
+
+class BackAdjustment:
+    NONE: ClassVar[BackAdjustment]
+    DIFFERENCE: ClassVar[BackAdjustment]
+    RATIO: ClassVar[BackAdjustment]
+
+class RollEvent:
+    @property
+    def time(self) -> datetime: ...
+    @property
+    def instrument(self) -> Instrument: ...
+    @property
+    def from_contract(self) -> Instrument: ...
+    @property
+    def to_contract(self) -> Instrument: ...
+    @property
+    def from_price(self) -> Decimal: ...
+    @property
+    def to_price(self) -> Decimal: ...
+    @property
+    def adjustment(self) -> BackAdjustment: ...
+    @property
+    def amount(self) -> Decimal: ...
+
+class ContinuousFuture:
+    def __init__(
+        self,
+        root: str,
+        rule: RollRule = ...,
+        days_before_expiry: int = 5,
+        adjustment: BackAdjustment = ...,
+        exchange: str | None = None,
+        currency: str = "USD",
+        expiries: dict[Instrument | str, date | str] | None = None,
+    ) -> None: ...
+    @property
+    def root(self) -> str: ...
+    @property
+    def instrument(self) -> Instrument: ...
+    @property
+    def rule(self) -> RollRule: ...
+    @property
+    def days_before_expiry(self) -> int: ...
+    @property
+    def adjustment(self) -> BackAdjustment: ...
+    @property
+    def active_contract(self) -> Instrument | None: ...
+    @property
+    def contracts(self) -> list[Instrument]: ...
+    @property
+    def rolls(self) -> list[RollEvent]: ...
+    def between(self, start: datetime | None = None, end: datetime | None = None) -> list[RollEvent]: ...
+    def count(self, as_of: datetime | None = None) -> int: ...
+    def add_open_interest(self, contract: Instrument | str, date: date | str, open_interest: _Value) -> None: ...
+    def load_open_interest(self, path: str | PathLike[str]) -> None: ...
+    def adjust(self, records: Sequence[_Record], as_of: datetime | None = None) -> list[_Record]: ...
+
+class ContinuousFuturesProvider(DataProvider):
+    def __init__(self, source: Iterable[MarketEvent], futures: Sequence[ContinuousFuture]) -> None: ...
+    @property
+    def source(self) -> Iterable[MarketEvent]: ...
+    @property
+    def futures(self) -> list[ContinuousFuture]: ...
+    def __iter__(self) -> Iterator[MarketEvent]: ...
+
+# Option pricing
+class Greeks:
+    @property
+    def price(self) -> _Result: ...
+    @property
+    def delta(self) -> _Result: ...
+    @property
+    def gamma(self) -> _Result: ...
+    @property
+    def vega(self) -> _Result: ...
+    @property
+    def theta(self) -> _Result: ...
+    @property
+    def rho(self) -> _Result: ...
+    @property
+    def vanna(self) -> _Result: ...
+    @property
+    def vomma(self) -> _Result: ...
+    @property
+    def charm(self) -> _Result: ...
+    def to_dict(self) -> dict[str, _Result]: ...
+
+class BlackScholesMerton:
+    def __init__(self) -> None: ...
+    def price(
+        self,
+        option_type: _OptionType,
+        spot: ArrayLike,
+        strike: ArrayLike,
+        time: ArrayLike,
+        rate: ArrayLike,
+        volatility: ArrayLike,
+        dividend_yield: ArrayLike | None = None,
+    ) -> _Result: ...
+    def greeks(
+        self,
+        option_type: _OptionType,
+        spot: ArrayLike,
+        strike: ArrayLike,
+        time: ArrayLike,
+        rate: ArrayLike,
+        volatility: ArrayLike,
+        dividend_yield: ArrayLike | None = None,
+    ) -> Greeks: ...
+    def implied_volatility(
+        self,
+        option_type: _OptionType,
+        price: ArrayLike,
+        spot: ArrayLike,
+        strike: ArrayLike,
+        time: ArrayLike,
+        rate: ArrayLike,
+        dividend_yield: ArrayLike | None = None,
+    ) -> _Result: ...
+
+class Black76:
+    def __init__(self) -> None: ...
+    def price(
+        self,
+        option_type: _OptionType,
+        forward: ArrayLike,
+        strike: ArrayLike,
+        time: ArrayLike,
+        rate: ArrayLike,
+        volatility: ArrayLike,
+    ) -> _Result: ...
+    def greeks(
+        self,
+        option_type: _OptionType,
+        forward: ArrayLike,
+        strike: ArrayLike,
+        time: ArrayLike,
+        rate: ArrayLike,
+        volatility: ArrayLike,
+    ) -> Greeks: ...
+    def implied_volatility(
+        self,
+        option_type: _OptionType,
+        price: ArrayLike,
+        forward: ArrayLike,
+        strike: ArrayLike,
+        time: ArrayLike,
+        rate: ArrayLike,
+    ) -> _Result: ...
+
+class BinomialTree:
+    def __init__(self, steps: int = 200, american: bool = True) -> None: ...
+    @property
+    def steps(self) -> int: ...
+    @property
+    def american(self) -> bool: ...
+    def price(
+        self,
+        option_type: _OptionType,
+        spot: ArrayLike,
+        strike: ArrayLike,
+        time: ArrayLike,
+        rate: ArrayLike,
+        volatility: ArrayLike,
+        dividend_yield: ArrayLike | None = None,
+    ) -> _Result: ...
+    def greeks(
+        self,
+        option_type: _OptionType,
+        spot: ArrayLike,
+        strike: ArrayLike,
+        time: ArrayLike,
+        rate: ArrayLike,
+        volatility: ArrayLike,
+        dividend_yield: ArrayLike | None = None,
+    ) -> Greeks: ...
+    def implied_volatility(
+        self,
+        option_type: _OptionType,
+        price: ArrayLike,
+        spot: ArrayLike,
+        strike: ArrayLike,
+        time: ArrayLike,
+        rate: ArrayLike,
+        dividend_yield: ArrayLike | None = None,
+    ) -> _Result: ...
+
+# Trailing stops
+class TrailingStopTracker:
+    def __init__(
+        self,
+        side: OrderSide | str,
+        trailing_amount: _Value | None = None,
+        trailing_percent: _Value | None = None,
+        stop_price: _Value | None = None,
+    ) -> None: ...
+    @staticmethod
+    def for_order(order_spec: OrderSpec) -> TrailingStopTracker: ...
+    def update(self, price: _Value) -> bool: ...
+    @property
+    def side(self) -> OrderSide: ...
+    @property
+    def trailing_amount(self) -> Decimal | None: ...
+    @property
+    def trailing_percent(self) -> Decimal | None: ...
+    @property
+    def stop_price(self) -> Decimal | None: ...
+    @property
+    def triggered(self) -> bool: ...
@@ -0,0 +1,114 @@
+//! Streaming consolidator interface and its Python base class
+
+use std::sync::Mutex;
+
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+
+use crate::data::schema::RecordKind;
+use crate::types::fixed::Fixed;
+use crate::types::market_data::{MarketData, Resolution};
+use crate::types::time::datetime_to_nanos;
+
+/// A bar a consolidator has finished, with the time it became complete
+pub struct ClosedBar {
+    pub end: i64,
+    pub bar: Py<PyAny>,
+}
+
+/// Incremental bar builder fed from a chronological record stream
+///
+/// Callers advance the clock to a record's time before feeding it, so bars
+/// that end at or before that time are closed first.
+pub trait Consolidate: Send + Sync {
+    /// Close bars that end at or before `now`
+    fn advance(&mut self, py: Python<'_>, now: i64, out: &mut Vec<ClosedBar>) -> PyResult<()>;
+
+    /// Feed one record; returns whether the consolidator used it
+    fn update(&mut self, record: &Bound<'_, PyAny>, out: &mut Vec<ClosedBar>) -> PyResult<bool>;
+
+    /// Close every bar still open, as at the end of the stream
+    fn flush(&mut self, py: Python<'_>, out: &mut Vec<ClosedBar>) -> PyResult<()>;
+
+    /// A consolidator with the same settings and no state
+    fn fresh(&self, py: Python<'_>) -> Box<dyn Consolidate>;
+}
+
+/// Build a native bar record timed at the bar's start
+pub fn build_bar(
+    py: Python<'_>,
+    kind: RecordKind,
+    instrument: &Py<PyAny>,
+    resolution: Resolution,
+    timestamp: Py<PyAny>,
+    start: i64,
+    values: &[Fixed],
+) -> PyResult<Py<PyAny>> {
+    let base =
+        MarketData::from_parts(timestamp, instrument.clone_ref(py), resolution.to_py(py)?.unbind(), resolution, start);
+    Ok(kind.build(py, base, values, None)?.unbind())
+}
+
+/// Bars in the order they closed
+fn bar_list<'py>(py: Python<'py>, mut closed: Vec<ClosedBar>) -> PyResult<Bound<'py, PyList>> {
+    closed.sort_by_key(|bar| bar.end);
+    PyList::new(py, closed.into_iter().map(|bar| bar.bar))
+}
+
+/// Base class of the streaming bar consolidators
+///
+/// Feed records in chronological order with `update()`; each call returns
+/// the bars that closed. `ConsolidatingDataProvider` drives consolidators
+/// from a data provider instead.
+#[pyclass(module = "_simulor_rust", subclass, frozen)]
+pub struct BarConsolidator {
+    state: Mutex<Box<dyn Consolidate>>,
+}
+
+impl BarConsolidator {
+    pub fn new(state: Box<dyn Consolidate>) -> Self {
+        BarConsolidator {
+            state: Mutex::new(state),
+        }
+    }
+
+    /// A fresh consolidator with this one's settings
+    pub fn fresh(&self, py: Python<'_>) -> Box<dyn Consolidate> {
+        self.state.lock().unwrap().fresh(py)
+    }
+}
+
+#[pymethods]
+impl BarConsolidator {
+    /// Feed a record; returns the bars that closed, oldest first
+    fn update<'py>(&self, record: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyList>> {
+        let py = record.py();
+        let now = record.cast::<MarketData>()?.get().timestamp_nanos(py)?;
+        let mut closed = Vec::new();
+        let mut state = self.state.lock().unwrap();
+        state.advance(py, now, &mut closed)?;
+        state.update(record, &mut closed)?;
+        bar_list(py, closed)
+    }
+
+    /// Move the clock to `time`; returns the bars that closed, oldest first
+    fn advance<'py>(&self, time: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyList>> {
+        let py = time.py();
+        let mut closed = Vec::new();
+        self.state.lock().unwrap().advance(py, datetime_to_nanos(time)?, &mut closed)?;
+        bar_list(py, closed)
+    }
+
+    /// Close and return every bar still open
+    fn flush<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let mut closed = Vec::new();
+        self.state.lock().unwrap().flush(py, &mut closed)?;
+        bar_list(py, closed)
+    }
+
+    /// Drop all open bars and history
+    fn reset(&self, py: Python<'_>) {
+        let mut state = self.state.lock().unwrap();
+        *state = state.fresh(py);
+    }
+}
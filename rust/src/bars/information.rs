@@ -0,0 +1,475 @@
+//! Information-driven bars
+//!
+//! Bars sampled by trading activity rather than the clock, after López de
+//! Prado, *Advances in Financial Machine Learning*, ch. 2: tick, volume and
+//! dollar bars close once a fixed amount of activity has traded; imbalance
+//! and run bars close once signed activity exceeds its expected value.
+//!
+//! Only trade ticks are consumed. Each trade is signed by its
+//! `TickDirection` when known, otherwise by the tick rule: up-ticks buy,
+//! down-ticks sell, and unchanged prices repeat the previous sign. Trades
+//! before the first price change are unsigned and do not count towards
+//! imbalances or runs. Bars are `TradeBar`s at `TICK` resolution,
+//! timestamped at their first trade.
+
+use std::collections::HashMap;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::bars::consolidator::{build_bar, BarConsolidator, ClosedBar, Consolidate};
+use crate::data::schema::RecordKind;
+use crate::types::fixed::Fixed;
+use crate::types::instrument::InstrumentId;
+use crate::types::market_data::{Resolution, TickDirection, TradeTick};
+use crate::types::price::{extract_fixed, to_decimal};
+
+/// What a trade contributes to a bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Metric {
+    Tick,
+    Volume,
+    Dollar,
+}
+
+impl Metric {
+    fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "tick" => Ok(Metric::Tick),
+            "volume" => Ok(Metric::Volume),
+            "dollar" => Ok(Metric::Dollar),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown bar metric: {other} (expected 'tick', 'volume' or 'dollar')"
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Metric::Tick => "tick",
+            Metric::Volume => "volume",
+            Metric::Dollar => "dollar",
+        }
+    }
+
+    fn value(self, tick: &TradeTick) -> PyResult<Fixed> {
+        Ok(match self {
+            Metric::Tick => Fixed::from_int(1),
+            Metric::Volume => tick.size,
+            Metric::Dollar => tick.price.checked_mul(tick.size)?,
+        })
+    }
+}
+
+/// Settings of the expected-value estimates behind imbalance and run bars
+#[derive(Debug, Clone, Copy)]
+struct Expectation {
+    /// Initial expected number of trades per bar
+    ticks: f64,
+    /// EWMA weight of each new bar length; zero keeps E[T] fixed
+    bar_alpha: f64,
+    /// EWMA weight of each new trade
+    tick_alpha: f64,
+}
+
+impl Expectation {
+    fn new(expected_ticks: u32, bar_span: u32, tick_span: u32, adaptive: bool) -> PyResult<Self> {
+        if expected_ticks == 0 || bar_span == 0 || tick_span == 0 {
+            return Err(PyValueError::new_err("expected_ticks, bar_span and tick_span must be positive"));
+        }
+        Ok(Expectation {
+            ticks: expected_ticks as f64,
+            bar_alpha: if adaptive { 2.0 / (bar_span as f64 + 1.0) } else { 0.0 },
+            tick_alpha: 2.0 / (tick_span as f64 + 1.0),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Rule {
+    /// Close once the metric sums to `threshold`
+    Threshold(Metric, Fixed),
+    /// Close once |Σ b·v| reaches E[T]·|E[b·v]|
+    Imbalance(Metric, Expectation),
+    /// Close once max(Σ buys, Σ sells) reaches E[T]·max(P[buy]·E[v|buy], P[sell]·E[v|sell])
+    Run(Metric, Expectation),
+}
+
+fn ewma(estimate: &mut Option<f64>, alpha: f64, x: f64) {
+    *estimate = Some(match *estimate {
+        Some(e) => e + alpha * (x - e),
+        None => x,
+    });
+}
+
+/// Running estimates for one instrument
+#[derive(Debug, Default)]
+struct Estimates {
+    ticks: Option<f64>,
+    signed: Option<f64>,
+    buy_share: Option<f64>,
+    buy_value: Option<f64>,
+    sell_value: Option<f64>,
+}
+
+struct OpenBar {
+    timestamp: Py<PyAny>,
+    start: i64,
+    /// Time of the latest trade
+    last: i64,
+    /// open, high, low, close, volume
+    values: [Fixed; 5],
+    ticks: u64,
+    total: Fixed,
+    imbalance: f64,
+    buys: f64,
+    sells: f64,
+}
+
+struct Slot {
+    instrument: Py<PyAny>,
+    bar: Option<OpenBar>,
+    last_price: Option<Fixed>,
+    sign: i8,
+    estimates: Estimates,
+}
+
+impl Slot {
+    /// Sign of a trade: its direction if known, else the tick rule
+    fn sign(&mut self, tick: &TradeTick) -> i8 {
+        let sign = match tick.native_direction {
+            Some(TickDirection::Buy) => 1,
+            Some(TickDirection::Sell) => -1,
+            _ => match self.last_price.map(|last| tick.price.cmp(&last)) {
+                Some(std::cmp::Ordering::Greater) => 1,
+                Some(std::cmp::Ordering::Less) => -1,
+                _ => self.sign,
+            },
+        };
+        self.last_price = Some(tick.price);
+        self.sign = sign;
+        sign
+    }
+}
+
+/// Information-driven bar state
+pub struct InformationBars {
+    rule: Rule,
+    slots: Vec<Slot>,
+    index: HashMap<InstrumentId, usize>,
+}
+
+impl InformationBars {
+    fn new(rule: Rule) -> Self {
+        InformationBars {
+            rule,
+            slots: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    fn emit(&self, py: Python<'_>, slot: &Slot, bar: &OpenBar, end: i64) -> PyResult<ClosedBar> {
+        let record = build_bar(
+            py,
+            RecordKind::TradeBar,
+            &slot.instrument,
+            Resolution::Tick,
+            bar.timestamp.clone_ref(py),
+            bar.start,
+            &bar.values,
+        )?;
+        Ok(ClosedBar { end, bar: record })
+    }
+
+    /// Whether the open bar of `slot` has reached its closing condition
+    fn is_complete(rule: &Rule, slot: &Slot, bar: &OpenBar) -> bool {
+        match rule {
+            Rule::Threshold(_, threshold) => bar.total >= *threshold,
+            Rule::Imbalance(_, expectation) => {
+                let Some(signed) = slot.estimates.signed else {
+                    return false;
+                };
+                let ticks = slot.estimates.ticks.unwrap_or(expectation.ticks);
+                bar.imbalance.abs() >= ticks * signed.abs()
+            }
+            Rule::Run(_, expectation) => {
+                let Some(share) = slot.estimates.buy_share else {
+                    return false;
+                };
+                let buy = slot.estimates.buy_value.unwrap_or(0.0);
+                let sell = slot.estimates.sell_value.unwrap_or(0.0);
+                let ticks = slot.estimates.ticks.unwrap_or(expectation.ticks);
+                bar.buys.max(bar.sells) >= ticks * (share * buy).max((1.0 - share) * sell)
+            }
+        }
+    }
+}
+
+impl Consolidate for InformationBars {
+    fn advance(&mut self, _py: Python<'_>, _now: i64, _out: &mut Vec<ClosedBar>) -> PyResult<()> {
+        // Closing is driven by trades, not time
+        Ok(())
+    }
+
+    fn update(&mut self, record: &Bound<'_, PyAny>, out: &mut Vec<ClosedBar>) -> PyResult<bool> {
+        let py = record.py();
+        let Ok(tick) = record.cast::<TradeTick>() else {
+            return Ok(false);
+        };
+        let base = tick.as_super().get();
+        let tick = tick.get();
+        let t = base.timestamp_nanos(py)?;
+        let id = base.instrument_id(py)?;
+        let index = match self.index.get(&id) {
+            Some(&index) => index,
+            None => {
+                self.slots.push(Slot {
+                    instrument: base.instrument.clone_ref(py),
+                    bar: None,
+                    last_price: None,
+                    sign: 0,
+                    estimates: Estimates::default(),
+                });
+                self.index.insert(id, self.slots.len() - 1);
+                self.slots.len() - 1
+            }
+        };
+        let rule = self.rule;
+        let (Rule::Threshold(metric, _) | Rule::Imbalance(metric, _) | Rule::Run(metric, _)) = rule;
+        let value = metric.value(tick)?;
+        let slot = &mut self.slots[index];
+        let sign = slot.sign(tick);
+        let bar = slot.bar.get_or_insert_with(|| OpenBar {
+            timestamp: base.timestamp.clone_ref(py),
+            start: t,
+            last: t,
+            values: [tick.price, tick.price, tick.price, tick.price, Fixed::ZERO],
+            ticks: 0,
+            total: Fixed::ZERO,
+            imbalance: 0.0,
+            buys: 0.0,
+            sells: 0.0,
+        });
+        bar.values[1] = bar.values[1].max(tick.price);
+        bar.values[2] = bar.values[2].min(tick.price);
+        bar.values[3] = tick.price;
+        bar.values[4] = bar.values[4].checked_add(tick.size)?;
+        bar.last = t;
+        bar.ticks += 1;
+        bar.total = bar.total.checked_add(value)?;
+        let v = value.to_f64();
+        if sign != 0 {
+            bar.imbalance += sign as f64 * v;
+            if sign > 0 {
+                bar.buys += v;
+            } else {
+                bar.sells += v;
+            }
+            if let Rule::Imbalance(_, expectation) | Rule::Run(_, expectation) = rule {
+                let estimates = &mut slot.estimates;
+                ewma(&mut estimates.signed, expectation.tick_alpha, sign as f64 * v);
+                ewma(&mut estimates.buy_share, expectation.tick_alpha, f64::from(u8::from(sign > 0)));
+                let side = if sign > 0 {
+                    &mut estimates.buy_value
+                } else {
+                    &mut estimates.sell_value
+                };
+                ewma(side, expectation.tick_alpha, v);
+            }
+        }
+        let slot = &self.slots[index];
+        let bar = slot.bar.as_ref().expect("bar opened above");
+        if InformationBars::is_complete(&rule, slot, bar) {
+            out.push(self.emit(py, slot, bar, t)?);
+            let slot = &mut self.slots[index];
+            let ticks = slot.bar.take().map_or(0, |bar| bar.ticks);
+            if let Rule::Imbalance(_, expectation) | Rule::Run(_, expectation) = rule {
+                let estimate = slot.estimates.ticks.get_or_insert(expectation.ticks);
+                *estimate += expectation.bar_alpha * (ticks as f64 - *estimate);
+            }
+        }
+        Ok(true)
+    }
+
+    fn flush(&mut self, py: Python<'_>, out: &mut Vec<ClosedBar>) -> PyResult<()> {
+        for index in 0..self.slots.len() {
+            if let Some(bar) = self.slots[index].bar.take() {
+                out.push(self.emit(py, &self.slots[index], &bar, bar.last)?);
+            }
+        }
+        Ok(())
+    }
+
+    fn fresh(&self, _py: Python<'_>) -> Box<dyn Consolidate> {
+        Box::new(InformationBars::new(self.rule))
+    }
+}
+
+fn positive_threshold(threshold: &Bound<'_, PyAny>) -> PyResult<Fixed> {
+    let threshold = extract_fixed(threshold)?;
+    if !threshold.is_positive() {
+        return Err(PyValueError::new_err("Threshold must be positive"));
+    }
+    Ok(threshold)
+}
+
+fn threshold_consolidator<T: pyo3::PyClass<BaseType = BarConsolidator>>(
+    metric: Metric,
+    threshold: Fixed,
+    sub: T,
+) -> PyClassInitializer<T> {
+    let state = InformationBars::new(Rule::Threshold(metric, threshold));
+    PyClassInitializer::from(BarConsolidator::new(Box::new(state))).add_subclass(sub)
+}
+
+/// Bars of a fixed number of trades
+#[pyclass(module = "_simulor_rust", extends = BarConsolidator, frozen)]
+pub struct TickBarConsolidator {
+    threshold: u64,
+}
+
+#[pymethods]
+impl TickBarConsolidator {
+    #[new]
+    fn py_new(threshold: u64) -> PyResult<PyClassInitializer<Self>> {
+        if threshold == 0 {
+            return Err(PyValueError::new_err("Threshold must be positive"));
+        }
+        let fixed = Fixed::from_int(i128::from(threshold));
+        Ok(threshold_consolidator(Metric::Tick, fixed, TickBarConsolidator { threshold }))
+    }
+
+    /// Trades per bar
+    #[getter]
+    fn threshold(&self) -> u64 {
+        self.threshold
+    }
+}
+
+/// Bars closing once traded volume reaches a threshold
+#[pyclass(module = "_simulor_rust", extends = BarConsolidator, frozen)]
+pub struct VolumeBarConsolidator {
+    threshold: Fixed,
+}
+
+#[pymethods]
+impl VolumeBarConsolidator {
+    #[new]
+    fn py_new(threshold: &Bound<'_, PyAny>) -> PyResult<PyClassInitializer<Self>> {
+        let threshold = positive_threshold(threshold)?;
+        Ok(threshold_consolidator(Metric::Volume, threshold, VolumeBarConsolidator { threshold }))
+    }
+
+    /// Volume per bar
+    #[getter]
+    fn threshold<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.threshold)
+    }
+}
+
+/// Bars closing once traded value (price × size) reaches a threshold
+#[pyclass(module = "_simulor_rust", extends = BarConsolidator, frozen)]
+pub struct DollarBarConsolidator {
+    threshold: Fixed,
+}
+
+#[pymethods]
+impl DollarBarConsolidator {
+    #[new]
+    fn py_new(threshold: &Bound<'_, PyAny>) -> PyResult<PyClassInitializer<Self>> {
+        let threshold = positive_threshold(threshold)?;
+        Ok(threshold_consolidator(Metric::Dollar, threshold, DollarBarConsolidator { threshold }))
+    }
+
+    /// Traded value per bar
+    #[getter]
+    fn threshold<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.threshold)
+    }
+}
+
+/// Tick, volume or dollar imbalance bars
+///
+/// A bar closes once its signed `metric` total |Σ b·v| reaches the expected
+/// imbalance E[T]·|E[b·v]|. E[T] starts at `expected_ticks` and follows an
+/// EWMA of bar lengths over `bar_span` bars; E[b·v] is an EWMA over
+/// `tick_span` trades. On balanced flow the adaptive E[T] tends to shrink
+/// towards one-trade bars; `adaptive=False` holds it at `expected_ticks`.
+#[pyclass(module = "_simulor_rust", extends = BarConsolidator, frozen)]
+pub struct ImbalanceBarConsolidator {
+    metric: Metric,
+    expected_ticks: u32,
+}
+
+#[pymethods]
+impl ImbalanceBarConsolidator {
+    #[new]
+    #[pyo3(signature = (metric="tick", expected_ticks=100, bar_span=20, tick_span=1000, adaptive=true))]
+    fn py_new(
+        metric: &str,
+        expected_ticks: u32,
+        bar_span: u32,
+        tick_span: u32,
+        adaptive: bool,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let metric = Metric::from_name(metric)?;
+        let rule = Rule::Imbalance(metric, Expectation::new(expected_ticks, bar_span, tick_span, adaptive)?);
+        let state = InformationBars::new(rule);
+        Ok(PyClassInitializer::from(BarConsolidator::new(Box::new(state)))
+            .add_subclass(ImbalanceBarConsolidator { metric, expected_ticks }))
+    }
+
+    /// "tick", "volume" or "dollar"
+    #[getter]
+    fn metric(&self) -> &'static str {
+        self.metric.name()
+    }
+
+    /// Initial expected number of trades per bar
+    #[getter]
+    fn expected_ticks(&self) -> u32 {
+        self.expected_ticks
+    }
+}
+
+/// Tick, volume or dollar run bars
+///
+/// A bar closes once the larger of its buy and sell `metric` totals reaches
+/// E[T]·max(P[buy]·E[v|buy], P[sell]·E[v|sell]), with the same estimates
+/// and arguments as `ImbalanceBarConsolidator`.
+#[pyclass(module = "_simulor_rust", extends = BarConsolidator, frozen)]
+pub struct RunBarConsolidator {
+    metric: Metric,
+    expected_ticks: u32,
+}
+
+#[pymethods]
+impl RunBarConsolidator {
+    #[new]
+    #[pyo3(signature = (metric="tick", expected_ticks=100, bar_span=20, tick_span=1000, adaptive=true))]
+    fn py_new(
+        metric: &str,
+        expected_ticks: u32,
+        bar_span: u32,
+        tick_span: u32,
+        adaptive: bool,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let metric = Metric::from_name(metric)?;
+        let rule = Rule::Run(metric, Expectation::new(expected_ticks, bar_span, tick_span, adaptive)?);
+        let state = InformationBars::new(rule);
+        Ok(PyClassInitializer::from(BarConsolidator::new(Box::new(state)))
+            .add_subclass(RunBarConsolidator { metric, expected_ticks }))
+    }
+
+    /// "tick", "volume" or "dollar"
+    #[getter]
+    fn metric(&self) -> &'static str {
+        self.metric.name()
+    }
+
+    /// Initial expected number of trades per bar
+    #[getter]
+    fn expected_ticks(&self) -> u32 {
+        self.expected_ticks
+    }
+}
@@ -0,0 +1,35 @@
+//! Streaming bar consolidation
+//!
+//! Consolidators turn a chronological stream of records into bars as the
+//! stream advances: time bars, or information-driven bars sampled by
+//! trading activity; `ConsolidatingDataProvider` inserts those bars into
+//! another provider's `MarketEvent`s.
+
+pub mod consolidator;
+pub mod information;
+pub mod provider;
+pub mod session;
+pub mod time;
+
+use pyo3::prelude::*;
+
+pub use consolidator::BarConsolidator;
+pub use information::{
+    DollarBarConsolidator, ImbalanceBarConsolidator, RunBarConsolidator, TickBarConsolidator, VolumeBarConsolidator,
+};
+pub use provider::{ConsolidatingDataProvider, ConsolidatingIterator};
+pub use time::TimeBarConsolidator;
+
+/// Register the consolidators on the extension module
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<BarConsolidator>()?;
+    m.add_class::<TimeBarConsolidator>()?;
+    m.add_class::<TickBarConsolidator>()?;
+    m.add_class::<VolumeBarConsolidator>()?;
+    m.add_class::<DollarBarConsolidator>()?;
+    m.add_class::<ImbalanceBarConsolidator>()?;
+    m.add_class::<RunBarConsolidator>()?;
+    m.add_class::<ConsolidatingDataProvider>()?;
+    m.add_class::<ConsolidatingIterator>()?;
+    Ok(())
+}
@@ -0,0 +1,164 @@
+//! Data provider that adds consolidated bars to another provider's stream
+
+use std::collections::VecDeque;
+
+use pyo3::prelude::*;
+use pyo3::types::PyIterator;
+
+use crate::bars::consolidator::{BarConsolidator, ClosedBar, Consolidate};
+use crate::events::market_event::MarketEvent;
+use crate::types::time::{datetime_to_nanos, nanos_to_datetime};
+
+/// Wrap a data provider and emit consolidated bars as they close
+///
+/// Bars are added to the first event at or after their end. A bar that
+/// closes between two source events gets an event of its own, timed at the
+/// bar's end, so the stream stays chronological. Bars still open when the
+/// source runs out are flushed at the end.
+///
+/// Args: `source` provider, `consolidators` to feed, and `include_source`;
+/// when false, records a consolidator used are dropped from the stream.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct ConsolidatingDataProvider {
+    source: Py<PyAny>,
+    consolidators: Vec<Py<BarConsolidator>>,
+    include_source: bool,
+}
+
+#[pymethods]
+impl ConsolidatingDataProvider {
+    #[new]
+    #[pyo3(signature = (source, consolidators, include_source=true))]
+    fn py_new(source: Py<PyAny>, consolidators: &Bound<'_, PyAny>, include_source: bool) -> PyResult<Self> {
+        let consolidators = if let Ok(one) = consolidators.cast::<BarConsolidator>() {
+            vec![one.clone().unbind()]
+        } else {
+            consolidators
+                .try_iter()?
+                .map(|item| Ok(item?.cast_into::<BarConsolidator>()?.unbind()))
+                .collect::<PyResult<_>>()?
+        };
+        Ok(ConsolidatingDataProvider {
+            source,
+            consolidators,
+            include_source,
+        })
+    }
+
+    /// The wrapped provider
+    #[getter]
+    fn source(&self, py: Python<'_>) -> Py<PyAny> {
+        self.source.clone_ref(py)
+    }
+
+    /// Return a new iterator; every pass starts from fresh consolidators
+    fn __iter__(&self, py: Python<'_>) -> PyResult<ConsolidatingIterator> {
+        Ok(ConsolidatingIterator {
+            source: self.source.bind(py).try_iter()?.unbind(),
+            consolidators: self.consolidators.iter().map(|c| c.get().fresh(py)).collect(),
+            include_source: self.include_source,
+            tzinfo: None,
+            pending: VecDeque::new(),
+            done: false,
+        })
+    }
+}
+
+/// Iterator over a `ConsolidatingDataProvider`
+#[pyclass(module = "_simulor_rust")]
+pub struct ConsolidatingIterator {
+    source: Py<PyIterator>,
+    consolidators: Vec<Box<dyn Consolidate>>,
+    include_source: bool,
+    /// Zone of the source's event times, for events created here
+    tzinfo: Option<Py<PyAny>>,
+    pending: VecDeque<Py<PyAny>>,
+    done: bool,
+}
+
+impl ConsolidatingIterator {
+    /// Queue one event per distinct bar end before `now`; returns the bars
+    /// ending exactly at `now`
+    fn queue_closed(
+        &mut self,
+        py: Python<'_>,
+        mut closed: Vec<ClosedBar>,
+        now: Option<i64>,
+    ) -> PyResult<Vec<ClosedBar>> {
+        closed.sort_by_key(|bar| bar.end);
+        let split = closed.partition_point(|bar| now.map_or(true, |now| bar.end < now));
+        let current = closed.split_off(split);
+        let mut closed = closed.into_iter().peekable();
+        while let Some(first) = closed.next() {
+            let time = nanos_to_datetime(py, first.end, self.tzinfo.as_ref().map(|tz| tz.bind(py)))?;
+            let mut event = MarketEvent::new(time.unbind());
+            event.add_record(first.bar.bind(py))?;
+            while let Some(bar) = closed.next_if(|bar| bar.end == first.end) {
+                event.add_record(bar.bar.bind(py))?;
+            }
+            self.pending.push_back(Py::new(py, event)?.into_any());
+        }
+        Ok(current)
+    }
+
+    fn process(&mut self, event: &Bound<'_, PyAny>) -> PyResult<()> {
+        let py = event.py();
+        let time = event.getattr("time")?;
+        let now = datetime_to_nanos(&time)?;
+        self.tzinfo = Some(time.getattr("tzinfo")?.unbind()).filter(|tz| !tz.is_none(py));
+        let mut closed = Vec::new();
+        for consolidator in &mut self.consolidators {
+            consolidator.advance(py, now, &mut closed)?;
+        }
+        let records = event.call_method0("flatten")?;
+        let mut kept = Vec::new();
+        for record in records.try_iter()? {
+            let record = record?;
+            let mut used = false;
+            for consolidator in &mut self.consolidators {
+                used |= consolidator.update(&record, &mut closed)?;
+            }
+            if self.include_source || !used {
+                kept.push(record);
+            }
+        }
+        let current = self.queue_closed(py, closed, Some(now))?;
+        let mut output = MarketEvent::new(time.unbind());
+        for record in kept.iter().chain(current.iter().map(|bar| bar.bar.bind(py))) {
+            output.add_record(record)?;
+        }
+        if output.count() > 0 {
+            self.pending.push_back(Py::new(py, output)?.into_any());
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl ConsolidatingIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Some(event));
+            }
+            if self.done {
+                return Ok(None);
+            }
+            match self.source.bind(py).clone().next() {
+                Some(event) => self.process(&event?)?,
+                None => {
+                    self.done = true;
+                    let mut closed = Vec::new();
+                    for consolidator in &mut self.consolidators {
+                        consolidator.flush(py, &mut closed)?;
+                    }
+                    self.queue_closed(py, closed, None)?;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Trading session hours that time bars align to
+
+use std::sync::Arc;
+
+use chrono::NaiveTime;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::calendar::Calendar;
+use crate::data::timestamp::TimestampParser;
+use crate::interop::time_type;
+
+/// One trading session, as epoch nanoseconds `[open, close)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Session {
+    pub open: i64,
+    pub close: i64,
+    /// Midday break, `[start, end)`
+    pub pause: Option<(i64, i64)>,
+}
+
+/// Where time bars take their sessions from
+#[derive(Clone)]
+pub enum Sessions {
+    /// The same hours every day
+    Hours(SessionHours),
+    /// An exchange calendar's regular sessions, skipping holidays and
+    /// honouring early closes
+    Calendar(Arc<Calendar>),
+}
+
+impl Sessions {
+    /// Whether trading runs around the clock
+    pub fn is_continuous(&self) -> bool {
+        match self {
+            Sessions::Hours(hours) => hours.is_continuous(),
+            Sessions::Calendar(calendar) => calendar.is_continuous(),
+        }
+    }
+
+    /// The session trading at `t`, or `None` outside trading hours and
+    /// during a midday break
+    pub fn session(&self, t: i64) -> Option<Session> {
+        match self {
+            Sessions::Hours(hours) => hours.session(t),
+            Sessions::Calendar(calendar) => {
+                let day = calendar.session_at(t, false).filter(|day| day.is_open(t, false))?;
+                Some(Session {
+                    open: day.open,
+                    close: day.close,
+                    pause: day.lunch,
+                })
+            }
+        }
+    }
+}
+
+/// Daily session hours in a timezone
+///
+/// Without hours the market is continuous and every local calendar day is
+/// one session. A close at or before the open makes the session run over
+/// midnight, e.g. 18:00 to 17:00 for futures.
+#[derive(Debug, Clone, Copy)]
+pub struct SessionHours {
+    parser: TimestampParser,
+    hours: Option<(NaiveTime, NaiveTime)>,
+}
+
+impl SessionHours {
+    pub fn new(parser: TimestampParser, hours: Option<(NaiveTime, NaiveTime)>) -> Self {
+        SessionHours { parser, hours }
+    }
+
+    /// Session hours from the optional `session_start`/`session_end` arguments
+    pub fn from_py(timezone: &str, start: Option<&Bound<'_, PyAny>>, end: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
+        let hours = match (start, end) {
+            (None, None) => None,
+            (Some(start), Some(end)) => {
+                let hours = (extract_time(start)?, extract_time(end)?);
+                if hours.0 == hours.1 {
+                    return Err(PyValueError::new_err("Session start and end must differ"));
+                }
+                Some(hours)
+            }
+            _ => return Err(PyValueError::new_err("session_start and session_end must be given together")),
+        };
+        Ok(SessionHours::new(TimestampParser::new(timezone)?, hours))
+    }
+
+    /// Whether trading runs around the clock
+    pub fn is_continuous(&self) -> bool {
+        self.hours.is_none()
+    }
+
+    pub fn hours(&self) -> Option<(NaiveTime, NaiveTime)> {
+        self.hours
+    }
+
+    /// The session containing `t`, or `None` outside trading hours
+    pub fn session(&self, t: i64) -> Option<Session> {
+        let local = self.parser.to_local(t);
+        let (date, time) = (local.date(), local.time());
+        let (open, close) = self.hours.unwrap_or((NaiveTime::MIN, NaiveTime::MIN));
+        let open_date = if open < close {
+            if time < open || time >= close {
+                return None;
+            }
+            date
+        } else if time >= open {
+            date
+        } else if time < close {
+            date.pred_opt()?
+        } else {
+            return None;
+        };
+        let close_date = if close <= open {
+            open_date.succ_opt()?
+        } else {
+            open_date
+        };
+        let session = Session {
+            open: self.parser.localize(&open_date.and_time(open))?,
+            close: self.parser.localize(&close_date.and_time(close))?,
+            pause: None,
+        };
+        // Around DST transitions the wall-clock check above can disagree
+        // with the localized bounds
+        (session.open <= t && t < session.close).then_some(session)
+    }
+}
+
+/// A `datetime.time`, or an ISO string such as "09:30"
+pub fn extract_time(obj: &Bound<'_, PyAny>) -> PyResult<NaiveTime> {
+    let py = obj.py();
+    let time = if obj.is_instance_of::<pyo3::types::PyString>() {
+        time_type(py)?.call_method1("fromisoformat", (obj,))?
+    } else {
+        obj.clone()
+    };
+    let field = |name: &str| -> PyResult<u32> { time.getattr(name)?.extract() };
+    NaiveTime::from_hms_micro_opt(field("hour")?, field("minute")?, field("second")?, field("microsecond")?)
+        .ok_or_else(|| PyValueError::new_err("Invalid session time"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3_600_000_000_000;
+    /// 2024-01-02 00:00 UTC
+    const JAN_2: i64 = 1_704_153_600_000_000_000;
+
+    fn hours(open: (u32, u32), close: (u32, u32)) -> SessionHours {
+        let time = |(h, m)| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        SessionHours::new(TimestampParser::from(chrono_tz::America::New_York), Some((time(open), time(close))))
+    }
+
+    #[test]
+    fn continuous_sessions_are_local_days() {
+        let hours = SessionHours::new(TimestampParser::from(chrono_tz::America::New_York), None);
+        assert!(hours.is_continuous());
+        // 03:00 UTC on Jan 2 is still Jan 1 in New York
+        let session = hours.session(JAN_2 + 3 * HOUR).unwrap();
+        assert_eq!((session.open, session.close), (JAN_2 - 19 * HOUR, JAN_2 + 5 * HOUR));
+        assert_eq!(session.pause, None);
+    }
+
+    #[test]
+    fn regular_hours_exclude_the_close() {
+        let hours = hours((9, 30), (16, 0));
+        let open = JAN_2 + 14 * HOUR + HOUR / 2;
+        let close = JAN_2 + 21 * HOUR;
+        assert_eq!(
+            hours.session(open),
+            Some(Session {
+                open,
+                close,
+                pause: None
+            })
+        );
+        assert_eq!(hours.session(close - 1).map(|s| s.open), Some(open));
+        assert_eq!(hours.session(open - 1), None);
+        assert_eq!(hours.session(close), None);
+    }
+
+    #[test]
+    fn overnight_sessions_span_midnight_and_dst() {
+        let hours = hours((18, 0), (17, 0));
+        // 02:00 on Jan 2 in New York belongs to the session opened on Jan 1
+        let session = hours.session(JAN_2 + 7 * HOUR).unwrap();
+        assert_eq!((session.open, session.close), (JAN_2 - HOUR, JAN_2 + 22 * HOUR));
+        // Between the close and the reopen
+        assert_eq!(hours.session(JAN_2 + 22 * HOUR + HOUR / 2), None);
+
+        // The session over the March 10 switch to daylight time is an hour short
+        let mar_10 = 1_710_028_800_000_000_000;
+        let session = hours.session(mar_10 + 12 * HOUR).unwrap();
+        assert_eq!(session.open, mar_10 - HOUR);
+        assert_eq!(session.close - session.open, 22 * HOUR);
+    }
+}
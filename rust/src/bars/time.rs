@@ -0,0 +1,370 @@
+//! Time bar consolidation
+//!
+//! Trade ticks and trade bars roll up into `TradeBar`s, quote ticks and
+//! quote bars into `QuoteBar`s, per instrument. Bars are anchored at the
+//! session open and cut at the session close, so with 09:30-16:00 hours an
+//! hourly bar runs 09:30-10:30 and the daily bar covers the whole session.
+//! Without session hours bars are anchored at local midnight. With an
+//! exchange calendar, holidays have no bars, early closes cut the last bar
+//! short, and intraday bars restart after a midday break.
+
+use std::collections::HashMap;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::bars::consolidator::{build_bar, BarConsolidator, ClosedBar, Consolidate};
+use crate::bars::session::{SessionHours, Sessions};
+use crate::calendar::ExchangeCalendar;
+use crate::data::schema::{payload, RecordKind, MAX_WIDTH};
+use crate::interop::zoneinfo_type;
+use crate::types::fixed::Fixed;
+use crate::types::instrument::InstrumentId;
+use crate::types::market_data::{MarketData, Resolution};
+use crate::types::time::nanos_to_datetime;
+
+/// One bar's time span, and the close of its session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Period {
+    start: i64,
+    end: i64,
+    session_close: i64,
+    /// Where the following bar starts: `end`, or the end of the midday
+    /// break that `end` begins
+    next: i64,
+}
+
+/// Running OHLC values of one bar: `open, high, low, close` for trades
+/// (then volume) and for each of bid and ask on quotes
+type Values = [Fixed; MAX_WIDTH];
+
+struct Slot {
+    kind: RecordKind,
+    instrument: Py<PyAny>,
+    open: Option<(Period, Values)>,
+    /// Last closed bar, the base for forward-filling
+    last: Option<(Period, Values)>,
+}
+
+impl Slot {
+    /// OHLC groups of the bar's value array
+    fn groups(&self) -> &'static [usize] {
+        match self.kind {
+            RecordKind::QuoteBar => &[0, 4],
+            _ => &[0],
+        }
+    }
+
+    fn merge(&mut self, period: Period, sample: &Values) -> PyResult<()> {
+        let groups = self.groups();
+        let kind = self.kind;
+        let Some((_, values)) = self.open.as_mut() else {
+            self.open = Some((period, *sample));
+            return Ok(());
+        };
+        for &g in groups {
+            values[g + 1] = values[g + 1].max(sample[g + 1]);
+            values[g + 2] = values[g + 2].min(sample[g + 2]);
+            values[g + 3] = sample[g + 3];
+        }
+        if kind == RecordKind::TradeBar {
+            values[4] = values[4].checked_add(sample[4])?;
+        }
+        Ok(())
+    }
+
+    /// A bar with no activity: flat at the previous close, no volume
+    fn flat(&self, previous: &Values) -> Values {
+        let mut values = [Fixed::ZERO; MAX_WIDTH];
+        for &g in self.groups() {
+            values[g..g + 4].fill(previous[g + 3]);
+        }
+        values
+    }
+}
+
+#[derive(Clone)]
+struct Settings {
+    resolution: Resolution,
+    sessions: Sessions,
+    fill_forward: bool,
+}
+
+/// Time bar consolidation state
+pub struct TimeBars {
+    settings: Settings,
+    tzinfo: Py<PyAny>,
+    slots: Vec<Slot>,
+    index: HashMap<(InstrumentId, RecordKind), usize>,
+    now: i64,
+}
+
+impl TimeBars {
+    fn new(settings: Settings, tzinfo: Py<PyAny>) -> Self {
+        TimeBars {
+            settings,
+            tzinfo,
+            slots: Vec::new(),
+            index: HashMap::new(),
+            now: i64::MIN,
+        }
+    }
+
+    /// The bar period containing `t`, or `None` outside trading hours
+    fn period(&self, t: i64) -> Option<Period> {
+        let session = self.settings.sessions.session(t)?;
+        let length = self.settings.resolution.nanos();
+        let (open, close) = match session.pause {
+            Some((_, resume)) if t >= resume => (resume, session.close),
+            Some((pause, _)) => (session.open, pause),
+            None => (session.open, session.close),
+        };
+        let (start, end) = if self.settings.resolution == Resolution::Daily {
+            (session.open, session.close)
+        } else {
+            let start = open + (t - open) / length * length;
+            (start, (start + length).min(close))
+        };
+        let next = match session.pause {
+            Some((pause, resume)) if end == pause => resume,
+            _ => end,
+        };
+        Some(Period {
+            start,
+            end,
+            session_close: session.close,
+            next,
+        })
+    }
+
+    /// The period after `period`; empty periods are only filled within a
+    /// session unless trading is continuous
+    fn next_period(&self, period: &Period) -> Option<Period> {
+        if period.end >= period.session_close && !self.settings.sessions.is_continuous() {
+            return None;
+        }
+        self.period(period.next)
+    }
+
+    fn emit(&self, py: Python<'_>, slot: &Slot, period: &Period, values: &Values) -> PyResult<ClosedBar> {
+        let kind = match slot.kind {
+            RecordKind::TradeBar => RecordKind::TradeBar,
+            _ => RecordKind::QuoteBar,
+        };
+        let bar = build_bar(
+            py,
+            kind,
+            &slot.instrument,
+            self.settings.resolution,
+            nanos_to_datetime(py, period.start, Some(self.tzinfo.bind(py)))?.unbind(),
+            period.start,
+            &values[..kind.width()],
+        )?;
+        Ok(ClosedBar { end: period.end, bar })
+    }
+
+    fn close_slot(&mut self, py: Python<'_>, index: usize, out: &mut Vec<ClosedBar>) -> PyResult<()> {
+        if let Some((period, values)) = self.slots[index].open.take() {
+            out.push(self.emit(py, &self.slots[index], &period, &values)?);
+            self.slots[index].last = Some((period, values));
+        }
+        Ok(())
+    }
+}
+
+impl Consolidate for TimeBars {
+    fn advance(&mut self, py: Python<'_>, now: i64, out: &mut Vec<ClosedBar>) -> PyResult<()> {
+        if now <= self.now {
+            return Ok(());
+        }
+        self.now = now;
+        for index in 0..self.slots.len() {
+            if self.slots[index].open.is_some_and(|(period, _)| period.end <= now) {
+                self.close_slot(py, index, out)?;
+            }
+            if !self.settings.fill_forward || self.slots[index].open.is_some() {
+                continue;
+            }
+            while let Some((last, values)) = self.slots[index].last {
+                let Some(next) = self.next_period(&last).filter(|next| next.end <= now) else {
+                    break;
+                };
+                let slot = &self.slots[index];
+                let values = slot.flat(&values);
+                out.push(self.emit(py, slot, &next, &values)?);
+                self.slots[index].last = Some((next, values));
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, record: &Bound<'_, PyAny>, out: &mut Vec<ClosedBar>) -> PyResult<bool> {
+        let py = record.py();
+        let Some((kind, values, _)) = payload(record) else {
+            return Ok(false);
+        };
+        let base = record.cast::<MarketData>()?.get();
+        let target = self.settings.resolution;
+        let t = base.timestamp_nanos(py)?;
+        // Samples are placed by their last instant, so a bar that starts
+        // before the session open but ends inside it still counts
+        let (slot_kind, sample, instant) = match kind {
+            RecordKind::TradeTick => {
+                let mut sample = [values[0]; MAX_WIDTH];
+                sample[4] = values[1];
+                (RecordKind::TradeBar, sample, t)
+            }
+            RecordKind::QuoteTick => {
+                let mut sample = [values[0]; MAX_WIDTH];
+                sample[4..].fill(values[2]);
+                (RecordKind::QuoteBar, sample, t)
+            }
+            RecordKind::TradeBar | RecordKind::QuoteBar => {
+                let resolution = base.native_resolution;
+                if resolution == Resolution::Tick || resolution >= target {
+                    return Ok(false);
+                }
+                (kind, values, t + resolution.nanos() - 1)
+            }
+        };
+        let Some(period) = self.period(instant) else {
+            return Ok(false);
+        };
+        if period.end <= self.now {
+            // Arrived after its bar closed
+            return Ok(false);
+        }
+        let key = (base.instrument_id(py)?, slot_kind);
+        let index = match self.index.get(&key) {
+            Some(&index) => index,
+            None => {
+                self.slots.push(Slot {
+                    kind: slot_kind,
+                    instrument: base.instrument.clone_ref(py),
+                    open: None,
+                    last: None,
+                });
+                self.index.insert(key, self.slots.len() - 1);
+                self.slots.len() - 1
+            }
+        };
+        if self.slots[index].open.is_some_and(|(open, _)| open != period) {
+            self.close_slot(py, index, out)?;
+        }
+        self.slots[index].merge(period, &sample)?;
+        Ok(true)
+    }
+
+    fn flush(&mut self, py: Python<'_>, out: &mut Vec<ClosedBar>) -> PyResult<()> {
+        for index in 0..self.slots.len() {
+            self.close_slot(py, index, out)?;
+        }
+        Ok(())
+    }
+
+    fn fresh(&self, py: Python<'_>) -> Box<dyn Consolidate> {
+        Box::new(TimeBars::new(self.settings.clone(), self.tzinfo.clone_ref(py)))
+    }
+}
+
+/// Consolidate ticks and finer bars into time bars
+///
+/// Args: target `resolution` (SECOND to DAILY), `timezone` that bars are
+/// aligned and timestamped in, optional `session_start`/`session_end`
+/// times (`datetime.time` or "HH:MM") outside which records are ignored,
+/// `fill_forward` to emit flat zero-volume bars for empty periods instead
+/// of skipping them, and a `calendar` (an `ExchangeCalendar` or its name)
+/// to take sessions from instead of fixed hours. The timezone defaults to
+/// the calendar's, or UTC.
+#[pyclass(module = "_simulor_rust", extends = BarConsolidator, frozen)]
+pub struct TimeBarConsolidator {
+    resolution: Py<PyAny>,
+    timezone_info: Py<PyAny>,
+    fill_forward: bool,
+    calendar: Option<Py<ExchangeCalendar>>,
+}
+
+#[pymethods]
+impl TimeBarConsolidator {
+    #[new]
+    #[pyo3(signature = (resolution, timezone=None, session_start=None, session_end=None, fill_forward=false, calendar=None))]
+    fn py_new(
+        py: Python<'_>,
+        resolution: &Bound<'_, PyAny>,
+        timezone: Option<&str>,
+        session_start: Option<&Bound<'_, PyAny>>,
+        session_end: Option<&Bound<'_, PyAny>>,
+        fill_forward: bool,
+        calendar: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let native = Resolution::from_py(resolution)?;
+        if native == Resolution::Tick {
+            return Err(PyValueError::new_err("Cannot consolidate to TICK resolution"));
+        }
+        let calendar = match calendar {
+            None => None,
+            Some(calendar) => Some(match calendar.cast::<ExchangeCalendar>() {
+                Ok(calendar) => calendar.clone().unbind(),
+                Err(_) => py
+                    .get_type::<ExchangeCalendar>()
+                    .call1((calendar,))?
+                    .cast_into::<ExchangeCalendar>()?
+                    .unbind(),
+            }),
+        };
+        let (timezone, sessions) = match &calendar {
+            Some(calendar) => {
+                if session_start.is_some() || session_end.is_some() {
+                    return Err(PyValueError::new_err(
+                        "session_start and session_end cannot be combined with a calendar",
+                    ));
+                }
+                let calendar = calendar.get().calendar();
+                (timezone.unwrap_or(calendar.timezone()), Sessions::Calendar(calendar))
+            }
+            None => {
+                let timezone = timezone.unwrap_or("UTC");
+                (timezone, Sessions::Hours(SessionHours::from_py(timezone, session_start, session_end)?))
+            }
+        };
+        let settings = Settings {
+            resolution: native,
+            sessions,
+            fill_forward,
+        };
+        let timezone_info = zoneinfo_type(py)?.call1((timezone,))?.unbind();
+        let state = TimeBars::new(settings, timezone_info.clone_ref(py));
+        Ok(
+            PyClassInitializer::from(BarConsolidator::new(Box::new(state))).add_subclass(TimeBarConsolidator {
+                resolution: native.to_py(py)?.unbind(),
+                timezone_info,
+                fill_forward,
+                calendar,
+            }),
+        )
+    }
+
+    /// Resolution of the bars produced
+    #[getter]
+    fn resolution(&self, py: Python<'_>) -> Py<PyAny> {
+        self.resolution.clone_ref(py)
+    }
+
+    /// Timezone bars are aligned in
+    #[getter]
+    fn timezone_info(&self, py: Python<'_>) -> Py<PyAny> {
+        self.timezone_info.clone_ref(py)
+    }
+
+    /// Whether empty periods produce flat bars
+    #[getter]
+    fn fill_forward(&self) -> bool {
+        self.fill_forward
+    }
+
+    /// Exchange calendar the sessions come from, if any
+    #[getter]
+    fn calendar(&self, py: Python<'_>) -> Option<Py<ExchangeCalendar>> {
+        self.calendar.as_ref().map(|calendar| calendar.clone_ref(py))
+    }
+}
@@ -0,0 +1,448 @@
+//! Level 2 order book rebuilt from market-by-price updates
+
+use std::collections::BTreeMap;
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyList, PyTuple};
+
+use crate::book::update::BookUpdate;
+use crate::interop::logger;
+use crate::types::fixed::Fixed;
+use crate::types::instrument::{default_registry, InstrumentId};
+use crate::types::market_data::{new_record, MarketData, QuoteTick, Resolution};
+use crate::types::price::to_decimal;
+
+const LOGGER: &str = "simulor.data.order_book";
+
+create_exception!(
+    _simulor_rust,
+    SequenceGapError,
+    PyRuntimeError,
+    "A book update skipped sequence numbers, so updates were lost"
+);
+
+/// How an update follows the last one applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Continuity {
+    /// The next in sequence, a snapshot, or unsequenced
+    Next,
+    /// At or before the last sequence number applied
+    Stale,
+    /// Past the next sequence number
+    Gap { expected: u64 },
+}
+
+/// Price levels of both sides of one instrument's book
+pub struct Book {
+    bids: BTreeMap<Fixed, Fixed>,
+    asks: BTreeMap<Fixed, Fixed>,
+    sequence: Option<u64>,
+    synced: bool,
+    gaps: u64,
+    /// Timestamp of the last update applied, with its epoch nanoseconds
+    updated: Option<(Py<PyAny>, i64)>,
+}
+
+impl Default for Book {
+    fn default() -> Self {
+        Book {
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            sequence: None,
+            synced: true,
+            gaps: 0,
+            updated: None,
+        }
+    }
+}
+
+impl Book {
+    pub fn continuity(&self, update: &BookUpdate) -> Continuity {
+        match (update.sequence, self.sequence) {
+            (Some(received), Some(last)) if !update.snapshot => {
+                if received <= last {
+                    Continuity::Stale
+                } else if received - last > 1 {
+                    Continuity::Gap { expected: last + 1 }
+                } else {
+                    Continuity::Next
+                }
+            }
+            _ => Continuity::Next,
+        }
+    }
+
+    /// Apply `update`, stamped `timestamp`, unless it is stale
+    ///
+    /// A gap leaves the book out of sync until the next snapshot.
+    pub fn apply(&mut self, update: &BookUpdate, timestamp: Py<PyAny>, nanos: i64) -> Continuity {
+        let continuity = self.continuity(update);
+        match continuity {
+            Continuity::Stale => return continuity,
+            Continuity::Gap { .. } => {
+                self.gaps += 1;
+                self.synced = false;
+            }
+            Continuity::Next => {}
+        }
+        if update.snapshot {
+            self.bids.clear();
+            self.asks.clear();
+            self.synced = true;
+            self.sequence = update.sequence;
+        } else if update.sequence.is_some() {
+            self.sequence = update.sequence;
+        }
+        for (levels, side) in [(&update.bids, &mut self.bids), (&update.asks, &mut self.asks)] {
+            for &(price, size) in levels {
+                if size.is_zero() {
+                    side.remove(&price);
+                } else {
+                    side.insert(price, size);
+                }
+            }
+        }
+        self.updated = Some((timestamp, nanos));
+        continuity
+    }
+
+    /// A synced, unsequenced book of `(price, size)` levels, as of `updated`
+    pub fn from_levels(
+        bids: impl IntoIterator<Item = (Fixed, Fixed)>,
+        asks: impl IntoIterator<Item = (Fixed, Fixed)>,
+        updated: Option<(Py<PyAny>, i64)>,
+    ) -> Book {
+        Book {
+            bids: bids.into_iter().collect(),
+            asks: asks.into_iter().collect(),
+            updated,
+            ..Book::default()
+        }
+    }
+
+    pub fn clone_ref(&self, py: Python<'_>) -> Book {
+        Book {
+            bids: self.bids.clone(),
+            asks: self.asks.clone(),
+            sequence: self.sequence,
+            synced: self.synced,
+            gaps: self.gaps,
+            updated: self.updated.as_ref().map(|(timestamp, nanos)| (timestamp.clone_ref(py), *nanos)),
+        }
+    }
+
+    /// Bid levels, best (highest) first
+    pub fn bids(&self) -> impl Iterator<Item = (Fixed, Fixed)> + '_ {
+        self.bids.iter().rev().map(|(price, size)| (*price, *size))
+    }
+
+    /// Ask levels, best (lowest) first
+    pub fn asks(&self) -> impl Iterator<Item = (Fixed, Fixed)> + '_ {
+        self.asks.iter().map(|(price, size)| (*price, *size))
+    }
+
+    pub fn best_bid(&self) -> Option<(Fixed, Fixed)> {
+        self.bids().next()
+    }
+
+    pub fn best_ask(&self) -> Option<(Fixed, Fixed)> {
+        self.asks().next()
+    }
+
+    /// Best bid and ask, if both sides have levels and they do not cross
+    pub fn top(&self) -> Option<((Fixed, Fixed), (Fixed, Fixed))> {
+        let (bid, ask) = (self.best_bid()?, self.best_ask()?);
+        (bid.0 < ask.0).then_some((bid, ask))
+    }
+
+    pub fn microprice(&self) -> PyResult<Option<Fixed>> {
+        let Some(((bid, bid_size), (ask, ask_size))) = self.top() else {
+            return Ok(None);
+        };
+        let weighted = bid.checked_mul(ask_size)?.checked_add(ask.checked_mul(bid_size)?)?;
+        Ok(Some(weighted.checked_quotient(bid_size.checked_add(ask_size)?)?))
+    }
+
+    /// `(bid size - ask size) / (bid size + ask size)` over the top `depth`
+    /// levels of each side
+    pub fn imbalance(&self, depth: usize) -> PyResult<Option<Fixed>> {
+        let total = |levels: &mut dyn Iterator<Item = (Fixed, Fixed)>| -> PyResult<Fixed> {
+            let mut sum = Fixed::ZERO;
+            for (_, size) in levels.take(depth) {
+                sum = sum.checked_add(size)?;
+            }
+            Ok(sum)
+        };
+        let bids = total(&mut self.bids())?;
+        let asks = total(&mut self.asks())?;
+        let both = bids.checked_add(asks)?;
+        if both.is_zero() {
+            return Ok(None);
+        }
+        Ok(Some(bids.checked_sub(asks)?.checked_quotient(both)?))
+    }
+}
+
+fn level_list<'py>(
+    py: Python<'py>,
+    levels: impl Iterator<Item = (Fixed, Fixed)>,
+    depth: Option<usize>,
+    cumulative: bool,
+) -> PyResult<Bound<'py, PyList>> {
+    let mut total = Fixed::ZERO;
+    let mut items = Vec::new();
+    for (price, size) in levels.take(depth.unwrap_or(usize::MAX)) {
+        total = total.checked_add(size)?;
+        let size = if cumulative { total } else { size };
+        items.push(PyTuple::new(py, [to_decimal(py, price)?, to_decimal(py, size)?])?);
+    }
+    PyList::new(py, items)
+}
+
+fn level_tuple<'py>(py: Python<'py>, level: Option<(Fixed, Fixed)>) -> PyResult<Option<Bound<'py, PyTuple>>> {
+    level
+        .map(|(price, size)| PyTuple::new(py, [to_decimal(py, price)?, to_decimal(py, size)?]))
+        .transpose()
+}
+
+fn decimal<'py>(py: Python<'py>, value: Option<Fixed>) -> PyResult<Option<Bound<'py, PyAny>>> {
+    value.map(|value| to_decimal(py, value)).transpose()
+}
+
+/// Aggregated depth of one instrument's book (Level 2)
+///
+/// `apply` takes the instrument's `BookUpdate`s in feed order: snapshots
+/// replace the book and incremental updates set or remove price levels.
+/// When updates carry sequence numbers, a skipped number marks the book
+/// out of sync until the next snapshot, or raises `SequenceGapError` with
+/// `strict`; repeated or older numbers are ignored.
+#[pyclass(module = "_simulor_rust")]
+pub struct OrderBookL2 {
+    instrument: Py<PyAny>,
+    instrument_id: InstrumentId,
+    strict: bool,
+    book: Book,
+}
+
+impl OrderBookL2 {
+    pub fn from_book(instrument: Py<PyAny>, instrument_id: InstrumentId, book: Book) -> Self {
+        OrderBookL2 {
+            instrument,
+            instrument_id,
+            strict: false,
+            book,
+        }
+    }
+}
+
+#[pymethods]
+impl OrderBookL2 {
+    #[new]
+    #[pyo3(signature = (instrument, strict=false))]
+    fn py_new(instrument: &Bound<'_, PyAny>, strict: bool) -> PyResult<Self> {
+        let instrument_id = default_registry(instrument.py())?.get().intern_instrument(instrument)?;
+        Ok(OrderBookL2 {
+            instrument: instrument.clone().unbind(),
+            instrument_id,
+            strict,
+            book: Book::default(),
+        })
+    }
+
+    /// Apply an update; returns whether it was applied rather than ignored
+    /// as stale
+    fn apply(&mut self, update: &Bound<'_, BookUpdate>) -> PyResult<bool> {
+        let py = update.py();
+        let base = update.as_super().get();
+        if base.instrument_id(py)? != self.instrument_id {
+            return Err(PyValueError::new_err(format!(
+                "BookUpdate for {} applied to the book of {}",
+                base.instrument.bind(py).getattr("symbol")?,
+                self.instrument.bind(py).getattr("symbol")?,
+            )));
+        }
+        let received = update.get().sequence.unwrap_or_default();
+        if let Continuity::Gap { expected } = self.book.continuity(update.get()) {
+            let symbol = self.instrument.bind(py).getattr("symbol")?;
+            if self.strict {
+                return Err(SequenceGapError::new_err(format!(
+                    "{symbol} book expected sequence {expected}, got {received}"
+                )));
+            }
+            logger(py, LOGGER)?.call_method1(
+                "warning",
+                (
+                    "Sequence gap in %s book: expected %d, got %d; out of sync until the next snapshot",
+                    symbol,
+                    expected,
+                    received,
+                ),
+            )?;
+        }
+        let continuity = self.book.apply(update.get(), base.timestamp.clone_ref(py), base.timestamp_nanos(py)?);
+        Ok(continuity != Continuity::Stale)
+    }
+
+    /// Remove every level and forget the sequence
+    fn clear(&mut self) {
+        self.book = Book::default();
+    }
+
+    /// An independent copy of the book
+    fn copy(&self, py: Python<'_>) -> Self {
+        OrderBookL2 {
+            instrument: self.instrument.clone_ref(py),
+            instrument_id: self.instrument_id,
+            strict: self.strict,
+            book: self.book.clone_ref(py),
+        }
+    }
+
+    #[getter]
+    fn instrument(&self, py: Python<'_>) -> Py<PyAny> {
+        self.instrument.clone_ref(py)
+    }
+
+    /// Timestamp of the last update applied, or `None`
+    #[getter]
+    fn timestamp(&self, py: Python<'_>) -> Option<Py<PyAny>> {
+        self.book.updated.as_ref().map(|(timestamp, _)| timestamp.clone_ref(py))
+    }
+
+    /// Sequence number of the last sequenced update applied, or `None`
+    #[getter]
+    fn sequence(&self) -> Option<u64> {
+        self.book.sequence
+    }
+
+    /// False after a sequence gap, until the next snapshot
+    #[getter]
+    fn synced(&self) -> bool {
+        self.book.synced
+    }
+
+    /// Number of sequence gaps seen
+    #[getter]
+    fn gaps(&self) -> u64 {
+        self.book.gaps
+    }
+
+    /// Bid levels as `(price, size)`, best first; the top `depth` only if given
+    #[pyo3(signature = (depth=None))]
+    fn bids<'py>(&self, py: Python<'py>, depth: Option<usize>) -> PyResult<Bound<'py, PyList>> {
+        level_list(py, self.book.bids(), depth, false)
+    }
+
+    /// Ask levels as `(price, size)`, best first; the top `depth` only if given
+    #[pyo3(signature = (depth=None))]
+    fn asks<'py>(&self, py: Python<'py>, depth: Option<usize>) -> PyResult<Bound<'py, PyList>> {
+        level_list(py, self.book.asks(), depth, false)
+    }
+
+    /// Bid levels as `(price, size at this price or better)`, best first
+    #[pyo3(signature = (depth=None))]
+    fn cumulative_bids<'py>(&self, py: Python<'py>, depth: Option<usize>) -> PyResult<Bound<'py, PyList>> {
+        level_list(py, self.book.bids(), depth, true)
+    }
+
+    /// Ask levels as `(price, size at this price or better)`, best first
+    #[pyo3(signature = (depth=None))]
+    fn cumulative_asks<'py>(&self, py: Python<'py>, depth: Option<usize>) -> PyResult<Bound<'py, PyList>> {
+        level_list(py, self.book.asks(), depth, true)
+    }
+
+    /// Best bid as `(price, size)`, or `None`
+    #[getter]
+    fn best_bid<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyTuple>>> {
+        level_tuple(py, self.book.best_bid())
+    }
+
+    /// Best ask as `(price, size)`, or `None`
+    #[getter]
+    fn best_ask<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyTuple>>> {
+        level_tuple(py, self.book.best_ask())
+    }
+
+    /// Best ask less best bid, or `None` unless both sides are quoted
+    /// without crossing
+    #[getter]
+    fn spread<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let spread = match self.book.top() {
+            Some(((bid, _), (ask, _))) => Some(ask.checked_sub(bid)?),
+            None => None,
+        };
+        decimal(py, spread)
+    }
+
+    /// Mid-point of the best bid and ask, or `None`
+    #[getter]
+    fn mid_price<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let mid = match self.book.top() {
+            Some(((bid, _), (ask, _))) => Some(bid.checked_add(ask)?.checked_quotient(Fixed::from_int(2))?),
+            None => None,
+        };
+        decimal(py, mid)
+    }
+
+    /// Mid-point weighted towards the thinner side: the best bid and ask
+    /// weighted by the size opposite, or `None`
+    #[getter]
+    fn microprice<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        decimal(py, self.book.microprice()?)
+    }
+
+    /// Order book imbalance over the top `depth` levels, from -1 (all
+    /// asks) to 1 (all bids), or `None` for an empty book
+    #[pyo3(signature = (depth=1))]
+    fn imbalance<'py>(&self, py: Python<'py>, depth: usize) -> PyResult<Option<Bound<'py, PyAny>>> {
+        if depth == 0 {
+            return Err(PyValueError::new_err("depth must be at least 1"));
+        }
+        decimal(py, self.book.imbalance(depth)?)
+    }
+
+    /// Top of book as a `QuoteTick` stamped at the last update, or `None`
+    /// unless both sides are quoted without crossing
+    fn to_quote_tick<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, QuoteTick>>> {
+        let (Some(((bid_price, bid_size), (ask_price, ask_size))), Some((timestamp, nanos))) =
+            (self.book.top(), self.book.updated.as_ref())
+        else {
+            return Ok(None);
+        };
+        let base = MarketData::from_parts(
+            timestamp.clone_ref(py),
+            self.instrument.clone_ref(py),
+            Resolution::Tick.to_py(py)?.unbind(),
+            Resolution::Tick,
+            *nanos,
+        );
+        let quote = QuoteTick {
+            bid_price,
+            bid_size,
+            ask_price,
+            ask_size,
+        };
+        Ok(Some(new_record(py, base, quote)?))
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let level = |level: Option<(Fixed, Fixed)>| -> PyResult<String> {
+            Ok(match level_tuple(py, level)? {
+                Some(level) => level.repr()?.to_string(),
+                None => "None".to_owned(),
+            })
+        };
+        Ok(format!(
+            "OrderBookL2(instrument={}, bids={}, asks={}, best_bid={}, best_ask={}, sequence={}, synced={})",
+            self.instrument.bind(py).repr()?,
+            self.book.bids.len(),
+            self.book.asks.len(),
+            level(self.book.best_bid())?,
+            level(self.book.best_ask())?,
+            self.book.sequence.map_or("None".to_owned(), |sequence| sequence.to_string()),
+            if self.book.synced { "True" } else { "False" },
+        ))
+    }
+}
@@ -0,0 +1,458 @@
+//! Level 3 order book tracking individual orders
+
+use std::collections::{BTreeMap, HashMap};
+
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyList, PyTuple};
+
+use crate::book::l2::{Book, OrderBookL2};
+use crate::book::update::Levels;
+use crate::interop::order_side_type;
+use crate::types::fixed::Fixed;
+use crate::types::instrument::{default_registry, InstrumentId};
+use crate::types::price::{extract_fixed, to_decimal};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+impl Side {
+    /// From an `OrderSide`, or its value: buy orders rest on the bid
+    fn from_py(side: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let side = order_side_type(side.py())?.call1((side,))?;
+        Ok(match side.getattr("value")?.extract::<String>()?.as_str() {
+            "buy" => Side::Bid,
+            _ => Side::Ask,
+        })
+    }
+
+    fn to_py<'py>(self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let side = order_side_type(py)?;
+        side.getattr(match self {
+            Side::Bid => "BUY",
+            Side::Ask => "SELL",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Order {
+    side: Side,
+    price: Fixed,
+    size: Fixed,
+    /// Arrival order across the book; lower is ahead in the queue
+    priority: u64,
+}
+
+/// Orders resting at one price, in time priority
+#[derive(Debug, Clone)]
+struct Level {
+    size: Fixed,
+    queue: BTreeMap<u64, u64>,
+}
+
+/// Aggregate size of a price level after a change, zero once it empties
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelChange {
+    pub side: Side,
+    pub price: Fixed,
+    pub size: Fixed,
+}
+
+/// Bid and ask levels of a `BookUpdate` carrying level changes
+pub fn update_levels(changes: impl IntoIterator<Item = LevelChange>) -> (Levels, Levels) {
+    let (mut bids, mut asks) = (Vec::new(), Vec::new());
+    for change in changes {
+        match change.side {
+            Side::Bid => bids.push((change.price, change.size)),
+            Side::Ask => asks.push((change.price, change.size)),
+        }
+    }
+    (bids, asks)
+}
+
+/// Resting orders of one instrument, by side and price, in time priority
+#[derive(Debug, Clone, Default)]
+pub struct OrderBook {
+    orders: HashMap<u64, Order>,
+    bids: BTreeMap<Fixed, Level>,
+    asks: BTreeMap<Fixed, Level>,
+    next_priority: u64,
+}
+
+fn unknown(id: u64) -> PyErr {
+    PyKeyError::new_err(format!("Unknown order {id}"))
+}
+
+impl OrderBook {
+    fn levels_mut(&mut self, side: Side) -> &mut BTreeMap<Fixed, Level> {
+        match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        }
+    }
+
+    fn levels(&self, side: Side) -> &BTreeMap<Fixed, Level> {
+        match side {
+            Side::Bid => &self.bids,
+            Side::Ask => &self.asks,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    pub fn contains(&self, id: u64) -> bool {
+        self.orders.contains_key(&id)
+    }
+
+    /// Rest a new order at the back of its price level's queue
+    pub fn add(&mut self, id: u64, side: Side, price: Fixed, size: Fixed) -> PyResult<LevelChange> {
+        if self.orders.contains_key(&id) {
+            return Err(PyValueError::new_err(format!("Order {id} is already in the book")));
+        }
+        if !price.is_positive() {
+            return Err(PyValueError::new_err("Prices must be positive"));
+        }
+        if !size.is_positive() {
+            return Err(PyValueError::new_err("Sizes must be positive"));
+        }
+        let priority = self.next_priority;
+        self.next_priority += 1;
+        let level = self.levels_mut(side).entry(price).or_insert_with(|| Level {
+            size: Fixed::ZERO,
+            queue: BTreeMap::new(),
+        });
+        level.size = level.size.checked_add(size)?;
+        level.queue.insert(priority, id);
+        let total = level.size;
+        self.orders.insert(
+            id,
+            Order {
+                side,
+                price,
+                size,
+                priority,
+            },
+        );
+        Ok(LevelChange {
+            side,
+            price,
+            size: total,
+        })
+    }
+
+    /// Take up to `size` off an order, keeping its priority; the order
+    /// leaves the book once nothing remains. Returns the level change and
+    /// the size actually taken.
+    pub fn reduce(&mut self, id: u64, size: Fixed) -> PyResult<(LevelChange, Fixed)> {
+        if size.is_negative() {
+            return Err(PyValueError::new_err("Sizes cannot be negative"));
+        }
+        let order = self.orders.get_mut(&id).ok_or_else(|| unknown(id))?;
+        let taken = if size < order.size { size } else { order.size };
+        order.size = order.size.checked_sub(taken)?;
+        let (side, price, priority, remaining) = (order.side, order.price, order.priority, order.size);
+        if remaining.is_zero() {
+            self.orders.remove(&id);
+        }
+        let levels = self.levels_mut(side);
+        let level = levels.get_mut(&price).expect("resting orders have a level");
+        level.size = level.size.checked_sub(taken)?;
+        if remaining.is_zero() {
+            level.queue.remove(&priority);
+        }
+        let total = level.size;
+        if level.queue.is_empty() {
+            levels.remove(&price);
+        }
+        Ok((
+            LevelChange {
+                side,
+                price,
+                size: total,
+            },
+            taken,
+        ))
+    }
+
+    /// Remove an order entirely
+    pub fn delete(&mut self, id: u64) -> PyResult<LevelChange> {
+        let size = self.orders.get(&id).ok_or_else(|| unknown(id))?.size;
+        Ok(self.reduce(id, size)?.0)
+    }
+
+    /// Cancel an order and rest `new_id` on the same side in its place, at
+    /// the back of the queue. Returns the levels changed, one if the price
+    /// is unchanged.
+    pub fn replace(&mut self, id: u64, new_id: u64, price: Fixed, size: Fixed) -> PyResult<Vec<LevelChange>> {
+        let side = self.orders.get(&id).ok_or_else(|| unknown(id))?.side;
+        if id != new_id && self.orders.contains_key(&new_id) {
+            return Err(PyValueError::new_err(format!("Order {new_id} is already in the book")));
+        }
+        let removed = self.delete(id)?;
+        let added = self.add(new_id, side, price, size)?;
+        Ok(if removed.price == added.price {
+            vec![added]
+        } else {
+            vec![removed, added]
+        })
+    }
+
+    /// Side, price and remaining size of an order
+    pub fn order(&self, id: u64) -> Option<(Side, Fixed, Fixed)> {
+        self.orders.get(&id).map(|order| (order.side, order.price, order.size))
+    }
+
+    /// Orders and size ahead of an order at its price level
+    pub fn queue_position(&self, id: u64) -> Option<(usize, Fixed)> {
+        let order = self.orders.get(&id)?;
+        let level = self.levels(order.side).get(&order.price)?;
+        let mut ahead = (0, Fixed::ZERO);
+        for other in level.queue.range(..order.priority).map(|(_, other)| &self.orders[other]) {
+            ahead = (ahead.0 + 1, ahead.1.checked_add(other.size).ok()?);
+        }
+        Some(ahead)
+    }
+
+    /// `(order id, size)` of the orders at a price level, front of the queue first
+    pub fn queue(&self, side: Side, price: Fixed) -> Vec<(u64, Fixed)> {
+        self.levels(side)
+            .get(&price)
+            .map(|level| level.queue.values().map(|id| (*id, self.orders[id].size)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Aggregated `(price, size)` levels, best first
+    pub fn depth(&self, side: Side) -> Box<dyn Iterator<Item = (Fixed, Fixed)> + '_> {
+        match side {
+            Side::Bid => Box::new(self.bids.iter().rev().map(|(price, level)| (*price, level.size))),
+            Side::Ask => Box::new(self.asks.iter().map(|(price, level)| (*price, level.size))),
+        }
+    }
+}
+
+fn level_list<'py>(py: Python<'py>, levels: impl Iterator<Item = (Fixed, Fixed)>) -> PyResult<Bound<'py, PyList>> {
+    let items = levels
+        .map(|(price, size)| PyTuple::new(py, [to_decimal(py, price)?, to_decimal(py, size)?]))
+        .collect::<PyResult<Vec<_>>>()?;
+    PyList::new(py, items)
+}
+
+fn parse_order_id(id: i128) -> PyResult<u64> {
+    u64::try_from(id).map_err(|_| PyValueError::new_err("Order ids must be non-negative integers"))
+}
+
+/// Individual resting orders of one instrument's book (Level 3)
+///
+/// Tracks each order through add, execute, cancel, replace and delete in
+/// price-time priority, so the aggregated depth (Level 2) and each order's
+/// place in its price level's queue can be read at any point. Sides are
+/// `OrderSide`s: buy orders rest on the bid.
+#[pyclass(module = "_simulor_rust")]
+pub struct OrderBookL3 {
+    instrument: Py<PyAny>,
+    instrument_id: InstrumentId,
+    pub book: OrderBook,
+    /// Timestamp of the last message a provider applied, with its epoch nanoseconds
+    pub updated: Option<(Py<PyAny>, i64)>,
+}
+
+impl OrderBookL3 {
+    pub fn from_book(
+        instrument: Py<PyAny>,
+        instrument_id: InstrumentId,
+        book: OrderBook,
+        updated: Option<(Py<PyAny>, i64)>,
+    ) -> Self {
+        OrderBookL3 {
+            instrument,
+            instrument_id,
+            book,
+            updated,
+        }
+    }
+}
+
+#[pymethods]
+impl OrderBookL3 {
+    #[new]
+    fn py_new(instrument: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let instrument_id = default_registry(instrument.py())?.get().intern_instrument(instrument)?;
+        Ok(OrderBookL3::from_book(
+            instrument.clone().unbind(),
+            instrument_id,
+            OrderBook::default(),
+            None,
+        ))
+    }
+
+    /// Rest a new order at the back of its price level
+    fn add(
+        &mut self,
+        order_id: i128,
+        side: &Bound<'_, PyAny>,
+        price: &Bound<'_, PyAny>,
+        size: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        let side = Side::from_py(side)?;
+        self.book
+            .add(parse_order_id(order_id)?, side, extract_fixed(price)?, extract_fixed(size)?)?;
+        Ok(())
+    }
+
+    /// Fill up to `size` of an order; returns the size filled
+    fn execute<'py>(
+        &mut self,
+        py: Python<'py>,
+        order_id: i128,
+        size: &Bound<'_, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let (_, taken) = self.book.reduce(parse_order_id(order_id)?, extract_fixed(size)?)?;
+        to_decimal(py, taken)
+    }
+
+    /// Cancel up to `size` of an order, keeping its priority; returns the
+    /// size cancelled
+    fn cancel<'py>(&mut self, py: Python<'py>, order_id: i128, size: &Bound<'_, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        let (_, taken) = self.book.reduce(parse_order_id(order_id)?, extract_fixed(size)?)?;
+        to_decimal(py, taken)
+    }
+
+    /// Remove an order
+    fn delete(&mut self, order_id: i128) -> PyResult<()> {
+        self.book.delete(parse_order_id(order_id)?)?;
+        Ok(())
+    }
+
+    /// Replace an order with `new_order_id` on the same side, losing its priority
+    fn replace(
+        &mut self,
+        order_id: i128,
+        new_order_id: i128,
+        price: &Bound<'_, PyAny>,
+        size: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        let (id, new_id) = (parse_order_id(order_id)?, parse_order_id(new_order_id)?);
+        self.book.replace(id, new_id, extract_fixed(price)?, extract_fixed(size)?)?;
+        Ok(())
+    }
+
+    /// `(side, price, remaining size)` of an order, or `None`
+    fn order<'py>(&self, py: Python<'py>, order_id: i128) -> PyResult<Option<Bound<'py, PyTuple>>> {
+        let Some((side, price, size)) = self.book.order(parse_order_id(order_id)?) else {
+            return Ok(None);
+        };
+        Some(PyTuple::new(py, [side.to_py(py)?, to_decimal(py, price)?, to_decimal(py, size)?])).transpose()
+    }
+
+    /// `(orders ahead, size ahead)` of an order at its price level, or `None`
+    fn queue_position<'py>(&self, py: Python<'py>, order_id: i128) -> PyResult<Option<(usize, Bound<'py, PyAny>)>> {
+        self.book
+            .queue_position(parse_order_id(order_id)?)
+            .map(|(orders, size)| Ok((orders, to_decimal(py, size)?)))
+            .transpose()
+    }
+
+    /// `(order id, size)` of the orders at a price level, front of the queue first
+    fn queue<'py>(
+        &self,
+        py: Python<'py>,
+        side: &Bound<'_, PyAny>,
+        price: &Bound<'_, PyAny>,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let orders = self.book.queue(Side::from_py(side)?, extract_fixed(price)?);
+        let items = orders
+            .into_iter()
+            .map(|(id, size)| Ok((id, to_decimal(py, size)?)))
+            .collect::<PyResult<Vec<_>>>()?;
+        PyList::new(py, items)
+    }
+
+    /// Aggregated bid levels as `(price, size)`, best first; the top `depth` only if given
+    #[pyo3(signature = (depth=None))]
+    fn bids<'py>(&self, py: Python<'py>, depth: Option<usize>) -> PyResult<Bound<'py, PyList>> {
+        level_list(py, self.book.depth(Side::Bid).take(depth.unwrap_or(usize::MAX)))
+    }
+
+    /// Aggregated ask levels as `(price, size)`, best first; the top `depth` only if given
+    #[pyo3(signature = (depth=None))]
+    fn asks<'py>(&self, py: Python<'py>, depth: Option<usize>) -> PyResult<Bound<'py, PyList>> {
+        level_list(py, self.book.depth(Side::Ask).take(depth.unwrap_or(usize::MAX)))
+    }
+
+    /// Best bid as `(price, size)`, or `None`
+    #[getter]
+    fn best_bid<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        level_list(py, self.book.depth(Side::Bid).take(1))?.iter().next().map(Ok).transpose()
+    }
+
+    /// Best ask as `(price, size)`, or `None`
+    #[getter]
+    fn best_ask<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        level_list(py, self.book.depth(Side::Ask).take(1))?.iter().next().map(Ok).transpose()
+    }
+
+    #[getter]
+    fn instrument(&self, py: Python<'_>) -> Py<PyAny> {
+        self.instrument.clone_ref(py)
+    }
+
+    /// Timestamp of the last message applied by a data provider, or `None`
+    #[getter]
+    fn timestamp(&self, py: Python<'_>) -> Option<Py<PyAny>> {
+        self.updated.as_ref().map(|(timestamp, _)| timestamp.clone_ref(py))
+    }
+
+    /// The aggregated depth as a Level 2 book
+    fn to_order_book_l2(&self, py: Python<'_>) -> OrderBookL2 {
+        let book = Book::from_levels(
+            self.book.depth(Side::Bid),
+            self.book.depth(Side::Ask),
+            self.updated.as_ref().map(|(timestamp, nanos)| (timestamp.clone_ref(py), *nanos)),
+        );
+        OrderBookL2::from_book(self.instrument.clone_ref(py), self.instrument_id, book)
+    }
+
+    /// Remove every order
+    fn clear(&mut self) {
+        self.book = OrderBook::default();
+        self.updated = None;
+    }
+
+    /// An independent copy of the book
+    fn copy(&self, py: Python<'_>) -> Self {
+        OrderBookL3::from_book(
+            self.instrument.clone_ref(py),
+            self.instrument_id,
+            self.book.clone(),
+            self.updated.as_ref().map(|(timestamp, nanos)| (timestamp.clone_ref(py), *nanos)),
+        )
+    }
+
+    fn __len__(&self) -> usize {
+        self.book.len()
+    }
+
+    fn __contains__(&self, order_id: i128) -> bool {
+        u64::try_from(order_id).is_ok_and(|id| self.book.contains(id))
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "OrderBookL3(instrument={}, orders={}, bids={}, asks={})",
+            self.instrument.bind(py).repr()?,
+            self.book.len(),
+            self.book.bids.len(),
+            self.book.asks.len(),
+        ))
+    }
+}
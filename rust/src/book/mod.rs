@@ -0,0 +1,25 @@
+//! Order books
+//!
+//! `BookUpdate` records carry market-by-price changes through
+//! `MarketEvent`; `OrderBookL2` rebuilds each instrument's aggregated depth
+//! from them and derives top-of-book prices and depth features.
+//! `OrderBookL3` tracks individual orders, for market-by-order feeds.
+
+pub mod l2;
+pub mod l3;
+pub mod update;
+
+use pyo3::prelude::*;
+
+pub use l2::{OrderBookL2, SequenceGapError};
+pub use l3::OrderBookL3;
+pub use update::BookUpdate;
+
+/// Register the order book classes on the extension module
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<BookUpdate>()?;
+    m.add_class::<OrderBookL2>()?;
+    m.add_class::<OrderBookL3>()?;
+    m.add("SequenceGapError", m.py().get_type::<SequenceGapError>())?;
+    Ok(())
+}
@@ -0,0 +1,173 @@
+//! Market-by-price book updates
+
+use pyo3::basic::CompareOp;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyTuple, PyType};
+
+use crate::types::fixed::Fixed;
+use crate::types::market_data::{decimal_repr, record_hash, record_richcmp, MarketData, Resolution};
+use crate::types::price::{extract_fixed, to_decimal};
+
+/// `(price, size)` levels of one side, in the order given
+pub type Levels = Vec<(Fixed, Fixed)>;
+
+fn extract_levels(levels: Option<&Bound<'_, PyAny>>) -> PyResult<Levels> {
+    let Some(levels) = levels else {
+        return Ok(Vec::new());
+    };
+    levels
+        .try_iter()?
+        .map(|level| {
+            let (price, size): (Bound<'_, PyAny>, Bound<'_, PyAny>) = level?.extract()?;
+            Ok((extract_fixed(&price)?, extract_fixed(&size)?))
+        })
+        .collect()
+}
+
+fn levels_tuple<'py>(py: Python<'py>, levels: &[(Fixed, Fixed)]) -> PyResult<Bound<'py, PyTuple>> {
+    let items = levels
+        .iter()
+        .map(|(price, size)| PyTuple::new(py, [to_decimal(py, *price)?, to_decimal(py, *size)?]))
+        .collect::<PyResult<Vec<_>>>()?;
+    PyTuple::new(py, items)
+}
+
+fn levels_repr(py: Python<'_>, levels: &[(Fixed, Fixed)]) -> PyResult<String> {
+    let items = levels
+        .iter()
+        .map(|(price, size)| Ok(format!("({}, {})", decimal_repr(py, *price)?, decimal_repr(py, *size)?)))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(match items.len() {
+        1 => format!("({},)", items[0]),
+        _ => format!("({})", items.join(", ")),
+    })
+}
+
+/// Changes to the price levels of an instrument's book (Level 2 data)
+///
+/// `bids` and `asks` hold `(price, size)` levels. An incremental update
+/// sets the size at each listed price, a zero size removing the level; a
+/// snapshot replaces the whole book. `sequence`, when the feed numbers its
+/// messages, lets a book detect dropped updates.
+#[pyclass(module = "_simulor_rust", extends = MarketData, frozen)]
+pub struct BookUpdate {
+    pub bids: Levels,
+    pub asks: Levels,
+    pub sequence: Option<u64>,
+    pub snapshot: bool,
+}
+
+impl BookUpdate {
+    pub fn validate(&self, resolution: Resolution) -> Result<(), &'static str> {
+        let levels = || self.bids.iter().chain(&self.asks);
+        if levels().any(|(price, _)| !price.is_positive()) {
+            return Err("Prices must be positive");
+        }
+        if levels().any(|(_, size)| size.is_negative()) {
+            return Err("Sizes cannot be negative");
+        }
+        if resolution != Resolution::Tick {
+            return Err("BookUpdate resolution must be TICK");
+        }
+        Ok(())
+    }
+
+    fn extra<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        Ok(vec![
+            levels_tuple(py, &self.bids)?.into_any(),
+            levels_tuple(py, &self.asks)?.into_any(),
+            self.sequence.into_pyobject(py)?.into_any(),
+            self.snapshot.into_pyobject(py)?.to_owned().into_any(),
+        ])
+    }
+}
+
+#[pymethods]
+impl BookUpdate {
+    #[new]
+    #[pyo3(signature = (timestamp, instrument, resolution, bids=None, asks=None, sequence=None, snapshot=false))]
+    #[allow(clippy::too_many_arguments)]
+    fn py_new(
+        timestamp: &Bound<'_, PyAny>,
+        instrument: &Bound<'_, PyAny>,
+        resolution: &Bound<'_, PyAny>,
+        bids: Option<&Bound<'_, PyAny>>,
+        asks: Option<&Bound<'_, PyAny>>,
+        sequence: Option<u64>,
+        snapshot: bool,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let base = MarketData::new(timestamp, instrument, resolution)?;
+        let update = BookUpdate {
+            bids: extract_levels(bids)?,
+            asks: extract_levels(asks)?,
+            sequence,
+            snapshot,
+        };
+        update.validate(base.native_resolution).map_err(PyValueError::new_err)?;
+        Ok(PyClassInitializer::from(base).add_subclass(update))
+    }
+
+    /// Bid levels as `(price, size)` pairs
+    #[getter(bids)]
+    fn py_bids<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyTuple>> {
+        levels_tuple(py, &self.bids)
+    }
+
+    /// Ask levels as `(price, size)` pairs
+    #[getter(asks)]
+    fn py_asks<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyTuple>> {
+        levels_tuple(py, &self.asks)
+    }
+
+    /// Feed sequence number (optional)
+    #[getter(sequence)]
+    fn py_sequence(&self) -> Option<u64> {
+        self.sequence
+    }
+
+    /// Whether the update replaces the whole book
+    #[getter(snapshot)]
+    fn py_snapshot(&self) -> bool {
+        self.snapshot
+    }
+
+    fn __repr__(slf: &Bound<'_, Self>) -> PyResult<String> {
+        let py = slf.py();
+        let update = slf.get();
+        Ok(format!(
+            "BookUpdate({}, bids={}, asks={}, sequence={}, snapshot={})",
+            slf.as_super().get().header_repr(py)?,
+            levels_repr(py, &update.bids)?,
+            levels_repr(py, &update.asks)?,
+            update.sequence.map_or("None".to_string(), |sequence| sequence.to_string()),
+            if update.snapshot { "True" } else { "False" },
+        ))
+    }
+
+    fn __richcmp__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<Py<PyAny>> {
+        let py = slf.py();
+        let base = slf.as_super().get();
+        let other_base = other.cast::<Self>().ok().map(|other| other.as_super().get());
+        record_richcmp(slf, other, op, |a, b| {
+            Ok(a.bids == b.bids
+                && a.asks == b.asks
+                && a.sequence == b.sequence
+                && a.snapshot == b.snapshot
+                && other_base.map_or(Ok(false), |other_base| base.header_eq(py, other_base))?)
+        })
+    }
+
+    fn __hash__(slf: &Bound<'_, Self>) -> PyResult<isize> {
+        let py = slf.py();
+        let extra = PyTuple::new(py, slf.get().extra(py)?)?.into_any();
+        record_hash(py, slf.as_super().get(), &[], Some(extra))
+    }
+
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyType>, Bound<'py, PyTuple>)> {
+        let py = slf.py();
+        let mut args = slf.as_super().get().header_items(py);
+        args.extend(slf.get().extra(py)?);
+        Ok((slf.get_type(), PyTuple::new(py, args)?))
+    }
+}
@@ -0,0 +1,239 @@
+//! New moons and solar terms for the Chinese lunisolar calendar
+//!
+//! Hong Kong's festivals follow the Chinese calendar: a month starts on the
+//! day of the new moon in China Standard Time (UTC+8), the month holding the
+//! winter solstice is the 11th, and in a year with 13 months the first month
+//! without a major solar term is the leap month. New moons use Meeus'
+//! series (Astronomical Algorithms, ch. 49), good to well under a minute;
+//! the Sun's apparent longitude uses his low-precision series (ch. 25),
+//! good to about 0.01 degrees, or a quarter of an hour of solar motion.
+
+use chrono::{Duration, NaiveDate};
+
+/// Julian day of the Unix epoch
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+/// China Standard Time, UTC+8, in days
+const CST_OFFSET: f64 = 8.0 / 24.0;
+const SYNODIC_MONTH: f64 = 29.530_588_861;
+const TROPICAL_YEAR: f64 = 365.242_2;
+
+fn sin(degrees: f64) -> f64 {
+    degrees.to_radians().sin()
+}
+
+/// TT - UT in days, from the Espenak-Meeus polynomials
+fn delta_t(jd: f64) -> f64 {
+    let year = 2000.0 + (jd - 2_451_545.0) / 365.25;
+    let seconds = if year < 1986.0 {
+        let t = year - 1975.0;
+        45.45 + 1.067 * t - t * t / 260.0 - t.powi(3) / 718.0
+    } else if year < 2005.0 {
+        let t = year - 2000.0;
+        63.86 + 0.3345 * t - 0.060374 * t.powi(2)
+            + 0.0017275 * t.powi(3)
+            + 0.000651814 * t.powi(4)
+            + 0.00002373599 * t.powi(5)
+    } else if year < 2050.0 {
+        let t = year - 2000.0;
+        62.92 + 0.32217 * t + 0.005589 * t * t
+    } else {
+        let u = (year - 1820.0) / 100.0;
+        -20.0 + 32.0 * u * u - 0.5628 * (2150.0 - year)
+    };
+    seconds / 86_400.0
+}
+
+/// Julian day (UT) of new moon number `k`, counted from January 2000
+fn new_moon(k: i64) -> f64 {
+    let k = k as f64;
+    let t = k / 1236.85;
+    let jde = 2_451_550.097_66 + SYNODIC_MONTH * k + 0.000_154_37 * t.powi(2) - 0.000_000_150 * t.powi(3)
+        + 0.000_000_000_73 * t.powi(4);
+    let e = 1.0 - 0.002_516 * t - 0.000_007_4 * t * t;
+    let m = 2.5534 + 29.105_356_70 * k - 0.000_001_4 * t.powi(2) - 0.000_000_11 * t.powi(3);
+    let mp =
+        201.5643 + 385.816_935_28 * k + 0.010_758_2 * t.powi(2) + 0.000_012_38 * t.powi(3) - 0.000_000_058 * t.powi(4);
+    let f =
+        160.7108 + 390.670_502_84 * k - 0.001_611_8 * t.powi(2) - 0.000_002_27 * t.powi(3) + 0.000_000_011 * t.powi(4);
+    let omega = 124.7746 - 1.563_755_88 * k + 0.002_067_2 * t.powi(2) + 0.000_002_15 * t.powi(3);
+    let periodic = -0.40720 * sin(mp)
+        + 0.17241 * e * sin(m)
+        + 0.01608 * sin(2.0 * mp)
+        + 0.01039 * sin(2.0 * f)
+        + 0.00739 * e * sin(mp - m)
+        - 0.00514 * e * sin(mp + m)
+        + 0.00208 * e * e * sin(2.0 * m)
+        - 0.00111 * sin(mp - 2.0 * f)
+        - 0.00057 * sin(mp + 2.0 * f)
+        + 0.00056 * e * sin(2.0 * mp + m)
+        - 0.00042 * sin(3.0 * mp)
+        + 0.00042 * e * sin(m + 2.0 * f)
+        + 0.00038 * e * sin(m - 2.0 * f)
+        - 0.00024 * e * sin(2.0 * mp - m)
+        - 0.00017 * sin(omega)
+        - 0.00007 * sin(mp + 2.0 * m)
+        + 0.00004 * sin(2.0 * mp - 2.0 * f)
+        + 0.00004 * sin(3.0 * m)
+        + 0.00003 * sin(mp + m - 2.0 * f)
+        + 0.00003 * sin(2.0 * mp + 2.0 * f)
+        - 0.00003 * sin(mp + m + 2.0 * f)
+        + 0.00003 * sin(mp - m + 2.0 * f)
+        - 0.00002 * sin(mp - m - 2.0 * f)
+        - 0.00002 * sin(3.0 * mp + m)
+        + 0.00002 * sin(4.0 * mp);
+    let planetary: [(f64, f64, f64); 14] = [
+        (0.000_325, 299.77 + 0.107_408 * k - 0.009_173 * t * t, 0.0),
+        (0.000_165, 251.88, 0.016_321),
+        (0.000_164, 251.83, 26.651_886),
+        (0.000_126, 349.42, 36.412_478),
+        (0.000_110, 84.66, 18.206_239),
+        (0.000_062, 141.74, 53.303_771),
+        (0.000_060, 207.14, 2.453_732),
+        (0.000_056, 154.84, 7.306_860),
+        (0.000_047, 34.52, 27.261_239),
+        (0.000_042, 207.19, 0.121_824),
+        (0.000_040, 291.34, 1.844_379),
+        (0.000_037, 161.72, 24.198_154),
+        (0.000_035, 239.56, 25.513_099),
+        (0.000_023, 331.55, 3.592_518),
+    ];
+    let additional: f64 = planetary
+        .iter()
+        .map(|&(coefficient, base, rate)| coefficient * sin(base + rate * k))
+        .sum();
+    let jde = jde + periodic + additional;
+    jde - delta_t(jde)
+}
+
+/// The Sun's apparent geocentric longitude in degrees at Julian day `jd` (UT)
+fn solar_longitude(jd: f64) -> f64 {
+    let t = (jd + delta_t(jd) - 2_451_545.0) / 36_525.0;
+    let l0 = 280.466_46 + 36_000.769_83 * t + 0.000_303_2 * t * t;
+    let m = 357.529_11 + 35_999.050_29 * t - 0.000_153_7 * t * t;
+    let center = (1.914_602 - 0.004_817 * t - 0.000_014 * t * t) * sin(m)
+        + (0.019_993 - 0.000_101 * t) * sin(2.0 * m)
+        + 0.000_289 * sin(3.0 * m);
+    let apparent = l0 + center - 0.005_69 - 0.004_78 * sin(125.04 - 1934.136 * t);
+    apparent.rem_euclid(360.0)
+}
+
+fn days_from_epoch(date: NaiveDate) -> i64 {
+    (date - NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()).num_days()
+}
+
+/// Calendar date in China Standard Time at Julian day `jd`
+fn cst_date(jd: f64) -> Option<NaiveDate> {
+    let days = (jd - UNIX_EPOCH_JD + CST_OFFSET).floor() as i64;
+    NaiveDate::from_ymd_opt(1970, 1, 1)?.checked_add_signed(Duration::days(days))
+}
+
+/// Julian day of midnight China Standard Time starting `date`
+fn cst_midnight(date: NaiveDate) -> f64 {
+    days_from_epoch(date) as f64 - CST_OFFSET + UNIX_EPOCH_JD
+}
+
+/// Julian day when the Sun reaches `longitude` degrees during `year`
+fn solar_term(year: i32, longitude: f64) -> f64 {
+    // Start from the March equinox and step by the longitude still to go
+    let equinox = NaiveDate::from_ymd_opt(year, 3, 20).map_or(0.0, cst_midnight);
+    let mut jd = equinox + longitude.rem_euclid(360.0) / 360.0 * TROPICAL_YEAR;
+    for _ in 0..20 {
+        let remaining = (longitude - solar_longitude(jd) + 180.0).rem_euclid(360.0) - 180.0;
+        jd += remaining / 360.0 * TROPICAL_YEAR;
+        if remaining.abs() < 1e-7 {
+            break;
+        }
+    }
+    jd
+}
+
+/// China Standard Time date when the Sun reaches `longitude` degrees in `year`
+pub fn solar_term_date(year: i32, longitude: f64) -> Option<NaiveDate> {
+    cst_date(solar_term(year, longitude))
+}
+
+/// Number of the last new moon falling on or before `date`
+fn new_moon_before(date: NaiveDate) -> Option<i64> {
+    let mut k = ((cst_midnight(date) - 2_451_550.1) / SYNODIC_MONTH).floor() as i64;
+    while cst_date(new_moon(k))? > date {
+        k -= 1;
+    }
+    while cst_date(new_moon(k + 1))? <= date {
+        k += 1;
+    }
+    Some(k)
+}
+
+/// Month number, whether it is a leap month, and first day of each lunar
+/// month from the 11th month before `year`'s lunar new year up to, but not
+/// including, the 11th month of that lunar year
+fn lunar_months(year: i32) -> Option<Vec<(u32, bool, NaiveDate)>> {
+    let first = new_moon_before(solar_term_date(year - 1, 270.0)?)?;
+    let last = new_moon_before(solar_term_date(year, 270.0)?)?;
+    let starts = (first..=last).map(|k| cst_date(new_moon(k))).collect::<Option<Vec<_>>>()?;
+    // A month holds a major solar term when the Sun crosses a multiple of
+    // 30 degrees between its first day and the next month's
+    let term = |date: NaiveDate| (solar_longitude(cst_midnight(date)) / 30.0).floor() as i64;
+    let mut leap_pending = starts.len() == 14;
+    let mut number = 10;
+    let mut months = Vec::with_capacity(starts.len() - 1);
+    for pair in starts.windows(2) {
+        let leap = leap_pending && term(pair[0]) == term(pair[1]);
+        if leap {
+            leap_pending = false;
+        } else {
+            number = number % 12 + 1;
+        }
+        months.push((number, leap, pair[0]));
+    }
+    Some(months)
+}
+
+/// Gregorian date of day `day` of the (non-leap) lunar `month` in the
+/// Chinese year that begins in Gregorian `year`
+pub fn lunar_date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    // Months 11 and 12 of a lunar year open the next year's table
+    let table_year = if month >= 11 { year + 1 } else { year };
+    let (_, _, start) = lunar_months(table_year)?
+        .into_iter()
+        .find(|&(number, leap, _)| number == month && !leap)?;
+    start.checked_add_signed(Duration::days(i64::from(day) - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(year, month, day)
+    }
+
+    #[test]
+    fn finds_lunar_new_year() {
+        assert_eq!(lunar_date(2020, 1, 1), date(2020, 1, 25));
+        assert_eq!(lunar_date(2023, 1, 1), date(2023, 1, 22));
+        assert_eq!(lunar_date(2024, 1, 1), date(2024, 2, 10));
+        assert_eq!(lunar_date(2025, 1, 1), date(2025, 1, 29));
+        // The year of the 2033 problem, whose leap month follows the 11th
+        assert_eq!(lunar_date(2033, 1, 1), date(2033, 1, 31));
+        assert_eq!(lunar_date(2034, 1, 1), date(2034, 2, 19));
+    }
+
+    #[test]
+    fn skips_leap_months() {
+        // 2020 repeats the 4th month and 2023 the 2nd
+        assert_eq!(lunar_date(2020, 4, 8), date(2020, 4, 30));
+        assert_eq!(lunar_date(2020, 5, 5), date(2020, 6, 25));
+        assert_eq!(lunar_date(2023, 8, 15), date(2023, 9, 29));
+        assert_eq!(lunar_date(2024, 8, 15), date(2024, 9, 17));
+        assert_eq!(lunar_date(2024, 9, 9), date(2024, 10, 11));
+    }
+
+    #[test]
+    fn finds_solar_terms() {
+        assert_eq!(solar_term_date(2024, 15.0), date(2024, 4, 4));
+        assert_eq!(solar_term_date(2025, 15.0), date(2025, 4, 4));
+        assert_eq!(solar_term_date(2024, 270.0), date(2024, 12, 21));
+        assert_eq!(solar_term_date(2024, 0.0), date(2024, 3, 20));
+    }
+}
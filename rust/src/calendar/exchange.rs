@@ -0,0 +1,337 @@
+//! Python classes for exchange calendars and their sessions
+
+use std::sync::Arc;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::calendar::exchanges;
+use crate::calendar::schedule::{Calendar, TradingDay};
+use crate::interop::zoneinfo_type;
+use crate::types::market_data::{MarketData, Resolution};
+use crate::types::time::{date_to_py, datetime_to_nanos, extract_date, nanos_to_datetime};
+
+/// One trading session of an `ExchangeCalendar`
+///
+/// Times are aware datetimes in the exchange's zone. Without pre-market or
+/// after-hours trading, `pre_open` and `post_close` equal `open` and
+/// `close`.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct TradingSession {
+    day: TradingDay,
+    tzinfo: Py<PyAny>,
+}
+
+impl TradingSession {
+    fn time<'py>(&self, py: Python<'py>, nanos: i64) -> PyResult<Bound<'py, PyAny>> {
+        nanos_to_datetime(py, nanos, Some(self.tzinfo.bind(py)))
+    }
+}
+
+#[pymethods]
+impl TradingSession {
+    /// Trade date of the session
+    #[getter]
+    fn date<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        date_to_py(py, self.day.date)
+    }
+
+    /// Start of regular trading
+    #[getter]
+    fn open<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.time(py, self.day.open)
+    }
+
+    /// End of regular trading
+    #[getter]
+    fn close<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.time(py, self.day.close)
+    }
+
+    /// Start of pre-market trading
+    #[getter]
+    fn pre_open<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.time(py, self.day.pre_open)
+    }
+
+    /// End of after-hours trading
+    #[getter]
+    fn post_close<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.time(py, self.day.post_close)
+    }
+
+    /// Start of the midday break, if there is one
+    #[getter]
+    fn break_start<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        self.day.lunch.map(|(start, _)| self.time(py, start)).transpose()
+    }
+
+    /// End of the midday break, if there is one
+    #[getter]
+    fn break_end<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        self.day.lunch.map(|(_, end)| self.time(py, end)).transpose()
+    }
+
+    /// Whether the session closes early
+    #[getter]
+    fn early_close(&self) -> bool {
+        self.day.early_close
+    }
+
+    /// Whether the market trades at `dt` during this session
+    #[pyo3(signature = (dt, extended=false))]
+    fn is_open(&self, dt: &Bound<'_, PyAny>, extended: bool) -> PyResult<bool> {
+        Ok(self.day.is_open(datetime_to_nanos(dt)?, extended))
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let iso = |nanos| -> PyResult<String> { self.time(py, nanos)?.call_method0("isoformat")?.extract() };
+        Ok(format!(
+            "TradingSession(date={}, open={}, close={}{})",
+            self.day.date,
+            iso(self.day.open)?,
+            iso(self.day.close)?,
+            if self.day.early_close { ", early_close=True" } else { "" }
+        ))
+    }
+}
+
+/// Trading calendar of an exchange
+///
+/// Built from rules: regular hours with optional pre-market, after-hours
+/// and midday break, weekly and public holidays, one-off closures and
+/// early closes. Supported calendars are "NYSE", "NASDAQ", "CME" (Globex),
+/// "LSE", "HKEX" and "CRYPTO"; ISO 10383 MICs such as "XNYS" work too.
+///
+/// Methods taking `dt` accept aware datetimes, or naive ones in UTC, and
+/// return datetimes in the exchange's zone. With `extended=True`,
+/// pre-market and after-hours trading count as open. For the open and
+/// close queries a midday break closes and reopens the market.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct ExchangeCalendar {
+    calendar: Arc<Calendar>,
+    tzinfo: Py<PyAny>,
+}
+
+impl ExchangeCalendar {
+    /// The calendar shared with native consumers
+    pub fn calendar(&self) -> Arc<Calendar> {
+        Arc::clone(&self.calendar)
+    }
+
+    fn session_object(&self, py: Python<'_>, day: Option<TradingDay>) -> PyResult<Option<TradingSession>> {
+        Ok(day.map(|day| TradingSession {
+            day,
+            tzinfo: self.tzinfo.clone_ref(py),
+        }))
+    }
+
+    fn time<'py>(&self, py: Python<'py>, nanos: Option<i64>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        nanos.map(|nanos| nanos_to_datetime(py, nanos, Some(self.tzinfo.bind(py)))).transpose()
+    }
+
+    /// Whether a record's time falls within trading hours; daily bars are
+    /// checked by date
+    fn in_session(&self, record: &Bound<'_, PyAny>, extended: bool) -> PyResult<bool> {
+        let py = record.py();
+        let base = record.cast::<MarketData>()?.get();
+        let start = base.timestamp_nanos(py)?;
+        Ok(match base.native_resolution {
+            Resolution::Tick => self.calendar.is_open(start, extended),
+            Resolution::Daily => self.calendar.is_session(extract_date(base.timestamp.bind(py))?),
+            resolution => self.calendar.trades_during(start, start + resolution.nanos(), extended),
+        })
+    }
+}
+
+#[pymethods]
+impl ExchangeCalendar {
+    #[new]
+    fn py_new(py: Python<'_>, name: &str) -> PyResult<Self> {
+        let calendar = Calendar::new(name)?;
+        let tzinfo = zoneinfo_type(py)?.call1((calendar.timezone(),))?.unbind();
+        Ok(ExchangeCalendar {
+            calendar: Arc::new(calendar),
+            tzinfo,
+        })
+    }
+
+    /// Names of the supported calendars
+    #[staticmethod]
+    fn names() -> Vec<&'static str> {
+        exchanges::ALL.iter().map(|spec| spec.name).collect()
+    }
+
+    /// Calendar name
+    #[getter]
+    fn name(&self) -> &'static str {
+        self.calendar.name()
+    }
+
+    /// Timezone of the exchange
+    #[getter]
+    fn timezone_info(&self, py: Python<'_>) -> Py<PyAny> {
+        self.tzinfo.clone_ref(py)
+    }
+
+    /// Whether the exchange holds a session on `date`
+    fn is_session(&self, date: &Bound<'_, PyAny>) -> PyResult<bool> {
+        Ok(self.calendar.is_session(extract_date(date)?))
+    }
+
+    /// Whether the session on `date` closes early
+    fn is_early_close(&self, date: &Bound<'_, PyAny>) -> PyResult<bool> {
+        Ok(self.calendar.is_early_close(extract_date(date)?))
+    }
+
+    /// Holidays and closures on weekdays of `year`, as `{date: name}` in
+    /// date order
+    fn holidays<'py>(&self, py: Python<'py>, year: i32) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (date, name) in self.calendar.holidays(year) {
+            dict.set_item(date_to_py(py, date)?, name)?;
+        }
+        Ok(dict)
+    }
+
+    /// The session held on `date`, or `None`
+    fn session(&self, py: Python<'_>, date: &Bound<'_, PyAny>) -> PyResult<Option<TradingSession>> {
+        self.session_object(py, self.calendar.trading_day(extract_date(date)?))
+    }
+
+    /// Sessions with dates from `start` to `end` inclusive
+    fn sessions<'py>(
+        &self,
+        py: Python<'py>,
+        start: &Bound<'py, PyAny>,
+        end: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let (start, end) = (extract_date(start)?, extract_date(end)?);
+        let list = PyList::empty(py);
+        let mut date = start;
+        while date <= end {
+            if let Some(session) = self.session_object(py, self.calendar.trading_day(date))? {
+                list.append(session)?;
+            }
+            let Some(next) = date.succ_opt() else { break };
+            date = next;
+        }
+        Ok(list)
+    }
+
+    /// Whether the market trades at `dt`
+    #[pyo3(signature = (dt, extended=false))]
+    fn is_open(&self, dt: &Bound<'_, PyAny>, extended: bool) -> PyResult<bool> {
+        Ok(self.calendar.is_open(datetime_to_nanos(dt)?, extended))
+    }
+
+    /// Trade date of the session in progress at `dt`, counting extended
+    /// hours, or else of the next session
+    fn session_date<'py>(&self, py: Python<'py>, dt: &Bound<'py, PyAny>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        self.calendar
+            .current_or_next(datetime_to_nanos(dt)?, true)
+            .map(|day| date_to_py(py, day.date))
+            .transpose()
+    }
+
+    /// The session in progress at `dt`, lunch break included, or `None`
+    #[pyo3(signature = (dt, extended=false))]
+    fn session_at(&self, py: Python<'_>, dt: &Bound<'_, PyAny>, extended: bool) -> PyResult<Option<TradingSession>> {
+        self.session_object(py, self.calendar.session_at(datetime_to_nanos(dt)?, extended))
+    }
+
+    /// The first session opening after `dt`
+    #[pyo3(signature = (dt, extended=false))]
+    fn next_session(&self, py: Python<'_>, dt: &Bound<'_, PyAny>, extended: bool) -> PyResult<Option<TradingSession>> {
+        self.session_object(py, self.calendar.next_session(datetime_to_nanos(dt)?, extended))
+    }
+
+    /// The last session closed at or before `dt`
+    #[pyo3(signature = (dt, extended=false))]
+    fn previous_session(
+        &self,
+        py: Python<'_>,
+        dt: &Bound<'_, PyAny>,
+        extended: bool,
+    ) -> PyResult<Option<TradingSession>> {
+        self.session_object(py, self.calendar.previous_session(datetime_to_nanos(dt)?, extended))
+    }
+
+    /// The first time trading starts after `dt`
+    #[pyo3(signature = (dt, extended=false))]
+    fn next_open<'py>(
+        &self,
+        py: Python<'py>,
+        dt: &Bound<'py, PyAny>,
+        extended: bool,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        self.time(py, self.calendar.next_open(datetime_to_nanos(dt)?, extended))
+    }
+
+    /// The first time trading stops after `dt`
+    #[pyo3(signature = (dt, extended=false))]
+    fn next_close<'py>(
+        &self,
+        py: Python<'py>,
+        dt: &Bound<'py, PyAny>,
+        extended: bool,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        self.time(py, self.calendar.next_close(datetime_to_nanos(dt)?, extended))
+    }
+
+    /// The last time trading started at or before `dt`
+    #[pyo3(signature = (dt, extended=false))]
+    fn previous_open<'py>(
+        &self,
+        py: Python<'py>,
+        dt: &Bound<'py, PyAny>,
+        extended: bool,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        self.time(py, self.calendar.previous_open(datetime_to_nanos(dt)?, extended))
+    }
+
+    /// The last time trading stopped at or before `dt`
+    #[pyo3(signature = (dt, extended=false))]
+    fn previous_close<'py>(
+        &self,
+        py: Python<'py>,
+        dt: &Bound<'py, PyAny>,
+        extended: bool,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        self.time(py, self.calendar.previous_close(datetime_to_nanos(dt)?, extended))
+    }
+
+    /// Drop the records of `event` that fall outside trading hours
+    ///
+    /// Returns `event` itself when every record is in session, `None` when
+    /// none is, and otherwise a new event of the same type with the records
+    /// that are. Ticks are checked at their time, intraday bars by whether
+    /// their interval overlaps trading hours, and daily bars by date.
+    #[pyo3(signature = (event, extended=false))]
+    fn filter_event<'py>(&self, event: &Bound<'py, PyAny>, extended: bool) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let records = event.call_method0("flatten")?;
+        let total = records.len()?;
+        let mut kept = Vec::with_capacity(total);
+        for record in records.try_iter()? {
+            let record = record?;
+            if self.in_session(&record, extended)? {
+                kept.push(record);
+            }
+        }
+        if kept.len() == total {
+            return Ok(Some(event.clone()));
+        }
+        if kept.is_empty() {
+            return Ok(None);
+        }
+        let filtered = event.get_type().call1((event.getattr("time")?,))?;
+        for record in kept {
+            filtered.call_method1("add", (record,))?;
+        }
+        Ok(Some(filtered))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ExchangeCalendar('{}')", self.calendar.name())
+    }
+}
@@ -0,0 +1,325 @@
+//! Rule sets of the supported exchanges
+//!
+//! Times are minutes after midnight on the session date, in the exchange's
+//! zone; a negative open starts the session the evening before. Holiday
+//! rules follow each exchange's published schedule in its current form and
+//! are not a record of every historical deviation: for CME Globex the
+//! shortened US holiday sessions are kept on the holiday itself, rather than
+//! folded into the next trade date as CME books them.
+
+use chrono::Weekday::{self, Mon, Sat, Sun, Thu};
+
+use crate::calendar::rules::DateRule::{Easter, Fixed, Lunar, NthWeekday, Shifted, SolarTerm};
+use crate::calendar::rules::Observance::{NearestWeekday, Substitute};
+use crate::calendar::rules::{AdHoc, DateRule, DayRule, Observance};
+
+const fn hm(hours: i64, minutes: i64) -> i64 {
+    hours * 60 + minutes
+}
+
+/// Trading hours of a regular session
+#[derive(Debug, Clone, Copy)]
+pub struct Hours {
+    pub open: i64,
+    pub close: i64,
+    /// Pre-market start, or the opening auction
+    pub pre_open: Option<i64>,
+    /// After-hours end, or the end of the closing auction
+    pub post_close: Option<i64>,
+    /// Midday break
+    pub lunch: Option<(i64, i64)>,
+}
+
+/// A shortened session and its close
+#[derive(Debug, Clone, Copy)]
+pub struct EarlyClose {
+    pub day: DayRule,
+    pub close: i64,
+}
+
+/// Everything that defines one exchange calendar
+#[derive(Debug)]
+pub struct Spec {
+    pub name: &'static str,
+    /// Other accepted names, such as the ISO 10383 MIC
+    pub aliases: &'static [&'static str],
+    pub timezone: &'static str,
+    /// Days of the week without sessions
+    pub weekend: &'static [Weekday],
+    pub hours: Hours,
+    pub holidays: &'static [DayRule],
+    pub closures: &'static [AdHoc],
+    pub early_closes: &'static [EarlyClose],
+}
+
+const WEEKEND: &[Weekday] = &[Sat, Sun];
+
+const US_NEW_YEAR: DayRule = DayRule::new("New Year's Day", Fixed(1, 1)).observed(Substitute(&[Sun]));
+const US_MLK_DAY: DayRule = DayRule::new("Martin Luther King Jr. Day", NthWeekday(1, Mon, 3)).since(1998);
+const US_PRESIDENTS_DAY: DayRule = DayRule::new("Washington's Birthday", NthWeekday(2, Mon, 3));
+const GOOD_FRIDAY: DayRule = DayRule::new("Good Friday", Easter(-2));
+const US_MEMORIAL_DAY: DayRule = DayRule::new("Memorial Day", NthWeekday(5, Mon, -1));
+const US_JUNETEENTH: DayRule = DayRule::new("Juneteenth", Fixed(6, 19)).observed(NearestWeekday).since(2022);
+const US_INDEPENDENCE_DAY: DayRule = DayRule::new("Independence Day", Fixed(7, 4)).observed(NearestWeekday);
+const US_LABOR_DAY: DayRule = DayRule::new("Labor Day", NthWeekday(9, Mon, 1));
+const THANKSGIVING: DateRule = NthWeekday(11, Thu, 4);
+const US_THANKSGIVING: DayRule = DayRule::new("Thanksgiving Day", THANKSGIVING);
+const US_CHRISTMAS: DayRule = DayRule::new("Christmas Day", Fixed(12, 25)).observed(NearestWeekday);
+
+const US_EQUITY_HOLIDAYS: &[DayRule] = &[
+    US_NEW_YEAR,
+    US_MLK_DAY,
+    US_PRESIDENTS_DAY,
+    GOOD_FRIDAY,
+    US_MEMORIAL_DAY,
+    US_JUNETEENTH,
+    US_INDEPENDENCE_DAY,
+    US_LABOR_DAY,
+    US_THANKSGIVING,
+    US_CHRISTMAS,
+];
+
+const US_EQUITY_CLOSURES: &[AdHoc] = &[
+    AdHoc::new("Hurricane Gloria", 1985, 9, 27),
+    AdHoc::new("Funeral of Richard Nixon", 1994, 4, 27),
+    AdHoc::new("September 11 attacks", 2001, 9, 11),
+    AdHoc::new("September 11 attacks", 2001, 9, 12),
+    AdHoc::new("September 11 attacks", 2001, 9, 13),
+    AdHoc::new("September 11 attacks", 2001, 9, 14),
+    AdHoc::new("Funeral of Ronald Reagan", 2004, 6, 11),
+    AdHoc::new("Funeral of Gerald Ford", 2007, 1, 2),
+    AdHoc::new("Hurricane Sandy", 2012, 10, 29),
+    AdHoc::new("Hurricane Sandy", 2012, 10, 30),
+    AdHoc::new("Funeral of George H. W. Bush", 2018, 12, 5),
+    AdHoc::new("Funeral of Jimmy Carter", 2025, 1, 9),
+];
+
+const US_EQUITY_EARLY_CLOSES: &[EarlyClose] = &[
+    EarlyClose {
+        day: DayRule::new("Day before Independence Day", Fixed(7, 3)).since(1995),
+        close: hm(13, 0),
+    },
+    EarlyClose {
+        day: DayRule::new("Day after Thanksgiving", Shifted(&THANKSGIVING, 1)).since(1993),
+        close: hm(13, 0),
+    },
+    EarlyClose {
+        day: DayRule::new("Christmas Eve", Fixed(12, 24)).since(1993),
+        close: hm(13, 0),
+    },
+];
+
+const US_EQUITY_HOURS: Hours = Hours {
+    open: hm(9, 30),
+    close: hm(16, 0),
+    pre_open: Some(hm(4, 0)),
+    post_close: Some(hm(20, 0)),
+    lunch: None,
+};
+
+/// New York Stock Exchange
+pub const NYSE: Spec = Spec {
+    name: "NYSE",
+    aliases: &["XNYS"],
+    timezone: "America/New_York",
+    weekend: WEEKEND,
+    hours: US_EQUITY_HOURS,
+    holidays: US_EQUITY_HOLIDAYS,
+    closures: US_EQUITY_CLOSURES,
+    early_closes: US_EQUITY_EARLY_CLOSES,
+};
+
+/// Nasdaq, which keeps the NYSE schedule
+pub const NASDAQ: Spec = Spec {
+    name: "NASDAQ",
+    aliases: &["XNAS"],
+    ..NYSE
+};
+
+/// CME Globex, 17:00 to 16:00 Central on the following trade date
+pub const CME: Spec = Spec {
+    name: "CME",
+    aliases: &["CME_GLOBEX", "GLOBEX", "XCME"],
+    timezone: "America/Chicago",
+    weekend: WEEKEND,
+    hours: Hours {
+        open: hm(17, 0) - hm(24, 0),
+        close: hm(16, 0),
+        pre_open: None,
+        post_close: None,
+        lunch: None,
+    },
+    holidays: &[US_NEW_YEAR, GOOD_FRIDAY, US_CHRISTMAS],
+    closures: &[],
+    early_closes: &[
+        EarlyClose {
+            day: US_MLK_DAY,
+            close: hm(12, 0),
+        },
+        EarlyClose {
+            day: US_PRESIDENTS_DAY,
+            close: hm(12, 0),
+        },
+        EarlyClose {
+            day: US_MEMORIAL_DAY,
+            close: hm(12, 0),
+        },
+        EarlyClose {
+            day: US_JUNETEENTH,
+            close: hm(12, 0),
+        },
+        EarlyClose {
+            day: US_INDEPENDENCE_DAY,
+            close: hm(12, 0),
+        },
+        EarlyClose {
+            day: US_LABOR_DAY,
+            close: hm(12, 0),
+        },
+        EarlyClose {
+            day: US_THANKSGIVING,
+            close: hm(12, 0),
+        },
+        EarlyClose {
+            day: DayRule::new("Day after Thanksgiving", Shifted(&THANKSGIVING, 1)),
+            close: hm(12, 15),
+        },
+        EarlyClose {
+            day: DayRule::new("Christmas Eve", Fixed(12, 24)),
+            close: hm(12, 15),
+        },
+    ],
+};
+
+const UK_WEEKEND: Observance = Substitute(WEEKEND);
+
+/// London Stock Exchange
+pub const LSE: Spec = Spec {
+    name: "LSE",
+    aliases: &["XLON"],
+    timezone: "Europe/London",
+    weekend: WEEKEND,
+    hours: Hours {
+        open: hm(8, 0),
+        close: hm(16, 30),
+        pre_open: Some(hm(7, 50)),
+        post_close: Some(hm(16, 35)),
+        lunch: None,
+    },
+    holidays: &[
+        DayRule::new("New Year's Day", Fixed(1, 1)).observed(UK_WEEKEND),
+        GOOD_FRIDAY,
+        DayRule::new("Easter Monday", Easter(1)),
+        DayRule::new("Early May Bank Holiday", NthWeekday(5, Mon, 1))
+            .since(1978)
+            .except(&[1995, 2020]),
+        DayRule::new("Spring Bank Holiday", NthWeekday(5, Mon, -1))
+            .since(1971)
+            .except(&[2002, 2012, 2022]),
+        DayRule::new("Summer Bank Holiday", NthWeekday(8, Mon, -1)).since(1971),
+        DayRule::new("Christmas Day", Fixed(12, 25)).observed(UK_WEEKEND),
+        DayRule::new("Boxing Day", Fixed(12, 26)).observed(UK_WEEKEND),
+    ],
+    closures: &[
+        AdHoc::new("VE Day anniversary", 1995, 5, 8),
+        AdHoc::new("Millennium celebrations", 1999, 12, 31),
+        AdHoc::new("Spring Bank Holiday", 2002, 6, 4),
+        AdHoc::new("Golden Jubilee", 2002, 6, 3),
+        AdHoc::new("Royal Wedding", 2011, 4, 29),
+        AdHoc::new("Spring Bank Holiday", 2012, 6, 4),
+        AdHoc::new("Diamond Jubilee", 2012, 6, 5),
+        AdHoc::new("VE Day anniversary", 2020, 5, 8),
+        AdHoc::new("Spring Bank Holiday", 2022, 6, 2),
+        AdHoc::new("Platinum Jubilee", 2022, 6, 3),
+        AdHoc::new("State Funeral of Queen Elizabeth II", 2022, 9, 19),
+        AdHoc::new("Coronation of King Charles III", 2023, 5, 8),
+    ],
+    early_closes: &[
+        EarlyClose {
+            day: DayRule::new("Christmas Eve", Fixed(12, 24)),
+            close: hm(12, 30),
+        },
+        EarlyClose {
+            day: DayRule::new("New Year's Eve", Fixed(12, 31)),
+            close: hm(12, 30),
+        },
+    ],
+};
+
+const HK_SUNDAY: Observance = Substitute(&[Sun]);
+
+/// Hong Kong Exchanges, with a lunch break and Chinese calendar holidays
+pub const HKEX: Spec = Spec {
+    name: "HKEX",
+    aliases: &["XHKG"],
+    timezone: "Asia/Hong_Kong",
+    weekend: WEEKEND,
+    hours: Hours {
+        open: hm(9, 30),
+        close: hm(16, 0),
+        pre_open: Some(hm(9, 0)),
+        post_close: Some(hm(16, 10)),
+        lunch: Some((hm(12, 0), hm(13, 0))),
+    },
+    holidays: &[
+        DayRule::new("New Year's Day", Fixed(1, 1)).observed(HK_SUNDAY),
+        DayRule::new("Lunar New Year", Lunar(1, 1)).observed(HK_SUNDAY),
+        DayRule::new("Second day of Lunar New Year", Lunar(1, 2)).observed(HK_SUNDAY),
+        DayRule::new("Third day of Lunar New Year", Lunar(1, 3)).observed(HK_SUNDAY),
+        GOOD_FRIDAY,
+        DayRule::new("Easter Monday", Easter(1)),
+        DayRule::new("Ching Ming Festival", SolarTerm(15.0)).observed(HK_SUNDAY),
+        DayRule::new("Labour Day", Fixed(5, 1)).observed(HK_SUNDAY),
+        DayRule::new("Buddha's Birthday", Lunar(4, 8)).observed(HK_SUNDAY).since(1999),
+        DayRule::new("Tuen Ng Festival", Lunar(5, 5)).observed(HK_SUNDAY),
+        DayRule::new("HKSAR Establishment Day", Fixed(7, 1)).observed(HK_SUNDAY).since(1997),
+        DayRule::new("Day after Mid-Autumn Festival", Lunar(8, 16)).observed(HK_SUNDAY),
+        DayRule::new("National Day", Fixed(10, 1)).observed(HK_SUNDAY).since(1997),
+        DayRule::new("Chung Yeung Festival", Lunar(9, 9)).observed(HK_SUNDAY),
+        DayRule::new("Christmas Day", Fixed(12, 25)).observed(HK_SUNDAY),
+        DayRule::new("First weekday after Christmas Day", Fixed(12, 26)).observed(HK_SUNDAY),
+    ],
+    closures: &[],
+    early_closes: &[
+        EarlyClose {
+            day: DayRule::new("Lunar New Year's Eve", Shifted(&Lunar(1, 1), -1)),
+            close: hm(12, 0),
+        },
+        EarlyClose {
+            day: DayRule::new("Christmas Eve", Fixed(12, 24)),
+            close: hm(12, 0),
+        },
+        EarlyClose {
+            day: DayRule::new("New Year's Eve", Fixed(12, 31)),
+            close: hm(12, 0),
+        },
+    ],
+};
+
+/// Round-the-clock crypto venues: one session per UTC day
+pub const CRYPTO: Spec = Spec {
+    name: "CRYPTO",
+    aliases: &["24/7"],
+    timezone: "UTC",
+    weekend: &[],
+    hours: Hours {
+        open: 0,
+        close: hm(24, 0),
+        pre_open: None,
+        post_close: None,
+        lunch: None,
+    },
+    holidays: &[],
+    closures: &[],
+    early_closes: &[],
+};
+
+/// Every supported calendar
+pub const ALL: &[&Spec] = &[&NYSE, &NASDAQ, &CME, &LSE, &HKEX, &CRYPTO];
+
+/// The calendar called `name` or one of its aliases, ignoring case
+pub fn find(name: &str) -> Option<&'static Spec> {
+    ALL.iter().copied().find(|spec| {
+        spec.name.eq_ignore_ascii_case(name) || spec.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name))
+    })
+}
@@ -0,0 +1,24 @@
+//! Exchange trading calendars
+//!
+//! Rule-based calendars answer which days an exchange trades, when each
+//! session opens and closes, and where holidays, early closes, extended
+//! hours and midday breaks fall. Hong Kong's lunar holidays are computed
+//! astronomically, so no yearly tables need maintaining.
+
+pub mod astronomy;
+pub mod exchange;
+pub mod exchanges;
+pub mod rules;
+pub mod schedule;
+
+use pyo3::prelude::*;
+
+pub use exchange::{ExchangeCalendar, TradingSession};
+pub use schedule::{Calendar, TradingDay};
+
+/// Register the calendar classes on the extension module
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<ExchangeCalendar>()?;
+    m.add_class::<TradingSession>()?;
+    Ok(())
+}
@@ -0,0 +1,245 @@
+//! Rules that place holidays and early closes in a given year
+
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+use crate::calendar::astronomy::{lunar_date, solar_term_date};
+
+/// How a rule picks its date each year
+#[derive(Debug, Clone, Copy)]
+pub enum DateRule {
+    /// A fixed month and day
+    Fixed(u32, u32),
+    /// The `n`th given weekday of a month, counted from the end when negative
+    NthWeekday(u32, Weekday, i32),
+    /// Days after Western Easter Sunday
+    Easter(i64),
+    /// A day of the Chinese lunar calendar, by month and day
+    Lunar(u32, u32),
+    /// The day, in China Standard Time, the Sun reaches a longitude in degrees
+    SolarTerm(f64),
+    /// Days after another rule's date
+    Shifted(&'static DateRule, i64),
+}
+
+impl DateRule {
+    pub fn date(&self, year: i32) -> Option<NaiveDate> {
+        match *self {
+            DateRule::Fixed(month, day) => NaiveDate::from_ymd_opt(year, month, day),
+            DateRule::NthWeekday(month, weekday, n) => nth_weekday(year, month, weekday, n),
+            DateRule::Easter(days) => easter(year)?.checked_add_signed(Duration::days(days)),
+            DateRule::Lunar(month, day) => lunar_date(year, month, day),
+            DateRule::SolarTerm(longitude) => solar_term_date(year, longitude),
+            DateRule::Shifted(rule, days) => rule.date(year)?.checked_add_signed(Duration::days(days)),
+        }
+    }
+}
+
+/// Where a holiday is observed when its date is unavailable
+#[derive(Debug, Clone, Copy)]
+pub enum Observance {
+    /// Kept on its date, weekend or not
+    Actual,
+    /// Saturday moves back to Friday and Sunday on to Monday
+    NearestWeekday,
+    /// A date falling on one of these weekdays, or on another holiday,
+    /// moves on to the next day that is neither
+    Substitute(&'static [Weekday]),
+}
+
+/// A recurring day, such as a holiday or an early close
+#[derive(Debug, Clone, Copy)]
+pub struct DayRule {
+    pub name: &'static str,
+    pub rule: DateRule,
+    pub observance: Observance,
+    /// First and last years the rule applies
+    pub years: (i32, i32),
+    /// Years the day was moved, with the replacement listed separately
+    pub except: &'static [i32],
+}
+
+impl DayRule {
+    pub const fn new(name: &'static str, rule: DateRule) -> Self {
+        DayRule {
+            name,
+            rule,
+            observance: Observance::Actual,
+            years: (i32::MIN, i32::MAX),
+            except: &[],
+        }
+    }
+
+    pub const fn observed(mut self, observance: Observance) -> Self {
+        self.observance = observance;
+        self
+    }
+
+    pub const fn since(mut self, year: i32) -> Self {
+        self.years.0 = year;
+        self
+    }
+
+    pub const fn until(mut self, year: i32) -> Self {
+        self.years.1 = year;
+        self
+    }
+
+    pub const fn except(mut self, years: &'static [i32]) -> Self {
+        self.except = years;
+        self
+    }
+
+    fn applies(&self, year: i32) -> bool {
+        self.years.0 <= year && year <= self.years.1 && !self.except.contains(&year)
+    }
+}
+
+/// A one-off day, such as an unscheduled closure
+#[derive(Debug, Clone, Copy)]
+pub struct AdHoc {
+    pub name: &'static str,
+    pub date: (i32, u32, u32),
+}
+
+impl AdHoc {
+    pub const fn new(name: &'static str, year: i32, month: u32, day: u32) -> Self {
+        AdHoc {
+            name,
+            date: (year, month, day),
+        }
+    }
+
+    fn date(&self) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(self.date.0, self.date.1, self.date.2)
+    }
+}
+
+/// The days `rules` and `adhoc` place in `year`, by date
+///
+/// Rules are applied in order, so a substitute day skips the holidays of
+/// the rules listed before it. The neighbouring years' rules are evaluated
+/// too, since observance can move a day across New Year.
+pub fn observed_days(rules: &[DayRule], adhoc: &[AdHoc], year: i32) -> BTreeMap<NaiveDate, &'static str> {
+    let mut days = BTreeMap::new();
+    for rule_year in year - 1..=year + 1 {
+        let mut taken = BTreeMap::new();
+        for rule in rules.iter().filter(|rule| rule.applies(rule_year)) {
+            let Some(date) = rule.rule.date(rule_year) else {
+                continue;
+            };
+            let date = match rule.observance {
+                Observance::Actual => date,
+                Observance::NearestWeekday => match date.weekday() {
+                    Weekday::Sat => date - Duration::days(1),
+                    Weekday::Sun => date + Duration::days(1),
+                    _ => date,
+                },
+                Observance::Substitute(weekdays) => {
+                    let mut date = date;
+                    while weekdays.contains(&date.weekday()) || taken.contains_key(&date) {
+                        date += Duration::days(1);
+                    }
+                    date
+                }
+            };
+            taken.entry(date).or_insert(rule.name);
+        }
+        days.extend(taken.into_iter().filter(|(date, _)| date.year() == year));
+    }
+    for day in adhoc {
+        if let Some(date) = day.date().filter(|date| date.year() == year) {
+            days.entry(date).or_insert(day.name);
+        }
+    }
+    days
+}
+
+/// Western Easter Sunday, by the anonymous Gregorian algorithm
+pub fn easter(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: i32) -> Option<NaiveDate> {
+    if n > 0 {
+        NaiveDate::from_weekday_of_month_opt(year, month, weekday, u8::try_from(n).ok()?)
+    } else {
+        let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+        let last = NaiveDate::from_ymd_opt(next_year, next_month, 1)?.pred_opt()?;
+        let back = (last.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+        let date = last - Duration::days(i64::from(back) + 7 * i64::from(-n - 1));
+        (date.month() == month).then_some(date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Weekday::{Mon, Sat, Sun, Thu};
+
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn computes_easter() {
+        assert_eq!(easter(2000), Some(date(2000, 4, 23)));
+        assert_eq!(easter(2024), Some(date(2024, 3, 31)));
+        assert_eq!(easter(2025), Some(date(2025, 4, 20)));
+        assert_eq!(easter(2038), Some(date(2038, 4, 25)));
+        assert_eq!(DateRule::Easter(-2).date(2024), Some(date(2024, 3, 29)));
+    }
+
+    #[test]
+    fn counts_weekdays_from_either_end() {
+        assert_eq!(nth_weekday(2024, 1, Mon, 3), Some(date(2024, 1, 15)));
+        assert_eq!(nth_weekday(2024, 11, Thu, 4), Some(date(2024, 11, 28)));
+        assert_eq!(nth_weekday(2024, 5, Mon, -1), Some(date(2024, 5, 27)));
+        assert_eq!(nth_weekday(2024, 12, Mon, -1), Some(date(2024, 12, 30)));
+        assert_eq!(nth_weekday(2024, 9, Mon, 5), Some(date(2024, 9, 30)));
+        assert_eq!(nth_weekday(2024, 2, Mon, 5), None);
+    }
+
+    #[test]
+    fn moves_observed_days() {
+        let independence = DayRule::new("Independence Day", DateRule::Fixed(7, 4)).observed(Observance::NearestWeekday);
+        let christmas =
+            DayRule::new("Christmas Day", DateRule::Fixed(12, 25)).observed(Observance::Substitute(&[Sat, Sun]));
+        let boxing = DayRule::new("Boxing Day", DateRule::Fixed(12, 26)).observed(Observance::Substitute(&[Sat, Sun]));
+        let days = |rules: &[DayRule], year| observed_days(rules, &[], year).into_keys().collect::<Vec<_>>();
+
+        // Saturday to Friday, Sunday to Monday
+        assert_eq!(days(&[independence], 2026), [date(2026, 7, 3)]);
+        assert_eq!(days(&[independence], 2027), [date(2027, 7, 5)]);
+        // A substitute skips past the other holiday it would land on
+        assert_eq!(days(&[christmas, boxing], 2021), [date(2021, 12, 27), date(2021, 12, 28)]);
+        assert_eq!(days(&[christmas, boxing], 2022), [date(2022, 12, 26), date(2022, 12, 27)]);
+    }
+
+    #[test]
+    fn limits_rules_to_their_years() {
+        let rule = DayRule::new("Juneteenth", DateRule::Fixed(6, 19)).since(2022).except(&[2023]);
+        let closures = [AdHoc::new("Closure", 2024, 3, 1)];
+        assert!(observed_days(&[rule], &[], 2021).is_empty());
+        assert!(observed_days(&[rule], &[], 2023).is_empty());
+        let days = observed_days(&[rule], &closures, 2024);
+        assert_eq!(days.get(&date(2024, 6, 19)), Some(&"Juneteenth"));
+        assert_eq!(days.get(&date(2024, 3, 1)), Some(&"Closure"));
+    }
+}
@@ -0,0 +1,485 @@
+//! Sessions of an exchange calendar as epoch-nanosecond intervals
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use chrono::{Datelike, Duration, NaiveDate};
+use chrono_tz::Tz;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::calendar::exchanges::{self, Spec};
+use crate::calendar::rules::observed_days;
+use crate::data::timestamp::TimestampParser;
+
+/// Longest run of days without a session that a search walks through
+/// before giving up
+const MAX_GAP_DAYS: usize = 60;
+
+/// One trading day's session, as epoch nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradingDay {
+    pub date: NaiveDate,
+    pub open: i64,
+    pub close: i64,
+    /// Start of pre-market trading, `open` when there is none
+    pub pre_open: i64,
+    /// End of after-hours trading, `close` when there is none
+    pub post_close: i64,
+    /// Midday break, `[start, end)`
+    pub lunch: Option<(i64, i64)>,
+    pub early_close: bool,
+}
+
+impl TradingDay {
+    pub fn start(&self, extended: bool) -> i64 {
+        if extended {
+            self.pre_open
+        } else {
+            self.open
+        }
+    }
+
+    pub fn end(&self, extended: bool) -> i64 {
+        if extended {
+            self.post_close
+        } else {
+            self.close
+        }
+    }
+
+    /// Trading intervals, `[start, end)`, split around the lunch break
+    pub fn intervals(&self, extended: bool) -> impl Iterator<Item = (i64, i64)> {
+        let (start, end) = (self.start(extended), self.end(extended));
+        let (first, second) = match self.lunch {
+            Some((lunch_start, lunch_end)) => ((start, lunch_start), Some((lunch_end, end))),
+            None => ((start, end), None),
+        };
+        std::iter::once(first).chain(second)
+    }
+
+    /// Whether the market trades at `t`
+    pub fn is_open(&self, t: i64, extended: bool) -> bool {
+        self.intervals(extended).any(|(start, end)| start <= t && t < end)
+    }
+}
+
+/// Holidays and early closes of one year
+struct Year {
+    holidays: BTreeMap<NaiveDate, &'static str>,
+    early_closes: HashMap<NaiveDate, i64>,
+}
+
+/// An exchange calendar, with holidays computed per year and cached
+pub struct Calendar {
+    spec: &'static Spec,
+    parser: TimestampParser,
+    years: Mutex<HashMap<i32, Arc<Year>>>,
+}
+
+impl Calendar {
+    pub fn new(name: &str) -> PyResult<Self> {
+        let spec = exchanges::find(name).ok_or_else(|| {
+            let names: Vec<_> = exchanges::ALL.iter().map(|spec| spec.name).collect();
+            PyValueError::new_err(format!("Unknown exchange calendar: {name}; expected one of {}", names.join(", ")))
+        })?;
+        Ok(Calendar::from_spec(spec))
+    }
+
+    fn from_spec(spec: &'static Spec) -> Self {
+        let tz: Tz = spec.timezone.parse().expect("exchange timezones are IANA names");
+        Calendar {
+            spec,
+            parser: TimestampParser::from(tz),
+            years: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.spec.name
+    }
+
+    pub fn timezone(&self) -> &'static str {
+        self.spec.timezone
+    }
+
+    /// Whether every day is a full session with no gap before the next
+    pub fn is_continuous(&self) -> bool {
+        let hours = &self.spec.hours;
+        self.spec.weekend.is_empty()
+            && self.spec.holidays.is_empty()
+            && self.spec.closures.is_empty()
+            && hours.lunch.is_none()
+            && hours.close - hours.open == 24 * 60
+    }
+
+    fn year(&self, year: i32) -> Arc<Year> {
+        let mut years = self.years.lock().unwrap();
+        years
+            .entry(year)
+            .or_insert_with(|| {
+                let holidays = observed_days(self.spec.holidays, self.spec.closures, year);
+                let early_closes = self
+                    .spec
+                    .early_closes
+                    .iter()
+                    .flat_map(|early| {
+                        observed_days(std::slice::from_ref(&early.day), &[], year)
+                            .into_keys()
+                            .map(|date| (date, early.close))
+                    })
+                    .collect();
+                Arc::new(Year { holidays, early_closes })
+            })
+            .clone()
+    }
+
+    /// Holidays falling on `year`'s trading weekdays, by date
+    pub fn holidays(&self, year: i32) -> Vec<(NaiveDate, &'static str)> {
+        self.year(year)
+            .holidays
+            .iter()
+            .filter(|(date, _)| !self.spec.weekend.contains(&date.weekday()))
+            .map(|(date, name)| (*date, *name))
+            .collect()
+    }
+
+    pub fn is_session(&self, date: NaiveDate) -> bool {
+        !self.spec.weekend.contains(&date.weekday()) && !self.year(date.year()).holidays.contains_key(&date)
+    }
+
+    /// Epoch nanoseconds of `minutes` after midnight starting `date`
+    fn at(&self, date: NaiveDate, minutes: i64) -> Option<i64> {
+        let local = date.and_hms_opt(0, 0, 0)?.checked_add_signed(Duration::minutes(minutes))?;
+        self.parser.localize(&local)
+    }
+
+    /// The session held on `date`, or `None` on weekends and holidays
+    pub fn trading_day(&self, date: NaiveDate) -> Option<TradingDay> {
+        if !self.is_session(date) {
+            return None;
+        }
+        let hours = &self.spec.hours;
+        let early = self.year(date.year()).early_closes.get(&date).copied();
+        let close = early.unwrap_or(hours.close);
+        // An early close keeps the after-hours session's length
+        let post_close = hours.post_close.map_or(close, |post| close + post - hours.close);
+        let lunch = hours.lunch.filter(|&(start, _)| start < close);
+        Some(TradingDay {
+            date,
+            open: self.at(date, hours.open)?,
+            close: self.at(date, close)?,
+            pre_open: self.at(date, hours.pre_open.unwrap_or(hours.open))?,
+            post_close: self.at(date, post_close)?,
+            lunch: match lunch {
+                Some((start, end)) => Some((self.at(date, start)?, self.at(date, end)?)),
+                None => None,
+            },
+            early_close: early.is_some(),
+        })
+    }
+
+    pub fn is_early_close(&self, date: NaiveDate) -> bool {
+        self.trading_day(date).is_some_and(|day| day.early_close)
+    }
+
+    /// Date in the exchange's zone at `t`
+    pub fn local_date(&self, t: i64) -> NaiveDate {
+        self.parser.to_local(t).date()
+    }
+
+    /// Sessions on `date` and after, oldest first
+    pub fn days_from(&self, date: NaiveDate) -> impl Iterator<Item = TradingDay> + '_ {
+        self.walk(date, 1)
+    }
+
+    /// Sessions on `date` and before, newest first
+    pub fn days_until(&self, date: NaiveDate) -> impl Iterator<Item = TradingDay> + '_ {
+        self.walk(date, -1)
+    }
+
+    fn walk(&self, date: NaiveDate, step: i64) -> impl Iterator<Item = TradingDay> + '_ {
+        let mut date = Some(date);
+        let mut gap = 0;
+        std::iter::from_fn(move || {
+            while gap < MAX_GAP_DAYS {
+                let current = date?;
+                date = current.checked_add_signed(Duration::days(step));
+                match self.trading_day(current) {
+                    Some(day) => {
+                        gap = 0;
+                        return Some(day);
+                    }
+                    None => gap += 1,
+                }
+            }
+            None
+        })
+    }
+
+    /// Sessions that may still be running at `t`, oldest first; a session
+    /// can open the evening before its date
+    fn days_around(&self, t: i64) -> impl Iterator<Item = TradingDay> + '_ {
+        self.days_from(self.local_date(t) - Duration::days(1))
+    }
+
+    /// The session in progress at `t`, or else the next to start
+    pub fn current_or_next(&self, t: i64, extended: bool) -> Option<TradingDay> {
+        self.days_around(t).find(|day| day.end(extended) > t)
+    }
+
+    /// The session whose hours, lunch included, contain `t`
+    pub fn session_at(&self, t: i64, extended: bool) -> Option<TradingDay> {
+        self.current_or_next(t, extended).filter(|day| day.start(extended) <= t)
+    }
+
+    pub fn is_open(&self, t: i64, extended: bool) -> bool {
+        self.session_at(t, extended).is_some_and(|day| day.is_open(t, extended))
+    }
+
+    /// The first session opening after `t`
+    pub fn next_session(&self, t: i64, extended: bool) -> Option<TradingDay> {
+        self.days_around(t).find(|day| day.start(extended) > t)
+    }
+
+    /// The last session closed at or before `t`
+    pub fn previous_session(&self, t: i64, extended: bool) -> Option<TradingDay> {
+        self.days_until(self.local_date(t) + Duration::days(1)).find(|day| day.end(extended) <= t)
+    }
+
+    /// Trading intervals from the sessions around `t` on, oldest first
+    fn intervals_from(&self, t: i64, extended: bool) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.days_around(t).flat_map(move |day| day.intervals(extended))
+    }
+
+    /// Trading intervals from the sessions around `t` back, newest first
+    fn intervals_until(&self, t: i64, extended: bool) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.days_until(self.local_date(t) + Duration::days(1))
+            .flat_map(move |day| day.intervals(extended).collect::<Vec<_>>().into_iter().rev())
+    }
+
+    /// The first time trading starts after `t`, counting the end of a
+    /// midday break
+    pub fn next_open(&self, t: i64, extended: bool) -> Option<i64> {
+        self.intervals_from(t, extended).map(|(start, _)| start).find(|&start| start > t)
+    }
+
+    /// The first time trading stops after `t`, counting the start of a
+    /// midday break
+    pub fn next_close(&self, t: i64, extended: bool) -> Option<i64> {
+        self.intervals_from(t, extended).map(|(_, end)| end).find(|&end| end > t)
+    }
+
+    /// The last time trading started at or before `t`
+    pub fn previous_open(&self, t: i64, extended: bool) -> Option<i64> {
+        self.intervals_until(t, extended).map(|(start, _)| start).find(|&start| start <= t)
+    }
+
+    /// The last time trading stopped at or before `t`
+    pub fn previous_close(&self, t: i64, extended: bool) -> Option<i64> {
+        self.intervals_until(t, extended).map(|(_, end)| end).find(|&end| end <= t)
+    }
+
+    /// Whether trading happens at some point in `[start, end)`
+    pub fn trades_during(&self, start: i64, end: i64, extended: bool) -> bool {
+        self.days_around(start)
+            .take_while(|day| day.start(extended) < end)
+            .any(|day| day.intervals(extended).any(|(open, close)| open < end && close > start))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use super::*;
+
+    fn calendar(name: &str) -> Calendar {
+        Calendar::from_spec(exchanges::find(name).unwrap())
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// Epoch nanoseconds of a wall-clock time in the calendar's zone
+    fn at(calendar: &Calendar, local: &str) -> i64 {
+        let naive = NaiveDateTime::parse_from_str(local, "%Y-%m-%d %H:%M").unwrap();
+        calendar.parser.localize(&naive).unwrap()
+    }
+
+    #[test]
+    fn every_calendar_builds() {
+        for spec in exchanges::ALL {
+            let calendar = Calendar::from_spec(spec);
+            assert!(calendar.days_from(date(2024, 1, 1)).next().is_some(), "{}", spec.name);
+        }
+        assert!(exchanges::find("nyse").is_some());
+    }
+
+    #[test]
+    fn us_holidays() {
+        let nyse = calendar("NYSE");
+        let days: Vec<_> = nyse.holidays(2024).into_iter().map(|(day, _)| day).collect();
+        assert_eq!(
+            days,
+            [
+                date(2024, 1, 1),
+                date(2024, 1, 15),
+                date(2024, 2, 19),
+                date(2024, 3, 29),
+                date(2024, 5, 27),
+                date(2024, 6, 19),
+                date(2024, 7, 4),
+                date(2024, 9, 2),
+                date(2024, 11, 28),
+                date(2024, 12, 25),
+            ]
+        );
+        // A Sunday New Year moves to Monday; a Saturday one is not observed
+        assert!(!nyse.is_session(date(2023, 1, 2)));
+        assert!(nyse.is_session(date(2021, 12, 31)));
+        assert!(!nyse.is_session(date(2026, 7, 3)));
+        assert!(!nyse.is_session(date(2022, 12, 26)));
+        // Juneteenth only from 2022
+        assert!(nyse.is_session(date(2021, 6, 18)));
+        assert!(!nyse.is_session(date(2025, 1, 9)));
+        assert!(!nyse.is_session(date(2024, 1, 6)));
+    }
+
+    #[test]
+    fn early_closes_keep_after_hours() {
+        let nyse = calendar("NYSE");
+        let day = nyse.trading_day(date(2024, 7, 3)).unwrap();
+        assert!(day.early_close);
+        assert_eq!(day.close, at(&nyse, "2024-07-03 13:00"));
+        assert_eq!(day.post_close - day.close, 4 * 3_600_000_000_000);
+        assert!(nyse.is_early_close(date(2024, 11, 29)));
+        assert!(nyse.is_early_close(date(2024, 12, 24)));
+        assert!(!nyse.is_early_close(date(2024, 7, 5)));
+
+        let regular = nyse.trading_day(date(2024, 7, 5)).unwrap();
+        assert_eq!(regular.pre_open, at(&nyse, "2024-07-05 04:00"));
+        assert_eq!(regular.open, at(&nyse, "2024-07-05 09:30"));
+        assert_eq!(regular.close, at(&nyse, "2024-07-05 16:00"));
+        assert_eq!(regular.post_close, at(&nyse, "2024-07-05 20:00"));
+    }
+
+    #[test]
+    fn answers_session_queries() {
+        let nyse = calendar("NYSE");
+        let t = |local| at(&nyse, local);
+        assert!(nyse.is_open(t("2024-07-03 12:59"), false));
+        assert!(!nyse.is_open(t("2024-07-03 13:00"), false));
+        assert!(nyse.is_open(t("2024-07-03 13:00"), true));
+        assert!(nyse.is_open(t("2024-07-05 08:00"), true));
+        assert!(!nyse.is_open(t("2024-07-05 08:00"), false));
+
+        // Independence Day is skipped
+        assert_eq!(nyse.next_open(t("2024-07-03 13:00"), false), Some(t("2024-07-05 09:30")));
+        assert_eq!(nyse.previous_close(t("2024-07-05 09:00"), false), Some(t("2024-07-03 13:00")));
+        assert_eq!(nyse.previous_open(t("2024-07-05 09:00"), true), Some(t("2024-07-05 04:00")));
+        assert_eq!(nyse.next_close(t("2024-07-05 10:00"), false), Some(t("2024-07-05 16:00")));
+        let monday = nyse.next_session(t("2024-07-05 17:00"), false).unwrap();
+        assert_eq!(monday.date, date(2024, 7, 8));
+        let wednesday = nyse.previous_session(t("2024-07-05 09:00"), false).unwrap();
+        assert_eq!(wednesday.date, date(2024, 7, 3));
+        assert_eq!(nyse.session_at(t("2024-07-06 12:00"), true), None);
+
+        assert!(nyse.trades_during(t("2024-07-05 15:59"), t("2024-07-05 17:00"), false));
+        assert!(!nyse.trades_during(t("2024-07-05 16:00"), t("2024-07-08 09:30"), false));
+        assert!(nyse.trades_during(t("2024-07-05 16:00"), t("2024-07-08 09:30"), true));
+    }
+
+    #[test]
+    fn lunch_breaks_split_sessions() {
+        let hkex = calendar("HKEX");
+        let t = |local| at(&hkex, local);
+        assert!(hkex.is_open(t("2024-03-04 11:59"), false));
+        assert!(!hkex.is_open(t("2024-03-04 12:30"), false));
+        // Lunch is still part of the session
+        assert!(hkex.session_at(t("2024-03-04 12:30"), false).is_some());
+        assert_eq!(hkex.next_open(t("2024-03-04 12:30"), false), Some(t("2024-03-04 13:00")));
+        assert_eq!(hkex.next_close(t("2024-03-04 10:00"), false), Some(t("2024-03-04 12:00")));
+        assert_eq!(hkex.previous_close(t("2024-03-04 12:30"), false), Some(t("2024-03-04 12:00")));
+        assert!(!hkex.trades_during(t("2024-03-04 12:00"), t("2024-03-04 13:00"), false));
+
+        // The half day before Lunar New Year ends at lunch
+        let eve = hkex.trading_day(date(2024, 2, 9)).unwrap();
+        assert!(eve.early_close && eve.lunch.is_none());
+        assert_eq!(eve.close, t("2024-02-09 12:00"));
+    }
+
+    #[test]
+    fn hong_kong_holidays() {
+        let hkex = calendar("HKEX");
+        let days: Vec<_> = hkex.holidays(2024).into_iter().map(|(day, _)| day).collect();
+        assert_eq!(
+            days,
+            [
+                date(2024, 1, 1),
+                date(2024, 2, 12),
+                date(2024, 2, 13),
+                date(2024, 3, 29),
+                date(2024, 4, 1),
+                date(2024, 4, 4),
+                date(2024, 5, 1),
+                date(2024, 5, 15),
+                date(2024, 6, 10),
+                date(2024, 7, 1),
+                date(2024, 9, 18),
+                date(2024, 10, 1),
+                date(2024, 10, 11),
+                date(2024, 12, 25),
+                date(2024, 12, 26),
+            ]
+        );
+    }
+
+    #[test]
+    fn uk_holidays() {
+        let lse = calendar("LSE");
+        let days: Vec<_> = lse.holidays(2024).into_iter().map(|(day, _)| day).collect();
+        assert_eq!(
+            days,
+            [
+                date(2024, 1, 1),
+                date(2024, 3, 29),
+                date(2024, 4, 1),
+                date(2024, 5, 6),
+                date(2024, 5, 27),
+                date(2024, 8, 26),
+                date(2024, 12, 25),
+                date(2024, 12, 26),
+            ]
+        );
+    }
+
+    #[test]
+    fn sessions_can_open_the_evening_before() {
+        let cme = calendar("CME");
+        let t = |local| at(&cme, local);
+        let day = cme.trading_day(date(2024, 1, 3)).unwrap();
+        assert_eq!(day.open, t("2024-01-02 17:00"));
+        assert_eq!(day.close, t("2024-01-03 16:00"));
+
+        let evening = t("2024-01-02 18:00");
+        assert_eq!(cme.local_date(evening), date(2024, 1, 2));
+        assert_eq!(cme.session_at(evening, false).map(|day| day.date), Some(date(2024, 1, 3)));
+        assert!(!cme.is_open(t("2024-01-02 16:30"), false));
+        // Sunday evening opens Monday's session
+        assert_eq!(cme.next_open(t("2024-01-06 12:00"), false), Some(t("2024-01-07 17:00")));
+    }
+
+    #[test]
+    fn crypto_never_closes() {
+        let crypto = calendar("CRYPTO");
+        assert!(crypto.is_continuous());
+        assert!(!calendar("NYSE").is_continuous());
+        assert!(crypto.holidays(2024).is_empty());
+        assert!(crypto.is_session(date(2024, 12, 25)));
+        let t = |local| at(&crypto, local);
+        assert!(crypto.is_open(t("2024-01-06 03:00"), false));
+        assert!(crypto.trades_during(t("2024-01-06 03:00"), t("2024-01-06 03:01"), false));
+    }
+}
@@ -0,0 +1,253 @@
+//! Corporate action records and price adjustment modes
+
+use chrono::NaiveDate;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyString;
+
+use crate::interop::instrument_type;
+use crate::types::fixed::Fixed;
+use crate::types::instrument::{default_registry, InstrumentId};
+use crate::types::price::{extract_fixed, to_decimal};
+use crate::types::time::{date_to_py, extract_date};
+
+/// Kind of a corporate action
+#[pyclass(module = "_simulor_rust", eq, eq_int, frozen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CorporateActionType {
+    /// Forward or reverse stock split
+    #[pyo3(name = "SPLIT")]
+    Split,
+    /// Cash dividend per share
+    #[pyo3(name = "DIVIDEND")]
+    Dividend,
+    /// The instrument trades under a new symbol
+    #[pyo3(name = "SYMBOL_CHANGE")]
+    SymbolChange,
+}
+
+impl CorporateActionType {
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "split" => Some(CorporateActionType::Split),
+            "dividend" => Some(CorporateActionType::Dividend),
+            "symbol_change" => Some(CorporateActionType::SymbolChange),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            CorporateActionType::Split => "SPLIT",
+            CorporateActionType::Dividend => "DIVIDEND",
+            CorporateActionType::SymbolChange => "SYMBOL_CHANGE",
+        }
+    }
+}
+
+/// How historical prices are restated for the corporate actions that
+/// have since taken effect
+#[pyclass(module = "_simulor_rust", eq, eq_int, frozen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PriceAdjustment {
+    /// Prices as traded
+    #[pyo3(name = "RAW")]
+    Raw,
+    /// Prices and sizes restated in today's share count, so splits leave
+    /// no gap in the series
+    #[pyo3(name = "BACK_ADJUSTED")]
+    BackAdjusted,
+    /// Back-adjusted prices, further scaled down before each ex-dividend
+    /// date as if dividends were reinvested
+    #[pyo3(name = "TOTAL_RETURN")]
+    TotalReturn,
+}
+
+/// An `Instrument`, or a symbol taken as a stock
+pub fn extract_instrument<'py>(obj: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    if obj.is_instance_of::<PyString>() {
+        instrument_type(obj.py())?.call_method1("stock", (obj,))
+    } else {
+        Ok(obj.clone())
+    }
+}
+
+/// A split ratio as a number or "new:old" text, such as "4:1" or "1:10"
+pub fn extract_ratio(obj: &Bound<'_, PyAny>) -> PyResult<Fixed> {
+    let ratio = match obj.cast::<PyString>() {
+        Ok(text) => parse_ratio(text.to_str()?)?,
+        Err(_) => extract_fixed(obj)?,
+    };
+    if !ratio.is_positive() {
+        return Err(PyValueError::new_err("Split ratio must be positive"));
+    }
+    Ok(ratio)
+}
+
+pub fn parse_ratio(text: &str) -> PyResult<Fixed> {
+    match text.split_once(':') {
+        Some((new, old)) => {
+            let new: Fixed = new.trim().parse()?;
+            let old: Fixed = old.trim().parse()?;
+            Ok(new.checked_quotient(old)?)
+        }
+        None => Ok(text.trim().parse()?),
+    }
+}
+
+/// A split, cash dividend or symbol change taking effect on its ex-date
+///
+/// Build one with `CorporateAction.split()`, `.dividend()` or
+/// `.symbol_change()`. Instruments may be given as symbols, which are
+/// taken as stocks. A split's `ratio` is new shares per old share: 4 for a
+/// 4-for-1 split, 0.1 for a 1-for-10 reverse split.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct CorporateAction {
+    pub kind: CorporateActionType,
+    pub instrument: Py<PyAny>,
+    pub instrument_id: InstrumentId,
+    pub ex_date: NaiveDate,
+    /// New shares per old share; one unless a split
+    pub ratio: Fixed,
+    /// Cash per share; zero unless a dividend
+    pub amount: Fixed,
+    pub new_instrument: Option<(Py<PyAny>, InstrumentId)>,
+}
+
+impl CorporateAction {
+    pub fn new(
+        kind: CorporateActionType,
+        instrument: &Bound<'_, PyAny>,
+        ex_date: NaiveDate,
+        ratio: Fixed,
+        amount: Fixed,
+        new_instrument: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Self> {
+        let py = instrument.py();
+        let registry = default_registry(py)?.get();
+        let instrument = extract_instrument(instrument)?;
+        let new_instrument = match new_instrument {
+            Some(new) => {
+                let new = extract_instrument(new)?;
+                if new.eq(&instrument)? {
+                    return Err(PyValueError::new_err("A symbol change needs a different instrument"));
+                }
+                let id = registry.intern_instrument(&new)?;
+                Some((new.unbind(), id))
+            }
+            None => None,
+        };
+        Ok(CorporateAction {
+            kind,
+            instrument_id: registry.intern_instrument(&instrument)?,
+            instrument: instrument.unbind(),
+            ex_date,
+            ratio,
+            amount,
+            new_instrument,
+        })
+    }
+}
+
+#[pymethods]
+impl CorporateAction {
+    /// A split of `ratio` new shares per old share
+    #[staticmethod]
+    fn split(instrument: &Bound<'_, PyAny>, ex_date: &Bound<'_, PyAny>, ratio: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let ratio = extract_ratio(ratio)?;
+        CorporateAction::new(CorporateActionType::Split, instrument, extract_date(ex_date)?, ratio, Fixed::ZERO, None)
+    }
+
+    /// A cash dividend of `amount` per share
+    #[staticmethod]
+    fn dividend(
+        instrument: &Bound<'_, PyAny>,
+        ex_date: &Bound<'_, PyAny>,
+        amount: &Bound<'_, PyAny>,
+    ) -> PyResult<Self> {
+        let amount = extract_fixed(amount)?;
+        if !amount.is_positive() {
+            return Err(PyValueError::new_err("Dividend amount must be positive"));
+        }
+        CorporateAction::new(
+            CorporateActionType::Dividend,
+            instrument,
+            extract_date(ex_date)?,
+            Fixed::from_int(1),
+            amount,
+            None,
+        )
+    }
+
+    /// A change of listing from `instrument` to `new_instrument`
+    #[staticmethod]
+    fn symbol_change(
+        instrument: &Bound<'_, PyAny>,
+        ex_date: &Bound<'_, PyAny>,
+        new_instrument: &Bound<'_, PyAny>,
+    ) -> PyResult<Self> {
+        CorporateAction::new(
+            CorporateActionType::SymbolChange,
+            instrument,
+            extract_date(ex_date)?,
+            Fixed::from_int(1),
+            Fixed::ZERO,
+            Some(new_instrument),
+        )
+    }
+
+    /// Kind of action
+    #[getter]
+    fn kind(&self) -> CorporateActionType {
+        self.kind
+    }
+
+    /// Instrument the action applies to
+    #[getter]
+    fn instrument(&self, py: Python<'_>) -> Py<PyAny> {
+        self.instrument.clone_ref(py)
+    }
+
+    /// First date the instrument trades with the action applied
+    #[getter]
+    fn ex_date<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        date_to_py(py, self.ex_date)
+    }
+
+    /// New shares per old share, for splits
+    #[getter(ratio)]
+    fn py_ratio<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        (self.kind == CorporateActionType::Split).then(|| to_decimal(py, self.ratio)).transpose()
+    }
+
+    /// Cash per share, for dividends
+    #[getter(amount)]
+    fn py_amount<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        (self.kind == CorporateActionType::Dividend)
+            .then(|| to_decimal(py, self.amount))
+            .transpose()
+    }
+
+    /// Instrument traded from the ex-date on, for symbol changes
+    #[getter(new_instrument)]
+    fn py_new_instrument(&self, py: Python<'_>) -> Option<Py<PyAny>> {
+        self.new_instrument.as_ref().map(|(new, _)| new.clone_ref(py))
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let detail = match self.kind {
+            CorporateActionType::Split => format!("ratio={}", self.ratio),
+            CorporateActionType::Dividend => format!("amount={}", self.amount),
+            CorporateActionType::SymbolChange => match &self.new_instrument {
+                Some((new, _)) => format!("new_instrument={}", new.bind(py).repr()?),
+                None => String::new(),
+            },
+        };
+        Ok(format!(
+            "CorporateAction({}, instrument={}, ex_date={}, {detail})",
+            self.kind.as_str(),
+            self.instrument.bind(py).repr()?,
+            self.ex_date,
+        ))
+    }
+}
@@ -0,0 +1,22 @@
+//! Corporate actions
+//!
+//! Splits, cash dividends and symbol changes, loaded once and queried
+//! point in time: price history is restated only for actions whose
+//! ex-date has passed, while the raw series stays untouched for fills.
+
+pub mod action;
+pub mod store;
+
+use pyo3::prelude::*;
+
+pub use action::{CorporateAction, CorporateActionType, PriceAdjustment};
+pub use store::CorporateActionStore;
+
+/// Register the corporate action classes on the extension module
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<CorporateActionType>()?;
+    m.add_class::<PriceAdjustment>()?;
+    m.add_class::<CorporateAction>()?;
+    m.add_class::<CorporateActionStore>()?;
+    Ok(())
+}
@@ -0,0 +1,432 @@
+//! Point-in-time store of corporate actions
+//!
+//! Actions take effect at midnight starting their ex-date in the store's
+//! timezone. Adjusted views only apply actions in effect at the `as_of`
+//! time, so a backtest never sees a split before it happens. An
+//! instrument's history includes the actions of the instruments it was
+//! renamed from, up to each rename.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::NaiveDate;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyList, PyString};
+
+use crate::corporate::action::{
+    extract_instrument, parse_ratio, CorporateAction, CorporateActionType, PriceAdjustment,
+};
+use crate::data::schema::{payload, RecordKind};
+use crate::data::source::DataError;
+use crate::data::timestamp::TimestampParser;
+use crate::interop::zoneinfo_type;
+use crate::types::fixed::{Fixed, RoundingMode};
+use crate::types::instrument::{default_registry, InstrumentId};
+use crate::types::market_data::MarketData;
+use crate::types::time::datetime_to_nanos;
+
+/// Decimal places kept in adjusted prices and dividend factors
+const SCALE: u8 = 10;
+
+/// Columns of a corporate actions CSV file
+const DATE_COLUMN: &str = "date";
+const SYMBOL_COLUMN: &str = "symbol";
+const ACTION_COLUMN: &str = "action";
+const VALUE_COLUMN: &str = "value";
+
+/// What an action does to prices, and from when
+#[derive(Debug, Clone, Copy)]
+struct Effect {
+    time: i64,
+    kind: CorporateActionType,
+    ratio: Fixed,
+    amount: Fixed,
+}
+
+/// An action with the time it takes effect
+struct Entry {
+    effect: Effect,
+    instrument: InstrumentId,
+    renamed_to: Option<InstrumentId>,
+    action: Py<CorporateAction>,
+}
+
+/// Actions ordered by time, indexed by instrument
+#[derive(Default)]
+struct Actions {
+    entries: Vec<Entry>,
+    by_instrument: HashMap<InstrumentId, Vec<usize>>,
+    /// Symbol changes, by the instrument renamed to
+    renames: HashMap<InstrumentId, Vec<usize>>,
+}
+
+impl Actions {
+    fn extend(&mut self, entries: impl IntoIterator<Item = Entry>) {
+        self.entries.extend(entries);
+        // Stable, so same-day actions keep the order they were added in
+        self.entries.sort_by_key(|entry| entry.effect.time);
+        self.by_instrument.clear();
+        self.renames.clear();
+        for (index, entry) in self.entries.iter().enumerate() {
+            self.by_instrument.entry(entry.instrument).or_default().push(index);
+            if let Some(new) = entry.renamed_to {
+                self.renames.entry(new).or_default().push(index);
+            }
+        }
+    }
+
+    /// Effects of the actions in `id`'s history in effect at `as_of`,
+    /// oldest first
+    fn history(&self, id: InstrumentId, as_of: i64) -> Vec<Effect> {
+        let mut out = Vec::new();
+        self.collect(id, as_of, i64::MAX, &mut out);
+        out.sort_by_key(|effect| effect.time);
+        out
+    }
+
+    /// Gather `id`'s actions up to `until`, then those of the instruments
+    /// renamed to it before `renamed_before`; rename times strictly
+    /// decrease, so a symbol reused after a rename cannot loop
+    fn collect(&self, id: InstrumentId, until: i64, renamed_before: i64, out: &mut Vec<Effect>) {
+        for &index in self.by_instrument.get(&id).into_iter().flatten() {
+            let effect = self.entries[index].effect;
+            if effect.time > until {
+                break;
+            }
+            out.push(effect);
+        }
+        for &index in self.renames.get(&id).into_iter().flatten() {
+            let rename = &self.entries[index];
+            let time = rename.effect.time;
+            if time > until || time >= renamed_before {
+                break;
+            }
+            self.collect(rename.instrument, time, time, out);
+        }
+    }
+}
+
+/// ID of an `Instrument`, or of a symbol taken as a stock
+fn instrument_id(instrument: &Bound<'_, PyAny>) -> PyResult<InstrumentId> {
+    let instrument = extract_instrument(instrument)?;
+    default_registry(instrument.py())?.get().intern_instrument(&instrument)
+}
+
+/// Price a dividend is measured against: the close, the trade price or
+/// the mid
+pub fn reference_price(kind: RecordKind, values: &[Fixed]) -> PyResult<Fixed> {
+    let mid = |bid: Fixed, ask: Fixed| -> PyResult<Fixed> {
+        Ok(bid.checked_add(ask)?.checked_div(Fixed::from_int(2), SCALE, RoundingMode::HalfEven)?)
+    };
+    match kind {
+        RecordKind::TradeBar => Ok(values[3]),
+        RecordKind::TradeTick => Ok(values[0]),
+        RecordKind::QuoteTick => mid(values[0], values[2]),
+        RecordKind::QuoteBar => mid(values[3], values[7]),
+    }
+}
+
+/// Drop trailing zeros, but keep at least the original value's decimals
+pub fn tidy(value: Fixed, like: Fixed) -> PyResult<Fixed> {
+    let value = value.normalize();
+    if value.scale() < like.scale() {
+        return Ok(value.rescale(like.scale(), RoundingMode::Down)?);
+    }
+    Ok(value)
+}
+
+/// Splits, cash dividends and symbol changes, with adjusted price views
+///
+/// Load actions from a CSV file with `from_csv()`, or pass
+/// `CorporateAction`s. `timezone` sets the midnight each ex-date starts
+/// at. Query times accept aware datetimes, or naive ones in UTC.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct CorporateActionStore {
+    parser: TimestampParser,
+    timezone_info: Py<PyAny>,
+    actions: Mutex<Actions>,
+}
+
+impl CorporateActionStore {
+    fn entry(&self, action: Bound<'_, CorporateAction>) -> PyResult<Entry> {
+        let data = action.get();
+        let time = data
+            .ex_date
+            .and_hms_opt(0, 0, 0)
+            .and_then(|midnight| self.parser.localize(&midnight))
+            .ok_or_else(|| PyValueError::new_err(format!("Ex-date out of range: {}", data.ex_date)))?;
+        Ok(Entry {
+            effect: Effect {
+                time,
+                kind: data.kind,
+                ratio: data.ratio,
+                amount: data.amount,
+            },
+            instrument: data.instrument_id,
+            renamed_to: data.new_instrument.as_ref().map(|(_, id)| *id),
+            action: action.unbind(),
+        })
+    }
+
+    fn add_all<'py>(&self, actions: impl IntoIterator<Item = Bound<'py, CorporateAction>>) -> PyResult<()> {
+        let entries = actions.into_iter().map(|action| self.entry(action)).collect::<PyResult<Vec<_>>>()?;
+        self.actions.lock().unwrap().extend(entries);
+        Ok(())
+    }
+}
+
+/// Read actions from a CSV file with `date`, `symbol`, `action` and `value`
+/// columns
+fn read_csv<'py>(py: Python<'py>, path: &Path) -> PyResult<Vec<Bound<'py, CorporateAction>>> {
+    let malformed = |message: String| DataError::Malformed {
+        path: path.to_path_buf(),
+        message,
+    };
+    let file = File::open(path).map_err(|source| DataError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(file);
+    let headers = reader.headers().map_err(|err| malformed(err.to_string()))?.clone();
+    let position = |column: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim() == column)
+            .ok_or_else(|| DataError::MissingColumn {
+                format: "CSV",
+                path: path.to_path_buf(),
+                column: column.to_owned(),
+            })
+    };
+    let columns = [
+        position(DATE_COLUMN)?,
+        position(SYMBOL_COLUMN)?,
+        position(ACTION_COLUMN)?,
+        position(VALUE_COLUMN)?,
+    ];
+
+    let mut actions = Vec::new();
+    for (row, record) in reader.records().enumerate() {
+        let record = record.map_err(|err| malformed(err.to_string()))?;
+        let line = row + 2;
+        let [date, symbol, action, value] = columns.map(|column| record.get(column).unwrap_or("").trim());
+        let invalid = |what: &str, text: &str| malformed(format!("line {line}: invalid {what} '{text}'"));
+        let ex_date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| invalid("date", date))?;
+        if symbol.is_empty() {
+            return Err(invalid("symbol", symbol).into());
+        }
+        let kind = CorporateActionType::parse(action).ok_or_else(|| invalid("action", action))?;
+        let instrument = PyString::new(py, symbol).into_any();
+        let (ratio, amount, new_instrument) = match kind {
+            CorporateActionType::Split => match parse_ratio(value) {
+                Ok(ratio) if ratio.is_positive() => (ratio, Fixed::ZERO, None),
+                _ => return Err(invalid("split ratio", value).into()),
+            },
+            CorporateActionType::Dividend => match value.parse::<Fixed>() {
+                Ok(amount) if amount.is_positive() => (Fixed::from_int(1), amount, None),
+                _ => return Err(invalid("dividend amount", value).into()),
+            },
+            CorporateActionType::SymbolChange if !value.is_empty() && value != symbol => {
+                (Fixed::from_int(1), Fixed::ZERO, Some(PyString::new(py, value).into_any()))
+            }
+            CorporateActionType::SymbolChange => return Err(invalid("new symbol", value).into()),
+        };
+        let action = CorporateAction::new(kind, &instrument, ex_date, ratio, amount, new_instrument.as_ref())?;
+        actions.push(Bound::new(py, action)?);
+    }
+    Ok(actions)
+}
+
+#[pymethods]
+impl CorporateActionStore {
+    #[new]
+    #[pyo3(signature = (actions=None, timezone="UTC"))]
+    fn py_new(py: Python<'_>, actions: Option<&Bound<'_, PyAny>>, timezone: &str) -> PyResult<Self> {
+        let store = CorporateActionStore {
+            parser: TimestampParser::new(timezone)?,
+            timezone_info: zoneinfo_type(py)?.call1((timezone,))?.unbind(),
+            actions: Mutex::new(Actions::default()),
+        };
+        if let Some(actions) = actions {
+            let actions = actions
+                .try_iter()?
+                .map(|action| Ok(action?.cast_into::<CorporateAction>()?))
+                .collect::<PyResult<Vec<_>>>()?;
+            store.add_all(actions)?;
+        }
+        Ok(store)
+    }
+
+    /// Load actions from a CSV file
+    ///
+    /// Columns: `date` (the ex-date, YYYY-MM-DD), `symbol`, `action`
+    /// (`split`, `dividend` or `symbol_change`) and `value`: the split
+    /// ratio as a number or "new:old", the dividend per share, or the new
+    /// symbol. Symbols are taken as stocks.
+    #[staticmethod]
+    #[pyo3(signature = (path, timezone="UTC"))]
+    fn from_csv(py: Python<'_>, path: PathBuf, timezone: &str) -> PyResult<Self> {
+        let store = CorporateActionStore::py_new(py, None, timezone)?;
+        store.add_all(read_csv(py, &path)?)?;
+        Ok(store)
+    }
+
+    /// Timezone ex-dates start in
+    #[getter]
+    fn timezone_info(&self, py: Python<'_>) -> Py<PyAny> {
+        self.timezone_info.clone_ref(py)
+    }
+
+    /// Add an action
+    fn add(&self, action: Bound<'_, CorporateAction>) -> PyResult<()> {
+        self.add_all([action])
+    }
+
+    /// All actions, or those of `instrument`, by ex-date
+    #[pyo3(signature = (instrument=None))]
+    fn actions<'py>(&self, py: Python<'py>, instrument: Option<&Bound<'py, PyAny>>) -> PyResult<Bound<'py, PyList>> {
+        let id = instrument.map(instrument_id).transpose()?;
+        let actions = self.actions.lock().unwrap();
+        let matching: Vec<_> = actions
+            .entries
+            .iter()
+            .filter(|entry| id.map_or(true, |id| entry.instrument == id))
+            .map(|entry| entry.action.clone_ref(py))
+            .collect();
+        PyList::new(py, matching)
+    }
+
+    /// Actions taking effect after `start` and at or before `end`, by
+    /// ex-date; either bound may be omitted
+    #[pyo3(signature = (start=None, end=None))]
+    fn between<'py>(
+        &self,
+        py: Python<'py>,
+        start: Option<&Bound<'py, PyAny>>,
+        end: Option<&Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let start = start.map(datetime_to_nanos).transpose()?.unwrap_or(i64::MIN);
+        let end = end.map(datetime_to_nanos).transpose()?.unwrap_or(i64::MAX);
+        let actions = self.actions.lock().unwrap();
+        let first = actions.entries.partition_point(|entry| entry.effect.time <= start);
+        let last = actions.entries.partition_point(|entry| entry.effect.time <= end);
+        PyList::new(py, actions.entries[first..last.max(first)].iter().map(|entry| entry.action.clone_ref(py)))
+    }
+
+    /// Number of actions in `instrument`'s history in effect at `as_of`;
+    /// adjusted views only change when it does
+    #[pyo3(signature = (instrument, as_of=None))]
+    fn count(&self, instrument: &Bound<'_, PyAny>, as_of: Option<&Bound<'_, PyAny>>) -> PyResult<usize> {
+        let id = instrument_id(instrument)?;
+        let as_of = as_of.map(datetime_to_nanos).transpose()?.unwrap_or(i64::MAX);
+        Ok(self.actions.lock().unwrap().history(id, as_of).len())
+    }
+
+    /// Restate one instrument's `records`, oldest first, for the actions
+    /// in effect at `as_of` (every action when omitted)
+    ///
+    /// The actions are those of `instrument`, by default the first
+    /// record's; pass it when the history was carried over from the
+    /// instrument's earlier symbol.
+    ///
+    /// Records before a split have prices divided and sizes multiplied by
+    /// its ratio. With `TOTAL_RETURN`, records before an ex-dividend date
+    /// are also scaled by one less the dividend's share of the last price
+    /// before it. Records needing no change are returned as they are.
+    #[pyo3(signature = (records, as_of=None, adjustment=PriceAdjustment::BackAdjusted, instrument=None))]
+    fn adjust<'py>(
+        &self,
+        records: &Bound<'py, PyAny>,
+        as_of: Option<&Bound<'py, PyAny>>,
+        adjustment: PriceAdjustment,
+        instrument: Option<&Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let py = records.py();
+        let records = records.try_iter()?.collect::<PyResult<Vec<_>>>()?;
+        let Some(first) = records.first() else {
+            return Ok(PyList::empty(py));
+        };
+        if adjustment == PriceAdjustment::Raw {
+            return PyList::new(py, records);
+        }
+        let id = match instrument {
+            Some(instrument) => instrument_id(instrument)?,
+            None => first.cast::<MarketData>()?.get().instrument_id(py)?,
+        };
+        let as_of = as_of.map(datetime_to_nanos).transpose()?.unwrap_or(i64::MAX);
+        let mut pending = self.actions.lock().unwrap().history(id, as_of);
+
+        let one = Fixed::from_int(1);
+        // New shares per share, and the dividend reinvestment factor, of
+        // the records walked so far
+        let (mut shares, mut reinvested) = (one, one);
+        let mut adjusted = Vec::with_capacity(records.len());
+        for record in records.iter().rev() {
+            let base = record.cast::<MarketData>()?.get();
+            let time = base.timestamp_nanos(py)?;
+            let Some((kind, mut values, direction)) = payload(record) else {
+                return Err(PyTypeError::new_err(format!("Unknown data type: {}", record.get_type().name()?)));
+            };
+            while let Some(effect) = pending.last() {
+                if time >= effect.time {
+                    break;
+                }
+                match effect.kind {
+                    CorporateActionType::Split => shares = shares.checked_mul(effect.ratio)?,
+                    CorporateActionType::Dividend if adjustment == PriceAdjustment::TotalReturn => {
+                        // Measured against this record, the last before the ex-date
+                        let price = reference_price(kind, &values)?;
+                        if price > effect.amount {
+                            let factor =
+                                price.checked_sub(effect.amount)?.checked_div(price, SCALE, RoundingMode::HalfEven)?;
+                            reinvested = reinvested.checked_mul(factor)?.rescale(SCALE, RoundingMode::HalfEven)?;
+                        }
+                    }
+                    _ => {}
+                }
+                pending.pop();
+            }
+            if shares == one && reinvested == one {
+                adjusted.push(record.clone());
+                continue;
+            }
+            let width = kind.width();
+            for (index, value) in values[..width].iter_mut().enumerate() {
+                *value = if kind.is_size(index) {
+                    tidy(value.checked_mul(shares)?, *value)?
+                } else {
+                    let price = value
+                        .checked_div(shares, SCALE, RoundingMode::HalfEven)?
+                        .checked_mul(reinvested)?
+                        .rescale(SCALE, RoundingMode::HalfEven)?;
+                    tidy(price, *value)?
+                };
+            }
+            let restated = MarketData::from_parts(
+                base.timestamp.clone_ref(py),
+                base.instrument.clone_ref(py),
+                base.resolution.clone_ref(py),
+                base.native_resolution,
+                time,
+            );
+            adjusted.push(kind.build(py, restated, &values[..width], direction)?);
+        }
+        adjusted.reverse();
+        PyList::new(py, adjusted)
+    }
+
+    fn __len__(&self) -> usize {
+        self.actions.lock().unwrap().entries.len()
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "CorporateActionStore({} actions, timezone='{}')",
+            self.__len__(),
+            self.timezone_info.bind(py).str()?
+        ))
+    }
+}
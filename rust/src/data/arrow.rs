@@ -0,0 +1,552 @@
+//! Decoding Arrow record batches into row chunks
+//!
+//! Shared by the Parquet and Arrow IPC providers. Columns are looked up by
+//! name with the same schema as the CSV provider; numeric columns may be any
+//! integer, float or decimal type (floats convert through their shortest
+//! representation, as `Decimal(str(x))` would), and timestamps may be Arrow
+//! timestamps, dates, epoch numbers or strings.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::*;
+use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Schema, TimeUnit};
+use chrono::DateTime;
+use pyo3::prelude::*;
+
+use crate::data::provider::{symbol_set, ProviderConfig};
+use crate::data::schema::{RecordKind, MAX_WIDTH};
+use crate::data::source::{Chunk, DataError, TimeWindow};
+use crate::data::timestamp::{epoch_nanos, TimestampParser};
+use crate::types::fixed::{Fixed, MAX_SCALE};
+use crate::types::market_data::Resolution;
+use crate::types::time::NANOS_PER_MICRO;
+
+const NANOS_PER_DAY: i64 = 86_400_000_000_000;
+
+/// Row filters pushed down into the columnar readers
+#[derive(Debug, Clone, Default)]
+pub struct RowFilter {
+    pub window: TimeWindow,
+    pub symbols: Option<HashSet<String>>,
+    /// Keep rows that fail record validation, for a quality stage to handle
+    pub keep_invalid: bool,
+}
+
+impl RowFilter {
+    pub fn accepts_symbol(&self, symbol: &str) -> bool {
+        self.symbols.as_ref().map_or(true, |symbols| symbols.contains(symbol))
+    }
+
+    /// Whether any requested symbol can lie in `[min, max]`
+    pub fn symbols_overlap(&self, min: &str, max: &str) -> bool {
+        self.symbols
+            .as_ref()
+            .map_or(true, |symbols| symbols.iter().any(|s| min <= s.as_str() && s.as_str() <= max))
+    }
+}
+
+/// Build a filter from the optional `start`, `end` and `symbols` provider
+/// arguments and the `validate` flag
+pub fn row_filter(
+    py: Python<'_>,
+    config: &ProviderConfig,
+    start: Option<&Bound<'_, PyAny>>,
+    end: Option<&Bound<'_, PyAny>>,
+    symbols: Option<&Bound<'_, PyAny>>,
+    validate: bool,
+) -> PyResult<RowFilter> {
+    let window = TimeWindow {
+        start: config.bound(py, start)?,
+        end: config.bound(py, end)?,
+    };
+    Ok(RowFilter {
+        window,
+        symbols: symbol_set(symbols)?,
+        keep_invalid: !validate,
+    })
+}
+
+/// Names of the columns a provider reads from each file
+pub struct ColumnNames<'a> {
+    pub date: &'a str,
+    pub symbol: &'a str,
+    pub instrument_type: &'a str,
+}
+
+/// Column layout of an Arrow schema
+pub struct BatchLayout {
+    pub kind: Option<RecordKind>,
+    pub timestamp: String,
+    pub symbol: String,
+    pub has_type_column: bool,
+}
+
+impl BatchLayout {
+    pub fn new(format: &'static str, path: &Path, schema: &Schema, names: &ColumnNames<'_>) -> Result<Self, DataError> {
+        let fields: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        for column in [names.symbol, names.date] {
+            if !fields.contains(&column) {
+                return Err(DataError::MissingColumn {
+                    format,
+                    path: path.to_path_buf(),
+                    column: column.to_owned(),
+                });
+            }
+        }
+        Ok(BatchLayout {
+            kind: RecordKind::detect(&fields),
+            timestamp: names.date.to_owned(),
+            symbol: names.symbol.to_owned(),
+            has_type_column: fields.contains(&names.instrument_type),
+        })
+    }
+
+    /// Names of the columns to read, for projection
+    pub fn projected(&self) -> Vec<&str> {
+        let mut columns = vec![self.timestamp.as_str(), self.symbol.as_str()];
+        if let Some(kind) = self.kind {
+            columns.extend_from_slice(kind.columns());
+        }
+        columns
+    }
+
+    /// Schema indices of the projected columns, ascending
+    pub fn projection(&self, schema: &Schema) -> Vec<usize> {
+        let mut indices: Vec<usize> = self.projected().iter().filter_map(|c| schema.index_of(c).ok()).collect();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+}
+
+/// Decodes batches of one file, keeping its symbol table
+pub struct BatchDecoder {
+    path: PathBuf,
+    layout: BatchLayout,
+    parser: TimestampParser,
+    bar_resolution: Resolution,
+    filter: RowFilter,
+    symbols: HashMap<String, u32>,
+}
+
+/// A string column of any Arrow string encoding
+pub enum Strings<'a> {
+    Utf8(&'a arrow_array::StringArray),
+    LargeUtf8(&'a arrow_array::LargeStringArray),
+    View(&'a arrow_array::StringViewArray),
+    Dictionary { keys: Vec<usize>, values: Box<Strings<'a>> },
+}
+
+impl<'a> Strings<'a> {
+    pub fn new(array: &'a dyn Array) -> Option<Self> {
+        Some(match array.data_type() {
+            DataType::Utf8 => Strings::Utf8(array.as_string::<i32>()),
+            DataType::LargeUtf8 => Strings::LargeUtf8(array.as_string::<i64>()),
+            DataType::Utf8View => Strings::View(array.as_string_view()),
+            DataType::Dictionary(_, _) => {
+                let dictionary = array.as_any_dictionary_opt()?;
+                Strings::Dictionary {
+                    keys: dictionary.normalized_keys(),
+                    values: Box::new(Strings::new(dictionary.values().as_ref())?),
+                }
+            }
+            _ => return None,
+        })
+    }
+
+    /// Value at `row`; the caller checks nulls on the outer array
+    pub fn value(&self, row: usize) -> &'a str {
+        match self {
+            Strings::Utf8(array) => array.value(row),
+            Strings::LargeUtf8(array) => array.value(row),
+            Strings::View(array) => array.value(row),
+            Strings::Dictionary { keys, values } => values.value(keys[row]),
+        }
+    }
+}
+
+/// Timestamp of one row, before localization
+enum RawTime<'a> {
+    /// Epoch nanoseconds (UTC)
+    Utc(i64),
+    /// Wall-clock nanoseconds to localize into the provider's zone
+    Wall(i64),
+    Text(&'a str),
+}
+
+/// A timestamp column of any supported type
+enum Times<'a> {
+    Timestamp { values: &'a [i64], scale: i64, utc: bool },
+    Date32(&'a [i32]),
+    Date64(&'a [i64]),
+    Epoch(Vec<f64>),
+    Text(Strings<'a>),
+}
+
+impl<'a> Times<'a> {
+    fn new(array: &'a ArrayRef) -> Option<Self> {
+        Some(match array.data_type() {
+            DataType::Timestamp(unit, tz) => {
+                let (values, scale): (&[i64], i64) = match unit {
+                    TimeUnit::Second => (array.as_primitive::<TimestampSecondType>().values(), 1_000_000_000),
+                    TimeUnit::Millisecond => (array.as_primitive::<TimestampMillisecondType>().values(), 1_000_000),
+                    TimeUnit::Microsecond => (array.as_primitive::<TimestampMicrosecondType>().values(), 1_000),
+                    TimeUnit::Nanosecond => (array.as_primitive::<TimestampNanosecondType>().values(), 1),
+                };
+                Times::Timestamp {
+                    values,
+                    scale,
+                    utc: tz.is_some(),
+                }
+            }
+            DataType::Date32 => Times::Date32(array.as_primitive::<Date32Type>().values()),
+            DataType::Date64 => Times::Date64(array.as_primitive::<Date64Type>().values()),
+            data_type if data_type.is_integer() || data_type.is_floating() => {
+                Times::Epoch((0..array.len()).map(|row| float_at(array.as_ref(), row).unwrap_or(f64::NAN)).collect())
+            }
+            _ => Times::Text(Strings::new(array.as_ref())?),
+        })
+    }
+
+    fn value(&self, row: usize) -> Option<RawTime<'a>> {
+        Some(match self {
+            Times::Timestamp { values, scale, utc } => {
+                let nanos = values[row].checked_mul(*scale)?;
+                if *utc {
+                    RawTime::Utc(nanos)
+                } else {
+                    RawTime::Wall(nanos)
+                }
+            }
+            Times::Date32(values) => RawTime::Wall((values[row] as i64).checked_mul(NANOS_PER_DAY)?),
+            Times::Date64(values) => RawTime::Wall(values[row].checked_mul(1_000_000)?),
+            Times::Epoch(values) => RawTime::Utc(epoch_nanos(values[row])?),
+            Times::Text(strings) => RawTime::Text(strings.value(row)),
+        })
+    }
+}
+
+/// Numeric value of a primitive array element as `f64`
+fn float_at(array: &dyn Array, row: usize) -> Option<f64> {
+    Some(match array.data_type() {
+        DataType::Int8 => array.as_primitive::<Int8Type>().value(row) as f64,
+        DataType::Int16 => array.as_primitive::<Int16Type>().value(row) as f64,
+        DataType::Int32 => array.as_primitive::<Int32Type>().value(row) as f64,
+        DataType::Int64 => array.as_primitive::<Int64Type>().value(row) as f64,
+        DataType::UInt8 => array.as_primitive::<UInt8Type>().value(row) as f64,
+        DataType::UInt16 => array.as_primitive::<UInt16Type>().value(row) as f64,
+        DataType::UInt32 => array.as_primitive::<UInt32Type>().value(row) as f64,
+        DataType::UInt64 => array.as_primitive::<UInt64Type>().value(row) as f64,
+        DataType::Float32 => array.as_primitive::<Float32Type>().value(row) as f64,
+        DataType::Float64 => array.as_primitive::<Float64Type>().value(row),
+        _ => return None,
+    })
+}
+
+/// Exact decimal value of a numeric or string array element
+fn fixed_at(array: &dyn Array, row: usize) -> Option<Fixed> {
+    let int = |value: i128| Some(Fixed::from_int(value));
+    match array.data_type() {
+        DataType::Int8 => int(array.as_primitive::<Int8Type>().value(row).into()),
+        DataType::Int16 => int(array.as_primitive::<Int16Type>().value(row).into()),
+        DataType::Int32 => int(array.as_primitive::<Int32Type>().value(row).into()),
+        DataType::Int64 => int(array.as_primitive::<Int64Type>().value(row).into()),
+        DataType::UInt8 => int(array.as_primitive::<UInt8Type>().value(row).into()),
+        DataType::UInt16 => int(array.as_primitive::<UInt16Type>().value(row).into()),
+        DataType::UInt32 => int(array.as_primitive::<UInt32Type>().value(row).into()),
+        DataType::UInt64 => int(array.as_primitive::<UInt64Type>().value(row).into()),
+        DataType::Float32 => float_to_fixed(array.as_primitive::<Float32Type>().value(row)),
+        DataType::Float64 => float_to_fixed(array.as_primitive::<Float64Type>().value(row)),
+        DataType::Decimal128(_, scale) => decimal_to_fixed(array.as_primitive::<Decimal128Type>().value(row), *scale),
+        DataType::Decimal64(_, scale) => {
+            decimal_to_fixed(array.as_primitive::<Decimal64Type>().value(row).into(), *scale)
+        }
+        DataType::Decimal32(_, scale) => {
+            decimal_to_fixed(array.as_primitive::<Decimal32Type>().value(row).into(), *scale)
+        }
+        _ => Strings::new(array)?.value(row).trim().parse().ok(),
+    }
+}
+
+/// Shortest round-trip representation, as `Decimal(str(x))`
+fn float_to_fixed<F: std::fmt::Display + Copy + Into<f64>>(value: F) -> Option<Fixed> {
+    if !value.into().is_finite() {
+        return None;
+    }
+    value.to_string().parse().ok()
+}
+
+fn decimal_to_fixed(raw: i128, scale: i8) -> Option<Fixed> {
+    if scale < 0 {
+        let factor = 10i128.checked_pow(scale.unsigned_abs() as u32)?;
+        return Some(Fixed::from_int(raw.checked_mul(factor)?));
+    }
+    if scale as u8 > MAX_SCALE {
+        return None;
+    }
+    Fixed::new(raw, scale as u8).ok()
+}
+
+impl BatchDecoder {
+    pub fn new(
+        path: &Path,
+        layout: BatchLayout,
+        parser: TimestampParser,
+        bar_resolution: Resolution,
+        filter: RowFilter,
+    ) -> Self {
+        BatchDecoder {
+            path: path.to_path_buf(),
+            layout,
+            parser,
+            bar_resolution,
+            filter,
+            symbols: HashMap::new(),
+        }
+    }
+
+    pub fn kind(&self) -> Option<RecordKind> {
+        self.layout.kind
+    }
+
+    fn malformed(&self, message: String) -> DataError {
+        DataError::Malformed {
+            path: self.path.clone(),
+            message,
+        }
+    }
+
+    fn column<'b>(&self, batch: &'b RecordBatch, name: &str) -> Result<&'b ArrayRef, DataError> {
+        batch
+            .column_by_name(name)
+            .ok_or_else(|| self.malformed(format!("record batch missing column '{name}'")))
+    }
+
+    /// Epoch nanoseconds of a raw timestamp, truncated to whole microseconds
+    /// like the `datetime` the row becomes; `None` if it needs Python
+    fn localize(&self, raw: &RawTime<'_>) -> Option<i64> {
+        let nanos = match *raw {
+            RawTime::Utc(nanos) => nanos,
+            RawTime::Wall(nanos) => {
+                let wall =
+                    DateTime::from_timestamp(nanos.div_euclid(1_000_000_000), nanos.rem_euclid(1_000_000_000) as u32)?;
+                self.parser.localize(&wall.naive_utc())?
+            }
+            RawTime::Text(text) => self.parser.parse(text)?,
+        };
+        Some(nanos - nanos.rem_euclid(NANOS_PER_MICRO))
+    }
+
+    /// Decode the rows of `batch` that pass the filter
+    pub fn decode(&mut self, batch: &RecordBatch) -> Result<Chunk, DataError> {
+        let kind = self.layout.kind.expect("batches are only read for recognised schemas");
+        let mut chunk = Chunk::new(kind);
+        let timestamps = self.column(batch, &self.layout.timestamp)?;
+        let symbols = self.column(batch, &self.layout.symbol)?;
+        let values = kind
+            .columns()
+            .iter()
+            .map(|name| self.column(batch, name).map(|array| array.as_ref()))
+            .collect::<Result<Vec<&dyn Array>, _>>()?;
+        let times = Times::new(timestamps)
+            .ok_or_else(|| self.malformed(format!("unsupported timestamp column type {}", timestamps.data_type())))?;
+        let strings = Strings::new(symbols.as_ref())
+            .ok_or_else(|| self.malformed(format!("unsupported symbol column type {}", symbols.data_type())))?;
+        let resolution = kind.resolution(self.bar_resolution);
+        let mut row_values = [Fixed::ZERO; MAX_WIDTH];
+        'rows: for row in 0..batch.num_rows() {
+            if symbols.is_null(row) || timestamps.is_null(row) {
+                continue;
+            }
+            let symbol = strings.value(row);
+            if symbol.is_empty() || !self.filter.accepts_symbol(symbol) {
+                continue;
+            }
+            for (slot, array) in row_values.iter_mut().zip(&values) {
+                match (array.is_valid(row)).then(|| fixed_at(*array, row)).flatten() {
+                    Some(value) => *slot = value,
+                    None => continue 'rows,
+                }
+            }
+            let row_values = &row_values[..kind.width()];
+            if !self.filter.keep_invalid && kind.validate(row_values, resolution).is_err() {
+                continue;
+            }
+            let Some(raw) = times.value(row) else {
+                continue;
+            };
+            match self.localize(&raw) {
+                Some(nanos) if !self.filter.window.contains(nanos) => continue,
+                Some(nanos) => chunk.timestamps.push(nanos),
+                None => {
+                    let RawTime::Text(text) = raw else {
+                        continue;
+                    };
+                    chunk.unparsed.push((chunk.len(), text.to_owned()));
+                    chunk.timestamps.push(0);
+                }
+            }
+            let next_index = self.symbols.len() as u32;
+            let index = *self.symbols.entry(symbol.to_owned()).or_insert_with(|| {
+                chunk.new_symbols.push(symbol.to_owned());
+                next_index
+            });
+            chunk.symbols.push(index);
+            chunk.values.extend_from_slice(row_values);
+        }
+        Ok(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{
+        Date32Array, Decimal128Array, DictionaryArray, Float64Array, Int64Array, StringArray, TimestampMillisecondArray,
+    };
+
+    use super::*;
+
+    const JAN_2: i64 = 1_704_153_600_000_000_000;
+
+    fn fixed(literal: &str) -> Fixed {
+        literal.parse().unwrap()
+    }
+
+    fn decoder(batch: &RecordBatch, zone: chrono_tz::Tz, filter: RowFilter) -> BatchDecoder {
+        let names = ColumnNames {
+            date: "timestamp",
+            symbol: "symbol",
+            instrument_type: "instrument_type",
+        };
+        let path = Path::new("batch.arrow");
+        let layout = BatchLayout::new("Arrow IPC", path, &batch.schema(), &names).unwrap();
+        BatchDecoder::new(path, layout, TimestampParser::from(zone), Resolution::Daily, filter)
+    }
+
+    fn ticks(timestamps: ArrayRef, symbols: ArrayRef, prices: ArrayRef, sizes: ArrayRef) -> RecordBatch {
+        RecordBatch::try_from_iter([
+            ("timestamp", timestamps),
+            ("symbol", symbols),
+            ("price", prices),
+            ("size", sizes),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn decodes_numbers_exactly() {
+        let batch = ticks(
+            Arc::new(TimestampMillisecondArray::from(vec![1_704_153_600_000, 1_704_153_600_250]).with_timezone("UTC")),
+            Arc::new(StringArray::from(vec!["AAPL", "MSFT"])),
+            Arc::new(Float64Array::from(vec![0.1, 370.125])),
+            Arc::new(Decimal128Array::from(vec![12_345, 5]).with_precision_and_scale(10, 2).unwrap()),
+        );
+        let chunk = decoder(&batch, chrono_tz::UTC, RowFilter::default()).decode(&batch).unwrap();
+
+        assert_eq!(chunk.kind, RecordKind::TradeTick);
+        assert_eq!(chunk.timestamps, [JAN_2, JAN_2 + 250_000_000]);
+        assert_eq!(chunk.new_symbols, ["AAPL", "MSFT"]);
+        // Floats convert through their shortest representation, not binary
+        assert_eq!(chunk.row_values(0), [fixed("0.1"), fixed("123.45")]);
+        assert_eq!(chunk.row_values(1), [fixed("370.125"), fixed("0.05")]);
+        assert_eq!(chunk.row_values(0)[1].scale(), 2);
+    }
+
+    #[test]
+    fn localizes_wall_clock_dates_and_text() {
+        let symbols: DictionaryArray<Int32Type> = vec!["SPY", "SPY"].into_iter().collect();
+        let batch = RecordBatch::try_from_iter([
+            ("timestamp", Arc::new(Date32Array::from(vec![19_724, 19_725])) as ArrayRef),
+            ("symbol", Arc::new(symbols) as ArrayRef),
+            ("price", Arc::new(Int64Array::from(vec![470, 471])) as ArrayRef),
+            ("size", Arc::new(StringArray::from(vec!["1", "2.50"])) as ArrayRef),
+        ])
+        .unwrap();
+        let chunk = decoder(&batch, chrono_tz::America::New_York, RowFilter::default())
+            .decode(&batch)
+            .unwrap();
+
+        // Dates are midnight in the provider's zone, five hours behind UTC
+        let offset = 5 * 3_600_000_000_000;
+        assert_eq!(chunk.timestamps, [JAN_2 + offset, JAN_2 + NANOS_PER_DAY + offset]);
+        assert_eq!(chunk.new_symbols, ["SPY"]);
+        assert_eq!(chunk.symbols, [0, 0]);
+        assert_eq!(chunk.row_values(1), [fixed("471"), fixed("2.50")]);
+
+        let text = ticks(
+            Arc::new(StringArray::from(vec!["2024-01-02 09:30:00", "1704153600", "next tuesday"])),
+            Arc::new(StringArray::from(vec!["SPY"; 3])),
+            Arc::new(Int64Array::from(vec![1; 3])),
+            Arc::new(Int64Array::from(vec![1; 3])),
+        );
+        let chunk = decoder(&text, chrono_tz::America::New_York, RowFilter::default()).decode(&text).unwrap();
+        assert_eq!(chunk.timestamps[0], JAN_2 + (9 * 60 + 30) * 60_000_000_000 + offset);
+        assert_eq!(chunk.timestamps[1], JAN_2);
+        assert_eq!(chunk.unparsed, [(2, "next tuesday".to_owned())]);
+    }
+
+    #[test]
+    fn filters_rows() {
+        let batch = ticks(
+            Arc::new(Int64Array::from(vec![
+                Some(1_704_153_600),
+                Some(1_704_153_601),
+                Some(1_704_153_602),
+                None,
+            ])),
+            Arc::new(StringArray::from(vec![Some("AAPL"), Some("MSFT"), Some("AAPL"), Some("AAPL")])),
+            Arc::new(Float64Array::from(vec![Some(1.0), Some(2.0), Some(0.0), Some(4.0)])),
+            Arc::new(Int64Array::from(vec![Some(1), None, Some(3), Some(4)])),
+        );
+        // Nulls are skipped, as is the zero price unless kept for a quality stage
+        let all = decoder(&batch, chrono_tz::UTC, RowFilter::default()).decode(&batch).unwrap();
+        assert_eq!(all.timestamps, [JAN_2]);
+
+        let keep_invalid = RowFilter {
+            keep_invalid: true,
+            ..RowFilter::default()
+        };
+        let kept = decoder(&batch, chrono_tz::UTC, keep_invalid).decode(&batch).unwrap();
+        assert_eq!(kept.timestamps, [JAN_2, JAN_2 + 2_000_000_000]);
+
+        let filter = RowFilter {
+            window: TimeWindow {
+                start: Some(JAN_2 + 1),
+                end: None,
+            },
+            symbols: Some(HashSet::from(["AAPL".to_owned()])),
+            keep_invalid: true,
+        };
+        let filtered = decoder(&batch, chrono_tz::UTC, filter).decode(&batch).unwrap();
+        assert_eq!(filtered.timestamps, [JAN_2 + 2_000_000_000]);
+        assert_eq!(filtered.new_symbols, ["AAPL"]);
+    }
+
+    #[test]
+    fn requires_symbol_and_timestamp_columns() {
+        let batch = RecordBatch::try_from_iter([
+            ("time", Arc::new(Int64Array::from(vec![0])) as ArrayRef),
+            ("symbol", Arc::new(StringArray::from(vec!["X"])) as ArrayRef),
+        ])
+        .unwrap();
+        let names = ColumnNames {
+            date: "timestamp",
+            symbol: "symbol",
+            instrument_type: "instrument_type",
+        };
+        let error = BatchLayout::new("Parquet", Path::new("x.parquet"), &batch.schema(), &names)
+            .err()
+            .unwrap();
+        assert!(matches!(error, DataError::MissingColumn { column, .. } if column == "timestamp"));
+
+        let names = ColumnNames { date: "time", ..names };
+        let layout = BatchLayout::new("Parquet", Path::new("x.parquet"), &batch.schema(), &names).unwrap();
+        assert_eq!(layout.kind, None);
+        assert!(!layout.has_type_column);
+    }
+}
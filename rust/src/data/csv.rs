@@ -0,0 +1,310 @@
+//! Native CSV data provider
+//!
+//! Drop-in counterpart of `simulor.data.providers.csv.CSVDataProvider`: same
+//! constructor, file discovery, column schema and timestamp rules, yielding
+//! native `MarketEvent`s. Files are decoded in parallel and merged by
+//! timestamp; rows that fail to parse or validate are skipped, as in the
+//! Python iterator. With `validate=False`, rows that parse but fail record
+//! validation are kept for a `DataQualityProvider` to check.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use pyo3::prelude::*;
+
+use crate::data::provider::{DataStreamIterator, FileDataProvider, FileFormat, ProviderConfig};
+use crate::data::schema::RecordKind;
+use crate::data::source::{Chunk, DataError, RowSource, TimeWindow};
+use crate::data::timestamp::TimestampParser;
+use crate::types::fixed::Fixed;
+use crate::types::market_data::Resolution;
+
+/// Column positions of one CSV file
+struct CsvLayout {
+    kind: Option<RecordKind>,
+    timestamp: usize,
+    symbol: usize,
+    values: Vec<usize>,
+}
+
+/// Streams decoded rows out of one CSV file
+pub struct CsvSource {
+    path: PathBuf,
+    reader: csv::Reader<File>,
+    layout: CsvLayout,
+    parser: TimestampParser,
+    bar_resolution: Resolution,
+    keep_invalid: bool,
+    symbols: HashMap<String, u32>,
+    record: csv::StringRecord,
+}
+
+impl CsvSource {
+    /// Open `path` and resolve its header; also reports whether the optional
+    /// instrument type column is present
+    #[allow(clippy::too_many_arguments)]
+    pub fn open(
+        path: &Path,
+        date_column: &str,
+        symbol_column: &str,
+        instrument_type_column: &str,
+        parser: TimestampParser,
+        bar_resolution: Resolution,
+        keep_invalid: bool,
+    ) -> Result<(Self, bool), DataError> {
+        let io_error = |source| DataError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let malformed = |err: csv::Error| DataError::Malformed {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        };
+        let file = File::open(path).map_err(io_error)?;
+        let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(file);
+        let headers: Vec<String> = reader.headers().map_err(malformed)?.iter().map(str::to_owned).collect();
+        let position = |column: &str| headers.iter().position(|h| h == column);
+        let missing = |column: &str| DataError::MissingColumn {
+            format: "CSV",
+            path: path.to_path_buf(),
+            column: column.to_owned(),
+        };
+        let symbol = position(symbol_column).ok_or_else(|| missing(symbol_column))?;
+        let timestamp = position(date_column).ok_or_else(|| missing(date_column))?;
+        let kind = RecordKind::detect(&headers);
+        let values = kind
+            .map(|kind| kind.columns().iter().filter_map(|c| position(c)).collect())
+            .unwrap_or_default();
+        let has_type_column = position(instrument_type_column).is_some();
+        let source = CsvSource {
+            path: path.to_path_buf(),
+            reader,
+            layout: CsvLayout {
+                kind,
+                timestamp,
+                symbol,
+                values,
+            },
+            parser,
+            bar_resolution,
+            keep_invalid,
+            symbols: HashMap::new(),
+            record: csv::StringRecord::new(),
+        };
+        Ok((source, has_type_column))
+    }
+
+    /// Decode the current record into `chunk`, or skip it if it is invalid
+    fn decode(&mut self, kind: RecordKind, chunk: &mut Chunk) {
+        let record = &self.record;
+        let layout = &self.layout;
+        let symbol = match record.get(layout.symbol) {
+            Some(symbol) if !symbol.is_empty() => symbol,
+            _ => return,
+        };
+        let Some(timestamp) = record.get(layout.timestamp) else {
+            return;
+        };
+        let mut values = [Fixed::ZERO; crate::data::schema::MAX_WIDTH];
+        for (slot, column) in values.iter_mut().zip(&layout.values) {
+            match record.get(*column).map(|text| text.trim().parse::<Fixed>()) {
+                Some(Ok(value)) => *slot = value,
+                _ => return,
+            }
+        }
+        let values = &values[..kind.width()];
+        if !self.keep_invalid && kind.validate(values, kind.resolution(self.bar_resolution)).is_err() {
+            return;
+        }
+        let row = chunk.len();
+        match self.parser.parse(timestamp) {
+            Some(nanos) => chunk.timestamps.push(nanos),
+            None => {
+                chunk.timestamps.push(0);
+                chunk.unparsed.push((row, timestamp.to_owned()));
+            }
+        }
+        let next_index = self.symbols.len() as u32;
+        let index = *self.symbols.entry(symbol.to_owned()).or_insert_with(|| {
+            chunk.new_symbols.push(symbol.to_owned());
+            next_index
+        });
+        chunk.symbols.push(index);
+        chunk.values.extend_from_slice(values);
+    }
+}
+
+impl RowSource for CsvSource {
+    fn next_chunk(&mut self, max_rows: usize) -> Result<Option<Chunk>, DataError> {
+        let Some(kind) = self.layout.kind else {
+            // No recognised record columns: every row would be rejected
+            return Ok(None);
+        };
+        let mut chunk = Chunk::new(kind);
+        let mut read = 0;
+        while read < max_rows {
+            let more = self.reader.read_record(&mut self.record).map_err(|err| DataError::Malformed {
+                path: self.path.clone(),
+                message: err.to_string(),
+            })?;
+            if !more {
+                break;
+            }
+            read += 1;
+            self.decode(kind, &mut chunk);
+        }
+        Ok((read > 0).then_some(chunk))
+    }
+}
+
+/// Load market data from CSV files
+#[pyclass(module = "_simulor_rust", name = "CSVDataProvider", extends = FileDataProvider, frozen)]
+pub struct CsvDataProvider {
+    validate: bool,
+}
+
+static CSV: FileFormat = FileFormat {
+    name: "CSV",
+    provider: "CSVDataProvider",
+    logger: "simulor.data.providers.csv",
+    extensions: &["csv"],
+};
+
+#[pymethods]
+impl CsvDataProvider {
+    #[new]
+    #[pyo3(signature = (
+        path,
+        resolution,
+        date_column="timestamp".to_owned(),
+        symbol_column="symbol".to_owned(),
+        instrument_type_column="instrument_type".to_owned(),
+        timezone="UTC",
+        validate=true,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn py_new(
+        py: Python<'_>,
+        path: &Bound<'_, PyAny>,
+        resolution: &Bound<'_, PyAny>,
+        date_column: String,
+        symbol_column: String,
+        instrument_type_column: String,
+        timezone: &str,
+        validate: bool,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let config = ProviderConfig::new(
+            py,
+            &CSV,
+            path,
+            resolution,
+            date_column,
+            symbol_column,
+            instrument_type_column,
+            timezone,
+        )?;
+        Ok(PyClassInitializer::from(FileDataProvider { config }).add_subclass(CsvDataProvider { validate }))
+    }
+
+    /// Return a new iterator over the data, one `MarketEvent` per timestamp
+    fn __iter__(slf: &Bound<'_, Self>) -> PyResult<DataStreamIterator> {
+        let py = slf.py();
+        let config = &slf.as_super().get().config;
+        let mut sources: Vec<Box<dyn RowSource>> = Vec::with_capacity(config.files.len());
+        let mut warned = false;
+        for file in &config.files {
+            let (source, has_type_column) = CsvSource::open(
+                file,
+                &config.date_column,
+                &config.symbol_column,
+                &config.instrument_type_column,
+                config.parser,
+                config.bar_resolution,
+                !slf.get().validate,
+            )?;
+            if !warned && !has_type_column {
+                config.warn_missing_type_column(py, file)?;
+                warned = true;
+            }
+            sources.push(Box::new(source));
+        }
+        Ok(DataStreamIterator::new(config.stream(py, sources, TimeWindow::default())?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("simulor-csv-{}-{name}.csv", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn open(path: &Path) -> Result<(CsvSource, bool), DataError> {
+        let parser = TimestampParser::from(chrono_tz::UTC);
+        CsvSource::open(path, "timestamp", "symbol", "instrument_type", parser, Resolution::Daily, false)
+    }
+
+    #[test]
+    fn decodes_rows_and_skips_invalid_ones() {
+        let path = write(
+            "bars",
+            "timestamp,symbol,open,high,low,close,volume\n\
+             2024-01-02,AAPL,1.0,2.0,0.5,1.5,100\n\
+             2024-01-02,,1.0,2.0,0.5,1.5,100\n\
+             2024-01-03,MSFT,3.0,2.0,0.5,1.5,100\n\
+             2024-01-03,MSFT,abc,2.0,0.5,1.5,100\n\
+             2024-01-04,MSFT,1,2,1,2,0\n\
+             2024-01-05,AAPL,1,2,1,2\n\
+             someday,AAPL,1,2,1,2,5\n",
+        );
+        let (mut source, has_type_column) = open(&path).unwrap();
+        let chunk = source.next_chunk(1024).unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(!has_type_column);
+        assert_eq!(chunk.kind, RecordKind::TradeBar);
+        assert_eq!(chunk.new_symbols, ["AAPL", "MSFT"]);
+        assert_eq!(chunk.symbols, [0, 1, 0]);
+        assert_eq!(chunk.timestamps[1] - chunk.timestamps[0], 2 * 86_400_000_000_000);
+        assert_eq!(chunk.row_values(0)[3], "1.5".parse::<Fixed>().unwrap());
+        // Unrecognised timestamps are left for the Python parser
+        assert_eq!(chunk.unparsed, [(2, "someday".to_owned())]);
+        assert!(source.next_chunk(1024).unwrap().is_none());
+    }
+
+    #[test]
+    fn reads_in_chunks() {
+        let mut contents = String::from("timestamp,symbol,price,size,instrument_type\n");
+        for second in 0..5 {
+            contents.push_str(&format!("{},X,1.5,{},stock\n", 1_704_153_600 + second, second + 1));
+        }
+        let path = write("ticks", &contents);
+        let (mut source, has_type_column) = open(&path).unwrap();
+        let sizes: Vec<usize> = std::iter::from_fn(|| source.next_chunk(2).unwrap()).map(|c| c.len()).collect();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(has_type_column);
+        assert_eq!(sizes, [2, 2, 1]);
+    }
+
+    #[test]
+    fn requires_symbol_and_timestamp_columns() {
+        let path = write("nosymbol", "timestamp,price,size\n2024-01-02,1,1\n");
+        let missing = open(&path).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(missing.to_string().ends_with("missing required column 'symbol'"));
+        assert!(matches!(open(Path::new("/nonexistent/simulor.csv")).err().unwrap(), DataError::Io { .. }));
+    }
+
+    #[test]
+    fn yields_nothing_without_record_columns() {
+        let path = write("unknown", "timestamp,symbol,foo\n2024-01-02,X,1\n");
+        let (mut source, _) = open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(source.next_chunk(1024).unwrap().is_none());
+    }
+}
@@ -0,0 +1,319 @@
+//! DBN metadata header
+//!
+//! Every file opens with `DBN`, a version byte and the length of the
+//! metadata that follows. Integers are little-endian and strings are
+//! NUL-padded to a fixed width: 16 bytes for the dataset, 22 for symbols
+//! in version 1 and the width given in the header after that.
+//!
+//! ```text
+//! dataset | schema u16 | start u64 | end u64 | limit u64 | [v1: record count u64]
+//! stype in u8 | stype out u8 | ts_out u8 | [v2+: symbol width u16] | reserved
+//! schema definition length u32 | symbols | partial | not found | mappings
+//! ```
+//!
+//! Symbol lists are a `u32` count then that many symbols. Mappings are a
+//! `u32` count of raw symbols, each followed by a `u32` count of
+//! `start date u32 | end date u32 | symbol` intervals, dates as `YYYYMMDD`
+//! and end dates exclusive.
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use chrono::{DateTime, Datelike};
+
+/// Symbol width of version 1 files
+const SYMBOL_LEN_V1: usize = 22;
+/// Bytes of the dataset name
+const DATASET_LEN: usize = 16;
+/// Reserved bytes before the schema definition length, by version
+const RESERVED_LEN_V1: usize = 47;
+const RESERVED_LEN: usize = 53;
+/// Marks an absent `u16` schema or `u64` timestamp
+const UNDEF_SCHEMA: u16 = u16::MAX;
+const UNDEF_TIMESTAMP: u64 = u64::MAX;
+
+/// Schema names, indexed by their DBN code
+const SCHEMAS: &[&str] = &[
+    "mbo",
+    "mbp-1",
+    "mbp-10",
+    "tbbo",
+    "trades",
+    "ohlcv-1s",
+    "ohlcv-1m",
+    "ohlcv-1h",
+    "ohlcv-1d",
+    "definition",
+    "statistics",
+    "status",
+    "imbalance",
+    "ohlcv-eod",
+    "cmbp-1",
+    "cbbo-1s",
+    "cbbo-1m",
+    "tcbbo",
+    "bbo-1s",
+    "bbo-1m",
+];
+
+/// Decoded metadata of one file
+#[derive(Debug, Clone)]
+pub struct Metadata {
+    pub version: u8,
+    pub dataset: String,
+    /// Schema code, `None` for files mixing schemas
+    pub schema: Option<u16>,
+    /// Epoch nanoseconds of the query's start, and its end if bounded
+    pub start: i64,
+    pub end: Option<i64>,
+    /// Width of symbols in symbol mapping records
+    pub symbol_len: usize,
+    /// Symbols requested
+    pub symbols: Vec<String>,
+    /// Instrument ID → `(start date, end date, raw symbol)` intervals
+    pub mappings: HashMap<u32, Vec<(u32, u32, String)>>,
+}
+
+/// Day of epoch nanoseconds in UTC, as `YYYYMMDD`
+pub fn day(nanos: i64) -> u32 {
+    let date = DateTime::from_timestamp_nanos(nanos).date_naive();
+    date.year() as u32 * 10_000 + date.month() * 100 + date.day()
+}
+
+impl Metadata {
+    /// Name of the file's schema, or "mixed"
+    pub fn schema_name(&self) -> String {
+        match self.schema {
+            None => "mixed".to_owned(),
+            Some(code) => SCHEMAS.get(usize::from(code)).map_or_else(|| code.to_string(), |name| (*name).to_owned()),
+        }
+    }
+
+    /// Raw symbol an instrument ID stood for on the day of `nanos`
+    pub fn symbol(&self, instrument_id: u32, nanos: i64) -> Option<&str> {
+        let day = day(nanos);
+        self.mappings
+            .get(&instrument_id)?
+            .iter()
+            .find(|(start, end, _)| (*start..*end).contains(&day))
+            .map(|(_, _, symbol)| symbol.as_str())
+    }
+}
+
+/// Sequential reads over the metadata bytes
+struct Fields<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Fields<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let bytes = self
+            .bytes
+            .get(self.at..self.at + len)
+            .ok_or_else(|| format!("truncated DBN metadata at byte {}", self.at))?;
+        self.at += len;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().expect("two bytes")))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().expect("four bytes")))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().expect("eight bytes")))
+    }
+
+    fn timestamp(&mut self) -> Result<Option<i64>, String> {
+        Ok(match self.u64()? {
+            UNDEF_TIMESTAMP => None,
+            nanos => Some(i64::try_from(nanos).map_err(|_| format!("DBN timestamp out of range: {nanos}"))?),
+        })
+    }
+
+    fn symbol(&mut self, len: usize) -> Result<String, String> {
+        Ok(c_str(self.take(len)?))
+    }
+
+    fn symbols(&mut self, len: usize) -> Result<Vec<String>, String> {
+        (0..self.u32()?).map(|_| self.symbol(len)).collect()
+    }
+}
+
+/// Text of a NUL-padded string field
+pub fn c_str(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|byte| *byte == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// Read the metadata at the start of a (decompressed) DBN stream
+pub fn read(reader: &mut impl Read) -> Result<Metadata, String> {
+    let mut prelude = [0u8; 8];
+    reader
+        .read_exact(&mut prelude)
+        .map_err(|_| "not a DBN file: shorter than its header".to_owned())?;
+    if &prelude[..3] != b"DBN" {
+        return Err("not a DBN file: missing the DBN signature".to_owned());
+    }
+    let version = prelude[3];
+    if !(1..=3).contains(&version) {
+        return Err(format!("unsupported DBN version {version}"));
+    }
+    let len = u32::from_le_bytes(prelude[4..].try_into().expect("four bytes")) as usize;
+    let mut bytes = vec![0; len];
+    reader.read_exact(&mut bytes).map_err(|_| "truncated DBN metadata".to_owned())?;
+    let mut fields = Fields { bytes: &bytes, at: 0 };
+    let dataset = fields.symbol(DATASET_LEN)?;
+    let schema = Some(fields.u16()?).filter(|schema| *schema != UNDEF_SCHEMA);
+    let start = fields.timestamp()?.unwrap_or(0);
+    let end = fields.timestamp()?;
+    // Limit, and the record count of version 1
+    fields.u64()?;
+    if version == 1 {
+        fields.u64()?;
+    }
+    // Symbology types and whether records carry a send timestamp
+    fields.take(3)?;
+    let symbol_len = if version == 1 {
+        fields.take(RESERVED_LEN_V1)?;
+        SYMBOL_LEN_V1
+    } else {
+        let len = usize::from(fields.u16()?);
+        fields.take(RESERVED_LEN)?;
+        len
+    };
+    let definition_len = fields.u32()? as usize;
+    fields.take(definition_len)?;
+    let symbols = fields.symbols(symbol_len)?;
+    // Partially resolved and unresolved symbols
+    fields.symbols(symbol_len)?;
+    fields.symbols(symbol_len)?;
+    let mut mappings: HashMap<u32, Vec<(u32, u32, String)>> = HashMap::new();
+    for _ in 0..fields.u32()? {
+        let raw = fields.symbol(symbol_len)?;
+        for _ in 0..fields.u32()? {
+            let (start, end) = (fields.u32()?, fields.u32()?);
+            let symbol = fields.symbol(symbol_len)?;
+            // Mapped either way round, the numeric side is the instrument ID
+            let (id, name) = match (symbol.parse::<u32>(), raw.parse::<u32>()) {
+                (Ok(id), _) => (id, raw.clone()),
+                (Err(_), Ok(id)) => (id, symbol),
+                _ => continue,
+            };
+            mappings.entry(id).or_default().push((start, end, name));
+        }
+    }
+    Ok(Metadata {
+        version,
+        dataset,
+        schema,
+        start,
+        end,
+        symbol_len,
+        symbols,
+        mappings,
+    })
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// Epoch nanoseconds of 2024-01-02 00:00 UTC
+    pub const JAN_2: i64 = 1_704_153_600_000_000_000;
+    pub const DAY: i64 = 86_400_000_000_000;
+
+    /// `(start date, end date, symbol)` mappings of one raw symbol
+    pub type Intervals<'a> = &'a [(u32, u32, &'a str)];
+
+    fn padded(text: &str, len: usize) -> Vec<u8> {
+        let mut bytes = text.as_bytes().to_vec();
+        bytes.resize(len, 0);
+        bytes
+    }
+
+    /// Metadata of a file of `schema` requesting AAPL, with `mappings` of raw symbol to intervals
+    pub fn header(version: u8, schema: u16, mappings: &[(&str, Intervals)]) -> Vec<u8> {
+        let symbol_len = if version == 1 { SYMBOL_LEN_V1 } else { 71 };
+        let mut body = padded("XNAS.ITCH", DATASET_LEN);
+        body.extend_from_slice(&schema.to_le_bytes());
+        body.extend_from_slice(&(JAN_2 as u64).to_le_bytes());
+        body.extend_from_slice(&UNDEF_TIMESTAMP.to_le_bytes());
+        body.extend_from_slice(&[0; 8]);
+        if version == 1 {
+            body.extend_from_slice(&[0; 8]);
+        }
+        body.extend_from_slice(&[1, 1, 0]);
+        if version == 1 {
+            body.extend_from_slice(&[0; RESERVED_LEN_V1]);
+        } else {
+            body.extend_from_slice(&(symbol_len as u16).to_le_bytes());
+            body.extend_from_slice(&[0; RESERVED_LEN]);
+        }
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(&1u32.to_le_bytes());
+        body.extend(padded("AAPL", symbol_len));
+        body.extend_from_slice(&[0; 8]);
+        body.extend_from_slice(&(mappings.len() as u32).to_le_bytes());
+        for (raw, intervals) in mappings {
+            body.extend(padded(raw, symbol_len));
+            body.extend_from_slice(&(intervals.len() as u32).to_le_bytes());
+            for (start, end, symbol) in *intervals {
+                body.extend_from_slice(&start.to_le_bytes());
+                body.extend_from_slice(&end.to_le_bytes());
+                body.extend(padded(symbol, symbol_len));
+            }
+        }
+        let mut bytes = vec![b'D', b'B', b'N', version];
+        bytes.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        bytes.extend(body);
+        bytes
+    }
+
+    #[test]
+    fn reads_the_header_and_symbology() {
+        let intervals: Intervals = &[(20240102, 20240103, "42"), (20240103, 20240104, "43")];
+        let bytes = header(2, 4, &[("AAPL", intervals)]);
+        let metadata = read(&mut bytes.as_slice()).unwrap();
+
+        assert_eq!((metadata.version, metadata.dataset.as_str()), (2, "XNAS.ITCH"));
+        assert_eq!((metadata.schema_name(), metadata.start, metadata.end), ("trades".to_owned(), JAN_2, None));
+        assert_eq!((metadata.symbol_len, metadata.symbols.as_slice()), (71, ["AAPL".to_owned()].as_slice()));
+        // Instrument IDs map to the symbol for their day only, end dates exclusive
+        assert_eq!(metadata.symbol(42, JAN_2 + 1), Some("AAPL"));
+        assert_eq!(metadata.symbol(42, JAN_2 + DAY), None);
+        assert_eq!(metadata.symbol(43, JAN_2 + DAY), Some("AAPL"));
+        assert_eq!(metadata.symbol(7, JAN_2), None);
+    }
+
+    #[test]
+    fn reads_version_1_and_mappings_either_way_round() {
+        let bytes = header(1, UNDEF_SCHEMA, &[("42", &[(20240102, 20240103, "AAPL")]), ("MSFT", &[])]);
+        let metadata = read(&mut bytes.as_slice()).unwrap();
+
+        assert_eq!((metadata.schema_name(), metadata.symbol_len), ("mixed".to_owned(), SYMBOL_LEN_V1));
+        assert_eq!(metadata.symbol(42, JAN_2), Some("AAPL"));
+        assert_eq!(metadata.mappings.len(), 1);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let error = |bytes: &[u8]| read(&mut &bytes[..]).unwrap_err();
+        assert_eq!(error(b"DBN"), "not a DBN file: shorter than its header");
+        assert_eq!(error(b"PAR1\0\0\0\0"), "not a DBN file: missing the DBN signature");
+        assert_eq!(error(&header(4, 0, &[])), "unsupported DBN version 4");
+
+        let mut truncated = header(3, 0, &[]);
+        truncated.pop();
+        assert_eq!(error(&truncated), "truncated DBN metadata");
+        // A length that stops short of the fields
+        let mut short = header(3, 0, &[])[..40].to_vec();
+        short[4..8].copy_from_slice(&32u32.to_le_bytes());
+        assert!(error(&short).starts_with("truncated DBN metadata at byte"));
+        assert_eq!(day(JAN_2 - 1), 20240101);
+    }
+}
@@ -0,0 +1,10 @@
+//! Databento Binary Encoding (DBN)
+//!
+//! Plain or zstd-compressed record files decoded into trades, quotes, bars
+//! and order book updates in `MarketEvent`s.
+
+pub mod metadata;
+pub mod reader;
+pub mod record;
+
+pub use reader::{DbnDataProvider, DbnStreamIterator};
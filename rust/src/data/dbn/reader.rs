@@ -0,0 +1,715 @@
+//! DBN data provider
+//!
+//! Files are streamed, decompressing zstd on the fly, and merged on the
+//! time Databento sorts them by: `ts_recv` for market data, and the open
+//! `ts_event` of OHLCV bars. Instrument IDs resolve to raw symbols through
+//! each file's symbology, or the symbol mapping records of live captures.
+//!
+//! ```text
+//! trades        TradeTick
+//! MBP-1, TBBO   QuoteTick of the top of book, plus a TradeTick for trades
+//! MBP-10        snapshot BookUpdate of the ten levels, plus a TradeTick for trades
+//! MBO           replayed into an OrderBook as ITCH is: a BookUpdate per level change, a TradeTick per trade
+//! OHLCV         TradeBar of the schema's resolution
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::book::l3::{update_levels, LevelChange, OrderBook, OrderBookL3, Side};
+use crate::book::update::{BookUpdate, Levels};
+use crate::data::dbn::metadata::{self, day, Metadata};
+use crate::data::dbn::record::{book_side, decode, direction, Body, Print, Record, HEADER_LEN};
+use crate::data::provider::{bound_nanos, discover, symbol_set};
+use crate::data::schema::RecordKind;
+use crate::data::source::{DataError, TimeWindow};
+use crate::events::market_event::MarketEvent;
+use crate::interop::{instrument_type, logger, path_type, zoneinfo_type};
+use crate::types::instrument::{default_registry, InstrumentId};
+use crate::types::market_data::{new_record, MarketData, Resolution};
+use crate::types::time::nanos_to_datetime;
+
+const LOGGER: &str = "simulor.data.providers.dbn";
+
+/// File extensions picked up from a directory, narrowed by `is_dbn`
+const EXTENSIONS: &[&str] = &["dbn", "zst"];
+
+/// First bytes of a zstd frame
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+fn is_dbn(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(".dbn") || name.ends_with(".dbn.zst"))
+}
+
+/// Open a DBN file, decompressing it if it is zstd, and read its metadata
+fn open(path: &Path) -> Result<(Box<dyn Read + Send + Sync>, Metadata), DataError> {
+    let io_error = |source| DataError::Io {
+        path: path.to_owned(),
+        source,
+    };
+    let mut file = BufReader::new(File::open(path).map_err(io_error)?);
+    let compressed = file.fill_buf().map_err(io_error)?.starts_with(&ZSTD_MAGIC);
+    let mut reader: Box<dyn Read + Send + Sync> = if compressed {
+        Box::new(zstd::stream::read::Decoder::with_buffer(file).map_err(io_error)?)
+    } else {
+        Box::new(file)
+    };
+    let metadata = metadata::read(&mut reader).map_err(|message| DataError::Malformed {
+        path: path.to_owned(),
+        message,
+    })?;
+    Ok((reader, metadata))
+}
+
+/// One file being read
+struct Source {
+    path: PathBuf,
+    reader: Box<dyn Read + Send + Sync>,
+    metadata: Metadata,
+    /// Instrument ID → symbol from symbol mapping records, ahead of the metadata's
+    live: HashMap<u32, String>,
+    /// Instrument ID and day → index into `tracked`, `None` when filtered out
+    resolved: HashMap<(u32, u32), Option<usize>>,
+    /// Instrument IDs warned about as missing from the symbology
+    unmapped: HashSet<u32>,
+    /// Whether the file already warned about unknown orders
+    warned: bool,
+    buf: Vec<u8>,
+    /// Next record, read ahead for the merge
+    head: Option<Record>,
+}
+
+impl Source {
+    fn new(path: PathBuf) -> Result<Self, DataError> {
+        let (reader, metadata) = open(&path)?;
+        Ok(Source {
+            path,
+            reader,
+            metadata,
+            live: HashMap::new(),
+            resolved: HashMap::new(),
+            unmapped: HashSet::new(),
+            warned: false,
+            buf: Vec::new(),
+            head: None,
+        })
+    }
+
+    /// Next record of a type this reader decodes, or `None` at the end of the file
+    fn read_record(&mut self) -> Result<Option<Record>, DataError> {
+        let malformed = |path: &Path, message| DataError::Malformed {
+            path: path.to_owned(),
+            message,
+        };
+        loop {
+            let mut words = [0u8; 1];
+            match self.reader.read_exact(&mut words) {
+                Ok(()) => {}
+                Err(error) if error.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+                Err(source) => {
+                    return Err(DataError::Io {
+                        path: self.path.clone(),
+                        source,
+                    })
+                }
+            }
+            let len = usize::from(words[0]) * 4;
+            if len < HEADER_LEN {
+                return Err(malformed(&self.path, format!("DBN record of {len} bytes is shorter than its header")));
+            }
+            self.buf.resize(len, 0);
+            self.buf[0] = words[0];
+            if let Err(source) = self.reader.read_exact(&mut self.buf[1..]) {
+                return Err(match source.kind() {
+                    ErrorKind::UnexpectedEof => malformed(&self.path, "truncated DBN record".to_owned()),
+                    _ => DataError::Io {
+                        path: self.path.clone(),
+                        source,
+                    },
+                });
+            }
+            let record = decode(&self.buf, self.metadata.version, self.metadata.symbol_len)
+                .map_err(|message| malformed(&self.path, message))?;
+            if record.is_some() {
+                return Ok(record);
+            }
+        }
+    }
+}
+
+/// One instrument's book and output state
+struct Tracked {
+    instrument: Py<PyAny>,
+    instrument_id: InstrumentId,
+    /// Book replayed from MBO records
+    book: OrderBook,
+    /// Sequence number of the last `BookUpdate` emitted
+    sequence: u64,
+    /// Whether the MBO book's snapshot was emitted
+    announced: bool,
+    updated: Option<(Py<PyAny>, i64)>,
+}
+
+/// What one MBO record produced, before records are built
+enum Output {
+    Levels(Vec<LevelChange>),
+    Trade(Print),
+    /// The book was cleared
+    Clear,
+}
+
+/// Unsequenced update of the given levels
+fn levels(bids: Levels, asks: Levels, snapshot: bool) -> BookUpdate {
+    BookUpdate {
+        bids,
+        asks,
+        sequence: None,
+        snapshot,
+    }
+}
+
+/// Iterator over the events of a `DbnDataProvider`
+///
+/// Besides events, it exposes the Level 3 book replayed from each
+/// instrument's MBO records as of the last event returned.
+#[pyclass(module = "_simulor_rust")]
+pub struct DbnStreamIterator {
+    sources: Vec<Source>,
+    /// Index time, arrival counter and source of each source's next record
+    heap: BinaryHeap<Reverse<(i64, u64, usize)>>,
+    counter: u64,
+    tzinfo: Py<PyAny>,
+    resolutions: HashMap<Resolution, Py<PyAny>>,
+    window: TimeWindow,
+    symbols: Option<HashSet<String>>,
+    tracked: Vec<Tracked>,
+    by_symbol: HashMap<String, usize>,
+}
+
+impl DbnStreamIterator {
+    /// Read a source's next record and queue it, unless the source is past the window
+    fn advance(&mut self, index: usize) -> Result<(), DataError> {
+        let source = &mut self.sources[index];
+        source.head = source.read_record()?;
+        match &source.head {
+            // Files are in time order, so nothing later is wanted
+            Some(record) if self.window.end.is_some_and(|end| record.nanos > end) => source.head = None,
+            Some(record) => {
+                self.heap.push(Reverse((record.nanos, self.counter, index)));
+                self.counter += 1;
+            }
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Index of a record's instrument state, or `None` if filtered out
+    fn resolve(&mut self, py: Python<'_>, index: usize, record: &Record) -> PyResult<Option<usize>> {
+        let id = record.instrument_id;
+        let key = (id, day(record.nanos));
+        if let Some(tracked) = self.sources[index].resolved.get(&key) {
+            return Ok(*tracked);
+        }
+        let source = &mut self.sources[index];
+        let symbol = match source.live.get(&id) {
+            Some(symbol) => symbol.clone(),
+            None => match source.metadata.symbol(id, record.nanos) {
+                Some(symbol) => symbol.to_owned(),
+                None => {
+                    if source.unmapped.insert(id) {
+                        logger(py, LOGGER)?.call_method1(
+                            "warning",
+                            (
+                                "%s: instrument ID %d has no symbology mapping; using the ID as its symbol",
+                                source.path.display().to_string(),
+                                id,
+                            ),
+                        )?;
+                    }
+                    id.to_string()
+                }
+            },
+        };
+        let tracked = self.track(py, &symbol)?;
+        self.sources[index].resolved.insert(key, tracked);
+        Ok(tracked)
+    }
+
+    /// Index of `symbol`'s state, created on first sight, or `None` if filtered out
+    fn track(&mut self, py: Python<'_>, symbol: &str) -> PyResult<Option<usize>> {
+        if self.symbols.as_ref().is_some_and(|symbols| !symbols.contains(symbol)) {
+            return Ok(None);
+        }
+        if let Some(index) = self.by_symbol.get(symbol) {
+            return Ok(Some(*index));
+        }
+        let instrument = instrument_type(py)?.call_method1("stock", (symbol,))?;
+        let instrument_id = default_registry(py)?.get().intern_instrument(&instrument)?;
+        self.tracked.push(Tracked {
+            instrument: instrument.unbind(),
+            instrument_id,
+            book: OrderBook::default(),
+            sequence: 0,
+            announced: false,
+            updated: None,
+        });
+        self.by_symbol.insert(symbol.to_owned(), self.tracked.len() - 1);
+        Ok(Some(self.tracked.len() - 1))
+    }
+
+    /// Apply an MBO record to its book; `None` if it names an unknown or duplicate order
+    ///
+    /// Trades and fills leave the book alone: the cancels and modifies that
+    /// follow them carry the size taken.
+    fn apply(book: &mut OrderBook, body: &Body) -> PyResult<Option<Vec<Output>>> {
+        let Body::Mbo {
+            order,
+            action,
+            side,
+            price,
+            size,
+        } = body
+        else {
+            return Ok(Some(Vec::new()));
+        };
+        let known = book.order(*order);
+        Ok(Some(match (action, known) {
+            (b'A', Some(_)) | (b'C', None) => return Ok(None),
+            // A modify of an order not in the book adds it
+            (b'A' | b'M', None) => match (book_side(*side), price) {
+                (Some(side), Some(price)) if size.is_positive() => {
+                    vec![Output::Levels(vec![book.add(*order, side, *price, *size)?])]
+                }
+                _ => Vec::new(),
+            },
+            (b'C', Some(_)) => vec![Output::Levels(vec![book.reduce(*order, *size)?.0])],
+            (b'M', Some((_, resting_price, resting_size))) => {
+                let price = price.unwrap_or(resting_price);
+                if size.is_zero() {
+                    vec![Output::Levels(vec![book.delete(*order)?])]
+                } else if price == resting_price && *size <= resting_size {
+                    // Shrinking in place keeps the order's priority
+                    let change = book.reduce(*order, resting_size.checked_sub(*size)?)?.0;
+                    vec![Output::Levels(vec![change])]
+                } else {
+                    vec![Output::Levels(book.replace(*order, *order, price, *size)?)]
+                }
+            }
+            (b'R', _) => {
+                *book = OrderBook::default();
+                vec![Output::Clear]
+            }
+            (b'T', _) => match price {
+                Some(price) if size.is_positive() => vec![Output::Trade(Print {
+                    price: *price,
+                    size: *size,
+                    direction: direction(*side),
+                })],
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        }))
+    }
+
+    fn warn_unknown(&mut self, py: Python<'_>, index: usize, nanos: i64) -> PyResult<()> {
+        let source = &mut self.sources[index];
+        if source.warned {
+            return Ok(());
+        }
+        source.warned = true;
+        logger(py, LOGGER)?.call_method1(
+            "warning",
+            (
+                "%s: MBO record at %d ns does not match the book (unknown or duplicate order); \
+                 books may be incomplete if the file does not start with a snapshot",
+                source.path.display().to_string(),
+                nanos,
+            ),
+        )?;
+        Ok(())
+    }
+
+    fn base(
+        &mut self,
+        py: Python<'_>,
+        tracked: usize,
+        time: &Py<PyAny>,
+        nanos: i64,
+        resolution: Resolution,
+    ) -> PyResult<MarketData> {
+        let resolution_py = match self.resolutions.get(&resolution) {
+            Some(obj) => obj.clone_ref(py),
+            None => {
+                let obj = resolution.to_py(py)?.unbind();
+                self.resolutions.insert(resolution, obj.clone_ref(py));
+                obj
+            }
+        };
+        Ok(MarketData::from_parts(
+            time.clone_ref(py),
+            self.tracked[tracked].instrument.clone_ref(py),
+            resolution_py,
+            resolution,
+            nanos,
+        ))
+    }
+
+    /// Add `update` to `event`, stamped with the instrument's next sequence number
+    fn book_update(
+        &mut self,
+        py: Python<'_>,
+        event: &mut MarketEvent,
+        tracked: usize,
+        time: &Py<PyAny>,
+        nanos: i64,
+        mut update: BookUpdate,
+    ) -> PyResult<()> {
+        let state = &mut self.tracked[tracked];
+        state.sequence += 1;
+        update.sequence = Some(state.sequence);
+        let base = self.base(py, tracked, time, nanos, Resolution::Tick)?;
+        event.add_record(new_record(py, base, update)?.as_any())
+    }
+
+    fn trade(
+        &mut self,
+        py: Python<'_>,
+        event: &mut MarketEvent,
+        tracked: usize,
+        time: &Py<PyAny>,
+        nanos: i64,
+        print: Print,
+    ) -> PyResult<()> {
+        let base = self.base(py, tracked, time, nanos, Resolution::Tick)?;
+        let values = [print.price, print.size];
+        event.add_record(&RecordKind::TradeTick.build(py, base, &values, Some(print.direction))?)
+    }
+
+    /// Turn one in-window record into records of `event`
+    fn emit(
+        &mut self,
+        py: Python<'_>,
+        event: &mut MarketEvent,
+        index: usize,
+        tracked: usize,
+        time: &Py<PyAny>,
+        record: Record,
+    ) -> PyResult<()> {
+        let nanos = record.nanos;
+        match record.body {
+            Body::Trade(print) => self.trade(py, event, tracked, time, nanos, print)?,
+            Body::Mbp1 { trade, level } => {
+                if let Some(print) = trade {
+                    self.trade(py, event, tracked, time, nanos, print)?;
+                }
+                if let (Some((bid, bid_size)), Some((ask, ask_size))) = (level.bid, level.ask) {
+                    let base = self.base(py, tracked, time, nanos, Resolution::Tick)?;
+                    let values = [bid, bid_size, ask, ask_size];
+                    event.add_record(&RecordKind::QuoteTick.build(py, base, &values, None)?)?;
+                }
+            }
+            Body::Mbp10 { trade, levels: depth } => {
+                if let Some(print) = trade {
+                    self.trade(py, event, tracked, time, nanos, print)?;
+                }
+                let bids = depth.iter().filter_map(|level| level.bid).collect();
+                let asks = depth.iter().filter_map(|level| level.ask).collect();
+                self.book_update(py, event, tracked, time, nanos, levels(bids, asks, true))?;
+            }
+            Body::Ohlcv { resolution, values } => {
+                let base = self.base(py, tracked, time, nanos, resolution)?;
+                event.add_record(&RecordKind::TradeBar.build(py, base, &values, None)?)?;
+            }
+            Body::Mbo { action, .. } => {
+                // A clear is a snapshot of its own
+                if !std::mem::replace(&mut self.tracked[tracked].announced, true) && action != b'R' {
+                    let book = &self.tracked[tracked].book;
+                    let snapshot = levels(book.depth(Side::Bid).collect(), book.depth(Side::Ask).collect(), true);
+                    self.book_update(py, event, tracked, time, nanos, snapshot)?;
+                }
+                let Some(outputs) = Self::apply(&mut self.tracked[tracked].book, &record.body)? else {
+                    return self.warn_unknown(py, index, nanos);
+                };
+                for output in outputs {
+                    match output {
+                        Output::Levels(changes) => {
+                            let (bids, asks) = update_levels(changes);
+                            self.book_update(py, event, tracked, time, nanos, levels(bids, asks, false))?;
+                        }
+                        Output::Trade(print) => self.trade(py, event, tracked, time, nanos, print)?,
+                        Output::Clear => {
+                            let cleared = levels(Vec::new(), Vec::new(), true);
+                            self.book_update(py, event, tracked, time, nanos, cleared)?;
+                        }
+                    }
+                }
+                self.tracked[tracked].updated = Some((time.clone_ref(py), nanos));
+            }
+            Body::SymbolMapping { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl DbnStreamIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<MarketEvent>> {
+        let mut current: Option<(i64, Py<PyAny>, MarketEvent)> = None;
+        while let Some(&Reverse((nanos, _, index))) = self.heap.peek() {
+            if current.as_ref().is_some_and(|(time, _, _)| *time != nanos) {
+                break;
+            }
+            self.heap.pop();
+            let record = self.sources[index].head.take().expect("queued sources have a record");
+            self.advance(index)?;
+            if let Body::SymbolMapping { symbol } = &record.body {
+                let source = &mut self.sources[index];
+                source.live.insert(record.instrument_id, symbol.clone());
+                source.resolved.retain(|(id, _), _| *id != record.instrument_id);
+                continue;
+            }
+            let Some(tracked) = self.resolve(py, index, &record)? else {
+                continue;
+            };
+            if !self.window.contains(nanos) {
+                // Before the window: keep MBO books current without emitting
+                if Self::apply(&mut self.tracked[tracked].book, &record.body)?.is_none() {
+                    self.warn_unknown(py, index, nanos)?;
+                }
+                continue;
+            }
+            let (_, time, mut event) = match current.take() {
+                Some(current) => current,
+                None => {
+                    let time = nanos_to_datetime(py, nanos, Some(self.tzinfo.bind(py)))?.unbind();
+                    let event = MarketEvent::new(time.clone_ref(py));
+                    (nanos, time, event)
+                }
+            };
+            self.emit(py, &mut event, index, tracked, &time, record)?;
+            current = Some((nanos, time, event));
+        }
+        Ok(current.map(|(_, _, event)| event))
+    }
+
+    /// Level 3 book an instrument's (or symbol's) MBO records built as of the last event, or `None`
+    fn order_book(&self, py: Python<'_>, instrument: &Bound<'_, PyAny>) -> PyResult<Option<OrderBookL3>> {
+        let symbol: String = match instrument.extract() {
+            Ok(symbol) => symbol,
+            Err(_) => instrument.getattr("symbol")?.extract()?,
+        };
+        Ok(self.by_symbol.get(&symbol).map(|index| {
+            let tracked = &self.tracked[*index];
+            OrderBookL3::from_book(
+                tracked.instrument.clone_ref(py),
+                tracked.instrument_id,
+                tracked.book.clone(),
+                tracked.updated.as_ref().map(|(time, nanos)| (time.clone_ref(py), *nanos)),
+            )
+        }))
+    }
+
+    /// Instruments seen so far
+    #[getter]
+    fn instruments<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        PyList::new(py, self.tracked.iter().map(|tracked| tracked.instrument.clone_ref(py)))
+    }
+}
+
+/// Read Databento DBN files as trades, quotes, bars and order book updates
+///
+/// Args: `path` to a `.dbn` or zstd-compressed `.dbn.zst` file, or a
+/// directory of them; `timezone` event times are expressed in (default
+/// "UTC"); and optional inclusive `start`/`end` datetimes and `symbols` to
+/// restrict the events emitted. Files of different schemas and symbols
+/// are merged into one stream.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct DbnDataProvider {
+    data_path: Py<PyAny>,
+    timezone_info: Py<PyAny>,
+    files: Vec<(PathBuf, Metadata)>,
+    window: TimeWindow,
+    symbols: Option<HashSet<String>>,
+}
+
+#[pymethods]
+impl DbnDataProvider {
+    #[new]
+    #[pyo3(signature = (path, timezone="UTC", start=None, end=None, symbols=None))]
+    fn py_new(
+        py: Python<'_>,
+        path: PathBuf,
+        timezone: &str,
+        start: Option<&Bound<'_, PyAny>>,
+        end: Option<&Bound<'_, PyAny>>,
+        symbols: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Self> {
+        let timezone_info = zoneinfo_type(py)?.call1((timezone,))?;
+        let paths = if path.is_dir() {
+            let mut files = discover(&path, EXTENSIONS)?;
+            files.retain(|file| is_dbn(file));
+            if files.is_empty() {
+                return Err(PyValueError::new_err(format!("No DBN files found in directory: {}", path.display())));
+            }
+            files
+        } else {
+            vec![path.clone()]
+        };
+        let mut files = Vec::with_capacity(paths.len());
+        for file in paths {
+            let (_, metadata) = open(&file)?;
+            files.push((file, metadata));
+        }
+        files.sort_by_key(|(_, metadata)| metadata.start);
+        let message = ("Loaded %d DBN files from %s", files.len(), path.display().to_string());
+        logger(py, LOGGER)?.call_method1("info", message)?;
+        Ok(DbnDataProvider {
+            data_path: path_type(py)?.call1((path,))?.unbind(),
+            window: TimeWindow {
+                start: bound_nanos(py, start, &timezone_info)?,
+                end: bound_nanos(py, end, &timezone_info)?,
+            },
+            timezone_info: timezone_info.unbind(),
+            files,
+            symbols: symbol_set(symbols)?,
+        })
+    }
+
+    /// Path to the DBN file or directory
+    #[getter]
+    fn data_path(&self, py: Python<'_>) -> Py<PyAny> {
+        self.data_path.clone_ref(py)
+    }
+
+    /// Timezone event times are expressed in
+    #[getter]
+    fn timezone_info(&self, py: Python<'_>) -> Py<PyAny> {
+        self.timezone_info.clone_ref(py)
+    }
+
+    /// Files read by this provider, by start time
+    #[getter]
+    fn files<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let path = path_type(py)?;
+        PyList::new(py, self.files.iter().map(|(f, _)| path.call1((f,))).collect::<PyResult<Vec<_>>>()?)
+    }
+
+    /// Metadata of each file, in the order of `files`
+    ///
+    /// Each is a dict of the DBN `version`, `dataset`, `schema` name,
+    /// query `start` and `end` (`None` when open-ended) and the `symbols`
+    /// requested.
+    #[getter]
+    fn metadata<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let tzinfo = self.timezone_info.bind(py);
+        let list = PyList::empty(py);
+        for (_, metadata) in &self.files {
+            let dict = PyDict::new(py);
+            dict.set_item("version", metadata.version)?;
+            dict.set_item("dataset", &metadata.dataset)?;
+            dict.set_item("schema", metadata.schema_name())?;
+            dict.set_item("start", nanos_to_datetime(py, metadata.start, Some(tzinfo))?)?;
+            let end = metadata.end.map(|end| nanos_to_datetime(py, end, Some(tzinfo))).transpose()?;
+            dict.set_item("end", end)?;
+            dict.set_item("symbols", &metadata.symbols)?;
+            list.append(dict)?;
+        }
+        Ok(list)
+    }
+
+    /// Return a new iterator over the data, one `MarketEvent` per timestamp
+    fn __iter__(&self, py: Python<'_>) -> PyResult<DbnStreamIterator> {
+        let sources = self
+            .files
+            .iter()
+            .map(|(path, _)| Source::new(path.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut iterator = DbnStreamIterator {
+            sources,
+            heap: BinaryHeap::new(),
+            counter: 0,
+            tzinfo: self.timezone_info.clone_ref(py),
+            resolutions: HashMap::new(),
+            window: self.window,
+            symbols: self.symbols.clone(),
+            tracked: Vec::new(),
+            by_symbol: HashMap::new(),
+        };
+        for index in 0..iterator.sources.len() {
+            iterator.advance(index)?;
+        }
+        Ok(iterator)
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "DbnDataProvider('{}', files={})",
+            self.data_path.bind(py).str()?,
+            self.files.len()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::dbn::metadata::tests::header;
+    use crate::data::dbn::record::tests::{record, trade};
+
+    fn write(name: &str, bytes: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("simulor-dbn-{}-{name}", std::process::id()));
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn reads_plain_and_compressed_files() {
+        let mut bytes = header(3, 4, &[("AAPL", &[(20240101, 20240201, "42")])]);
+        // A definition record is skipped by its length
+        bytes.extend(record(0x13, 64));
+        bytes.extend(trade(0x00, 48));
+        let compressed = zstd::stream::encode_all(bytes.as_slice(), 0).unwrap();
+        for (name, contents) in [("plain.dbn", &bytes), ("compressed.dbn.zst", &compressed)] {
+            let path = write(name, contents);
+            let mut source = Source::new(path.clone()).unwrap();
+            let first = source.read_record().unwrap().unwrap();
+            let end = source.read_record().unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            assert!(is_dbn(&path));
+            assert_eq!(source.metadata.symbol(first.instrument_id, first.nanos), Some("AAPL"));
+            assert!(matches!(first.body, Body::Trade(_)));
+            assert_eq!(end, None);
+        }
+        assert!(!is_dbn(Path::new("trades.zst")));
+    }
+
+    #[test]
+    fn rejects_truncated_records() {
+        let mut bytes = header(2, 4, &[]);
+        bytes.extend(&trade(0x00, 48)[..40]);
+        let path = write("truncated.dbn", &bytes);
+        let mut source = Source::new(path.clone()).unwrap();
+        let error = source.read_record().unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(error.to_string().ends_with("truncated DBN record"));
+
+        let path = write("parquet.dbn", b"PAR1\0\0\0\0");
+        let error = Source::new(path.clone()).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(error.to_string().ends_with("missing the DBN signature"));
+    }
+}
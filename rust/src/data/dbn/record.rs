@@ -0,0 +1,416 @@
+//! DBN record decoding
+//!
+//! Records follow the metadata back to back. Each opens with a 16-byte
+//! header: its length in 4-byte words, record type, publisher ID,
+//! instrument ID and `ts_event`. Integers are little-endian and prices are
+//! `i64` with nine implied decimals, `i64::MAX` marking an absent price.
+//! Only the types simulor has records for are decoded; the rest are
+//! skipped by length.
+//!
+//! ```text
+//! 0x00  trades          price | size | action | side | flags | depth | ts_recv | delta | sequence
+//! 0x01  MBP-1, TBBO     as trades, then one bid/ask level
+//! 0x0A  MBP-10          as trades, then ten bid/ask levels
+//! 0x16  symbol mapping  [v2+: stype in] | in symbol | [v2+: stype out] | out symbol | start | end
+//! 0x20  OHLCV-1s        open | high | low | close | volume u64 (also 0x21 1m, 0x22 1h, 0x23 1d, 0x24 EOD)
+//! 0xA0  MBO             order ID | price | size | flags | channel | action | side | ts_recv | ...
+//!
+//! level: bid price | ask price | bid size u32 | ask size u32 | bid count u32 | ask count u32
+//! ```
+
+use crate::book::l3::Side;
+use crate::data::dbn::metadata::c_str;
+use crate::types::fixed::Fixed;
+use crate::types::market_data::{Resolution, TickDirection};
+
+/// Implied decimals of a DBN price
+const PRICE_SCALE: u8 = 9;
+const UNDEF_PRICE: i64 = i64::MAX;
+/// Bytes of the record header
+pub const HEADER_LEN: usize = 16;
+/// Bytes of one MBP bid/ask level, and where levels start
+const LEVEL_LEN: usize = 32;
+const LEVELS_AT: usize = 48;
+
+/// A trade print
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Print {
+    pub price: Fixed,
+    pub size: Fixed,
+    pub direction: TickDirection,
+}
+
+/// One bid/ask level, each side `(price, size)` when present
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Level {
+    pub bid: Option<(Fixed, Fixed)>,
+    pub ask: Option<(Fixed, Fixed)>,
+}
+
+/// A decoded record of interest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Body {
+    Trade(Print),
+    /// Top of book after an event, with its trade if it was one
+    Mbp1 {
+        trade: Option<Print>,
+        level: Level,
+    },
+    /// Ten levels of depth after an event, with its trade if it was one
+    Mbp10 {
+        trade: Option<Print>,
+        levels: Vec<Level>,
+    },
+    Mbo {
+        order: u64,
+        action: u8,
+        side: u8,
+        price: Option<Fixed>,
+        size: Fixed,
+    },
+    Ohlcv {
+        resolution: Resolution,
+        /// Open, high, low, close and volume
+        values: [Fixed; 5],
+    },
+    SymbolMapping {
+        symbol: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub instrument_id: u32,
+    /// Epoch nanoseconds of `ts_recv`, or `ts_event` for types without it
+    pub nanos: i64,
+    pub body: Body,
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().expect("four bytes"))
+}
+
+fn u64_at(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().expect("eight bytes"))
+}
+
+fn price_at(bytes: &[u8], at: usize) -> Option<Fixed> {
+    match u64_at(bytes, at) as i64 {
+        UNDEF_PRICE => None,
+        raw => Some(
+            Fixed::new(i128::from(raw), PRICE_SCALE)
+                .expect("DBN prices have a valid scale")
+                .normalize(),
+        ),
+    }
+}
+
+fn size_at(bytes: &[u8], at: usize) -> Fixed {
+    Fixed::from_int(i128::from(u32_at(bytes, at)))
+}
+
+/// Epoch nanoseconds of a timestamp field, `None` when absent
+fn timestamp_at(bytes: &[u8], at: usize) -> Option<i64> {
+    i64::try_from(u64_at(bytes, at)).ok()
+}
+
+/// Book side of an order
+pub fn book_side(side: u8) -> Option<Side> {
+    match side {
+        b'B' => Some(Side::Bid),
+        b'A' => Some(Side::Ask),
+        _ => None,
+    }
+}
+
+/// Trades carry the aggressor's side: an ask is a sell hitting the bid
+pub fn direction(side: u8) -> TickDirection {
+    match side {
+        b'B' => TickDirection::Buy,
+        b'A' => TickDirection::Sell,
+        _ => TickDirection::Neutral,
+    }
+}
+
+/// Trade print of a trades or MBP record, when it is one with a price
+fn print_at(bytes: &[u8]) -> Option<Print> {
+    if bytes[28] != b'T' {
+        return None;
+    }
+    Some(Print {
+        price: price_at(bytes, 16)?,
+        size: size_at(bytes, 24),
+        direction: direction(bytes[29]),
+    })
+}
+
+fn level_at(bytes: &[u8], index: usize) -> Level {
+    let at = LEVELS_AT + index * LEVEL_LEN;
+    Level {
+        bid: price_at(bytes, at).map(|price| (price, size_at(bytes, at + 16))),
+        ask: price_at(bytes, at + 8).map(|price| (price, size_at(bytes, at + 20))),
+    }
+}
+
+/// Length of the record types decoded here, header included
+fn expected_len(rtype: u8, version: u8, symbol_len: usize) -> Option<usize> {
+    Some(match rtype {
+        0x00 => 48,
+        0x01 => LEVELS_AT + LEVEL_LEN,
+        0x0A => LEVELS_AT + 10 * LEVEL_LEN,
+        // Version 1 pads its two symbols with four bytes rather than stypes
+        0x16 if version == 1 => HEADER_LEN + 2 * symbol_len + 4 + 16,
+        0x16 => HEADER_LEN + 2 * (symbol_len + 1) + 16,
+        0x20..=0x24 | 0xA0 => 56,
+        _ => return None,
+    })
+}
+
+/// Decode one record, or `None` for a type (or an empty print) this reader skips
+pub fn decode(bytes: &[u8], version: u8, symbol_len: usize) -> Result<Option<Record>, String> {
+    if bytes.len() < HEADER_LEN {
+        return Err(format!("DBN record of {} bytes is shorter than its header", bytes.len()));
+    }
+    let rtype = bytes[1];
+    let Some(len) = expected_len(rtype, version, symbol_len) else {
+        return Ok(None);
+    };
+    if bytes.len() < len {
+        return Err(format!("DBN record of type {rtype:#04x} has {} bytes, expected {len}", bytes.len()));
+    }
+    let ts_event = timestamp_at(bytes, 8).ok_or("DBN record without an event timestamp")?;
+    let (nanos, body) = match rtype {
+        0x00 | 0x01 | 0x0A => {
+            let nanos = timestamp_at(bytes, 32).unwrap_or(ts_event);
+            let trade = print_at(bytes);
+            let body = match rtype {
+                0x00 => match trade {
+                    Some(print) => Body::Trade(print),
+                    None => return Ok(None),
+                },
+                0x01 => Body::Mbp1 {
+                    trade,
+                    level: level_at(bytes, 0),
+                },
+                _ => Body::Mbp10 {
+                    trade,
+                    levels: (0..10).map(|index| level_at(bytes, index)).collect(),
+                },
+            };
+            (nanos, body)
+        }
+        0x16 => {
+            let at = if version == 1 { HEADER_LEN } else { HEADER_LEN + 1 };
+            let symbol = c_str(&bytes[at..at + symbol_len]);
+            (ts_event, Body::SymbolMapping { symbol })
+        }
+        0x20..=0x24 => {
+            let resolution = match rtype {
+                0x20 => Resolution::Second,
+                0x21 => Resolution::Minute,
+                0x22 => Resolution::Hour,
+                _ => Resolution::Daily,
+            };
+            let mut values = [Fixed::ZERO; 5];
+            for (index, value) in values[..4].iter_mut().enumerate() {
+                let Some(price) = price_at(bytes, 16 + 8 * index) else {
+                    return Ok(None);
+                };
+                *value = price;
+            }
+            values[4] = Fixed::from_int(i128::from(u64_at(bytes, 48)));
+            (ts_event, Body::Ohlcv { resolution, values })
+        }
+        0xA0 => {
+            let nanos = timestamp_at(bytes, 40).unwrap_or(ts_event);
+            let body = Body::Mbo {
+                order: u64_at(bytes, 16),
+                action: bytes[38],
+                side: bytes[39],
+                price: price_at(bytes, 24),
+                size: size_at(bytes, 32),
+            };
+            (nanos, body)
+        }
+        _ => unreachable!("only types with an expected length are decoded"),
+    };
+    Ok(Some(Record {
+        instrument_id: u32_at(bytes, 4),
+        nanos,
+        body,
+    }))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// Epoch nanoseconds of 2024-01-02 14:30 UTC
+    pub const OPEN: i64 = 1_704_205_800_000_000_000;
+
+    /// A record of `rtype` and `len` bytes for instrument 42 at `OPEN`, zeroed after its header
+    pub fn record(rtype: u8, len: usize) -> Vec<u8> {
+        let mut bytes = vec![0; len];
+        bytes[0] = (len / 4) as u8;
+        bytes[1] = rtype;
+        bytes[4..8].copy_from_slice(&42u32.to_le_bytes());
+        bytes[8..16].copy_from_slice(&OPEN.to_le_bytes());
+        bytes
+    }
+
+    pub fn put(bytes: &mut [u8], at: usize, value: &[u8]) {
+        bytes[at..at + value.len()].copy_from_slice(value);
+    }
+
+    /// A DBN price of `literal`
+    pub fn price(literal: &str) -> [u8; 8] {
+        let fixed: Fixed = literal.parse().unwrap();
+        ((fixed.raw() * 10i128.pow(u32::from(PRICE_SCALE - fixed.scale()))) as i64).to_le_bytes()
+    }
+
+    fn fixed(literal: &str) -> Fixed {
+        literal.parse().unwrap()
+    }
+
+    /// A trade of 100 at 185.05 by a buyer, received 1µs after the event
+    pub fn trade(rtype: u8, len: usize) -> Vec<u8> {
+        let mut bytes = record(rtype, len);
+        put(&mut bytes, 16, &price("185.05"));
+        put(&mut bytes, 24, &100u32.to_le_bytes());
+        put(&mut bytes, 28, b"TB");
+        put(&mut bytes, 32, &(OPEN + 1_000).to_le_bytes());
+        bytes
+    }
+
+    fn decoded(bytes: &[u8]) -> Option<Record> {
+        decode(bytes, 2, 71).unwrap()
+    }
+
+    #[test]
+    fn decodes_trades() {
+        let print = Print {
+            price: fixed("185.05"),
+            size: Fixed::from_int(100),
+            direction: TickDirection::Buy,
+        };
+        let expected = Record {
+            instrument_id: 42,
+            nanos: OPEN + 1_000,
+            body: Body::Trade(print),
+        };
+        assert_eq!(decoded(&trade(0x00, 48)), Some(expected));
+
+        // Records without a receive time fall back to the event's
+        let mut bytes = trade(0x00, 48);
+        put(&mut bytes, 29, b"N");
+        put(&mut bytes, 32, &u64::MAX.to_le_bytes());
+        let record = decoded(&bytes).unwrap();
+        assert_eq!(record.nanos, OPEN);
+        assert!(matches!(
+            record.body,
+            Body::Trade(Print {
+                direction: TickDirection::Neutral,
+                ..
+            })
+        ));
+        // Only trade actions print
+        put(&mut bytes, 28, b"A");
+        assert_eq!(decoded(&bytes), None);
+    }
+
+    #[test]
+    fn decodes_book_levels() {
+        let mut bytes = trade(0x01, 80);
+        put(&mut bytes, 28, b"C");
+        put(&mut bytes, 48, &price("185.04"));
+        put(&mut bytes, 56, &i64::MAX.to_le_bytes());
+        put(&mut bytes, 64, &300u32.to_le_bytes());
+        let Some(Record {
+            body: Body::Mbp1 { trade: print, level },
+            ..
+        }) = decoded(&bytes)
+        else {
+            panic!("not an MBP-1 record");
+        };
+        assert_eq!(print, None);
+        // An absent price leaves its side of the level empty
+        assert_eq!(level.bid, Some((fixed("185.04"), Fixed::from_int(300))));
+        assert_eq!(level.ask, None);
+
+        let mut bytes = trade(0x0A, 368);
+        put(&mut bytes, 48 + 9 * 32 + 8, &price("186"));
+        put(&mut bytes, 48 + 9 * 32 + 20, &5u32.to_le_bytes());
+        let Some(Record {
+            body: Body::Mbp10 { trade: print, levels },
+            ..
+        }) = decoded(&bytes)
+        else {
+            panic!("not an MBP-10 record");
+        };
+        assert_eq!(print.map(|print| print.size), Some(Fixed::from_int(100)));
+        assert_eq!(levels.len(), 10);
+        assert_eq!(levels[9].ask, Some((fixed("186"), Fixed::from_int(5))));
+    }
+
+    #[test]
+    fn decodes_bars_orders_and_symbol_mappings() {
+        let mut bytes = record(0x21, 56);
+        for (index, literal) in ["10", "12.5", "9.75", "11"].iter().enumerate() {
+            put(&mut bytes, 16 + 8 * index, &price(literal));
+        }
+        put(&mut bytes, 48, &5_000_000_000u64.to_le_bytes());
+        let expected = Body::Ohlcv {
+            resolution: Resolution::Minute,
+            values: [
+                fixed("10"),
+                fixed("12.5"),
+                fixed("9.75"),
+                fixed("11"),
+                Fixed::from_int(5_000_000_000),
+            ],
+        };
+        let bar = decoded(&bytes).unwrap();
+        assert_eq!((bar.nanos, bar.body), (OPEN, expected));
+        put(&mut bytes, 40, &i64::MAX.to_le_bytes());
+        assert_eq!(decoded(&bytes), None);
+
+        let mut bytes = record(0xA0, 56);
+        put(&mut bytes, 16, &7u64.to_le_bytes());
+        put(&mut bytes, 24, &price("185"));
+        put(&mut bytes, 32, &50u32.to_le_bytes());
+        put(&mut bytes, 38, b"AB");
+        put(&mut bytes, 40, &(OPEN + 5).to_le_bytes());
+        let expected = Body::Mbo {
+            order: 7,
+            action: b'A',
+            side: b'B',
+            price: Some(fixed("185")),
+            size: Fixed::from_int(50),
+        };
+        let mbo = decoded(&bytes).unwrap();
+        assert_eq!((mbo.nanos, mbo.body), (OPEN + 5, expected));
+
+        let mut v2 = record(0x16, HEADER_LEN + 2 * 72 + 16);
+        put(&mut v2, HEADER_LEN + 1, b"AAPL");
+        let mut v1 = record(0x16, HEADER_LEN + 2 * 22 + 4 + 16);
+        put(&mut v1, HEADER_LEN, b"MSFT");
+        let symbol = |decoded: Option<Record>| match decoded.map(|mapping| mapping.body) {
+            Some(Body::SymbolMapping { symbol }) => symbol,
+            other => panic!("not a symbol mapping: {other:?}"),
+        };
+        assert_eq!(symbol(decoded(&v2)), "AAPL");
+        assert_eq!(symbol(decode(&v1, 1, 22).unwrap()), "MSFT");
+    }
+
+    #[test]
+    fn skips_other_types_and_rejects_short_records() {
+        assert_eq!(decoded(&record(0x13, 64)), None);
+        assert_eq!(decode(&[0; 8], 2, 71).unwrap_err(), "DBN record of 8 bytes is shorter than its header");
+        assert_eq!(
+            decode(&record(0x01, 48), 2, 71).unwrap_err(),
+            "DBN record of type 0x01 has 48 bytes, expected 80"
+        );
+        assert_eq!(direction(b'A'), TickDirection::Sell);
+        assert_eq!((book_side(b'B'), book_side(b'N')), (Some(Side::Bid), None));
+    }
+}
@@ -0,0 +1,285 @@
+//! Native Arrow IPC data provider
+//!
+//! Reads both the IPC file format (Feather v2) and the streaming format,
+//! detected from the file's magic bytes. Only the timestamp, symbol and
+//! record columns are decoded; IPC carries no statistics, so the time window
+//! and symbol filters are applied per row.
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use arrow_array::RecordBatch;
+use arrow_ipc::reader::{FileReader, StreamReader};
+use arrow_schema::ArrowError;
+use pyo3::prelude::*;
+
+use crate::data::arrow::{row_filter, BatchDecoder, BatchLayout, ColumnNames, RowFilter};
+use crate::data::provider::{DataStreamIterator, FileDataProvider, FileFormat, ProviderConfig};
+use crate::data::source::{Chunk, DataError, RowSource};
+use crate::data::timestamp::TimestampParser;
+use crate::types::market_data::Resolution;
+
+/// Leading magic of the IPC file format; streams start with a message instead
+const FILE_MAGIC: &[u8; 6] = b"ARROW1";
+
+static ARROW_IPC: FileFormat = FileFormat {
+    name: "Arrow IPC",
+    provider: "ArrowIpcDataProvider",
+    logger: "simulor.data.providers.arrow",
+    extensions: &["arrow", "arrows", "feather", "ipc"],
+};
+
+enum IpcReader {
+    File(FileReader<BufReader<File>>),
+    Stream(StreamReader<BufReader<File>>),
+}
+
+impl IpcReader {
+    fn open(path: &Path, projection: Option<Vec<usize>>) -> Result<Self, DataError> {
+        let io_error = |source| DataError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let malformed = |err: ArrowError| DataError::Malformed {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        };
+        let mut magic = [0u8; 6];
+        let is_file = File::open(path)
+            .and_then(|mut file| file.read_exact(&mut magic))
+            .is_ok_and(|()| &magic == FILE_MAGIC);
+        let file = File::open(path).map_err(io_error)?;
+        Ok(if is_file {
+            IpcReader::File(FileReader::try_new_buffered(file, projection).map_err(malformed)?)
+        } else {
+            IpcReader::Stream(StreamReader::try_new_buffered(file, projection).map_err(malformed)?)
+        })
+    }
+
+    fn schema(&self) -> arrow_schema::SchemaRef {
+        match self {
+            IpcReader::File(reader) => reader.schema(),
+            IpcReader::Stream(reader) => reader.schema(),
+        }
+    }
+
+    fn next(&mut self) -> Option<Result<RecordBatch, ArrowError>> {
+        match self {
+            IpcReader::File(reader) => reader.next(),
+            IpcReader::Stream(reader) => reader.next(),
+        }
+    }
+}
+
+/// Streams decoded rows out of one Arrow IPC file
+pub struct IpcSource {
+    reader: Option<IpcReader>,
+    decoder: BatchDecoder,
+    path: PathBuf,
+}
+
+impl IpcSource {
+    /// Open `path` and resolve its schema; also reports whether the optional
+    /// instrument type column is present
+    pub fn open(
+        path: &Path,
+        names: &ColumnNames<'_>,
+        parser: TimestampParser,
+        bar_resolution: Resolution,
+        filter: RowFilter,
+    ) -> Result<(Self, bool), DataError> {
+        // Reading the schema only touches the header (stream) or footer (file)
+        let schema = IpcReader::open(path, None)?.schema();
+        let layout = BatchLayout::new(ARROW_IPC.name, path, &schema, names)?;
+        let has_type_column = layout.has_type_column;
+        let reader = match layout.kind {
+            Some(_) => Some(IpcReader::open(path, Some(layout.projection(&schema)))?),
+            // No recognised record columns: every row would be rejected
+            None => None,
+        };
+        let source = IpcSource {
+            reader,
+            decoder: BatchDecoder::new(path, layout, parser, bar_resolution, filter),
+            path: path.to_path_buf(),
+        };
+        Ok((source, has_type_column))
+    }
+}
+
+impl RowSource for IpcSource {
+    fn next_chunk(&mut self, _max_rows: usize) -> Result<Option<Chunk>, DataError> {
+        let Some(reader) = self.reader.as_mut() else {
+            return Ok(None);
+        };
+        match reader.next() {
+            Some(Ok(batch)) => self.decoder.decode(&batch).map(Some),
+            Some(Err(err)) => Err(DataError::Malformed {
+                path: self.path.clone(),
+                message: err.to_string(),
+            }),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Load market data from Arrow IPC (Feather) files
+///
+/// Takes the same arguments as `ParquetDataProvider`.
+#[pyclass(module = "_simulor_rust", extends = FileDataProvider, frozen)]
+pub struct ArrowIpcDataProvider {
+    filter: RowFilter,
+}
+
+#[pymethods]
+impl ArrowIpcDataProvider {
+    #[new]
+    #[pyo3(signature = (
+        path,
+        resolution,
+        date_column="timestamp".to_owned(),
+        symbol_column="symbol".to_owned(),
+        instrument_type_column="instrument_type".to_owned(),
+        timezone="UTC",
+        start=None,
+        end=None,
+        symbols=None,
+        validate=true,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn py_new(
+        py: Python<'_>,
+        path: &Bound<'_, PyAny>,
+        resolution: &Bound<'_, PyAny>,
+        date_column: String,
+        symbol_column: String,
+        instrument_type_column: String,
+        timezone: &str,
+        start: Option<&Bound<'_, PyAny>>,
+        end: Option<&Bound<'_, PyAny>>,
+        symbols: Option<&Bound<'_, PyAny>>,
+        validate: bool,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let config = ProviderConfig::new(
+            py,
+            &ARROW_IPC,
+            path,
+            resolution,
+            date_column,
+            symbol_column,
+            instrument_type_column,
+            timezone,
+        )?;
+        let filter = row_filter(py, &config, start, end, symbols, validate)?;
+        Ok(PyClassInitializer::from(FileDataProvider { config }).add_subclass(ArrowIpcDataProvider { filter }))
+    }
+
+    /// Return a new iterator over the data, one `MarketEvent` per timestamp
+    fn __iter__(slf: &Bound<'_, Self>) -> PyResult<DataStreamIterator> {
+        let py = slf.py();
+        let config = &slf.as_super().get().config;
+        let filter = &slf.get().filter;
+        let names = config.column_names();
+        let mut sources: Vec<Box<dyn RowSource>> = Vec::with_capacity(config.files.len());
+        let mut warned = false;
+        for file in &config.files {
+            let (source, has_type_column) =
+                IpcSource::open(file, &names, config.parser, config.bar_resolution, filter.clone())?;
+            if !warned && !has_type_column {
+                config.warn_missing_type_column(py, file)?;
+                warned = true;
+            }
+            sources.push(Box::new(source));
+        }
+        Ok(DataStreamIterator::new(config.stream(py, sources, filter.window)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{ArrayRef, BinaryArray, Float64Array, Int64Array, StringArray};
+    use arrow_ipc::writer::{FileWriter, StreamWriter};
+
+    use super::*;
+
+    const NAMES: ColumnNames<'static> = ColumnNames {
+        date: "timestamp",
+        symbol: "symbol",
+        instrument_type: "instrument_type",
+    };
+
+    fn batch() -> RecordBatch {
+        RecordBatch::try_from_iter([
+            ("timestamp", Arc::new(Int64Array::from(vec![1_704_153_600, 1_704_153_601])) as ArrayRef),
+            ("symbol", Arc::new(StringArray::from(vec!["AAPL", "MSFT"])) as ArrayRef),
+            ("instrument_type", Arc::new(StringArray::from(vec!["stock", "stock"])) as ArrayRef),
+            // Not projected, so its type does not matter
+            ("note", Arc::new(BinaryArray::from(vec![&b"a"[..], &b"b"[..]])) as ArrayRef),
+            ("price", Arc::new(Float64Array::from(vec![185.25, 370.5])) as ArrayRef),
+            ("size", Arc::new(Int64Array::from(vec![10, 3])) as ArrayRef),
+        ])
+        .unwrap()
+    }
+
+    fn read(path: &Path) -> (Vec<i64>, Vec<String>, usize, bool) {
+        let parser = TimestampParser::from(chrono_tz::UTC);
+        let (mut source, has_type_column) =
+            IpcSource::open(path, &NAMES, parser, Resolution::Daily, RowFilter::default()).unwrap();
+        let (mut timestamps, mut symbols, mut batches) = (Vec::new(), Vec::new(), 0);
+        while let Some(chunk) = source.next_chunk(1024).unwrap() {
+            timestamps.extend_from_slice(&chunk.timestamps);
+            symbols.extend(chunk.new_symbols);
+            batches += 1;
+        }
+        std::fs::remove_file(path).unwrap();
+        (timestamps, symbols, batches, has_type_column)
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("simulor-ipc-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn reads_file_and_stream_formats() {
+        let batch = batch();
+        let file_path = temp_path("file.arrow");
+        let mut writer = FileWriter::try_new(File::create(&file_path).unwrap(), &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.write(&batch.slice(1, 1)).unwrap();
+        writer.finish().unwrap();
+        let stream_path = temp_path("stream.arrows");
+        let mut writer = StreamWriter::try_new(File::create(&stream_path).unwrap(), &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.write(&batch.slice(1, 1)).unwrap();
+        writer.finish().unwrap();
+
+        let expected = (
+            vec![
+                1_704_153_600_000_000_000,
+                1_704_153_601_000_000_000,
+                1_704_153_601_000_000_000,
+            ],
+            vec!["AAPL".to_owned(), "MSFT".to_owned()],
+            2,
+            true,
+        );
+        assert_eq!(read(&file_path), expected);
+        assert_eq!(read(&stream_path), expected);
+    }
+
+    #[test]
+    fn yields_nothing_without_record_columns() {
+        let path = temp_path("empty.arrow");
+        let batch = batch().project(&[0, 1]).unwrap();
+        let mut writer = FileWriter::try_new(File::create(&path).unwrap(), &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(read(&path), (vec![], vec![], 0, false));
+
+        let parser = TimestampParser::from(chrono_tz::UTC);
+        let missing = IpcSource::open(&path, &NAMES, parser, Resolution::Daily, RowFilter::default());
+        assert!(matches!(missing, Err(DataError::Io { .. })));
+    }
+}
@@ -0,0 +1,381 @@
+//! NASDAQ TotalView-ITCH 5.0 message decoding
+//!
+//! Files hold messages back to back, each behind a big-endian `u16` length.
+//! Every message opens with its type, stock locate, tracking number and a
+//! 6-byte timestamp in nanoseconds since midnight. Integers are big-endian
+//! and prices carry four implied decimals. Only the messages that move the
+//! book or print trades are decoded; the rest are skipped by length.
+//!
+//! ```text
+//! R  stock directory      locate → symbol for the day
+//! A  add order            order ref | side | shares | stock | price
+//! F  add order (MPID)     as A, plus attribution
+//! E  order executed       order ref | shares | match number
+//! C  executed with price  order ref | shares | match number | printable | price
+//! X  order cancel         order ref | cancelled shares
+//! D  order delete         order ref
+//! U  order replace        order ref | new order ref | shares | price
+//! P  trade (non-cross)    order ref | side | shares | stock | price | match number
+//! Q  cross trade          shares (u64) | stock | cross price | match number | cross type
+//! ```
+
+use crate::book::l3::Side;
+use crate::types::fixed::Fixed;
+
+/// Implied decimals of an ITCH price
+const PRICE_SCALE: u8 = 4;
+
+/// A decoded message of interest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Body {
+    StockDirectory {
+        stock: String,
+    },
+    AddOrder {
+        order: u64,
+        side: Side,
+        shares: u32,
+        price: Fixed,
+    },
+    OrderExecuted {
+        order: u64,
+        shares: u32,
+    },
+    OrderExecutedWithPrice {
+        order: u64,
+        shares: u32,
+        printable: bool,
+        price: Fixed,
+    },
+    OrderCancel {
+        order: u64,
+        shares: u32,
+    },
+    OrderDelete {
+        order: u64,
+    },
+    OrderReplace {
+        order: u64,
+        new_order: u64,
+        shares: u32,
+        price: Fixed,
+    },
+    Trade {
+        side: Side,
+        shares: u32,
+        price: Fixed,
+    },
+    CrossTrade {
+        shares: u64,
+        price: Fixed,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub locate: u16,
+    /// Nanoseconds since midnight
+    pub nanos: i64,
+    pub body: Body,
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> u16 {
+    u16::from_be_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_be_bytes(bytes[at..at + 4].try_into().expect("four bytes"))
+}
+
+fn u64_at(bytes: &[u8], at: usize) -> u64 {
+    u64::from_be_bytes(bytes[at..at + 8].try_into().expect("eight bytes"))
+}
+
+fn timestamp_at(bytes: &[u8], at: usize) -> i64 {
+    bytes[at..at + 6].iter().fold(0, |nanos, byte| nanos << 8 | i64::from(*byte))
+}
+
+fn price_at(bytes: &[u8], at: usize) -> Fixed {
+    Fixed::new(i128::from(u32_at(bytes, at)), PRICE_SCALE)
+        .expect("ITCH prices have a valid scale")
+        .normalize()
+}
+
+fn side_at(bytes: &[u8], at: usize) -> Side {
+    match bytes[at] {
+        b'B' => Side::Bid,
+        _ => Side::Ask,
+    }
+}
+
+/// Length of the message types decoded here, type byte included
+fn expected_len(kind: u8) -> Option<usize> {
+    Some(match kind {
+        b'R' => 39,
+        b'A' => 36,
+        b'F' => 40,
+        b'E' => 31,
+        b'C' => 36,
+        b'X' => 23,
+        b'D' => 19,
+        b'U' => 35,
+        b'P' => 44,
+        b'Q' => 40,
+        _ => return None,
+    })
+}
+
+/// Decode one message, or `None` for a type this reader skips
+pub fn decode(bytes: &[u8]) -> Result<Option<Message>, String> {
+    let Some(&kind) = bytes.first() else {
+        return Err("empty ITCH message".to_owned());
+    };
+    let Some(len) = expected_len(kind) else {
+        return Ok(None);
+    };
+    if bytes.len() < len {
+        return Err(format!("ITCH '{}' message of {} bytes, expected {len}", kind as char, bytes.len()));
+    }
+    let body = match kind {
+        b'R' => Body::StockDirectory {
+            stock: String::from_utf8_lossy(&bytes[11..19]).trim_end().to_owned(),
+        },
+        b'A' | b'F' => Body::AddOrder {
+            order: u64_at(bytes, 11),
+            side: side_at(bytes, 19),
+            shares: u32_at(bytes, 20),
+            price: price_at(bytes, 32),
+        },
+        b'E' => Body::OrderExecuted {
+            order: u64_at(bytes, 11),
+            shares: u32_at(bytes, 19),
+        },
+        b'C' => Body::OrderExecutedWithPrice {
+            order: u64_at(bytes, 11),
+            shares: u32_at(bytes, 19),
+            printable: bytes[31] == b'Y',
+            price: price_at(bytes, 32),
+        },
+        b'X' => Body::OrderCancel {
+            order: u64_at(bytes, 11),
+            shares: u32_at(bytes, 19),
+        },
+        b'D' => Body::OrderDelete {
+            order: u64_at(bytes, 11),
+        },
+        b'U' => Body::OrderReplace {
+            order: u64_at(bytes, 11),
+            new_order: u64_at(bytes, 19),
+            shares: u32_at(bytes, 27),
+            price: price_at(bytes, 31),
+        },
+        b'P' => Body::Trade {
+            side: side_at(bytes, 19),
+            shares: u32_at(bytes, 20),
+            price: price_at(bytes, 32),
+        },
+        b'Q' => Body::CrossTrade {
+            shares: u64_at(bytes, 11),
+            price: price_at(bytes, 27),
+        },
+        _ => unreachable!("only types with an expected length are decoded"),
+    };
+    Ok(Some(Message {
+        locate: u16_at(bytes, 1),
+        nanos: timestamp_at(bytes, 5),
+        body,
+    }))
+}
+
+/// Splits a file's bytes into length-prefixed messages
+pub struct Frames<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Frames<'a> {
+    pub fn new(bytes: &'a [u8], offset: usize) -> Self {
+        Frames { bytes, offset }
+    }
+
+    /// Offset of the next frame
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = Result<&'a [u8], String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.bytes[self.offset..];
+        if rest.is_empty() {
+            return None;
+        }
+        if rest.len() < 2 {
+            self.offset = self.bytes.len();
+            return Some(Err(format!("truncated ITCH frame at offset {}", self.offset - rest.len())));
+        }
+        let len = usize::from(u16_at(rest, 0));
+        let Some(frame) = rest.get(2..2 + len) else {
+            let at = self.offset;
+            self.offset = self.bytes.len();
+            return Some(Err(format!("truncated ITCH message at offset {at}")));
+        };
+        self.offset += 2 + len;
+        Some(Ok(frame))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A message of `kind` for stock locate 7 at 09:30, followed by `fields`
+    fn message(kind: u8, fields: &[&[u8]]) -> Vec<u8> {
+        let mut bytes = vec![kind, 0, 7, 0, 1];
+        bytes.extend_from_slice(&34_200_000_000_001i64.to_be_bytes()[2..]);
+        for field in fields {
+            bytes.extend_from_slice(field);
+        }
+        bytes
+    }
+
+    fn decoded(bytes: &[u8]) -> Body {
+        let message = decode(bytes).unwrap().unwrap();
+        assert_eq!((message.locate, message.nanos), (7, 34_200_000_000_001));
+        message.body
+    }
+
+    fn price(literal: &str) -> Fixed {
+        literal.parse().unwrap()
+    }
+
+    #[test]
+    fn decodes_book_messages() {
+        let (order, new_order) = (42u64.to_be_bytes(), 43u64.to_be_bytes());
+        let add = message(
+            b'A',
+            &[
+                &order,
+                b"S",
+                &100u32.to_be_bytes(),
+                b"AAPL    ",
+                &1_850_500u32.to_be_bytes(),
+            ],
+        );
+        let expected = Body::AddOrder {
+            order: 42,
+            side: Side::Ask,
+            shares: 100,
+            price: price("185.05"),
+        };
+        assert_eq!(decoded(&add), expected);
+        // The attribution of F messages is ignored
+        let attributed = [add.as_slice(), b"MPID"].concat();
+        assert_eq!(decoded(&[b"F", &attributed[1..]].concat()), expected);
+
+        let executed = message(
+            b'C',
+            &[
+                &order,
+                &40u32.to_be_bytes(),
+                &9u64.to_be_bytes(),
+                b"N",
+                &1_850_600u32.to_be_bytes(),
+            ],
+        );
+        assert_eq!(
+            decoded(&executed),
+            Body::OrderExecutedWithPrice {
+                order: 42,
+                shares: 40,
+                printable: false,
+                price: price("185.06"),
+            }
+        );
+        let replace = message(b'U', &[&order, &new_order, &60u32.to_be_bytes(), &1_850_400u32.to_be_bytes()]);
+        assert_eq!(
+            decoded(&replace),
+            Body::OrderReplace {
+                order: 42,
+                new_order: 43,
+                shares: 60,
+                price: price("185.04"),
+            }
+        );
+        let cancel = message(b'X', &[&order, &10u32.to_be_bytes()]);
+        assert_eq!(decoded(&cancel), Body::OrderCancel { order: 42, shares: 10 });
+        assert_eq!(decoded(&message(b'D', &[&order])), Body::OrderDelete { order: 42 });
+    }
+
+    #[test]
+    fn decodes_directory_and_trades() {
+        let directory = message(b'R', &[b"MSFT    ", &[b' '; 20]]);
+        assert_eq!(
+            decoded(&directory),
+            Body::StockDirectory {
+                stock: "MSFT".to_owned()
+            }
+        );
+
+        let trade = message(
+            b'P',
+            &[
+                &1u64.to_be_bytes(),
+                b"B",
+                &25u32.to_be_bytes(),
+                b"AAPL    ",
+                &1_850_200u32.to_be_bytes(),
+                &[0; 8],
+            ],
+        );
+        let expected = Body::Trade {
+            side: Side::Bid,
+            shares: 25,
+            price: price("185.02"),
+        };
+        assert_eq!(decoded(&trade), expected);
+        let cross = message(
+            b'Q',
+            &[
+                &1000u64.to_be_bytes(),
+                b"AAPL    ",
+                &1_850_300u32.to_be_bytes(),
+                &[0; 8],
+                b"O",
+            ],
+        );
+        assert_eq!(
+            decoded(&cross),
+            Body::CrossTrade {
+                shares: 1000,
+                price: price("185.03"),
+            }
+        );
+    }
+
+    #[test]
+    fn skips_other_types_and_rejects_short_messages() {
+        assert_eq!(decode(&message(b'S', &[b"Q"])), Ok(None));
+        assert!(decode(&[]).is_err());
+        let short = message(b'D', &[&[0; 4]]);
+        assert_eq!(decode(&short), Err("ITCH 'D' message of 15 bytes, expected 19".to_owned()));
+    }
+
+    #[test]
+    fn splits_length_prefixed_frames() {
+        let bytes = [&[0, 2][..], b"ab", &[0, 0], &[0, 1], b"c", &[0, 5], b"de"].concat();
+        let mut frames = Frames::new(&bytes, 0);
+        assert_eq!(frames.next(), Some(Ok(&b"ab"[..])));
+        assert_eq!(frames.next(), Some(Ok(&b""[..])));
+        assert_eq!(frames.next(), Some(Ok(&b"c"[..])));
+        assert_eq!(frames.offset(), 9);
+        assert_eq!(frames.next(), Some(Err("truncated ITCH message at offset 9".to_owned())));
+        assert_eq!(frames.next(), None);
+
+        let mut resumed = Frames::new(&bytes, 4);
+        assert_eq!(resumed.next(), Some(Ok(&b""[..])));
+        assert_eq!(Frames::new(&[0], 0).next(), Some(Err("truncated ITCH frame at offset 0".to_owned())));
+    }
+}
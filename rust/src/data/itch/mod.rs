@@ -0,0 +1,9 @@
+//! NASDAQ TotalView-ITCH 5.0
+//!
+//! Binary market-by-order files decoded into per-instrument Level 3 books,
+//! which are replayed as `BookUpdate`s and `TradeTick`s in `MarketEvent`s.
+
+pub mod message;
+pub mod reader;
+
+pub use reader::{ItchDataProvider, ItchStreamIterator};
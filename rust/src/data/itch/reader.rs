@@ -0,0 +1,557 @@
+//! ITCH 5.0 data provider
+//!
+//! A file is one trading day of NASDAQ's feed. Every order message is
+//! replayed into a per-instrument `OrderBook`, from the start of the file
+//! even when a time window is given, so books are complete when events
+//! begin. Each message that changes a price level becomes a `BookUpdate`
+//! of the new level sizes, sequenced per instrument, and each printed
+//! execution a `TradeTick`. An instrument's first update of a day is a
+//! snapshot, so Level 2 books downstream start in sync.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use memmap2::Mmap;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+
+use crate::book::l3::{update_levels, LevelChange, OrderBook, OrderBookL3, Side};
+use crate::book::update::{BookUpdate, Levels};
+use crate::data::itch::message::{decode, Body, Frames, Message};
+use crate::data::provider::{bound_nanos, discover, symbol_set};
+use crate::data::schema::RecordKind;
+use crate::data::source::{DataError, TimeWindow};
+use crate::data::timestamp::TimestampParser;
+use crate::events::market_event::MarketEvent;
+use crate::interop::{instrument_type, logger, path_type, zoneinfo_type};
+use crate::types::fixed::Fixed;
+use crate::types::instrument::{default_registry, InstrumentId};
+use crate::types::market_data::{new_record, MarketData, Resolution, TickDirection};
+use crate::types::time::{extract_date, nanos_to_datetime};
+
+const LOGGER: &str = "simulor.data.providers.itch";
+
+/// File extensions picked up from a directory
+const EXTENSIONS: &[&str] = &["itch", "NASDAQ_ITCH50"];
+
+/// Trading date from a NASDAQ file name such as `01302019.NASDAQ_ITCH50`
+fn file_date(path: &Path) -> Option<NaiveDate> {
+    let name = path.file_name()?.to_str()?;
+    let digits = name.get(..8).filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))?;
+    NaiveDate::parse_from_str(digits, "%m%d%Y").ok()
+}
+
+/// One instrument's book and output state
+struct Tracked {
+    instrument: Py<PyAny>,
+    instrument_id: InstrumentId,
+    book: OrderBook,
+    /// Sequence number of the last `BookUpdate` emitted
+    sequence: u64,
+    /// Whether today's snapshot was emitted
+    announced: bool,
+    updated: Option<(Py<PyAny>, i64)>,
+}
+
+/// What one message produced, before records are built
+enum Output {
+    Levels(Vec<LevelChange>),
+    Trade(Fixed, Fixed, TickDirection),
+}
+
+/// Unsequenced update of the given levels
+fn levels(bids: Levels, asks: Levels, snapshot: bool) -> BookUpdate {
+    BookUpdate {
+        bids,
+        asks,
+        sequence: None,
+        snapshot,
+    }
+}
+
+/// Aggressor of an execution against a resting order on `side`
+fn aggressor(side: Side) -> TickDirection {
+    match side {
+        Side::Bid => TickDirection::Sell,
+        Side::Ask => TickDirection::Buy,
+    }
+}
+
+/// Iterator over the events of an `ItchDataProvider`
+///
+/// Besides events, it exposes each instrument's Level 3 book as of the
+/// last event returned.
+#[pyclass(module = "_simulor_rust")]
+pub struct ItchStreamIterator {
+    /// Files with the epoch nanoseconds of their midnight, in order
+    files: Vec<(PathBuf, i64)>,
+    next_file: usize,
+    map: Option<(Mmap, usize)>,
+    midnight: i64,
+    path: PathBuf,
+    tzinfo: Py<PyAny>,
+    tick: Py<PyAny>,
+    window: TimeWindow,
+    symbols: Option<HashSet<String>>,
+    /// Today's stock locate → index into `tracked`, `None` when filtered out
+    locates: HashMap<u16, Option<usize>>,
+    tracked: Vec<Tracked>,
+    by_symbol: HashMap<String, usize>,
+    /// Message read past the last event, with its epoch nanoseconds
+    pending: Option<(Message, i64)>,
+    /// Whether the current file already warned about unknown orders
+    warned: bool,
+}
+
+impl ItchStreamIterator {
+    /// Map the next file, or return false when none are left
+    fn open_next(&mut self) -> Result<bool, DataError> {
+        let Some((path, midnight)) = self.files.get(self.next_file).cloned() else {
+            self.map = None;
+            return Ok(false);
+        };
+        self.next_file += 1;
+        let io_error = |source| DataError::Io {
+            path: path.clone(),
+            source,
+        };
+        let file = File::open(&path).map_err(io_error)?;
+        // SAFETY: feed files are read-only archives; a file truncated
+        // underneath us is outside that contract.
+        let map = unsafe { Mmap::map(&file) }.map_err(io_error)?;
+        self.map = Some((map, 0));
+        self.midnight = midnight;
+        self.path = path;
+        self.warned = false;
+        // Locates and orders are only valid for the day
+        self.locates.clear();
+        for tracked in &mut self.tracked {
+            tracked.book = OrderBook::default();
+            tracked.announced = false;
+        }
+        Ok(true)
+    }
+
+    /// Next decoded message of a tracked instrument, with its epoch nanoseconds
+    fn next_message(&mut self, py: Python<'_>) -> PyResult<Option<(Message, i64)>> {
+        if let Some(pending) = self.pending.take() {
+            return Ok(Some(pending));
+        }
+        loop {
+            let Some((map, offset)) = self.map.as_mut() else {
+                if !self.open_next()? {
+                    return Ok(None);
+                }
+                continue;
+            };
+            let mut frames = Frames::new(map, *offset);
+            let Some(frame) = frames.next() else {
+                self.map = None;
+                continue;
+            };
+            *offset = frames.offset();
+            let malformed = |message| DataError::Malformed {
+                path: self.path.clone(),
+                message,
+            };
+            let Some(message) = decode(frame.map_err(malformed)?).map_err(malformed)? else {
+                continue;
+            };
+            if let Body::StockDirectory { stock } = &message.body {
+                let index = self.track(py, stock)?;
+                self.locates.insert(message.locate, index);
+                continue;
+            }
+            if self.locates.get(&message.locate).copied().flatten().is_none() {
+                continue;
+            }
+            let nanos = self.midnight + message.nanos;
+            return Ok(Some((message, nanos)));
+        }
+    }
+
+    /// Index of `stock`'s state, created on first sight, or `None` if filtered out
+    fn track(&mut self, py: Python<'_>, stock: &str) -> PyResult<Option<usize>> {
+        if self.symbols.as_ref().is_some_and(|symbols| !symbols.contains(stock)) {
+            return Ok(None);
+        }
+        if let Some(index) = self.by_symbol.get(stock) {
+            return Ok(Some(*index));
+        }
+        let instrument = instrument_type(py)?.call_method1("stock", (stock,))?;
+        let instrument_id = default_registry(py)?.get().intern_instrument(&instrument)?;
+        self.tracked.push(Tracked {
+            instrument: instrument.unbind(),
+            instrument_id,
+            book: OrderBook::default(),
+            sequence: 0,
+            announced: false,
+            updated: None,
+        });
+        self.by_symbol.insert(stock.to_owned(), self.tracked.len() - 1);
+        Ok(Some(self.tracked.len() - 1))
+    }
+
+    /// Apply a message to its book; `None` if it names an unknown or duplicate order
+    fn apply(book: &mut OrderBook, body: &Body) -> PyResult<Option<Vec<Output>>> {
+        let known = |order: &u64| book.contains(*order);
+        let shares = |shares: u32| Fixed::from_int(i128::from(shares));
+        Ok(Some(match body {
+            Body::AddOrder {
+                order,
+                side,
+                shares: size,
+                price,
+            } => {
+                if known(order) {
+                    return Ok(None);
+                }
+                vec![Output::Levels(vec![book.add(*order, *side, *price, shares(*size))?])]
+            }
+            Body::OrderExecuted { order, shares: size } => {
+                if !known(order) {
+                    return Ok(None);
+                }
+                let (change, taken) = book.reduce(*order, shares(*size))?;
+                vec![
+                    Output::Trade(change.price, taken, aggressor(change.side)),
+                    Output::Levels(vec![change]),
+                ]
+            }
+            Body::OrderExecutedWithPrice {
+                order,
+                shares: size,
+                printable,
+                price,
+            } => {
+                if !known(order) {
+                    return Ok(None);
+                }
+                let (change, taken) = book.reduce(*order, shares(*size))?;
+                let mut outputs = Vec::with_capacity(2);
+                if *printable {
+                    outputs.push(Output::Trade(*price, taken, aggressor(change.side)));
+                }
+                outputs.push(Output::Levels(vec![change]));
+                outputs
+            }
+            Body::OrderCancel { order, shares: size } => {
+                if !known(order) {
+                    return Ok(None);
+                }
+                vec![Output::Levels(vec![book.reduce(*order, shares(*size))?.0])]
+            }
+            Body::OrderDelete { order } => {
+                if !known(order) {
+                    return Ok(None);
+                }
+                vec![Output::Levels(vec![book.delete(*order)?])]
+            }
+            Body::OrderReplace {
+                order,
+                new_order,
+                shares: size,
+                price,
+            } => {
+                if !known(order) || (new_order != order && known(new_order)) {
+                    return Ok(None);
+                }
+                vec![Output::Levels(book.replace(
+                    *order,
+                    *new_order,
+                    *price,
+                    shares(*size),
+                )?)]
+            }
+            Body::Trade {
+                side,
+                shares: size,
+                price,
+            } => vec![Output::Trade(*price, shares(*size), aggressor(*side))],
+            Body::CrossTrade { shares: size, price } if *size > 0 => {
+                vec![Output::Trade(
+                    *price,
+                    Fixed::from_int(i128::from(*size)),
+                    TickDirection::Neutral,
+                )]
+            }
+            Body::CrossTrade { .. } | Body::StockDirectory { .. } => Vec::new(),
+        }))
+    }
+
+    fn warn_unknown(&mut self, py: Python<'_>, message: &Message) -> PyResult<()> {
+        if self.warned {
+            return Ok(());
+        }
+        self.warned = true;
+        logger(py, LOGGER)?.call_method1(
+            "warning",
+            (
+                "%s: ITCH message at %d ns does not match the book (unknown or duplicate order); \
+                 books may be incomplete if the file does not start at the open",
+                self.path.display().to_string(),
+                message.nanos,
+            ),
+        )?;
+        Ok(())
+    }
+
+    /// Record of `update`, stamped with the instrument's next sequence number
+    fn book_update<'py>(
+        &mut self,
+        py: Python<'py>,
+        index: usize,
+        time: &Py<PyAny>,
+        nanos: i64,
+        mut update: BookUpdate,
+    ) -> PyResult<Bound<'py, BookUpdate>> {
+        let tracked = &mut self.tracked[index];
+        tracked.sequence += 1;
+        update.sequence = Some(tracked.sequence);
+        let base = MarketData::from_parts(
+            time.clone_ref(py),
+            tracked.instrument.clone_ref(py),
+            self.tick.clone_ref(py),
+            Resolution::Tick,
+            nanos,
+        );
+        new_record(py, base, update)
+    }
+
+    /// Turn one message's outputs into records of `event`
+    fn emit(
+        &mut self,
+        py: Python<'_>,
+        event: &mut MarketEvent,
+        index: usize,
+        time: &Py<PyAny>,
+        nanos: i64,
+        outputs: Vec<Output>,
+    ) -> PyResult<()> {
+        for output in outputs {
+            match output {
+                Output::Levels(changes) => {
+                    let (bids, asks) = update_levels(changes);
+                    let update = levels(bids, asks, false);
+                    let update = self.book_update(py, index, time, nanos, update)?;
+                    event.add_record(update.as_any())?;
+                }
+                Output::Trade(price, size, direction) => {
+                    let base = MarketData::from_parts(
+                        time.clone_ref(py),
+                        self.tracked[index].instrument.clone_ref(py),
+                        self.tick.clone_ref(py),
+                        Resolution::Tick,
+                        nanos,
+                    );
+                    let tick = RecordKind::TradeTick.build(py, base, &[price, size], Some(direction))?;
+                    event.add_record(&tick)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl ItchStreamIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<MarketEvent>> {
+        let mut current: Option<(i64, Py<PyAny>, MarketEvent)> = None;
+        while let Some((message, nanos)) = self.next_message(py)? {
+            if self.window.end.is_some_and(|end| nanos > end) {
+                // Files are in time order, so nothing later is wanted
+                self.files.clear();
+                self.map = None;
+                break;
+            }
+            if current.as_ref().is_some_and(|(time, _, _)| *time != nanos) {
+                self.pending = Some((message, nanos));
+                break;
+            }
+            let index = self.locates[&message.locate].expect("only tracked messages are returned");
+            if !self.window.contains(nanos) {
+                // Before the window: keep the book current without emitting
+                if Self::apply(&mut self.tracked[index].book, &message.body)?.is_none() {
+                    self.warn_unknown(py, &message)?;
+                }
+                continue;
+            }
+            let (_, time, mut event) = match current.take() {
+                Some(current) => current,
+                None => {
+                    let time = nanos_to_datetime(py, nanos, Some(self.tzinfo.bind(py)))?.unbind();
+                    let event = MarketEvent::new(time.clone_ref(py));
+                    (nanos, time, event)
+                }
+            };
+            if !self.tracked[index].announced {
+                self.tracked[index].announced = true;
+                let book = &self.tracked[index].book;
+                let bids = book.depth(Side::Bid).collect();
+                let asks = book.depth(Side::Ask).collect();
+                let snapshot = self.book_update(py, index, &time, nanos, levels(bids, asks, true))?;
+                event.add_record(snapshot.as_any())?;
+            }
+            match Self::apply(&mut self.tracked[index].book, &message.body)? {
+                Some(outputs) => self.emit(py, &mut event, index, &time, nanos, outputs)?,
+                None => self.warn_unknown(py, &message)?,
+            }
+            self.tracked[index].updated = Some((time.clone_ref(py), nanos));
+            current = Some((nanos, time, event));
+        }
+        Ok(current.map(|(_, _, event)| event))
+    }
+
+    /// Level 3 book of an instrument (or symbol) as of the last event, or `None`
+    fn order_book(&self, py: Python<'_>, instrument: &Bound<'_, PyAny>) -> PyResult<Option<OrderBookL3>> {
+        let symbol: String = match instrument.extract() {
+            Ok(symbol) => symbol,
+            Err(_) => instrument.getattr("symbol")?.extract()?,
+        };
+        Ok(self.by_symbol.get(&symbol).map(|index| {
+            let tracked = &self.tracked[*index];
+            OrderBookL3::from_book(
+                tracked.instrument.clone_ref(py),
+                tracked.instrument_id,
+                tracked.book.clone(),
+                tracked.updated.as_ref().map(|(time, nanos)| (time.clone_ref(py), *nanos)),
+            )
+        }))
+    }
+
+    /// Instruments seen so far
+    #[getter]
+    fn instruments<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        PyList::new(py, self.tracked.iter().map(|tracked| tracked.instrument.clone_ref(py)))
+    }
+}
+
+/// Read NASDAQ TotalView-ITCH 5.0 files as order book updates and trades
+///
+/// Args: `path` to an uncompressed ITCH file, or a directory of `.itch` and
+/// `.NASDAQ_ITCH50` files; `date` of the trading day, needed unless file
+/// names start with it as NASDAQ's do (`MMDDYYYY`); `timezone` of the
+/// exchange, which ITCH timestamps count from midnight in (default
+/// "America/New_York"); and optional inclusive `start`/`end` datetimes and
+/// `symbols` to restrict the events emitted.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct ItchDataProvider {
+    data_path: Py<PyAny>,
+    timezone_info: Py<PyAny>,
+    files: Vec<(PathBuf, i64)>,
+    window: TimeWindow,
+    symbols: Option<HashSet<String>>,
+}
+
+#[pymethods]
+impl ItchDataProvider {
+    #[new]
+    #[pyo3(signature = (path, date=None, timezone="America/New_York", start=None, end=None, symbols=None))]
+    fn py_new(
+        py: Python<'_>,
+        path: PathBuf,
+        date: Option<&Bound<'_, PyAny>>,
+        timezone: &str,
+        start: Option<&Bound<'_, PyAny>>,
+        end: Option<&Bound<'_, PyAny>>,
+        symbols: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Self> {
+        let timezone_info = zoneinfo_type(py)?.call1((timezone,))?;
+        let parser = TimestampParser::new(timezone)?;
+        let paths = if path.is_dir() {
+            let files = discover(&path, EXTENSIONS)?;
+            if files.is_empty() {
+                return Err(PyValueError::new_err(format!("No ITCH files found in directory: {}", path.display())));
+            }
+            files
+        } else {
+            // Opening the file reports a missing path
+            File::open(&path).map_err(|source| DataError::Io {
+                path: path.clone(),
+                source,
+            })?;
+            vec![path.clone()]
+        };
+        let date = date.map(extract_date).transpose()?;
+        let mut files = Vec::with_capacity(paths.len());
+        for file in paths {
+            let day = date.or_else(|| file_date(&file)).ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "Cannot tell the trading date of {} from its name; pass date=",
+                    file.display()
+                ))
+            })?;
+            let midnight = parser
+                .localize(&day.and_hms_opt(0, 0, 0).expect("midnight exists"))
+                .ok_or_else(|| PyValueError::new_err(format!("Date out of range: {day}")))?;
+            files.push((file, midnight));
+        }
+        files.sort_by_key(|(_, midnight)| *midnight);
+        let message = ("Loaded %d ITCH files from %s", files.len(), path.display().to_string());
+        logger(py, LOGGER)?.call_method1("info", message)?;
+        Ok(ItchDataProvider {
+            data_path: path_type(py)?.call1((path,))?.unbind(),
+            window: TimeWindow {
+                start: bound_nanos(py, start, &timezone_info)?,
+                end: bound_nanos(py, end, &timezone_info)?,
+            },
+            timezone_info: timezone_info.unbind(),
+            files,
+            symbols: symbol_set(symbols)?,
+        })
+    }
+
+    /// Path to the ITCH file or directory
+    #[getter]
+    fn data_path(&self, py: Python<'_>) -> Py<PyAny> {
+        self.data_path.clone_ref(py)
+    }
+
+    /// Timezone of the exchange, which event times are expressed in
+    #[getter]
+    fn timezone_info(&self, py: Python<'_>) -> Py<PyAny> {
+        self.timezone_info.clone_ref(py)
+    }
+
+    /// Files read by this provider, in date order
+    #[getter]
+    fn files<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let path = path_type(py)?;
+        PyList::new(py, self.files.iter().map(|(f, _)| path.call1((f,))).collect::<PyResult<Vec<_>>>()?)
+    }
+
+    /// Return a new iterator over the data, one `MarketEvent` per timestamp
+    fn __iter__(&self, py: Python<'_>) -> PyResult<ItchStreamIterator> {
+        Ok(ItchStreamIterator {
+            files: self.files.clone(),
+            next_file: 0,
+            map: None,
+            midnight: 0,
+            path: PathBuf::new(),
+            tzinfo: self.timezone_info.clone_ref(py),
+            tick: Resolution::Tick.to_py(py)?.unbind(),
+            window: self.window,
+            symbols: self.symbols.clone(),
+            locates: HashMap::new(),
+            tracked: Vec::new(),
+            by_symbol: HashMap::new(),
+            pending: None,
+            warned: false,
+        })
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "ItchDataProvider('{}', files={})",
+            self.data_path.bind(py).str()?,
+            self.files.len()
+        ))
+    }
+}
@@ -0,0 +1,250 @@
+//! K-way timestamp merge of row sources into `MarketEvent`s
+//!
+//! Each source keeps one chunk being merged and one being decoded on the
+//! rayon pool, so files are parsed in parallel while memory stays bounded.
+//! The merge heap holds one row per source, ordered by `(timestamp, counter)`
+//! where the counter increases with every push: rows with equal timestamps
+//! come out in the order they entered the heap, exactly like the `heapq`
+//! merge in the Python `CSVDataIterator`.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::Mutex;
+
+use crossbeam_channel::{bounded, Receiver};
+use pyo3::prelude::*;
+
+use crate::data::source::{Chunk, DataError, RowSource, TimeWindow, CHUNK_ROWS};
+use crate::data::timestamp::TimestampParser;
+use crate::events::market_event::MarketEvent;
+use crate::types::market_data::{MarketData, Resolution};
+use crate::types::time::nanos_to_datetime;
+
+type Decoded = (Box<dyn RowSource>, Result<Option<Chunk>, DataError>);
+
+enum Prefetch {
+    /// The mutex only makes the parked source `Sync`; it is never contended
+    Idle(Mutex<Box<dyn RowSource>>),
+    InFlight(Receiver<Decoded>),
+    Done,
+}
+
+impl Prefetch {
+    /// Start decoding the next chunk in the background
+    fn request(&mut self) {
+        if let Prefetch::Idle(_) = self {
+            let Prefetch::Idle(source) = std::mem::replace(self, Prefetch::Done) else {
+                unreachable!()
+            };
+            let mut source = source.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let (tx, rx) = bounded(1);
+            rayon::spawn(move || {
+                let chunk = source.next_chunk(CHUNK_ROWS);
+                let _ = tx.send((source, chunk));
+            });
+            *self = Prefetch::InFlight(rx);
+        }
+    }
+
+    /// Wait for the chunk in flight (GIL released) and queue the next one
+    fn take(&mut self, py: Python<'_>) -> Result<Option<Chunk>, DataError> {
+        self.request();
+        let Prefetch::InFlight(rx) = std::mem::replace(self, Prefetch::Done) else {
+            return Ok(None);
+        };
+        let (source, chunk) = py.detach(|| rx.recv().expect("decoder thread dropped its result"));
+        let chunk = chunk?;
+        if chunk.is_some() {
+            *self = Prefetch::Idle(Mutex::new(source));
+            self.request();
+        }
+        Ok(chunk)
+    }
+}
+
+struct Cursor {
+    prefetch: Prefetch,
+    chunk: Option<Chunk>,
+    row: usize,
+    /// Source symbol index -> `Instrument`
+    instruments: Vec<Py<PyAny>>,
+}
+
+impl Cursor {
+    fn timestamp(&self) -> Option<i64> {
+        self.chunk.as_ref().map(|chunk| chunk.timestamps[self.row])
+    }
+}
+
+/// Turns raw chunk contents into Python-facing values
+struct Resolver {
+    parser: TimestampParser,
+    tzinfo: Py<PyAny>,
+    window: TimeWindow,
+    instrument_factory: Py<PyAny>,
+    instruments: HashMap<String, Py<PyAny>>,
+    resolutions: HashMap<Resolution, Py<PyAny>>,
+}
+
+impl Resolver {
+    /// Replace the cursor's exhausted chunk with the next non-empty one
+    fn load_chunk(&mut self, py: Python<'_>, cursor: &mut Cursor) -> PyResult<()> {
+        cursor.chunk = None;
+        cursor.row = 0;
+        while let Some(mut chunk) = cursor.prefetch.take(py)? {
+            for symbol in chunk.new_symbols.drain(..) {
+                let instrument = match self.instruments.get(&symbol) {
+                    Some(instrument) => instrument.clone_ref(py),
+                    None => {
+                        let instrument = self.instrument_factory.call1(py, (symbol.as_str(),))?;
+                        self.instruments.insert(symbol, instrument.clone_ref(py));
+                        instrument
+                    }
+                };
+                cursor.instruments.push(instrument);
+            }
+            if !chunk.unparsed.is_empty() {
+                let tzinfo = self.tzinfo.bind(py);
+                for (row, text) in std::mem::take(&mut chunk.unparsed) {
+                    chunk.timestamps[row] = self.parser.parse_with_python(py, &text, tzinfo)?;
+                }
+                // Sources can only apply the window to timestamps they parsed themselves
+                if !self.window.is_unbounded() {
+                    let window = self.window;
+                    chunk.retain_timestamps(|timestamp| window.contains(timestamp));
+                }
+            }
+            if !chunk.is_empty() {
+                cursor.chunk = Some(chunk);
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn resolution(&mut self, py: Python<'_>, resolution: Resolution) -> PyResult<Py<PyAny>> {
+        if let Some(obj) = self.resolutions.get(&resolution) {
+            return Ok(obj.clone_ref(py));
+        }
+        let obj = resolution.to_py(py)?.unbind();
+        self.resolutions.insert(resolution, obj.clone_ref(py));
+        Ok(obj)
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct HeapItem {
+    timestamp: i64,
+    counter: u64,
+    cursor: usize,
+}
+
+/// Merges sources into chronological `MarketEvent`s, one per timestamp
+pub struct EventStream {
+    cursors: Vec<Cursor>,
+    heap: BinaryHeap<Reverse<HeapItem>>,
+    counter: u64,
+    bar_resolution: Resolution,
+    resolver: Resolver,
+}
+
+impl EventStream {
+    /// Start decoding every source and seed the heap with their first rows
+    ///
+    /// `instrument_factory` is called once per distinct symbol to build its
+    /// `Instrument`; `tzinfo` is the zone output timestamps are expressed in.
+    /// Rows outside `window` are expected to be dropped by the sources.
+    pub fn new(
+        py: Python<'_>,
+        sources: Vec<Box<dyn RowSource>>,
+        bar_resolution: Resolution,
+        parser: TimestampParser,
+        tzinfo: Py<PyAny>,
+        window: TimeWindow,
+        instrument_factory: Py<PyAny>,
+    ) -> PyResult<Self> {
+        let cursors = sources
+            .into_iter()
+            .map(|source| {
+                let mut prefetch = Prefetch::Idle(Mutex::new(source));
+                prefetch.request();
+                Cursor {
+                    prefetch,
+                    chunk: None,
+                    row: 0,
+                    instruments: Vec::new(),
+                }
+            })
+            .collect();
+        let mut stream = EventStream {
+            cursors,
+            heap: BinaryHeap::new(),
+            counter: 0,
+            bar_resolution,
+            resolver: Resolver {
+                parser,
+                tzinfo,
+                window,
+                instrument_factory,
+                instruments: HashMap::new(),
+                resolutions: HashMap::new(),
+            },
+        };
+        for index in 0..stream.cursors.len() {
+            stream.resolver.load_chunk(py, &mut stream.cursors[index])?;
+            stream.push(index);
+        }
+        Ok(stream)
+    }
+
+    fn push(&mut self, index: usize) {
+        if let Some(timestamp) = self.cursors[index].timestamp() {
+            self.heap.push(Reverse(HeapItem {
+                timestamp,
+                counter: self.counter,
+                cursor: index,
+            }));
+            self.counter += 1;
+        }
+    }
+
+    /// Build the record at a cursor's current row and advance past it
+    fn take_record<'py>(&mut self, py: Python<'py>, index: usize, time: &Py<PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        let cursor = &mut self.cursors[index];
+        let chunk = cursor.chunk.as_ref().expect("heap only references loaded rows");
+        let row = cursor.row;
+        let resolution = chunk.kind.resolution(chunk.resolution.unwrap_or(self.bar_resolution));
+        let base = MarketData::from_parts(
+            time.clone_ref(py),
+            cursor.instruments[chunk.symbols[row] as usize].clone_ref(py),
+            self.resolver.resolution(py, resolution)?,
+            resolution,
+            chunk.timestamps[row],
+        );
+        let record = chunk.kind.build(py, base, chunk.row_values(row), chunk.direction(row))?;
+        cursor.row += 1;
+        if cursor.row == chunk.len() {
+            self.resolver.load_chunk(py, cursor)?;
+        }
+        self.push(index);
+        Ok(record)
+    }
+
+    /// Next event: every row sharing the earliest remaining timestamp
+    pub fn next_event(&mut self, py: Python<'_>) -> PyResult<Option<MarketEvent>> {
+        let Some(Reverse(first)) = self.heap.peek() else {
+            return Ok(None);
+        };
+        let timestamp = first.timestamp;
+        let time = nanos_to_datetime(py, timestamp, Some(self.resolver.tzinfo.bind(py)))?.unbind();
+        let mut event = MarketEvent::new(time.clone_ref(py));
+        while self.heap.peek().is_some_and(|Reverse(item)| item.timestamp == timestamp) {
+            let Some(Reverse(item)) = self.heap.pop() else {
+                break;
+            };
+            let record = self.take_record(py, item.cursor, &time)?;
+            event.add_record(&record)?;
+        }
+        Ok(Some(event))
+    }
+}
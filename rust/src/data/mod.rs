@@ -0,0 +1,43 @@
+//! Native market data providers
+//!
+//! Tabular sources decode rows off the GIL into typed chunks; `merge` turns
+//! them into chronological `MarketEvent`s.
+
+pub mod arrow;
+pub mod csv;
+pub mod dbn;
+pub mod ipc;
+pub mod itch;
+pub mod merge;
+pub mod parquet;
+pub mod provider;
+pub mod schema;
+pub mod source;
+pub mod tickstore;
+pub mod timestamp;
+
+use pyo3::prelude::*;
+
+pub use csv::CsvDataProvider;
+pub use dbn::{DbnDataProvider, DbnStreamIterator};
+pub use ipc::ArrowIpcDataProvider;
+pub use itch::{ItchDataProvider, ItchStreamIterator};
+pub use parquet::ParquetDataProvider;
+pub use provider::{DataStreamIterator, FileDataProvider};
+pub use tickstore::{TickStoreDataProvider, TickStoreWriter};
+
+/// Register the data providers on the extension module
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<FileDataProvider>()?;
+    m.add_class::<CsvDataProvider>()?;
+    m.add_class::<ParquetDataProvider>()?;
+    m.add_class::<ArrowIpcDataProvider>()?;
+    m.add_class::<DataStreamIterator>()?;
+    m.add_class::<TickStoreWriter>()?;
+    m.add_class::<TickStoreDataProvider>()?;
+    m.add_class::<ItchDataProvider>()?;
+    m.add_class::<ItchStreamIterator>()?;
+    m.add_class::<DbnDataProvider>()?;
+    m.add_class::<DbnStreamIterator>()?;
+    Ok(())
+}
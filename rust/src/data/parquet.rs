@@ -0,0 +1,395 @@
+//! Native Parquet data provider
+//!
+//! Reads only the timestamp, symbol and record columns of each file, and
+//! skips row groups whose column statistics rule out the requested time
+//! window or symbols before decoding them. Rows are then filtered exactly.
+
+use std::fs::File;
+use std::path::Path;
+
+use parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
+use parquet::arrow::ProjectionMask;
+use parquet::basic::{LogicalType, TimeUnit};
+use parquet::file::metadata::RowGroupMetaData;
+use parquet::file::statistics::Statistics;
+use parquet::schema::types::SchemaDescriptor;
+use pyo3::prelude::*;
+
+use crate::data::arrow::{row_filter, BatchDecoder, BatchLayout, ColumnNames, RowFilter};
+use crate::data::provider::{DataStreamIterator, FileDataProvider, FileFormat, ProviderConfig};
+use crate::data::source::{Chunk, DataError, RowSource, CHUNK_ROWS};
+use crate::data::timestamp::TimestampParser;
+use crate::types::market_data::Resolution;
+
+/// Widest UTC offset, used to bound naive timestamps
+const MAX_OFFSET_NANOS: i64 = 86_400_000_000_000;
+
+static PARQUET: FileFormat = FileFormat {
+    name: "Parquet",
+    provider: "ParquetDataProvider",
+    logger: "simulor.data.providers.parquet",
+    extensions: &["parquet", "pq"],
+};
+
+/// Streams decoded rows out of one Parquet file
+pub struct ParquetSource {
+    reader: Option<ParquetRecordBatchReader>,
+    decoder: BatchDecoder,
+    path: std::path::PathBuf,
+}
+
+/// How the timestamp column's statistics map to epoch nanoseconds
+#[derive(Clone, Copy)]
+struct TimestampStats {
+    column: usize,
+    /// Nanoseconds per stored unit
+    scale: i64,
+    /// Stored values are wall-clock times in an unknown zone
+    naive: bool,
+}
+
+impl TimestampStats {
+    fn new(schema: &SchemaDescriptor, name: &str) -> Option<Self> {
+        let column = leaf_index(schema, name)?;
+        let (scale, naive) = match schema.column(column).logical_type_ref()? {
+            LogicalType::Timestamp(timestamp) => {
+                let scale = match timestamp.unit {
+                    TimeUnit::MILLIS => 1_000_000,
+                    TimeUnit::MICROS => 1_000,
+                    TimeUnit::NANOS => 1,
+                };
+                (scale, !timestamp.is_adjusted_to_u_t_c)
+            }
+            LogicalType::Date => (86_400_000_000_000, true),
+            _ => return None,
+        };
+        Some(TimestampStats { column, scale, naive })
+    }
+
+    /// Conservative `[min, max]` of the row group in epoch nanoseconds
+    fn range(&self, row_group: &RowGroupMetaData) -> Option<(i64, i64)> {
+        let (min, max) = match row_group.column(self.column).statistics()? {
+            Statistics::Int64(stats) => (*stats.min_opt()?, *stats.max_opt()?),
+            Statistics::Int32(stats) => (i64::from(*stats.min_opt()?), i64::from(*stats.max_opt()?)),
+            _ => return None,
+        };
+        let (min, max) = (min.checked_mul(self.scale)?, max.checked_mul(self.scale)?);
+        if self.naive {
+            // Wall-clock values: widen by the largest possible offset
+            Some((min.saturating_sub(MAX_OFFSET_NANOS), max.saturating_add(MAX_OFFSET_NANOS)))
+        } else {
+            Some((min, max))
+        }
+    }
+}
+
+/// Index of the top-level primitive column `name`
+fn leaf_index(schema: &SchemaDescriptor, name: &str) -> Option<usize> {
+    schema
+        .columns()
+        .iter()
+        .position(|column| column.path().parts().len() == 1 && column.name() == name)
+}
+
+/// Symbol statistics of a row group, if present and valid UTF-8
+fn symbol_range(row_group: &RowGroupMetaData, column: usize) -> Option<(&str, &str)> {
+    let Statistics::ByteArray(stats) = row_group.column(column).statistics()? else {
+        return None;
+    };
+    Some((stats.min_opt()?.as_utf8().ok()?, stats.max_opt()?.as_utf8().ok()?))
+}
+
+/// Row groups that may contain rows passing `filter`
+fn select_row_groups(
+    schema: &SchemaDescriptor,
+    row_groups: &[RowGroupMetaData],
+    layout: &BatchLayout,
+    filter: &RowFilter,
+) -> Vec<usize> {
+    let timestamps = (!filter.window.is_unbounded())
+        .then(|| TimestampStats::new(schema, &layout.timestamp))
+        .flatten();
+    let symbols = filter.symbols.as_ref().and_then(|_| leaf_index(schema, &layout.symbol));
+    (0..row_groups.len())
+        .filter(|&index| {
+            let row_group = &row_groups[index];
+            let in_window = timestamps
+                .and_then(|stats| stats.range(row_group))
+                .map_or(true, |(min, max)| filter.window.overlaps(min, max));
+            let has_symbol = symbols
+                .and_then(|column| symbol_range(row_group, column))
+                .map_or(true, |(min, max)| filter.symbols_overlap(min, max));
+            in_window && has_symbol
+        })
+        .collect()
+}
+
+impl ParquetSource {
+    /// Open `path`, resolve its schema and plan the row groups to read; also
+    /// reports whether the optional instrument type column is present
+    pub fn open(
+        path: &Path,
+        names: &ColumnNames<'_>,
+        parser: TimestampParser,
+        bar_resolution: Resolution,
+        filter: RowFilter,
+    ) -> Result<(Self, bool), DataError> {
+        let malformed = |err: parquet::errors::ParquetError| DataError::Malformed {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        };
+        let file = File::open(path).map_err(|source| DataError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file).map_err(malformed)?;
+        let layout = BatchLayout::new(PARQUET.name, path, builder.schema(), names)?;
+        let has_type_column = layout.has_type_column;
+        let reader = match layout.kind {
+            Some(_) => {
+                let schema = builder.parquet_schema();
+                let row_groups = select_row_groups(schema, builder.metadata().row_groups(), &layout, &filter);
+                let projection = ProjectionMask::roots(schema, layout.projection(builder.schema()));
+                let reader = builder
+                    .with_projection(projection)
+                    .with_row_groups(row_groups)
+                    .with_batch_size(CHUNK_ROWS)
+                    .build()
+                    .map_err(malformed)?;
+                Some(reader)
+            }
+            // No recognised record columns: every row would be rejected
+            None => None,
+        };
+        let source = ParquetSource {
+            reader,
+            decoder: BatchDecoder::new(path, layout, parser, bar_resolution, filter),
+            path: path.to_path_buf(),
+        };
+        Ok((source, has_type_column))
+    }
+}
+
+impl RowSource for ParquetSource {
+    fn next_chunk(&mut self, _max_rows: usize) -> Result<Option<Chunk>, DataError> {
+        let Some(reader) = self.reader.as_mut() else {
+            return Ok(None);
+        };
+        match reader.next() {
+            Some(Ok(batch)) => self.decoder.decode(&batch).map(Some),
+            Some(Err(err)) => Err(DataError::Malformed {
+                path: self.path.clone(),
+                message: err.to_string(),
+            }),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Load market data from Parquet files
+///
+/// Takes the same arguments as `CSVDataProvider`, plus optional `start` and
+/// `end` datetimes (inclusive; naive ones are in `timezone`) and `symbols` to
+/// restrict the rows read. With `validate=False`, rows that fail record
+/// validation are kept for a `DataQualityProvider` to check.
+#[pyclass(module = "_simulor_rust", extends = FileDataProvider, frozen)]
+pub struct ParquetDataProvider {
+    filter: RowFilter,
+}
+
+#[pymethods]
+impl ParquetDataProvider {
+    #[new]
+    #[pyo3(signature = (
+        path,
+        resolution,
+        date_column="timestamp".to_owned(),
+        symbol_column="symbol".to_owned(),
+        instrument_type_column="instrument_type".to_owned(),
+        timezone="UTC",
+        start=None,
+        end=None,
+        symbols=None,
+        validate=true,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn py_new(
+        py: Python<'_>,
+        path: &Bound<'_, PyAny>,
+        resolution: &Bound<'_, PyAny>,
+        date_column: String,
+        symbol_column: String,
+        instrument_type_column: String,
+        timezone: &str,
+        start: Option<&Bound<'_, PyAny>>,
+        end: Option<&Bound<'_, PyAny>>,
+        symbols: Option<&Bound<'_, PyAny>>,
+        validate: bool,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let config = ProviderConfig::new(
+            py,
+            &PARQUET,
+            path,
+            resolution,
+            date_column,
+            symbol_column,
+            instrument_type_column,
+            timezone,
+        )?;
+        let filter = row_filter(py, &config, start, end, symbols, validate)?;
+        Ok(PyClassInitializer::from(FileDataProvider { config }).add_subclass(ParquetDataProvider { filter }))
+    }
+
+    /// Return a new iterator over the data, one `MarketEvent` per timestamp
+    fn __iter__(slf: &Bound<'_, Self>) -> PyResult<DataStreamIterator> {
+        let py = slf.py();
+        let config = &slf.as_super().get().config;
+        let filter = &slf.get().filter;
+        let names = config.column_names();
+        let mut sources: Vec<Box<dyn RowSource>> = Vec::with_capacity(config.files.len());
+        let mut warned = false;
+        for file in &config.files {
+            let (source, has_type_column) =
+                ParquetSource::open(file, &names, config.parser, config.bar_resolution, filter.clone())?;
+            if !warned && !has_type_column {
+                config.warn_missing_type_column(py, file)?;
+                warned = true;
+            }
+            sources.push(Box::new(source));
+        }
+        Ok(DataStreamIterator::new(config.stream(py, sources, filter.window)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, TimestampMicrosecondArray};
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+
+    use super::*;
+    use crate::data::csv::CsvSource;
+    use crate::data::source::TimeWindow;
+    use crate::types::fixed::Fixed;
+
+    const DAY: i64 = 86_400_000_000_000;
+    const JAN_2: i64 = 1_704_153_600_000_000_000;
+
+    const NAMES: ColumnNames<'static> = ColumnNames {
+        date: "timestamp",
+        symbol: "symbol",
+        instrument_type: "instrument_type",
+    };
+
+    /// Four daily AAPL bars then four MSFT bars, in row groups of two
+    fn write_bars(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("simulor-parquet-{}-{name}.parquet", std::process::id()));
+        let days = (0..8).map(|row| (JAN_2 + (row % 4) * DAY) / 1_000);
+        let symbols = (0..8).map(|row| if row < 4 { "AAPL" } else { "MSFT" });
+        let closes = (0..8).map(|row| 100.5 + row as f64);
+        let batch = RecordBatch::try_from_iter([
+            (
+                "timestamp",
+                Arc::new(TimestampMicrosecondArray::from_iter_values(days).with_timezone("UTC")) as ArrayRef,
+            ),
+            ("symbol", Arc::new(StringArray::from_iter_values(symbols)) as ArrayRef),
+            ("open", Arc::new(Float64Array::from(vec![100.0; 8])) as ArrayRef),
+            ("high", Arc::new(Float64Array::from(vec![110.0; 8])) as ArrayRef),
+            ("low", Arc::new(Float64Array::from(vec![90.0; 8])) as ArrayRef),
+            ("close", Arc::new(Float64Array::from_iter_values(closes)) as ArrayRef),
+            ("volume", Arc::new(Float64Array::from(vec![1000.0; 8])) as ArrayRef),
+        ])
+        .unwrap();
+        let properties = WriterProperties::builder().set_max_row_group_row_count(Some(2)).build();
+        let mut writer = ArrowWriter::try_new(File::create(&path).unwrap(), batch.schema(), Some(properties)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        path
+    }
+
+    fn read_all(source: &mut impl RowSource) -> Vec<(i64, String, Vec<Fixed>)> {
+        let mut table = Vec::new();
+        let mut rows = Vec::new();
+        while let Some(chunk) = source.next_chunk(CHUNK_ROWS).unwrap() {
+            table.extend(chunk.new_symbols.iter().cloned());
+            for row in 0..chunk.len() {
+                let symbol = table[chunk.symbols[row] as usize].clone();
+                rows.push((chunk.timestamps[row], symbol, chunk.row_values(row).to_vec()));
+            }
+        }
+        rows
+    }
+
+    fn open(path: &Path, filter: RowFilter) -> ParquetSource {
+        let parser = TimestampParser::from(chrono_tz::UTC);
+        ParquetSource::open(path, &NAMES, parser, Resolution::Daily, filter).unwrap().0
+    }
+
+    fn planned_row_groups(path: &Path, filter: &RowFilter) -> Vec<usize> {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap()).unwrap();
+        let layout = BatchLayout::new(PARQUET.name, path, builder.schema(), &NAMES).unwrap();
+        select_row_groups(builder.parquet_schema(), builder.metadata().row_groups(), &layout, filter)
+    }
+
+    #[test]
+    fn decodes_the_same_rows_as_csv() {
+        let path = write_bars("csv");
+        let rows = read_all(&mut open(&path, RowFilter::default()));
+
+        let mut contents = String::from("timestamp,symbol,open,high,low,close,volume\n");
+        for row in 0..8 {
+            let symbol = if row < 4 { "AAPL" } else { "MSFT" };
+            let close = 100.5 + row as f64;
+            contents.push_str(&format!("2024-01-0{},{symbol},100.0,110.0,90.0,{close},1000.0\n", 2 + row % 4));
+        }
+        let csv_path = path.with_extension("csv");
+        std::fs::write(&csv_path, contents).unwrap();
+        let parser = TimestampParser::from(chrono_tz::UTC);
+        let mut csv =
+            CsvSource::open(&csv_path, "timestamp", "symbol", "instrument_type", parser, Resolution::Daily, false)
+                .unwrap()
+                .0;
+        let expected = read_all(&mut csv);
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&csv_path).unwrap();
+
+        assert_eq!(rows.len(), 8);
+        assert_eq!(rows, expected);
+    }
+
+    #[test]
+    fn prunes_row_groups_by_statistics() {
+        let path = write_bars("prune");
+        let window = TimeWindow {
+            start: Some(JAN_2 + 2 * DAY),
+            end: None,
+        };
+        let by_time = RowFilter {
+            window,
+            ..RowFilter::default()
+        };
+        let by_symbol = RowFilter {
+            symbols: Some(["MSFT".to_owned()].into()),
+            ..RowFilter::default()
+        };
+        let both = RowFilter {
+            window,
+            symbols: by_symbol.symbols.clone(),
+            keep_invalid: false,
+        };
+
+        assert_eq!(planned_row_groups(&path, &RowFilter::default()), [0, 1, 2, 3]);
+        assert_eq!(planned_row_groups(&path, &by_time), [1, 3]);
+        assert_eq!(planned_row_groups(&path, &by_symbol), [2, 3]);
+        assert_eq!(planned_row_groups(&path, &both), [3]);
+
+        // Rows of the remaining groups are still filtered exactly
+        let rows = read_all(&mut open(&path, both));
+        std::fs::remove_file(&path).unwrap();
+        let times: Vec<i64> = rows.iter().map(|row| row.0).collect();
+        assert_eq!(times, [JAN_2 + 2 * DAY, JAN_2 + 3 * DAY]);
+        assert!(rows.iter().all(|row| row.1 == "MSFT"));
+    }
+}
@@ -0,0 +1,264 @@
+//! Configuration shared by the file-based data providers
+//!
+//! Every provider takes the same core arguments as `CSVDataProvider` and
+//! discovers its files the same way: a single file, or every file with a
+//! matching extension directly inside a directory, in sorted order.
+//! `FileDataProvider` is the common base class holding that configuration.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use pyo3::exceptions::{PyFileNotFoundError, PyUserWarning, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::data::arrow::ColumnNames;
+use crate::data::merge::EventStream;
+use crate::data::source::{RowSource, TimeWindow};
+use crate::data::timestamp::TimestampParser;
+use crate::events::market_event::MarketEvent;
+use crate::interop::{instrument_type, logger, path_type, zoneinfo_type};
+use crate::types::market_data::Resolution;
+use crate::types::time::datetime_to_nanos;
+
+/// Static description of a file format
+pub struct FileFormat {
+    /// Name used in messages, e.g. "CSV"
+    pub name: &'static str,
+    /// Python class name of the provider
+    pub provider: &'static str,
+    /// Logger the provider reports through
+    pub logger: &'static str,
+    /// File extensions picked up from a directory, without the dot
+    pub extensions: &'static [&'static str],
+}
+
+/// Arguments and discovered files common to all providers
+pub struct ProviderConfig {
+    pub format: &'static FileFormat,
+    pub data_path: Py<PyAny>,
+    pub resolution: Py<PyAny>,
+    pub bar_resolution: Resolution,
+    pub date_column: String,
+    pub symbol_column: String,
+    pub instrument_type_column: String,
+    pub timezone_info: Py<PyAny>,
+    pub parser: TimestampParser,
+    pub files: Vec<PathBuf>,
+}
+
+/// Epoch nanoseconds for an optional `start`/`end` argument; naive datetimes are in `tzinfo`
+pub fn bound_nanos(py: Python<'_>, dt: Option<&Bound<'_, PyAny>>, tzinfo: &Bound<'_, PyAny>) -> PyResult<Option<i64>> {
+    let Some(dt) = dt else {
+        return Ok(None);
+    };
+    if dt.getattr("tzinfo")?.is_none() {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("tzinfo", tzinfo)?;
+        return datetime_to_nanos(&dt.call_method("replace", (), Some(&kwargs))?).map(Some);
+    }
+    datetime_to_nanos(dt).map(Some)
+}
+
+/// Symbols of an optional `symbols` argument: strings, or anything with a
+/// `symbol` attribute such as `Instrument`s
+pub fn symbol_set(symbols: Option<&Bound<'_, PyAny>>) -> PyResult<Option<HashSet<String>>> {
+    let Some(symbols) = symbols else {
+        return Ok(None);
+    };
+    let mut set = HashSet::new();
+    for item in symbols.try_iter()? {
+        let item = item?;
+        let symbol = match item.extract::<String>() {
+            Ok(symbol) => symbol,
+            Err(_) => item.getattr("symbol")?.extract()?,
+        };
+        set.insert(symbol);
+    }
+    Ok(Some(set))
+}
+
+/// Files directly inside `dir` with one of `extensions`, sorted by path
+pub fn discover(dir: &Path, extensions: &[&str]) -> PyResult<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let matches = path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| extensions.contains(&ext));
+        if matches && path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+impl ProviderConfig {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        py: Python<'_>,
+        format: &'static FileFormat,
+        path: &Bound<'_, PyAny>,
+        resolution: &Bound<'_, PyAny>,
+        date_column: String,
+        symbol_column: String,
+        instrument_type_column: String,
+        timezone: &str,
+    ) -> PyResult<Self> {
+        let log = logger(py, format.logger)?;
+        let init_message = format!("Initializing {} with path=%s, timezone=%s", format.provider);
+        log.call_method1("debug", (init_message, path, timezone))?;
+        let data_path = path_type(py)?.call1((path,))?;
+        let timezone_info = zoneinfo_type(py)?.call1((timezone,))?;
+        let parser = TimestampParser::new(timezone)?;
+        let fs_path: PathBuf = data_path.extract()?;
+        let name = format.name;
+        let files = if !fs_path.exists() {
+            log.call_method1("error", ("Path not found: %s", path))?;
+            return Err(PyFileNotFoundError::new_err(format!("Path not found: {}", path.str()?)));
+        } else if fs_path.is_file() {
+            log.call_method1("info", (format!("Loaded single {name} file: %s"), &data_path))?;
+            vec![fs_path]
+        } else if fs_path.is_dir() {
+            let files = discover(&fs_path, format.extensions)?;
+            if files.is_empty() {
+                log.call_method1("error", (format!("No {name} files found in directory: %s"), path))?;
+                return Err(PyValueError::new_err(format!("No {name} files found in directory: {}", path.str()?)));
+            }
+            log.call_method1("info", (format!("Loaded %d {name} files from directory: %s"), files.len(), &data_path))?;
+            files
+        } else {
+            log.call_method1("error", ("Invalid path: %s", path))?;
+            return Err(PyValueError::new_err(format!("Invalid path: {}", path.str()?)));
+        };
+        Ok(ProviderConfig {
+            format,
+            data_path: data_path.unbind(),
+            resolution: resolution.clone().unbind(),
+            bar_resolution: Resolution::from_py(resolution)?,
+            date_column,
+            symbol_column,
+            instrument_type_column,
+            timezone_info: timezone_info.unbind(),
+            parser,
+            files,
+        })
+    }
+
+    pub fn column_names(&self) -> ColumnNames<'_> {
+        ColumnNames {
+            date: &self.date_column,
+            symbol: &self.symbol_column,
+            instrument_type: &self.instrument_type_column,
+        }
+    }
+
+    /// Epoch nanoseconds for an optional bound; naive datetimes are in the provider's zone
+    pub fn bound(&self, py: Python<'_>, dt: Option<&Bound<'_, PyAny>>) -> PyResult<Option<i64>> {
+        bound_nanos(py, dt, self.timezone_info.bind(py))
+    }
+
+    /// Warn that `file` has no instrument type column
+    pub fn warn_missing_type_column(&self, py: Python<'_>, file: &Path) -> PyResult<()> {
+        let message = format!(
+            "{} file '{}' missing '{col}' column. Inferring instrument types from symbol format. \
+             Add '{col}' column for explicit control.",
+            self.format.name,
+            file.display(),
+            col = self.instrument_type_column,
+        );
+        PyErr::warn(py, &py.get_type::<PyUserWarning>(), &std::ffi::CString::new(message)?, 1)
+    }
+
+    /// Merge `sources` into a stream of events in the provider's zone
+    pub fn stream(
+        &self,
+        py: Python<'_>,
+        sources: Vec<Box<dyn RowSource>>,
+        window: TimeWindow,
+    ) -> PyResult<EventStream> {
+        EventStream::new(
+            py,
+            sources,
+            self.bar_resolution,
+            self.parser,
+            self.timezone_info.clone_ref(py),
+            window,
+            instrument_type(py)?.getattr("stock")?.unbind(),
+        )
+    }
+}
+
+/// Base class of the native file-based data providers
+#[pyclass(module = "_simulor_rust", subclass, frozen)]
+pub struct FileDataProvider {
+    pub config: ProviderConfig,
+}
+
+#[pymethods]
+impl FileDataProvider {
+    /// Path to the data file or directory
+    #[getter]
+    fn data_path(&self, py: Python<'_>) -> Py<PyAny> {
+        self.config.data_path.clone_ref(py)
+    }
+
+    /// Resolution for bar data
+    #[getter]
+    fn resolution(&self, py: Python<'_>) -> Py<PyAny> {
+        self.config.resolution.clone_ref(py)
+    }
+
+    /// Name of the timestamp column
+    #[getter]
+    fn date_column(&self) -> &str {
+        &self.config.date_column
+    }
+
+    /// Name of the symbol column
+    #[getter]
+    fn symbol_column(&self) -> &str {
+        &self.config.symbol_column
+    }
+
+    /// Name of the optional instrument type column
+    #[getter]
+    fn instrument_type_column(&self) -> &str {
+        &self.config.instrument_type_column
+    }
+
+    /// Timezone timestamps are localized to
+    #[getter]
+    fn timezone_info(&self, py: Python<'_>) -> Py<PyAny> {
+        self.config.timezone_info.clone_ref(py)
+    }
+
+    /// Files read by this provider, in merge order
+    #[getter]
+    fn files<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let path = path_type(py)?;
+        PyList::new(py, self.config.files.iter().map(|f| path.call1((f,))).collect::<PyResult<Vec<_>>>()?)
+    }
+}
+
+/// Iterator over the events of a native data provider
+#[pyclass(module = "_simulor_rust")]
+pub struct DataStreamIterator {
+    stream: EventStream,
+}
+
+impl DataStreamIterator {
+    pub fn new(stream: EventStream) -> Self {
+        DataStreamIterator { stream }
+    }
+}
+
+#[pymethods]
+impl DataStreamIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<MarketEvent>> {
+        self.stream.next_event(py)
+    }
+}
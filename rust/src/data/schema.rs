@@ -0,0 +1,191 @@
+//! Column schema shared by the tabular data providers
+//!
+//! Column names follow `simulor.types.ColumnName`. The record type of a file
+//! is detected from its header with the same precedence as the Python
+//! providers: trade bar, trade tick, quote tick, then quote bar.
+
+use pyo3::prelude::*;
+
+use crate::types::fixed::Fixed;
+use crate::types::market_data::{
+    new_record, MarketData, QuoteBar, QuoteTick, Resolution, TickDirection, TradeBar, TradeTick,
+};
+
+pub const TRADE_BAR_COLUMNS: &[&str] = &["open", "high", "low", "close", "volume"];
+pub const TRADE_TICK_COLUMNS: &[&str] = &["price", "size"];
+pub const QUOTE_TICK_COLUMNS: &[&str] = &["bid_price", "bid_size", "ask_price", "ask_size"];
+pub const QUOTE_BAR_COLUMNS: &[&str] = &[
+    "bid_open",
+    "bid_high",
+    "bid_low",
+    "bid_close",
+    "ask_open",
+    "ask_high",
+    "ask_low",
+    "ask_close",
+];
+
+/// Widest record payload, in values
+pub const MAX_WIDTH: usize = 8;
+
+/// Kind of record a row decodes to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RecordKind {
+    TradeBar,
+    TradeTick,
+    QuoteTick,
+    QuoteBar,
+}
+
+impl RecordKind {
+    /// Value columns in constructor order
+    pub fn columns(self) -> &'static [&'static str] {
+        match self {
+            RecordKind::TradeBar => TRADE_BAR_COLUMNS,
+            RecordKind::TradeTick => TRADE_TICK_COLUMNS,
+            RecordKind::QuoteTick => QUOTE_TICK_COLUMNS,
+            RecordKind::QuoteBar => QUOTE_BAR_COLUMNS,
+        }
+    }
+
+    pub fn width(self) -> usize {
+        self.columns().len()
+    }
+
+    /// Detect the record kind from the available column names
+    pub fn detect<S: AsRef<str>>(names: &[S]) -> Option<Self> {
+        let has = |column: &&str| names.iter().any(|name| name.as_ref() == *column);
+        [
+            RecordKind::TradeBar,
+            RecordKind::TradeTick,
+            RecordKind::QuoteTick,
+            RecordKind::QuoteBar,
+        ]
+        .into_iter()
+        .find(|kind| kind.columns().iter().all(has))
+    }
+
+    /// Whether the value at `index` is a size or volume rather than a price
+    pub fn is_size(self, index: usize) -> bool {
+        matches!(self.columns()[index], "volume" | "size" | "bid_size" | "ask_size")
+    }
+
+    /// Resolution of the records: ticks are always `TICK`, bars use `bars`
+    pub fn resolution(self, bars: Resolution) -> Resolution {
+        match self {
+            RecordKind::TradeTick | RecordKind::QuoteTick => Resolution::Tick,
+            RecordKind::TradeBar | RecordKind::QuoteBar => bars,
+        }
+    }
+
+    /// Validate a payload with the same rules as the record constructors
+    pub fn validate(self, values: &[Fixed], resolution: Resolution) -> Result<(), &'static str> {
+        match self {
+            RecordKind::TradeBar => trade_bar(values).validate(),
+            RecordKind::TradeTick => TradeTick::validate_values(values[0], values[1], resolution),
+            RecordKind::QuoteTick => quote_tick(values).validate(resolution),
+            RecordKind::QuoteBar => quote_bar(values).validate(),
+        }
+    }
+
+    /// Build the Python record for a payload, without validating it
+    ///
+    /// `direction` only applies to trade ticks.
+    pub fn build<'py>(
+        self,
+        py: Python<'py>,
+        base: MarketData,
+        values: &[Fixed],
+        direction: Option<TickDirection>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        Ok(match self {
+            RecordKind::TradeBar => new_record(py, base, trade_bar(values))?.into_any(),
+            RecordKind::TradeTick => {
+                let mut tick = trade_tick(values);
+                if let Some(direction) = direction {
+                    tick.direction = Some(direction.to_py(py)?.unbind());
+                    tick.native_direction = Some(direction);
+                }
+                new_record(py, base, tick)?.into_any()
+            }
+            RecordKind::QuoteTick => new_record(py, base, quote_tick(values))?.into_any(),
+            RecordKind::QuoteBar => new_record(py, base, quote_bar(values))?.into_any(),
+        })
+    }
+}
+
+/// Kind, payload values and trade direction of a native record
+pub fn payload(record: &Bound<'_, PyAny>) -> Option<(RecordKind, [Fixed; MAX_WIDTH], Option<TickDirection>)> {
+    let mut values = [Fixed::ZERO; MAX_WIDTH];
+    let mut fill = |payload: &[Fixed]| values[..payload.len()].copy_from_slice(payload);
+    let (kind, direction) = if let Ok(bar) = record.cast::<TradeBar>() {
+        let bar = bar.get();
+        fill(&[bar.open, bar.high, bar.low, bar.close, bar.volume]);
+        (RecordKind::TradeBar, None)
+    } else if let Ok(tick) = record.cast::<TradeTick>() {
+        let tick = tick.get();
+        fill(&[tick.price, tick.size]);
+        (RecordKind::TradeTick, tick.native_direction)
+    } else if let Ok(tick) = record.cast::<QuoteTick>() {
+        let tick = tick.get();
+        fill(&[tick.bid_price, tick.bid_size, tick.ask_price, tick.ask_size]);
+        (RecordKind::QuoteTick, None)
+    } else if let Ok(bar) = record.cast::<QuoteBar>() {
+        let bar = bar.get();
+        fill(&[
+            bar.bid_open,
+            bar.bid_high,
+            bar.bid_low,
+            bar.bid_close,
+            bar.ask_open,
+            bar.ask_high,
+            bar.ask_low,
+            bar.ask_close,
+        ]);
+        (RecordKind::QuoteBar, None)
+    } else {
+        return None;
+    };
+    Some((kind, values, direction))
+}
+
+fn trade_bar(v: &[Fixed]) -> TradeBar {
+    TradeBar {
+        open: v[0],
+        high: v[1],
+        low: v[2],
+        close: v[3],
+        volume: v[4],
+    }
+}
+
+fn trade_tick(v: &[Fixed]) -> TradeTick {
+    TradeTick {
+        price: v[0],
+        size: v[1],
+        direction: None,
+        native_direction: None,
+    }
+}
+
+fn quote_tick(v: &[Fixed]) -> QuoteTick {
+    QuoteTick {
+        bid_price: v[0],
+        bid_size: v[1],
+        ask_price: v[2],
+        ask_size: v[3],
+    }
+}
+
+fn quote_bar(v: &[Fixed]) -> QuoteBar {
+    QuoteBar {
+        bid_open: v[0],
+        bid_high: v[1],
+        bid_low: v[2],
+        bid_close: v[3],
+        ask_open: v[4],
+        ask_high: v[5],
+        ask_low: v[6],
+        ask_close: v[7],
+    }
+}
@@ -0,0 +1,166 @@
+//! Chunked row sources
+//!
+//! A source decodes one file into batches of rows on a worker thread. Values
+//! are stored flat with a stride of the record width, and symbols as indices
+//! into a per-source table so strings cross to the main thread only once.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use pyo3::exceptions::{PyFileNotFoundError, PyOSError, PyValueError};
+use pyo3::PyErr;
+
+use crate::data::schema::RecordKind;
+use crate::types::fixed::Fixed;
+use crate::types::market_data::{Resolution, TickDirection};
+
+/// Rows per chunk; two chunks per source are alive at a time
+pub const CHUNK_ROWS: usize = 1024;
+
+/// Errors raised while reading a data source
+#[derive(Debug)]
+pub enum DataError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Malformed {
+        path: PathBuf,
+        message: String,
+    },
+    MissingColumn {
+        format: &'static str,
+        path: PathBuf,
+        column: String,
+    },
+}
+
+impl fmt::Display for DataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataError::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            DataError::Malformed { path, message } => write!(f, "{}: {message}", path.display()),
+            DataError::MissingColumn { format, path, column } => {
+                write!(f, "{format} file {} missing required column '{column}'", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for DataError {}
+
+impl From<DataError> for PyErr {
+    fn from(err: DataError) -> PyErr {
+        match &err {
+            DataError::Io { source, .. } if source.kind() == std::io::ErrorKind::NotFound => {
+                PyFileNotFoundError::new_err(err.to_string())
+            }
+            DataError::Io { .. } => PyOSError::new_err(err.to_string()),
+            DataError::Malformed { .. } | DataError::MissingColumn { .. } => PyValueError::new_err(err.to_string()),
+        }
+    }
+}
+
+/// A batch of decoded rows of one record kind
+pub struct Chunk {
+    pub kind: RecordKind,
+    /// Epoch nanoseconds (UTC); placeholders for rows listed in `unparsed`
+    pub timestamps: Vec<i64>,
+    /// Index into the source's symbol table
+    pub symbols: Vec<u32>,
+    /// Payload values, `kind.width()` per row
+    pub values: Vec<Fixed>,
+    /// Symbols first seen in this chunk, continuing the source's table
+    pub new_symbols: Vec<String>,
+    /// Rows whose timestamp text needs the Python parser
+    pub unparsed: Vec<(usize, String)>,
+    /// Bar resolution, when the source records it; the stream default otherwise
+    pub resolution: Option<Resolution>,
+    /// Trade tick aggressor sides, one per row, or empty if the source has none
+    pub directions: Vec<Option<TickDirection>>,
+}
+
+impl Chunk {
+    pub fn new(kind: RecordKind) -> Self {
+        Chunk {
+            kind,
+            timestamps: Vec::new(),
+            symbols: Vec::new(),
+            values: Vec::new(),
+            new_symbols: Vec::new(),
+            unparsed: Vec::new(),
+            resolution: None,
+            directions: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.is_empty()
+    }
+
+    pub fn row_values(&self, row: usize) -> &[Fixed] {
+        let width = self.kind.width();
+        &self.values[row * width..(row + 1) * width]
+    }
+
+    pub fn direction(&self, row: usize) -> Option<TickDirection> {
+        self.directions.get(row).copied().flatten()
+    }
+
+    /// Keep only the rows whose timestamp satisfies `keep`
+    ///
+    /// Only valid once every timestamp is parsed (`unparsed` is empty).
+    pub fn retain_timestamps(&mut self, mut keep: impl FnMut(i64) -> bool) {
+        let width = self.kind.width();
+        let mut kept = 0;
+        for row in 0..self.len() {
+            if keep(self.timestamps[row]) {
+                self.timestamps[kept] = self.timestamps[row];
+                self.symbols[kept] = self.symbols[row];
+                self.values.copy_within(row * width..(row + 1) * width, kept * width);
+                if !self.directions.is_empty() {
+                    self.directions[kept] = self.directions[row];
+                }
+                kept += 1;
+            }
+        }
+        self.timestamps.truncate(kept);
+        self.symbols.truncate(kept);
+        self.values.truncate(kept * width);
+        if !self.directions.is_empty() {
+            self.directions.truncate(kept);
+        }
+    }
+}
+
+/// Inclusive bounds on row timestamps, in epoch nanoseconds
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimeWindow {
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+}
+
+impl TimeWindow {
+    pub fn is_unbounded(&self) -> bool {
+        self.start.is_none() && self.end.is_none()
+    }
+
+    pub fn contains(&self, timestamp: i64) -> bool {
+        self.start.map_or(true, |start| timestamp >= start) && self.end.map_or(true, |end| timestamp <= end)
+    }
+
+    /// Whether any timestamp in `[min, max]` can fall inside the window
+    pub fn overlaps(&self, min: i64, max: i64) -> bool {
+        self.start.map_or(true, |start| max >= start) && self.end.map_or(true, |end| min <= end)
+    }
+}
+
+/// Something that yields chunks of rows in file order
+pub trait RowSource: Send {
+    /// The next chunk of at most `max_rows` rows, or `None` when exhausted
+    fn next_chunk(&mut self, max_rows: usize) -> Result<Option<Chunk>, DataError>;
+}
@@ -0,0 +1,592 @@
+//! On-disk layout of a tick store file
+//!
+//! All integers are little-endian.
+//!
+//! ```text
+//! header   magic "SIMTICK\0" | version u16 | reserved [u8; 6]
+//! chunks   one per (series, UTC day), back to back
+//! footer   instruments | series | chunk index
+//! trailer  footer offset u64 | footer length u64 | magic "SIMTICK\0"
+//! ```
+//!
+//! A series is one instrument's records of one kind and resolution. A chunk
+//! body holds, for `n` rows of width `w`:
+//!
+//! ```text
+//! scales       [u8; w]        decimal scale of each value column
+//! timestamps   zigzag varint  first timestamp (epoch ns), then n-1 varint deltas
+//! columns      w × n zigzag   per column, deltas of the integer-scaled values
+//! directions   [u8; n]        trade ticks only: 0 none, 1 buy, 2 sell, 3 neutral
+//! ```
+//!
+//! Values are stored at the largest scale seen in their column and read back
+//! normalized, so they compare equal to what was written.
+
+use std::path::Path;
+
+use crate::data::schema::{RecordKind, MAX_WIDTH};
+use crate::data::source::{Chunk, DataError};
+use crate::types::fixed::Fixed;
+use crate::types::market_data::{Resolution, TickDirection};
+
+pub const MAGIC: &[u8; 8] = b"SIMTICK\0";
+pub const VERSION: u16 = 1;
+pub const HEADER_LEN: usize = 16;
+pub const TRAILER_LEN: usize = 24;
+
+/// `Instrument` fields persisted in the footer, in constructor keyword order
+pub const INSTRUMENT_FIELDS: [&str; 9] = [
+    "symbol",
+    "asset_type",
+    "exchange",
+    "currency",
+    "tick_size",
+    "expiry",
+    "strike",
+    "option_type",
+    "contract_size",
+];
+
+/// Instrument fields as text; `None` for fields that are `None`
+pub type InstrumentFields = [Option<String>; 9];
+
+/// One instrument's records of one kind and resolution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Series {
+    pub instrument: u32,
+    pub kind: RecordKind,
+    pub resolution: Resolution,
+}
+
+/// Footer entry locating one chunk
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkEntry {
+    pub series: u32,
+    /// Days since the Unix epoch (UTC)
+    pub day: i32,
+    pub offset: u64,
+    pub len: u64,
+    pub rows: u32,
+    pub first: i64,
+    pub last: i64,
+}
+
+#[derive(Debug, Default)]
+pub struct Footer {
+    pub instruments: Vec<InstrumentFields>,
+    pub series: Vec<Series>,
+    pub chunks: Vec<ChunkEntry>,
+}
+
+/// One decoded or to-be-encoded row
+#[derive(Debug, Clone, Copy)]
+pub struct Row {
+    pub timestamp: i64,
+    pub values: [Fixed; MAX_WIDTH],
+    pub direction: Option<TickDirection>,
+}
+
+fn kind_code(kind: RecordKind) -> u8 {
+    match kind {
+        RecordKind::TradeBar => 0,
+        RecordKind::TradeTick => 1,
+        RecordKind::QuoteTick => 2,
+        RecordKind::QuoteBar => 3,
+    }
+}
+
+fn kind_from_code(code: u8) -> Option<RecordKind> {
+    Some(match code {
+        0 => RecordKind::TradeBar,
+        1 => RecordKind::TradeTick,
+        2 => RecordKind::QuoteTick,
+        3 => RecordKind::QuoteBar,
+        _ => return None,
+    })
+}
+
+fn direction_code(direction: Option<TickDirection>) -> u8 {
+    match direction {
+        None => 0,
+        Some(TickDirection::Buy) => 1,
+        Some(TickDirection::Sell) => 2,
+        Some(TickDirection::Neutral) => 3,
+    }
+}
+
+fn direction_from_code(code: u8) -> Option<Option<TickDirection>> {
+    Some(match code {
+        0 => None,
+        1 => Some(TickDirection::Buy),
+        2 => Some(TickDirection::Sell),
+        3 => Some(TickDirection::Neutral),
+        _ => return None,
+    })
+}
+
+/// Days since the Unix epoch of a UTC timestamp
+pub fn day_of(timestamp: i64) -> i32 {
+    timestamp.div_euclid(86_400_000_000_000) as i32
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+/// Appends encoded values to a buffer
+#[derive(Default)]
+pub struct Encoder {
+    pub buf: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn u8(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+
+    pub fn u16(&mut self, value: u16) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn u32(&mut self, value: u32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn u64(&mut self, value: u64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn i64(&mut self, value: i64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buf.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.buf.push(value as u8);
+    }
+
+    pub fn signed(&mut self, value: i64) {
+        self.varint(zigzag(value));
+    }
+
+    /// Optional string: length + 1 as a varint (0 for `None`), then UTF-8 bytes
+    pub fn string(&mut self, value: Option<&str>) {
+        match value {
+            Some(text) => {
+                self.varint(text.len() as u64 + 1);
+                self.buf.extend_from_slice(text.as_bytes());
+            }
+            None => self.varint(0),
+        }
+    }
+}
+
+/// Reads encoded values from a byte slice; `None` past the end
+pub struct Decoder<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Decoder { buf, pos: 0 }
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(len)?;
+        let bytes = self.buf.get(self.pos..end)?;
+        self.pos = end;
+        Some(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.bytes(N)?.try_into().ok()
+    }
+
+    pub fn u8(&mut self) -> Option<u8> {
+        Some(self.array::<1>()?[0])
+    }
+
+    pub fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.array()?))
+    }
+
+    pub fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.array()?))
+    }
+
+    pub fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.array()?))
+    }
+
+    pub fn i64(&mut self) -> Option<i64> {
+        Some(i64::from_le_bytes(self.array()?))
+    }
+
+    pub fn varint(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    pub fn signed(&mut self) -> Option<i64> {
+        self.varint().map(unzigzag)
+    }
+
+    pub fn string(&mut self) -> Option<Option<String>> {
+        match self.varint()? {
+            0 => Some(None),
+            len => {
+                let bytes = self.bytes(usize::try_from(len - 1).ok()?)?;
+                Some(Some(std::str::from_utf8(bytes).ok()?.to_owned()))
+            }
+        }
+    }
+}
+
+pub fn header() -> Vec<u8> {
+    let mut encoder = Encoder::default();
+    encoder.buf.extend_from_slice(MAGIC);
+    encoder.u16(VERSION);
+    encoder.buf.resize(HEADER_LEN, 0);
+    encoder.buf
+}
+
+pub fn trailer(footer_offset: u64, footer_len: u64) -> Vec<u8> {
+    let mut encoder = Encoder::default();
+    encoder.u64(footer_offset);
+    encoder.u64(footer_len);
+    encoder.buf.extend_from_slice(MAGIC);
+    encoder.buf
+}
+
+fn corrupt(path: &Path, what: &str) -> DataError {
+    DataError::Malformed {
+        path: path.to_path_buf(),
+        message: format!("corrupt tick store: {what}"),
+    }
+}
+
+impl Footer {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut encoder = Encoder::default();
+        encoder.varint(self.instruments.len() as u64);
+        for fields in &self.instruments {
+            for field in fields {
+                encoder.string(field.as_deref());
+            }
+        }
+        encoder.varint(self.series.len() as u64);
+        for series in &self.series {
+            encoder.u32(series.instrument);
+            encoder.u8(kind_code(series.kind));
+            encoder.u32(series.resolution.secs());
+        }
+        encoder.varint(self.chunks.len() as u64);
+        for chunk in &self.chunks {
+            encoder.u32(chunk.series);
+            encoder.u32(chunk.day as u32);
+            encoder.u64(chunk.offset);
+            encoder.u64(chunk.len);
+            encoder.u32(chunk.rows);
+            encoder.i64(chunk.first);
+            encoder.i64(chunk.last);
+        }
+        encoder.buf
+    }
+
+    /// Validate the header and trailer of a whole file and decode its footer
+    pub fn read(path: &Path, file: &[u8]) -> Result<Footer, DataError> {
+        if file.len() < HEADER_LEN + TRAILER_LEN || &file[..MAGIC.len()] != MAGIC {
+            return Err(corrupt(path, "not a tick store file"));
+        }
+        let version = u16::from_le_bytes([file[8], file[9]]);
+        if version != VERSION {
+            return Err(DataError::Malformed {
+                path: path.to_path_buf(),
+                message: format!("unsupported tick store version {version} (expected {VERSION})"),
+            });
+        }
+        let mut trailer = Decoder::new(&file[file.len() - TRAILER_LEN..]);
+        let (offset, len) = (trailer.u64(), trailer.u64());
+        if trailer.bytes(MAGIC.len()) != Some(MAGIC.as_slice()) {
+            return Err(corrupt(path, "missing trailer (was the writer closed?)"));
+        }
+        let footer = offset
+            .zip(len)
+            .and_then(|(offset, len)| {
+                let start = usize::try_from(offset).ok()?;
+                file.get(start..start.checked_add(usize::try_from(len).ok()?)?)
+            })
+            .ok_or_else(|| corrupt(path, "footer out of bounds"))?;
+        Footer::decode(footer).ok_or_else(|| corrupt(path, "malformed footer"))
+    }
+
+    fn decode(buf: &[u8]) -> Option<Footer> {
+        let mut decoder = Decoder::new(buf);
+        let mut footer = Footer::default();
+        for _ in 0..decoder.varint()? {
+            let mut fields: InstrumentFields = Default::default();
+            for field in &mut fields {
+                *field = decoder.string()?;
+            }
+            footer.instruments.push(fields);
+        }
+        for _ in 0..decoder.varint()? {
+            footer.series.push(Series {
+                instrument: decoder.u32()?,
+                kind: kind_from_code(decoder.u8()?)?,
+                resolution: Resolution::from_secs(decoder.u32()?)?,
+            });
+        }
+        for _ in 0..decoder.varint()? {
+            footer.chunks.push(ChunkEntry {
+                series: decoder.u32()?,
+                day: decoder.u32()? as i32,
+                offset: decoder.u64()?,
+                len: decoder.u64()?,
+                rows: decoder.u32()?,
+                first: decoder.i64()?,
+                last: decoder.i64()?,
+            });
+        }
+        let consistent = footer.series.iter().all(|s| (s.instrument as usize) < footer.instruments.len())
+            && footer.chunks.iter().all(|c| (c.series as usize) < footer.series.len());
+        consistent.then_some(footer)
+    }
+}
+
+/// Encode a chunk body; fails if a value does not fit 64 bits at its column's scale
+pub fn encode_chunk(kind: RecordKind, rows: &[Row]) -> Result<Vec<u8>, &'static str> {
+    let width = kind.width();
+    let mut encoder = Encoder::default();
+    let scales: Vec<u8> = (0..width)
+        .map(|column| rows.iter().map(|row| row.values[column].scale()).max().unwrap_or(0))
+        .collect();
+    encoder.buf.extend_from_slice(&scales);
+    let mut previous = None;
+    for row in rows {
+        match previous {
+            None => encoder.signed(row.timestamp),
+            Some(previous) => encoder.varint((row.timestamp - previous) as u64),
+        }
+        previous = Some(row.timestamp);
+    }
+    for (column, &scale) in scales.iter().enumerate() {
+        let mut previous = 0i64;
+        for row in rows {
+            let raw = row.values[column].rescale_exact(scale).map_err(|_| "value out of range")?.raw();
+            let raw = i64::try_from(raw).map_err(|_| "value out of range")?;
+            encoder.signed(raw.wrapping_sub(previous));
+            previous = raw;
+        }
+    }
+    if kind == RecordKind::TradeTick {
+        encoder.buf.extend(rows.iter().map(|row| direction_code(row.direction)));
+    }
+    Ok(encoder.buf)
+}
+
+/// Decode a chunk body into a row chunk whose rows all use symbol index 0
+pub fn decode_chunk(path: &Path, series: &Series, entry: &ChunkEntry, body: &[u8]) -> Result<Chunk, DataError> {
+    decode_rows(series, entry.rows as usize, body).ok_or_else(|| corrupt(path, "malformed chunk"))
+}
+
+fn decode_rows(series: &Series, rows: usize, body: &[u8]) -> Option<Chunk> {
+    let kind = series.kind;
+    let width = kind.width();
+    let mut decoder = Decoder::new(body);
+    let scales = decoder.bytes(width)?;
+    let mut chunk = Chunk::new(kind);
+    chunk.resolution = Some(series.resolution);
+    chunk.timestamps.reserve(rows);
+    let mut timestamp = 0i64;
+    for row in 0..rows {
+        timestamp = match row {
+            0 => decoder.signed()?,
+            _ => timestamp.checked_add(i64::try_from(decoder.varint()?).ok()?)?,
+        };
+        chunk.timestamps.push(timestamp);
+    }
+    chunk.symbols = vec![0; rows];
+    chunk.values = vec![Fixed::ZERO; rows * width];
+    for (column, &scale) in scales.iter().enumerate() {
+        let mut raw = 0i64;
+        for row in 0..rows {
+            raw = raw.wrapping_add(decoder.signed()?);
+            chunk.values[row * width + column] = Fixed::new(raw.into(), scale).ok()?.normalize();
+        }
+    }
+    if kind == RecordKind::TradeTick {
+        let codes = decoder.bytes(rows)?;
+        if codes.iter().any(|&code| code != 0) {
+            chunk.directions = codes.iter().map(|&code| direction_from_code(code)).collect::<Option<_>>()?;
+        }
+    }
+    Some(chunk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed(literal: &str) -> Fixed {
+        literal.parse().unwrap()
+    }
+
+    fn row(timestamp: i64, values: &[&str], direction: Option<TickDirection>) -> Row {
+        let mut row = Row {
+            timestamp,
+            values: [Fixed::ZERO; MAX_WIDTH],
+            direction,
+        };
+        for (slot, value) in row.values.iter_mut().zip(values) {
+            *slot = fixed(value);
+        }
+        row
+    }
+
+    fn round_trip(series: &Series, rows: &[Row]) -> Chunk {
+        let body = encode_chunk(series.kind, rows).unwrap();
+        let entry = ChunkEntry {
+            series: 0,
+            day: day_of(rows[0].timestamp),
+            offset: 0,
+            len: body.len() as u64,
+            rows: rows.len() as u32,
+            first: rows[0].timestamp,
+            last: rows[rows.len() - 1].timestamp,
+        };
+        decode_chunk(Path::new("store"), series, &entry, &body).unwrap()
+    }
+
+    #[test]
+    fn varints_and_zigzag_cover_the_full_range() {
+        let values = [0, 1, -1, 63, -64, 64, i64::MAX, i64::MIN];
+        let mut encoder = Encoder::default();
+        for value in values {
+            encoder.signed(value);
+        }
+        encoder.varint(u64::MAX);
+        encoder.string(Some("AAPL"));
+        encoder.string(Some(""));
+        encoder.string(None);
+
+        let mut decoder = Decoder::new(&encoder.buf);
+        for value in values {
+            assert_eq!(decoder.signed(), Some(value));
+        }
+        assert_eq!(decoder.varint(), Some(u64::MAX));
+        assert_eq!(decoder.string(), Some(Some("AAPL".to_owned())));
+        assert_eq!(decoder.string(), Some(Some(String::new())));
+        assert_eq!(decoder.string(), Some(None));
+        // Reading past the end fails instead of panicking
+        assert_eq!(decoder.u8(), None);
+        assert_eq!(Decoder::new(&[0x80, 0x80]).varint(), None);
+    }
+
+    #[test]
+    fn chunks_round_trip_mixed_scales_and_directions() {
+        let series = Series {
+            instrument: 0,
+            kind: RecordKind::TradeTick,
+            resolution: Resolution::Tick,
+        };
+        let rows = [
+            row(1_704_205_800_000_000_000, &["185.01", "10"], Some(TickDirection::Buy)),
+            row(1_704_205_800_000_000_000, &["185", "0.5"], None),
+            row(1_704_205_801_250_000_000, &["184.999", "3"], Some(TickDirection::Neutral)),
+            row(1_704_205_802_000_000_000, &["-2.5", "1000000"], Some(TickDirection::Sell)),
+        ];
+        let chunk = round_trip(&series, &rows);
+
+        assert_eq!(chunk.kind, RecordKind::TradeTick);
+        assert_eq!(chunk.resolution, Some(Resolution::Tick));
+        assert_eq!(chunk.symbols, [0; 4]);
+        for (index, row) in rows.iter().enumerate() {
+            assert_eq!(chunk.timestamps[index], row.timestamp);
+            assert_eq!(chunk.row_values(index), &row.values[..2]);
+            assert_eq!(chunk.direction(index), row.direction);
+        }
+        // Values come back normalized rather than at the column's scale
+        assert_eq!(chunk.row_values(1)[0].to_string(), "185");
+
+        let quotes = Series {
+            kind: RecordKind::QuoteBar,
+            resolution: Resolution::Minute,
+            ..series
+        };
+        let bar = row(0, &["1.1", "1.2", "1.0", "1.15", "1.101", "1.201", "1.001", "1.151"], None);
+        let chunk = round_trip(&quotes, &[bar]);
+        assert_eq!(chunk.row_values(0), &bar.values);
+        assert!(chunk.directions.is_empty());
+    }
+
+    #[test]
+    fn rejects_values_beyond_64_bits() {
+        let rows = [row(0, &["0.000000001", "1"], None), row(1, &["92233720368", "1"], None)];
+        assert_eq!(encode_chunk(RecordKind::TradeTick, &rows).err(), Some("value out of range"));
+    }
+
+    #[test]
+    fn footers_round_trip_and_detect_corruption() {
+        let mut fields: InstrumentFields = Default::default();
+        fields[0] = Some("ES".to_owned());
+        fields[5] = Some("2024-03-15T00:00:00".to_owned());
+        let footer = Footer {
+            instruments: vec![fields.clone()],
+            series: vec![Series {
+                instrument: 0,
+                kind: RecordKind::TradeBar,
+                resolution: Resolution::Daily,
+            }],
+            chunks: vec![ChunkEntry {
+                series: 0,
+                day: -1,
+                offset: HEADER_LEN as u64,
+                len: 0,
+                rows: 0,
+                first: -5,
+                last: -1,
+            }],
+        };
+        let encoded = footer.encode();
+        let mut file = header();
+        let offset = file.len() as u64;
+        file.extend_from_slice(&encoded);
+        file.extend_from_slice(&trailer(offset, encoded.len() as u64));
+
+        let read = Footer::read(Path::new("store"), &file).unwrap();
+        assert_eq!(read.instruments, [fields]);
+        assert_eq!(read.series, footer.series);
+        assert_eq!((read.chunks[0].day, read.chunks[0].first), (-1, -5));
+
+        let message = |file: &[u8]| match Footer::read(Path::new("store"), file) {
+            Err(DataError::Malformed { message, .. }) => message,
+            _ => panic!("expected a malformed store"),
+        };
+        assert_eq!(message(&file[..20]), "corrupt tick store: not a tick store file");
+        let mut newer = file.clone();
+        newer[8] = 2;
+        assert_eq!(message(&newer), "unsupported tick store version 2 (expected 1)");
+        let unclosed = [header(), encoded.clone(), vec![0; TRAILER_LEN]].concat();
+        assert_eq!(message(&unclosed), "corrupt tick store: missing trailer (was the writer closed?)");
+        let mut dangling = [header(), encoded.clone(), trailer(offset, encoded.len() as u64)].concat();
+        let len = dangling.len();
+        dangling[len - TRAILER_LEN] = 0xff;
+        assert_eq!(message(&dangling), "corrupt tick store: footer out of bounds");
+    }
+}
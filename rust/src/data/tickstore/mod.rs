@@ -0,0 +1,14 @@
+//! Binary tick store
+//!
+//! A single-file, append-once columnar format for caching market data:
+//! records are grouped by instrument, kind and resolution, then by UTC day,
+//! and delta-encoded. Readers map the file and only decode the chunks a
+//! query touches, which makes re-running a backtest far cheaper than
+//! re-parsing its source files.
+
+pub mod format;
+pub mod reader;
+pub mod writer;
+
+pub use reader::TickStoreDataProvider;
+pub use writer::TickStoreWriter;
@@ -0,0 +1,261 @@
+//! Memory-mapped tick store reader
+//!
+//! Opening a store maps the file and decodes only its footer. Each series
+//! becomes one row source that decodes its day chunks straight from the
+//! mapping on the rayon pool, and the usual timestamp merge turns them into
+//! `MarketEvent`s. Chunks outside the requested time window, and series of
+//! other symbols, are never touched.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use memmap2::Mmap;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::data::merge::EventStream;
+use crate::data::provider::{bound_nanos, symbol_set, DataStreamIterator};
+use crate::data::source::{Chunk, DataError, RowSource, TimeWindow};
+use crate::data::tickstore::format::{decode_chunk, ChunkEntry, Footer, Series, INSTRUMENT_FIELDS};
+use crate::data::timestamp::TimestampParser;
+use crate::interop::{asset_type_type, datetime_type, decimal_type, instrument_type, option_type_type, zoneinfo_type};
+use crate::types::market_data::Resolution;
+use crate::types::time::nanos_to_datetime;
+
+/// An open, mapped store file
+struct Store {
+    path: PathBuf,
+    map: Mmap,
+    footer: Footer,
+}
+
+impl Store {
+    fn open(path: &Path) -> Result<Self, DataError> {
+        let io_error = |source| DataError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let file = File::open(path).map_err(io_error)?;
+        // SAFETY: store files are written once and renamed into place, never
+        // modified; a file truncated underneath us is outside that contract.
+        let map = unsafe { Mmap::map(&file) }.map_err(io_error)?;
+        let footer = Footer::read(path, &map)?;
+        Ok(Store {
+            path: path.to_path_buf(),
+            map,
+            footer,
+        })
+    }
+}
+
+/// Streams the chunks of one series out of the mapping
+struct SeriesSource {
+    store: Arc<Store>,
+    series: Series,
+    /// Key the stream's instrument factory resolves
+    key: String,
+    chunks: Vec<ChunkEntry>,
+    next: usize,
+    window: TimeWindow,
+}
+
+impl RowSource for SeriesSource {
+    fn next_chunk(&mut self, _max_rows: usize) -> Result<Option<Chunk>, DataError> {
+        let Some(entry) = self.chunks.get(self.next) else {
+            return Ok(None);
+        };
+        let body = usize::try_from(entry.offset)
+            .ok()
+            .zip(usize::try_from(entry.len).ok())
+            .and_then(|(offset, len)| self.store.map.get(offset..offset.checked_add(len)?))
+            .ok_or_else(|| DataError::Malformed {
+                path: self.store.path.clone(),
+                message: "corrupt tick store: chunk out of bounds".to_owned(),
+            })?;
+        let mut chunk = decode_chunk(&self.store.path, &self.series, entry, body)?;
+        if self.next == 0 {
+            chunk.new_symbols.push(self.key.clone());
+        }
+        self.next += 1;
+        if !self.window.is_unbounded() {
+            let window = self.window;
+            chunk.retain_timestamps(|timestamp| window.contains(timestamp));
+        }
+        Ok(Some(chunk))
+    }
+}
+
+/// Read market data from a tick store written by `TickStoreWriter`
+///
+/// Args: `path` to the store, `timezone` for event times (default "UTC"),
+/// and optional inclusive `start`/`end` datetimes and `symbols` to restrict
+/// what is read.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct TickStoreDataProvider {
+    store: Arc<Store>,
+    data_path: Py<PyAny>,
+    timezone_info: Py<PyAny>,
+    parser: TimestampParser,
+    window: TimeWindow,
+    symbols: Option<HashSet<String>>,
+    /// Instruments of the store, keyed by their index as text
+    instruments: Py<PyDict>,
+}
+
+/// Rebuild an `Instrument` from its stored fields
+fn instrument<'py>(py: Python<'py>, fields: &[Option<String>]) -> PyResult<Bound<'py, PyAny>> {
+    let kwargs = PyDict::new(py);
+    for (name, value) in INSTRUMENT_FIELDS.iter().zip(fields) {
+        let Some(value) = value else {
+            continue;
+        };
+        let value = match *name {
+            "asset_type" => asset_type_type(py)?.call1((value,))?,
+            "option_type" => option_type_type(py)?.call1((value,))?,
+            "expiry" => datetime_type(py)?.call_method1("fromisoformat", (value,))?,
+            "tick_size" | "strike" | "contract_size" => decimal_type(py)?.call1((value,))?,
+            _ => value.into_pyobject(py)?.into_any(),
+        };
+        kwargs.set_item(name, value)?;
+    }
+    instrument_type(py)?.call((), Some(&kwargs))
+}
+
+impl TickStoreDataProvider {
+    fn series_symbol(&self, series: &Series) -> Option<&str> {
+        self.store.footer.instruments[series.instrument as usize][0].as_deref()
+    }
+}
+
+#[pymethods]
+impl TickStoreDataProvider {
+    #[new]
+    #[pyo3(signature = (path, timezone="UTC", start=None, end=None, symbols=None))]
+    fn py_new(
+        py: Python<'_>,
+        path: PathBuf,
+        timezone: &str,
+        start: Option<&Bound<'_, PyAny>>,
+        end: Option<&Bound<'_, PyAny>>,
+        symbols: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Self> {
+        let store = Store::open(&path)?;
+        let timezone_info = zoneinfo_type(py)?.call1((timezone,))?;
+        let window = TimeWindow {
+            start: bound_nanos(py, start, &timezone_info)?,
+            end: bound_nanos(py, end, &timezone_info)?,
+        };
+        let instruments = PyDict::new(py);
+        for (index, fields) in store.footer.instruments.iter().enumerate() {
+            instruments.set_item(index.to_string(), instrument(py, fields)?)?;
+        }
+        Ok(TickStoreDataProvider {
+            store: Arc::new(store),
+            data_path: crate::interop::path_type(py)?.call1((path,))?.unbind(),
+            timezone_info: timezone_info.unbind(),
+            parser: TimestampParser::new(timezone)?,
+            window,
+            symbols: symbol_set(symbols)?,
+            instruments: instruments.unbind(),
+        })
+    }
+
+    /// Path to the store file
+    #[getter]
+    fn data_path(&self, py: Python<'_>) -> Py<PyAny> {
+        self.data_path.clone_ref(py)
+    }
+
+    /// Timezone event times are expressed in
+    #[getter]
+    fn timezone_info(&self, py: Python<'_>) -> Py<PyAny> {
+        self.timezone_info.clone_ref(py)
+    }
+
+    /// Every instrument in the store
+    #[getter]
+    fn instruments<'py>(&self, py: Python<'py>) -> Bound<'py, PyList> {
+        self.instruments.bind(py).values()
+    }
+
+    /// Total number of records in the store
+    #[getter]
+    fn row_count(&self) -> u64 {
+        self.store.footer.chunks.iter().map(|c| u64::from(c.rows)).sum()
+    }
+
+    /// Number of (series, day) chunks in the store
+    #[getter]
+    fn chunk_count(&self) -> usize {
+        self.store.footer.chunks.len()
+    }
+
+    /// Earliest record time in the store, or None if it is empty
+    #[getter]
+    fn start<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let first = self.store.footer.chunks.iter().map(|c| c.first).min();
+        first.map(|t| nanos_to_datetime(py, t, Some(self.timezone_info.bind(py)))).transpose()
+    }
+
+    /// Latest record time in the store, or None if it is empty
+    #[getter]
+    fn end<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let last = self.store.footer.chunks.iter().map(|c| c.last).max();
+        last.map(|t| nanos_to_datetime(py, t, Some(self.timezone_info.bind(py)))).transpose()
+    }
+
+    /// Return a new iterator over the data, one `MarketEvent` per timestamp
+    fn __iter__(&self, py: Python<'_>) -> PyResult<DataStreamIterator> {
+        let footer = &self.store.footer;
+        let mut sources: Vec<Box<dyn RowSource>> = Vec::new();
+        for (index, series) in footer.series.iter().enumerate() {
+            let wanted = match (&self.symbols, self.series_symbol(series)) {
+                (Some(symbols), Some(symbol)) => symbols.contains(symbol),
+                (Some(_), None) => false,
+                (None, _) => true,
+            };
+            if !wanted {
+                continue;
+            }
+            let chunks: Vec<ChunkEntry> = footer
+                .chunks
+                .iter()
+                .filter(|c| c.series as usize == index && self.window.overlaps(c.first, c.last))
+                .copied()
+                .collect();
+            if chunks.is_empty() {
+                continue;
+            }
+            sources.push(Box::new(SeriesSource {
+                store: Arc::clone(&self.store),
+                series: *series,
+                key: series.instrument.to_string(),
+                chunks,
+                next: 0,
+                window: self.window,
+            }));
+        }
+        let stream = EventStream::new(
+            py,
+            sources,
+            // Every chunk carries its own resolution
+            Resolution::Tick,
+            self.parser,
+            self.timezone_info.clone_ref(py),
+            self.window,
+            self.instruments.bind(py).getattr("__getitem__")?.unbind(),
+        )?;
+        Ok(DataStreamIterator::new(stream))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "TickStoreDataProvider('{}', instruments={}, rows={})",
+            self.store.path.display(),
+            self.store.footer.instruments.len(),
+            self.row_count()
+        )
+    }
+}
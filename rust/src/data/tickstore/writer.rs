@@ -0,0 +1,308 @@
+//! Tick store writer
+//!
+//! Records are buffered per series until their UTC day is over, then written
+//! as one chunk per series and day. The file is built under a temporary name
+//! and renamed into place on `close()`, so a reader never sees a partial
+//! store.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::data::schema::payload;
+use crate::data::source::DataError;
+use crate::data::tickstore::format::{
+    day_of, encode_chunk, header, trailer, ChunkEntry, Footer, InstrumentFields, Row, Series, INSTRUMENT_FIELDS,
+};
+use crate::interop::{datetime_type, logger};
+use crate::types::instrument::InstrumentId;
+use crate::types::market_data::MarketData;
+
+/// Rows after which a series' buffer is flushed even if its day is not over
+const MAX_CHUNK_ROWS: usize = 1 << 20;
+
+struct Buffer {
+    day: i32,
+    rows: Vec<Row>,
+}
+
+struct State {
+    path: PathBuf,
+    tmp_path: PathBuf,
+    file: BufWriter<File>,
+    offset: u64,
+    footer: Footer,
+    instrument_index: HashMap<InstrumentId, u32>,
+    series_index: HashMap<Series, u32>,
+    /// Last timestamp written or buffered, per series
+    last: Vec<i64>,
+    /// Open buffers keyed by series
+    buffers: BTreeMap<u32, Buffer>,
+    rows: u64,
+}
+
+impl State {
+    fn io_error(&self, source: std::io::Error) -> DataError {
+        DataError::Io {
+            path: self.tmp_path.clone(),
+            source,
+        }
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), DataError> {
+        self.file.write_all(bytes).map_err(|err| self.io_error(err))?;
+        self.offset += bytes.len() as u64;
+        Ok(())
+    }
+
+    fn flush_series(&mut self, series: u32) -> PyResult<()> {
+        let Some(buffer) = self.buffers.remove(&series) else {
+            return Ok(());
+        };
+        let (Some(first), Some(last)) = (buffer.rows.first(), buffer.rows.last()) else {
+            return Ok(());
+        };
+        let kind = self.footer.series[series as usize].kind;
+        let body = encode_chunk(kind, &buffer.rows).map_err(PyValueError::new_err)?;
+        let entry = ChunkEntry {
+            series,
+            day: buffer.day,
+            offset: self.offset,
+            len: body.len() as u64,
+            rows: buffer.rows.len() as u32,
+            first: first.timestamp,
+            last: last.timestamp,
+        };
+        self.write_bytes(&body)?;
+        self.footer.chunks.push(entry);
+        Ok(())
+    }
+
+    /// Write every buffer of a day before `day`
+    fn flush_before(&mut self, day: i32) -> PyResult<()> {
+        let done: Vec<u32> = self.buffers.iter().filter(|(_, b)| b.day < day).map(|(s, _)| *s).collect();
+        for series in done {
+            self.flush_series(series)?;
+        }
+        Ok(())
+    }
+
+    fn instrument(&mut self, py: Python<'_>, base: &MarketData) -> PyResult<u32> {
+        let id = base.instrument_id(py)?;
+        if let Some(&index) = self.instrument_index.get(&id) {
+            return Ok(index);
+        }
+        let instrument = base.instrument.bind(py);
+        let mut fields: InstrumentFields = Default::default();
+        for (field, name) in fields.iter_mut().zip(INSTRUMENT_FIELDS) {
+            let value = match instrument.getattr(name) {
+                Ok(value) => value,
+                Err(_) if name != "symbol" => continue,
+                Err(err) => return Err(err),
+            };
+            *field = field_text(py, &value)?;
+        }
+        let index = self.footer.instruments.len() as u32;
+        self.footer.instruments.push(fields);
+        self.instrument_index.insert(id, index);
+        Ok(index)
+    }
+
+    fn add(&mut self, record: &Bound<'_, PyAny>) -> PyResult<()> {
+        let py = record.py();
+        let (Ok(base), Some((kind, values, direction))) = (record.cast::<MarketData>(), payload(record)) else {
+            return Err(PyTypeError::new_err(format!("Unknown data type: {}", record.get_type().repr()?)));
+        };
+        let base = base.get();
+        let timestamp = base.timestamp_nanos(py)?;
+        let series = Series {
+            instrument: self.instrument(py, base)?,
+            kind,
+            resolution: base.native_resolution,
+        };
+        let index = match self.series_index.get(&series) {
+            Some(&index) => index,
+            None => {
+                let index = self.footer.series.len() as u32;
+                self.footer.series.push(series);
+                self.series_index.insert(series, index);
+                self.last.push(i64::MIN);
+                index
+            }
+        };
+        if timestamp < self.last[index as usize] {
+            return Err(PyValueError::new_err(format!(
+                "Records must be added in chronological order: {} went back in time",
+                record.repr()?
+            )));
+        }
+        self.last[index as usize] = timestamp;
+        let day = day_of(timestamp);
+        self.flush_before(day)?;
+        let buffer = self.buffers.entry(index).or_insert_with(|| Buffer { day, rows: Vec::new() });
+        buffer.rows.push(Row {
+            timestamp,
+            values,
+            direction,
+        });
+        if buffer.rows.len() >= MAX_CHUNK_ROWS {
+            self.flush_series(index)?;
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> PyResult<u64> {
+        self.flush_before(i32::MAX)?;
+        let footer = self.footer.encode();
+        let footer_offset = self.offset;
+        self.write_bytes(&footer)?;
+        self.write_bytes(&trailer(footer_offset, footer.len() as u64))?;
+        self.file.flush().map_err(|err| self.io_error(err))?;
+        std::fs::rename(&self.tmp_path, &self.path).map_err(|source| DataError::Io {
+            path: self.path.clone(),
+            source,
+        })?;
+        Ok(self.rows)
+    }
+}
+
+/// Text form of an instrument field: enum values, ISO datetimes, `str()` otherwise
+fn field_text(py: Python<'_>, value: &Bound<'_, PyAny>) -> PyResult<Option<String>> {
+    if value.is_none() {
+        return Ok(None);
+    }
+    if value.is_instance(datetime_type(py)?)? {
+        return Ok(Some(value.call_method0("isoformat")?.extract()?));
+    }
+    let value = value.getattr("value").unwrap_or_else(|_| value.clone());
+    Ok(Some(value.str()?.to_string()))
+}
+
+/// Write market data to a tick store file
+///
+/// Add records (or whole events, or a whole provider) in chronological
+/// order, then `close()` to make the store visible at `path`. Also usable as
+/// a context manager; leaving the block with an exception discards the file.
+#[pyclass(module = "_simulor_rust")]
+pub struct TickStoreWriter {
+    state: Option<State>,
+    rows: u64,
+}
+
+impl TickStoreWriter {
+    fn state(&mut self) -> PyResult<&mut State> {
+        self.state
+            .as_mut()
+            .ok_or_else(|| PyValueError::new_err("I/O operation on closed TickStoreWriter"))
+    }
+}
+
+#[pymethods]
+impl TickStoreWriter {
+    #[new]
+    fn py_new(path: PathBuf) -> PyResult<Self> {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".tmp");
+        let tmp_path = path.with_file_name(name);
+        let file = File::create(&tmp_path).map_err(|source| DataError::Io {
+            path: tmp_path.clone(),
+            source,
+        })?;
+        let mut state = State {
+            path,
+            tmp_path,
+            file: BufWriter::new(file),
+            offset: 0,
+            footer: Footer::default(),
+            instrument_index: HashMap::new(),
+            series_index: HashMap::new(),
+            last: Vec::new(),
+            buffers: BTreeMap::new(),
+            rows: 0,
+        };
+        state.write_bytes(&header())?;
+        Ok(TickStoreWriter {
+            state: Some(state),
+            rows: 0,
+        })
+    }
+
+    /// Add one market data record
+    fn add(&mut self, record: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.state()?.add(record)
+    }
+
+    /// Add every record of a `MarketEvent`
+    fn add_event(&mut self, event: &Bound<'_, PyAny>) -> PyResult<()> {
+        let state = self.state()?;
+        for record in event.call_method0("flatten")?.try_iter()? {
+            state.add(&record?)?;
+        }
+        Ok(())
+    }
+
+    /// Add every event of a data provider; returns the number of records added
+    fn write(&mut self, provider: &Bound<'_, PyAny>) -> PyResult<u64> {
+        let before = self.state()?.rows;
+        for event in provider.try_iter()? {
+            self.add_event(&event?)?;
+        }
+        let state = self.state()?;
+        logger(provider.py(), "simulor.data.cache")?
+            .call_method1("info", ("Wrote %d records to tick store %s", state.rows - before, state.path.clone()))?;
+        Ok(state.rows - before)
+    }
+
+    /// Records added so far
+    #[getter]
+    fn rows_written(&self) -> u64 {
+        self.state.as_ref().map_or(self.rows, |state| state.rows)
+    }
+
+    /// Whether `close()` has been called
+    #[getter]
+    fn closed(&self) -> bool {
+        self.state.is_none()
+    }
+
+    /// Flush buffered chunks, write the footer and move the file into place
+    fn close(&mut self) -> PyResult<()> {
+        if let Some(state) = self.state.take() {
+            self.rows = state.finish()?;
+        }
+        Ok(())
+    }
+
+    /// Discard everything written so far
+    fn abort(&mut self) {
+        if let Some(state) = self.state.take() {
+            let State { file, tmp_path, .. } = state;
+            drop(file);
+            let _ = std::fs::remove_file(tmp_path);
+        }
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[pyo3(signature = (exc_type, _exc_value=None, _traceback=None))]
+    fn __exit__(
+        &mut self,
+        exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<bool> {
+        if exc_type.is_some_and(|t| !t.is_none()) {
+            self.abort();
+        } else {
+            self.close()?;
+        }
+        Ok(false)
+    }
+}
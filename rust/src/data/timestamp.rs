@@ -0,0 +1,291 @@
+//! Timestamp parsing for tabular data sources
+//!
+//! Mirrors `CSVDataProvider._parse_timestamp`: numeric strings are Unix epoch
+//! seconds (milliseconds above 1e10), ISO 8601 strings with an offset are
+//! converted, and naive ones are localized to the configured zone. Forms the
+//! native parser does not cover are reported as `None` so the caller can hand
+//! them to Python's `datetime.fromisoformat`.
+
+use chrono::{LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone};
+use chrono_tz::Tz;
+use pyo3::exceptions::PyValueError;
+use pyo3::intern;
+use pyo3::prelude::*;
+
+use crate::interop::datetime_type;
+use crate::types::time::{datetime_to_nanos, NANOS_PER_MICRO, NANOS_PER_SECOND};
+
+/// Parses timestamp strings into UTC epoch nanoseconds
+#[derive(Debug, Clone, Copy)]
+pub struct TimestampParser {
+    tz: Tz,
+}
+
+impl From<Tz> for TimestampParser {
+    fn from(tz: Tz) -> Self {
+        TimestampParser { tz }
+    }
+}
+
+impl TimestampParser {
+    pub fn new(timezone: &str) -> PyResult<Self> {
+        let tz = timezone
+            .parse::<Tz>()
+            .map_err(|_| PyValueError::new_err(format!("Unknown timezone: {timezone}")))?;
+        Ok(TimestampParser::from(tz))
+    }
+
+    /// Epoch nanoseconds for `text`, or `None` if it needs the Python fallback
+    pub fn parse(&self, text: &str) -> Option<i64> {
+        let text = text.trim();
+        if !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+            if let Some(nanos) = parse_epoch(text) {
+                return Some(nanos);
+            }
+        }
+        let (naive, offset) = parse_iso(text)?;
+        match offset {
+            Some(offset_secs) => Some(naive_nanos(&naive)? - offset_secs as i64 * NANOS_PER_SECOND),
+            None => self.localize(&naive),
+        }
+    }
+
+    /// Interpret a wall-clock time in the configured zone
+    ///
+    /// Like `datetime.replace(tzinfo=...)` with `fold=0`, an ambiguous time
+    /// resolves to its first occurrence and a time inside a gap uses the
+    /// offset in effect before the transition.
+    pub fn localize(&self, naive: &NaiveDateTime) -> Option<i64> {
+        let offset_secs = match self.tz.offset_from_local_datetime(naive) {
+            LocalResult::Single(offset) => offset.fix().local_minus_utc(),
+            LocalResult::Ambiguous(first, _) => first.fix().local_minus_utc(),
+            LocalResult::None => {
+                let before = *naive - chrono::Duration::hours(3);
+                self.tz.offset_from_local_datetime(&before).earliest()?.fix().local_minus_utc()
+            }
+        };
+        Some(naive_nanos(naive)? - offset_secs as i64 * NANOS_PER_SECOND)
+    }
+
+    /// Wall-clock time in the configured zone at epoch nanoseconds
+    pub fn to_local(&self, nanos: i64) -> NaiveDateTime {
+        self.tz.timestamp_nanos(nanos).naive_local()
+    }
+
+    /// Parse through Python, for the forms `parse` declines
+    pub fn parse_with_python(&self, py: Python<'_>, text: &str, tzinfo: &Bound<'_, PyAny>) -> PyResult<i64> {
+        let text = text.trim();
+        let parsed = datetime_type(py)?.call_method1(intern!(py, "fromisoformat"), (text,));
+        let Ok(dt) = parsed else {
+            return Err(PyValueError::new_err(format!("Could not parse timestamp: {text}")));
+        };
+        let dt = if dt.getattr(intern!(py, "tzinfo"))?.is_none() {
+            let kwargs = pyo3::types::PyDict::new(py);
+            kwargs.set_item("tzinfo", tzinfo)?;
+            dt.call_method(intern!(py, "replace"), (), Some(&kwargs))?
+        } else {
+            dt
+        };
+        datetime_to_nanos(&dt)
+    }
+}
+
+fn naive_nanos(naive: &NaiveDateTime) -> Option<i64> {
+    let utc = naive.and_utc();
+    utc.timestamp()
+        .checked_mul(NANOS_PER_SECOND)?
+        .checked_add(utc.timestamp_subsec_micros() as i64 * NANOS_PER_MICRO)
+}
+
+/// Round half to even, as `datetime.fromtimestamp` does for microseconds
+fn round_half_even(x: f64) -> f64 {
+    let rounded = x.round();
+    if (x - x.trunc()).abs() == 0.5 {
+        2.0 * (x / 2.0).round()
+    } else {
+        rounded
+    }
+}
+
+fn parse_epoch(text: &str) -> Option<i64> {
+    epoch_nanos(text.parse().ok()?)
+}
+
+/// Unix epoch seconds, or milliseconds above 1e10, to nanoseconds
+pub fn epoch_nanos(mut value: f64) -> Option<i64> {
+    if !value.is_finite() {
+        return None;
+    }
+    if value > 1e10 {
+        value /= 1000.0;
+    }
+    let secs = value.trunc();
+    let mut micros = round_half_even((value - secs) * 1e6) as i64;
+    let mut secs = secs as i64;
+    if micros >= 1_000_000 {
+        secs += 1;
+        micros -= 1_000_000;
+    }
+    secs.checked_mul(NANOS_PER_SECOND)?.checked_add(micros * NANOS_PER_MICRO)
+}
+
+fn digits(text: &str) -> Option<u32> {
+    if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// ISO 8601 date, optional time and optional UTC offset (in seconds)
+fn parse_iso(text: &str) -> Option<(NaiveDateTime, Option<i32>)> {
+    if !text.is_ascii() {
+        return None;
+    }
+    let (date, rest) = if text.len() >= 10 && text.as_bytes()[4] == b'-' {
+        (&text[..10], &text[10..])
+    } else if text.len() >= 8 {
+        (&text[..8], &text[8..])
+    } else {
+        return None;
+    };
+    let date = match date.len() {
+        10 if date.as_bytes()[7] == b'-' => {
+            NaiveDate::from_ymd_opt(digits(&date[..4])? as i32, digits(&date[5..7])?, digits(&date[8..])?)?
+        }
+        8 => NaiveDate::from_ymd_opt(digits(&date[..4])? as i32, digits(&date[4..6])?, digits(&date[6..])?)?,
+        _ => return None,
+    };
+    if rest.is_empty() {
+        return Some((date.and_time(NaiveTime::MIN), None));
+    }
+    // Any single character separates date and time
+    let rest = &rest[1..];
+    let tz_start = rest.find(['Z', 'z', '+', '-']).unwrap_or(rest.len());
+    let (time, tz) = rest.split_at(tz_start);
+    Some((date.and_time(parse_time(time)?), parse_offset(tz)?))
+}
+
+fn parse_time(text: &str) -> Option<NaiveTime> {
+    let (hms, fraction) = match text.find(['.', ',']) {
+        Some(i) => (&text[..i], Some(&text[i + 1..])),
+        None => (text, None),
+    };
+    let mut parts = hms.split(':');
+    let hour = parts.next().filter(|p| p.len() == 2).and_then(digits)?;
+    let minute = match parts.next() {
+        Some(p) if p.len() == 2 => digits(p)?,
+        Some(_) => return None,
+        None => 0,
+    };
+    let second = match parts.next() {
+        Some(p) if p.len() == 2 => digits(p)?,
+        Some(_) => return None,
+        None => 0,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    let micros = match fraction {
+        Some(f) if (1..=6).contains(&f.len()) && second_given(hms) => digits(f)? * 10u32.pow(6 - f.len() as u32),
+        Some(_) => return None,
+        None => 0,
+    };
+    NaiveTime::from_hms_micro_opt(hour, minute, second, micros)
+}
+
+fn second_given(hms: &str) -> bool {
+    hms.matches(':').count() == 2
+}
+
+/// `Some(None)` for no offset, `Some(Some(secs))` for a parsed one
+fn parse_offset(text: &str) -> Option<Option<i32>> {
+    match text {
+        "" => Some(None),
+        "Z" | "z" => Some(Some(0)),
+        _ => {
+            let sign = match text.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let body = &text[1..];
+            let (hours, minutes) = match body.len() {
+                2 => (digits(body)?, 0),
+                4 => (digits(&body[..2])?, digits(&body[2..])?),
+                5 if body.as_bytes()[2] == b':' => (digits(&body[..2])?, digits(&body[3..])?),
+                _ => return None,
+            };
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            Some(Some(sign * (hours * 3600 + minutes * 60) as i32))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JAN_2_2024: i64 = 1_704_153_600 * NANOS_PER_SECOND;
+
+    fn parser(timezone: &str) -> TimestampParser {
+        TimestampParser::from(timezone.parse::<Tz>().unwrap())
+    }
+
+    #[test]
+    fn parses_epoch_seconds_and_milliseconds() {
+        let utc = parser("UTC");
+        assert_eq!(utc.parse("1704153600"), Some(JAN_2_2024));
+        assert_eq!(utc.parse("1704153600000"), Some(JAN_2_2024));
+        assert_eq!(utc.parse("1704153600.5"), Some(JAN_2_2024 + NANOS_PER_SECOND / 2));
+        // Zones never apply to epoch values
+        assert_eq!(parser("Asia/Tokyo").parse(" 1704153600 "), Some(JAN_2_2024));
+    }
+
+    #[test]
+    fn localizes_naive_iso_timestamps() {
+        let utc = parser("UTC");
+        assert_eq!(utc.parse("2024-01-02"), Some(JAN_2_2024));
+        assert_eq!(utc.parse("2024-01-02 00:00:00"), Some(JAN_2_2024));
+        assert_eq!(utc.parse("2024-01-02T00:00:01.25"), Some(JAN_2_2024 + 1_250_000_000));
+        let new_york = parser("America/New_York");
+        assert_eq!(
+            new_york.parse("2024-01-02 09:30:00"),
+            Some(JAN_2_2024 + 14 * 3600 * NANOS_PER_SECOND + 1_800_000_000_000)
+        );
+    }
+
+    #[test]
+    fn converts_timestamps_with_offsets() {
+        let new_york = parser("America/New_York");
+        assert_eq!(new_york.parse("2024-01-02T00:00:00Z"), Some(JAN_2_2024));
+        assert_eq!(new_york.parse("2024-01-02T01:00:00+01:00"), Some(JAN_2_2024));
+    }
+
+    #[test]
+    fn resolves_daylight_saving_transitions_like_python() {
+        let new_york = parser("America/New_York");
+        // 01:30 happens twice on 2024-11-03; fold=0 takes the EDT occurrence
+        let ambiguous = new_york.parse("2024-11-03 01:30:00").unwrap();
+        assert_eq!(ambiguous, parser("UTC").parse("2024-11-03 05:30:00").unwrap());
+        // 02:30 never happens on 2024-03-10; the offset before the gap applies
+        let skipped = new_york.parse("2024-03-10 02:30:00").unwrap();
+        assert_eq!(skipped, parser("UTC").parse("2024-03-10 07:30:00").unwrap());
+    }
+
+    #[test]
+    fn declines_what_it_cannot_parse() {
+        let utc = parser("UTC");
+        assert_eq!(utc.parse("yesterday"), None);
+        assert_eq!(utc.parse(""), None);
+        assert_eq!(utc.parse("2024-13-01"), None);
+    }
+
+    #[test]
+    fn rounds_epoch_microseconds_half_even() {
+        assert_eq!(epoch_nanos(0.0000005), Some(0));
+        assert_eq!(epoch_nanos(0.0000015), Some(2_000));
+        assert_eq!(epoch_nanos(f64::NAN), None);
+    }
+}
@@ -0,0 +1,357 @@
+//! Native `EventBus`
+//!
+//! Data events travel through a bounded MPMC channel, which provides the
+//! flow control of the Python `queue.Queue(maxsize=...)` (and, like it, is
+//! unbounded for a size of zero or less); system events use an unbounded
+//! channel that `next` always drains first. Every wait (a blocking publish
+//! into a full data channel, or `next` with nothing queued) happens with the
+//! GIL released, so feed threads and the engine thread do not contend for it
+//! while idle.
+//!
+//! The channels and counters are lock-free, but the bus as a whole is not:
+//! the subscriber table sits behind a `Mutex` taken once per consumed event,
+//! and the per-type counters are found through an `RwLock` that is only
+//! written when an event type is first seen.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use crossbeam_channel::{bounded, unbounded, Receiver, Select, Sender, TrySendError};
+use pyo3::exceptions::PyValueError;
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::events::market_event::MarketEvent;
+use crate::interop::{data_event_type, logger, system_event_type};
+
+const LOGGER: &str = "simulor.core.events";
+
+/// Published / consumed / dropped counts for one event type
+#[derive(Default)]
+struct Counters {
+    published: AtomicU64,
+    consumed: AtomicU64,
+    dropped: AtomicU64,
+}
+
+struct TypeStats {
+    event_type: Py<PyAny>,
+    name: String,
+    counters: Counters,
+}
+
+/// An event in flight, tagged with the index of its type's counters
+struct Queued {
+    event: Py<PyAny>,
+    stats: usize,
+}
+
+/// Handlers per event type, matched by identity of the `EventType` member
+type Subscribers = Vec<(Py<PyAny>, Vec<Py<PyAny>>)>;
+
+#[derive(Clone, Copy)]
+enum Lane {
+    Data,
+    System,
+}
+
+/// Event bus with a bounded data lane and a prioritized system lane
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct EventBus {
+    data_tx: Sender<Queued>,
+    data_rx: Receiver<Queued>,
+    system_tx: Sender<Queued>,
+    system_rx: Receiver<Queued>,
+    subscribers: Mutex<Subscribers>,
+    stats: RwLock<Vec<Arc<TypeStats>>>,
+    consumed: AtomicU64,
+    data_unfinished: AtomicUsize,
+    system_unfinished: AtomicUsize,
+}
+
+impl EventBus {
+    /// Bus whose data lane holds up to `data_qsize` events, any number for zero
+    ///
+    /// A zero-capacity channel would be a rendezvous, blocking every publish
+    /// until a consumer takes the event.
+    pub fn new(data_qsize: usize) -> Self {
+        let (data_tx, data_rx) = match data_qsize {
+            0 => unbounded(),
+            size => bounded(size),
+        };
+        let (system_tx, system_rx) = unbounded();
+        EventBus {
+            data_tx,
+            data_rx,
+            system_tx,
+            system_rx,
+            subscribers: Mutex::new(Vec::new()),
+            stats: RwLock::new(Vec::new()),
+            consumed: AtomicU64::new(0),
+            data_unfinished: AtomicUsize::new(0),
+            system_unfinished: AtomicUsize::new(0),
+        }
+    }
+
+    /// Index of the counters for `event.type`, registering it on first sight
+    ///
+    /// Event types are enum members, so identity is enough to tell them apart.
+    fn stats_index(&self, event: &Bound<'_, PyAny>) -> PyResult<usize> {
+        let py = event.py();
+        let event_type = event.getattr(intern!(py, "type"))?;
+        if let Some(index) = self.find_stats(&event_type) {
+            return Ok(index);
+        }
+        let name = match event_type.getattr(intern!(py, "name")) {
+            Ok(name) => name.extract()?,
+            Err(_) => event_type.str()?.to_string(),
+        };
+        let mut stats = self.stats.write().unwrap();
+        if let Some(index) = stats.iter().position(|s| s.event_type.is(&event_type)) {
+            return Ok(index);
+        }
+        stats.push(Arc::new(TypeStats {
+            event_type: event_type.unbind(),
+            name,
+            counters: Counters::default(),
+        }));
+        Ok(stats.len() - 1)
+    }
+
+    fn find_stats(&self, event_type: &Bound<'_, PyAny>) -> Option<usize> {
+        let stats = self.stats.read().unwrap();
+        stats.iter().position(|s| s.event_type.is(event_type))
+    }
+
+    fn counters(&self, index: usize) -> Arc<TypeStats> {
+        Arc::clone(&self.stats.read().unwrap()[index])
+    }
+
+    fn lane(event: &Bound<'_, PyAny>) -> PyResult<Option<Lane>> {
+        let py = event.py();
+        if event.is_instance_of::<MarketEvent>() || event.is_instance(data_event_type(py)?)? {
+            Ok(Some(Lane::Data))
+        } else if event.is_instance(system_event_type(py)?)? {
+            Ok(Some(Lane::System))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Wait up to `timeout` (forever with `None`) for an event, system first
+    fn receive(&self, py: Python<'_>, timeout: Option<Duration>) -> Option<Queued> {
+        if let Ok(queued) = self.system_rx.try_recv() {
+            return Some(queued);
+        }
+        if let Ok(queued) = self.data_rx.try_recv() {
+            return Some(queued);
+        }
+        if timeout == Some(Duration::ZERO) {
+            return None;
+        }
+        py.detach(|| {
+            let mut select = Select::new();
+            select.recv(&self.system_rx);
+            select.recv(&self.data_rx);
+            let ready = match timeout {
+                Some(timeout) => select.ready_timeout(timeout).ok(),
+                None => Some(select.ready()),
+            };
+            ready?;
+            // Re-check the system lane first: both may have become ready
+            self.system_rx.try_recv().or_else(|_| self.data_rx.try_recv()).ok()
+        })
+    }
+
+    fn notify_subscribers(&self, event: &Bound<'_, PyAny>, event_type: &Py<PyAny>) -> PyResult<()> {
+        let py = event.py();
+        let handlers: Vec<Py<PyAny>> = {
+            let subscribers = self.subscribers.lock().unwrap();
+            subscribers
+                .iter()
+                .find(|(t, _)| t.is(event_type))
+                .map(|(_, handlers)| handlers.iter().map(|h| h.clone_ref(py)).collect())
+                .unwrap_or_default()
+        };
+        for handler in handlers {
+            if let Err(err) = handler.call1(py, (event,)) {
+                let kwargs = PyDict::new(py);
+                kwargs.set_item("exc_info", err.value(py))?;
+                logger(py, LOGGER)?.call_method(
+                    "error",
+                    ("Error in event handler while processing %s", event),
+                    Some(&kwargs),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl EventBus {
+    #[new]
+    #[pyo3(signature = (data_qsize=4096))]
+    fn py_new(data_qsize: i64) -> Self {
+        EventBus::new(usize::try_from(data_qsize).unwrap_or(0))
+    }
+
+    /// Get the total number of events consumed
+    #[getter]
+    fn consumed_event_count(&self) -> u64 {
+        self.consumed.load(Ordering::Relaxed)
+    }
+
+    /// Publish an event to the appropriate queue based on its type
+    ///
+    /// With `backpressure="block"` a full data queue is waited on with the GIL
+    /// released; with `"drop"` the event is discarded and counted as dropped.
+    /// System events are never blocked or dropped. Returns whether the event
+    /// was enqueued.
+    #[pyo3(signature = (event, backpressure="block"))]
+    fn publish(&self, py: Python<'_>, event: &Bound<'_, PyAny>, backpressure: &str) -> PyResult<bool> {
+        let block = match backpressure {
+            "block" => true,
+            "drop" => false,
+            other => {
+                return Err(PyValueError::new_err(format!("backpressure must be 'block' or 'drop', got {other:?}")))
+            }
+        };
+        let Some(lane) = EventBus::lane(event)? else {
+            logger(py, LOGGER)?.call_method1("warning", ("Unknown event type: %s", event.get_type()))?;
+            return Ok(false);
+        };
+        let index = self.stats_index(event)?;
+        let stats = self.counters(index);
+        let queued = Queued {
+            event: event.clone().unbind(),
+            stats: index,
+        };
+        match lane {
+            Lane::System => {
+                self.system_unfinished.fetch_add(1, Ordering::AcqRel);
+                // The receiver lives as long as `self`, so sending cannot fail
+                let _ = self.system_tx.send(queued);
+            }
+            Lane::Data => {
+                self.data_unfinished.fetch_add(1, Ordering::AcqRel);
+                let sent = match self.data_tx.try_send(queued) {
+                    Ok(()) => true,
+                    Err(TrySendError::Full(queued)) if block => py.detach(|| self.data_tx.send(queued).is_ok()),
+                    Err(_) => false,
+                };
+                if !sent {
+                    self.data_unfinished.fetch_sub(1, Ordering::AcqRel);
+                    stats.counters.dropped.fetch_add(1, Ordering::Relaxed);
+                    logger(py, LOGGER)?.call_method1("warning", ("Event queue full, dropping event: %s", event))?;
+                    return Ok(false);
+                }
+            }
+        }
+        stats.counters.published.fetch_add(1, Ordering::Relaxed);
+        Ok(true)
+    }
+
+    /// Subscribe a handler to a specific event type
+    fn subscribe(&self, event_type: &Bound<'_, PyAny>, handler: &Bound<'_, PyAny>) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        match subscribers.iter_mut().find(|(t, _)| t.is(event_type)) {
+            Some((_, handlers)) => handlers.push(handler.clone().unbind()),
+            None => subscribers.push((event_type.clone().unbind(), vec![handler.clone().unbind()])),
+        }
+    }
+
+    /// Unsubscribe a handler from a specific event type
+    fn unsubscribe(&self, event_type: &Bound<'_, PyAny>, handler: &Bound<'_, PyAny>) -> PyResult<()> {
+        let py = handler.py();
+        let mut subscribers = self.subscribers.lock().unwrap();
+        let Some(slot) = subscribers.iter().position(|(t, _)| t.is(event_type)) else {
+            return Ok(());
+        };
+        let handlers = &mut subscribers[slot].1;
+        let mut found = None;
+        for (i, h) in handlers.iter().enumerate() {
+            if h.bind(py).eq(handler)? {
+                found = Some(i);
+                break;
+            }
+        }
+        match found {
+            Some(i) => {
+                handlers.remove(i);
+                if handlers.is_empty() {
+                    // remove empty entry to avoid leaking keys
+                    subscribers.remove(slot);
+                }
+            }
+            None => {
+                drop(subscribers);
+                logger(py, LOGGER)?.call_method1(
+                    "debug",
+                    ("Attempted to unsubscribe handler not present for %s: %s", event_type, handler),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Retrieve the next available event, prioritizing system events
+    ///
+    /// Waits up to `timeout` seconds (indefinitely with `None`) with the GIL
+    /// released. Returns `None` if no event arrived in time.
+    #[pyo3(signature = (timeout=0.1))]
+    fn next(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<Option<Py<PyAny>>> {
+        let timeout = timeout
+            .map(|secs| Duration::try_from_secs_f64(secs.max(0.0)))
+            .transpose()
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+        let Some(queued) = self.receive(py, timeout) else {
+            return Ok(None);
+        };
+        let stats = self.counters(queued.stats);
+        self.notify_subscribers(queued.event.bind(py), &stats.event_type)?;
+        stats.counters.consumed.fetch_add(1, Ordering::Relaxed);
+        self.consumed.fetch_add(1, Ordering::Relaxed);
+        Ok(Some(queued.event))
+    }
+
+    /// Indicate that a previously enqueued event has been processed
+    fn task_done(&self, queue_type: &str) -> PyResult<()> {
+        let unfinished = match queue_type {
+            "data" => &self.data_unfinished,
+            "system" => &self.system_unfinished,
+            _ => return Ok(()),
+        };
+        unfinished
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+            .map(|_| ())
+            .map_err(|_| PyValueError::new_err("task_done() called too many times"))
+    }
+
+    /// Number of data events waiting to be consumed
+    #[getter]
+    fn data_qsize(&self) -> usize {
+        self.data_rx.len()
+    }
+
+    /// Number of system events waiting to be consumed
+    #[getter]
+    fn system_qsize(&self) -> usize {
+        self.system_rx.len()
+    }
+
+    /// Per-event-type counters: `{name: {"published", "consumed", "dropped"}}`
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let out = PyDict::new(py);
+        for stats in self.stats.read().unwrap().iter() {
+            let counters = PyDict::new(py);
+            counters.set_item("published", stats.counters.published.load(Ordering::Relaxed))?;
+            counters.set_item("consumed", stats.counters.consumed.load(Ordering::Relaxed))?;
+            counters.set_item("dropped", stats.counters.dropped.load(Ordering::Relaxed))?;
+            out.set_item(&stats.name, counters)?;
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,539 @@
+//! Native `MarketEvent`
+//!
+//! Records live in five arrays, one per data type, each sorted by
+//! `(InstrumentId, Resolution)`. Ticks and book updates keep arrival order
+//! within an instrument; bars are unique per resolution, a later bar replacing an
+//! earlier one like the dict assignment in the Python implementation.
+//!
+//! `filter_by_instrument` does not copy: the filtered event shares the arrays
+//! and only records the selected IDs. Lookups for one instrument are binary
+//! searches, and the minimum-resolution bar is the first entry of its range.
+//!
+//! Unlike the Python implementation, the grouped getters (`trade_ticks`,
+//! `quote_bars`, `book_updates`, ...) build new dicts and lists from the arrays
+//! on every access. The records in them are shared, but adding to or removing
+//! from the containers does not change the event; use `add` instead.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use pyo3::exceptions::PyTypeError;
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PySet};
+use pyo3::PyClass;
+
+use crate::book::BookUpdate;
+use crate::interop::event_type;
+use crate::types::instrument::{default_registry, InstrumentId};
+use crate::types::market_data::{MarketData, QuoteBar, QuoteTick, Resolution, TradeBar, TradeTick};
+
+/// A record together with its sort key
+pub struct Entry<T> {
+    pub id: InstrumentId,
+    pub resolution: Resolution,
+    pub record: Py<T>,
+}
+
+impl<T> Entry<T> {
+    fn key(&self) -> (InstrumentId, Resolution) {
+        (self.id, self.resolution)
+    }
+
+    fn clone_ref(&self, py: Python<'_>) -> Self {
+        Entry {
+            id: self.id,
+            resolution: self.resolution,
+            record: self.record.clone_ref(py),
+        }
+    }
+}
+
+/// All entries for `id`
+fn range<T>(entries: &[Entry<T>], id: InstrumentId) -> &[Entry<T>] {
+    let lo = entries.partition_point(|e| e.id < id);
+    let hi = lo + entries[lo..].partition_point(|e| e.id == id);
+    &entries[lo..hi]
+}
+
+/// The entries visible through `selection`, as one slice per instrument
+/// (or a single slice when nothing is filtered)
+fn visible<'a, T>(entries: &'a [Entry<T>], selection: Option<&[InstrumentId]>) -> Vec<&'a [Entry<T>]> {
+    match selection {
+        None => vec![entries],
+        Some(ids) => ids.iter().map(|id| range(entries, *id)).filter(|r| !r.is_empty()).collect(),
+    }
+}
+
+/// Append after any existing ticks for the same instrument
+fn insert_tick<T>(entries: &mut Vec<Entry<T>>, entry: Entry<T>) {
+    let pos = entries.partition_point(|e| e.id <= entry.id);
+    entries.insert(pos, entry);
+}
+
+/// Insert, replacing any bar with the same instrument and resolution
+fn insert_bar<T>(entries: &mut Vec<Entry<T>>, entry: Entry<T>) {
+    let pos = entries.partition_point(|e| e.key() < entry.key());
+    match entries.get_mut(pos) {
+        Some(existing) if existing.key() == entry.key() => *existing = entry,
+        _ => entries.insert(pos, entry),
+    }
+}
+
+fn clone_visible<T>(py: Python<'_>, entries: &[Entry<T>], selection: Option<&[InstrumentId]>) -> Vec<Entry<T>> {
+    visible(entries, selection)
+        .into_iter()
+        .flatten()
+        .map(|entry| entry.clone_ref(py))
+        .collect()
+}
+
+#[derive(Default)]
+struct Buckets {
+    trade_ticks: Vec<Entry<TradeTick>>,
+    quote_ticks: Vec<Entry<QuoteTick>>,
+    trade_bars: Vec<Entry<TradeBar>>,
+    quote_bars: Vec<Entry<QuoteBar>>,
+    book_updates: Vec<Entry<BookUpdate>>,
+}
+
+impl Buckets {
+    fn clone_visible(&self, py: Python<'_>, selection: Option<&[InstrumentId]>) -> Self {
+        Buckets {
+            trade_ticks: clone_visible(py, &self.trade_ticks, selection),
+            quote_ticks: clone_visible(py, &self.quote_ticks, selection),
+            trade_bars: clone_visible(py, &self.trade_bars, selection),
+            quote_bars: clone_visible(py, &self.quote_bars, selection),
+            book_updates: clone_visible(py, &self.book_updates, selection),
+        }
+    }
+
+    /// Number of entries for `id` across all types
+    fn len_for(&self, id: InstrumentId) -> usize {
+        range(&self.trade_ticks, id).len()
+            + range(&self.quote_ticks, id).len()
+            + range(&self.trade_bars, id).len()
+            + range(&self.quote_bars, id).len()
+            + range(&self.book_updates, id).len()
+    }
+}
+
+fn entry<'py, T>(record: &Bound<'py, T>) -> PyResult<Entry<T>>
+where
+    T: PyClass<BaseType = MarketData, Frozen = pyo3::pyclass::boolean_struct::True> + Sync,
+{
+    let base = record.as_super().get();
+    Ok(Entry {
+        id: base.instrument_id(record.py())?,
+        resolution: base.native_resolution,
+        record: record.clone().unbind(),
+    })
+}
+
+fn instrument_of<T>(py: Python<'_>, entry: &Entry<T>) -> Py<PyAny>
+where
+    T: PyClass<BaseType = MarketData, Frozen = pyo3::pyclass::boolean_struct::True> + Sync,
+{
+    entry.record.bind(py).as_super().get().instrument.clone_ref(py)
+}
+
+/// Time-slice of market data for one timestamp, indexed by instrument
+#[pyclass(module = "_simulor_rust")]
+pub struct MarketEvent {
+    time: Py<PyAny>,
+    buckets: Arc<Buckets>,
+    /// Sorted instrument IDs visible through this event; `None` means all
+    selection: Option<Arc<[InstrumentId]>>,
+    count: usize,
+}
+
+impl MarketEvent {
+    pub fn new(time: Py<PyAny>) -> Self {
+        MarketEvent {
+            time,
+            buckets: Arc::new(Buckets::default()),
+            selection: None,
+            count: 0,
+        }
+    }
+
+    pub fn time(&self) -> &Py<PyAny> {
+        &self.time
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    fn selection(&self) -> Option<&[InstrumentId]> {
+        self.selection.as_deref()
+    }
+
+    fn is_visible(&self, id: InstrumentId) -> bool {
+        self.selection().map_or(true, |ids| ids.binary_search(&id).is_ok())
+    }
+
+    /// Mutable access to the arrays, copying them first if they are shared
+    /// with another event or only partly visible through this one
+    fn buckets_mut(&mut self, py: Python<'_>) -> &mut Buckets {
+        if self.selection.is_some() || Arc::get_mut(&mut self.buckets).is_none() {
+            self.buckets = Arc::new(self.buckets.clone_visible(py, self.selection()));
+            self.selection = None;
+        }
+        Arc::get_mut(&mut self.buckets).expect("buckets are uniquely owned after copy")
+    }
+
+    /// Add a `TradeTick`, `QuoteTick`, `TradeBar`, `QuoteBar` or `BookUpdate`
+    pub fn add_record(&mut self, record: &Bound<'_, PyAny>) -> PyResult<()> {
+        let py = record.py();
+        if let Ok(tick) = record.cast::<TradeTick>() {
+            let entry = entry(tick)?;
+            insert_tick(&mut self.buckets_mut(py).trade_ticks, entry);
+        } else if let Ok(tick) = record.cast::<QuoteTick>() {
+            let entry = entry(tick)?;
+            insert_tick(&mut self.buckets_mut(py).quote_ticks, entry);
+        } else if let Ok(bar) = record.cast::<TradeBar>() {
+            let entry = entry(bar)?;
+            insert_bar(&mut self.buckets_mut(py).trade_bars, entry);
+        } else if let Ok(bar) = record.cast::<QuoteBar>() {
+            let entry = entry(bar)?;
+            insert_bar(&mut self.buckets_mut(py).quote_bars, entry);
+        } else if let Ok(update) = record.cast::<BookUpdate>() {
+            let entry = entry(update)?;
+            insert_tick(&mut self.buckets_mut(py).book_updates, entry);
+        } else {
+            return Err(PyTypeError::new_err(format!("Unknown data type: {}", record.get_type().repr()?)));
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    /// IDs of the instruments with visible data, in ascending order
+    pub fn instrument_ids(&self) -> Vec<InstrumentId> {
+        let buckets = &self.buckets;
+        let mut ids: Vec<InstrumentId> = match self.selection() {
+            Some(ids) => ids.iter().copied().filter(|id| buckets.len_for(*id) > 0).collect(),
+            None => buckets
+                .trade_ticks
+                .iter()
+                .map(|e| e.id)
+                .chain(buckets.quote_ticks.iter().map(|e| e.id))
+                .chain(buckets.trade_bars.iter().map(|e| e.id))
+                .chain(buckets.quote_bars.iter().map(|e| e.id))
+                .chain(buckets.book_updates.iter().map(|e| e.id))
+                .collect(),
+        };
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    pub fn trade_ticks_for(&self, id: InstrumentId) -> &[Entry<TradeTick>] {
+        if self.is_visible(id) {
+            range(&self.buckets.trade_ticks, id)
+        } else {
+            &[]
+        }
+    }
+
+    pub fn quote_ticks_for(&self, id: InstrumentId) -> &[Entry<QuoteTick>] {
+        if self.is_visible(id) {
+            range(&self.buckets.quote_ticks, id)
+        } else {
+            &[]
+        }
+    }
+
+    /// Trade bars for `id`, finest resolution first
+    pub fn trade_bars_for(&self, id: InstrumentId) -> &[Entry<TradeBar>] {
+        if self.is_visible(id) {
+            range(&self.buckets.trade_bars, id)
+        } else {
+            &[]
+        }
+    }
+
+    /// Quote bars for `id`, finest resolution first
+    pub fn quote_bars_for(&self, id: InstrumentId) -> &[Entry<QuoteBar>] {
+        if self.is_visible(id) {
+            range(&self.buckets.quote_bars, id)
+        } else {
+            &[]
+        }
+    }
+
+    pub fn book_updates_for(&self, id: InstrumentId) -> &[Entry<BookUpdate>] {
+        if self.is_visible(id) {
+            range(&self.buckets.book_updates, id)
+        } else {
+            &[]
+        }
+    }
+
+    /// Event restricted to `ids` (sorted, deduplicated), sharing this event's data
+    pub fn filter_ids(&self, py: Python<'_>, ids: Vec<InstrumentId>) -> Self {
+        let ids: Vec<InstrumentId> = ids
+            .into_iter()
+            .filter(|id| self.is_visible(*id) && self.buckets.len_for(*id) > 0)
+            .collect();
+        MarketEvent {
+            time: self.time.clone_ref(py),
+            buckets: Arc::clone(&self.buckets),
+            count: ids.iter().map(|id| self.buckets.len_for(*id)).sum(),
+            selection: Some(ids.into()),
+        }
+    }
+
+    /// Resolve a Python `Instrument` to its ID, if any data could exist for it
+    fn lookup(py: Python<'_>, instrument: &Bound<'_, PyAny>) -> PyResult<Option<InstrumentId>> {
+        default_registry(py)?.get().lookup_instrument(instrument)
+    }
+}
+
+/// Split a sorted slice into runs sharing the same instrument
+fn groups<T>(mut entries: &[Entry<T>]) -> impl Iterator<Item = &[Entry<T>]> {
+    std::iter::from_fn(move || {
+        let first = entries.first()?;
+        let (group, rest) = entries.split_at(range(entries, first.id).len());
+        entries = rest;
+        Some(group)
+    })
+}
+
+fn ticks_dict<'py, T>(
+    py: Python<'py>,
+    entries: &[Entry<T>],
+    selection: Option<&[InstrumentId]>,
+) -> PyResult<Bound<'py, PyDict>>
+where
+    T: PyClass<BaseType = MarketData, Frozen = pyo3::pyclass::boolean_struct::True> + Sync,
+{
+    let dict = PyDict::new(py);
+    for slice in visible(entries, selection) {
+        for group in groups(slice) {
+            let list = PyList::new(py, group.iter().map(|e| e.record.bind(py)))?;
+            dict.set_item(instrument_of(py, &group[0]), list)?;
+        }
+    }
+    Ok(dict)
+}
+
+fn bars_dict<'py, T>(
+    py: Python<'py>,
+    entries: &[Entry<T>],
+    selection: Option<&[InstrumentId]>,
+) -> PyResult<Bound<'py, PyDict>>
+where
+    T: PyClass<BaseType = MarketData, Frozen = pyo3::pyclass::boolean_struct::True> + Sync,
+{
+    let dict = PyDict::new(py);
+    for slice in visible(entries, selection) {
+        for group in groups(slice) {
+            let by_resolution = PyDict::new(py);
+            for entry in group {
+                let record = entry.record.bind(py);
+                by_resolution.set_item(record.as_super().get().resolution.bind(py), record)?;
+            }
+            dict.set_item(instrument_of(py, &group[0]), by_resolution)?;
+        }
+    }
+    Ok(dict)
+}
+
+#[pymethods]
+impl MarketEvent {
+    #[new]
+    fn py_new(time: Py<PyAny>) -> Self {
+        MarketEvent::new(time)
+    }
+
+    /// Always `EventType.MARKET`
+    #[getter(r#type)]
+    fn py_type<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        event_type(py)?.getattr(intern!(py, "MARKET"))
+    }
+
+    /// Timestamp of the event
+    #[getter(time)]
+    fn py_time(&self, py: Python<'_>) -> Py<PyAny> {
+        self.time.clone_ref(py)
+    }
+
+    #[setter(time)]
+    fn set_time(&mut self, time: Py<PyAny>) {
+        self.time = time;
+    }
+
+    /// Get total number of data points in this event
+    #[getter(count)]
+    fn py_count(&self) -> usize {
+        self.count
+    }
+
+    /// Get all instruments contained in this market event
+    fn instruments<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PySet>> {
+        let buckets = &self.buckets;
+        let selection = self.selection();
+        let mut instruments = BTreeMap::new();
+        for slice in visible(&buckets.trade_ticks, selection) {
+            instruments.extend(slice.iter().map(|e| (e.id, instrument_of(py, e))));
+        }
+        for slice in visible(&buckets.quote_ticks, selection) {
+            instruments.extend(slice.iter().map(|e| (e.id, instrument_of(py, e))));
+        }
+        for slice in visible(&buckets.trade_bars, selection) {
+            instruments.extend(slice.iter().map(|e| (e.id, instrument_of(py, e))));
+        }
+        for slice in visible(&buckets.quote_bars, selection) {
+            instruments.extend(slice.iter().map(|e| (e.id, instrument_of(py, e))));
+        }
+        for slice in visible(&buckets.book_updates, selection) {
+            instruments.extend(slice.iter().map(|e| (e.id, instrument_of(py, e))));
+        }
+        PySet::new(py, instruments.into_values())
+    }
+
+    /// Create a filtered MarketEvent for the given set of instruments
+    ///
+    /// The result shares this event's data; nothing is copied unless records
+    /// are later added to it.
+    fn filter_by_instrument(&self, py: Python<'_>, instruments: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let mut ids = Vec::new();
+        for instrument in instruments.try_iter()? {
+            if let Some(id) = MarketEvent::lookup(py, &instrument?)? {
+                ids.push(id);
+            }
+        }
+        ids.sort_unstable();
+        ids.dedup();
+        Ok(self.filter_ids(py, ids))
+    }
+
+    /// Flatten all market data in this event into a single list
+    fn flatten<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let buckets = &self.buckets;
+        let selection = self.selection();
+        let list = PyList::empty(py);
+        for slice in visible(&buckets.trade_ticks, selection) {
+            for entry in slice {
+                list.append(entry.record.bind(py))?;
+            }
+        }
+        for slice in visible(&buckets.quote_ticks, selection) {
+            for entry in slice {
+                list.append(entry.record.bind(py))?;
+            }
+        }
+        for slice in visible(&buckets.trade_bars, selection) {
+            for entry in slice {
+                list.append(entry.record.bind(py))?;
+            }
+        }
+        for slice in visible(&buckets.quote_bars, selection) {
+            for entry in slice {
+                list.append(entry.record.bind(py))?;
+            }
+        }
+        for slice in visible(&buckets.book_updates, selection) {
+            for entry in slice {
+                list.append(entry.record.bind(py))?;
+            }
+        }
+        Ok(list)
+    }
+
+    /// Dispatch item to correct bucket
+    fn add(&mut self, market_data: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.add_record(market_data)
+    }
+
+    /// Get all trade ticks grouped by instrument
+    ///
+    /// Returns a new dict on each access; mutating it does not change the event.
+    #[getter]
+    fn trade_ticks<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        ticks_dict(py, &self.buckets.trade_ticks, self.selection())
+    }
+
+    /// Get all quote ticks grouped by instrument
+    ///
+    /// Returns a new dict on each access; mutating it does not change the event.
+    #[getter]
+    fn quote_ticks<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        ticks_dict(py, &self.buckets.quote_ticks, self.selection())
+    }
+
+    /// Get all trade bars grouped by instrument and resolution
+    ///
+    /// Returns a new dict on each access; mutating it does not change the event.
+    #[getter]
+    fn trade_bars<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        bars_dict(py, &self.buckets.trade_bars, self.selection())
+    }
+
+    /// Get all quote bars grouped by instrument and resolution
+    ///
+    /// Returns a new dict on each access; mutating it does not change the event.
+    #[getter]
+    fn quote_bars<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        bars_dict(py, &self.buckets.quote_bars, self.selection())
+    }
+
+    /// Get all book updates grouped by instrument, in arrival order
+    ///
+    /// Returns a new dict on each access; mutating it does not change the event.
+    #[getter(book_updates)]
+    fn py_book_updates<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        ticks_dict(py, &self.buckets.book_updates, self.selection())
+    }
+
+    /// Get the last trade tick for a given instrument
+    fn get_last_trade_tick(&self, py: Python<'_>, instrument: &Bound<'_, PyAny>) -> PyResult<Option<Py<TradeTick>>> {
+        let Some(id) = MarketEvent::lookup(py, instrument)? else {
+            return Ok(None);
+        };
+        Ok(self.trade_ticks_for(id).last().map(|e| e.record.clone_ref(py)))
+    }
+
+    /// Get the last quote tick for a given instrument
+    fn get_last_quote_tick(&self, py: Python<'_>, instrument: &Bound<'_, PyAny>) -> PyResult<Option<Py<QuoteTick>>> {
+        let Some(id) = MarketEvent::lookup(py, instrument)? else {
+            return Ok(None);
+        };
+        Ok(self.quote_ticks_for(id).last().map(|e| e.record.clone_ref(py)))
+    }
+
+    /// Get the book updates for a given instrument, in arrival order
+    ///
+    /// Returns a new list on each call; mutating it does not change the event.
+    fn get_book_updates<'py>(&self, py: Python<'py>, instrument: &Bound<'_, PyAny>) -> PyResult<Bound<'py, PyList>> {
+        let Some(id) = MarketEvent::lookup(py, instrument)? else {
+            return Ok(PyList::empty(py));
+        };
+        PyList::new(py, self.book_updates_for(id).iter().map(|e| e.record.bind(py)))
+    }
+
+    /// Get the minimum resolution trade bar for a given instrument
+    fn get_min_res_trade_bar(&self, py: Python<'_>, instrument: &Bound<'_, PyAny>) -> PyResult<Option<Py<TradeBar>>> {
+        let Some(id) = MarketEvent::lookup(py, instrument)? else {
+            return Ok(None);
+        };
+        Ok(self.trade_bars_for(id).first().map(|e| e.record.clone_ref(py)))
+    }
+
+    /// Get the minimum resolution quote bar for a given instrument
+    fn get_min_res_quote_bar(&self, py: Python<'_>, instrument: &Bound<'_, PyAny>) -> PyResult<Option<Py<QuoteBar>>> {
+        let Some(id) = MarketEvent::lookup(py, instrument)? else {
+            return Ok(None);
+        };
+        Ok(self.quote_bars_for(id).first().map(|e| e.record.clone_ref(py)))
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "MarketEvent(type={}, time={})",
+            self.py_type(py)?.repr()?,
+            self.time.bind(py).repr()?
+        ))
+    }
+}
@@ -0,0 +1,16 @@
+//! Native event types
+
+pub mod bus;
+pub mod market_event;
+
+use pyo3::prelude::*;
+
+pub use bus::EventBus;
+pub use market_event::MarketEvent;
+
+/// Register the event types on the extension module
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<MarketEvent>()?;
+    m.add_class::<EventBus>()?;
+    Ok(())
+}
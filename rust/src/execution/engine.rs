@@ -0,0 +1,639 @@
+//! Exchange side of the simulated broker
+
+use std::collections::{BTreeMap, HashMap};
+
+use pyo3::exceptions::{PyNotImplementedError, PyOverflowError};
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::execution::intrabar::{walk, IntrabarFillModel, IntrabarPath, PathTime, Walk};
+use crate::execution::latency::LatencyQueue;
+use crate::execution::market::{Books, EventView, Quote, Snapshot};
+use crate::execution::order::{Order, Role, Saved, Side};
+use crate::execution::partial::{PartialFillModel, Participation};
+use crate::execution::trailing::TrailingStopTracker;
+use crate::interop::{fill_type, instant_fill_model_type, logger};
+use crate::types::fixed::Fixed;
+use crate::types::instrument::{default_registry, InstrumentId};
+use crate::types::price::{extract_fixed, to_decimal};
+use crate::types::time::datetime_to_nanos;
+
+const LOGGER: &str = "simulor.execution.simulation.matching";
+/// `logging.WARNING`
+const WARNING: u8 = 30;
+
+/// An order filled by the matching engine, in full or in part
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct OrderFill {
+    order_id: String,
+    strategy_name: String,
+    order: Py<PyAny>,
+    fill: Py<PyAny>,
+    filled_quantity: Fixed,
+    remaining_quantity: Fixed,
+    average_price: Fixed,
+}
+
+#[pymethods]
+impl OrderFill {
+    #[getter]
+    fn order_id(&self) -> &str {
+        &self.order_id
+    }
+
+    /// Strategy that placed the order
+    #[getter]
+    fn strategy_name(&self) -> &str {
+        &self.strategy_name
+    }
+
+    /// The `OrderSpec` as submitted
+    #[getter]
+    fn order(&self, py: Python<'_>) -> Py<PyAny> {
+        self.order.clone_ref(py)
+    }
+
+    /// This fill alone
+    #[getter]
+    fn fill(&self, py: Python<'_>) -> Py<PyAny> {
+        self.fill.clone_ref(py)
+    }
+
+    /// Quantity of the order filled so far, this fill included
+    #[getter(filled_quantity)]
+    fn py_filled_quantity<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.filled_quantity)
+    }
+
+    /// Quantity of the order left resting in the book, zero once filled
+    #[getter(remaining_quantity)]
+    fn py_remaining_quantity<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.remaining_quantity)
+    }
+
+    /// Volume-weighted average price of the fills so far
+    #[getter(average_price)]
+    fn py_average_price<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.average_price)
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "OrderFill(order_id={:?}, strategy_name={:?}, fill={}, filled_quantity={}, remaining_quantity={})",
+            self.order_id,
+            self.strategy_name,
+            self.fill.bind(py).repr()?,
+            self.filled_quantity,
+            self.remaining_quantity,
+        ))
+    }
+}
+
+/// A conditional order whose trigger fired, making it a market or limit
+/// order
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct OrderTrigger {
+    order_id: String,
+    strategy_name: String,
+    order: Py<PyAny>,
+    trigger_price: Fixed,
+    market_price: Fixed,
+}
+
+#[pymethods]
+impl OrderTrigger {
+    #[getter]
+    fn order_id(&self) -> &str {
+        &self.order_id
+    }
+
+    /// Strategy that placed the order
+    #[getter]
+    fn strategy_name(&self) -> &str {
+        &self.strategy_name
+    }
+
+    /// The `OrderSpec` as submitted
+    #[getter]
+    fn order(&self, py: Python<'_>) -> Py<PyAny> {
+        self.order.clone_ref(py)
+    }
+
+    /// Level that was reached: the `stop_price` of an if-touched order, the
+    /// stop of a trailing one
+    #[getter(trigger_price)]
+    fn py_trigger_price<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.trigger_price)
+    }
+
+    /// Market price that reached it
+    #[getter(market_price)]
+    fn py_market_price<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.market_price)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "OrderTrigger(order_id={:?}, strategy_name={:?}, trigger_price={}, market_price={})",
+            self.order_id, self.strategy_name, self.trigger_price, self.market_price,
+        )
+    }
+}
+
+/// What matching did to a resting order, in the order it happened
+enum Matched<'py> {
+    Trigger(OrderTrigger),
+    /// Order ID, price and quantity
+    Fill(String, Bound<'py, PyAny>, Fixed),
+    /// Order ID of an order with data in the event that did not fill
+    Unfilled(String),
+}
+
+/// How the engine prices orders itself
+#[derive(Debug, Clone, Copy)]
+enum Pricing {
+    /// As `InstantFillModel` does
+    Instant,
+    /// As `IntrabarFillModel` does
+    Intrabar(IntrabarPath),
+    /// As `PartialFillModel` does, at this participation
+    Partial(Fixed),
+}
+
+/// An order's walk through one event
+struct Planned {
+    arrival: u64,
+    saved: Saved,
+    /// Whether it walked a bar's path
+    intrabar: bool,
+    walk: Walk,
+}
+
+/// Match the resting orders of one instrument against its prices, in the
+/// order each trigger and fill happened
+///
+/// Within a bar, a stop and a target of one strategy on one side both
+/// reached leave only the first to fill; the other is restored to how it
+/// was before the bar. Orders with data in the event that did not fill
+/// come last, as `Unfilled`.
+fn match_orders<'py>(
+    py: Python<'py>,
+    orders: &mut BTreeMap<u64, Order>,
+    snapshot: &Snapshot,
+    pricing: Pricing,
+) -> PyResult<Vec<Matched<'py>>> {
+    let (buy, sell) = (snapshot.quote(Side::Buy), snapshot.quote(Side::Sell));
+    let mut planned = Vec::new();
+    let mut unfilled = Vec::new();
+    for (&arrival, order) in orders.iter_mut() {
+        let quote = match order.side {
+            Side::Buy => buy,
+            Side::Sell => sell,
+        };
+        let Some(quote) = quote else {
+            unfilled.push(order.id.clone());
+            continue;
+        };
+        let saved = order.save();
+        let (walk, intrabar) = match (pricing, quote) {
+            (Pricing::Intrabar(path), Quote::Bar(bar)) => (walk(order, path.points(bar))?, true),
+            (_, quote) => (Walk::at_once(order.match_price(quote.last())?, quote.last()), false),
+        };
+        if walk.fill.is_none() {
+            unfilled.push(order.id.clone());
+        }
+        if walk.trigger.is_some() || walk.fill.is_some() {
+            planned.push(Planned {
+                arrival,
+                saved,
+                intrabar,
+                walk,
+            });
+        }
+    }
+
+    let mut filling: Vec<&Planned> = planned.iter().filter(|plan| plan.walk.fill.is_some()).collect();
+    filling.sort_by_key(|plan| (plan.walk.fill.map(|(_, time)| time), plan.arrival));
+    let mut taken: Vec<(&str, Side, Role)> = Vec::new();
+    let mut undone: Vec<u64> = Vec::new();
+    for plan in filling {
+        let order = &orders[&plan.arrival];
+        let Some(role) = order.role().filter(|_| plan.intrabar) else {
+            continue;
+        };
+        let strategy = order.strategy.as_str();
+        let conflict = taken.iter().any(|&(other_strategy, other_side, other_role)| {
+            other_strategy == strategy && other_side == order.side && other_role != role
+        });
+        if conflict {
+            undone.push(plan.arrival);
+        } else {
+            taken.push((strategy, order.side, role));
+        }
+    }
+
+    let mut steps: Vec<(PathTime, u64, Matched<'py>)> = Vec::new();
+    for plan in &planned {
+        if undone.contains(&plan.arrival) {
+            continue;
+        }
+        let order = &orders[&plan.arrival];
+        if let Some((trigger_price, market_price, time)) = plan.walk.trigger {
+            let trigger = OrderTrigger {
+                order_id: order.id.clone(),
+                strategy_name: order.strategy.clone(),
+                order: order.spec.clone_ref(py),
+                trigger_price,
+                market_price,
+            };
+            steps.push((time, plan.arrival, Matched::Trigger(trigger)));
+        }
+        if let Some((price, time)) = plan.walk.fill {
+            let fill = Matched::Fill(order.id.clone(), to_decimal(py, price)?, order.remaining()?);
+            steps.push((time, plan.arrival, fill));
+        }
+    }
+    for plan in planned.iter().filter(|plan| undone.contains(&plan.arrival)) {
+        if let Some(order) = orders.get_mut(&plan.arrival) {
+            order.restore(plan.saved);
+        }
+    }
+    steps.sort_by_key(|(time, arrival, _)| (*time, *arrival));
+    let unfilled = unfilled.into_iter().map(Matched::Unfilled);
+    Ok(steps.into_iter().map(|(_, _, matched)| matched).chain(unfilled).collect())
+}
+
+/// Orders in flight to the exchange and resting in its book, matched
+/// against each market event
+///
+/// With a plain `InstantFillModel`, an `IntrabarFillModel` or a
+/// `PartialFillModel` orders are priced natively, by the same waterfall and
+/// order type rules; any other fill model is asked for the price of each
+/// order with data in the event, and fills it in full. Commissions come
+/// from `cost_model.calculate_total_cost`, per fill.
+#[pyclass(module = "_simulor_rust")]
+pub struct MatchingEngine {
+    fill_model: Py<PyAny>,
+    cost_model: Py<PyAny>,
+    /// How orders are priced natively, unless `fill_model` prices them
+    pricing: Option<Pricing>,
+    in_flight: LatencyQueue,
+    /// Resting orders of each instrument, by arrival
+    resting: BTreeMap<InstrumentId, BTreeMap<u64, Order>>,
+    /// Instrument and arrival of each resting order
+    index: HashMap<String, (InstrumentId, u64)>,
+    arrivals: u64,
+    /// Level 2 books, when the native pricing uses them
+    books: Option<Books>,
+}
+
+impl MatchingEngine {
+    fn rest(&mut self, order: Order) {
+        self.arrivals += 1;
+        self.index.insert(order.id.clone(), (order.instrument_id, self.arrivals));
+        self.resting.entry(order.instrument_id).or_default().insert(self.arrivals, order);
+    }
+
+    fn take(&mut self, order_id: &str) -> Option<Order> {
+        let (id, arrival) = self.index.remove(order_id)?;
+        let orders = self.resting.get_mut(&id)?;
+        let order = orders.remove(&arrival);
+        if orders.is_empty() {
+            self.resting.remove(&id);
+        }
+        order
+    }
+
+    /// The resting order `order_id`, while it is in the book
+    fn resting_order(&self, order_id: &str) -> Option<&Order> {
+        let &(id, arrival) = self.index.get(order_id)?;
+        self.resting.get(&id)?.get(&arrival)
+    }
+
+    /// Take out the resting orders matching `remove`, in arrival order
+    fn take_where(&mut self, remove: impl Fn(&Order) -> bool) -> Vec<Order> {
+        let ids: Vec<String> = self
+            .resting
+            .values()
+            .flat_map(BTreeMap::values)
+            .filter(|order| remove(order))
+            .map(|order| order.id.clone())
+            .collect();
+        let mut taken: Vec<(u64, Order)> = Vec::with_capacity(ids.len());
+        for order_id in ids {
+            let arrival = self.index[&order_id].1;
+            taken.extend(self.take(&order_id).map(|order| (arrival, order)));
+        }
+        taken.sort_by_key(|(arrival, _)| *arrival);
+        taken.into_iter().map(|(_, order)| order).collect()
+    }
+
+    /// Fill `quantity` of the resting order `order_id` at `price`, the
+    /// order leaving the book once filled in full
+    ///
+    /// The fill is first offered to `accept`, when given; if it declines,
+    /// the order rests as it was.
+    fn fill<'py>(
+        &mut self,
+        py: Python<'py>,
+        order_id: &str,
+        price: &Bound<'py, PyAny>,
+        quantity: Fixed,
+        accept: Option<&Bound<'py, PyAny>>,
+    ) -> PyResult<Option<Bound<'py, OrderFill>>> {
+        let Some(&(id, arrival)) = self.index.get(order_id) else {
+            return Ok(None);
+        };
+        let Some(order) = self.resting.get_mut(&id).and_then(|orders| orders.get_mut(&arrival)) else {
+            return Ok(None);
+        };
+        let spec = order.spec.bind(py);
+        let filled = if quantity == order.quantity {
+            spec.getattr(intern!(py, "quantity"))?
+        } else {
+            to_decimal(py, quantity)?
+        };
+        let kwargs = PyDict::new(py);
+        kwargs.set_item(intern!(py, "quantity"), &filled)?;
+        kwargs.set_item(intern!(py, "price"), price)?;
+        let commission =
+            self.cost_model
+                .bind(py)
+                .call_method(intern!(py, "calculate_total_cost"), (), Some(&kwargs))?;
+        let signed = match order.side {
+            Side::Buy => filled,
+            Side::Sell => filled.neg()?,
+        };
+        let fill = fill_type(py)?.call1((order.instrument.bind(py), signed, price, commission))?;
+        let filled = order.filled.checked_add(quantity)?;
+        let notional = order.notional.checked_add(extract_fixed(price)?.checked_mul(quantity)?)?;
+        let order_fill = Bound::new(
+            py,
+            OrderFill {
+                order_id: order.id.clone(),
+                strategy_name: order.strategy.clone(),
+                order: order.spec.clone_ref(py),
+                fill: fill.unbind(),
+                filled_quantity: filled,
+                remaining_quantity: order.quantity.checked_sub(filled)?,
+                average_price: notional.checked_quotient(filled)?,
+            },
+        )?;
+        if let Some(accept) = accept {
+            if !accept.call1((&order_fill,))?.is_truthy()? {
+                return Ok(None);
+            }
+        }
+        order.filled = filled;
+        order.notional = notional;
+        if !order_fill.get().remaining_quantity.is_positive() {
+            self.take(order_id);
+        }
+        Ok(Some(order_fill))
+    }
+
+    /// Warn that the resting order `order_id`, with data in `event`, cannot
+    /// be filled; `enabled` caches whether such warnings are logged
+    fn warn_unfilled(&self, event: &Bound<'_, PyAny>, order_id: &str, enabled: &mut Option<bool>) -> PyResult<()> {
+        let py = event.py();
+        let enabled = match *enabled {
+            Some(enabled) => enabled,
+            None => *enabled.insert(logger(py, LOGGER)?.call_method1("isEnabledFor", (WARNING,))?.is_truthy()?),
+        };
+        let Some((id, arrival)) = self.index.get(order_id).filter(|_| enabled) else {
+            return Ok(());
+        };
+        let order = &self.resting[id][arrival];
+        logger(py, LOGGER)?.call_method1(
+            "warning",
+            (
+                "Order %s for %s cannot be filled at %s",
+                order_id,
+                order.instrument.bind(py).getattr(intern!(py, "display_name"))?,
+                event.getattr(intern!(py, "time"))?,
+            ),
+        )?;
+        Ok(())
+    }
+}
+
+fn ids(orders: &[Order]) -> Vec<String> {
+    orders.iter().map(|order| order.id.clone()).collect()
+}
+
+#[pymethods]
+impl MatchingEngine {
+    #[new]
+    fn py_new(fill_model: &Bound<'_, PyAny>, cost_model: Py<PyAny>) -> PyResult<Self> {
+        let py = fill_model.py();
+        let (pricing, use_book) = if fill_model.get_type().is(instant_fill_model_type(py)?) {
+            let use_book = fill_model.getattr(intern!(py, "use_book"))?.is_truthy()?;
+            (Some(Pricing::Instant), use_book)
+        } else if let Ok(intrabar) = fill_model.cast::<IntrabarFillModel>() {
+            (Some(Pricing::Intrabar(intrabar.get().path())), intrabar.get().use_book())
+        } else if let Ok(partial) = fill_model.cast::<PartialFillModel>() {
+            (Some(Pricing::Partial(partial.get().participation())), partial.get().use_book())
+        } else {
+            (None, false)
+        };
+        Ok(MatchingEngine {
+            fill_model: fill_model.clone().unbind(),
+            cost_model,
+            pricing,
+            in_flight: LatencyQueue::default(),
+            resting: BTreeMap::new(),
+            index: HashMap::new(),
+            arrivals: 0,
+            books: use_book.then(Books::default),
+        })
+    }
+
+    /// Send an order to the exchange, where it arrives at `release_time`
+    ///
+    /// Trailing and if-touched orders are only matched with native pricing:
+    /// a fill model prices one event at a time, and cannot carry their
+    /// trigger across.
+    fn submit(
+        &mut self,
+        order_id: String,
+        strategy_name: String,
+        order_spec: &Bound<'_, PyAny>,
+        release_time: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        let py = order_spec.py();
+        let release = match datetime_to_nanos(release_time) {
+            Ok(nanos) => nanos,
+            // Submitted before the first event, when the clock is still at `datetime.min`
+            Err(err) if err.is_instance_of::<PyOverflowError>(py) => i64::MIN,
+            Err(err) => return Err(err),
+        };
+        let order = Order::from_spec(order_id, strategy_name, order_spec)?;
+        if self.pricing.is_none() && order.kind.is_conditional() {
+            let fill_model = self.fill_model.bind(py).get_type().name()?;
+            return Err(PyNotImplementedError::new_err(format!(
+                "Trailing and if-touched orders are only matched with an InstantFillModel, IntrabarFillModel or \
+                 PartialFillModel, not a {fill_model}"
+            )));
+        }
+        self.in_flight.push(release, order);
+        Ok(())
+    }
+
+    /// Strategy owning a resting order, if it is in the book
+    fn owner(&self, order_id: &str) -> Option<String> {
+        let (id, arrival) = self.index.get(order_id)?;
+        Some(self.resting[id][arrival].strategy.clone())
+    }
+
+    /// Stop tracker of a resting trailing order
+    fn trailing_stop(&self, py: Python<'_>, order_id: &str) -> Option<Py<TrailingStopTracker>> {
+        let (id, arrival) = self.index.get(order_id)?;
+        let trail = self.resting[id][arrival].trail.as_ref()?;
+        Some(trail.clone_ref(py))
+    }
+
+    /// Remove a resting order from the book, returning its `OrderSpec`
+    fn cancel(&mut self, order_id: &str) -> Option<Py<PyAny>> {
+        self.take(order_id).map(|order| order.spec)
+    }
+
+    /// Expire DAY orders at the session close; returns the IDs of those
+    /// resting in the book and of those still in flight
+    fn expire_day_orders(&mut self) -> (Vec<String>, Vec<String>) {
+        let resting = self.take_where(|order| order.day);
+        let in_flight = self.in_flight.remove_where(|order| order.day);
+        (ids(&resting), ids(&in_flight))
+    }
+
+    /// Cancel the orders of `instrument`; returns the IDs of those resting
+    /// in the book and of those still in flight
+    fn cancel_instrument(&mut self, instrument: &Bound<'_, PyAny>) -> PyResult<(Vec<String>, Vec<String>)> {
+        let Some(id) = default_registry(instrument.py())?.get().lookup_instrument(instrument)? else {
+            return Ok((Vec::new(), Vec::new()));
+        };
+        let resting = self.take_where(|order| order.instrument_id == id);
+        let in_flight = self.in_flight.remove_where(|order| order.instrument_id == id);
+        Ok((ids(&resting), ids(&in_flight)))
+    }
+
+    /// Resting orders by ID, in arrival order
+    #[getter]
+    fn open_orders<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let mut orders: Vec<(u64, &Order)> = self
+            .resting
+            .values()
+            .flat_map(|orders| orders.iter().map(|(arrival, order)| (*arrival, order)))
+            .collect();
+        orders.sort_by_key(|(arrival, _)| *arrival);
+        let dict = PyDict::new(py);
+        for (_, order) in orders {
+            dict.set_item(&order.id, order.spec.bind(py))?;
+        }
+        Ok(dict)
+    }
+
+    /// Number of orders still in flight
+    #[getter]
+    fn in_flight_count(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    /// Release the orders arriving by the event's time, then match the
+    /// book against the event; returns the `OrderTrigger`s and `OrderFill`s
+    /// in the order they happened, orders leaving the book once filled in
+    /// full
+    ///
+    /// `accept`, when given, is called with each `OrderFill` before it is
+    /// made; a fill it declines is left out, its order resting as before.
+    #[pyo3(signature = (event, accept=None))]
+    fn on_market_event<'py>(
+        &mut self,
+        event: &Bound<'py, PyAny>,
+        accept: Option<&Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let py = event.py();
+        let now = datetime_to_nanos(&event.getattr(intern!(py, "time"))?)?;
+        for order in self.in_flight.release(now) {
+            self.rest(order);
+        }
+
+        let view = EventView::new(event);
+        if self.pricing.is_some() {
+            if let Some(books) = self.books.as_mut() {
+                books.apply(py, &view)?;
+            }
+        } else {
+            self.fill_model.bind(py).call_method1(intern!(py, "on_market_event"), (event,))?;
+        }
+
+        let events = PyList::empty(py);
+        if self.resting.is_empty() {
+            return Ok(events);
+        }
+        let mut warn = None;
+        for id in view.instrument_ids(py)? {
+            let Some(orders) = self.resting.get_mut(&id) else {
+                continue;
+            };
+            let mut matched: Vec<Matched<'py>> = Vec::new();
+            let mut participation = None;
+            if let Some(pricing) = self.pricing {
+                let instrument = orders.values().next().map(|order| order.instrument.bind(py).clone());
+                let Some(instrument) = instrument else {
+                    continue;
+                };
+                let snapshot = view.snapshot(id, &instrument, self.books.as_ref())?;
+                matched = match_orders(py, orders, &snapshot, pricing)?;
+                if let Pricing::Partial(rate) = pricing {
+                    participation = Some(Participation::new(rate, &snapshot));
+                }
+            } else {
+                let mut specs: Vec<(String, Py<PyAny>, Fixed)> = Vec::with_capacity(orders.len());
+                for order in orders.values() {
+                    specs.push((order.id.clone(), order.spec.clone_ref(py), order.remaining()?));
+                }
+                let fill_model = self.fill_model.bind(py);
+                for (order_id, spec, quantity) in specs {
+                    let price = fill_model.call_method1(intern!(py, "get_fill_price"), (spec, event))?;
+                    if price.is_none() {
+                        matched.push(Matched::Unfilled(order_id));
+                    } else {
+                        matched.push(Matched::Fill(order_id, price, quantity));
+                    }
+                }
+            }
+            for matched in matched {
+                match matched {
+                    Matched::Trigger(trigger) => events.append(trigger)?,
+                    Matched::Fill(order_id, price, quantity) => {
+                        // Partial fills share the event's quantity in the order
+                        // they happen, taking it only once made
+                        let share = match (&participation, self.resting_order(&order_id)) {
+                            (Some(participation), Some(order)) => Some((order.side, participation.share(order)?)),
+                            _ => None,
+                        };
+                        let quantity = share.map_or(quantity, |(_, share)| share);
+                        if !quantity.is_positive() {
+                            self.warn_unfilled(event, &order_id, &mut warn)?;
+                            continue;
+                        }
+                        let Some(fill) = self.fill(py, &order_id, &price, quantity, accept)? else {
+                            continue;
+                        };
+                        if let (Some(participation), Some((side, _))) = (participation.as_mut(), share) {
+                            participation.take(side, quantity)?;
+                        }
+                        events.append(fill)?;
+                    }
+                    Matched::Unfilled(order_id) => self.warn_unfilled(event, &order_id, &mut warn)?,
+                }
+            }
+        }
+        Ok(events)
+    }
+
+    fn __len__(&self) -> usize {
+        self.index.len()
+    }
+}
@@ -0,0 +1,224 @@
+//! Fills inside bars
+//!
+//! A bar only tells where the price opened, how far it ranged and where it
+//! closed. Orders matched against one follow a path through those points,
+//! the price moving continuously between them: a limit fills at its limit
+//! when the path touches it, a stop at its stop, or at the open when the
+//! bar gaps through either. The order of the high and the low is inferred.
+
+use std::sync::Mutex;
+
+use pyo3::prelude::*;
+
+use crate::execution::market::{Books, EventView, Ohlc, Quote};
+use crate::execution::order::{Band, Order, Outcome};
+use crate::types::fixed::Fixed;
+use crate::types::instrument::default_registry;
+use crate::types::price::to_decimal;
+
+/// Order in which a bar is assumed to visit its high and low
+#[pyclass(module = "_simulor_rust", eq, eq_int, frozen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntrabarPath {
+    /// Low first for bars closing at or above their open, high first for
+    /// those closing below
+    #[pyo3(name = "AUTO")]
+    Auto,
+    /// Open, high, low, close
+    #[pyo3(name = "OPEN_HIGH_LOW_CLOSE")]
+    OpenHighLowClose,
+    /// Open, low, high, close
+    #[pyo3(name = "OPEN_LOW_HIGH_CLOSE")]
+    OpenLowHighClose,
+}
+
+impl IntrabarPath {
+    pub fn points(self, bar: Ohlc) -> [Fixed; 4] {
+        let high_first = match self {
+            IntrabarPath::Auto => bar.close < bar.open,
+            IntrabarPath::OpenHighLowClose => true,
+            IntrabarPath::OpenLowHighClose => false,
+        };
+        if high_first {
+            [bar.open, bar.high, bar.low, bar.close]
+        } else {
+            [bar.open, bar.low, bar.high, bar.close]
+        }
+    }
+}
+
+/// Position along a bar's path: the leg, the open being leg 0, and the
+/// distance travelled along it
+pub type PathTime = (usize, Fixed);
+
+/// What happened to an order along a bar's path
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Walk {
+    /// Level of the trigger that fired, the price there and when
+    pub trigger: Option<(Fixed, Fixed, PathTime)>,
+    /// Fill price and when
+    pub fill: Option<(Fixed, PathTime)>,
+}
+
+impl Walk {
+    /// What one price did, all at once
+    pub fn at_once(outcome: Outcome, market: Fixed) -> Self {
+        let mut walk = Walk::default();
+        walk.record(outcome, market, (0, Fixed::ZERO));
+        walk
+    }
+
+    fn record(&mut self, outcome: Outcome, market: Fixed, time: PathTime) {
+        if let Some(level) = outcome.trigger {
+            self.trigger = Some((level, market, time));
+        }
+        if let Some(price) = outcome.fill {
+            self.fill = Some((price, time));
+        }
+    }
+}
+
+/// First price on the leg from `from` to `to` inside `band`
+fn first_touch(from: Fixed, to: Fixed, band: Band) -> Option<Fixed> {
+    if band.contains(from) {
+        return Some(from);
+    }
+    match (band.low, band.high) {
+        (Some(low), _) if from < low => (to >= low && band.contains(low)).then_some(low),
+        (_, Some(high)) if from > high => (to <= high && band.contains(high)).then_some(high),
+        _ => None,
+    }
+}
+
+fn distance(from: Fixed, to: Fixed) -> PyResult<Fixed> {
+    Ok(to.checked_sub(from)?.checked_abs()?)
+}
+
+/// Match `order` along the path through `points`, stopping at its fill
+pub fn walk(order: &mut Order, points: [Fixed; 4]) -> PyResult<Walk> {
+    let mut walk = Walk::default();
+    walk.record(order.match_price(points[0])?, points[0], (0, Fixed::ZERO));
+    if walk.fill.is_some() {
+        return Ok(walk);
+    }
+    for (leg, ends) in points.windows(2).enumerate() {
+        let (start, end) = (ends[0], ends[1]);
+        let mut from = start;
+        loop {
+            let touch = order.band().and_then(|band| first_touch(from, end, band));
+            let Some(touch) = touch else {
+                order.drift(end)?;
+                break;
+            };
+            let outcome = order.match_price(touch)?;
+            walk.record(outcome, touch, (leg + 1, distance(start, touch)?));
+            if walk.fill.is_some() {
+                return Ok(walk);
+            }
+            if outcome.trigger.is_none() {
+                break;
+            }
+            from = touch;
+        }
+    }
+    Ok(walk)
+}
+
+/// Fill model matching orders inside bars
+///
+/// Against trade bars and quote bars, the latter by the side of the spread
+/// an order trades on, orders follow the bar's inferred path: market orders
+/// fill at the open, limit and if-touched orders at their level when it is
+/// touched, stops at their stop, and any of them at the open when the bar
+/// gaps through. Ticks, and books with `use_book`, price orders as
+/// `InstantFillModel` does, whose waterfall picks what each order is
+/// matched against.
+///
+/// Used by the simulated broker, fills inside one bar are applied in the
+/// order the path reaches them. When a bar reaches both a stop and a target
+/// of the same strategy on the same side, only the first reached fills;
+/// the other is left as it was before the bar, so that the strategy can
+/// cancel it.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct IntrabarFillModel {
+    path: IntrabarPath,
+    /// Level 2 books, with `use_book`
+    books: Option<Mutex<Books>>,
+}
+
+impl IntrabarFillModel {
+    pub fn path(&self) -> IntrabarPath {
+        self.path
+    }
+
+    pub fn use_book(&self) -> bool {
+        self.books.is_some()
+    }
+}
+
+#[pymethods]
+impl IntrabarFillModel {
+    #[new]
+    #[pyo3(signature = (path=IntrabarPath::Auto, use_book=false))]
+    fn py_new(path: IntrabarPath, use_book: bool) -> Self {
+        IntrabarFillModel {
+            path,
+            books: use_book.then(|| Mutex::new(Books::default())),
+        }
+    }
+
+    #[getter(path)]
+    fn py_path(&self) -> IntrabarPath {
+        self.path
+    }
+
+    /// Whether the top of the Level 2 book prices orders
+    #[getter(use_book)]
+    fn py_use_book(&self) -> bool {
+        self.use_book()
+    }
+
+    /// Apply the event's book updates to the per-instrument books, with `use_book`
+    fn on_market_event(&self, market_event: &Bound<'_, PyAny>) -> PyResult<()> {
+        match &self.books {
+            Some(books) => books.lock().unwrap().apply(market_event.py(), &EventView::new(market_event)),
+            None => Ok(()),
+        }
+    }
+
+    /// Fill price of an order against the event, or None
+    ///
+    /// Conditional orders are matched as if placed at the start of the
+    /// event; triggers carried across events need the simulated broker.
+    fn get_fill_price<'py>(
+        &self,
+        order_spec: &Bound<'py, PyAny>,
+        market_event: &Bound<'py, PyAny>,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let py = order_spec.py();
+        let mut order = Order::from_spec(String::new(), String::new(), order_spec)?;
+        let instrument = order.instrument.bind(py).clone();
+        let Some(id) = default_registry(py)?.get().lookup_instrument(&instrument)? else {
+            return Ok(None);
+        };
+        let view = EventView::new(market_event);
+        let books = self.books.as_ref().map(|books| books.lock().unwrap());
+        let snapshot = view.snapshot(id, &instrument, books.as_deref())?;
+        let fill = match snapshot.quote(order.side) {
+            None => None,
+            Some(Quote::Price(price)) => order.match_price(price)?.fill,
+            Some(Quote::Bar(bar)) => walk(&mut order, self.path.points(bar))?.fill.map(|(price, _)| price),
+        };
+        fill.map(|price| to_decimal(py, price)).transpose()
+    }
+
+    fn __repr__(&self) -> String {
+        let path = match self.path {
+            IntrabarPath::Auto => "AUTO",
+            IntrabarPath::OpenHighLowClose => "OPEN_HIGH_LOW_CLOSE",
+            IntrabarPath::OpenLowHighClose => "OPEN_LOW_HIGH_CLOSE",
+        };
+        let book = if self.use_book() { ", use_book=True" } else { "" };
+        format!("IntrabarFillModel(path=IntrabarPath.{path}{book})")
+    }
+}
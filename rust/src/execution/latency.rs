@@ -0,0 +1,87 @@
+//! Orders in flight between a strategy and the exchange
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::execution::order::Order;
+
+/// An order and the time it reaches the exchange
+struct InFlight {
+    release: i64,
+    /// Submission order, so orders released together keep it
+    sequence: u64,
+    order: Order,
+}
+
+impl InFlight {
+    fn key(&self) -> (i64, u64) {
+        (self.release, self.sequence)
+    }
+}
+
+impl PartialEq for InFlight {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for InFlight {}
+
+impl PartialOrd for InFlight {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for InFlight {
+    /// Reversed, so the max-heap pops the earliest release first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key().cmp(&self.key())
+    }
+}
+
+/// Priority queue of orders by release time, in epoch nanoseconds
+#[derive(Default)]
+pub struct LatencyQueue {
+    heap: BinaryHeap<InFlight>,
+    sequence: u64,
+}
+
+impl LatencyQueue {
+    pub fn push(&mut self, release: i64, order: Order) {
+        self.sequence += 1;
+        self.heap.push(InFlight {
+            release,
+            sequence: self.sequence,
+            order,
+        });
+    }
+
+    /// Orders released at or before `now`, earliest first
+    pub fn release(&mut self, now: i64) -> Vec<Order> {
+        let mut released = Vec::new();
+        while self.heap.peek().is_some_and(|top| top.release <= now) {
+            released.extend(self.heap.pop().map(|in_flight| in_flight.order));
+        }
+        released
+    }
+
+    /// Take out the orders matching `remove`, earliest first
+    pub fn remove_where(&mut self, remove: impl Fn(&Order) -> bool) -> Vec<Order> {
+        let (mut removed, kept): (Vec<InFlight>, Vec<InFlight>) = std::mem::take(&mut self.heap)
+            .into_vec()
+            .into_iter()
+            .partition(|in_flight| remove(&in_flight.order));
+        self.heap = kept.into();
+        removed.sort_by_key(InFlight::key);
+        removed.into_iter().map(|in_flight| in_flight.order).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+}
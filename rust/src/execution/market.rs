@@ -0,0 +1,358 @@
+//! Market prices orders are matched against
+//!
+//! Each instrument's price for a side is chosen by the waterfall of
+//! `InstantFillModel`: Book Update > Quote Tick > Quote Bar > Trade Tick >
+//! Trade Bar. With `use_book`, book updates are applied to a Level 2 book
+//! per instrument, whose top of book prices orders when the event updated
+//! it. The sizes and volumes of the same records bound partial fills.
+
+use std::collections::HashMap;
+
+use pyo3::intern;
+use pyo3::prelude::*;
+
+use crate::book::l2::{Book, Continuity};
+use crate::book::BookUpdate;
+use crate::events::market_event::MarketEvent;
+use crate::execution::order::Side;
+use crate::interop::logger;
+use crate::types::fixed::Fixed;
+use crate::types::instrument::{default_registry, InstrumentId};
+use crate::types::price::extract_fixed;
+
+const BOOK_LOGGER: &str = "simulor.data.order_book";
+
+/// Open, high, low and close of a bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ohlc {
+    pub open: Fixed,
+    pub high: Fixed,
+    pub low: Fixed,
+    pub close: Fixed,
+}
+
+/// What an order on one side is matched against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quote {
+    Price(Fixed),
+    /// A bar, for its side of the spread in the case of quote bars
+    Bar(Ohlc),
+}
+
+impl Quote {
+    /// The last price, which instant fills use
+    pub fn last(self) -> Fixed {
+        match self {
+            Quote::Price(price) => price,
+            Quote::Bar(bar) => bar.close,
+        }
+    }
+}
+
+/// Quantity available to an order at the price it is matched against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Liquidity {
+    /// Displayed on the order's side of the book or of a quote
+    Displayed(Fixed),
+    /// Traded by a tick or a bar, on both sides
+    Traded(Fixed),
+}
+
+/// The ask for buys, the bid for sells
+fn pick<T>(side: Side, (bid, ask): (T, T)) -> T {
+    match side {
+        Side::Buy => ask,
+        Side::Sell => bid,
+    }
+}
+
+/// Prices available for one instrument in one event
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Snapshot {
+    /// Best bid and ask of the book, when the event updated it
+    pub book: Option<(Fixed, Fixed)>,
+    /// Sizes at the best bid and ask of the book
+    pub book_size: Option<(Fixed, Fixed)>,
+    /// Bid and ask of the last quote tick
+    pub quote_tick: Option<(Fixed, Fixed)>,
+    /// Bid and ask sizes of the last quote tick
+    pub quote_size: Option<(Fixed, Fixed)>,
+    /// Bid and ask side of the finest quote bar
+    pub quote_bar: Option<(Ohlc, Ohlc)>,
+    /// Price of the last trade tick
+    pub trade_tick: Option<Fixed>,
+    /// Size of the last trade tick
+    pub trade_size: Option<Fixed>,
+    /// The finest trade bar
+    pub trade_bar: Option<Ohlc>,
+    /// Volume of the finest trade bar
+    pub volume: Option<Fixed>,
+}
+
+impl Snapshot {
+    /// What an order on `side` is matched against: the ask for buys, the
+    /// bid for sells, or the last trade without quotes
+    pub fn quote(&self, side: Side) -> Option<Quote> {
+        self.source(side).map(|(quote, _)| quote)
+    }
+
+    /// Quantity available to an order on `side`, from the record that
+    /// `quote` picks, a quote bar taking the volume of a trade bar beside
+    /// it; `None` when there is no size or volume
+    pub fn liquidity(&self, side: Side) -> Option<Liquidity> {
+        self.source(side).and_then(|(_, liquidity)| liquidity)
+    }
+
+    /// What an order on `side` is matched against and the quantity there
+    fn source(&self, side: Side) -> Option<(Quote, Option<Liquidity>)> {
+        let displayed = |sizes: Option<(Fixed, Fixed)>| sizes.map(|sizes| Liquidity::Displayed(pick(side, sizes)));
+        if let Some(top) = self.book {
+            return Some((Quote::Price(pick(side, top)), displayed(self.book_size)));
+        }
+        let positive = |price: Fixed| price.is_positive().then_some(price);
+        let closed = |bar: Ohlc| bar.close.is_positive().then_some(Quote::Bar(bar));
+        self.quote_tick
+            .and_then(|quote| positive(pick(side, quote)))
+            .map(|price| (Quote::Price(price), displayed(self.quote_size)))
+            .or_else(|| {
+                let quote = self.quote_bar.and_then(|bar| closed(pick(side, bar)))?;
+                Some((quote, self.volume.map(Liquidity::Traded)))
+            })
+            .or_else(|| {
+                let price = self.trade_tick.and_then(positive)?;
+                Some((Quote::Price(price), self.trade_size.map(Liquidity::Traded)))
+            })
+            .or_else(|| {
+                let quote = self.trade_bar.and_then(closed)?;
+                Some((quote, self.volume.map(Liquidity::Traded)))
+            })
+    }
+
+    /// Price an order on `side` trades at, the close of a bar
+    pub fn price(&self, side: Side) -> Option<Fixed> {
+        self.quote(side).map(Quote::last)
+    }
+}
+
+/// A market event, native or from the Python implementation
+pub enum EventView<'py> {
+    Native(PyRef<'py, MarketEvent>),
+    Python(Bound<'py, PyAny>),
+}
+
+impl<'py> EventView<'py> {
+    pub fn new(event: &Bound<'py, PyAny>) -> Self {
+        match event.cast::<MarketEvent>() {
+            Ok(native) => EventView::Native(native.borrow()),
+            Err(_) => EventView::Python(event.clone()),
+        }
+    }
+
+    /// IDs of the instruments with data, in ascending order
+    pub fn instrument_ids(&self, py: Python<'py>) -> PyResult<Vec<InstrumentId>> {
+        match self {
+            EventView::Native(event) => Ok(event.instrument_ids()),
+            EventView::Python(event) => {
+                let registry = default_registry(py)?;
+                let mut ids = Vec::new();
+                for instrument in event.call_method0(intern!(py, "instruments"))?.try_iter()? {
+                    ids.push(registry.get().intern_instrument(&instrument?)?);
+                }
+                ids.sort_unstable();
+                Ok(ids)
+            }
+        }
+    }
+
+    /// Book updates of the event, grouped by instrument in arrival order
+    fn book_updates(&self, py: Python<'py>) -> PyResult<Vec<(InstrumentId, Vec<Bound<'py, BookUpdate>>)>> {
+        match self {
+            EventView::Native(event) => Ok(event
+                .instrument_ids()
+                .into_iter()
+                .map(|id| {
+                    let updates = event.book_updates_for(id).iter().map(|e| e.record.bind(py).clone());
+                    (id, updates.collect::<Vec<_>>())
+                })
+                .filter(|(_, updates)| !updates.is_empty())
+                .collect()),
+            EventView::Python(event) => {
+                let registry = default_registry(py)?;
+                let mut grouped = Vec::new();
+                for item in event.getattr(intern!(py, "book_updates"))?.call_method0("items")?.try_iter()? {
+                    let (instrument, updates): (Bound<'py, PyAny>, Bound<'py, PyAny>) = item?.extract()?;
+                    let mut records = Vec::new();
+                    for update in updates.try_iter()? {
+                        records.push(update?.cast_into::<BookUpdate>()?);
+                    }
+                    grouped.push((registry.get().intern_instrument(&instrument)?, records));
+                }
+                Ok(grouped)
+            }
+        }
+    }
+
+    /// Prices of instrument `id`, with `books` as updated by this event;
+    /// without books, book updates are ignored
+    pub fn snapshot(
+        &self,
+        id: InstrumentId,
+        instrument: &Bound<'py, PyAny>,
+        books: Option<&Books>,
+    ) -> PyResult<Snapshot> {
+        match self {
+            EventView::Native(event) => {
+                let top = match books {
+                    Some(books) if !event.book_updates_for(id).is_empty() => books.top(id),
+                    _ => None,
+                };
+                let quote_tick = event.quote_ticks_for(id).last().map(|e| e.record.get());
+                let trade_tick = event.trade_ticks_for(id).last().map(|e| e.record.get());
+                let trade_bar = event.trade_bars_for(id).first().map(|e| e.record.get());
+                Ok(Snapshot {
+                    book: top.map(|((bid, _), (ask, _))| (bid, ask)),
+                    book_size: top.map(|((_, bid_size), (_, ask_size))| (bid_size, ask_size)),
+                    quote_tick: quote_tick.map(|quote| (quote.bid_price, quote.ask_price)),
+                    quote_size: quote_tick.map(|quote| (quote.bid_size, quote.ask_size)),
+                    quote_bar: event.quote_bars_for(id).first().map(|e| {
+                        let bar = e.record.get();
+                        let bid = Ohlc {
+                            open: bar.bid_open,
+                            high: bar.bid_high,
+                            low: bar.bid_low,
+                            close: bar.bid_close,
+                        };
+                        let ask = Ohlc {
+                            open: bar.ask_open,
+                            high: bar.ask_high,
+                            low: bar.ask_low,
+                            close: bar.ask_close,
+                        };
+                        (bid, ask)
+                    }),
+                    trade_tick: trade_tick.map(|trade| trade.price),
+                    trade_size: trade_tick.map(|trade| trade.size),
+                    trade_bar: trade_bar.map(|bar| Ohlc {
+                        open: bar.open,
+                        high: bar.high,
+                        low: bar.low,
+                        close: bar.close,
+                    }),
+                    volume: trade_bar.map(|bar| bar.volume),
+                })
+            }
+            EventView::Python(event) => {
+                let record = |method: &str| event.call_method1(method, (instrument,));
+                let top = match books {
+                    Some(books) if record("get_book_updates")?.is_truthy()? => books.top(id),
+                    _ => None,
+                };
+                let (quote_tick, trade_tick) = (record("get_last_quote_tick")?, record("get_last_trade_tick")?);
+                let trade_bar = record("get_min_res_trade_bar")?;
+                Ok(Snapshot {
+                    book: top.map(|((bid, _), (ask, _))| (bid, ask)),
+                    book_size: top.map(|((_, bid_size), (_, ask_size))| (bid_size, ask_size)),
+                    quote_tick: quote(&quote_tick, "bid_price", "ask_price")?,
+                    quote_size: quote(&quote_tick, "bid_size", "ask_size")?,
+                    quote_bar: quote_bar(&record("get_min_res_quote_bar")?)?,
+                    trade_tick: price(&trade_tick, "price")?,
+                    trade_size: price(&trade_tick, "size")?,
+                    trade_bar: bar(&trade_bar, "")?,
+                    volume: price(&trade_bar, "volume")?,
+                })
+            }
+        }
+    }
+}
+
+/// A price or size field of a record, absent with the record or when unset
+fn price(record: &Bound<'_, PyAny>, name: &str) -> PyResult<Option<Fixed>> {
+    if record.is_none() {
+        return Ok(None);
+    }
+    let value = record.getattr(name)?;
+    if value.is_none() {
+        return Ok(None);
+    }
+    extract_fixed(&value).map(Some)
+}
+
+/// Bid and ask fields of a record, prices or sizes, unset ones read as zero
+fn quote(record: &Bound<'_, PyAny>, bid: &str, ask: &str) -> PyResult<Option<(Fixed, Fixed)>> {
+    if record.is_none() {
+        return Ok(None);
+    }
+    Ok(Some((
+        price(record, bid)?.unwrap_or(Fixed::ZERO),
+        price(record, ask)?.unwrap_or(Fixed::ZERO),
+    )))
+}
+
+/// Bar fields named `{prefix}open` to `{prefix}close`, absent with the
+/// record or without a close
+fn bar(record: &Bound<'_, PyAny>, prefix: &str) -> PyResult<Option<Ohlc>> {
+    let Some(close) = price(record, &format!("{prefix}close"))? else {
+        return Ok(None);
+    };
+    let field = |name: &str| -> PyResult<Fixed> { Ok(price(record, &format!("{prefix}{name}"))?.unwrap_or(close)) };
+    Ok(Some(Ohlc {
+        open: field("open")?,
+        high: field("high")?,
+        low: field("low")?,
+        close,
+    }))
+}
+
+/// Bid and ask side of a quote bar, a missing side reading as zero
+fn quote_bar(record: &Bound<'_, PyAny>) -> PyResult<Option<(Ohlc, Ohlc)>> {
+    if record.is_none() {
+        return Ok(None);
+    }
+    let zero = Ohlc {
+        open: Fixed::ZERO,
+        high: Fixed::ZERO,
+        low: Fixed::ZERO,
+        close: Fixed::ZERO,
+    };
+    Ok(Some((bar(record, "bid_")?.unwrap_or(zero), bar(record, "ask_")?.unwrap_or(zero))))
+}
+
+/// Level 2 books of the instruments with book updates
+#[derive(Default)]
+pub struct Books {
+    books: HashMap<InstrumentId, Book>,
+}
+
+impl Books {
+    /// Apply the book updates of `event`
+    ///
+    /// Sequence gaps are logged as `OrderBookL2` does, leaving the book out
+    /// of sync until the next snapshot.
+    pub fn apply(&mut self, py: Python<'_>, event: &EventView<'_>) -> PyResult<()> {
+        for (id, updates) in event.book_updates(py)? {
+            let book = self.books.entry(id).or_default();
+            for update in updates {
+                let base = update.as_super().get();
+                if let Continuity::Gap { expected } = book.continuity(update.get()) {
+                    logger(py, BOOK_LOGGER)?.call_method1(
+                        "warning",
+                        (
+                            "Sequence gap in %s book: expected %d, got %d; out of sync until the next snapshot",
+                            base.instrument.bind(py).getattr("symbol")?,
+                            expected,
+                            update.get().sequence.unwrap_or_default(),
+                        ),
+                    )?;
+                }
+                book.apply(update.get(), base.timestamp.clone_ref(py), base.timestamp_nanos(py)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Best bid and ask of `id` as price and size, if its book has both
+    /// and they do not cross
+    fn top(&self, id: InstrumentId) -> Option<((Fixed, Fixed), (Fixed, Fixed))> {
+        self.books.get(&id)?.top()
+    }
+}
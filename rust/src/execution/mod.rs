@@ -0,0 +1,32 @@
+//! Order matching for the simulated broker
+//!
+//! `MatchingEngine` holds the orders in flight to the simulated exchange
+//! and those resting in its book, and matches them against each market
+//! event, leaving cash and positions to the broker.
+
+pub mod engine;
+pub mod intrabar;
+pub mod latency;
+pub mod market;
+pub mod order;
+pub mod partial;
+pub mod trailing;
+
+use pyo3::prelude::*;
+
+pub use engine::{MatchingEngine, OrderFill, OrderTrigger};
+pub use intrabar::{IntrabarFillModel, IntrabarPath};
+pub use partial::PartialFillModel;
+pub use trailing::TrailingStopTracker;
+
+/// Register the order matching classes on the extension module
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<MatchingEngine>()?;
+    m.add_class::<OrderFill>()?;
+    m.add_class::<OrderTrigger>()?;
+    m.add_class::<TrailingStopTracker>()?;
+    m.add_class::<IntrabarFillModel>()?;
+    m.add_class::<IntrabarPath>()?;
+    m.add_class::<PartialFillModel>()?;
+    Ok(())
+}
@@ -0,0 +1,344 @@
+//! Native form of an `OrderSpec` resting in the matching engine
+
+use pyo3::exceptions::PyValueError;
+use pyo3::intern;
+use pyo3::prelude::*;
+
+use crate::execution::trailing::{State as TrailState, TrailingStopTracker};
+use crate::interop::order_side_type;
+use crate::types::fixed::Fixed;
+use crate::types::instrument::{default_registry, InstrumentId};
+use crate::types::price::extract_fixed;
+
+/// `OrderSide`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    /// From an `OrderSide`, or its value
+    pub fn from_py(side: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let side = order_side_type(side.py())?.call1((side,))?;
+        Ok(match enum_value(&side)?.as_str() {
+            "buy" => Side::Buy,
+            _ => Side::Sell,
+        })
+    }
+
+    pub fn to_py<'py>(self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        order_side_type(py)?.getattr(match self {
+            Side::Buy => "BUY",
+            Side::Sell => "SELL",
+        })
+    }
+}
+
+/// `OrderType`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderKind {
+    Market,
+    Limit,
+    Stop,
+    StopLimit,
+    MarketIfTouched,
+    LimitIfTouched,
+    TrailingStop,
+    TrailingStopLimit,
+}
+
+impl OrderKind {
+    /// Whether the order carries a trailing stop across events
+    pub fn is_trailing(self) -> bool {
+        matches!(self, OrderKind::TrailingStop | OrderKind::TrailingStopLimit)
+    }
+
+    /// Whether the order carries a trigger across events, until it works
+    /// as a market or limit order
+    pub fn is_conditional(self) -> bool {
+        self.is_trailing() || matches!(self, OrderKind::MarketIfTouched | OrderKind::LimitIfTouched)
+    }
+
+    fn from_value(value: &str) -> PyResult<Self> {
+        Ok(match value {
+            "market" => OrderKind::Market,
+            "limit" => OrderKind::Limit,
+            "stop" => OrderKind::Stop,
+            "stop_limit" => OrderKind::StopLimit,
+            "mit" => OrderKind::MarketIfTouched,
+            "lit" => OrderKind::LimitIfTouched,
+            "trailing_stop" => OrderKind::TrailingStop,
+            "trailing_stop_limit" => OrderKind::TrailingStopLimit,
+            other => return Err(PyValueError::new_err(format!("Unknown order type: {other}"))),
+        })
+    }
+}
+
+fn enum_value(obj: &Bound<'_, PyAny>) -> PyResult<String> {
+    obj.getattr(intern!(obj.py(), "value"))?.extract()
+}
+
+fn optional_fixed(spec: &Bound<'_, PyAny>, name: &str) -> PyResult<Option<Fixed>> {
+    let value = spec.getattr(name)?;
+    if value.is_none() {
+        return Ok(None);
+    }
+    extract_fixed(&value).map(Some)
+}
+
+/// What one market price did to an order
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Outcome {
+    /// Level of the trigger that fired at this price
+    pub trigger: Option<Fixed>,
+    /// Price the order fills at
+    pub fill: Option<Fixed>,
+}
+
+/// Range of prices, either end open, at which an order acts next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Band {
+    pub low: Option<Fixed>,
+    pub high: Option<Fixed>,
+}
+
+impl Band {
+    const ANY: Band = Band { low: None, high: None };
+
+    fn at_most(price: Fixed) -> Self {
+        Band {
+            low: None,
+            high: Some(price),
+        }
+    }
+
+    fn at_least(price: Fixed) -> Self {
+        Band {
+            low: Some(price),
+            high: None,
+        }
+    }
+
+    pub fn contains(&self, price: Fixed) -> bool {
+        self.low.map_or(true, |low| price >= low) && self.high.map_or(true, |high| price <= high)
+    }
+}
+
+/// Whether an order protects against an adverse move or takes a favourable one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Stop,
+    Target,
+}
+
+/// Matching state of an order, to undo a match
+#[derive(Debug, Clone, Copy)]
+pub struct Saved {
+    triggered: bool,
+    limit_price: Option<Fixed>,
+    trail: Option<TrailState>,
+}
+
+/// An order and who placed it
+pub struct Order {
+    pub id: String,
+    pub strategy: String,
+    /// The `OrderSpec` as submitted
+    pub spec: Py<PyAny>,
+    pub instrument: Py<PyAny>,
+    pub instrument_id: InstrumentId,
+    pub side: Side,
+    pub kind: OrderKind,
+    pub quantity: Fixed,
+    pub limit_price: Option<Fixed>,
+    pub stop_price: Option<Fixed>,
+    /// Expires at the session close
+    pub day: bool,
+    /// Stop of a trailing order
+    pub trail: Option<Py<TrailingStopTracker>>,
+    /// Whether the trigger of a trailing or if-touched order has fired,
+    /// making it a market or limit order
+    pub triggered: bool,
+    /// Quantity filled so far
+    pub filled: Fixed,
+    /// Price times quantity, summed over the fills so far
+    pub notional: Fixed,
+}
+
+impl Order {
+    pub fn from_spec(id: String, strategy: String, spec: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let py = spec.py();
+        let instrument = spec.getattr(intern!(py, "instrument"))?;
+        let side = Side::from_py(&spec.getattr(intern!(py, "side"))?)?;
+        let kind = OrderKind::from_value(&enum_value(&spec.getattr(intern!(py, "order_type"))?)?)?;
+        let stop_price = optional_fixed(spec, "stop_price")?;
+        let trail = if kind.is_trailing() {
+            Some(Py::new(py, TrailingStopTracker::from_spec(spec, side, stop_price)?)?)
+        } else {
+            None
+        };
+        Ok(Order {
+            id,
+            strategy,
+            spec: spec.clone().unbind(),
+            instrument_id: default_registry(py)?.get().intern_instrument(&instrument)?,
+            instrument: instrument.unbind(),
+            side,
+            kind,
+            quantity: extract_fixed(&spec.getattr(intern!(py, "quantity"))?)?,
+            limit_price: optional_fixed(spec, "limit_price")?,
+            stop_price,
+            day: enum_value(&spec.getattr(intern!(py, "time_in_force"))?)? == "day",
+            trail,
+            triggered: false,
+            filled: Fixed::ZERO,
+            notional: Fixed::ZERO,
+        })
+    }
+
+    /// Quantity still to fill
+    pub fn remaining(&self) -> PyResult<Fixed> {
+        Ok(self.quantity.checked_sub(self.filled)?)
+    }
+
+    /// Match the order against the market trading at `market` for its side
+    ///
+    /// Conditional orders trigger first: a trailing order once the market
+    /// comes back through its stop, which is ratcheted otherwise; a buy
+    /// if-touched order once the market falls to its `stop_price`, a sell
+    /// one once it rises to it. A triggered trailing stop or
+    /// market-if-touched order fills at the market; a trailing stop-limit
+    /// or limit-if-touched order rests as a limit order at its
+    /// `limit_price`, a trailing stop-limit without one at its stop level.
+    pub fn match_price(&mut self, market: Fixed) -> PyResult<Outcome> {
+        let mut outcome = Outcome::default();
+        if !self.triggered {
+            let trigger = match (self.kind, &self.trail) {
+                (_, Some(trail)) => {
+                    let trail = trail.get();
+                    trail.observe(market)?.then(|| trail.stop()).flatten()
+                }
+                (OrderKind::MarketIfTouched | OrderKind::LimitIfTouched, None) => {
+                    self.stop_price.filter(|&stop| match self.side {
+                        Side::Buy => market <= stop,
+                        Side::Sell => market >= stop,
+                    })
+                }
+                _ => None,
+            };
+            if let Some(level) = trigger {
+                self.triggered = true;
+                if self.limit_price.is_none() && self.kind == OrderKind::TrailingStopLimit {
+                    self.limit_price = Some(level);
+                }
+            }
+            outcome.trigger = trigger;
+        }
+        outcome.fill = self.fill_price(market);
+        Ok(outcome)
+    }
+
+    /// Prices at which the order triggers or fills next; `None` if it never can
+    pub fn band(&self) -> Option<Band> {
+        let limit = |limit: Option<Fixed>| {
+            limit.map(|limit| match self.side {
+                Side::Buy => Band::at_most(limit),
+                Side::Sell => Band::at_least(limit),
+            })
+        };
+        let stop = |stop: Option<Fixed>| {
+            stop.map(|stop| match self.side {
+                Side::Buy => Band::at_least(stop),
+                Side::Sell => Band::at_most(stop),
+            })
+        };
+        match (self.kind, self.triggered) {
+            (OrderKind::Market, _) | (OrderKind::TrailingStop | OrderKind::MarketIfTouched, true) => Some(Band::ANY),
+            (OrderKind::Limit, _) | (OrderKind::TrailingStopLimit | OrderKind::LimitIfTouched, true) => {
+                limit(self.limit_price)
+            }
+            // If-touched orders trigger where a limit order would fill
+            (OrderKind::MarketIfTouched | OrderKind::LimitIfTouched, false) => limit(self.stop_price),
+            (OrderKind::Stop, _) => stop(self.stop_price),
+            (OrderKind::StopLimit, _) => {
+                let (stop, limit) = (stop(self.stop_price)?, limit(self.limit_price)?);
+                let band = Band {
+                    low: stop.low.or(limit.low),
+                    high: stop.high.or(limit.high),
+                };
+                match (band.low, band.high) {
+                    (Some(low), Some(high)) if low > high => None,
+                    _ => Some(band),
+                }
+            }
+            (OrderKind::TrailingStop | OrderKind::TrailingStopLimit, false) => {
+                stop(self.trail.as_ref().and_then(|trail| trail.get().stop()))
+            }
+        }
+    }
+
+    /// Let the market move to `market` without reaching the order's band,
+    /// ratcheting the stop of a trailing order
+    pub fn drift(&self, market: Fixed) -> PyResult<()> {
+        if let (Some(trail), false) = (&self.trail, self.triggered) {
+            trail.get().observe(market)?;
+        }
+        Ok(())
+    }
+
+    pub fn role(&self) -> Option<Role> {
+        match self.kind {
+            OrderKind::Market => None,
+            OrderKind::Stop | OrderKind::StopLimit | OrderKind::TrailingStop | OrderKind::TrailingStopLimit => {
+                Some(Role::Stop)
+            }
+            OrderKind::Limit | OrderKind::MarketIfTouched | OrderKind::LimitIfTouched => Some(Role::Target),
+        }
+    }
+
+    pub fn save(&self) -> Saved {
+        Saved {
+            triggered: self.triggered,
+            limit_price: self.limit_price,
+            trail: self.trail.as_ref().map(|trail| trail.get().state()),
+        }
+    }
+
+    pub fn restore(&mut self, saved: Saved) {
+        self.triggered = saved.triggered;
+        self.limit_price = saved.limit_price;
+        if let (Some(trail), Some(state)) = (&self.trail, saved.trail) {
+            trail.get().restore(state);
+        }
+    }
+
+    /// Price the order fills at when the market trades at `market` for its
+    /// side, as `InstantFillModel.get_fill_price` decides, conditional
+    /// orders once triggered
+    fn fill_price(&self, market: Fixed) -> Option<Fixed> {
+        let fills = match (self.kind, self.side) {
+            (OrderKind::Market, _) => true,
+            (OrderKind::Limit, Side::Buy) => self.limit_price.is_some_and(|limit| market <= limit),
+            (OrderKind::Limit, Side::Sell) => self.limit_price.is_some_and(|limit| market >= limit),
+            (OrderKind::Stop, Side::Buy) => self.stop_price.is_some_and(|stop| market >= stop),
+            (OrderKind::Stop, Side::Sell) => self.stop_price.is_some_and(|stop| market <= stop),
+            (OrderKind::StopLimit, Side::Buy) => match (self.stop_price, self.limit_price) {
+                (Some(stop), Some(limit)) => market >= stop && market <= limit,
+                _ => false,
+            },
+            (OrderKind::StopLimit, Side::Sell) => match (self.stop_price, self.limit_price) {
+                (Some(stop), Some(limit)) => market <= stop && market >= limit,
+                _ => false,
+            },
+            (OrderKind::TrailingStop | OrderKind::MarketIfTouched, _) => self.triggered,
+            (OrderKind::TrailingStopLimit | OrderKind::LimitIfTouched, Side::Buy) => {
+                self.triggered && self.limit_price.is_some_and(|limit| market <= limit)
+            }
+            (OrderKind::TrailingStopLimit | OrderKind::LimitIfTouched, Side::Sell) => {
+                self.triggered && self.limit_price.is_some_and(|limit| market >= limit)
+            }
+        };
+        fills.then_some(market)
+    }
+}
@@ -0,0 +1,207 @@
+//! Fills limited by the quantity the market shows
+//!
+//! An order only takes a fraction of what trades or is displayed where it
+//! is matched: of a trade bar's volume or a trade tick's size, or of the
+//! size on its side of the book or of a quote tick. Orders sharing one
+//! event take from the same quantity in arrival order; what is left of
+//! each rests for later events.
+
+use std::sync::Mutex;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::execution::market::{Books, EventView, Liquidity, Snapshot};
+use crate::execution::order::{Order, Side};
+use crate::types::fixed::{Fixed, RoundingMode};
+use crate::types::instrument::default_registry;
+use crate::types::price::{extract_fixed, to_decimal};
+
+/// Quantity taken by the orders filled against one event's prices
+pub struct Participation {
+    rate: Fixed,
+    /// Available to buys and to sells, from the event's snapshot
+    liquidity: (Option<Liquidity>, Option<Liquidity>),
+    /// Taken from the displayed sizes, bid then ask
+    displayed: (Fixed, Fixed),
+    /// Taken from the traded volume, by both sides
+    traded: Fixed,
+}
+
+impl Participation {
+    pub fn new(rate: Fixed, snapshot: &Snapshot) -> Self {
+        Participation {
+            rate,
+            liquidity: (snapshot.liquidity(Side::Buy), snapshot.liquidity(Side::Sell)),
+            displayed: (Fixed::ZERO, Fixed::ZERO),
+            traded: Fixed::ZERO,
+        }
+    }
+
+    /// Quantity of `order` that fills against what is left, without taking it
+    ///
+    /// Capped at what is left and rounded down to the precision of the
+    /// order's quantity; all of what remains without a known quantity.
+    pub fn share(&self, order: &Order) -> PyResult<Fixed> {
+        let remaining = order.remaining()?;
+        let Some((available, taken)) = self.pool(order.side) else {
+            return Ok(remaining);
+        };
+        let left = self.rate.checked_mul(available)?.checked_sub(taken)?;
+        if !left.is_positive() {
+            return Ok(Fixed::ZERO);
+        }
+        Ok(left.min(remaining).rescale(order.quantity.scale(), RoundingMode::Down)?)
+    }
+
+    /// Take `quantity`, once an order on `side` has filled it
+    pub fn take(&mut self, side: Side, quantity: Fixed) -> PyResult<()> {
+        let taken = match self.liquidity(side) {
+            None => return Ok(()),
+            Some(Liquidity::Displayed(_)) => match side {
+                Side::Buy => &mut self.displayed.1,
+                Side::Sell => &mut self.displayed.0,
+            },
+            Some(Liquidity::Traded(_)) => &mut self.traded,
+        };
+        *taken = taken.checked_add(quantity)?;
+        Ok(())
+    }
+
+    fn liquidity(&self, side: Side) -> Option<Liquidity> {
+        match side {
+            Side::Buy => self.liquidity.0,
+            Side::Sell => self.liquidity.1,
+        }
+    }
+
+    /// Quantity available to `side` and how much of it is taken
+    fn pool(&self, side: Side) -> Option<(Fixed, Fixed)> {
+        Some(match self.liquidity(side)? {
+            Liquidity::Displayed(size) => match side {
+                Side::Buy => (size, self.displayed.1),
+                Side::Sell => (size, self.displayed.0),
+            },
+            Liquidity::Traded(volume) => (volume, self.traded),
+        })
+    }
+}
+
+/// Fill model capping each fill at a share of the market's quantity
+///
+/// Orders are priced as `InstantFillModel` prices them, and fill at most
+/// `participation` of the volume or size of the record priced against:
+/// the volume of a trade bar, the size of a trade tick, or the size on the
+/// order's side of a quote tick or, with `use_book`, of the book. Quote
+/// bars carry no sizes: the volume of a trade bar in the same event caps
+/// them, and without one they fill in full. Fills round down to the
+/// precision of the order's quantity, so they never exceed the share.
+///
+/// Used by the simulated broker, the unfilled remainder of an order rests
+/// for later events, each fill reporting the quantity filled so far and
+/// its average price.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct PartialFillModel {
+    participation: Fixed,
+    /// Level 2 books, with `use_book`
+    books: Option<Mutex<Books>>,
+}
+
+impl PartialFillModel {
+    pub fn participation(&self) -> Fixed {
+        self.participation
+    }
+
+    pub fn use_book(&self) -> bool {
+        self.books.is_some()
+    }
+
+    /// Price and quantity of an order matched against the event alone
+    fn match_event(
+        &self,
+        order_spec: &Bound<'_, PyAny>,
+        market_event: &Bound<'_, PyAny>,
+    ) -> PyResult<Option<(Fixed, Fixed)>> {
+        let py = order_spec.py();
+        let mut order = Order::from_spec(String::new(), String::new(), order_spec)?;
+        let instrument = order.instrument.bind(py).clone();
+        let Some(id) = default_registry(py)?.get().lookup_instrument(&instrument)? else {
+            return Ok(None);
+        };
+        let view = EventView::new(market_event);
+        let books = self.books.as_ref().map(|books| books.lock().unwrap());
+        let snapshot = view.snapshot(id, &instrument, books.as_deref())?;
+        let Some(quote) = snapshot.quote(order.side) else {
+            return Ok(None);
+        };
+        let Some(price) = order.match_price(quote.last())?.fill else {
+            return Ok(None);
+        };
+        let quantity = Participation::new(self.participation, &snapshot).share(&order)?;
+        Ok(quantity.is_positive().then_some((price, quantity)))
+    }
+}
+
+#[pymethods]
+impl PartialFillModel {
+    #[new]
+    #[pyo3(signature = (participation=None, use_book=false))]
+    fn py_new(participation: Option<&Bound<'_, PyAny>>, use_book: bool) -> PyResult<Self> {
+        let participation = match participation {
+            Some(participation) => extract_fixed(participation)?,
+            None => Fixed::new(1, 1)?,
+        };
+        if !participation.is_positive() || participation > Fixed::from_int(1) {
+            return Err(PyValueError::new_err("Participation must be greater than 0 and at most 1"));
+        }
+        Ok(PartialFillModel {
+            participation,
+            books: use_book.then(|| Mutex::new(Books::default())),
+        })
+    }
+
+    /// Largest share of the market's quantity one event fills
+    #[getter(participation)]
+    fn py_participation<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.participation)
+    }
+
+    /// Whether the top of the Level 2 book prices orders and bounds fills
+    #[getter(use_book)]
+    fn py_use_book(&self) -> bool {
+        self.use_book()
+    }
+
+    /// Apply the event's book updates to the per-instrument books, with `use_book`
+    fn on_market_event(&self, market_event: &Bound<'_, PyAny>) -> PyResult<()> {
+        match &self.books {
+            Some(books) => books.lock().unwrap().apply(market_event.py(), &EventView::new(market_event)),
+            None => Ok(()),
+        }
+    }
+
+    /// Fill price of an order against the event, or None
+    fn get_fill_price<'py>(
+        &self,
+        order_spec: &Bound<'py, PyAny>,
+        market_event: &Bound<'py, PyAny>,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let fill = self.match_event(order_spec, market_event)?;
+        fill.map(|(price, _)| to_decimal(order_spec.py(), price)).transpose()
+    }
+
+    /// Quantity of an order that fills against the event, or None
+    fn get_fill_quantity<'py>(
+        &self,
+        order_spec: &Bound<'py, PyAny>,
+        market_event: &Bound<'py, PyAny>,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let fill = self.match_event(order_spec, market_event)?;
+        fill.map(|(_, quantity)| to_decimal(order_spec.py(), quantity)).transpose()
+    }
+
+    fn __repr__(&self) -> String {
+        let book = if self.use_book() { ", use_book=True" } else { "" };
+        format!("PartialFillModel(participation={}{book})", self.participation)
+    }
+}
@@ -0,0 +1,215 @@
+//! Trailing stops
+//!
+//! A trailing stop follows the market at a fixed distance, an amount or a
+//! percentage of the price, and only ever moves in the order's favour: up
+//! for a sell stop, down for a buy stop. It triggers once the market comes
+//! back through it.
+
+use std::sync::Mutex;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::execution::order::Side;
+use crate::types::fixed::{Fixed, RoundingMode, MAX_SCALE};
+use crate::types::price::{extract_fixed, to_decimal};
+
+/// Distance of the stop from the market
+#[derive(Debug, Clone, Copy)]
+enum Offset {
+    Amount(Fixed),
+    /// Percent of the price, `5` being 5%
+    Percent(Fixed),
+}
+
+impl Offset {
+    fn at(self, price: Fixed) -> PyResult<Fixed> {
+        Ok(match self {
+            Offset::Amount(amount) => amount,
+            Offset::Percent(percent) => {
+                let product = price.checked_mul(percent)?;
+                product
+                    .checked_div(Fixed::from_int(100), (product.scale() + 2).min(MAX_SCALE), RoundingMode::HalfEven)?
+                    .normalize()
+            }
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct State {
+    stop: Option<Fixed>,
+    triggered: bool,
+}
+
+/// Stop level of a trailing order, ratcheted on each price update
+///
+/// Without a `stop_price` the stop is placed at the first price seen. A
+/// triggered stop stays where it fired.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct TrailingStopTracker {
+    side: Side,
+    offset: Offset,
+    state: Mutex<State>,
+}
+
+impl TrailingStopTracker {
+    pub fn new(side: Side, amount: Option<Fixed>, percent: Option<Fixed>, stop: Option<Fixed>) -> PyResult<Self> {
+        let offset = match (amount, percent) {
+            (Some(amount), None) if !amount.is_negative() => Offset::Amount(amount),
+            (None, Some(percent)) if !percent.is_negative() => Offset::Percent(percent),
+            (Some(_), Some(_)) => {
+                return Err(PyValueError::new_err("Cannot specify both trailing_amount and trailing_percent"))
+            }
+            (None, None) => {
+                return Err(PyValueError::new_err("Trailing orders require trailing_amount OR trailing_percent"))
+            }
+            _ => return Err(PyValueError::new_err("Trailing distance cannot be negative")),
+        };
+        Ok(TrailingStopTracker {
+            side,
+            offset,
+            state: Mutex::new(State { stop, triggered: false }),
+        })
+    }
+
+    /// Trailing orders in `OrderSpec` form, zero amounts counting as unset
+    pub fn from_spec(spec: &Bound<'_, PyAny>, side: Side, stop: Option<Fixed>) -> PyResult<Self> {
+        let distance = |name: &str| -> PyResult<Option<Fixed>> {
+            let value = spec.getattr(name)?;
+            if value.is_none() {
+                return Ok(None);
+            }
+            Ok(Some(extract_fixed(&value)?).filter(|distance| !distance.is_zero()))
+        };
+        TrailingStopTracker::new(side, distance("trailing_amount")?, distance("trailing_percent")?, stop)
+    }
+
+    /// Observe the market price; returns whether the stop has triggered
+    pub fn observe(&self, price: Fixed) -> PyResult<bool> {
+        let mut state = self.state.lock().unwrap();
+        if state.triggered {
+            return Ok(true);
+        }
+        if let Some(stop) = state.stop {
+            let crossed = match self.side {
+                Side::Buy => price >= stop,
+                Side::Sell => price <= stop,
+            };
+            if crossed {
+                state.triggered = true;
+                return Ok(true);
+            }
+        }
+        let offset = self.offset.at(price)?;
+        let trailed = match self.side {
+            Side::Buy => price.checked_add(offset)?,
+            Side::Sell => price.checked_sub(offset)?,
+        };
+        state.stop = Some(match (state.stop, self.side) {
+            (None, _) => trailed,
+            (Some(stop), Side::Buy) => stop.min(trailed),
+            (Some(stop), Side::Sell) => stop.max(trailed),
+        });
+        Ok(false)
+    }
+
+    /// Current stop level, unset until the first price
+    pub fn stop(&self) -> Option<Fixed> {
+        self.state.lock().unwrap().stop
+    }
+
+    pub fn state(&self) -> State {
+        *self.state.lock().unwrap()
+    }
+
+    pub fn restore(&self, state: State) {
+        *self.state.lock().unwrap() = state;
+    }
+}
+
+#[pymethods]
+impl TrailingStopTracker {
+    #[new]
+    #[pyo3(signature = (side, trailing_amount=None, trailing_percent=None, stop_price=None))]
+    fn py_new(
+        side: &Bound<'_, PyAny>,
+        trailing_amount: Option<&Bound<'_, PyAny>>,
+        trailing_percent: Option<&Bound<'_, PyAny>>,
+        stop_price: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Self> {
+        TrailingStopTracker::new(
+            Side::from_py(side)?,
+            trailing_amount.map(extract_fixed).transpose()?,
+            trailing_percent.map(extract_fixed).transpose()?,
+            stop_price.map(extract_fixed).transpose()?,
+        )
+    }
+
+    /// Tracker for a trailing `OrderSpec`, starting from its `stop_price` if set
+    #[staticmethod]
+    fn for_order(order_spec: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let stop = order_spec.getattr("stop_price")?;
+        let stop = if stop.is_none() {
+            None
+        } else {
+            Some(extract_fixed(&stop)?)
+        };
+        TrailingStopTracker::from_spec(order_spec, Side::from_py(&order_spec.getattr("side")?)?, stop)
+    }
+
+    /// Ratchet the stop towards `price`, or trigger if `price` reached it;
+    /// returns whether the stop has triggered
+    fn update(&self, price: &Bound<'_, PyAny>) -> PyResult<bool> {
+        self.observe(extract_fixed(price)?)
+    }
+
+    #[getter]
+    fn side<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.side.to_py(py)
+    }
+
+    #[getter]
+    fn trailing_amount<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        match self.offset {
+            Offset::Amount(amount) => to_decimal(py, amount).map(Some),
+            Offset::Percent(_) => Ok(None),
+        }
+    }
+
+    #[getter]
+    fn trailing_percent<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        match self.offset {
+            Offset::Percent(percent) => to_decimal(py, percent).map(Some),
+            Offset::Amount(_) => Ok(None),
+        }
+    }
+
+    /// Current stop level, `None` until the first price
+    #[getter]
+    fn stop_price<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        self.stop().map(|stop| to_decimal(py, stop)).transpose()
+    }
+
+    #[getter]
+    fn triggered(&self) -> bool {
+        self.state.lock().unwrap().triggered
+    }
+
+    fn __repr__(&self) -> String {
+        let state = self.state.lock().unwrap();
+        let distance = match self.offset {
+            Offset::Amount(amount) => format!("trailing_amount={amount}"),
+            Offset::Percent(percent) => format!("trailing_percent={percent}"),
+        };
+        let stop = state.stop.map_or_else(|| "None".to_string(), |stop| stop.to_string());
+        format!(
+            "TrailingStopTracker(side={}, {distance}, stop_price={stop}, triggered={})",
+            match self.side {
+                Side::Buy => "BUY",
+                Side::Sell => "SELL",
+            },
+            if state.triggered { "True" } else { "False" },
+        )
+    }
+}
@@ -0,0 +1,607 @@
+//! Continuous contract built from the contracts of one futures root
+//!
+//! The builder follows one contract at a time, the front contract until
+//! its roll rule fires, and records each roll as it happens. Rolls are
+//! decided from data already seen: volume and open interest are compared
+//! for the previous day, so a crossover moves the series on the next
+//! day's first record. Back-adjusted views are point in time, like those
+//! of corporate actions: records are only restated for rolls made by the
+//! `as_of` time.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::NaiveDate;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyString};
+
+use crate::corporate::store::{reference_price, tidy};
+use crate::data::schema::{payload, RecordKind, MAX_WIDTH};
+use crate::data::source::DataError;
+use crate::futures::contract::{parse_code, third_friday, weekdays_before};
+use crate::futures::roll::{BackAdjustment, RollEvent, RollRule};
+use crate::interop::instrument_type;
+use crate::types::fixed::{Fixed, RoundingMode};
+use crate::types::instrument::{default_registry, InstrumentId};
+use crate::types::market_data::{MarketData, TickDirection};
+use crate::types::price::extract_fixed;
+use crate::types::time::{datetime_to_nanos, extract_date};
+
+/// Decimal places kept in roll ratios and ratio-adjusted prices
+const SCALE: u8 = 10;
+
+/// Columns of an open interest CSV file
+const DATE_COLUMN: &str = "date";
+const SYMBOL_COLUMN: &str = "symbol";
+const OPEN_INTEREST_COLUMN: &str = "open_interest";
+
+/// Open interest by contract symbol and the day reported for
+type OpenInterest = HashMap<(String, NaiveDate), Fixed>;
+
+/// Continuous records of one event, and the rolls made before them
+pub type Stitched<'py> = (Vec<Bound<'py, PyAny>>, Vec<Py<RollEvent>>);
+
+/// One record of a contract, with its decoded payload
+pub struct ContractRecord<'py> {
+    pub id: InstrumentId,
+    pub instrument: Bound<'py, PyAny>,
+    pub symbol: String,
+    pub expiry: NaiveDate,
+    pub record: Bound<'py, PyAny>,
+    pub kind: RecordKind,
+    pub values: [Fixed; MAX_WIDTH],
+    pub direction: Option<TickDirection>,
+}
+
+/// A contract seen in the stream
+struct Contract {
+    id: InstrumentId,
+    instrument: Py<PyAny>,
+    symbol: String,
+    expiry: NaiveDate,
+    last_price: Option<Fixed>,
+    /// Volume traded today, and on the previous day seen
+    volume: Fixed,
+    prior_volume: Fixed,
+}
+
+/// A roll decided but not yet recorded
+struct PendingRoll {
+    from_contract: Py<PyAny>,
+    to_contract: Py<PyAny>,
+    from_price: Fixed,
+    to_price: Fixed,
+    amount: Fixed,
+}
+
+/// A recorded roll, with the time it took effect
+struct Roll {
+    nanos: i64,
+    amount: Fixed,
+    event: Py<RollEvent>,
+}
+
+#[derive(Default)]
+struct State {
+    /// By expiry
+    contracts: Vec<Contract>,
+    /// Index of the contract followed
+    active: Option<usize>,
+    day: Option<NaiveDate>,
+    /// By time
+    rolls: Vec<Roll>,
+}
+
+/// Roll rules and back-adjusted views of one continuous futures series
+///
+/// `root` is the contract code root, such as "ES"; contracts `ESH24`,
+/// `ESM24` and so on are stitched into the continuous instrument
+/// `Instrument.future(root)`. `expiries` maps contract symbols to their
+/// expiry dates; other contracts expire on the instrument's `expiry`, or
+/// else on the third Friday of their month.
+///
+/// Whatever `rule` is chosen, the series rolls by `days_before_expiry`
+/// weekdays before the front contract expires at the latest. Open
+/// interest for `RollRule.OPEN_INTEREST` is loaded with
+/// `load_open_interest()` or `add_open_interest()`, dated the day it was
+/// reported for. Rolls are made by `ContinuousFuturesProvider` while it
+/// streams, and start afresh on every pass.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct ContinuousFuture {
+    root: String,
+    instrument: Py<PyAny>,
+    instrument_id: InstrumentId,
+    rule: RollRule,
+    days_before_expiry: u32,
+    adjustment: BackAdjustment,
+    expiries: HashMap<String, NaiveDate>,
+    open_interest: Mutex<OpenInterest>,
+    state: Mutex<State>,
+}
+
+impl ContinuousFuture {
+    pub fn instrument_id(&self) -> InstrumentId {
+        self.instrument_id
+    }
+
+    /// Expiry of `instrument`, with symbol `symbol`, if it is a contract of this root
+    pub fn expiry_of(&self, instrument: &Bound<'_, PyAny>, symbol: &str) -> PyResult<Option<NaiveDate>> {
+        if let Some(expiry) = self.expiries.get(symbol) {
+            return Ok(Some(*expiry));
+        }
+        let Some((year, month)) = parse_code(symbol, &self.root) else {
+            return Ok(None);
+        };
+        match instrument.getattr("expiry") {
+            Ok(expiry) if !expiry.is_none() => Ok(Some(extract_date(&expiry)?)),
+            _ => Ok(third_friday(year, month)),
+        }
+    }
+
+    /// Forget the contracts and rolls of a previous pass
+    pub fn reset(&self) {
+        *self.state.lock().unwrap() = State::default();
+    }
+
+    /// Whether the series should move from the contract at `from` to the
+    /// one at `to` on `date`, the first record of a new day if `new_day`
+    fn roll_due(&self, state: &State, from: usize, to: usize, date: NaiveDate, new_day: bool) -> bool {
+        let (front, next) = (&state.contracts[from], &state.contracts[to]);
+        if date >= weekdays_before(front.expiry, self.days_before_expiry) {
+            return true;
+        }
+        if !new_day {
+            return false;
+        }
+        match self.rule {
+            RollRule::Calendar => false,
+            RollRule::Volume => next.prior_volume > front.prior_volume,
+            RollRule::OpenInterest => {
+                let Some(previous) = state.day else {
+                    return false;
+                };
+                let open_interest = self.open_interest.lock().unwrap();
+                let reported = |contract: &Contract| open_interest.get(&(contract.symbol.clone(), previous)).copied();
+                matches!((reported(front), reported(next)), (Some(front), Some(next)) if next > front)
+            }
+        }
+    }
+
+    /// Amount a roll from `from_price` to `to_price` adjusts earlier prices by
+    fn roll_amount(&self, from_price: Fixed, to_price: Fixed) -> PyResult<Fixed> {
+        Ok(match self.adjustment {
+            BackAdjustment::None => Fixed::ZERO,
+            BackAdjustment::Difference => to_price.checked_sub(from_price)?,
+            BackAdjustment::Ratio if from_price.is_positive() => {
+                to_price.checked_div(from_price, SCALE, RoundingMode::HalfEven)?
+            }
+            BackAdjustment::Ratio => Fixed::from_int(1),
+        })
+    }
+
+    /// Take the records of one event; returns the continuous instrument's
+    /// records for it, copied from the contract followed after any roll,
+    /// and the rolls made
+    pub fn on_event<'py>(
+        &self,
+        time: &Bound<'py, PyAny>,
+        nanos: i64,
+        date: NaiveDate,
+        records: &[ContractRecord<'py>],
+    ) -> PyResult<Stitched<'py>> {
+        let py = time.py();
+        let mut pending = Vec::new();
+        let active = {
+            let mut state = self.state.lock().unwrap();
+            for record in records {
+                if state.contracts.iter().any(|contract| contract.id == record.id) {
+                    continue;
+                }
+                let at = state.contracts.partition_point(|contract| contract.expiry <= record.expiry);
+                state.contracts.insert(
+                    at,
+                    Contract {
+                        id: record.id,
+                        instrument: record.instrument.clone().unbind(),
+                        symbol: record.symbol.clone(),
+                        expiry: record.expiry,
+                        last_price: None,
+                        volume: Fixed::ZERO,
+                        prior_volume: Fixed::ZERO,
+                    },
+                );
+                if let Some(active) = state.active.as_mut() {
+                    if at <= *active {
+                        *active += 1;
+                    }
+                }
+            }
+
+            let new_day = state.day.is_some_and(|day| day != date);
+            if new_day {
+                for contract in &mut state.contracts {
+                    contract.prior_volume = std::mem::replace(&mut contract.volume, Fixed::ZERO);
+                }
+            }
+            if state.active.is_none() {
+                let days = self.days_before_expiry;
+                state.active =
+                    state.contracts.iter().position(|contract| date < weekdays_before(contract.expiry, days));
+            }
+            // Roll into the next contract that has traded, as often as due
+            while let Some(from) = state.active {
+                let Some(to) = (from + 1..state.contracts.len()).find(|&to| state.contracts[to].last_price.is_some())
+                else {
+                    break;
+                };
+                if !self.roll_due(&state, from, to, date, new_day) {
+                    break;
+                }
+                let to_price = state.contracts[to].last_price.expect("only contracts that traded are rolled into");
+                let from_price = state.contracts[from].last_price.unwrap_or(to_price);
+                pending.push(PendingRoll {
+                    from_contract: state.contracts[from].instrument.clone_ref(py),
+                    to_contract: state.contracts[to].instrument.clone_ref(py),
+                    from_price,
+                    to_price,
+                    amount: self.roll_amount(from_price, to_price)?,
+                });
+                state.active = Some(to);
+            }
+            state.day = Some(date);
+
+            for record in records {
+                let Some(contract) = state.contracts.iter_mut().find(|contract| contract.id == record.id) else {
+                    continue;
+                };
+                contract.last_price = Some(reference_price(record.kind, &record.values)?);
+                match record.kind {
+                    RecordKind::TradeTick => contract.volume = contract.volume.checked_add(record.values[1])?,
+                    RecordKind::TradeBar => contract.volume = contract.volume.checked_add(record.values[4])?,
+                    _ => {}
+                }
+            }
+            state.active.map(|active| state.contracts[active].id)
+        };
+
+        let mut rolls = Vec::with_capacity(pending.len());
+        let mut events = Vec::with_capacity(pending.len());
+        for roll in pending {
+            let event = RollEvent {
+                time: time.clone().unbind(),
+                nanos,
+                instrument: self.instrument.clone_ref(py),
+                from_contract: roll.from_contract,
+                to_contract: roll.to_contract,
+                from_price: roll.from_price,
+                to_price: roll.to_price,
+                adjustment: self.adjustment,
+                amount: roll.amount,
+            };
+            let event = Py::new(py, event)?;
+            events.push(event.clone_ref(py));
+            rolls.push(Roll {
+                nanos,
+                amount: roll.amount,
+                event,
+            });
+        }
+        if !rolls.is_empty() {
+            self.state.lock().unwrap().rolls.extend(rolls);
+        }
+
+        let mut continuous = Vec::new();
+        for record in records.iter().filter(|record| Some(record.id) == active) {
+            let base = record.record.cast::<MarketData>()?.get();
+            let base = MarketData::from_parts(
+                base.timestamp.clone_ref(py),
+                self.instrument.clone_ref(py),
+                base.resolution.clone_ref(py),
+                base.native_resolution,
+                base.timestamp_nanos(py)?,
+            );
+            continuous.push(record.kind.build(py, base, &record.values[..record.kind.width()], record.direction)?);
+        }
+        Ok((continuous, events))
+    }
+
+    /// Time and amount of the rolls made by `as_of`, oldest first
+    fn effects(&self, as_of: i64) -> Vec<(i64, Fixed)> {
+        let state = self.state.lock().unwrap();
+        let last = state.rolls.partition_point(|roll| roll.nanos <= as_of);
+        state.rolls[..last].iter().map(|roll| (roll.nanos, roll.amount)).collect()
+    }
+}
+
+/// Symbol of an `Instrument`, or a symbol given as text
+fn extract_symbol(obj: &Bound<'_, PyAny>) -> PyResult<String> {
+    if obj.is_instance_of::<PyString>() {
+        obj.extract()
+    } else {
+        obj.getattr("symbol")?.extract()
+    }
+}
+
+/// Read `date,symbol,open_interest` rows
+fn read_open_interest(path: &Path) -> Result<OpenInterest, DataError> {
+    let malformed = |message: String| DataError::Malformed {
+        path: path.to_path_buf(),
+        message,
+    };
+    let file = File::open(path).map_err(|source| DataError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let mut reader = csv::Reader::from_reader(file);
+    let headers = reader.headers().map_err(|err| malformed(err.to_string()))?.clone();
+    let position = |column: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim().eq_ignore_ascii_case(column))
+            .ok_or_else(|| DataError::MissingColumn {
+                format: "CSV",
+                path: path.to_path_buf(),
+                column: column.to_owned(),
+            })
+    };
+    let columns = [
+        position(DATE_COLUMN)?,
+        position(SYMBOL_COLUMN)?,
+        position(OPEN_INTEREST_COLUMN)?,
+    ];
+
+    let mut rows = OpenInterest::new();
+    for (row, record) in reader.records().enumerate() {
+        let record = record.map_err(|err| malformed(err.to_string()))?;
+        let line = row + 2;
+        let [date, symbol, value] = columns.map(|column| record.get(column).unwrap_or("").trim());
+        let invalid = |what: &str, text: &str| malformed(format!("line {line}: invalid {what} '{text}'"));
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| invalid("date", date))?;
+        if symbol.is_empty() {
+            return Err(invalid("symbol", symbol));
+        }
+        match value.parse::<Fixed>() {
+            Ok(open_interest) if !open_interest.is_negative() => {
+                rows.insert((symbol.to_owned(), date), open_interest);
+            }
+            _ => return Err(invalid("open interest", value)),
+        }
+    }
+    Ok(rows)
+}
+
+#[pymethods]
+impl ContinuousFuture {
+    #[new]
+    #[pyo3(signature = (
+        root,
+        rule=RollRule::Calendar,
+        days_before_expiry=5,
+        adjustment=BackAdjustment::Difference,
+        exchange=None,
+        currency="USD",
+        expiries=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn py_new(
+        py: Python<'_>,
+        root: String,
+        rule: RollRule,
+        days_before_expiry: u32,
+        adjustment: BackAdjustment,
+        exchange: Option<String>,
+        currency: &str,
+        expiries: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Self> {
+        if root.trim().is_empty() {
+            return Err(PyValueError::new_err("Futures root cannot be empty"));
+        }
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("exchange", exchange)?;
+        kwargs.set_item("currency", currency)?;
+        let instrument = instrument_type(py)?.call_method("future", (&root,), Some(&kwargs))?;
+        let mut by_symbol = HashMap::new();
+        for (symbol, expiry) in expiries.into_iter().flatten() {
+            by_symbol.insert(extract_symbol(&symbol)?, extract_date(&expiry)?);
+        }
+        Ok(ContinuousFuture {
+            root,
+            instrument_id: default_registry(py)?.get().intern_instrument(&instrument)?,
+            instrument: instrument.unbind(),
+            rule,
+            days_before_expiry,
+            adjustment,
+            expiries: by_symbol,
+            open_interest: Mutex::new(HashMap::new()),
+            state: Mutex::new(State::default()),
+        })
+    }
+
+    #[getter]
+    fn root(&self) -> &str {
+        &self.root
+    }
+
+    /// The continuous instrument, `Instrument.future(root)`
+    #[getter]
+    fn instrument(&self, py: Python<'_>) -> Py<PyAny> {
+        self.instrument.clone_ref(py)
+    }
+
+    #[getter]
+    fn rule(&self) -> RollRule {
+        self.rule
+    }
+
+    #[getter]
+    fn days_before_expiry(&self) -> u32 {
+        self.days_before_expiry
+    }
+
+    #[getter]
+    fn adjustment(&self) -> BackAdjustment {
+        self.adjustment
+    }
+
+    /// Contract the series follows as of the latest record streamed
+    #[getter]
+    fn active_contract(&self, py: Python<'_>) -> Option<Py<PyAny>> {
+        let state = self.state.lock().unwrap();
+        state.active.map(|active| state.contracts[active].instrument.clone_ref(py))
+    }
+
+    /// Contracts seen so far, by expiry
+    #[getter]
+    fn contracts<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let state = self.state.lock().unwrap();
+        PyList::new(py, state.contracts.iter().map(|contract| contract.instrument.clone_ref(py)))
+    }
+
+    /// Rolls made so far, by time
+    #[getter]
+    fn rolls<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let state = self.state.lock().unwrap();
+        PyList::new(py, state.rolls.iter().map(|roll| roll.event.clone_ref(py)))
+    }
+
+    /// Rolls made after `start` and at or before `end`; either bound may
+    /// be omitted
+    #[pyo3(signature = (start=None, end=None))]
+    fn between<'py>(
+        &self,
+        py: Python<'py>,
+        start: Option<&Bound<'py, PyAny>>,
+        end: Option<&Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let start = start.map(datetime_to_nanos).transpose()?.unwrap_or(i64::MIN);
+        let end = end.map(datetime_to_nanos).transpose()?.unwrap_or(i64::MAX);
+        let state = self.state.lock().unwrap();
+        let first = state.rolls.partition_point(|roll| roll.nanos <= start);
+        let last = state.rolls.partition_point(|roll| roll.nanos <= end);
+        PyList::new(py, state.rolls[first..last.max(first)].iter().map(|roll| roll.event.clone_ref(py)))
+    }
+
+    /// Number of rolls adjusting prices as of `as_of`; adjusted views
+    /// only change when it does
+    #[pyo3(signature = (as_of=None))]
+    fn count(&self, as_of: Option<&Bound<'_, PyAny>>) -> PyResult<usize> {
+        if self.adjustment == BackAdjustment::None {
+            return Ok(0);
+        }
+        let as_of = as_of.map(datetime_to_nanos).transpose()?.unwrap_or(i64::MAX);
+        Ok(self.effects(as_of).len())
+    }
+
+    /// Add the open interest of `contract` reported for `date`
+    fn add_open_interest(
+        &self,
+        contract: &Bound<'_, PyAny>,
+        date: &Bound<'_, PyAny>,
+        open_interest: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        let open_interest = extract_fixed(open_interest)?;
+        if open_interest.is_negative() {
+            return Err(PyValueError::new_err("Open interest cannot be negative"));
+        }
+        let key = (extract_symbol(contract)?, extract_date(date)?);
+        self.open_interest.lock().unwrap().insert(key, open_interest);
+        Ok(())
+    }
+
+    /// Load open interest from a CSV file
+    ///
+    /// Columns: `date` (the day reported for, YYYY-MM-DD), `symbol` (the
+    /// contract) and `open_interest`.
+    fn load_open_interest(&self, path: PathBuf) -> PyResult<()> {
+        let rows = read_open_interest(&path)?;
+        self.open_interest.lock().unwrap().extend(rows);
+        Ok(())
+    }
+
+    /// Restate continuous `records`, oldest first, for the rolls made by
+    /// `as_of` (every roll when omitted)
+    ///
+    /// Records before a roll have their prices shifted by its difference
+    /// or scaled by its ratio, so the series joins the contract followed
+    /// since without a gap. Sizes are left as they are, as are records
+    /// needing no change.
+    #[pyo3(signature = (records, as_of=None))]
+    fn adjust<'py>(
+        &self,
+        records: &Bound<'py, PyAny>,
+        as_of: Option<&Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let py = records.py();
+        let records = records.try_iter()?.collect::<PyResult<Vec<_>>>()?;
+        if self.adjustment == BackAdjustment::None {
+            return PyList::new(py, records);
+        }
+        let as_of = as_of.map(datetime_to_nanos).transpose()?.unwrap_or(i64::MAX);
+        let mut pending = self.effects(as_of);
+
+        let ratio = self.adjustment == BackAdjustment::Ratio;
+        let identity = if ratio { Fixed::from_int(1) } else { Fixed::ZERO };
+        // Shift or factor of the rolls after the records walked so far
+        let mut total = identity;
+        let mut adjusted = Vec::with_capacity(records.len());
+        for record in records.iter().rev() {
+            let base = record.cast::<MarketData>()?.get();
+            let time = base.timestamp_nanos(py)?;
+            let Some((kind, mut values, direction)) = payload(record) else {
+                return Err(PyTypeError::new_err(format!("Unknown data type: {}", record.get_type().name()?)));
+            };
+            while let Some(&(roll_time, amount)) = pending.last() {
+                if time >= roll_time {
+                    break;
+                }
+                total = if ratio {
+                    total.checked_mul(amount)?.rescale(SCALE, RoundingMode::HalfEven)?
+                } else {
+                    total.checked_add(amount)?
+                };
+                pending.pop();
+            }
+            if total == identity {
+                adjusted.push(record.clone());
+                continue;
+            }
+            let width = kind.width();
+            for (index, value) in values[..width].iter_mut().enumerate() {
+                if kind.is_size(index) {
+                    continue;
+                }
+                let price = if ratio {
+                    value.checked_mul(total)?.rescale(SCALE, RoundingMode::HalfEven)?
+                } else {
+                    value.checked_add(total)?
+                };
+                *value = tidy(price, *value)?;
+            }
+            let restated = MarketData::from_parts(
+                base.timestamp.clone_ref(py),
+                base.instrument.clone_ref(py),
+                base.resolution.clone_ref(py),
+                base.native_resolution,
+                time,
+            );
+            adjusted.push(kind.build(py, restated, &values[..width], direction)?);
+        }
+        adjusted.reverse();
+        PyList::new(py, adjusted)
+    }
+
+    fn __repr__(&self) -> String {
+        let state = self.state.lock().unwrap();
+        format!(
+            "ContinuousFuture(root='{}', rule={}, adjustment={}, {} contracts, {} rolls)",
+            self.root,
+            self.rule.as_str(),
+            self.adjustment.as_str(),
+            state.contracts.len(),
+            state.rolls.len()
+        )
+    }
+}
@@ -0,0 +1,81 @@
+//! Futures contract codes and expiry dates
+//!
+//! Contracts are recognised by their CME code: the root, a month code and
+//! a two-digit year, as in `ESZ24`. Two-digit years are taken as 20xx.
+//! Without an explicit expiry a contract expires on the third Friday of
+//! its month, as equity index futures do.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+/// Month codes, January first
+const MONTH_CODES: &[u8; 12] = b"FGHJKMNQUVXZ";
+
+/// Year and month of `symbol` if it is a contract code of `root`
+pub fn parse_code(symbol: &str, root: &str) -> Option<(i32, u32)> {
+    let rest = symbol.strip_prefix(root)?.as_bytes();
+    let [month, tens, ones] = rest else {
+        return None;
+    };
+    if !tens.is_ascii_digit() || !ones.is_ascii_digit() {
+        return None;
+    }
+    let month = MONTH_CODES.iter().position(|code| code == month)? as u32 + 1;
+    Some((2000 + i32::from((tens - b'0') * 10 + (ones - b'0')), month))
+}
+
+/// Third Friday of a month
+pub fn third_friday(year: i32, month: u32) -> Option<NaiveDate> {
+    NaiveDate::from_weekday_of_month_opt(year, month, Weekday::Fri, 3)
+}
+
+/// The date `days` weekdays before `date`
+pub fn weekdays_before(date: NaiveDate, days: u32) -> NaiveDate {
+    let mut date = date;
+    let mut left = days;
+    while left > 0 {
+        date -= Duration::days(1);
+        if date.weekday().number_from_monday() <= 5 {
+            left -= 1;
+        }
+    }
+    date
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parses_contract_codes_of_a_root() {
+        assert_eq!(parse_code("ESZ24", "ES"), Some((2024, 12)));
+        assert_eq!(parse_code("ESF30", "ES"), Some((2030, 1)));
+        assert_eq!(parse_code("NQH24", "ES"), None);
+        // Other roots sharing the prefix, bad month codes and years
+        assert_eq!(parse_code("ESTRZ24", "ES"), None);
+        assert_eq!(parse_code("ESA24", "ES"), None);
+        assert_eq!(parse_code("ESZ2X", "ES"), None);
+        assert_eq!(parse_code("ES", "ES"), None);
+    }
+
+    #[test]
+    fn expiries_fall_on_third_fridays() {
+        assert_eq!(third_friday(2024, 3), Some(date(2024, 3, 15)));
+        // A month starting on a Friday
+        assert_eq!(third_friday(2024, 11), Some(date(2024, 11, 15)));
+        assert_eq!(third_friday(2024, 13), None);
+    }
+
+    #[test]
+    fn counts_back_weekdays() {
+        let friday = date(2024, 3, 15);
+        assert_eq!(weekdays_before(friday, 0), friday);
+        assert_eq!(weekdays_before(friday, 2), date(2024, 3, 13));
+        // Weekends are skipped
+        assert_eq!(weekdays_before(friday, 5), date(2024, 3, 8));
+        assert_eq!(weekdays_before(date(2024, 3, 17), 1), friday);
+    }
+}
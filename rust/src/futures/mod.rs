@@ -0,0 +1,28 @@
+//! Continuous futures
+//!
+//! Individual contracts of a futures root stitched into one continuous
+//! series under calendar, volume or open interest roll rules. Rolls are
+//! decided causally as the stream reaches them, and back-adjusted views
+//! are point in time, like those of corporate actions.
+
+pub mod continuous;
+pub mod contract;
+pub mod provider;
+pub mod roll;
+
+use pyo3::prelude::*;
+
+pub use continuous::ContinuousFuture;
+pub use provider::{ContinuousFuturesIterator, ContinuousFuturesProvider};
+pub use roll::{BackAdjustment, RollEvent, RollRule};
+
+/// Register the continuous futures classes on the extension module
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<RollRule>()?;
+    m.add_class::<BackAdjustment>()?;
+    m.add_class::<RollEvent>()?;
+    m.add_class::<ContinuousFuture>()?;
+    m.add_class::<ContinuousFuturesProvider>()?;
+    m.add_class::<ContinuousFuturesIterator>()?;
+    Ok(())
+}
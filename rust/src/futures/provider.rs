@@ -0,0 +1,182 @@
+//! Data provider that adds continuous futures series to another provider's stream
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use pyo3::prelude::*;
+use pyo3::types::PyIterator;
+
+use crate::data::schema::payload;
+use crate::events::market_event::MarketEvent;
+use crate::futures::continuous::{ContinuousFuture, ContractRecord};
+use crate::interop::logger;
+use crate::types::instrument::InstrumentId;
+use crate::types::market_data::MarketData;
+use crate::types::time::{datetime_to_nanos, extract_date};
+
+const LOGGER: &str = "simulor.data.futures";
+
+/// Wrap a data provider and stitch its futures contracts into continuous series
+///
+/// Each event passes through with, for every `ContinuousFuture` in
+/// `futures`, copies of the records of the contract it follows under the
+/// continuous instrument. Those copies are prices as traded; back-adjusted
+/// views come from the future itself, as of any time. Rolls are decided as
+/// the stream reaches them and recorded on the futures, which start afresh
+/// on every pass. Book updates are not copied.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct ContinuousFuturesProvider {
+    source: Py<PyAny>,
+    futures: Vec<Py<ContinuousFuture>>,
+}
+
+#[pymethods]
+impl ContinuousFuturesProvider {
+    #[new]
+    fn py_new(source: Py<PyAny>, futures: Vec<Py<ContinuousFuture>>) -> Self {
+        ContinuousFuturesProvider { source, futures }
+    }
+
+    /// The wrapped provider
+    #[getter]
+    fn source(&self, py: Python<'_>) -> Py<PyAny> {
+        self.source.clone_ref(py)
+    }
+
+    #[getter]
+    fn futures(&self, py: Python<'_>) -> Vec<Py<ContinuousFuture>> {
+        self.futures.iter().map(|future| future.clone_ref(py)).collect()
+    }
+
+    /// Return a new iterator; every pass rolls the futures afresh
+    fn __iter__(&self, py: Python<'_>) -> PyResult<ContinuousFuturesIterator> {
+        for future in &self.futures {
+            future.get().reset();
+        }
+        Ok(ContinuousFuturesIterator {
+            source: self.source.bind(py).try_iter()?.unbind(),
+            futures: self.futures.iter().map(|future| future.clone_ref(py)).collect(),
+            contracts: HashMap::new(),
+            logger: logger(py, LOGGER)?.unbind(),
+        })
+    }
+}
+
+/// Iterator over a `ContinuousFuturesProvider`
+#[pyclass(module = "_simulor_rust")]
+pub struct ContinuousFuturesIterator {
+    source: Py<PyIterator>,
+    futures: Vec<Py<ContinuousFuture>>,
+    /// Future, symbol and expiry of each instrument that is a contract
+    contracts: HashMap<InstrumentId, Option<(usize, String, NaiveDate)>>,
+    logger: Py<PyAny>,
+}
+
+impl ContinuousFuturesIterator {
+    /// Future, symbol and expiry of `instrument`, if it is a contract of one
+    fn contract(
+        &mut self,
+        id: InstrumentId,
+        instrument: &Bound<'_, PyAny>,
+    ) -> PyResult<Option<(usize, String, NaiveDate)>> {
+        if let Some(contract) = self.contracts.get(&id) {
+            return Ok(contract.clone());
+        }
+        let mut contract = None;
+        let symbol: String = instrument.getattr("symbol")?.extract()?;
+        for (index, future) in self.futures.iter().enumerate() {
+            let future = future.get();
+            if future.instrument_id() == id {
+                break;
+            }
+            if let Some(expiry) = future.expiry_of(instrument, &symbol)? {
+                contract = Some((index, symbol, expiry));
+                break;
+            }
+        }
+        self.contracts.insert(id, contract.clone());
+        Ok(contract)
+    }
+
+    /// The event with the continuous records added
+    fn process<'py>(&mut self, event: Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        let py = event.py();
+        let records = event.call_method0("flatten")?;
+        let mut by_future: Vec<Vec<ContractRecord<'py>>> = self.futures.iter().map(|_| Vec::new()).collect();
+        for record in records.try_iter()? {
+            let record = record?;
+            let Some((kind, values, direction)) = payload(&record) else {
+                continue;
+            };
+            let base = record.cast::<MarketData>()?.get();
+            let id = base.instrument_id(py)?;
+            let instrument = base.instrument.bind(py).clone();
+            let Some((index, symbol, expiry)) = self.contract(id, &instrument)? else {
+                continue;
+            };
+            by_future[index].push(ContractRecord {
+                id,
+                instrument,
+                symbol,
+                expiry,
+                record,
+                kind,
+                values,
+                direction,
+            });
+        }
+        if by_future.iter().all(Vec::is_empty) {
+            return Ok(event);
+        }
+
+        let time = event.getattr("time")?;
+        let nanos = datetime_to_nanos(&time)?;
+        let date = extract_date(&time)?;
+        let mut continuous = Vec::new();
+        for (future, contract_records) in self.futures.iter().zip(&by_future) {
+            if contract_records.is_empty() {
+                continue;
+            }
+            let (records, rolls) = future.get().on_event(&time, nanos, date, contract_records)?;
+            continuous.extend(records);
+            for roll in rolls {
+                let roll = roll.bind(py).get();
+                self.logger.bind(py).call_method1(
+                    "info",
+                    (
+                        "Rolled %s from %s to %s at %s",
+                        roll.instrument.bind(py).getattr("display_name")?,
+                        roll.from_contract.bind(py).getattr("display_name")?,
+                        roll.to_contract.bind(py).getattr("display_name")?,
+                        &time,
+                    ),
+                )?;
+            }
+        }
+        if continuous.is_empty() {
+            return Ok(event);
+        }
+        let mut output = MarketEvent::new(time.unbind());
+        for record in records.try_iter()? {
+            output.add_record(&record?)?;
+        }
+        for record in &continuous {
+            output.add_record(record)?;
+        }
+        Ok(Bound::new(py, output)?.into_any())
+    }
+}
+
+#[pymethods]
+impl ContinuousFuturesIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        match self.source.bind(py).clone().next() {
+            Some(event) => Ok(Some(self.process(event?)?.unbind())),
+            None => Ok(None),
+        }
+    }
+}
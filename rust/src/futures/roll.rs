@@ -0,0 +1,138 @@
+//! Roll rules, back-adjustment modes and roll events
+
+use pyo3::prelude::*;
+
+use crate::types::fixed::Fixed;
+use crate::types::price::to_decimal;
+
+/// When a continuous series moves from one contract to the next
+#[pyclass(module = "_simulor_rust", eq, eq_int, frozen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RollRule {
+    /// A fixed number of weekdays before the front contract expires
+    #[pyo3(name = "CALENDAR")]
+    Calendar,
+    /// Once the next contract traded more than the front one the day before
+    #[pyo3(name = "VOLUME")]
+    Volume,
+    /// Once the next contract's open interest exceeded the front one's the
+    /// day before
+    #[pyo3(name = "OPEN_INTEREST")]
+    OpenInterest,
+}
+
+impl RollRule {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RollRule::Calendar => "CALENDAR",
+            RollRule::Volume => "VOLUME",
+            RollRule::OpenInterest => "OPEN_INTEREST",
+        }
+    }
+}
+
+/// How prices before a roll are restated to join the next contract's
+#[pyclass(module = "_simulor_rust", eq, eq_int, frozen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BackAdjustment {
+    /// Prices as traded, gaps at each roll left in
+    #[pyo3(name = "NONE")]
+    None,
+    /// Earlier prices shifted by the gap between the two contracts,
+    /// preserving price differences
+    #[pyo3(name = "DIFFERENCE")]
+    Difference,
+    /// Earlier prices scaled by the ratio of the two contracts, preserving
+    /// returns
+    #[pyo3(name = "RATIO")]
+    Ratio,
+}
+
+impl BackAdjustment {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BackAdjustment::None => "NONE",
+            BackAdjustment::Difference => "DIFFERENCE",
+            BackAdjustment::Ratio => "RATIO",
+        }
+    }
+}
+
+/// A continuous series moving from one contract to the next
+///
+/// `from_price` and `to_price` are the last prices of each contract seen
+/// before the roll; a position in the continuous instrument is closed at
+/// the first and reopened at the second.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct RollEvent {
+    pub time: Py<PyAny>,
+    pub nanos: i64,
+    pub instrument: Py<PyAny>,
+    pub from_contract: Py<PyAny>,
+    pub to_contract: Py<PyAny>,
+    pub from_price: Fixed,
+    pub to_price: Fixed,
+    pub adjustment: BackAdjustment,
+    /// Added to earlier prices, or multiplying them, per `adjustment`
+    pub amount: Fixed,
+}
+
+#[pymethods]
+impl RollEvent {
+    /// Time of the first record of the new contract
+    #[getter]
+    fn time(&self, py: Python<'_>) -> Py<PyAny> {
+        self.time.clone_ref(py)
+    }
+
+    /// The continuous instrument
+    #[getter]
+    fn instrument(&self, py: Python<'_>) -> Py<PyAny> {
+        self.instrument.clone_ref(py)
+    }
+
+    /// Contract rolled out of
+    #[getter(from_contract)]
+    fn py_from_contract(&self, py: Python<'_>) -> Py<PyAny> {
+        self.from_contract.clone_ref(py)
+    }
+
+    /// Contract rolled into
+    #[getter]
+    fn to_contract(&self, py: Python<'_>) -> Py<PyAny> {
+        self.to_contract.clone_ref(py)
+    }
+
+    #[getter(from_price)]
+    fn py_from_price<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.from_price)
+    }
+
+    #[getter(to_price)]
+    fn py_to_price<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.to_price)
+    }
+
+    #[getter]
+    fn adjustment(&self) -> BackAdjustment {
+        self.adjustment
+    }
+
+    /// Amount added to earlier prices (`DIFFERENCE`) or factor multiplying
+    /// them (`RATIO`); `None` when prices are not adjusted
+    #[getter(amount)]
+    fn py_amount<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        (self.adjustment != BackAdjustment::None).then(|| to_decimal(py, self.amount)).transpose()
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "RollEvent(time={}, from_contract={}, to_contract={}, from_price={}, to_price={})",
+            self.time.bind(py).str()?,
+            self.from_contract.bind(py).repr()?,
+            self.to_contract.bind(py).repr()?,
+            self.from_price,
+            self.to_price,
+        ))
+    }
+}
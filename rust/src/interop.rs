@@ -0,0 +1,134 @@
+//! Helpers for crossing the Python boundary
+//!
+//! Python types that the native classes need to produce or recognise are
+//! imported once and cached for the lifetime of the interpreter.
+
+use pyo3::prelude::*;
+use pyo3::sync::PyOnceLock;
+use pyo3::types::PyType;
+
+static DECIMAL: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static DATE: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static DATETIME: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static TIME: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static TIMEDELTA: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static RESOLUTION: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static TICK_DIRECTION: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static EVENT_TYPE: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static DATA_EVENT: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static SYSTEM_EVENT: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static ASSET_TYPE: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static OPTION_TYPE: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static ORDER_SIDE: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static INSTRUMENT: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static FILL: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static INSTANT_FILL_MODEL: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static PATH: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static ZONEINFO: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static NUMPY_ASARRAY: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static NUMPY_EMPTY: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
+/// `decimal.Decimal`
+pub fn decimal_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    DECIMAL.import(py, "decimal", "Decimal")
+}
+
+/// `datetime.date`
+pub fn date_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    DATE.import(py, "datetime", "date")
+}
+
+/// `datetime.datetime`
+pub fn datetime_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    DATETIME.import(py, "datetime", "datetime")
+}
+
+/// `datetime.time`
+pub fn time_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    TIME.import(py, "datetime", "time")
+}
+
+/// `datetime.timedelta`
+pub fn timedelta_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    TIMEDELTA.import(py, "datetime", "timedelta")
+}
+
+/// `simulor.types.common.Resolution`
+pub fn resolution_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    RESOLUTION.import(py, "simulor.types.common", "Resolution")
+}
+
+/// `simulor.types.common.TickDirection`
+pub fn tick_direction_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    TICK_DIRECTION.import(py, "simulor.types.common", "TickDirection")
+}
+
+/// `simulor.core.events.EventType`
+pub fn event_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    EVENT_TYPE.import(py, "simulor.core.events", "EventType")
+}
+
+/// `simulor.core.events.DataEvent`
+pub fn data_event_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    DATA_EVENT.import(py, "simulor.core.events", "DataEvent")
+}
+
+/// `simulor.core.events.SystemEvent`
+pub fn system_event_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    SYSTEM_EVENT.import(py, "simulor.core.events", "SystemEvent")
+}
+
+/// `simulor.types.common.AssetType`
+pub fn asset_type_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    ASSET_TYPE.import(py, "simulor.types.common", "AssetType")
+}
+
+/// `simulor.types.common.OptionType`
+pub fn option_type_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    OPTION_TYPE.import(py, "simulor.types.common", "OptionType")
+}
+
+/// `simulor.types.common.OrderSide`
+pub fn order_side_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    ORDER_SIDE.import(py, "simulor.types.common", "OrderSide")
+}
+
+/// `simulor.types.instruments.Instrument`
+pub fn instrument_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    INSTRUMENT.import(py, "simulor.types.instruments", "Instrument")
+}
+
+/// `simulor.types.orders.Fill`
+pub fn fill_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    FILL.import(py, "simulor.types.orders", "Fill")
+}
+
+/// `simulor.execution.simulation.fill_models.InstantFillModel`
+pub fn instant_fill_model_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    INSTANT_FILL_MODEL.import(py, "simulor.execution.simulation.fill_models", "InstantFillModel")
+}
+
+/// `pathlib.Path`
+pub fn path_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    PATH.import(py, "pathlib", "Path")
+}
+
+/// `zoneinfo.ZoneInfo`
+pub fn zoneinfo_type(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    ZONEINFO.import(py, "zoneinfo", "ZoneInfo")
+}
+
+/// `numpy.asarray`
+pub fn numpy_asarray(py: Python<'_>) -> PyResult<&Bound<'_, PyAny>> {
+    NUMPY_ASARRAY.import(py, "numpy", "asarray")
+}
+
+/// `numpy.empty`
+pub fn numpy_empty(py: Python<'_>) -> PyResult<&Bound<'_, PyAny>> {
+    NUMPY_EMPTY.import(py, "numpy", "empty")
+}
+
+/// Standard-library logger, so native components log alongside their Python counterparts
+pub fn logger<'py>(py: Python<'py>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+    py.import("logging")?.call_method1("getLogger", (name,))
+}
@@ -5,10 +5,52 @@
 
 use pyo3::prelude::*;
 
+pub mod bars;
+pub mod book;
+pub mod calendar;
+pub mod corporate;
+pub mod data;
+pub mod events;
+pub mod execution;
+pub mod futures;
+pub mod interop;
+pub mod options;
+pub mod quality;
+pub mod store;
+pub mod types;
+pub mod universe;
+
 /// Python module definition
 #[pymodule]
 fn _simulor_rust(m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Version info
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+
+    // Value types and market data records
+    types::register(m)?;
+    // Order books
+    book::register(m)?;
+    // Events
+    events::register(m)?;
+    // Order matching
+    execution::register(m)?;
+    // Data providers
+    data::register(m)?;
+    // Exchange calendars
+    calendar::register(m)?;
+    // Corporate actions
+    corporate::register(m)?;
+    // Continuous futures
+    futures::register(m)?;
+    // Options analytics
+    options::register(m)?;
+    // Data quality checks
+    quality::register(m)?;
+    // Time-fenced market data store
+    store::register(m)?;
+    // Point-in-time universe membership
+    universe::register(m)?;
+    // Bar consolidators
+    bars::register(m)?;
     Ok(())
 }
@@ -0,0 +1,258 @@
+//! Closed-form European option prices and Greeks
+//!
+//! Both Black-Scholes-Merton and Black-76 are the generalized Black-Scholes
+//! model with a cost of carry `b`: `b = r - q` for a spot with dividend yield
+//! `q`, and `b = 0` for a futures price. At expiry or with no volatility the
+//! option is worth its discounted intrinsic value on the forward.
+
+use crate::options::greeks::Sensitivities;
+use crate::options::normal::{cdf, pdf};
+use crate::options::payoff::OptionKind;
+
+/// One European option under the generalized Black-Scholes model
+#[derive(Debug, Clone, Copy)]
+pub struct Inputs {
+    pub kind: OptionKind,
+    /// Spot, or futures price for Black-76
+    pub underlying: f64,
+    pub strike: f64,
+    /// Years to expiry
+    pub time: f64,
+    /// Continuously compounded risk-free rate
+    pub rate: f64,
+    /// Cost of carry
+    pub carry: f64,
+    pub volatility: f64,
+}
+
+impl Inputs {
+    /// Whether the inputs describe an option that can be priced
+    pub fn valid(&self) -> bool {
+        self.underlying > 0.0
+            && self.underlying.is_finite()
+            && self.strike > 0.0
+            && self.strike.is_finite()
+            && self.time >= 0.0
+            && self.time.is_finite()
+            && self.volatility >= 0.0
+            && self.volatility.is_finite()
+            && self.rate.is_finite()
+            && self.carry.is_finite()
+    }
+
+    /// `d1` and `d2`, or `None` at expiry or without volatility
+    fn d(&self) -> Option<(f64, f64)> {
+        if self.time <= 0.0 || self.volatility <= 0.0 {
+            return None;
+        }
+        let deviation = self.volatility * self.time.sqrt();
+        let drift = (self.carry + 0.5 * self.volatility * self.volatility) * self.time;
+        let d1 = ((self.underlying / self.strike).ln() + drift) / deviation;
+        Some((d1, d1 - deviation))
+    }
+
+    /// `N(±d1)` and `N(±d2)`, or whether the option is in the money on the
+    /// forward when the distribution has collapsed
+    fn probabilities(&self, carry_discount: f64, discount: f64) -> (f64, f64) {
+        let sign = self.kind.sign();
+        match self.d() {
+            Some((d1, d2)) => (cdf(sign * d1), cdf(sign * d2)),
+            None => {
+                let in_the_money = sign * (self.underlying * carry_discount - self.strike * discount) > 0.0;
+                let probability = if in_the_money { 1.0 } else { 0.0 };
+                (probability, probability)
+            }
+        }
+    }
+}
+
+/// Price of the option; NaN when the inputs are invalid
+pub fn price(inputs: &Inputs) -> f64 {
+    if !inputs.valid() {
+        return f64::NAN;
+    }
+    let carry_discount = ((inputs.carry - inputs.rate) * inputs.time).exp();
+    let discount = (-inputs.rate * inputs.time).exp();
+    let (n1, n2) = inputs.probabilities(carry_discount, discount);
+    inputs.kind.sign() * (inputs.underlying * carry_discount * n1 - inputs.strike * discount * n2)
+}
+
+/// Price and Greeks of the option; NaN throughout when the inputs are invalid
+///
+/// `carry_tracks_rate` says whether the cost of carry moves with the rate,
+/// as `r - q` does, which decides what rho measures.
+pub fn sensitivities(inputs: &Inputs, carry_tracks_rate: bool) -> Sensitivities {
+    if !inputs.valid() {
+        return Sensitivities::NAN;
+    }
+    let Inputs {
+        kind,
+        underlying,
+        strike,
+        time,
+        rate,
+        carry,
+        volatility,
+    } = *inputs;
+    let sign = kind.sign();
+    let carry_discount = ((carry - rate) * time).exp();
+    let discount = (-rate * time).exp();
+    let (n1, n2) = inputs.probabilities(carry_discount, discount);
+    let forward_leg = underlying * carry_discount * n1;
+    let strike_leg = strike * discount * n2;
+    let value = sign * (forward_leg - strike_leg);
+
+    let mut greeks = Sensitivities {
+        price: value,
+        delta: sign * carry_discount * n1,
+        theta: -sign * (carry - rate) * forward_leg - sign * rate * strike_leg,
+        rho: -time * value
+            + if carry_tracks_rate {
+                sign * time * forward_leg
+            } else {
+                0.0
+            },
+        charm: -sign * (carry - rate) * carry_discount * n1,
+        ..Sensitivities::ZERO
+    };
+    if let Some((d1, d2)) = inputs.d() {
+        let root_time = time.sqrt();
+        let density = carry_discount * pdf(d1);
+        greeks.gamma = density / (underlying * volatility * root_time);
+        greeks.vega = underlying * density * root_time;
+        greeks.theta -= underlying * density * volatility / (2.0 * root_time);
+        greeks.vanna = -density * d2 / volatility;
+        greeks.vomma = greeks.vega * d1 * d2 / volatility;
+        greeks.charm -= density * (carry / (volatility * root_time) - d2 / (2.0 * time));
+    }
+    greeks
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// At the money for a year, as in Hull's examples
+    pub fn option(kind: OptionKind) -> Inputs {
+        Inputs {
+            kind,
+            underlying: 100.0,
+            strike: 100.0,
+            time: 1.0,
+            rate: 0.05,
+            carry: 0.05,
+            volatility: 0.2,
+        }
+    }
+
+    fn close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() <= tolerance, "{actual} is not within {tolerance} of {expected}");
+    }
+
+    #[test]
+    fn prices_european_options() {
+        close(price(&option(OptionKind::Call)), 10.450_583_572_185_565, 1e-12);
+        close(price(&option(OptionKind::Put)), 5.573_526_022_256_971, 1e-12);
+
+        // Put-call parity with a dividend yield, and on a futures price
+        for carry in [0.03, 0.0] {
+            let call = Inputs {
+                carry,
+                ..option(OptionKind::Call)
+            };
+            let put = Inputs {
+                kind: OptionKind::Put,
+                ..call
+            };
+            let parity = 100.0 * (carry - 0.05).exp() - 100.0 * (-0.05f64).exp();
+            close(price(&call) - price(&put), parity, 1e-12);
+        }
+    }
+
+    #[test]
+    fn collapses_to_intrinsic_value() {
+        let expired = Inputs {
+            underlying: 110.0,
+            time: 0.0,
+            ..option(OptionKind::Call)
+        };
+        assert_eq!(price(&expired), 10.0);
+        assert_eq!(
+            price(&Inputs {
+                kind: OptionKind::Put,
+                ..expired
+            }),
+            0.0
+        );
+        let greeks = sensitivities(&expired, true);
+        assert_eq!((greeks.delta, greeks.gamma, greeks.vega), (1.0, 0.0, 0.0));
+
+        // Without volatility, the discounted payoff on the forward
+        let certain = Inputs {
+            volatility: 0.0,
+            ..option(OptionKind::Call)
+        };
+        close(price(&certain), 100.0 - 100.0 * (-0.05f64).exp(), 1e-12);
+
+        for invalid in [
+            Inputs {
+                strike: 0.0,
+                ..option(OptionKind::Call)
+            },
+            Inputs {
+                time: -1.0,
+                ..option(OptionKind::Call)
+            },
+            Inputs {
+                volatility: f64::NAN,
+                ..option(OptionKind::Call)
+            },
+        ] {
+            assert!(price(&invalid).is_nan());
+            assert!(sensitivities(&invalid, true).values().iter().all(|value| value.is_nan()));
+        }
+    }
+
+    #[test]
+    fn greeks_match_finite_differences() {
+        for kind in [OptionKind::Call, OptionKind::Put] {
+            let inputs = Inputs {
+                underlying: 95.0,
+                carry: 0.03,
+                ..option(kind)
+            };
+            let bumped = |bump: &dyn Fn(&mut Inputs, f64), h: f64| {
+                let (mut up, mut down) = (inputs, inputs);
+                bump(&mut up, h);
+                bump(&mut down, -h);
+                (sensitivities(&up, true), sensitivities(&down, true))
+            };
+            let greeks = sensitivities(&inputs, true);
+            assert_eq!(greeks.price, price(&inputs));
+
+            let (up, down) = bumped(&|inputs, h| inputs.underlying += h, 1e-3);
+            close(greeks.delta, (up.price - down.price) / 2e-3, 1e-7);
+            close(greeks.gamma, (up.delta - down.delta) / 2e-3, 1e-7);
+            let (up, down) = bumped(&|inputs, h| inputs.volatility += h, 1e-5);
+            close(greeks.vega, (up.price - down.price) / 2e-5, 1e-5);
+            close(greeks.vanna, (up.delta - down.delta) / 2e-5, 1e-6);
+            close(greeks.vomma, (up.vega - down.vega) / 2e-5, 1e-5);
+            // Time passing shortens the time to expiry
+            let (up, down) = bumped(&|inputs, h| inputs.time -= h, 1e-5);
+            close(greeks.theta, (up.price - down.price) / 2e-5, 1e-5);
+            close(greeks.charm, (up.delta - down.delta) / 2e-5, 1e-6);
+            // The carry moves with the rate, as r - q does
+            let (up, down) = bumped(
+                &|inputs, h| {
+                    inputs.rate += h;
+                    inputs.carry += h;
+                },
+                1e-5,
+            );
+            close(greeks.rho, (up.price - down.price) / 2e-5, 1e-5);
+            // ... or stays, as for a futures price
+            let (up, down) = bumped(&|inputs, h| inputs.rate += h, 1e-5);
+            close(sensitivities(&inputs, false).rho, (up.price - down.price) / 2e-5, 1e-5);
+        }
+    }
+}
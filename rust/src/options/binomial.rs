@@ -0,0 +1,274 @@
+//! American and European options on a Cox-Ross-Rubinstein binomial tree
+//!
+//! Delta, gamma and theta are read off the first two steps of the tree. The
+//! other Greeks are central differences of trees with the volatility, rate
+//! or time to expiry bumped. A tree's error oscillates as the strike moves
+//! between its nodes, which differences amplify, so those are averaged over
+//! trees of `steps` and `steps + 1`, whose errors roughly cancel.
+
+use crate::options::analytic::{self, Inputs};
+use crate::options::greeks::Sensitivities;
+
+/// Volatility bump for vega and vanna
+const VOLATILITY_BUMP: f64 = 1e-2;
+/// Volatility bump for vomma, wider since second differences amplify the
+/// tree's error the most
+const VOMMA_BUMP: f64 = 5e-2;
+/// Rate bump for rho
+const RATE_BUMP: f64 = 1e-4;
+/// Time bump for charm: one day
+const TIME_BUMP: f64 = 1.0 / 365.0;
+
+/// Cox-Ross-Rubinstein tree of a fixed number of steps
+#[derive(Debug, Clone, Copy)]
+pub struct Tree {
+    pub steps: usize,
+    pub american: bool,
+}
+
+impl Tree {
+    /// Lowest volatility for which the tree's probabilities stay within
+    /// `[0, 1]` at these inputs
+    pub fn min_volatility(&self, inputs: &Inputs) -> f64 {
+        inputs.carry.abs() * (inputs.time / self.steps as f64).sqrt()
+    }
+
+    /// Price, delta, gamma and theta from one tree, or `None` when its
+    /// probabilities are not probabilities
+    fn roll_back(&self, inputs: &Inputs) -> Option<Sensitivities> {
+        let Inputs {
+            kind,
+            underlying,
+            strike,
+            time,
+            rate,
+            carry,
+            volatility,
+        } = *inputs;
+        let steps = self.steps;
+        let dt = time / steps as f64;
+        let log_up = volatility * dt.sqrt();
+        let (up, down) = (log_up.exp(), (-log_up).exp());
+        let probability = ((carry * dt).exp() - down) / (up - down);
+        if !(0.0..=1.0).contains(&probability) {
+            return None;
+        }
+        let discount = (-rate * dt).exp();
+        let node = |step: usize, ups: usize| underlying * (log_up * (2.0 * ups as f64 - step as f64)).exp();
+
+        let mut values: Vec<f64> = (0..=steps).map(|ups| kind.intrinsic(node(steps, ups), strike)).collect();
+        let mut second = [0.0; 3];
+        let mut first = [0.0; 2];
+        if steps == 2 {
+            second.copy_from_slice(&values[..3]);
+        }
+        for step in (0..steps).rev() {
+            for ups in 0..=step {
+                let continuation = discount * (probability * values[ups + 1] + (1.0 - probability) * values[ups]);
+                values[ups] = if self.american {
+                    continuation.max(kind.intrinsic(node(step, ups), strike))
+                } else {
+                    continuation
+                };
+            }
+            match step {
+                2 => second.copy_from_slice(&values[..3]),
+                1 => first.copy_from_slice(&values[..2]),
+                _ => {}
+            }
+        }
+        let [down_down, up_down, up_up] = second;
+        let [down_value, up_value] = first;
+        let (spot_up_up, spot_down_down) = (underlying * up * up, underlying * down * down);
+        let (delta_up, delta_down) = (
+            (up_up - up_down) / (spot_up_up - underlying),
+            (up_down - down_down) / (underlying - spot_down_down),
+        );
+        Some(Sensitivities {
+            price: values[0],
+            delta: (up_value - down_value) / (underlying * (up - down)),
+            gamma: (delta_up - delta_down) / (0.5 * (spot_up_up - spot_down_down)),
+            theta: (up_down - values[0]) / (2.0 * dt),
+            ..Sensitivities::ZERO
+        })
+    }
+
+    /// Vega, rho, vanna, vomma and charm as central differences of trees
+    /// with the volatility, rate or time to expiry bumped
+    fn bumped(&self, inputs: &Inputs) -> Sensitivities {
+        let bumped = |bump: &dyn Fn(&mut Inputs)| {
+            let mut inputs = *inputs;
+            bump(&mut inputs);
+            self.roll_back(&inputs).unwrap_or(Sensitivities::NAN)
+        };
+        let base = bumped(&|_| {});
+
+        let room = 0.5 * (inputs.volatility - self.min_volatility(inputs));
+        let h = VOLATILITY_BUMP.min(room);
+        let up = bumped(&|inputs| inputs.volatility += h);
+        let down = bumped(&|inputs| inputs.volatility -= h);
+        let vega = (up.price - down.price) / (2.0 * h);
+        let vanna = (up.delta - down.delta) / (2.0 * h);
+        let h = VOMMA_BUMP.min(room);
+        let up = bumped(&|inputs| inputs.volatility += h);
+        let down = bumped(&|inputs| inputs.volatility -= h);
+        let vomma = (up.price - 2.0 * base.price + down.price) / (h * h);
+
+        let up = bumped(&|inputs| {
+            inputs.rate += RATE_BUMP;
+            inputs.carry += RATE_BUMP;
+        });
+        let down = bumped(&|inputs| {
+            inputs.rate -= RATE_BUMP;
+            inputs.carry -= RATE_BUMP;
+        });
+        let rho = (up.price - down.price) / (2.0 * RATE_BUMP);
+
+        let h = TIME_BUMP.min(0.5 * inputs.time);
+        let later = bumped(&|inputs| inputs.time += h);
+        let sooner = bumped(&|inputs| inputs.time -= h);
+        Sensitivities {
+            vega,
+            rho,
+            vanna,
+            vomma,
+            charm: (sooner.delta - later.delta) / (2.0 * h),
+            ..base
+        }
+    }
+
+    /// Price of the option; NaN when the inputs are invalid or the volatility
+    /// is below `min_volatility`
+    pub fn price(&self, inputs: &Inputs) -> f64 {
+        if !inputs.valid() {
+            return f64::NAN;
+        }
+        if inputs.time == 0.0 {
+            return analytic::price(inputs);
+        }
+        self.roll_back(inputs).map_or(f64::NAN, |tree| tree.price)
+    }
+
+    /// Price and Greeks of the option; NaN throughout where `price` is
+    ///
+    /// The cost of carry moves with the rate for rho, as `r - q` does.
+    pub fn sensitivities(&self, inputs: &Inputs) -> Sensitivities {
+        if !inputs.valid() {
+            return Sensitivities::NAN;
+        }
+        if inputs.time == 0.0 {
+            return analytic::sensitivities(inputs, true);
+        }
+        let Some(mut greeks) = self.roll_back(inputs) else {
+            return Sensitivities::NAN;
+        };
+        let bumped = self.bumped(inputs);
+        let next = Tree {
+            steps: self.steps + 1,
+            ..*self
+        }
+        .bumped(inputs);
+        greeks.vega = 0.5 * (bumped.vega + next.vega);
+        greeks.rho = 0.5 * (bumped.rho + next.rho);
+        greeks.vanna = 0.5 * (bumped.vanna + next.vanna);
+        greeks.vomma = 0.5 * (bumped.vomma + next.vomma);
+        greeks.charm = 0.5 * (bumped.charm + next.charm);
+        greeks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::analytic::tests::option;
+    use crate::options::payoff::OptionKind;
+
+    const EUROPEAN: Tree = Tree {
+        steps: 500,
+        american: false,
+    };
+    const AMERICAN: Tree = Tree {
+        steps: 500,
+        american: true,
+    };
+
+    #[test]
+    fn european_trees_converge_to_black_scholes() {
+        for kind in [OptionKind::Call, OptionKind::Put] {
+            let inputs = Inputs {
+                strike: 105.0,
+                carry: 0.03,
+                ..option(kind)
+            };
+            let (tree, exact) = (EUROPEAN.sensitivities(&inputs), analytic::sensitivities(&inputs, true));
+            assert!((tree.price - exact.price).abs() < 0.01, "{tree:?}");
+            assert!((tree.delta - exact.delta).abs() < 1e-3);
+            assert!((tree.gamma - exact.gamma).abs() < 1e-3);
+            assert!((tree.theta - exact.theta).abs() < 0.02);
+            assert!((tree.vega / exact.vega - 1.0).abs() < 1e-3);
+            assert!((tree.rho / exact.rho - 1.0).abs() < 1e-2);
+            assert!((tree.vanna - exact.vanna).abs() < 1e-2);
+            assert!((tree.charm - exact.charm).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn american_options_are_worth_early_exercise() {
+        // Hull's American put: S = K = 50, five months, r = 10%, σ = 40%
+        let put = Inputs {
+            underlying: 50.0,
+            strike: 50.0,
+            time: 5.0 / 12.0,
+            rate: 0.1,
+            carry: 0.1,
+            volatility: 0.4,
+            kind: OptionKind::Put,
+        };
+        let (american, european) = (AMERICAN.price(&put), EUROPEAN.price(&put));
+        assert!((american - 4.28).abs() < 0.01, "{american}");
+        assert!(american > european + 0.1);
+        // Deep in the money it is exercised at once
+        let deep = Inputs {
+            underlying: 20.0,
+            ..put
+        };
+        assert_eq!(AMERICAN.price(&deep), 30.0);
+
+        // Calls on a spot without dividends are never exercised early
+        let call = Inputs {
+            kind: OptionKind::Call,
+            ..put
+        };
+        assert!((AMERICAN.price(&call) - EUROPEAN.price(&call)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn needs_enough_volatility_for_its_probabilities() {
+        let inputs = Inputs {
+            carry: 0.5,
+            volatility: 0.3,
+            ..option(OptionKind::Call)
+        };
+        let tree = Tree {
+            steps: 4,
+            american: true,
+        };
+        assert_eq!(tree.min_volatility(&inputs), 0.25);
+        let calm = Inputs {
+            volatility: 0.2,
+            ..inputs
+        };
+        assert!(tree.price(&calm).is_nan());
+        assert!(tree.sensitivities(&calm).values().iter().all(|value| value.is_nan()));
+        assert!(!tree.price(&inputs).is_nan());
+        // At expiry, the payoff
+        assert_eq!(
+            tree.price(&Inputs {
+                time: 0.0,
+                underlying: 101.0,
+                ..inputs
+            }),
+            1.0
+        );
+    }
+}
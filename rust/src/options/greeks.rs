@@ -0,0 +1,139 @@
+//! Option Greeks
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// Price and Greeks of one option
+///
+/// Sensitivities are per unit change: vega and vomma per 1.00 of
+/// volatility, rho per 1.00 of rate, and theta and charm per year of time
+/// passing, so theta is usually negative for a long option.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sensitivities {
+    pub price: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub theta: f64,
+    pub rho: f64,
+    pub vanna: f64,
+    pub vomma: f64,
+    pub charm: f64,
+}
+
+impl Sensitivities {
+    pub const ZERO: Sensitivities = Sensitivities {
+        price: 0.0,
+        delta: 0.0,
+        gamma: 0.0,
+        vega: 0.0,
+        theta: 0.0,
+        rho: 0.0,
+        vanna: 0.0,
+        vomma: 0.0,
+        charm: 0.0,
+    };
+
+    pub const NAN: Sensitivities = Sensitivities {
+        price: f64::NAN,
+        delta: f64::NAN,
+        gamma: f64::NAN,
+        vega: f64::NAN,
+        theta: f64::NAN,
+        rho: f64::NAN,
+        vanna: f64::NAN,
+        vomma: f64::NAN,
+        charm: f64::NAN,
+    };
+
+    /// Field names, in the order of `values`
+    pub const NAMES: [&'static str; 9] = [
+        "price", "delta", "gamma", "vega", "theta", "rho", "vanna", "vomma", "charm",
+    ];
+
+    pub fn values(&self) -> [f64; 9] {
+        [
+            self.price, self.delta, self.gamma, self.vega, self.theta, self.rho, self.vanna, self.vomma, self.charm,
+        ]
+    }
+}
+
+/// Price and Greeks of an option, or of each option in an array
+///
+/// Each field is a float when every input was a scalar and a NumPy array
+/// otherwise. First order: `delta` (to the underlying), `vega` (per 1.00 of
+/// volatility), `theta` (per year of time passing) and `rho` (per 1.00 of
+/// rate). Second order: `gamma` (delta to the underlying), `vanna` (delta to
+/// volatility), `vomma` (vega to volatility) and `charm` (delta per year of
+/// time passing).
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct Greeks {
+    /// One Python value per field of `Sensitivities`, in `NAMES` order
+    pub values: [Py<PyAny>; 9],
+}
+
+#[pymethods]
+impl Greeks {
+    #[getter]
+    fn price(&self, py: Python<'_>) -> Py<PyAny> {
+        self.values[0].clone_ref(py)
+    }
+
+    #[getter]
+    fn delta(&self, py: Python<'_>) -> Py<PyAny> {
+        self.values[1].clone_ref(py)
+    }
+
+    #[getter]
+    fn gamma(&self, py: Python<'_>) -> Py<PyAny> {
+        self.values[2].clone_ref(py)
+    }
+
+    #[getter]
+    fn vega(&self, py: Python<'_>) -> Py<PyAny> {
+        self.values[3].clone_ref(py)
+    }
+
+    #[getter]
+    fn theta(&self, py: Python<'_>) -> Py<PyAny> {
+        self.values[4].clone_ref(py)
+    }
+
+    #[getter]
+    fn rho(&self, py: Python<'_>) -> Py<PyAny> {
+        self.values[5].clone_ref(py)
+    }
+
+    #[getter]
+    fn vanna(&self, py: Python<'_>) -> Py<PyAny> {
+        self.values[6].clone_ref(py)
+    }
+
+    #[getter]
+    fn vomma(&self, py: Python<'_>) -> Py<PyAny> {
+        self.values[7].clone_ref(py)
+    }
+
+    #[getter]
+    fn charm(&self, py: Python<'_>) -> Py<PyAny> {
+        self.values[8].clone_ref(py)
+    }
+
+    /// Fields as a dict keyed by name
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (name, value) in Sensitivities::NAMES.iter().zip(&self.values) {
+            dict.set_item(name, value)?;
+        }
+        Ok(dict)
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let fields = Sensitivities::NAMES
+            .iter()
+            .zip(&self.values)
+            .map(|(name, value)| Ok(format!("{name}={}", value.bind(py).repr()?)))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(format!("Greeks({})", fields.join(", ")))
+    }
+}
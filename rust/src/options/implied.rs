@@ -0,0 +1,154 @@
+//! Implied volatility
+//!
+//! Option prices rise with volatility, so the volatility matching a price is
+//! bracketed between a floor and `MAX_VOLATILITY` and found with Brent's
+//! method, which keeps the bracket while interpolating and so converges even
+//! where vega vanishes and Newton's method would step out of range.
+
+/// Highest volatility searched: 1000%
+pub const MAX_VOLATILITY: f64 = 10.0;
+/// Convergence tolerance on the volatility
+const TOLERANCE: f64 = 1e-12;
+const MAX_ITERATIONS: usize = 200;
+
+/// Volatility in `[low, high]` at which `price_at` equals `target`, or NaN
+/// when the target lies outside the prices at the two ends
+pub fn solve(price_at: impl Fn(f64) -> f64, target: f64, low: f64, high: f64) -> f64 {
+    if !target.is_finite() {
+        return f64::NAN;
+    }
+    let f = |volatility: f64| price_at(volatility) - target;
+    let (mut a, mut b) = (low, high);
+    let (mut fa, mut fb) = (f(a), f(b));
+    if !fa.is_finite() || !fb.is_finite() {
+        return f64::NAN;
+    }
+    if fa == 0.0 {
+        return a;
+    }
+    if fb == 0.0 {
+        return b;
+    }
+    if (fa > 0.0) == (fb > 0.0) {
+        return f64::NAN;
+    }
+
+    let (mut c, mut fc) = (b, fb);
+    let mut step = b - a;
+    let mut previous_step = step;
+    for _ in 0..MAX_ITERATIONS {
+        if (fb > 0.0) == (fc > 0.0) {
+            c = a;
+            fc = fa;
+            step = b - a;
+            previous_step = step;
+        }
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a;
+            fa = fb;
+            fb = fc;
+            fc = fa;
+        }
+        let tolerance = 2.0 * f64::EPSILON * b.abs() + 0.5 * TOLERANCE;
+        let midpoint = 0.5 * (c - b);
+        if midpoint.abs() <= tolerance || fb == 0.0 {
+            return b;
+        }
+        if previous_step.abs() >= tolerance && fa.abs() > fb.abs() {
+            // Secant or inverse quadratic interpolation
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                (2.0 * midpoint * s, 1.0 - s)
+            } else {
+                let q = fa / fc;
+                let r = fb / fc;
+                (
+                    s * (2.0 * midpoint * q * (q - r) - (b - a) * (r - 1.0)),
+                    (q - 1.0) * (r - 1.0) * (s - 1.0),
+                )
+            };
+            if p > 0.0 {
+                q = -q;
+            } else {
+                p = -p;
+            }
+            if 2.0 * p < (3.0 * midpoint * q - (tolerance * q).abs()).min((previous_step * q).abs()) {
+                previous_step = step;
+                step = p / q;
+            } else {
+                step = midpoint;
+                previous_step = step;
+            }
+        } else {
+            step = midpoint;
+            previous_step = step;
+        }
+        a = b;
+        fa = fb;
+        b += if step.abs() > tolerance {
+            step
+        } else {
+            tolerance.copysign(midpoint)
+        };
+        fb = f(b);
+        if !fb.is_finite() {
+            return f64::NAN;
+        }
+    }
+    b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::analytic::tests::option;
+    use crate::options::analytic::{price, Inputs};
+    use crate::options::payoff::OptionKind;
+
+    fn implied(inputs: Inputs, target: f64) -> f64 {
+        solve(|volatility| price(&Inputs { volatility, ..inputs }), target, 0.0, MAX_VOLATILITY)
+    }
+
+    #[test]
+    fn recovers_the_volatility_of_a_price() {
+        for (kind, strike, volatility) in [
+            (OptionKind::Call, 100.0, 0.2),
+            (OptionKind::Put, 60.0, 0.9),
+            // Far out of the money, where vega is tiny
+            (OptionKind::Call, 250.0, 0.15),
+            (OptionKind::Put, 100.0, 7.5),
+        ] {
+            let inputs = Inputs {
+                strike,
+                volatility,
+                ..option(kind)
+            };
+            let solved = implied(inputs, price(&inputs));
+            assert!((solved - volatility).abs() < 1e-8, "{solved} for {volatility}");
+        }
+    }
+
+    #[test]
+    fn is_nan_outside_the_bracket() {
+        let inputs = option(OptionKind::Call);
+        // Below the discounted intrinsic value, above the spot, or not a price
+        assert!(implied(inputs, 4.0).is_nan());
+        assert!(implied(inputs, 101.0).is_nan());
+        assert!(implied(inputs, f64::NAN).is_nan());
+        // Exactly at the floor
+        assert_eq!(
+            implied(
+                inputs,
+                price(&Inputs {
+                    volatility: 0.0,
+                    ..inputs
+                })
+            ),
+            0.0
+        );
+        assert_eq!(solve(|x| x - 1.0, 0.0, -1.0, 1.0), 1.0);
+        assert!((solve(|x| x * x * x, 8.0, 0.0, 5.0) - 2.0).abs() < 1e-12);
+    }
+}
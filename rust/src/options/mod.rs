@@ -0,0 +1,29 @@
+//! Options analytics
+//!
+//! Prices, implied volatilities and first- and second-order Greeks of
+//! European options under Black-Scholes-Merton (spot with dividend yield)
+//! and Black-76 (futures), and of American options on a binomial tree. All
+//! of them take scalars or NumPy arrays and evaluate arrays in parallel.
+
+pub mod analytic;
+pub mod binomial;
+pub mod greeks;
+pub mod implied;
+pub mod models;
+pub mod normal;
+pub mod payoff;
+pub mod vector;
+
+use pyo3::prelude::*;
+
+pub use greeks::Greeks;
+pub use models::{BinomialTree, Black76, BlackScholesMerton};
+
+/// Register the options analytics classes on the extension module
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<BlackScholesMerton>()?;
+    m.add_class::<Black76>()?;
+    m.add_class::<BinomialTree>()?;
+    m.add_class::<Greeks>()?;
+    Ok(())
+}
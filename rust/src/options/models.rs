@@ -0,0 +1,407 @@
+//! Option pricing models exposed to Python
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::options::analytic::{self, Inputs};
+use crate::options::binomial::Tree;
+use crate::options::greeks::{Greeks, Sensitivities};
+use crate::options::implied::{solve, MAX_VOLATILITY};
+use crate::options::payoff::OptionKind;
+use crate::options::vector::Batch;
+
+/// Generalized Black-Scholes inputs on a spot paying a continuous dividend
+/// yield; the volatility is left at zero for the implied volatility solver
+fn spot_inputs(kind: OptionKind, [spot, strike, time, rate, volatility, dividend_yield]: [f64; 6]) -> Inputs {
+    Inputs {
+        kind,
+        underlying: spot,
+        strike,
+        time,
+        rate,
+        carry: rate - dividend_yield,
+        volatility,
+    }
+}
+
+/// Generalized Black-Scholes inputs on a futures price
+fn forward_inputs(kind: OptionKind, [forward, strike, time, rate, volatility]: [f64; 5]) -> Inputs {
+    Inputs {
+        kind,
+        underlying: forward,
+        strike,
+        time,
+        rate,
+        carry: 0.0,
+        volatility,
+    }
+}
+
+/// Volatility at which the analytic price equals `target`
+fn analytic_implied(inputs: Inputs, target: f64) -> f64 {
+    if !inputs.valid() {
+        return f64::NAN;
+    }
+    solve(
+        |volatility| analytic::price(&Inputs { volatility, ..inputs }),
+        target,
+        0.0,
+        MAX_VOLATILITY,
+    )
+}
+
+/// Black-Scholes-Merton model of European options on a spot with a
+/// continuous dividend yield
+///
+/// Every float argument is a number or a NumPy array; arrays must share a
+/// shape and results are arrays of it. `option_type` is an `OptionType`,
+/// `"call"` / `"put"`, or a sequence of them. `time` is in years and `rate`,
+/// `volatility` and `dividend_yield` are annualized and continuously
+/// compounded. Elements with invalid inputs come back as NaN.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct BlackScholesMerton;
+
+#[pymethods]
+impl BlackScholesMerton {
+    #[new]
+    fn py_new() -> Self {
+        BlackScholesMerton
+    }
+
+    /// Option price
+    #[pyo3(signature = (option_type, spot, strike, time, rate, volatility, dividend_yield=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn price(
+        &self,
+        py: Python<'_>,
+        option_type: &Bound<'_, PyAny>,
+        spot: &Bound<'_, PyAny>,
+        strike: &Bound<'_, PyAny>,
+        time: &Bound<'_, PyAny>,
+        rate: &Bound<'_, PyAny>,
+        volatility: &Bound<'_, PyAny>,
+        dividend_yield: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Py<PyAny>> {
+        let batch = Batch::new(
+            option_type,
+            [
+                ("spot", Some(spot), 0.0),
+                ("strike", Some(strike), 0.0),
+                ("time", Some(time), 0.0),
+                ("rate", Some(rate), 0.0),
+                ("volatility", Some(volatility), 0.0),
+                ("dividend_yield", dividend_yield, 0.0),
+            ],
+        )?;
+        let prices = batch.evaluate(py, |kind, values| analytic::price(&spot_inputs(kind, values)));
+        batch.output(py, &prices)
+    }
+
+    /// Price and Greeks; rho holds the dividend yield fixed
+    #[pyo3(signature = (option_type, spot, strike, time, rate, volatility, dividend_yield=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn greeks(
+        &self,
+        py: Python<'_>,
+        option_type: &Bound<'_, PyAny>,
+        spot: &Bound<'_, PyAny>,
+        strike: &Bound<'_, PyAny>,
+        time: &Bound<'_, PyAny>,
+        rate: &Bound<'_, PyAny>,
+        volatility: &Bound<'_, PyAny>,
+        dividend_yield: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Greeks> {
+        let batch = Batch::new(
+            option_type,
+            [
+                ("spot", Some(spot), 0.0),
+                ("strike", Some(strike), 0.0),
+                ("time", Some(time), 0.0),
+                ("rate", Some(rate), 0.0),
+                ("volatility", Some(volatility), 0.0),
+                ("dividend_yield", dividend_yield, 0.0),
+            ],
+        )?;
+        let greeks = batch.evaluate(py, |kind, values| analytic::sensitivities(&spot_inputs(kind, values), true));
+        batch.greeks(py, &greeks)
+    }
+
+    /// Volatility at which the model prices the option at `price`; NaN when
+    /// no volatility up to 1000% does, as below intrinsic value
+    #[pyo3(signature = (option_type, price, spot, strike, time, rate, dividend_yield=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn implied_volatility(
+        &self,
+        py: Python<'_>,
+        option_type: &Bound<'_, PyAny>,
+        price: &Bound<'_, PyAny>,
+        spot: &Bound<'_, PyAny>,
+        strike: &Bound<'_, PyAny>,
+        time: &Bound<'_, PyAny>,
+        rate: &Bound<'_, PyAny>,
+        dividend_yield: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Py<PyAny>> {
+        let batch = Batch::new(
+            option_type,
+            [
+                ("price", Some(price), 0.0),
+                ("spot", Some(spot), 0.0),
+                ("strike", Some(strike), 0.0),
+                ("time", Some(time), 0.0),
+                ("rate", Some(rate), 0.0),
+                ("dividend_yield", dividend_yield, 0.0),
+            ],
+        )?;
+        let volatilities = batch.evaluate(py, |kind, [price, spot, strike, time, rate, dividend_yield]| {
+            analytic_implied(spot_inputs(kind, [spot, strike, time, rate, 0.0, dividend_yield]), price)
+        });
+        batch.output(py, &volatilities)
+    }
+
+    fn __repr__(&self) -> &'static str {
+        "BlackScholesMerton()"
+    }
+}
+
+/// Black-76 model of European options on futures
+///
+/// Arguments are as for `BlackScholesMerton`, with the futures price in
+/// place of the spot and no dividend yield. Rho is taken with the futures
+/// price fixed.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct Black76;
+
+#[pymethods]
+impl Black76 {
+    #[new]
+    fn py_new() -> Self {
+        Black76
+    }
+
+    /// Option price
+    #[allow(clippy::too_many_arguments)]
+    fn price(
+        &self,
+        py: Python<'_>,
+        option_type: &Bound<'_, PyAny>,
+        forward: &Bound<'_, PyAny>,
+        strike: &Bound<'_, PyAny>,
+        time: &Bound<'_, PyAny>,
+        rate: &Bound<'_, PyAny>,
+        volatility: &Bound<'_, PyAny>,
+    ) -> PyResult<Py<PyAny>> {
+        let batch = Batch::new(
+            option_type,
+            [
+                ("forward", Some(forward), 0.0),
+                ("strike", Some(strike), 0.0),
+                ("time", Some(time), 0.0),
+                ("rate", Some(rate), 0.0),
+                ("volatility", Some(volatility), 0.0),
+            ],
+        )?;
+        let prices = batch.evaluate(py, |kind, values| analytic::price(&forward_inputs(kind, values)));
+        batch.output(py, &prices)
+    }
+
+    /// Price and Greeks; delta, gamma, vanna and charm are to the futures price
+    #[allow(clippy::too_many_arguments)]
+    fn greeks(
+        &self,
+        py: Python<'_>,
+        option_type: &Bound<'_, PyAny>,
+        forward: &Bound<'_, PyAny>,
+        strike: &Bound<'_, PyAny>,
+        time: &Bound<'_, PyAny>,
+        rate: &Bound<'_, PyAny>,
+        volatility: &Bound<'_, PyAny>,
+    ) -> PyResult<Greeks> {
+        let batch = Batch::new(
+            option_type,
+            [
+                ("forward", Some(forward), 0.0),
+                ("strike", Some(strike), 0.0),
+                ("time", Some(time), 0.0),
+                ("rate", Some(rate), 0.0),
+                ("volatility", Some(volatility), 0.0),
+            ],
+        )?;
+        let greeks = batch.evaluate(py, |kind, values| analytic::sensitivities(&forward_inputs(kind, values), false));
+        batch.greeks(py, &greeks)
+    }
+
+    /// Volatility at which the model prices the option at `price`; NaN when
+    /// no volatility up to 1000% does
+    #[allow(clippy::too_many_arguments)]
+    fn implied_volatility(
+        &self,
+        py: Python<'_>,
+        option_type: &Bound<'_, PyAny>,
+        price: &Bound<'_, PyAny>,
+        forward: &Bound<'_, PyAny>,
+        strike: &Bound<'_, PyAny>,
+        time: &Bound<'_, PyAny>,
+        rate: &Bound<'_, PyAny>,
+    ) -> PyResult<Py<PyAny>> {
+        let batch = Batch::new(
+            option_type,
+            [
+                ("price", Some(price), 0.0),
+                ("forward", Some(forward), 0.0),
+                ("strike", Some(strike), 0.0),
+                ("time", Some(time), 0.0),
+                ("rate", Some(rate), 0.0),
+            ],
+        )?;
+        let volatilities = batch.evaluate(py, |kind, [price, forward, strike, time, rate]| {
+            analytic_implied(forward_inputs(kind, [forward, strike, time, rate, 0.0]), price)
+        });
+        batch.output(py, &volatilities)
+    }
+
+    fn __repr__(&self) -> &'static str {
+        "Black76()"
+    }
+}
+
+/// Cox-Ross-Rubinstein binomial model of American or European options on a
+/// spot with a continuous dividend yield
+///
+/// Arguments are as for `BlackScholesMerton`. The tree needs a volatility of
+/// at least `|rate - dividend_yield| * sqrt(time / steps)`; below it prices
+/// are NaN. Vega, rho, vanna, vomma and charm are central differences of
+/// bumped trees, averaged over trees of `steps` and `steps + 1`.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct BinomialTree {
+    tree: Tree,
+}
+
+#[pymethods]
+impl BinomialTree {
+    #[new]
+    #[pyo3(signature = (steps=200, american=true))]
+    fn py_new(steps: usize, american: bool) -> PyResult<Self> {
+        if steps < 2 {
+            return Err(PyValueError::new_err("steps must be at least 2"));
+        }
+        Ok(BinomialTree {
+            tree: Tree { steps, american },
+        })
+    }
+
+    #[getter]
+    fn steps(&self) -> usize {
+        self.tree.steps
+    }
+
+    /// Whether options may be exercised at every step rather than only at expiry
+    #[getter]
+    fn american(&self) -> bool {
+        self.tree.american
+    }
+
+    /// Option price
+    #[pyo3(signature = (option_type, spot, strike, time, rate, volatility, dividend_yield=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn price(
+        &self,
+        py: Python<'_>,
+        option_type: &Bound<'_, PyAny>,
+        spot: &Bound<'_, PyAny>,
+        strike: &Bound<'_, PyAny>,
+        time: &Bound<'_, PyAny>,
+        rate: &Bound<'_, PyAny>,
+        volatility: &Bound<'_, PyAny>,
+        dividend_yield: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Py<PyAny>> {
+        let batch = Batch::new(
+            option_type,
+            [
+                ("spot", Some(spot), 0.0),
+                ("strike", Some(strike), 0.0),
+                ("time", Some(time), 0.0),
+                ("rate", Some(rate), 0.0),
+                ("volatility", Some(volatility), 0.0),
+                ("dividend_yield", dividend_yield, 0.0),
+            ],
+        )?;
+        let tree = self.tree;
+        let prices = batch.evaluate(py, |kind, values| tree.price(&spot_inputs(kind, values)));
+        batch.output(py, &prices)
+    }
+
+    /// Price and Greeks; rho holds the dividend yield fixed
+    #[pyo3(signature = (option_type, spot, strike, time, rate, volatility, dividend_yield=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn greeks(
+        &self,
+        py: Python<'_>,
+        option_type: &Bound<'_, PyAny>,
+        spot: &Bound<'_, PyAny>,
+        strike: &Bound<'_, PyAny>,
+        time: &Bound<'_, PyAny>,
+        rate: &Bound<'_, PyAny>,
+        volatility: &Bound<'_, PyAny>,
+        dividend_yield: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Greeks> {
+        let batch = Batch::new(
+            option_type,
+            [
+                ("spot", Some(spot), 0.0),
+                ("strike", Some(strike), 0.0),
+                ("time", Some(time), 0.0),
+                ("rate", Some(rate), 0.0),
+                ("volatility", Some(volatility), 0.0),
+                ("dividend_yield", dividend_yield, 0.0),
+            ],
+        )?;
+        let tree = self.tree;
+        let greeks: Vec<Sensitivities> =
+            batch.evaluate(py, |kind, values| tree.sensitivities(&spot_inputs(kind, values)));
+        batch.greeks(py, &greeks)
+    }
+
+    /// Volatility at which the tree prices the option at `price`; NaN when
+    /// no volatility the tree supports up to 1000% does
+    #[pyo3(signature = (option_type, price, spot, strike, time, rate, dividend_yield=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn implied_volatility(
+        &self,
+        py: Python<'_>,
+        option_type: &Bound<'_, PyAny>,
+        price: &Bound<'_, PyAny>,
+        spot: &Bound<'_, PyAny>,
+        strike: &Bound<'_, PyAny>,
+        time: &Bound<'_, PyAny>,
+        rate: &Bound<'_, PyAny>,
+        dividend_yield: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Py<PyAny>> {
+        let batch = Batch::new(
+            option_type,
+            [
+                ("price", Some(price), 0.0),
+                ("spot", Some(spot), 0.0),
+                ("strike", Some(strike), 0.0),
+                ("time", Some(time), 0.0),
+                ("rate", Some(rate), 0.0),
+                ("dividend_yield", dividend_yield, 0.0),
+            ],
+        )?;
+        let tree = self.tree;
+        let volatilities = batch.evaluate(py, |kind, [price, spot, strike, time, rate, dividend_yield]| {
+            let inputs = spot_inputs(kind, [spot, strike, time, rate, 0.0, dividend_yield]);
+            if !inputs.valid() {
+                return f64::NAN;
+            }
+            // Just above the lowest volatility the tree supports
+            let low = tree.min_volatility(&inputs).max(1e-6) * (1.0 + 1e-9);
+            solve(|volatility| tree.price(&Inputs { volatility, ..inputs }), price, low, MAX_VOLATILITY)
+        });
+        batch.output(py, &volatilities)
+    }
+
+    fn __repr__(&self) -> String {
+        let american = if self.tree.american { "True" } else { "False" };
+        format!("BinomialTree(steps={}, american={american})", self.tree.steps)
+    }
+}
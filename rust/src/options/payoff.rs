@@ -0,0 +1,42 @@
+//! Option rights and their payoffs
+
+use pyo3::exceptions::PyValueError;
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::types::PyString;
+
+/// Call or put
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionKind {
+    Call,
+    Put,
+}
+
+impl OptionKind {
+    /// `1` for a call, `-1` for a put, so that prices read `sign * (S - K)`
+    pub fn sign(self) -> f64 {
+        match self {
+            OptionKind::Call => 1.0,
+            OptionKind::Put => -1.0,
+        }
+    }
+
+    /// Value of exercising now at `spot`
+    pub fn intrinsic(self, spot: f64, strike: f64) -> f64 {
+        (self.sign() * (spot - strike)).max(0.0)
+    }
+
+    /// Parse an `OptionType`, or its value `"call"` / `"put"`
+    pub fn extract(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let value = if obj.is_instance_of::<PyString>() {
+            obj.clone()
+        } else {
+            obj.getattr(intern!(obj.py(), "value"))?
+        };
+        match value.extract::<String>()?.to_ascii_lowercase().as_str() {
+            "call" | "c" => Ok(OptionKind::Call),
+            "put" | "p" => Ok(OptionKind::Put),
+            other => Err(PyValueError::new_err(format!("Unknown option type: {other}"))),
+        }
+    }
+}
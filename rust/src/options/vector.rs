@@ -0,0 +1,194 @@
+//! Scalar and NumPy array arguments
+//!
+//! Every float argument of the option models is a number or an array of
+//! them; the arrays must share one shape and scalars apply to every element.
+//! Results come back as floats when every argument was a scalar and as
+//! arrays of the common shape otherwise. Elements are evaluated in parallel
+//! without the GIL.
+
+use pyo3::buffer::PyBuffer;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyFloat, PyInt, PyString, PyTuple};
+use rayon::prelude::*;
+
+use crate::interop::{numpy_asarray, numpy_empty};
+use crate::options::greeks::{Greeks, Sensitivities};
+use crate::options::payoff::OptionKind;
+
+/// Fewest elements worth spreading across threads
+const PARALLEL_THRESHOLD: usize = 256;
+
+/// A float argument
+enum Arg {
+    Scalar(f64),
+    Array(Vec<f64>, Vec<usize>),
+}
+
+impl Arg {
+    fn extract(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if obj.is_instance_of::<PyFloat>() || obj.is_instance_of::<PyInt>() {
+            return Ok(Arg::Scalar(obj.extract()?));
+        }
+        if let Ok(buffer) = PyBuffer::<f64>::get(obj) {
+            return Self::from_buffer(obj.py(), &buffer);
+        }
+        if let Ok(value) = obj.extract::<f64>() {
+            return Ok(Arg::Scalar(value));
+        }
+        let array = numpy_asarray(obj.py())?.call1((obj, "float64"))?;
+        Self::from_buffer(obj.py(), &PyBuffer::<f64>::get(&array)?)
+    }
+
+    fn from_buffer(py: Python<'_>, buffer: &PyBuffer<f64>) -> PyResult<Self> {
+        let values = buffer.to_vec(py)?;
+        if buffer.dimensions() == 0 {
+            return Ok(Arg::Scalar(values[0]));
+        }
+        Ok(Arg::Array(values, buffer.shape().to_vec()))
+    }
+
+    fn at(&self, index: usize) -> f64 {
+        match self {
+            Arg::Scalar(value) => *value,
+            Arg::Array(values, _) => values[index],
+        }
+    }
+}
+
+/// The option type argument: one for every element, or one per element
+enum Kinds {
+    Scalar(OptionKind),
+    Array(Vec<OptionKind>),
+}
+
+impl Kinds {
+    fn extract(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if obj.is_instance_of::<PyString>() || obj.hasattr("value")? {
+            return Ok(Kinds::Scalar(OptionKind::extract(obj)?));
+        }
+        let kinds = obj
+            .try_iter()
+            .map_err(|_| {
+                PyValueError::new_err("option_type must be an OptionType, 'call', 'put' or a sequence of them")
+            })?
+            .map(|item| OptionKind::extract(&item?))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(Kinds::Array(kinds))
+    }
+
+    fn at(&self, index: usize) -> OptionKind {
+        match self {
+            Kinds::Scalar(kind) => *kind,
+            Kinds::Array(kinds) => kinds[index],
+        }
+    }
+}
+
+/// The arguments of one call, checked to share a shape
+pub struct Batch<const N: usize> {
+    kinds: Kinds,
+    args: [Arg; N],
+    /// Common shape of the array arguments, `None` when all are scalars
+    shape: Option<Vec<usize>>,
+    len: usize,
+}
+
+impl<const N: usize> Batch<N> {
+    /// Extract `option_type` and the named float arguments, in order; an
+    /// absent argument takes its default
+    pub fn new(option_type: &Bound<'_, PyAny>, args: [(&str, Option<&Bound<'_, PyAny>>, f64); N]) -> PyResult<Self> {
+        let kinds = Kinds::extract(option_type)?;
+        let mut shape: Option<(&str, Vec<usize>)> = match &kinds {
+            Kinds::Scalar(_) => None,
+            Kinds::Array(kinds) => Some(("option_type", vec![kinds.len()])),
+        };
+        let mut extracted = Vec::with_capacity(N);
+        for (name, obj, default) in args {
+            let arg = match obj {
+                Some(obj) => Arg::extract(obj).map_err(|err| {
+                    PyValueError::new_err(format!("{name} must be a number or an array of numbers: {err}"))
+                })?,
+                None => Arg::Scalar(default),
+            };
+            if let Arg::Array(_, arg_shape) = &arg {
+                match &shape {
+                    Some((first, first_shape)) if first_shape != arg_shape => {
+                        return Err(PyValueError::new_err(format!(
+                            "{name} has shape {arg_shape:?} but {first} has shape {first_shape:?}"
+                        )));
+                    }
+                    Some(_) => {}
+                    None => shape = Some((name, arg_shape.clone())),
+                }
+            }
+            extracted.push(arg);
+        }
+        let shape = shape.map(|(_, shape)| shape);
+        let len = shape.as_ref().map_or(1, |shape| shape.iter().product());
+        let args = extracted.try_into().unwrap_or_else(|_| unreachable!("one argument extracted per name"));
+        Ok(Batch {
+            kinds,
+            args,
+            shape,
+            len,
+        })
+    }
+
+    /// Option type and float arguments of element `index`
+    pub fn row(&self, index: usize) -> (OptionKind, [f64; N]) {
+        (self.kinds.at(index), std::array::from_fn(|arg| self.args[arg].at(index)))
+    }
+
+    /// `f` of every element, in parallel for large batches
+    pub fn evaluate<T, F>(&self, py: Python<'_>, f: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(OptionKind, [f64; N]) -> T + Send + Sync,
+    {
+        if self.len < PARALLEL_THRESHOLD {
+            return (0..self.len)
+                .map(|index| {
+                    let (kind, values) = self.row(index);
+                    f(kind, values)
+                })
+                .collect();
+        }
+        py.detach(|| {
+            (0..self.len)
+                .into_par_iter()
+                .map(|index| {
+                    let (kind, values) = self.row(index);
+                    f(kind, values)
+                })
+                .collect()
+        })
+    }
+
+    /// `values` as a float, or an array of the batch's shape
+    pub fn output(&self, py: Python<'_>, values: &[f64]) -> PyResult<Py<PyAny>> {
+        let Some(shape) = &self.shape else {
+            return Ok(PyFloat::new(py, values[0]).into_any().unbind());
+        };
+        let array = numpy_empty(py)?.call1((PyTuple::new(py, shape)?,))?;
+        PyBuffer::<f64>::get(&array)?.copy_from_slice(py, values)?;
+        Ok(array.unbind())
+    }
+
+    /// `Greeks` of every element
+    pub fn greeks(&self, py: Python<'_>, sensitivities: &[Sensitivities]) -> PyResult<Greeks> {
+        let mut columns: [Vec<f64>; 9] = std::array::from_fn(|_| Vec::with_capacity(sensitivities.len()));
+        for element in sensitivities {
+            for (column, value) in columns.iter_mut().zip(element.values()) {
+                column.push(value);
+            }
+        }
+        let mut values = Vec::with_capacity(9);
+        for column in &columns {
+            values.push(self.output(py, column)?);
+        }
+        Ok(Greeks {
+            values: values.try_into().unwrap_or_else(|_| unreachable!("one value per Greek")),
+        })
+    }
+}
@@ -0,0 +1,455 @@
+//! Record-level checks and the per-series state they need
+
+use std::collections::VecDeque;
+
+use crate::data::schema::{RecordKind, MAX_WIDTH};
+use crate::quality::policy::{DataQualityPolicy, QualityAction, QualityIssue, SpikeMethod};
+use crate::types::fixed::Fixed;
+
+/// Scale of the median absolute deviation to the standard deviation of a
+/// normal distribution
+const MAD_SCALE: f64 = 1.4826;
+
+/// Returns needed before spikes are scored
+const MIN_SPIKE_HISTORY: usize = 10;
+
+/// Consecutive outlying prices after which the move is taken as a genuine
+/// level shift: the last is accepted as the new reference, but its return
+/// stays out of the history
+const SPIKE_PERSISTENCE: u32 = 3;
+
+/// What became of an inspected record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Clean,
+    Repaired,
+    Flagged,
+    Dropped,
+}
+
+/// Issues found in one record and the payload to emit
+pub struct Inspection {
+    pub issues: Vec<QualityIssue>,
+    pub outcome: Outcome,
+    pub values: [Fixed; MAX_WIDTH],
+}
+
+impl Inspection {
+    /// Record `issue` and decide what to do with it; issues that cannot be
+    /// repaired are dropped under REPAIR
+    fn resolve(&mut self, policy: &DataQualityPolicy, issue: QualityIssue) -> QualityAction {
+        self.issues.push(issue);
+        let action = match policy.action_for(issue) {
+            QualityAction::Repair if !issue.is_repairable() => QualityAction::Drop,
+            action => action,
+        };
+        self.outcome = match (action, self.outcome) {
+            (QualityAction::Drop, _) => Outcome::Dropped,
+            (QualityAction::Repair, _) => Outcome::Repaired,
+            (QualityAction::Flag, Outcome::Clean) => Outcome::Flagged,
+            (QualityAction::Flag, outcome) => outcome,
+        };
+        action
+    }
+}
+
+/// Offsets of the open-high-low-close groups in a bar payload
+fn ohlc_groups(kind: RecordKind) -> &'static [usize] {
+    match kind {
+        RecordKind::TradeBar => &[0],
+        RecordKind::QuoteBar => &[0, 4],
+        RecordKind::TradeTick | RecordKind::QuoteTick => &[],
+    }
+}
+
+/// Payload indices of the bid and ask compared for crossing
+fn quote_sides(kind: RecordKind) -> Option<(usize, usize)> {
+    match kind {
+        RecordKind::QuoteTick => Some((0, 2)),
+        RecordKind::QuoteBar => Some((3, 7)),
+        RecordKind::TradeTick | RecordKind::TradeBar => None,
+    }
+}
+
+/// Price the spike check follows: trade price, close, or closing mid
+fn reference_price(kind: RecordKind, values: &[Fixed]) -> f64 {
+    match kind {
+        RecordKind::TradeTick => values[0].to_f64(),
+        RecordKind::TradeBar => values[3].to_f64(),
+        RecordKind::QuoteTick => (values[0].to_f64() + values[2].to_f64()) / 2.0,
+        RecordKind::QuoteBar => (values[3].to_f64() + values[7].to_f64()) / 2.0,
+    }
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_unstable_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
+
+/// Distance of `x` from the centre of `history`, in units of its spread;
+/// `None` when the history has no spread
+fn spike_score(method: SpikeMethod, history: &VecDeque<f64>, x: f64) -> Option<f64> {
+    let n = history.len() as f64;
+    let (centre, spread) = match method {
+        SpikeMethod::Mad => {
+            let mut sorted: Vec<f64> = history.iter().copied().collect();
+            let centre = median(&mut sorted);
+            let mut deviations: Vec<f64> = sorted.iter().map(|r| (r - centre).abs()).collect();
+            (centre, MAD_SCALE * median(&mut deviations))
+        }
+        SpikeMethod::ZScore => {
+            let mean = history.iter().sum::<f64>() / n;
+            let variance = history.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+            (mean, variance.sqrt())
+        }
+    };
+    (spread > 0.0).then(|| (x - centre).abs() / spread)
+}
+
+/// State of one series: an instrument's records of one kind and resolution
+pub struct Series {
+    last_time: Option<i64>,
+    last_values: [Fixed; MAX_WIDTH],
+    /// Bid and ask of the last quote, and when they last changed
+    quote: Option<(Fixed, Fixed, i64)>,
+    /// Reference price of the last accepted record
+    reference: Option<f64>,
+    /// Recent accepted log returns
+    returns: VecDeque<f64>,
+    /// Consecutive outlying prices
+    outliers: u32,
+}
+
+impl Default for Series {
+    fn default() -> Self {
+        Series {
+            last_time: None,
+            last_values: [Fixed::ZERO; MAX_WIDTH],
+            quote: None,
+            reference: None,
+            returns: VecDeque::new(),
+            outliers: 0,
+        }
+    }
+}
+
+impl Series {
+    /// Check one record and update the series with it unless it is dropped
+    pub fn inspect(
+        &mut self,
+        policy: &DataQualityPolicy,
+        kind: RecordKind,
+        time: i64,
+        mut values: [Fixed; MAX_WIDTH],
+    ) -> Inspection {
+        let width = kind.width();
+        let mut inspection = Inspection {
+            issues: Vec::new(),
+            outcome: Outcome::Clean,
+            values,
+        };
+
+        let positive = (0..width).all(|i| kind.is_size(i) || values[i].is_positive());
+        if !positive && inspection.resolve(policy, QualityIssue::NonPositivePrice) == QualityAction::Drop {
+            return inspection;
+        }
+
+        for &offset in ohlc_groups(kind) {
+            let [open, high, low, close] = [
+                values[offset],
+                values[offset + 1],
+                values[offset + 2],
+                values[offset + 3],
+            ];
+            if low <= open && open <= high && low <= close && close <= high {
+                continue;
+            }
+            match inspection.resolve(policy, QualityIssue::OhlcInconsistent) {
+                QualityAction::Drop => return inspection,
+                QualityAction::Repair => {
+                    values[offset + 1] = open.max(high).max(low).max(close);
+                    values[offset + 2] = open.min(high).min(low).min(close);
+                }
+                QualityAction::Flag => {}
+            }
+        }
+
+        if let Some((bid, ask)) = quote_sides(kind) {
+            if values[bid] > values[ask] {
+                match inspection.resolve(policy, QualityIssue::CrossedQuote) {
+                    QualityAction::Drop => return inspection,
+                    QualityAction::Repair => {
+                        // Bid and ask fields sit in two equal halves of the payload
+                        let half = width / 2;
+                        let (bids, asks) = values[..width].split_at_mut(half);
+                        bids.swap_with_slice(asks);
+                    }
+                    QualityAction::Flag => {}
+                }
+            } else if values[bid] == values[ask]
+                && inspection.resolve(policy, QualityIssue::LockedQuote) == QualityAction::Drop
+            {
+                return inspection;
+            }
+        }
+
+        let mut in_order = true;
+        if let Some(last) = self.last_time {
+            let duplicate = match kind {
+                RecordKind::TradeBar | RecordKind::QuoteBar => time == last,
+                RecordKind::TradeTick | RecordKind::QuoteTick => time == last && values == self.last_values,
+            };
+            if time < last {
+                in_order = false;
+                if inspection.resolve(policy, QualityIssue::OutOfOrder) == QualityAction::Drop {
+                    return inspection;
+                }
+            } else if duplicate && inspection.resolve(policy, QualityIssue::DuplicateTimestamp) == QualityAction::Drop {
+                return inspection;
+            }
+        }
+
+        let mut quote = self.quote;
+        if let Some((bid, ask)) = quote_sides(kind) {
+            let (bid, ask) = (values[bid], values[ask]);
+            match quote {
+                Some((last_bid, last_ask, since)) if last_bid == bid && last_ask == ask => {
+                    let stale = policy.stale_after.is_some_and(|after| time - since > after);
+                    if stale && inspection.resolve(policy, QualityIssue::StaleQuote) == QualityAction::Drop {
+                        return inspection;
+                    }
+                }
+                _ => quote = Some((bid, ask, time)),
+            }
+        }
+
+        let mut accepted_return = None;
+        let mut outlier = false;
+        let price = reference_price(kind, &values);
+        if let (Some(threshold), Some(reference), true) = (policy.spike_threshold, self.reference, positive) {
+            let r = (price / reference).ln();
+            let history = self.returns.len() >= MIN_SPIKE_HISTORY;
+            let score = if history {
+                spike_score(policy.spike_method, &self.returns, r)
+            } else {
+                None
+            };
+            let spike = score.is_some_and(|score| score > threshold);
+            if spike && self.outliers + 1 < SPIKE_PERSISTENCE {
+                outlier = true;
+                if inspection.resolve(policy, QualityIssue::PriceSpike) == QualityAction::Drop {
+                    self.outliers += 1;
+                    return inspection;
+                }
+            } else if !spike {
+                accepted_return = Some(r);
+            }
+        }
+
+        // The record stays in the stream: fold it into the series
+        if in_order {
+            self.last_time = Some(time);
+            self.last_values = values;
+        }
+        self.quote = quote;
+        if outlier {
+            self.outliers += 1;
+        } else if positive {
+            self.outliers = 0;
+            self.reference = Some(price);
+            if let Some(r) = accepted_return {
+                if self.returns.len() == policy.spike_window {
+                    self.returns.pop_front();
+                }
+                self.returns.push_back(r);
+            }
+        }
+        inspection.values = values;
+        inspection
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: i64 = 1_000_000_000;
+
+    fn payload(values: &[&str]) -> [Fixed; MAX_WIDTH] {
+        let mut out = [Fixed::ZERO; MAX_WIDTH];
+        for (slot, value) in out.iter_mut().zip(values) {
+            *slot = value.parse().unwrap();
+        }
+        out
+    }
+
+    fn policy(default: QualityAction) -> DataQualityPolicy {
+        DataQualityPolicy {
+            default,
+            ..DataQualityPolicy::default()
+        }
+    }
+
+    #[test]
+    fn clean_records_pass() {
+        let policy = DataQualityPolicy::default();
+        let mut series = Series::default();
+        let bar = payload(&["10", "11", "9", "10.5", "100"]);
+        let inspection = series.inspect(&policy, RecordKind::TradeBar, 0, bar);
+        assert_eq!(inspection.outcome, Outcome::Clean);
+        assert!(inspection.issues.is_empty());
+        assert_eq!(inspection.values, bar);
+    }
+
+    #[test]
+    fn non_positive_prices_cannot_be_repaired() {
+        let tick = payload(&["0", "5"]);
+        let mut series = Series::default();
+        let inspection = series.inspect(&policy(QualityAction::Repair), RecordKind::TradeTick, 0, tick);
+        assert_eq!(inspection.issues, [QualityIssue::NonPositivePrice]);
+        assert_eq!(inspection.outcome, Outcome::Dropped);
+        // Zero sizes are fine
+        let inspection = series.inspect(&policy(QualityAction::Drop), RecordKind::TradeTick, 0, payload(&["1", "0"]));
+        assert_eq!(inspection.outcome, Outcome::Clean);
+    }
+
+    #[test]
+    fn repairs_inconsistent_bars() {
+        let bar = payload(&["10", "9.5", "9", "8", "100"]);
+        let mut series = Series::default();
+        let inspection = series.inspect(&policy(QualityAction::Repair), RecordKind::TradeBar, 0, bar);
+        assert_eq!(inspection.issues, [QualityIssue::OhlcInconsistent]);
+        assert_eq!(inspection.outcome, Outcome::Repaired);
+        assert_eq!(inspection.values, payload(&["10", "10", "8", "8", "100"]));
+
+        let flagged = Series::default().inspect(&policy(QualityAction::Flag), RecordKind::TradeBar, 0, bar);
+        assert_eq!(flagged.outcome, Outcome::Flagged);
+        assert_eq!(flagged.values, bar);
+    }
+
+    #[test]
+    fn crossed_and_locked_quotes() {
+        let crossed = payload(&["10.1", "5", "10", "7"]);
+        let repaired = Series::default().inspect(&policy(QualityAction::Repair), RecordKind::QuoteTick, 0, crossed);
+        assert_eq!(repaired.issues, [QualityIssue::CrossedQuote]);
+        assert_eq!(repaired.values, payload(&["10", "7", "10.1", "5"]));
+
+        // Crossed quote bars swap every bid field with its ask field
+        let bar = payload(&["2", "2", "2", "2", "1", "1", "1", "1"]);
+        let repaired = Series::default().inspect(&policy(QualityAction::Repair), RecordKind::QuoteBar, 0, bar);
+        assert_eq!(repaired.values, payload(&["1", "1", "1", "1", "2", "2", "2", "2"]));
+
+        let locked = payload(&["10", "5", "10", "7"]);
+        let dropped = Series::default().inspect(&policy(QualityAction::Repair), RecordKind::QuoteTick, 0, locked);
+        assert_eq!(dropped.issues, [QualityIssue::LockedQuote]);
+        assert_eq!(dropped.outcome, Outcome::Dropped);
+    }
+
+    #[test]
+    fn duplicates_and_out_of_order_records() {
+        let policy = policy(QualityAction::Drop);
+        let mut series = Series::default();
+        let tick = payload(&["10", "1"]);
+        assert_eq!(series.inspect(&policy, RecordKind::TradeTick, SECOND, tick).outcome, Outcome::Clean);
+        // Ticks sharing a timestamp are only duplicates when identical
+        let other = payload(&["10", "2"]);
+        assert_eq!(series.inspect(&policy, RecordKind::TradeTick, SECOND, other).outcome, Outcome::Clean);
+        let duplicate = series.inspect(&policy, RecordKind::TradeTick, SECOND, other);
+        assert_eq!(duplicate.issues, [QualityIssue::DuplicateTimestamp]);
+        let late = series.inspect(&policy, RecordKind::TradeTick, 0, tick);
+        assert_eq!(late.issues, [QualityIssue::OutOfOrder]);
+
+        // Bars are duplicates by timestamp alone
+        let mut bars = Series::default();
+        let bar = payload(&["10", "10", "10", "10", "1"]);
+        bars.inspect(&policy, RecordKind::TradeBar, SECOND, bar);
+        let next = payload(&["11", "11", "11", "11", "1"]);
+        assert_eq!(
+            bars.inspect(&policy, RecordKind::TradeBar, SECOND, next).issues,
+            [QualityIssue::DuplicateTimestamp]
+        );
+    }
+
+    #[test]
+    fn flagged_out_of_order_records_keep_the_latest_time() {
+        let policy = policy(QualityAction::Flag);
+        let mut series = Series::default();
+        let tick = payload(&["10", "1"]);
+        series.inspect(&policy, RecordKind::TradeTick, 2 * SECOND, tick);
+        assert_eq!(series.inspect(&policy, RecordKind::TradeTick, SECOND, tick).outcome, Outcome::Flagged);
+        let again = series.inspect(&policy, RecordKind::TradeTick, SECOND, payload(&["10", "2"]));
+        assert_eq!(again.issues, [QualityIssue::OutOfOrder]);
+    }
+
+    #[test]
+    fn stale_quotes() {
+        let policy = DataQualityPolicy {
+            stale_after: Some(5 * SECOND),
+            ..DataQualityPolicy::default()
+        };
+        let mut series = Series::default();
+        let quote = payload(&["10", "1", "10.1", "1"]);
+        for time in [0, 3, 5] {
+            let inspection = series.inspect(&policy, RecordKind::QuoteTick, time * SECOND, quote);
+            assert_eq!(inspection.outcome, Outcome::Clean);
+        }
+        let stale = series.inspect(&policy, RecordKind::QuoteTick, 6 * SECOND, quote);
+        assert_eq!(stale.issues, [QualityIssue::StaleQuote]);
+        // A changed quote starts over
+        let moved = payload(&["10", "1", "10.2", "1"]);
+        assert_eq!(series.inspect(&policy, RecordKind::QuoteTick, 7 * SECOND, moved).outcome, Outcome::Clean);
+    }
+
+    /// Outcomes of a run of trade prices, one a second
+    fn run(policy: &DataQualityPolicy, prices: &[f64]) -> Vec<Outcome> {
+        let mut series = Series::default();
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| {
+                let values = payload(&[&format!("{price:.4}"), "1"]);
+                series.inspect(policy, RecordKind::TradeTick, i as i64 * SECOND, values).outcome
+            })
+            .collect()
+    }
+
+    /// Prices alternating around 100 by small, uneven steps
+    fn wiggle(n: usize) -> Vec<f64> {
+        (0..n).map(|i| 100.0 + [0.0, 0.1, -0.05, 0.12, -0.08][i % 5]).collect()
+    }
+
+    #[test]
+    fn drops_price_spikes() {
+        for method in [SpikeMethod::Mad, SpikeMethod::ZScore] {
+            let policy = DataQualityPolicy {
+                spike_method: method,
+                ..DataQualityPolicy::default()
+            };
+            let mut prices = wiggle(15);
+            prices.push(150.0);
+            prices.extend(wiggle(3));
+            let outcomes = run(&policy, &prices);
+            assert_eq!(outcomes[15], Outcome::Dropped, "{method:?}");
+            assert_eq!(outcomes.iter().filter(|&&outcome| outcome != Outcome::Clean).count(), 1);
+
+            let disabled = DataQualityPolicy {
+                spike_threshold: None,
+                ..policy
+            };
+            assert!(run(&disabled, &prices).iter().all(|&outcome| outcome == Outcome::Clean));
+        }
+    }
+
+    #[test]
+    fn persistent_moves_become_the_new_level() {
+        let mut prices = wiggle(15);
+        prices.extend([150.0, 150.1, 150.0, 150.1]);
+        let outcomes = run(&DataQualityPolicy::default(), &prices);
+        assert_eq!(outcomes[15..], [Outcome::Dropped, Outcome::Dropped, Outcome::Clean, Outcome::Clean]);
+    }
+}
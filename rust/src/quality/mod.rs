@@ -0,0 +1,31 @@
+//! Market data quality checks
+//!
+//! `DataQualityProvider` sits between a data provider and the engine and
+//! checks every record for crossed or locked quotes, non-positive prices,
+//! inconsistent bars, duplicate and out-of-order timestamps, stale quotes
+//! and price spikes. A `DataQualityPolicy` drops, repairs or flags each
+//! issue, and a `DataQualityReport` counts them per instrument.
+
+pub mod check;
+pub mod policy;
+pub mod provider;
+pub mod report;
+
+use pyo3::prelude::*;
+
+pub use policy::{DataQualityPolicy, QualityAction, QualityIssue, SpikeMethod};
+pub use provider::{DataQualityIterator, DataQualityProvider};
+pub use report::{DataQualityReport, InstrumentQuality};
+
+/// Register the data quality classes on the extension module
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<QualityIssue>()?;
+    m.add_class::<QualityAction>()?;
+    m.add_class::<SpikeMethod>()?;
+    m.add_class::<DataQualityPolicy>()?;
+    m.add_class::<InstrumentQuality>()?;
+    m.add_class::<DataQualityReport>()?;
+    m.add_class::<DataQualityProvider>()?;
+    m.add_class::<DataQualityIterator>()?;
+    Ok(())
+}
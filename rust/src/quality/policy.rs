@@ -0,0 +1,277 @@
+//! Data quality issues and the policy deciding what happens to them
+
+use std::collections::HashMap;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::interop::timedelta_type;
+use crate::types::time::{timedelta_to_nanos, NANOS_PER_MICRO};
+
+/// A problem found in a market data record
+#[pyclass(module = "_simulor_rust", eq, eq_int, frozen, hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QualityIssue {
+    /// Quote with the bid above the ask
+    #[pyo3(name = "CROSSED_QUOTE")]
+    CrossedQuote,
+    /// Quote with the bid equal to the ask
+    #[pyo3(name = "LOCKED_QUOTE")]
+    LockedQuote,
+    /// Zero or negative price
+    #[pyo3(name = "NON_POSITIVE_PRICE")]
+    NonPositivePrice,
+    /// Open or close outside the bar's low-high range
+    #[pyo3(name = "OHLC_INCONSISTENT")]
+    OhlcInconsistent,
+    /// Bar repeating the previous bar's timestamp, or tick repeating the
+    /// previous tick exactly
+    #[pyo3(name = "DUPLICATE_TIMESTAMP")]
+    DuplicateTimestamp,
+    /// Record older than the one before it
+    #[pyo3(name = "OUT_OF_ORDER")]
+    OutOfOrder,
+    /// Quote prices unchanged for longer than the policy's `stale_after`
+    #[pyo3(name = "STALE_QUOTE")]
+    StaleQuote,
+    /// Price move far outside the recent distribution of moves
+    #[pyo3(name = "PRICE_SPIKE")]
+    PriceSpike,
+}
+
+impl QualityIssue {
+    pub const ALL: [QualityIssue; 8] = [
+        QualityIssue::CrossedQuote,
+        QualityIssue::LockedQuote,
+        QualityIssue::NonPositivePrice,
+        QualityIssue::OhlcInconsistent,
+        QualityIssue::DuplicateTimestamp,
+        QualityIssue::OutOfOrder,
+        QualityIssue::StaleQuote,
+        QualityIssue::PriceSpike,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            QualityIssue::CrossedQuote => "CROSSED_QUOTE",
+            QualityIssue::LockedQuote => "LOCKED_QUOTE",
+            QualityIssue::NonPositivePrice => "NON_POSITIVE_PRICE",
+            QualityIssue::OhlcInconsistent => "OHLC_INCONSISTENT",
+            QualityIssue::DuplicateTimestamp => "DUPLICATE_TIMESTAMP",
+            QualityIssue::OutOfOrder => "OUT_OF_ORDER",
+            QualityIssue::StaleQuote => "STALE_QUOTE",
+            QualityIssue::PriceSpike => "PRICE_SPIKE",
+        }
+    }
+
+    /// Whether a REPAIR action can fix the record; other issues are dropped
+    pub fn is_repairable(self) -> bool {
+        matches!(self, QualityIssue::CrossedQuote | QualityIssue::OhlcInconsistent)
+    }
+}
+
+#[pymethods]
+impl QualityIssue {
+    /// Member name, e.g. "CROSSED_QUOTE"
+    #[getter]
+    fn name(&self) -> &'static str {
+        self.as_str()
+    }
+}
+
+/// What to do with a record that has an issue
+#[pyclass(module = "_simulor_rust", eq, eq_int, frozen, hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QualityAction {
+    /// Remove the record from the stream
+    #[pyo3(name = "DROP")]
+    Drop,
+    /// Fix the record where possible, otherwise drop it
+    #[pyo3(name = "REPAIR")]
+    Repair,
+    /// Keep the record as it is and count the issue
+    #[pyo3(name = "FLAG")]
+    Flag,
+}
+
+impl QualityAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            QualityAction::Drop => "DROP",
+            QualityAction::Repair => "REPAIR",
+            QualityAction::Flag => "FLAG",
+        }
+    }
+}
+
+/// How price spikes are scored
+#[pyclass(module = "_simulor_rust", eq, eq_int, frozen, hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpikeMethod {
+    /// Distance from the median in scaled median absolute deviations
+    #[pyo3(name = "MAD")]
+    Mad,
+    /// Distance from the mean in standard deviations
+    #[pyo3(name = "Z_SCORE")]
+    ZScore,
+}
+
+impl SpikeMethod {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SpikeMethod::Mad => "MAD",
+            SpikeMethod::ZScore => "Z_SCORE",
+        }
+    }
+}
+
+/// What the quality stage checks and what it does with each issue
+///
+/// Args: `default` action for issues not listed in `actions`, a dict of
+/// `QualityIssue` to `QualityAction`; `spike_method`, `spike_threshold` and
+/// `spike_window` for spike detection, which scores each log return against
+/// the last `spike_window` accepted returns of the series (`None` disables
+/// it); `stale_after`, a `timedelta` after which an unchanged quote counts
+/// as stale (`None` disables the check).
+///
+/// REPAIR swaps the sides of a crossed quote and widens a bar's high and
+/// low to cover its open and close; other issues cannot be repaired and are
+/// dropped instead.
+#[pyclass(module = "_simulor_rust", frozen)]
+#[derive(Debug, Clone)]
+pub struct DataQualityPolicy {
+    pub default: QualityAction,
+    pub actions: HashMap<QualityIssue, QualityAction>,
+    pub spike_method: SpikeMethod,
+    pub spike_threshold: Option<f64>,
+    pub spike_window: usize,
+    pub stale_after: Option<i64>,
+}
+
+impl Default for DataQualityPolicy {
+    fn default() -> Self {
+        DataQualityPolicy {
+            default: QualityAction::Drop,
+            actions: HashMap::new(),
+            spike_method: SpikeMethod::Mad,
+            spike_threshold: Some(10.0),
+            spike_window: 50,
+            stale_after: None,
+        }
+    }
+}
+
+impl DataQualityPolicy {
+    pub fn action_for(&self, issue: QualityIssue) -> QualityAction {
+        self.actions.get(&issue).copied().unwrap_or(self.default)
+    }
+}
+
+#[pymethods]
+impl DataQualityPolicy {
+    #[new]
+    #[pyo3(signature = (
+        default=QualityAction::Drop,
+        actions=None,
+        spike_method=SpikeMethod::Mad,
+        spike_threshold=Some(10.0),
+        spike_window=50,
+        stale_after=None,
+    ))]
+    fn py_new(
+        default: QualityAction,
+        actions: Option<HashMap<QualityIssue, QualityAction>>,
+        spike_method: SpikeMethod,
+        spike_threshold: Option<f64>,
+        spike_window: usize,
+        stale_after: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Self> {
+        if spike_threshold.is_some_and(|threshold| !threshold.is_finite() || threshold <= 0.0) {
+            return Err(PyValueError::new_err("spike_threshold must be positive"));
+        }
+        if spike_window < 2 {
+            return Err(PyValueError::new_err("spike_window must be at least 2"));
+        }
+        let stale_after = stale_after.map(timedelta_to_nanos).transpose()?;
+        if stale_after.is_some_and(|nanos| nanos <= 0) {
+            return Err(PyValueError::new_err("stale_after must be positive"));
+        }
+        Ok(DataQualityPolicy {
+            default,
+            actions: actions.unwrap_or_default(),
+            spike_method,
+            spike_threshold,
+            spike_window,
+            stale_after,
+        })
+    }
+
+    /// Action taken for `issue`
+    fn action(&self, issue: QualityIssue) -> QualityAction {
+        self.action_for(issue)
+    }
+
+    /// Action for issues without one of their own
+    #[getter(default)]
+    fn py_default(&self) -> QualityAction {
+        self.default
+    }
+
+    /// Actions set per issue
+    #[getter(actions)]
+    fn py_actions<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for issue in QualityIssue::ALL {
+            if let Some(action) = self.actions.get(&issue) {
+                dict.set_item(issue, *action)?;
+            }
+        }
+        Ok(dict)
+    }
+
+    #[getter(spike_method)]
+    fn py_spike_method(&self) -> SpikeMethod {
+        self.spike_method
+    }
+
+    #[getter(spike_threshold)]
+    fn py_spike_threshold(&self) -> Option<f64> {
+        self.spike_threshold
+    }
+
+    #[getter(spike_window)]
+    fn py_spike_window(&self) -> usize {
+        self.spike_window
+    }
+
+    /// Age at which an unchanged quote is stale, or `None`
+    #[getter(stale_after)]
+    fn py_stale_after<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        self.stale_after
+            .map(|nanos| timedelta_type(py)?.call1((0, 0, nanos / NANOS_PER_MICRO)))
+            .transpose()
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let actions: Vec<String> = QualityIssue::ALL
+            .iter()
+            .filter_map(|issue| {
+                self.actions.get(issue).map(|action| format!("{}: {}", issue.as_str(), action.as_str()))
+            })
+            .collect();
+        let threshold = self.spike_threshold.map_or("None".to_owned(), |threshold| threshold.to_string());
+        let stale_after = match self.py_stale_after(py)? {
+            Some(delta) => delta.repr()?.to_string(),
+            None => "None".to_owned(),
+        };
+        Ok(format!(
+            "DataQualityPolicy(default={}, actions={{{}}}, spike_method={}, spike_threshold={threshold}, \
+             spike_window={}, stale_after={stale_after})",
+            self.default.as_str(),
+            actions.join(", "),
+            self.spike_method.as_str(),
+            self.spike_window,
+        ))
+    }
+}
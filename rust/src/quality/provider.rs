@@ -0,0 +1,206 @@
+//! Data provider that checks another provider's stream
+
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use pyo3::types::PyIterator;
+
+use crate::data::schema::{payload, RecordKind};
+use crate::events::market_event::MarketEvent;
+use crate::interop::logger;
+use crate::quality::check::{Outcome, Series};
+use crate::quality::policy::DataQualityPolicy;
+use crate::quality::report::DataQualityReport;
+use crate::types::instrument::InstrumentId;
+use crate::types::market_data::{MarketData, Resolution};
+
+const LOGGER: &str = "simulor.data.quality";
+
+/// Wrap a data provider and check its records before they reach the engine
+///
+/// Each record is checked against the previous records of its series (its
+/// instrument's records of the same type and resolution) and dropped,
+/// repaired or flagged according to `policy`, which defaults to dropping
+/// every issue. Events left without records are skipped. Records that are
+/// not native are passed through unchecked.
+///
+/// `report` holds the per-instrument counts of the latest pass. Wrap a
+/// native provider created with `validate=False` to also see the rows it
+/// would otherwise skip silently, such as crossed quotes and broken bars.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct DataQualityProvider {
+    source: Py<PyAny>,
+    policy: Py<DataQualityPolicy>,
+    report: Py<DataQualityReport>,
+}
+
+#[pymethods]
+impl DataQualityProvider {
+    #[new]
+    #[pyo3(signature = (source, policy=None))]
+    fn py_new(py: Python<'_>, source: Py<PyAny>, policy: Option<Py<DataQualityPolicy>>) -> PyResult<Self> {
+        let policy = match policy {
+            Some(policy) => policy,
+            None => Py::new(py, DataQualityPolicy::default())?,
+        };
+        Ok(DataQualityProvider {
+            source,
+            policy,
+            report: Py::new(py, DataQualityReport::default())?,
+        })
+    }
+
+    /// The wrapped provider
+    #[getter]
+    fn source(&self, py: Python<'_>) -> Py<PyAny> {
+        self.source.clone_ref(py)
+    }
+
+    #[getter]
+    fn policy(&self, py: Python<'_>) -> Py<DataQualityPolicy> {
+        self.policy.clone_ref(py)
+    }
+
+    /// Quality report of the latest pass over the data
+    #[getter]
+    fn report(&self, py: Python<'_>) -> Py<DataQualityReport> {
+        self.report.clone_ref(py)
+    }
+
+    /// Return a new iterator; every pass starts a fresh report
+    fn __iter__(&self, py: Python<'_>) -> PyResult<DataQualityIterator> {
+        self.report.get().reset();
+        Ok(DataQualityIterator {
+            source: self.source.bind(py).try_iter()?.unbind(),
+            policy: self.policy.get().clone(),
+            report: self.report.clone_ref(py),
+            series: HashMap::new(),
+            logger: logger(py, LOGGER)?.unbind(),
+            done: false,
+        })
+    }
+}
+
+/// Iterator over a `DataQualityProvider`
+#[pyclass(module = "_simulor_rust")]
+pub struct DataQualityIterator {
+    source: Py<PyIterator>,
+    policy: DataQualityPolicy,
+    report: Py<DataQualityReport>,
+    series: HashMap<(InstrumentId, RecordKind, Resolution), Series>,
+    logger: Py<PyAny>,
+    done: bool,
+}
+
+impl DataQualityIterator {
+    /// Check one record; returns the record to emit, if any, and whether it
+    /// differs from the input
+    fn check<'py>(&mut self, record: &Bound<'py, PyAny>) -> PyResult<(Option<Bound<'py, PyAny>>, bool)> {
+        let py = record.py();
+        let Some((kind, values, direction)) = payload(record) else {
+            return Ok((Some(record.clone()), false));
+        };
+        let base = record.cast::<MarketData>()?.get();
+        let id = base.instrument_id(py)?;
+        let time = base.timestamp_nanos(py)?;
+        let series = self.series.entry((id, kind, base.native_resolution)).or_default();
+        let inspection = series.inspect(&self.policy, kind, time, values);
+        let instrument = base.instrument.bind(py);
+        self.report.get().record(id, instrument, &inspection.issues, inspection.outcome);
+        if inspection.outcome != Outcome::Clean {
+            let issues: Vec<&str> = inspection.issues.iter().map(|issue| issue.as_str()).collect();
+            let outcome = match inspection.outcome {
+                Outcome::Repaired => "Repaired",
+                Outcome::Flagged => "Flagged",
+                _ => "Dropped",
+            };
+            self.logger.bind(py).call_method1(
+                "debug",
+                (
+                    format!("{outcome} %s record of %s at %s: {}", issues.join(", ")),
+                    record.get_type().name()?,
+                    instrument,
+                    base.timestamp.bind(py),
+                ),
+            )?;
+        }
+        Ok(match inspection.outcome {
+            Outcome::Clean | Outcome::Flagged => (Some(record.clone()), false),
+            Outcome::Dropped => (None, true),
+            Outcome::Repaired => {
+                let base = MarketData::from_parts(
+                    base.timestamp.clone_ref(py),
+                    base.instrument.clone_ref(py),
+                    base.resolution.clone_ref(py),
+                    base.native_resolution,
+                    time,
+                );
+                let repaired = kind.build(py, base, &inspection.values[..kind.width()], direction)?;
+                (Some(repaired), true)
+            }
+        })
+    }
+
+    /// The event with its records checked, or `None` if none are left
+    fn process<'py>(&mut self, event: Bound<'py, PyAny>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let py = event.py();
+        let records = event.call_method0("flatten")?;
+        let mut kept = Vec::new();
+        let mut changed = false;
+        for record in records.try_iter()? {
+            let (output, modified) = self.check(&record?)?;
+            changed |= modified;
+            kept.extend(output);
+        }
+        if !changed {
+            return Ok(Some(event));
+        }
+        if kept.is_empty() {
+            return Ok(None);
+        }
+        let mut output = MarketEvent::new(event.getattr("time")?.unbind());
+        for record in &kept {
+            output.add_record(record)?;
+        }
+        Ok(Some(Bound::new(py, output)?.into_any()))
+    }
+
+    fn log_summary(&self, py: Python<'_>) -> PyResult<()> {
+        let (records, dropped, repaired, flagged, _) = self.report.get().totals();
+        self.logger.bind(py).call_method1(
+            "info",
+            (
+                "Data quality: checked %d records, dropped %d, repaired %d, flagged %d",
+                records,
+                dropped,
+                repaired,
+                flagged,
+            ),
+        )?;
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl DataQualityIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        while !self.done {
+            match self.source.bind(py).clone().next() {
+                Some(event) => {
+                    if let Some(event) = self.process(event?)? {
+                        return Ok(Some(event.unbind()));
+                    }
+                }
+                None => {
+                    self.done = true;
+                    self.log_summary(py)?;
+                }
+            }
+        }
+        Ok(None)
+    }
+}
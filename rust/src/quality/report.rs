@@ -0,0 +1,245 @@
+//! Per-instrument counts of what the quality stage found and did
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use pyo3::exceptions::PyKeyError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::quality::check::Outcome;
+use crate::quality::policy::QualityIssue;
+use crate::types::instrument::{default_registry, InstrumentId};
+
+/// Counts for one instrument
+struct Counts {
+    instrument: Py<PyAny>,
+    records: u64,
+    dropped: u64,
+    repaired: u64,
+    flagged: u64,
+    issues: [u64; QualityIssue::ALL.len()],
+}
+
+impl Counts {
+    fn snapshot(&self, py: Python<'_>) -> InstrumentQuality {
+        InstrumentQuality {
+            instrument: self.instrument.clone_ref(py),
+            records: self.records,
+            dropped: self.dropped,
+            repaired: self.repaired,
+            flagged: self.flagged,
+            issues: self.issues,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Tally {
+    /// Instruments in the order they were first seen
+    counts: Vec<Counts>,
+    index: HashMap<InstrumentId, usize>,
+}
+
+fn issues_dict<'py>(py: Python<'py>, issues: &[u64]) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for (issue, count) in QualityIssue::ALL.iter().zip(issues) {
+        if *count > 0 {
+            dict.set_item(*issue, count)?;
+        }
+    }
+    Ok(dict)
+}
+
+/// Data quality of one instrument's records
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct InstrumentQuality {
+    instrument: Py<PyAny>,
+    records: u64,
+    dropped: u64,
+    repaired: u64,
+    flagged: u64,
+    issues: [u64; QualityIssue::ALL.len()],
+}
+
+#[pymethods]
+impl InstrumentQuality {
+    #[getter]
+    fn instrument(&self, py: Python<'_>) -> Py<PyAny> {
+        self.instrument.clone_ref(py)
+    }
+
+    /// Records inspected
+    #[getter]
+    fn records(&self) -> u64 {
+        self.records
+    }
+
+    /// Records removed from the stream
+    #[getter]
+    fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Records emitted with corrected values
+    #[getter]
+    fn repaired(&self) -> u64 {
+        self.repaired
+    }
+
+    /// Records emitted unchanged despite an issue
+    #[getter]
+    fn flagged(&self) -> u64 {
+        self.flagged
+    }
+
+    /// Occurrences of each issue found, by `QualityIssue`
+    #[getter]
+    fn issues<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        issues_dict(py, &self.issues)
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "InstrumentQuality(instrument={}, records={}, dropped={}, repaired={}, flagged={})",
+            self.instrument.bind(py).repr()?,
+            self.records,
+            self.dropped,
+            self.repaired,
+            self.flagged,
+        ))
+    }
+}
+
+/// Per-instrument data quality report of a `DataQualityProvider` pass
+///
+/// Filled in as the stream is read, and reset when a new pass starts.
+/// Index it by instrument for an `InstrumentQuality`, or iterate over all.
+#[pyclass(module = "_simulor_rust", frozen)]
+#[derive(Default)]
+pub struct DataQualityReport {
+    tally: Mutex<Tally>,
+}
+
+impl DataQualityReport {
+    pub fn reset(&self) {
+        *self.tally.lock().unwrap() = Tally::default();
+    }
+
+    /// Count a record of `instrument` and what became of it
+    pub fn record(&self, id: InstrumentId, instrument: &Bound<'_, PyAny>, issues: &[QualityIssue], outcome: Outcome) {
+        let mut tally = self.tally.lock().unwrap();
+        let tally = &mut *tally;
+        let index = *tally.index.entry(id).or_insert_with(|| {
+            tally.counts.push(Counts {
+                instrument: instrument.clone().unbind(),
+                records: 0,
+                dropped: 0,
+                repaired: 0,
+                flagged: 0,
+                issues: [0; QualityIssue::ALL.len()],
+            });
+            tally.counts.len() - 1
+        });
+        let counts = &mut tally.counts[index];
+        counts.records += 1;
+        for issue in issues {
+            counts.issues[*issue as usize] += 1;
+        }
+        match outcome {
+            Outcome::Clean => {}
+            Outcome::Repaired => counts.repaired += 1,
+            Outcome::Flagged => counts.flagged += 1,
+            Outcome::Dropped => counts.dropped += 1,
+        }
+    }
+
+    /// Totals over all instruments: records, dropped, repaired, flagged and issues
+    pub fn totals(&self) -> (u64, u64, u64, u64, [u64; QualityIssue::ALL.len()]) {
+        let tally = self.tally.lock().unwrap();
+        let mut totals = (0, 0, 0, 0, [0; QualityIssue::ALL.len()]);
+        for counts in &tally.counts {
+            totals.0 += counts.records;
+            totals.1 += counts.dropped;
+            totals.2 += counts.repaired;
+            totals.3 += counts.flagged;
+            for (total, count) in totals.4.iter_mut().zip(counts.issues) {
+                *total += count;
+            }
+        }
+        totals
+    }
+}
+
+#[pymethods]
+impl DataQualityReport {
+    /// Instruments seen, in order of first appearance
+    #[getter]
+    fn instruments<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let tally = self.tally.lock().unwrap();
+        PyList::new(py, tally.counts.iter().map(|counts| counts.instrument.clone_ref(py)))
+    }
+
+    /// Records inspected, over all instruments
+    #[getter]
+    fn records(&self) -> u64 {
+        self.totals().0
+    }
+
+    #[getter]
+    fn dropped(&self) -> u64 {
+        self.totals().1
+    }
+
+    #[getter]
+    fn repaired(&self) -> u64 {
+        self.totals().2
+    }
+
+    #[getter]
+    fn flagged(&self) -> u64 {
+        self.totals().3
+    }
+
+    /// Occurrences of each issue over all instruments, by `QualityIssue`
+    #[getter]
+    fn issues<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        issues_dict(py, &self.totals().4)
+    }
+
+    fn __getitem__(&self, py: Python<'_>, instrument: &Bound<'_, PyAny>) -> PyResult<InstrumentQuality> {
+        let id = default_registry(py)?.get().lookup_instrument(instrument)?;
+        let tally = self.tally.lock().unwrap();
+        match id.and_then(|id| tally.index.get(&id)) {
+            Some(index) => Ok(tally.counts[*index].snapshot(py)),
+            None => Err(PyKeyError::new_err(instrument.clone().unbind())),
+        }
+    }
+
+    fn __contains__(&self, py: Python<'_>, instrument: &Bound<'_, PyAny>) -> PyResult<bool> {
+        let id = default_registry(py)?.get().lookup_instrument(instrument)?;
+        Ok(id.is_some_and(|id| self.tally.lock().unwrap().index.contains_key(&id)))
+    }
+
+    fn __len__(&self) -> usize {
+        self.tally.lock().unwrap().counts.len()
+    }
+
+    /// Iterate over the `InstrumentQuality` of each instrument
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let snapshots: Vec<InstrumentQuality> = {
+            let tally = self.tally.lock().unwrap();
+            tally.counts.iter().map(|counts| counts.snapshot(py)).collect()
+        };
+        Ok(PyList::new(py, snapshots)?.try_iter()?.into_any())
+    }
+
+    fn __repr__(&self) -> String {
+        let (records, dropped, repaired, flagged, _) = self.totals();
+        format!(
+            "DataQualityReport({} instruments, records={records}, dropped={dropped}, repaired={repaired}, \
+             flagged={flagged})",
+            self.__len__(),
+        )
+    }
+}
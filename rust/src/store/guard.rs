@@ -0,0 +1,147 @@
+//! When market data becomes available to a strategy
+
+use std::collections::HashMap;
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::interop::timedelta_type;
+use crate::types::market_data::Resolution;
+use crate::types::time::{timedelta_to_nanos, NANOS_PER_MICRO};
+
+const RESOLUTIONS: [Resolution; 5] = [
+    Resolution::Tick,
+    Resolution::Second,
+    Resolution::Minute,
+    Resolution::Hour,
+    Resolution::Daily,
+];
+
+create_exception!(
+    _simulor_rust,
+    LookAheadError,
+    PyRuntimeError,
+    "A market data query reached for records that were not yet available"
+);
+
+/// Availability rules of a time-fenced market store
+///
+/// A record becomes available `lag(resolution)` after its timestamp: the
+/// bar length when `bar_end` is set, since bars are stamped at their start
+/// but only known at their end, plus the publication delay in `delays`
+/// for the record's resolution. Records not yet available are hidden from
+/// lookbacks; with `strict`, a lookback that would have included one raises
+/// `LookAheadError` instead.
+#[pyclass(module = "_simulor_rust", frozen)]
+#[derive(Debug, Clone)]
+pub struct LookAheadGuard {
+    delays: HashMap<Resolution, i64>,
+    bar_end: bool,
+    pub strict: bool,
+}
+
+impl LookAheadGuard {
+    /// Nanoseconds from a record's timestamp to its availability
+    pub fn lag_nanos(&self, resolution: Resolution) -> i64 {
+        let bar = if self.bar_end { resolution.nanos() } else { 0 };
+        bar + self.delays.get(&resolution).copied().unwrap_or(0)
+    }
+}
+
+fn timedelta<'py>(py: Python<'py>, nanos: i64) -> PyResult<Bound<'py, PyAny>> {
+    timedelta_type(py)?.call1((0, 0, nanos / NANOS_PER_MICRO))
+}
+
+#[pymethods]
+impl LookAheadGuard {
+    #[new]
+    #[pyo3(signature = (delays=None, bar_end=true, strict=false))]
+    fn py_new(delays: Option<&Bound<'_, PyDict>>, bar_end: bool, strict: bool) -> PyResult<Self> {
+        let mut nanos = HashMap::new();
+        for (resolution, delay) in delays.into_iter().flat_map(|delays| delays.iter()) {
+            let delay = timedelta_to_nanos(&delay)?;
+            if delay < 0 {
+                return Err(PyValueError::new_err("publication delays must not be negative"));
+            }
+            nanos.insert(Resolution::from_py(&resolution)?, delay);
+        }
+        Ok(LookAheadGuard {
+            delays: nanos,
+            bar_end,
+            strict,
+        })
+    }
+
+    /// Publication delay per `Resolution`
+    #[getter]
+    fn delays<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for resolution in RESOLUTIONS {
+            if let Some(nanos) = self.delays.get(&resolution) {
+                dict.set_item(resolution.to_py(py)?, timedelta(py, *nanos)?)?;
+            }
+        }
+        Ok(dict)
+    }
+
+    /// Whether bars become available at their end rather than their start
+    #[getter]
+    fn bar_end(&self) -> bool {
+        self.bar_end
+    }
+
+    /// Whether a lookback reaching for unavailable records raises
+    #[getter(strict)]
+    fn py_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Time from a record's timestamp until it is available
+    fn lag<'py>(&self, py: Python<'py>, resolution: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        timedelta(py, self.lag_nanos(Resolution::from_py(resolution)?))
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let delays: Vec<String> = RESOLUTIONS
+            .iter()
+            .filter_map(|resolution| self.delays.get(resolution).map(|nanos| (resolution, nanos)))
+            .map(|(resolution, nanos)| {
+                Ok(format!("{}: {}", resolution.to_py(py)?.repr()?, timedelta(py, *nanos)?.repr()?))
+            })
+            .collect::<PyResult<_>>()?;
+        Ok(format!(
+            "LookAheadGuard(delays={{{}}}, bar_end={}, strict={})",
+            delays.join(", "),
+            if self.bar_end { "True" } else { "False" },
+            if self.strict { "True" } else { "False" },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: i64 = 60_000_000_000;
+
+    #[test]
+    fn lags_add_bar_length_and_delay() {
+        let guard = LookAheadGuard {
+            delays: HashMap::from([(Resolution::Daily, 30 * MINUTE), (Resolution::Tick, 5)]),
+            bar_end: true,
+            strict: false,
+        };
+        assert_eq!(guard.lag_nanos(Resolution::Daily), 24 * 60 * MINUTE + 30 * MINUTE);
+        assert_eq!(guard.lag_nanos(Resolution::Minute), MINUTE);
+        assert_eq!(guard.lag_nanos(Resolution::Tick), 5);
+
+        let at_start = LookAheadGuard {
+            bar_end: false,
+            ..guard
+        };
+        assert_eq!(at_start.lag_nanos(Resolution::Daily), 30 * MINUTE);
+        assert_eq!(at_start.lag_nanos(Resolution::Hour), 0);
+    }
+}
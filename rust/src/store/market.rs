@@ -0,0 +1,376 @@
+//! Market data store fenced at the engine's current time
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+
+use crate::book::l2::Book;
+use crate::book::{BookUpdate, OrderBookL2};
+use crate::data::schema::{payload, RecordKind};
+use crate::store::guard::{LookAheadError, LookAheadGuard};
+use crate::store::series::{Series, SeriesView};
+use crate::types::instrument::{default_registry, InstrumentId};
+use crate::types::market_data::{MarketData, Resolution};
+use crate::types::time::{datetime_to_nanos, nanos_to_datetime};
+
+/// Book updates of one instrument, and its book as of the first `applied`
+struct BookFeed {
+    updates: Series,
+    book: Book,
+    applied: usize,
+}
+
+/// Series of one instrument, in the order they were first seen
+struct Holdings {
+    instrument: Py<PyAny>,
+    series: Vec<(RecordKind, Resolution, Series)>,
+    book: Option<BookFeed>,
+}
+
+impl Holdings {
+    fn find(&self, kind: RecordKind, resolution: Resolution) -> Option<&Series> {
+        self.series
+            .iter()
+            .find(|(k, r, _)| *k == kind && *r == resolution)
+            .map(|(_, _, series)| series)
+    }
+}
+
+struct State {
+    now: i64,
+    now_py: Option<Py<PyAny>>,
+    holdings: HashMap<InstrumentId, Holdings>,
+}
+
+/// In-memory market data, visible only once available at the current time
+///
+/// `update` adds an event's records and moves the store's clock to the
+/// event's time. Lookbacks return a `SeriesView` of the records available
+/// by then, per the `guard`'s lags; without a guard a record is available
+/// from its timestamp. Book updates are kept apart, lagged as ticks, and
+/// also replayed into an `OrderBookL2` per instrument.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct TimeFencedStore {
+    guard: Option<Py<LookAheadGuard>>,
+    state: Mutex<State>,
+}
+
+impl TimeFencedStore {
+    fn lag_nanos(&self, resolution: Resolution) -> i64 {
+        self.guard.as_ref().map_or(0, |guard| guard.get().lag_nanos(resolution))
+    }
+
+    fn strict(&self) -> bool {
+        self.guard.as_ref().is_some_and(|guard| guard.get().strict)
+    }
+
+    /// The available part of a series, or `None` if nothing was stored
+    fn lookback(
+        &self,
+        instrument: &Bound<'_, PyAny>,
+        kind: RecordKind,
+        resolution: Resolution,
+    ) -> PyResult<Option<SeriesView>> {
+        self.fenced(instrument, resolution, |holdings| holdings.find(kind, resolution))
+    }
+
+    /// The available part of the series `find` picks from an instrument's
+    /// holdings, or `None`
+    fn fenced(
+        &self,
+        instrument: &Bound<'_, PyAny>,
+        resolution: Resolution,
+        find: impl FnOnce(&Holdings) -> Option<&Series>,
+    ) -> PyResult<Option<SeriesView>> {
+        let py = instrument.py();
+        let Some(id) = default_registry(py)?.get().lookup_instrument(instrument)? else {
+            return Ok(None);
+        };
+        let (series, now) = {
+            let state = self.state.lock().unwrap();
+            match state.holdings.get(&id).and_then(find) {
+                Some(series) => (series.clone(), state.now),
+                None => return Ok(None),
+            }
+        };
+        let available = series.available(now);
+        if self.strict() && available < series.count() {
+            let record = series.get(py, available).expect("index within the series");
+            return Err(self.violation(record.bind(py), resolution));
+        }
+        Ok(Some(series.view(available)))
+    }
+
+    /// Error for a lookback reaching for `record`
+    fn violation(&self, record: &Bound<'_, PyAny>, resolution: Resolution) -> PyErr {
+        let describe = || -> PyResult<String> {
+            let py = record.py();
+            let timestamp = record.getattr("timestamp")?;
+            let tzinfo = timestamp.getattr("tzinfo")?;
+            let tzinfo = (!tzinfo.is_none()).then_some(&tzinfo);
+            let available = datetime_to_nanos(&timestamp)? + self.lag_nanos(resolution);
+            let now = self.state.lock().unwrap().now_py.as_ref().map(|now| now.clone_ref(py));
+            Ok(format!(
+                "{} of {} at {} is not available until {} (now {})",
+                record.get_type().name()?,
+                record.getattr("instrument")?.getattr("symbol")?.str()?,
+                timestamp.str()?,
+                nanos_to_datetime(py, available, tzinfo)?.str()?,
+                match now {
+                    Some(now) => now.bind(py).str()?.to_string(),
+                    None => "before the first update".to_owned(),
+                },
+            ))
+        };
+        match describe() {
+            Ok(message) => LookAheadError::new_err(message),
+            Err(err) => err,
+        }
+    }
+}
+
+#[pymethods]
+impl TimeFencedStore {
+    #[new]
+    #[pyo3(signature = (guard=None))]
+    fn py_new(guard: Option<Py<LookAheadGuard>>) -> Self {
+        TimeFencedStore {
+            guard,
+            state: Mutex::new(State {
+                now: i64::MIN,
+                now_py: None,
+                holdings: HashMap::new(),
+            }),
+        }
+    }
+
+    #[getter]
+    fn guard(&self, py: Python<'_>) -> Option<Py<LookAheadGuard>> {
+        self.guard.as_ref().map(|guard| guard.clone_ref(py))
+    }
+
+    /// Current time of the store, or `None` before the first update
+    #[getter]
+    fn now(&self, py: Python<'_>) -> Option<Py<PyAny>> {
+        let state = self.state.lock().unwrap();
+        state.now_py.as_ref().map(|now| now.clone_ref(py))
+    }
+
+    /// Move the store's clock forward to `time`
+    fn advance(&self, time: &Bound<'_, PyAny>) -> PyResult<()> {
+        let nanos = datetime_to_nanos(time)?;
+        let mut state = self.state.lock().unwrap();
+        if nanos < state.now {
+            return Err(PyValueError::new_err("the store's time cannot move backwards"));
+        }
+        state.now = nanos;
+        state.now_py = Some(time.clone().unbind());
+        Ok(())
+    }
+
+    /// Add the records of `market_event` and advance to its time
+    fn update(&self, market_event: &Bound<'_, PyAny>) -> PyResult<()> {
+        let py = market_event.py();
+        self.advance(&market_event.getattr("time")?)?;
+        let registry = default_registry(py)?;
+        for record in market_event.call_method0("flatten")?.try_iter()? {
+            let record = record?;
+            let base = record.cast::<MarketData>()?.get();
+            let id = registry.get().intern_instrument(base.instrument.bind(py))?;
+            if record.is_instance_of::<BookUpdate>() {
+                let available = base.timestamp_nanos(py)?.saturating_add(self.lag_nanos(Resolution::Tick));
+                let mut state = self.state.lock().unwrap();
+                let holdings = state.holdings.entry(id).or_insert_with(|| Holdings {
+                    instrument: base.instrument.clone_ref(py),
+                    series: Vec::new(),
+                    book: None,
+                });
+                let feed = holdings.book.get_or_insert_with(|| BookFeed {
+                    updates: Series::default(),
+                    book: Book::default(),
+                    applied: 0,
+                });
+                feed.updates.push(record.unbind(), available);
+                continue;
+            }
+            let Some((kind, _, _)) = payload(&record) else {
+                return Err(PyTypeError::new_err(format!("Unknown data type: {}", record.get_type().name()?)));
+            };
+            // Ticks are kept together whatever resolution they are labelled with
+            let resolution = match kind {
+                RecordKind::TradeTick | RecordKind::QuoteTick => Resolution::Tick,
+                RecordKind::TradeBar | RecordKind::QuoteBar => base.native_resolution,
+            };
+            let available = base.timestamp_nanos(py)?.saturating_add(self.lag_nanos(resolution));
+
+            let mut state = self.state.lock().unwrap();
+            let holdings = state.holdings.entry(id).or_insert_with(|| Holdings {
+                instrument: base.instrument.clone_ref(py),
+                series: Vec::new(),
+                book: None,
+            });
+            if holdings.find(kind, resolution).is_none() {
+                holdings.series.push((kind, resolution, Series::default()));
+            }
+            let series = holdings.find(kind, resolution).expect("series just added");
+            series.push(record.unbind(), available);
+        }
+        Ok(())
+    }
+
+    /// Available trade ticks of `instrument`, or `None` if none were stored
+    fn trade_ticks(&self, instrument: &Bound<'_, PyAny>) -> PyResult<Option<SeriesView>> {
+        self.lookback(instrument, RecordKind::TradeTick, Resolution::Tick)
+    }
+
+    /// Available quote ticks of `instrument`, or `None` if none were stored
+    fn quote_ticks(&self, instrument: &Bound<'_, PyAny>) -> PyResult<Option<SeriesView>> {
+        self.lookback(instrument, RecordKind::QuoteTick, Resolution::Tick)
+    }
+
+    /// Available trade bars of `instrument` at `resolution`, or `None` if
+    /// none were stored
+    fn trade_bars(&self, instrument: &Bound<'_, PyAny>, resolution: &Bound<'_, PyAny>) -> PyResult<Option<SeriesView>> {
+        self.lookback(instrument, RecordKind::TradeBar, Resolution::from_py(resolution)?)
+    }
+
+    /// Available quote bars of `instrument` at `resolution`, or `None` if
+    /// none were stored
+    fn quote_bars(&self, instrument: &Bound<'_, PyAny>, resolution: &Bound<'_, PyAny>) -> PyResult<Option<SeriesView>> {
+        self.lookback(instrument, RecordKind::QuoteBar, Resolution::from_py(resolution)?)
+    }
+
+    /// Available book updates of `instrument`, or `None` if none were stored
+    fn book_updates(&self, instrument: &Bound<'_, PyAny>) -> PyResult<Option<SeriesView>> {
+        self.fenced(instrument, Resolution::Tick, |holdings| holdings.book.as_ref().map(|feed| &feed.updates))
+    }
+
+    /// Copy of `instrument`'s book with the available updates applied, or
+    /// `None` if none were stored; never raises under a strict guard
+    fn order_book(&self, py: Python<'_>, instrument: &Bound<'_, PyAny>) -> PyResult<Option<OrderBookL2>> {
+        let Some(id) = default_registry(py)?.get().lookup_instrument(instrument)? else {
+            return Ok(None);
+        };
+        let (pending, from) = {
+            let state = self.state.lock().unwrap();
+            let Some(feed) = state.holdings.get(&id).and_then(|holdings| holdings.book.as_ref()) else {
+                return Ok(None);
+            };
+            let available = feed.updates.available(state.now);
+            let pending: Vec<Py<PyAny>> =
+                (feed.applied..available).filter_map(|index| feed.updates.get(py, index)).collect();
+            (pending, feed.applied)
+        };
+        // Timestamps are read before taking the lock again
+        let mut stamped = Vec::with_capacity(pending.len());
+        for record in pending {
+            let nanos = record.bind(py).cast::<MarketData>()?.get().timestamp_nanos(py)?;
+            stamped.push((record, nanos));
+        }
+        let mut state = self.state.lock().unwrap();
+        let Some(holdings) = state.holdings.get_mut(&id) else {
+            return Ok(None);
+        };
+        let instrument = holdings.instrument.clone_ref(py);
+        let Some(feed) = holdings.book.as_mut() else {
+            return Ok(None);
+        };
+        // Another query may have caught the book up meanwhile
+        if feed.applied == from {
+            for (record, nanos) in stamped {
+                let update = record.bind(py).cast::<BookUpdate>()?;
+                feed.book.apply(update.get(), update.as_super().get().timestamp.clone_ref(py), nanos);
+                feed.applied += 1;
+            }
+        }
+        Ok(Some(OrderBookL2::from_book(instrument, id, feed.book.clone_ref(py))))
+    }
+
+    /// Most recent available record of `instrument` of any type and
+    /// resolution, or `None`; never raises under a strict guard
+    fn latest(&self, py: Python<'_>, instrument: &Bound<'_, PyAny>) -> PyResult<Option<Py<PyAny>>> {
+        let Some(id) = default_registry(py)?.get().lookup_instrument(instrument)? else {
+            return Ok(None);
+        };
+        let candidates: Vec<Py<PyAny>> = {
+            let state = self.state.lock().unwrap();
+            let Some(holdings) = state.holdings.get(&id) else {
+                return Ok(None);
+            };
+            holdings
+                .series
+                .iter()
+                .filter_map(|(_, _, series)| series.available(state.now).checked_sub(1).and_then(|i| series.get(py, i)))
+                .collect()
+        };
+        let mut latest: Option<(i64, Py<PyAny>)> = None;
+        for record in candidates {
+            let time = record.bind(py).cast::<MarketData>()?.get().timestamp_nanos(py)?;
+            if latest.as_ref().map_or(true, |(best, _)| time > *best) {
+                latest = Some((time, record));
+            }
+        }
+        Ok(latest.map(|(_, record)| record))
+    }
+
+    /// Instruments with available records
+    fn instruments<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let state = self.state.lock().unwrap();
+        let instruments: Vec<Py<PyAny>> = state
+            .holdings
+            .values()
+            .filter(|holdings| {
+                let books = holdings.book.iter().map(|feed| &feed.updates);
+                let mut series = holdings.series.iter().map(|(_, _, series)| series).chain(books);
+                series.any(|series| series.available(state.now) > 0)
+            })
+            .map(|holdings| holdings.instrument.clone_ref(py))
+            .collect();
+        PyList::new(py, instruments)
+    }
+
+    /// Carry `old`'s records over to `new`, ahead of any `new` already has
+    ///
+    /// Records keep their original instrument. Views taken before the
+    /// rename are unaffected.
+    fn rename(&self, py: Python<'_>, old: &Bound<'_, PyAny>, new: &Bound<'_, PyAny>) -> PyResult<()> {
+        let registry = default_registry(py)?;
+        let Some(old_id) = registry.get().lookup_instrument(old)? else {
+            return Ok(());
+        };
+        let new_id = registry.get().intern_instrument(new)?;
+        if old_id == new_id {
+            return Ok(());
+        }
+        let mut state = self.state.lock().unwrap();
+        let Some(moved) = state.holdings.remove(&old_id) else {
+            return Ok(());
+        };
+        let holdings = state.holdings.entry(new_id).or_insert_with(|| Holdings {
+            instrument: new.clone().unbind(),
+            series: Vec::new(),
+            book: None,
+        });
+        for (kind, resolution, series) in moved.series {
+            match holdings.series.iter_mut().find(|(k, r, _)| *k == kind && *r == resolution) {
+                Some((_, _, existing)) => *existing = series.chain(py, existing),
+                None => holdings.series.push((kind, resolution, series)),
+            }
+        }
+        if let Some(moved) = moved.book {
+            holdings.book = Some(match holdings.book.take() {
+                // The book is rebuilt from the chained updates on next query
+                Some(existing) => BookFeed {
+                    updates: moved.updates.chain(py, &existing.updates),
+                    book: Book::default(),
+                    applied: 0,
+                },
+                None => moved,
+            });
+        }
+        Ok(())
+    }
+}
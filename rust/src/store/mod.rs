@@ -0,0 +1,26 @@
+//! Time-fenced market data store
+//!
+//! `TimeFencedStore` backs `simulor.data.MarketStore`. It tracks the
+//! engine's current time and hides the records not yet available then: a
+//! `LookAheadGuard` makes bars available at their end rather than at the
+//! start they are stamped with, adds a publication delay per resolution,
+//! and can raise `LookAheadError` when a lookback reaches for hidden data.
+
+pub mod guard;
+pub mod market;
+pub mod series;
+
+use pyo3::prelude::*;
+
+pub use guard::{LookAheadError, LookAheadGuard};
+pub use market::TimeFencedStore;
+pub use series::SeriesView;
+
+/// Register the store classes on the extension module
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("LookAheadError", m.py().get_type::<LookAheadError>())?;
+    m.add_class::<LookAheadGuard>()?;
+    m.add_class::<SeriesView>()?;
+    m.add_class::<TimeFencedStore>()?;
+    Ok(())
+}
@@ -0,0 +1,140 @@
+//! Append-only record series and read-only views of their available part
+
+use std::sync::{Arc, Mutex};
+
+use pyo3::basic::CompareOp;
+use pyo3::exceptions::PyIndexError;
+use pyo3::prelude::*;
+use pyo3::types::{PyList, PySlice};
+
+#[derive(Default)]
+struct Records {
+    records: Vec<Py<PyAny>>,
+    /// Latest availability time of each record and all before it
+    available: Vec<i64>,
+}
+
+/// One instrument's records of one type and resolution, in arrival order
+///
+/// A record arriving out of order keeps the later ones hidden until it is
+/// available itself, so a view is always a prefix of the series.
+#[derive(Clone, Default)]
+pub struct Series {
+    records: Arc<Mutex<Records>>,
+}
+
+impl Series {
+    pub fn push(&self, record: Py<PyAny>, available: i64) {
+        let mut records = self.records.lock().unwrap();
+        let available = records.available.last().map_or(available, |last| available.max(*last));
+        records.records.push(record);
+        records.available.push(available);
+    }
+
+    /// A new series holding the records of `self` then those of `other`
+    pub fn chain(&self, py: Python<'_>, other: &Series) -> Series {
+        let chained = Series::default();
+        for series in [self, other] {
+            let records = series.records.lock().unwrap();
+            for (record, available) in records.records.iter().zip(&records.available) {
+                chained.push(record.clone_ref(py), *available);
+            }
+        }
+        chained
+    }
+
+    /// Records stored, available or not
+    pub fn count(&self) -> usize {
+        self.records.lock().unwrap().records.len()
+    }
+
+    /// Records available at `now`
+    pub fn available(&self, now: i64) -> usize {
+        self.records.lock().unwrap().available.partition_point(|time| *time <= now)
+    }
+
+    pub fn get(&self, py: Python<'_>, index: usize) -> Option<Py<PyAny>> {
+        let records = self.records.lock().unwrap();
+        records.records.get(index).map(|record| record.clone_ref(py))
+    }
+
+    /// View of the first `len` records
+    pub fn view(&self, len: usize) -> SeriesView {
+        SeriesView {
+            series: self.clone(),
+            len,
+        }
+    }
+}
+
+/// Read-only sequence of the records of a series available at query time
+///
+/// Records arriving later do not show up in an existing view. Slicing
+/// returns a list.
+#[pyclass(module = "_simulor_rust", frozen, sequence)]
+pub struct SeriesView {
+    series: Series,
+    len: usize,
+}
+
+impl SeriesView {
+    fn records(&self, py: Python<'_>) -> Vec<Py<PyAny>> {
+        let records = self.series.records.lock().unwrap();
+        records.records[..self.len].iter().map(|record| record.clone_ref(py)).collect()
+    }
+}
+
+#[pymethods]
+impl SeriesView {
+    fn __len__(&self) -> usize {
+        self.len
+    }
+
+    fn __getitem__(&self, py: Python<'_>, index: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        if let Ok(slice) = index.cast::<PySlice>() {
+            let indices = slice.indices(self.len as isize)?;
+            let records = self.series.records.lock().unwrap();
+            let picked: Vec<Py<PyAny>> = (0..indices.slicelength)
+                .map(|i| records.records[(indices.start + i as isize * indices.step) as usize].clone_ref(py))
+                .collect();
+            drop(records);
+            return Ok(PyList::new(py, picked)?.into_any().unbind());
+        }
+        let index: isize = index.extract()?;
+        let position = if index < 0 { index + self.len as isize } else { index };
+        if position < 0 || position as usize >= self.len {
+            return Err(PyIndexError::new_err("SeriesView index out of range"));
+        }
+        Ok(self.series.get(py, position as usize).expect("view within its series"))
+    }
+
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        Ok(PyList::new(py, self.records(py))?.try_iter()?.into_any())
+    }
+
+    fn count(&self, py: Python<'_>, value: &Bound<'_, PyAny>) -> PyResult<usize> {
+        PyList::new(py, self.records(py))?.call_method1("count", (value,))?.extract()
+    }
+
+    #[pyo3(signature = (value, start=0, stop=None))]
+    fn index(&self, py: Python<'_>, value: &Bound<'_, PyAny>, start: isize, stop: Option<isize>) -> PyResult<usize> {
+        let stop = stop.unwrap_or(self.len as isize);
+        PyList::new(py, self.records(py))?.call_method1("index", (value, start, stop))?.extract()
+    }
+
+    fn __richcmp__(&self, py: Python<'_>, other: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<Py<PyAny>> {
+        if !matches!(op, CompareOp::Eq | CompareOp::Ne) {
+            return Ok(py.NotImplemented());
+        }
+        let records = PyList::new(py, self.records(py))?;
+        let result = match other.cast::<SeriesView>() {
+            Ok(view) => records.rich_compare(PyList::new(py, view.get().records(py))?, op)?,
+            Err(_) => records.rich_compare(other, op)?,
+        };
+        Ok(result.unbind())
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!("SeriesView({})", PyList::new(py, self.records(py))?.repr()?))
+    }
+}
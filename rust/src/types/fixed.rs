@@ -0,0 +1,584 @@
+//! Fixed-point decimal arithmetic
+//!
+//! `Fixed` stores a value as an `i128` mantissa scaled by `10^-scale`. Every
+//! operation is checked: anything that would overflow or silently drop digits
+//! returns a `FixedError` instead of producing a wrong number.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use pyo3::exceptions::{PyOverflowError, PyValueError, PyZeroDivisionError};
+use pyo3::prelude::*;
+
+/// Largest supported scale (matches the default precision of `decimal`)
+pub const MAX_SCALE: u8 = 28;
+
+/// Powers of ten that fit in an `i128` (10^0 ..= 10^38)
+const POW10: [i128; 39] = {
+    let mut table = [1i128; 39];
+    let mut i = 1;
+    while i < table.len() {
+        table[i] = table[i - 1] * 10;
+        i += 1;
+    }
+    table
+};
+
+/// Modulus used by CPython's numeric hash (64-bit builds)
+const PY_HASH_MODULUS: u128 = (1 << 61) - 1;
+/// Inverse of 10 modulo `PY_HASH_MODULUS`
+const PY_HASH_10INV: u128 = 2_075_258_708_292_324_556;
+
+/// Errors raised by fixed-point operations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FixedError {
+    Overflow,
+    DivisionByZero,
+    Inexact { scale: u8 },
+    ScaleOutOfRange(u32),
+    NonPositiveTick,
+    InvalidLiteral(String),
+}
+
+impl fmt::Display for FixedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FixedError::Overflow => write!(f, "fixed-point overflow"),
+            FixedError::DivisionByZero => write!(f, "division by zero"),
+            FixedError::Inexact { scale } => {
+                write!(f, "value cannot be represented exactly at scale {scale}")
+            }
+            FixedError::ScaleOutOfRange(scale) => {
+                write!(f, "scale {scale} is out of range (max {MAX_SCALE})")
+            }
+            FixedError::NonPositiveTick => write!(f, "tick size must be positive"),
+            FixedError::InvalidLiteral(literal) => write!(f, "invalid decimal literal: {literal:?}"),
+        }
+    }
+}
+
+impl std::error::Error for FixedError {}
+
+impl From<FixedError> for PyErr {
+    fn from(err: FixedError) -> PyErr {
+        match err {
+            FixedError::Overflow => PyOverflowError::new_err(err.to_string()),
+            FixedError::DivisionByZero => PyZeroDivisionError::new_err(err.to_string()),
+            _ => PyValueError::new_err(err.to_string()),
+        }
+    }
+}
+
+/// Rounding strategy, mirroring the `decimal.ROUND_*` constants
+#[pyclass(module = "_simulor_rust", eq, eq_int, frozen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// Round to nearest, ties to even (banker's rounding)
+    #[pyo3(name = "HALF_EVEN")]
+    HalfEven,
+    /// Round to nearest, ties away from zero
+    #[pyo3(name = "HALF_UP")]
+    HalfUp,
+    /// Round to nearest, ties toward zero
+    #[pyo3(name = "HALF_DOWN")]
+    HalfDown,
+    /// Round away from zero
+    #[pyo3(name = "UP")]
+    Up,
+    /// Round toward zero (truncate)
+    #[pyo3(name = "DOWN")]
+    Down,
+    /// Round toward positive infinity
+    #[pyo3(name = "CEILING")]
+    Ceiling,
+    /// Round toward negative infinity
+    #[pyo3(name = "FLOOR")]
+    Floor,
+}
+
+/// Divide `n` by `d` and round the quotient to an integer
+fn div_round(n: i128, d: i128, mode: RoundingMode) -> Result<i128, FixedError> {
+    if d == 0 {
+        return Err(FixedError::DivisionByZero);
+    }
+
+    let negative = (n < 0) != (d < 0);
+    let (num, den) = (n.unsigned_abs(), d.unsigned_abs());
+    let (quot, rem) = (num / den, num % den);
+
+    let away = if rem == 0 {
+        false
+    } else {
+        match mode {
+            RoundingMode::Down => false,
+            RoundingMode::Up => true,
+            RoundingMode::Ceiling => !negative,
+            RoundingMode::Floor => negative,
+            RoundingMode::HalfUp | RoundingMode::HalfDown | RoundingMode::HalfEven => match rem.cmp(&(den - rem)) {
+                Ordering::Greater => true,
+                Ordering::Less => false,
+                Ordering::Equal => match mode {
+                    RoundingMode::HalfUp => true,
+                    RoundingMode::HalfDown => false,
+                    _ => quot % 2 == 1,
+                },
+            },
+        }
+    };
+
+    let magnitude = if away { quot + 1 } else { quot };
+    let value = i128::try_from(magnitude).map_err(|_| FixedError::Overflow)?;
+    Ok(if negative { -value } else { value })
+}
+
+fn pow10(exp: u32) -> Result<i128, FixedError> {
+    POW10.get(exp as usize).copied().ok_or(FixedError::Overflow)
+}
+
+fn check_scale(scale: u32) -> Result<u8, FixedError> {
+    if scale > MAX_SCALE as u32 {
+        return Err(FixedError::ScaleOutOfRange(scale));
+    }
+    Ok(scale as u8)
+}
+
+/// 128-bit fixed-point decimal: `raw * 10^-scale`
+#[derive(Debug, Clone, Copy)]
+pub struct Fixed {
+    raw: i128,
+    scale: u8,
+}
+
+impl Fixed {
+    pub const ZERO: Fixed = Fixed { raw: 0, scale: 0 };
+
+    pub fn new(raw: i128, scale: u8) -> Result<Self, FixedError> {
+        check_scale(scale as u32)?;
+        Ok(Fixed { raw, scale })
+    }
+
+    pub fn from_int(value: i128) -> Self {
+        Fixed { raw: value, scale: 0 }
+    }
+
+    pub fn raw(&self) -> i128 {
+        self.raw
+    }
+
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.raw == 0
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.raw > 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.raw < 0
+    }
+
+    pub fn checked_neg(self) -> Result<Self, FixedError> {
+        let raw = self.raw.checked_neg().ok_or(FixedError::Overflow)?;
+        Ok(Fixed { raw, scale: self.scale })
+    }
+
+    pub fn checked_abs(self) -> Result<Self, FixedError> {
+        let raw = self.raw.checked_abs().ok_or(FixedError::Overflow)?;
+        Ok(Fixed { raw, scale: self.scale })
+    }
+
+    /// Strip trailing zeros without changing the value
+    pub fn normalize(self) -> Self {
+        self.normalize_to(0)
+    }
+
+    /// Strip trailing zeros, keeping at least `min_scale` fractional digits
+    fn normalize_to(self, min_scale: u8) -> Self {
+        let mut out = self;
+        while out.scale > min_scale && out.raw % 10 == 0 {
+            out.raw /= 10;
+            out.scale -= 1;
+        }
+        out
+    }
+
+    /// Change the scale, rounding with `mode` when digits are dropped
+    pub fn rescale(self, scale: u8, mode: RoundingMode) -> Result<Self, FixedError> {
+        let scale = check_scale(scale as u32)?;
+        match scale.cmp(&self.scale) {
+            Ordering::Equal => Ok(self),
+            Ordering::Greater => {
+                let factor = pow10((scale - self.scale) as u32)?;
+                let raw = self.raw.checked_mul(factor).ok_or(FixedError::Overflow)?;
+                Ok(Fixed { raw, scale })
+            }
+            Ordering::Less => {
+                let factor = pow10((self.scale - scale) as u32)?;
+                Ok(Fixed {
+                    raw: div_round(self.raw, factor, mode)?,
+                    scale,
+                })
+            }
+        }
+    }
+
+    /// Change the scale, failing if any non-zero digit would be dropped
+    pub fn rescale_exact(self, scale: u8) -> Result<Self, FixedError> {
+        let out = self.rescale(scale, RoundingMode::Down)?;
+        if out.cmp(&self) != Ordering::Equal {
+            return Err(FixedError::Inexact { scale });
+        }
+        Ok(out)
+    }
+
+    /// Bring both operands to a common scale
+    fn align(self, other: Fixed) -> Result<(i128, i128, u8), FixedError> {
+        let scale = self.scale.max(other.scale);
+        let lhs = self.rescale(scale, RoundingMode::Down)?;
+        let rhs = other.rescale(scale, RoundingMode::Down)?;
+        Ok((lhs.raw, rhs.raw, scale))
+    }
+
+    pub fn checked_add(self, other: Fixed) -> Result<Self, FixedError> {
+        let (lhs, rhs, scale) = self.align(other)?;
+        let raw = lhs.checked_add(rhs).ok_or(FixedError::Overflow)?;
+        Ok(Fixed { raw, scale })
+    }
+
+    pub fn checked_sub(self, other: Fixed) -> Result<Self, FixedError> {
+        let (lhs, rhs, scale) = self.align(other)?;
+        let raw = lhs.checked_sub(rhs).ok_or(FixedError::Overflow)?;
+        Ok(Fixed { raw, scale })
+    }
+
+    /// Exact product; the result keeps the larger operand scale when possible
+    pub fn checked_mul(self, other: Fixed) -> Result<Self, FixedError> {
+        let target = self.scale.max(other.scale);
+        let (lhs, rhs) = match self.raw.checked_mul(other.raw) {
+            Some(_) => (self, other),
+            None => (self.normalize(), other.normalize()),
+        };
+        let raw = lhs.raw.checked_mul(rhs.raw).ok_or(FixedError::Overflow)?;
+        let product = Fixed {
+            raw,
+            scale: lhs.scale + rhs.scale,
+        }
+        .normalize_to(target);
+        check_scale(product.scale as u32)?;
+        Ok(product)
+    }
+
+    /// Quotient at an explicit scale, rounded with `mode`
+    pub fn checked_div(self, other: Fixed, scale: u8, mode: RoundingMode) -> Result<Self, FixedError> {
+        let scale = check_scale(scale as u32)?;
+        if other.raw == 0 {
+            return Err(FixedError::DivisionByZero);
+        }
+        // raw = self.raw * 10^(scale + other.scale - self.scale) / other.raw
+        let exp = scale as i32 + other.scale as i32 - self.scale as i32;
+        let (num, den) = if exp >= 0 {
+            let num = self.raw.checked_mul(pow10(exp as u32)?).ok_or(FixedError::Overflow)?;
+            (num, other.raw)
+        } else {
+            let den = other.raw.checked_mul(pow10((-exp) as u32)?).ok_or(FixedError::Overflow)?;
+            (self.raw, den)
+        };
+        Ok(Fixed {
+            raw: div_round(num, den, mode)?,
+            scale,
+        })
+    }
+
+    /// Quotient at the finest scale that fits, trimmed back to the operand scale
+    pub fn checked_quotient(self, other: Fixed) -> Result<Self, FixedError> {
+        if other.raw == 0 {
+            return Err(FixedError::DivisionByZero);
+        }
+        let floor = self.scale.max(other.scale);
+        for scale in (floor..=MAX_SCALE).rev() {
+            match self.checked_div(other, scale, RoundingMode::HalfEven) {
+                Ok(quotient) => return Ok(quotient.normalize_to(floor)),
+                Err(FixedError::Overflow) => continue,
+                Err(err) => return Err(err),
+            }
+        }
+        Err(FixedError::Overflow)
+    }
+
+    /// Round to the nearest multiple of `tick`; the result has the tick's scale
+    pub fn round_to_tick(self, tick: Fixed, mode: RoundingMode) -> Result<Self, FixedError> {
+        if tick.raw <= 0 {
+            return Err(FixedError::NonPositiveTick);
+        }
+        let (value, step, scale) = self.align(tick)?;
+        let ticks = div_round(value, step, mode)?;
+        let raw = ticks.checked_mul(step).ok_or(FixedError::Overflow)?;
+        Fixed { raw, scale }.rescale_exact(tick.scale)
+    }
+
+    /// Whether the value is an exact multiple of `tick`
+    pub fn is_multiple_of(self, tick: Fixed) -> Result<bool, FixedError> {
+        if tick.raw <= 0 {
+            return Err(FixedError::NonPositiveTick);
+        }
+        let (value, step, _) = self.align(tick)?;
+        Ok(value % step == 0)
+    }
+
+    /// Truncated integer part
+    pub fn trunc(self) -> i128 {
+        self.raw / POW10[self.scale as usize]
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.raw as f64 / POW10[self.scale as usize] as f64
+    }
+
+    /// Hash matching CPython's `hash()` for numerically equal `Decimal`/`int`
+    pub fn py_hash(&self) -> isize {
+        let mut exp_hash: u128 = 1;
+        for _ in 0..self.scale {
+            exp_hash = exp_hash * PY_HASH_10INV % PY_HASH_MODULUS;
+        }
+        let magnitude = self.raw.unsigned_abs() % PY_HASH_MODULUS;
+        let hash = (magnitude * exp_hash % PY_HASH_MODULUS) as i64;
+        let signed = if self.raw < 0 { -hash } else { hash };
+        if signed == -1 {
+            -2
+        } else {
+            signed as isize
+        }
+    }
+}
+
+impl PartialEq for Fixed {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Fixed {}
+
+impl PartialOrd for Fixed {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Fixed {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.align(*other) {
+            Ok((lhs, rhs, _)) => lhs.cmp(&rhs),
+            // Only the coarser-scaled side is upscaled, so only it can overflow,
+            // and then its magnitude exceeds anything the other side can hold.
+            Err(_) if self.scale < other.scale => self.raw.cmp(&0),
+            Err(_) => 0.cmp(&other.raw),
+        }
+    }
+}
+
+impl Hash for Fixed {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let normal = self.normalize();
+        normal.raw.hash(state);
+        normal.scale.hash(state);
+    }
+}
+
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.raw.unsigned_abs().to_string();
+        let sign = if self.raw < 0 { "-" } else { "" };
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{sign}{digits}");
+        }
+        let padded = format!("{digits:0>width$}", width = scale + 1);
+        let (int_part, frac_part) = padded.split_at(padded.len() - scale);
+        write!(f, "{sign}{int_part}.{frac_part}")
+    }
+}
+
+impl FromStr for Fixed {
+    type Err = FixedError;
+
+    /// Parse `[+-]digits[.digits][(e|E)[+-]digits]`, preserving trailing zeros
+    fn from_str(literal: &str) -> Result<Self, Self::Err> {
+        let invalid = || FixedError::InvalidLiteral(literal.to_string());
+        let text = literal.trim();
+
+        let (mantissa, exponent) = match text.find(['e', 'E']) {
+            Some(pos) => {
+                let exp: i32 = text[pos + 1..].parse().map_err(|_| invalid())?;
+                (&text[..pos], exp)
+            }
+            None => (text, 0),
+        };
+
+        let (negative, unsigned) = match mantissa.as_bytes().first() {
+            Some(b'-') => (true, &mantissa[1..]),
+            Some(b'+') => (false, &mantissa[1..]),
+            _ => (false, mantissa),
+        };
+
+        let (int_digits, frac_digits) = match unsigned.split_once('.') {
+            Some((int_part, frac_part)) => (int_part, frac_part),
+            None => (unsigned, ""),
+        };
+        if int_digits.is_empty() && frac_digits.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut raw: i128 = 0;
+        for byte in int_digits.bytes().chain(frac_digits.bytes()) {
+            if !byte.is_ascii_digit() {
+                return Err(invalid());
+            }
+            raw = raw
+                .checked_mul(10)
+                .and_then(|r| r.checked_add((byte - b'0') as i128))
+                .ok_or(FixedError::Overflow)?;
+        }
+        if negative {
+            raw = -raw;
+        }
+
+        let scale = frac_digits.len() as i64 - exponent as i64;
+        if scale < 0 {
+            let factor = pow10(u32::try_from(-scale).map_err(|_| FixedError::Overflow)?)?;
+            raw = raw.checked_mul(factor).ok_or(FixedError::Overflow)?;
+            return Ok(Fixed { raw, scale: 0 });
+        }
+        let scale = check_scale(u32::try_from(scale).map_err(|_| FixedError::Overflow)?)?;
+        Ok(Fixed { raw, scale })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed(literal: &str) -> Fixed {
+        literal.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_and_displays_preserving_scale() {
+        assert_eq!(fixed("1.50").raw(), 150);
+        assert_eq!(fixed("1.50").scale(), 2);
+        assert_eq!(fixed("-0.001").to_string(), "-0.001");
+        assert_eq!(fixed("1.5e2").to_string(), "150");
+        assert_eq!(fixed("15e-3").to_string(), "0.015");
+        assert_eq!(fixed("+7").to_string(), "7");
+        for bad in ["", ".", "1.2.3", "abc", "1e", "--1"] {
+            assert!(matches!(bad.parse::<Fixed>(), Err(FixedError::InvalidLiteral(_))), "{bad:?}");
+        }
+        assert_eq!("1e-29".parse::<Fixed>(), Err(FixedError::ScaleOutOfRange(29)));
+        assert_eq!("1e39".parse::<Fixed>(), Err(FixedError::Overflow));
+    }
+
+    #[test]
+    fn compares_and_hashes_by_value_across_scales() {
+        assert_eq!(fixed("1.5"), fixed("1.500"));
+        assert!(fixed("1.49") < fixed("1.5"));
+        assert!(fixed("-2") < fixed("-1.99"));
+        assert_eq!(fixed("1.5").normalize(), fixed("1.500").normalize());
+        assert_eq!(fixed("1.500").normalize().scale(), 1);
+        assert_eq!(fixed("2.5").py_hash(), fixed("2.50").py_hash());
+        // hash(Decimal("2.5")) on 64-bit CPython
+        assert_eq!(fixed("2.5").py_hash(), 1_152_921_504_606_846_978);
+        assert_eq!(Fixed::from_int(-1).py_hash(), -2);
+    }
+
+    #[test]
+    fn orders_values_whose_alignment_overflows() {
+        let huge = Fixed::new(i128::MAX / 10, 0).unwrap();
+        let tiny = Fixed::new(1, MAX_SCALE).unwrap();
+        assert!(huge.align(tiny).is_err());
+        assert!(huge > tiny);
+        assert!(tiny < huge);
+        assert!(huge.checked_neg().unwrap() < tiny);
+        assert!(tiny > huge.checked_neg().unwrap());
+    }
+
+    #[test]
+    fn rounds_with_every_mode() {
+        use RoundingMode::*;
+        let cases: [(&str, [&str; 7]); 4] = [
+            ("2.5", ["2", "3", "2", "3", "2", "3", "2"]),
+            ("-2.5", ["-2", "-3", "-2", "-3", "-2", "-2", "-3"]),
+            ("3.5", ["4", "4", "3", "4", "3", "4", "3"]),
+            ("2.51", ["3", "3", "3", "3", "2", "3", "2"]),
+        ];
+        let modes = [HalfEven, HalfUp, HalfDown, Up, Down, Ceiling, Floor];
+        for (value, expected) in cases {
+            for (mode, want) in modes.iter().zip(expected) {
+                assert_eq!(fixed(value).rescale(0, *mode).unwrap(), fixed(want), "{value} {mode:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn rescale_exact_refuses_to_drop_digits() {
+        assert_eq!(fixed("1.50").rescale_exact(1).unwrap().to_string(), "1.5");
+        assert_eq!(fixed("1.55").rescale_exact(1), Err(FixedError::Inexact { scale: 1 }));
+        assert_eq!(fixed("1").rescale(29, RoundingMode::Down), Err(FixedError::ScaleOutOfRange(29)));
+    }
+
+    #[test]
+    fn arithmetic_is_exact_and_checked() {
+        assert_eq!(fixed("0.1").checked_add(fixed("0.2")).unwrap().to_string(), "0.3");
+        assert_eq!(fixed("1.10").checked_sub(fixed("0.1")).unwrap().to_string(), "1.00");
+        assert_eq!(fixed("1.5").checked_mul(fixed("2.25")).unwrap(), fixed("3.375"));
+        assert_eq!(fixed("2.50").checked_mul(fixed("4")).unwrap().to_string(), "10.00");
+        let max = Fixed::new(i128::MAX, 0).unwrap();
+        assert_eq!(max.checked_add(Fixed::from_int(1)), Err(FixedError::Overflow));
+        assert_eq!(max.checked_mul(Fixed::from_int(2)), Err(FixedError::Overflow));
+        assert_eq!(Fixed::new(i128::MIN, 0).unwrap().checked_neg(), Err(FixedError::Overflow));
+        assert_eq!(Fixed::new(i128::MIN, 0).unwrap().checked_abs(), Err(FixedError::Overflow));
+    }
+
+    #[test]
+    fn divides_at_a_scale_or_as_fine_as_fits() {
+        let third = Fixed::from_int(1).checked_div(Fixed::from_int(3), 4, RoundingMode::HalfEven);
+        assert_eq!(third.unwrap().to_string(), "0.3333");
+        let up = Fixed::from_int(2).checked_div(Fixed::from_int(3), 2, RoundingMode::HalfUp);
+        assert_eq!(up.unwrap().to_string(), "0.67");
+        assert_eq!(fixed("10").checked_quotient(fixed("4")).unwrap().to_string(), "2.5");
+        assert_eq!(fixed("1.00").checked_quotient(fixed("3")).unwrap().scale(), MAX_SCALE);
+        assert_eq!(fixed("1").checked_quotient(Fixed::ZERO), Err(FixedError::DivisionByZero));
+        assert_eq!(
+            fixed("1").checked_div(Fixed::ZERO, 2, RoundingMode::Down),
+            Err(FixedError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn rounds_to_ticks() {
+        assert_eq!(
+            fixed("10.037").round_to_tick(fixed("0.05"), RoundingMode::HalfEven).unwrap(),
+            fixed("10.05")
+        );
+        assert_eq!(fixed("10.037").round_to_tick(fixed("0.05"), RoundingMode::Down).unwrap(), fixed("10.00"));
+        assert_eq!(fixed("10.037").round_to_tick(fixed("0.05"), RoundingMode::Down).unwrap().scale(), 2);
+        assert!(fixed("10.10").is_multiple_of(fixed("0.05")).unwrap());
+        assert!(!fixed("10.11").is_multiple_of(fixed("0.05")).unwrap());
+        assert_eq!(
+            fixed("1").round_to_tick(Fixed::ZERO, RoundingMode::Down),
+            Err(FixedError::NonPositiveTick)
+        );
+    }
+
+    #[test]
+    fn converts_to_integers_and_floats() {
+        assert_eq!(fixed("-7.9").trunc(), -7);
+        assert_eq!(fixed("7.9").trunc(), 7);
+        assert_eq!(fixed("0.25").to_f64(), 0.25);
+    }
+}
@@ -0,0 +1,256 @@
+//! Interned instrument identities
+//!
+//! Hashing a Python `Instrument` means hashing its composite key on every
+//! lookup. The registry maps each distinct key once to a dense `u32`
+//! `InstrumentId`; native code then keys maps and arrays by that integer and
+//! resolves back to the original `Instrument` object by index.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use pyo3::exceptions::{PyKeyError, PyOverflowError};
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::sync::PyOnceLock;
+use pyo3::types::{PyList, PyString};
+
+use crate::types::fixed::Fixed;
+use crate::types::price::extract_fixed;
+use crate::types::time::datetime_to_nanos;
+
+/// Compact identifier of an interned `Instrument`
+#[pyclass(module = "_simulor_rust", frozen, eq, ord, hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct InstrumentId(pub u32);
+
+impl InstrumentId {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[pymethods]
+impl InstrumentId {
+    #[new]
+    fn py_new(value: u32) -> Self {
+        InstrumentId(value)
+    }
+
+    /// The underlying integer
+    #[getter]
+    fn value(&self) -> u32 {
+        self.0
+    }
+
+    fn __int__(&self) -> u32 {
+        self.0
+    }
+
+    fn __index__(&self) -> u32 {
+        self.0
+    }
+
+    fn __repr__(&self) -> String {
+        format!("InstrumentId({})", self.0)
+    }
+}
+
+/// Native form of `Instrument.key`
+///
+/// Enum members are reduced to their values, the expiry to epoch nanoseconds
+/// (plus whether it carried a timezone, since aware and naive datetimes never
+/// compare equal in Python) and the strike to a normalized `Fixed`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InstrumentKey {
+    pub symbol: String,
+    pub asset_type: String,
+    pub exchange: Option<String>,
+    pub currency: String,
+    pub expiry: Option<(i64, bool)>,
+    pub strike: Option<Fixed>,
+    pub option_type: Option<String>,
+}
+
+fn enum_value(obj: &Bound<'_, PyAny>) -> PyResult<String> {
+    obj.getattr(intern!(obj.py(), "value"))?.extract()
+}
+
+fn optional<'py>(obj: &Bound<'py, PyAny>, name: &Bound<'py, PyString>) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let value = obj.getattr(name)?;
+    Ok((!value.is_none()).then_some(value))
+}
+
+impl InstrumentKey {
+    pub fn from_py(instrument: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let py = instrument.py();
+        let expiry = match optional(instrument, intern!(py, "expiry"))? {
+            Some(expiry) => {
+                let aware = !expiry.getattr(intern!(py, "tzinfo"))?.is_none();
+                Some((datetime_to_nanos(&expiry)?, aware))
+            }
+            None => None,
+        };
+        Ok(InstrumentKey {
+            symbol: instrument.getattr(intern!(py, "symbol"))?.extract()?,
+            asset_type: enum_value(&instrument.getattr(intern!(py, "asset_type"))?)?,
+            exchange: optional(instrument, intern!(py, "exchange"))?.map(|e| e.extract()).transpose()?,
+            currency: instrument.getattr(intern!(py, "currency"))?.extract()?,
+            expiry,
+            strike: optional(instrument, intern!(py, "strike"))?
+                .map(|s| extract_fixed(&s).map(Fixed::normalize))
+                .transpose()?,
+            option_type: optional(instrument, intern!(py, "option_type"))?.map(|o| enum_value(&o)).transpose()?,
+        })
+    }
+}
+
+/// Upper bound on remembered object identities; beyond it lookups fall back
+/// to building the key, so callers that allocate fresh `Instrument` objects
+/// cannot grow the cache without limit
+const MAX_ALIASES: usize = 1 << 16;
+
+#[derive(Default)]
+struct Interner {
+    ids: HashMap<InstrumentKey, InstrumentId>,
+    instruments: Vec<Py<PyAny>>,
+    // Object address -> ID for `Instrument` objects already seen. The objects
+    // are kept alive in `alias_refs` so an address is never reused.
+    aliases: HashMap<usize, InstrumentId>,
+    alias_refs: Vec<Py<PyAny>>,
+}
+
+impl Interner {
+    fn remember(&mut self, instrument: &Bound<'_, PyAny>, id: InstrumentId) {
+        if self.aliases.len() < MAX_ALIASES {
+            self.aliases.insert(instrument.as_ptr() as usize, id);
+            self.alias_refs.push(instrument.clone().unbind());
+        }
+    }
+}
+
+/// Bidirectional map between instruments and dense integer IDs
+///
+/// IDs are assigned in first-seen order starting from zero and are never
+/// reused. The first `Instrument` object seen for a key is the one returned by
+/// `resolve`.
+#[pyclass(module = "_simulor_rust", frozen)]
+#[derive(Default)]
+pub struct InstrumentRegistry {
+    inner: RwLock<Interner>,
+}
+
+static DEFAULT_REGISTRY: PyOnceLock<Py<InstrumentRegistry>> = PyOnceLock::new();
+
+/// Process-wide registry shared by the native data and execution components
+pub fn default_registry(py: Python<'_>) -> PyResult<&Bound<'_, InstrumentRegistry>> {
+    DEFAULT_REGISTRY
+        .get_or_try_init(py, || Py::new(py, InstrumentRegistry::default()))
+        .map(|registry| registry.bind(py))
+}
+
+impl InstrumentRegistry {
+    /// ID for `instrument`, assigning a new one if the key is unseen
+    pub fn intern_instrument(&self, instrument: &Bound<'_, PyAny>) -> PyResult<InstrumentId> {
+        if let Some(id) = self.alias(instrument) {
+            return Ok(id);
+        }
+        let key = InstrumentKey::from_py(instrument)?;
+        let mut inner = self.inner.write().unwrap();
+        let id = match inner.ids.get(&key) {
+            Some(id) => *id,
+            None => {
+                let id = u32::try_from(inner.instruments.len())
+                    .map(InstrumentId)
+                    .map_err(|_| PyOverflowError::new_err("instrument registry is full"))?;
+                inner.instruments.push(instrument.clone().unbind());
+                inner.ids.insert(key, id);
+                id
+            }
+        };
+        inner.remember(instrument, id);
+        Ok(id)
+    }
+
+    /// ID for `instrument` if it has been interned
+    pub fn lookup_instrument(&self, instrument: &Bound<'_, PyAny>) -> PyResult<Option<InstrumentId>> {
+        if let Some(id) = self.alias(instrument) {
+            return Ok(Some(id));
+        }
+        let key = InstrumentKey::from_py(instrument)?;
+        let mut inner = self.inner.write().unwrap();
+        let id = inner.ids.get(&key).copied();
+        if let Some(id) = id {
+            inner.remember(instrument, id);
+        }
+        Ok(id)
+    }
+
+    fn alias(&self, instrument: &Bound<'_, PyAny>) -> Option<InstrumentId> {
+        let inner = self.inner.read().unwrap();
+        inner.aliases.get(&(instrument.as_ptr() as usize)).copied()
+    }
+
+    /// `Instrument` for `id`, or `None` if it was never assigned
+    pub fn resolve_id(&self, py: Python<'_>, id: InstrumentId) -> Option<Py<PyAny>> {
+        let inner = self.inner.read().unwrap();
+        inner.instruments.get(id.index()).map(|instrument| instrument.clone_ref(py))
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.read().unwrap().instruments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[pymethods]
+impl InstrumentRegistry {
+    #[new]
+    fn py_new() -> Self {
+        InstrumentRegistry::default()
+    }
+
+    /// The process-wide registry used by native components
+    #[staticmethod]
+    #[pyo3(name = "default")]
+    fn py_default(py: Python<'_>) -> PyResult<Py<InstrumentRegistry>> {
+        Ok(default_registry(py)?.clone().unbind())
+    }
+
+    /// Return the ID for `instrument`, assigning one on first sight
+    fn intern(&self, instrument: &Bound<'_, PyAny>) -> PyResult<InstrumentId> {
+        self.intern_instrument(instrument)
+    }
+
+    /// Return the ID for `instrument`, or `None` if it is not interned
+    fn lookup(&self, instrument: &Bound<'_, PyAny>) -> PyResult<Option<InstrumentId>> {
+        self.lookup_instrument(instrument)
+    }
+
+    /// Return the `Instrument` for an ID (`InstrumentId` or `int`)
+    ///
+    /// Raises `KeyError` if the ID has not been assigned.
+    fn resolve(&self, py: Python<'_>, id: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        let id = match id.cast::<InstrumentId>() {
+            Ok(id) => *id.get(),
+            Err(_) => InstrumentId(id.extract()?),
+        };
+        self.resolve_id(py, id).ok_or_else(|| PyKeyError::new_err(id.0))
+    }
+
+    /// All interned instruments, ordered by ID
+    fn instruments<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let inner = self.inner.read().unwrap();
+        PyList::new(py, inner.instruments.iter().map(|instrument| instrument.bind(py)))
+    }
+
+    fn __len__(&self) -> usize {
+        self.len()
+    }
+
+    fn __contains__(&self, instrument: &Bound<'_, PyAny>) -> PyResult<bool> {
+        Ok(self.lookup_instrument(instrument)?.is_some())
+    }
+}
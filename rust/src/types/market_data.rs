@@ -0,0 +1,832 @@
+//! Native market data records
+//!
+//! `TradeTick`, `QuoteTick`, `TradeBar` and `QuoteBar` mirror the frozen
+//! dataclasses in `simulor.types.market_data`: same field names, same
+//! constructor signature and the same validation messages. Prices and sizes
+//! are held as `Fixed` and surfaced to Python as `decimal.Decimal`; the
+//! timestamp, instrument and resolution objects are kept exactly as given.
+
+use std::sync::OnceLock;
+
+use pyo3::basic::CompareOp;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyTuple, PyType};
+use pyo3::{intern, PyClass};
+
+use crate::interop::{resolution_type, tick_direction_type};
+use crate::types::fixed::Fixed;
+use crate::types::instrument::{default_registry, InstrumentId};
+use crate::types::price::{extract_fixed, to_decimal};
+use crate::types::time::{datetime_to_nanos, NANOS_PER_SECOND};
+
+/// Native counterpart of `simulor.types.Resolution`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Resolution {
+    Tick,
+    Second,
+    Minute,
+    Hour,
+    Daily,
+}
+
+impl Resolution {
+    pub fn from_secs(secs: u32) -> Option<Self> {
+        match secs {
+            0 => Some(Resolution::Tick),
+            1 => Some(Resolution::Second),
+            60 => Some(Resolution::Minute),
+            3600 => Some(Resolution::Hour),
+            86400 => Some(Resolution::Daily),
+            _ => None,
+        }
+    }
+
+    pub fn secs(self) -> u32 {
+        match self {
+            Resolution::Tick => 0,
+            Resolution::Second => 1,
+            Resolution::Minute => 60,
+            Resolution::Hour => 3600,
+            Resolution::Daily => 86400,
+        }
+    }
+
+    /// Bar length in nanoseconds (zero for ticks)
+    pub fn nanos(self) -> i64 {
+        self.secs() as i64 * NANOS_PER_SECOND
+    }
+
+    pub fn from_py(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let secs: u32 = obj.extract()?;
+        Resolution::from_secs(secs).ok_or_else(|| PyValueError::new_err(format!("Unknown resolution: {secs}")))
+    }
+
+    /// The matching `simulor.types.Resolution` member
+    pub fn to_py(self, py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        resolution_type(py)?.call1((self.secs(),))
+    }
+}
+
+/// Native counterpart of `simulor.types.TickDirection`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TickDirection {
+    Buy,
+    Sell,
+    Neutral,
+}
+
+impl TickDirection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TickDirection::Buy => "buy",
+            TickDirection::Sell => "sell",
+            TickDirection::Neutral => "neutral",
+        }
+    }
+
+    pub fn from_py(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let value = obj.getattr(intern!(obj.py(), "value"))?;
+        match value.extract::<&str>()? {
+            "buy" => Ok(TickDirection::Buy),
+            "sell" => Ok(TickDirection::Sell),
+            "neutral" => Ok(TickDirection::Neutral),
+            other => Err(PyValueError::new_err(format!("Unknown tick direction: {other}"))),
+        }
+    }
+
+    /// The matching `simulor.types.TickDirection` member
+    pub fn to_py(self, py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        tick_direction_type(py)?.call1((self.as_str(),))
+    }
+}
+
+/// Base class for all market data records
+#[pyclass(module = "_simulor_rust", subclass, frozen)]
+pub struct MarketData {
+    pub timestamp: Py<PyAny>,
+    pub instrument: Py<PyAny>,
+    pub resolution: Py<PyAny>,
+    pub native_resolution: Resolution,
+    nanos: OnceLock<i64>,
+    instrument_id: OnceLock<InstrumentId>,
+}
+
+impl MarketData {
+    pub fn new(
+        timestamp: &Bound<'_, PyAny>,
+        instrument: &Bound<'_, PyAny>,
+        resolution: &Bound<'_, PyAny>,
+    ) -> PyResult<Self> {
+        Ok(MarketData {
+            timestamp: timestamp.clone().unbind(),
+            instrument: instrument.clone().unbind(),
+            resolution: resolution.clone().unbind(),
+            native_resolution: Resolution::from_py(resolution)?,
+            nanos: OnceLock::new(),
+            instrument_id: OnceLock::new(),
+        })
+    }
+
+    /// Base for a record built natively, where the timestamp is already known
+    pub fn from_parts(
+        timestamp: Py<PyAny>,
+        instrument: Py<PyAny>,
+        resolution: Py<PyAny>,
+        native_resolution: Resolution,
+        nanos: i64,
+    ) -> Self {
+        MarketData {
+            timestamp,
+            instrument,
+            resolution,
+            native_resolution,
+            nanos: OnceLock::from(nanos),
+            instrument_id: OnceLock::new(),
+        }
+    }
+
+    /// Timestamp as nanoseconds since the Unix epoch, computed on first use
+    pub fn timestamp_nanos(&self, py: Python<'_>) -> PyResult<i64> {
+        if let Some(nanos) = self.nanos.get() {
+            return Ok(*nanos);
+        }
+        let nanos = datetime_to_nanos(self.timestamp.bind(py))?;
+        Ok(*self.nanos.get_or_init(|| nanos))
+    }
+
+    /// ID of the instrument in the default registry, interned on first use
+    pub fn instrument_id(&self, py: Python<'_>) -> PyResult<InstrumentId> {
+        if let Some(id) = self.instrument_id.get() {
+            return Ok(*id);
+        }
+        let id = default_registry(py)?.get().intern_instrument(self.instrument.bind(py))?;
+        Ok(*self.instrument_id.get_or_init(|| id))
+    }
+
+    pub fn header_repr(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "timestamp={}, instrument={}, resolution={}",
+            self.timestamp.bind(py).repr()?,
+            self.instrument.bind(py).repr()?,
+            self.resolution.bind(py).repr()?,
+        ))
+    }
+
+    pub fn header_eq(&self, py: Python<'_>, other: &MarketData) -> PyResult<bool> {
+        Ok(self.native_resolution == other.native_resolution
+            && self.timestamp.bind(py).eq(other.timestamp.bind(py))?
+            && self.instrument.bind(py).eq(other.instrument.bind(py))?)
+    }
+
+    pub fn header_items<'py>(&self, py: Python<'py>) -> Vec<Bound<'py, PyAny>> {
+        vec![
+            self.timestamp.bind(py).clone(),
+            self.instrument.bind(py).clone(),
+            self.resolution.bind(py).clone(),
+        ]
+    }
+}
+
+#[pymethods]
+impl MarketData {
+    #[new]
+    fn py_new(
+        timestamp: &Bound<'_, PyAny>,
+        instrument: &Bound<'_, PyAny>,
+        resolution: &Bound<'_, PyAny>,
+    ) -> PyResult<Self> {
+        MarketData::new(timestamp, instrument, resolution)
+    }
+
+    /// Time of the market data point
+    #[getter(timestamp)]
+    fn py_timestamp(&self, py: Python<'_>) -> Py<PyAny> {
+        self.timestamp.clone_ref(py)
+    }
+
+    /// The financial instrument this data represents
+    #[getter(instrument)]
+    fn py_instrument(&self, py: Python<'_>) -> Py<PyAny> {
+        self.instrument.clone_ref(py)
+    }
+
+    /// Time resolution of the data
+    #[getter(resolution)]
+    fn py_resolution(&self, py: Python<'_>) -> Py<PyAny> {
+        self.resolution.clone_ref(py)
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!("MarketData({})", self.header_repr(py)?))
+    }
+
+    fn __richcmp__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<Py<PyAny>> {
+        record_richcmp(slf, other, op, |a, b| a.header_eq(slf.py(), b))
+    }
+
+    fn __hash__(&self, py: Python<'_>) -> PyResult<isize> {
+        PyTuple::new(py, self.header_items(py))?.hash()
+    }
+
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyType>, Bound<'py, PyTuple>)> {
+        let py = slf.py();
+        Ok((slf.get_type(), PyTuple::new(py, slf.get().header_items(py))?))
+    }
+}
+
+/// Create a record object from its base and payload
+pub fn new_record<'py, T>(py: Python<'py>, base: MarketData, record: T) -> PyResult<Bound<'py, T>>
+where
+    T: PyClass<BaseType = MarketData>,
+{
+    Bound::new(py, PyClassInitializer::from(base).add_subclass(record))
+}
+
+/// Dataclass-style equality: only `==`/`!=` against the exact same class
+pub fn record_richcmp<T, F>(slf: &Bound<'_, T>, other: &Bound<'_, PyAny>, op: CompareOp, eq: F) -> PyResult<Py<PyAny>>
+where
+    T: PyClass<Frozen = pyo3::pyclass::boolean_struct::True> + Sync,
+    F: FnOnce(&T, &T) -> PyResult<bool>,
+{
+    let py = slf.py();
+    if !matches!(op, CompareOp::Eq | CompareOp::Ne) || !other.get_type().is(slf.as_any().get_type()) {
+        return Ok(py.NotImplemented());
+    }
+    let other = other.cast::<T>()?;
+    let equal = eq(slf.get(), other.get())?;
+    Ok((equal == matches!(op, CompareOp::Eq)).into_pyobject(py)?.to_owned().into_any().unbind())
+}
+
+pub fn decimal_repr(py: Python<'_>, value: Fixed) -> PyResult<String> {
+    Ok(to_decimal(py, value)?.repr()?.to_string())
+}
+
+pub fn record_hash<'py>(
+    py: Python<'py>,
+    base: &MarketData,
+    values: &[Fixed],
+    extra: Option<Bound<'py, PyAny>>,
+) -> PyResult<isize> {
+    let mut items = base.header_items(py);
+    for value in values {
+        items.push(value.py_hash().into_pyobject(py)?.into_any());
+    }
+    items.extend(extra);
+    PyTuple::new(py, items)?.hash()
+}
+
+/// Single trade execution (Level 1 data)
+#[pyclass(module = "_simulor_rust", extends = MarketData, frozen)]
+pub struct TradeTick {
+    pub price: Fixed,
+    pub size: Fixed,
+    pub direction: Option<Py<PyAny>>,
+    pub native_direction: Option<TickDirection>,
+}
+
+impl TradeTick {
+    pub fn validate(&self, resolution: Resolution) -> Result<(), &'static str> {
+        TradeTick::validate_values(self.price, self.size, resolution)
+    }
+
+    /// `validate` without a record, which would own a Python direction
+    pub fn validate_values(price: Fixed, size: Fixed, resolution: Resolution) -> Result<(), &'static str> {
+        if !price.is_positive() {
+            return Err("Price must be positive");
+        }
+        if !size.is_positive() {
+            return Err("Size must be positive");
+        }
+        if resolution != Resolution::Tick {
+            return Err("TradeTick resolution must be TICK");
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl TradeTick {
+    #[new]
+    #[pyo3(signature = (timestamp, instrument, resolution, price, size, direction=None))]
+    fn py_new(
+        timestamp: &Bound<'_, PyAny>,
+        instrument: &Bound<'_, PyAny>,
+        resolution: &Bound<'_, PyAny>,
+        price: &Bound<'_, PyAny>,
+        size: &Bound<'_, PyAny>,
+        direction: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let base = MarketData::new(timestamp, instrument, resolution)?;
+        let direction = direction.filter(|d| !d.is_none());
+        let tick = TradeTick {
+            price: extract_fixed(price)?,
+            size: extract_fixed(size)?,
+            native_direction: direction.map(TickDirection::from_py).transpose()?,
+            direction: direction.map(|d| d.clone().unbind()),
+        };
+        tick.validate(base.native_resolution).map_err(PyValueError::new_err)?;
+        Ok(PyClassInitializer::from(base).add_subclass(tick))
+    }
+
+    /// Execution price of the trade
+    #[getter(price)]
+    fn py_price<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.price)
+    }
+
+    /// Quantity traded
+    #[getter(size)]
+    fn py_size<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.size)
+    }
+
+    /// Direction of the trade (optional)
+    #[getter(direction)]
+    fn py_direction(&self, py: Python<'_>) -> Option<Py<PyAny>> {
+        self.direction.as_ref().map(|d| d.clone_ref(py))
+    }
+
+    fn __repr__(slf: &Bound<'_, Self>) -> PyResult<String> {
+        let py = slf.py();
+        let tick = slf.get();
+        Ok(format!(
+            "TradeTick({}, price={}, size={}, direction={})",
+            slf.as_super().get().header_repr(py)?,
+            decimal_repr(py, tick.price)?,
+            decimal_repr(py, tick.size)?,
+            tick.direction
+                .as_ref()
+                .map_or(Ok("None".to_string()), |d| d.bind(py).repr().map(|r| r.to_string()))?,
+        ))
+    }
+
+    fn __richcmp__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<Py<PyAny>> {
+        let py = slf.py();
+        let other_base = match other.cast::<Self>() {
+            Ok(other) => Some(other.as_super().get()),
+            Err(_) => None,
+        };
+        let base = slf.as_super().get();
+        record_richcmp(slf, other, op, |a, b| {
+            Ok(a.price == b.price
+                && a.size == b.size
+                && a.native_direction == b.native_direction
+                && other_base.map_or(Ok(false), |other_base| base.header_eq(py, other_base))?)
+        })
+    }
+
+    fn __hash__(slf: &Bound<'_, Self>) -> PyResult<isize> {
+        let py = slf.py();
+        let tick = slf.get();
+        let direction = tick.direction.as_ref().map(|d| d.bind(py).clone());
+        record_hash(
+            py,
+            slf.as_super().get(),
+            &[tick.price, tick.size],
+            Some(direction.into_pyobject(py)?.into_any()),
+        )
+    }
+
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyType>, Bound<'py, PyTuple>)> {
+        let py = slf.py();
+        let tick = slf.get();
+        let mut args = slf.as_super().get().header_items(py);
+        args.push(to_decimal(py, tick.price)?);
+        args.push(to_decimal(py, tick.size)?);
+        args.push(tick.direction.as_ref().map(|d| d.bind(py).clone()).into_pyobject(py)?.into_any());
+        Ok((slf.get_type(), PyTuple::new(py, args)?))
+    }
+}
+
+/// Bid/ask quote snapshot (Level 1 data)
+#[pyclass(module = "_simulor_rust", extends = MarketData, frozen)]
+pub struct QuoteTick {
+    pub bid_price: Fixed,
+    pub bid_size: Fixed,
+    pub ask_price: Fixed,
+    pub ask_size: Fixed,
+}
+
+impl QuoteTick {
+    pub fn validate(&self, resolution: Resolution) -> Result<(), &'static str> {
+        if !self.bid_price.is_positive() || !self.ask_price.is_positive() {
+            return Err("Prices must be positive");
+        }
+        if self.bid_price >= self.ask_price {
+            return Err("Bid must be less than ask");
+        }
+        if self.bid_size.is_negative() || self.ask_size.is_negative() {
+            return Err("Sizes cannot be negative");
+        }
+        if resolution != Resolution::Tick {
+            return Err("QuoteTick resolution must be TICK");
+        }
+        Ok(())
+    }
+
+    pub fn spread(&self) -> PyResult<Fixed> {
+        Ok(self.ask_price.checked_sub(self.bid_price)?)
+    }
+
+    pub fn mid_price(&self) -> PyResult<Fixed> {
+        Ok(self.bid_price.checked_add(self.ask_price)?.checked_quotient(Fixed::from_int(2))?)
+    }
+
+    fn values(&self) -> [Fixed; 4] {
+        [self.bid_price, self.bid_size, self.ask_price, self.ask_size]
+    }
+}
+
+#[pymethods]
+impl QuoteTick {
+    #[new]
+    fn py_new(
+        timestamp: &Bound<'_, PyAny>,
+        instrument: &Bound<'_, PyAny>,
+        resolution: &Bound<'_, PyAny>,
+        bid_price: &Bound<'_, PyAny>,
+        bid_size: &Bound<'_, PyAny>,
+        ask_price: &Bound<'_, PyAny>,
+        ask_size: &Bound<'_, PyAny>,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let base = MarketData::new(timestamp, instrument, resolution)?;
+        let quote = QuoteTick {
+            bid_price: extract_fixed(bid_price)?,
+            bid_size: extract_fixed(bid_size)?,
+            ask_price: extract_fixed(ask_price)?,
+            ask_size: extract_fixed(ask_size)?,
+        };
+        quote.validate(base.native_resolution).map_err(PyValueError::new_err)?;
+        Ok(PyClassInitializer::from(base).add_subclass(quote))
+    }
+
+    /// Best bid price
+    #[getter(bid_price)]
+    fn py_bid_price<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.bid_price)
+    }
+
+    /// Size available at the best bid
+    #[getter(bid_size)]
+    fn py_bid_size<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.bid_size)
+    }
+
+    /// Best ask (offer) price
+    #[getter(ask_price)]
+    fn py_ask_price<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.ask_price)
+    }
+
+    /// Size available at the best ask
+    #[getter(ask_size)]
+    fn py_ask_size<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.ask_size)
+    }
+
+    /// Bid-ask spread
+    #[getter(spread)]
+    fn py_spread<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.spread()?)
+    }
+
+    /// Mid-point price
+    #[getter(mid_price)]
+    fn py_mid_price<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.mid_price()?)
+    }
+
+    fn __repr__(slf: &Bound<'_, Self>) -> PyResult<String> {
+        let py = slf.py();
+        let quote = slf.get();
+        Ok(format!(
+            "QuoteTick({}, bid_price={}, bid_size={}, ask_price={}, ask_size={})",
+            slf.as_super().get().header_repr(py)?,
+            decimal_repr(py, quote.bid_price)?,
+            decimal_repr(py, quote.bid_size)?,
+            decimal_repr(py, quote.ask_price)?,
+            decimal_repr(py, quote.ask_size)?,
+        ))
+    }
+
+    fn __richcmp__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<Py<PyAny>> {
+        bar_richcmp(slf, other, op, |q: &Self| q.values())
+    }
+
+    fn __hash__(slf: &Bound<'_, Self>) -> PyResult<isize> {
+        record_hash(slf.py(), slf.as_super().get(), &slf.get().values(), None)
+    }
+
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyType>, Bound<'py, PyTuple>)> {
+        record_reduce(slf, &slf.get().values())
+    }
+}
+
+/// Aggregated trade data over a time period (OHLCV bar)
+#[pyclass(module = "_simulor_rust", extends = MarketData, frozen)]
+pub struct TradeBar {
+    pub open: Fixed,
+    pub high: Fixed,
+    pub low: Fixed,
+    pub close: Fixed,
+    pub volume: Fixed,
+}
+
+impl TradeBar {
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if !(self.low <= self.open && self.open <= self.high) {
+            return Err("Open must be between low and high");
+        }
+        if !(self.low <= self.close && self.close <= self.high) {
+            return Err("Close must be between low and high");
+        }
+        if self.volume.is_negative() {
+            return Err("Volume cannot be negative");
+        }
+        Ok(())
+    }
+
+    fn values(&self) -> [Fixed; 5] {
+        [self.open, self.high, self.low, self.close, self.volume]
+    }
+}
+
+#[pymethods]
+impl TradeBar {
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    fn py_new(
+        timestamp: &Bound<'_, PyAny>,
+        instrument: &Bound<'_, PyAny>,
+        resolution: &Bound<'_, PyAny>,
+        open: &Bound<'_, PyAny>,
+        high: &Bound<'_, PyAny>,
+        low: &Bound<'_, PyAny>,
+        close: &Bound<'_, PyAny>,
+        volume: &Bound<'_, PyAny>,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let base = MarketData::new(timestamp, instrument, resolution)?;
+        let bar = TradeBar {
+            open: extract_fixed(open)?,
+            high: extract_fixed(high)?,
+            low: extract_fixed(low)?,
+            close: extract_fixed(close)?,
+            volume: extract_fixed(volume)?,
+        };
+        bar.validate().map_err(PyValueError::new_err)?;
+        Ok(PyClassInitializer::from(base).add_subclass(bar))
+    }
+
+    /// Opening price for the period
+    #[getter(open)]
+    fn py_open<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.open)
+    }
+
+    /// Highest price during the period
+    #[getter(high)]
+    fn py_high<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.high)
+    }
+
+    /// Lowest price during the period
+    #[getter(low)]
+    fn py_low<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.low)
+    }
+
+    /// Closing price for the period
+    #[getter(close)]
+    fn py_close<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.close)
+    }
+
+    /// Total volume traded during the period
+    #[getter(volume)]
+    fn py_volume<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.volume)
+    }
+
+    fn __repr__(slf: &Bound<'_, Self>) -> PyResult<String> {
+        let py = slf.py();
+        let bar = slf.get();
+        Ok(format!(
+            "TradeBar({}, open={}, high={}, low={}, close={}, volume={})",
+            slf.as_super().get().header_repr(py)?,
+            decimal_repr(py, bar.open)?,
+            decimal_repr(py, bar.high)?,
+            decimal_repr(py, bar.low)?,
+            decimal_repr(py, bar.close)?,
+            decimal_repr(py, bar.volume)?,
+        ))
+    }
+
+    fn __richcmp__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<Py<PyAny>> {
+        bar_richcmp(slf, other, op, |b: &Self| b.values())
+    }
+
+    fn __hash__(slf: &Bound<'_, Self>) -> PyResult<isize> {
+        record_hash(slf.py(), slf.as_super().get(), &slf.get().values(), None)
+    }
+
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyType>, Bound<'py, PyTuple>)> {
+        record_reduce(slf, &slf.get().values())
+    }
+}
+
+/// Aggregated quote data over a time period
+#[pyclass(module = "_simulor_rust", extends = MarketData, frozen)]
+pub struct QuoteBar {
+    pub bid_open: Fixed,
+    pub bid_high: Fixed,
+    pub bid_low: Fixed,
+    pub bid_close: Fixed,
+    pub ask_open: Fixed,
+    pub ask_high: Fixed,
+    pub ask_low: Fixed,
+    pub ask_close: Fixed,
+}
+
+impl QuoteBar {
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if !(self.bid_low <= self.bid_open && self.bid_open <= self.bid_high) {
+            return Err("Bid open must be between bid low and high");
+        }
+        if !(self.bid_low <= self.bid_close && self.bid_close <= self.bid_high) {
+            return Err("Bid close must be between bid low and high");
+        }
+        if !(self.ask_low <= self.ask_open && self.ask_open <= self.ask_high) {
+            return Err("Ask open must be between ask low and high");
+        }
+        if !(self.ask_low <= self.ask_close && self.ask_close <= self.ask_high) {
+            return Err("Ask close must be between ask low and high");
+        }
+        Ok(())
+    }
+
+    pub fn mid_close(&self) -> PyResult<Fixed> {
+        Ok(self.bid_close.checked_add(self.ask_close)?.checked_quotient(Fixed::from_int(2))?)
+    }
+
+    fn values(&self) -> [Fixed; 8] {
+        [
+            self.bid_open,
+            self.bid_high,
+            self.bid_low,
+            self.bid_close,
+            self.ask_open,
+            self.ask_high,
+            self.ask_low,
+            self.ask_close,
+        ]
+    }
+}
+
+#[pymethods]
+impl QuoteBar {
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    fn py_new(
+        timestamp: &Bound<'_, PyAny>,
+        instrument: &Bound<'_, PyAny>,
+        resolution: &Bound<'_, PyAny>,
+        bid_open: &Bound<'_, PyAny>,
+        bid_high: &Bound<'_, PyAny>,
+        bid_low: &Bound<'_, PyAny>,
+        bid_close: &Bound<'_, PyAny>,
+        ask_open: &Bound<'_, PyAny>,
+        ask_high: &Bound<'_, PyAny>,
+        ask_low: &Bound<'_, PyAny>,
+        ask_close: &Bound<'_, PyAny>,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let base = MarketData::new(timestamp, instrument, resolution)?;
+        let bar = QuoteBar {
+            bid_open: extract_fixed(bid_open)?,
+            bid_high: extract_fixed(bid_high)?,
+            bid_low: extract_fixed(bid_low)?,
+            bid_close: extract_fixed(bid_close)?,
+            ask_open: extract_fixed(ask_open)?,
+            ask_high: extract_fixed(ask_high)?,
+            ask_low: extract_fixed(ask_low)?,
+            ask_close: extract_fixed(ask_close)?,
+        };
+        bar.validate().map_err(PyValueError::new_err)?;
+        Ok(PyClassInitializer::from(base).add_subclass(bar))
+    }
+
+    /// Opening bid price
+    #[getter(bid_open)]
+    fn py_bid_open<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.bid_open)
+    }
+
+    /// Highest bid price
+    #[getter(bid_high)]
+    fn py_bid_high<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.bid_high)
+    }
+
+    /// Lowest bid price
+    #[getter(bid_low)]
+    fn py_bid_low<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.bid_low)
+    }
+
+    /// Closing bid price
+    #[getter(bid_close)]
+    fn py_bid_close<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.bid_close)
+    }
+
+    /// Opening ask price
+    #[getter(ask_open)]
+    fn py_ask_open<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.ask_open)
+    }
+
+    /// Highest ask price
+    #[getter(ask_high)]
+    fn py_ask_high<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.ask_high)
+    }
+
+    /// Lowest ask price
+    #[getter(ask_low)]
+    fn py_ask_low<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.ask_low)
+    }
+
+    /// Closing ask price
+    #[getter(ask_close)]
+    fn py_ask_close<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.ask_close)
+    }
+
+    /// Mid-point close price
+    #[getter(mid_close)]
+    fn py_mid_close<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_decimal(py, self.mid_close()?)
+    }
+
+    fn __repr__(slf: &Bound<'_, Self>) -> PyResult<String> {
+        let py = slf.py();
+        let names = [
+            "bid_open",
+            "bid_high",
+            "bid_low",
+            "bid_close",
+            "ask_open",
+            "ask_high",
+            "ask_low",
+            "ask_close",
+        ];
+        let mut fields = vec![slf.as_super().get().header_repr(py)?];
+        for (name, value) in names.iter().zip(slf.get().values()) {
+            fields.push(format!("{name}={}", decimal_repr(py, value)?));
+        }
+        Ok(format!("QuoteBar({})", fields.join(", ")))
+    }
+
+    fn __richcmp__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<Py<PyAny>> {
+        bar_richcmp(slf, other, op, |b: &Self| b.values())
+    }
+
+    fn __hash__(slf: &Bound<'_, Self>) -> PyResult<isize> {
+        record_hash(slf.py(), slf.as_super().get(), &slf.get().values(), None)
+    }
+
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyType>, Bound<'py, PyTuple>)> {
+        record_reduce(slf, &slf.get().values())
+    }
+}
+
+/// Equality for records whose payload is a fixed list of `Fixed` values
+fn bar_richcmp<T, const N: usize>(
+    slf: &Bound<'_, T>,
+    other: &Bound<'_, PyAny>,
+    op: CompareOp,
+    values: fn(&T) -> [Fixed; N],
+) -> PyResult<Py<PyAny>>
+where
+    T: PyClass<BaseType = MarketData, Frozen = pyo3::pyclass::boolean_struct::True> + Sync,
+{
+    let py = slf.py();
+    let base = slf.as_super().get();
+    let other_base = other.cast::<T>().ok().map(|o| o.as_super().get());
+    record_richcmp(slf, other, op, |a, b| {
+        Ok(values(a) == values(b) && other_base.map_or(Ok(false), |other_base| base.header_eq(py, other_base))?)
+    })
+}
+
+fn record_reduce<'py, T>(slf: &Bound<'py, T>, values: &[Fixed]) -> PyResult<(Bound<'py, PyType>, Bound<'py, PyTuple>)>
+where
+    T: PyClass<BaseType = MarketData, Frozen = pyo3::pyclass::boolean_struct::True> + Sync,
+{
+    let py = slf.py();
+    let mut args = slf.as_super().get().header_items(py);
+    for value in values {
+        args.push(to_decimal(py, *value)?);
+    }
+    Ok((slf.as_any().get_type(), PyTuple::new(py, args)?))
+}
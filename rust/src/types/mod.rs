@@ -0,0 +1,29 @@
+//! Core value types shared across the crate
+
+pub mod fixed;
+pub mod instrument;
+pub mod market_data;
+pub mod price;
+pub mod time;
+
+use pyo3::prelude::*;
+
+pub use fixed::{Fixed, FixedError, RoundingMode};
+pub use instrument::{default_registry, InstrumentId, InstrumentKey, InstrumentRegistry};
+pub use market_data::{MarketData, QuoteBar, QuoteTick, Resolution, TickDirection, TradeBar, TradeTick};
+pub use price::{Price, Quantity};
+
+/// Register the value types on the extension module
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<RoundingMode>()?;
+    m.add_class::<Price>()?;
+    m.add_class::<Quantity>()?;
+    m.add_class::<InstrumentId>()?;
+    m.add_class::<InstrumentRegistry>()?;
+    m.add_class::<MarketData>()?;
+    m.add_class::<TradeTick>()?;
+    m.add_class::<QuoteTick>()?;
+    m.add_class::<TradeBar>()?;
+    m.add_class::<QuoteBar>()?;
+    Ok(())
+}
@@ -0,0 +1,329 @@
+//! Python-facing `Price` and `Quantity` types
+//!
+//! Both wrap a `Fixed` value and share the same arithmetic. Mixing them is only
+//! allowed where the result is meaningful: `Price * Quantity` (and the reverse)
+//! yields a `Price` notional, and a notional divided by a `Quantity` yields a
+//! `Price`. Dividing a price by a price, or a quantity by a quantity, yields a
+//! plain `Decimal` ratio. Adding a price to a quantity, multiplying two prices
+//! or two quantities, and ordering a price against a quantity raise
+//! `TypeError`; a price never equals a quantity.
+
+use pyo3::basic::CompareOp;
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::{PyFloat, PyInt, PyString, PyType};
+
+use crate::interop::decimal_type;
+use crate::types::fixed::{Fixed, RoundingMode};
+
+/// Fixed-point price with exact decimal arithmetic
+#[pyclass(module = "_simulor_rust", frozen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Price(pub Fixed);
+
+/// Fixed-point quantity (size, volume) with exact decimal arithmetic
+#[pyclass(module = "_simulor_rust", frozen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Quantity(pub Fixed);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Price,
+    Quantity,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// Extract an arithmetic operand: `Price`, `Quantity`, `int` or `Decimal`
+///
+/// Returns `None` for any other type so callers can answer `NotImplemented`.
+fn operand(obj: &Bound<'_, PyAny>) -> PyResult<Option<(Fixed, Option<Kind>)>> {
+    if let Ok(price) = obj.cast::<Price>() {
+        return Ok(Some((price.get().0, Some(Kind::Price))));
+    }
+    if let Ok(quantity) = obj.cast::<Quantity>() {
+        return Ok(Some((quantity.get().0, Some(Kind::Quantity))));
+    }
+    if obj.is_instance_of::<PyInt>() {
+        return Ok(Some((Fixed::from_int(obj.extract()?), None)));
+    }
+    if obj.is_instance(decimal_type(obj.py())?)? {
+        return Ok(Some((obj.str()?.to_str()?.parse()?, None)));
+    }
+    Ok(None)
+}
+
+/// Convert any numeric Python value into a `Fixed`
+///
+/// Accepts everything `operand` does plus `str` and `float`. Floats go through
+/// their shortest round-trip representation, so `0.1` becomes exactly `0.1`.
+pub fn extract_fixed(obj: &Bound<'_, PyAny>) -> PyResult<Fixed> {
+    if let Some((value, _)) = operand(obj)? {
+        return Ok(value);
+    }
+    if let Ok(text) = obj.cast::<PyString>() {
+        return Ok(text.to_str()?.parse()?);
+    }
+    if let Ok(float) = obj.cast::<PyFloat>() {
+        let value = float.value();
+        if !value.is_finite() {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "cannot convert {value} to a fixed-point value"
+            )));
+        }
+        return Ok(value.to_string().parse()?);
+    }
+    Err(PyTypeError::new_err(format!(
+        "cannot convert {} to a fixed-point value",
+        obj.get_type().name()?
+    )))
+}
+
+/// Convert a `Fixed` into a `decimal.Decimal`, preserving its scale
+pub fn to_decimal<'py>(py: Python<'py>, value: Fixed) -> PyResult<Bound<'py, PyAny>> {
+    decimal_type(py)?.call1((value.to_string(),))
+}
+
+fn wrap(py: Python<'_>, kind: Kind, value: Fixed) -> PyResult<Py<PyAny>> {
+    Ok(match kind {
+        Kind::Price => Py::new(py, Price(value))?.into_any(),
+        Kind::Quantity => Py::new(py, Quantity(value))?.into_any(),
+    })
+}
+
+fn apply(op: BinOp, lhs: Fixed, rhs: Fixed) -> PyResult<Fixed> {
+    Ok(match op {
+        BinOp::Add => lhs.checked_add(rhs)?,
+        BinOp::Sub => lhs.checked_sub(rhs)?,
+        BinOp::Mul => lhs.checked_mul(rhs)?,
+        BinOp::Div => lhs.checked_quotient(rhs)?,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Output {
+    Typed(Kind),
+    /// A dimensionless `Decimal`
+    Ratio,
+}
+
+/// Result of `lhs op rhs`, or `None` if the combination is not supported
+fn result_kind(op: BinOp, lhs: Option<Kind>, rhs: Option<Kind>) -> Option<Output> {
+    match (op, lhs, rhs) {
+        (_, Some(kind), None) | (_, None, Some(kind)) => Some(Output::Typed(kind)),
+        (BinOp::Add | BinOp::Sub, Some(a), Some(b)) if a == b => Some(Output::Typed(a)),
+        (BinOp::Mul, Some(a), Some(b)) if a != b => Some(Output::Typed(Kind::Price)),
+        (BinOp::Div, Some(Kind::Price), Some(Kind::Quantity)) => Some(Output::Typed(Kind::Price)),
+        (BinOp::Div, Some(a), Some(b)) if a == b => Some(Output::Ratio),
+        _ => None,
+    }
+}
+
+fn binary(
+    py: Python<'_>,
+    this: (Fixed, Kind),
+    other: &Bound<'_, PyAny>,
+    op: BinOp,
+    reflected: bool,
+) -> PyResult<Py<PyAny>> {
+    let Some((value, kind)) = operand(other)? else {
+        return Ok(py.NotImplemented());
+    };
+    let (lhs, rhs) = if reflected {
+        ((value, kind), (this.0, Some(this.1)))
+    } else {
+        ((this.0, Some(this.1)), (value, kind))
+    };
+    match result_kind(op, lhs.1, rhs.1) {
+        Some(Output::Typed(out)) => wrap(py, out, apply(op, lhs.0, rhs.0)?),
+        Some(Output::Ratio) => Ok(to_decimal(py, apply(op, lhs.0, rhs.0)?)?.unbind()),
+        None => Ok(py.NotImplemented()),
+    }
+}
+
+fn compare(py: Python<'_>, this: (Fixed, Kind), other: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<Py<PyAny>> {
+    match operand(other)? {
+        Some((value, kind)) if kind.unwrap_or(this.1) == this.1 => {
+            Ok(op.matches(this.0.cmp(&value)).into_pyobject(py)?.to_owned().into_any().unbind())
+        }
+        _ => Ok(py.NotImplemented()),
+    }
+}
+
+macro_rules! fixed_point_methods {
+    ($name:ident, $kind:expr) => {
+        #[pymethods]
+        impl $name {
+            /// Create from an `int`, `str`, `float`, `Decimal`, `Price` or `Quantity`
+            ///
+            /// With `scale`, the value is rescaled; dropping digits requires an
+            /// explicit `rounding` mode, otherwise `ValueError` is raised.
+            #[new]
+            #[pyo3(signature = (value, scale=None, rounding=None))]
+            fn py_new(value: &Bound<'_, PyAny>, scale: Option<u8>, rounding: Option<RoundingMode>) -> PyResult<Self> {
+                let fixed = extract_fixed(value)?;
+                let fixed = match (scale, rounding) {
+                    (Some(scale), Some(mode)) => fixed.rescale(scale, mode)?,
+                    (Some(scale), None) => fixed.rescale_exact(scale)?,
+                    (None, _) => fixed,
+                };
+                Ok($name(fixed))
+            }
+
+            /// Create directly from a scaled integer mantissa: `raw * 10^-scale`
+            #[staticmethod]
+            fn from_raw(raw: i128, scale: u8) -> PyResult<Self> {
+                Ok($name(Fixed::new(raw, scale)?))
+            }
+
+            /// Scaled integer mantissa
+            #[getter]
+            fn raw(&self) -> i128 {
+                self.0.raw()
+            }
+
+            /// Number of fractional digits
+            #[getter]
+            fn scale(&self) -> u8 {
+                self.0.scale()
+            }
+
+            /// Lossless conversion to `decimal.Decimal`
+            #[pyo3(name = "to_decimal")]
+            fn as_decimal<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+                to_decimal(py, self.0)
+            }
+
+            /// Return a copy with the given scale
+            #[pyo3(signature = (scale, rounding=RoundingMode::HalfEven))]
+            fn rescale(&self, scale: u8, rounding: RoundingMode) -> PyResult<Self> {
+                Ok($name(self.0.rescale(scale, rounding)?))
+            }
+
+            /// Round to a multiple of `tick`; the result carries the tick's scale
+            #[pyo3(signature = (tick, rounding=RoundingMode::HalfEven))]
+            fn round_to_tick(&self, tick: &Bound<'_, PyAny>, rounding: RoundingMode) -> PyResult<Self> {
+                Ok($name(self.0.round_to_tick(extract_fixed(tick)?, rounding)?))
+            }
+
+            /// Whether the value is an exact multiple of `tick`
+            fn is_on_tick(&self, tick: &Bound<'_, PyAny>) -> PyResult<bool> {
+                Ok(self.0.is_multiple_of(extract_fixed(tick)?)?)
+            }
+
+            /// Return a copy with trailing fractional zeros removed
+            fn normalize(&self) -> Self {
+                $name(self.0.normalize())
+            }
+
+            fn __str__(&self) -> String {
+                self.0.to_string()
+            }
+
+            fn __repr__(&self) -> String {
+                format!("{}('{}')", stringify!($name), self.0)
+            }
+
+            fn __hash__(&self) -> isize {
+                self.0.py_hash()
+            }
+
+            fn __bool__(&self) -> bool {
+                !self.0.is_zero()
+            }
+
+            fn __float__(&self) -> f64 {
+                self.0.to_f64()
+            }
+
+            fn __int__(&self) -> i128 {
+                self.0.trunc()
+            }
+
+            fn __richcmp__(&self, py: Python<'_>, other: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<Py<PyAny>> {
+                compare(py, (self.0, $kind), other, op)
+            }
+
+            fn __add__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+                binary(py, (self.0, $kind), other, BinOp::Add, false)
+            }
+
+            fn __radd__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+                binary(py, (self.0, $kind), other, BinOp::Add, true)
+            }
+
+            fn __sub__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+                binary(py, (self.0, $kind), other, BinOp::Sub, false)
+            }
+
+            fn __rsub__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+                binary(py, (self.0, $kind), other, BinOp::Sub, true)
+            }
+
+            fn __mul__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+                binary(py, (self.0, $kind), other, BinOp::Mul, false)
+            }
+
+            fn __rmul__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+                binary(py, (self.0, $kind), other, BinOp::Mul, true)
+            }
+
+            fn __truediv__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+                binary(py, (self.0, $kind), other, BinOp::Div, false)
+            }
+
+            fn __rtruediv__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+                binary(py, (self.0, $kind), other, BinOp::Div, true)
+            }
+
+            fn __neg__(&self) -> PyResult<Self> {
+                Ok($name(self.0.checked_neg()?))
+            }
+
+            fn __pos__(&self) -> Self {
+                *self
+            }
+
+            fn __abs__(&self) -> PyResult<Self> {
+                Ok($name(self.0.checked_abs()?))
+            }
+
+            fn __reduce__<'py>(slf: &Bound<'py, Self>) -> (Bound<'py, PyType>, (String,)) {
+                (slf.get_type(), (slf.get().0.to_string(),))
+            }
+        }
+    };
+}
+
+fixed_point_methods!(Price, Kind::Price);
+fixed_point_methods!(Quantity, Kind::Quantity);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_results_dimensionally_sound() {
+        use BinOp::*;
+        use Kind::*;
+        let (p, q) = (Some(Price), Some(Quantity));
+        assert_eq!(result_kind(Add, p, p), Some(Output::Typed(Price)));
+        assert_eq!(result_kind(Sub, q, None), Some(Output::Typed(Quantity)));
+        assert_eq!(result_kind(Mul, p, None), Some(Output::Typed(Price)));
+        assert_eq!(result_kind(Mul, p, q), Some(Output::Typed(Price)));
+        assert_eq!(result_kind(Mul, q, p), Some(Output::Typed(Price)));
+        assert_eq!(result_kind(Div, p, q), Some(Output::Typed(Price)));
+        assert_eq!(result_kind(Div, p, p), Some(Output::Ratio));
+        assert_eq!(result_kind(Div, q, q), Some(Output::Ratio));
+        assert_eq!(result_kind(Add, p, q), None);
+        assert_eq!(result_kind(Mul, p, p), None);
+        assert_eq!(result_kind(Mul, q, q), None);
+        assert_eq!(result_kind(Div, q, p), None);
+    }
+}
@@ -0,0 +1,94 @@
+//! Conversions between Python datetimes and native timestamps
+//!
+//! Native code works on `i64` nanoseconds since the Unix epoch (UTC). Python
+//! datetimes carry microsecond precision, so round trips truncate to whole
+//! microseconds. Naive datetimes are interpreted as UTC wall-clock time.
+
+use chrono::{Datelike, NaiveDate};
+use pyo3::exceptions::{PyOverflowError, PyValueError};
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::sync::PyOnceLock;
+use pyo3::types::PyString;
+
+use crate::interop::{date_type, datetime_type, timedelta_type};
+
+pub const NANOS_PER_MICRO: i64 = 1_000;
+pub const NANOS_PER_SECOND: i64 = 1_000_000_000;
+
+static EPOCH_UTC: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static EPOCH_NAIVE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static ONE_MICROSECOND: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
+fn epoch(py: Python<'_>, aware: bool) -> PyResult<&Bound<'_, PyAny>> {
+    let cell = if aware { &EPOCH_UTC } else { &EPOCH_NAIVE };
+    cell.get_or_try_init(py, || {
+        let datetime = datetime_type(py)?;
+        let value = if aware {
+            let utc = py.import("datetime")?.getattr("timezone")?.getattr("utc")?;
+            datetime.call((1970, 1, 1, 0, 0, 0, 0, utc), None)?
+        } else {
+            datetime.call1((1970, 1, 1))?
+        };
+        Ok::<_, PyErr>(value.unbind())
+    })
+    .map(|value| value.bind(py))
+}
+
+fn one_microsecond(py: Python<'_>) -> PyResult<&Bound<'_, PyAny>> {
+    ONE_MICROSECOND
+        .get_or_try_init(py, || Ok::<_, PyErr>(timedelta_type(py)?.call1((0, 0, 1))?.unbind()))
+        .map(|value| value.bind(py))
+}
+
+/// Nanoseconds since the Unix epoch for a Python `datetime`
+pub fn datetime_to_nanos(dt: &Bound<'_, PyAny>) -> PyResult<i64> {
+    let py = dt.py();
+    let aware = !dt.getattr(intern!(py, "tzinfo"))?.is_none();
+    let micros: i64 = dt.sub(epoch(py, aware)?)?.floor_div(one_microsecond(py)?)?.extract()?;
+    micros
+        .checked_mul(NANOS_PER_MICRO)
+        .ok_or_else(|| PyOverflowError::new_err("datetime out of range for nanosecond timestamps"))
+}
+
+/// Nanoseconds in a Python `timedelta`
+pub fn timedelta_to_nanos(delta: &Bound<'_, PyAny>) -> PyResult<i64> {
+    let micros: i64 = delta.floor_div(one_microsecond(delta.py())?)?.extract()?;
+    micros
+        .checked_mul(NANOS_PER_MICRO)
+        .ok_or_else(|| PyOverflowError::new_err("timedelta out of range for nanoseconds"))
+}
+
+/// Python `datetime` for a nanosecond timestamp
+///
+/// With `tzinfo`, the result is an aware datetime converted to that zone;
+/// without it, a naive UTC datetime.
+pub fn nanos_to_datetime<'py>(
+    py: Python<'py>,
+    nanos: i64,
+    tzinfo: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let delta = timedelta_type(py)?.call1((0, 0, nanos.div_euclid(NANOS_PER_MICRO)))?;
+    match tzinfo {
+        Some(tz) => epoch(py, true)?.add(delta)?.call_method1(intern!(py, "astimezone"), (tz,)),
+        None => epoch(py, false)?.add(delta),
+    }
+}
+
+/// A `datetime.date`, the wall-clock date of a `datetime`, or an ISO string
+pub fn extract_date(obj: &Bound<'_, PyAny>) -> PyResult<NaiveDate> {
+    let py = obj.py();
+    let date = if obj.is_instance_of::<PyString>() {
+        date_type(py)?.call_method1("fromisoformat", (obj,))?
+    } else {
+        obj.clone()
+    };
+    let field = |name: &str| -> PyResult<u32> { date.getattr(name)?.extract() };
+    NaiveDate::from_ymd_opt(date.getattr("year")?.extract()?, field("month")?, field("day")?)
+        .ok_or_else(|| PyValueError::new_err("Invalid date"))
+}
+
+/// Python `datetime.date` for a native date
+pub fn date_to_py(py: Python<'_>, date: NaiveDate) -> PyResult<Bound<'_, PyAny>> {
+    date_type(py)?.call1((date.year(), date.month(), date.day()))
+}
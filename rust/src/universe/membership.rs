@@ -0,0 +1,534 @@
+//! Point-in-time store of index membership
+//!
+//! Each instrument is a member over half-open date intervals: from midnight
+//! starting the date it was added, in the store's timezone, until midnight
+//! starting the date it was removed. Instruments keep their intervals after
+//! they leave the index or are delisted, so a backtest sees the members of
+//! the day rather than today's survivors.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::*;
+use arrow_array::Array;
+use arrow_schema::{DataType, TimeUnit};
+use chrono::{DateTime, NaiveDate};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ProjectionMask;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyList, PyString, PyTuple};
+
+use crate::corporate::action::extract_instrument;
+use crate::data::arrow::Strings;
+use crate::data::source::DataError;
+use crate::data::timestamp::TimestampParser;
+use crate::interop::zoneinfo_type;
+use crate::types::instrument::{default_registry, InstrumentId};
+use crate::types::time::{date_to_py, datetime_to_nanos, extract_date};
+
+/// Columns of a membership file
+const SYMBOL_COLUMN: &str = "symbol";
+const START_COLUMN: &str = "start";
+const END_COLUMN: &str = "end";
+
+/// Changes replayed at most per query; a snapshot of the members is kept
+/// every this many changes
+const SNAPSHOT_EVERY: usize = 64;
+
+/// Days from 0001-01-01 to the Unix epoch
+const EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+/// A membership interval, with the times it starts and ends
+#[derive(Debug, Clone, Copy)]
+struct Interval {
+    start: NaiveDate,
+    end: Option<NaiveDate>,
+    from: i64,
+    until: i64,
+}
+
+struct Member {
+    instrument: Py<PyAny>,
+    /// Disjoint and ordered by start
+    intervals: Vec<Interval>,
+}
+
+impl Member {
+    /// Add `interval`, merging it with any it overlaps or touches
+    fn insert(&mut self, interval: Interval) {
+        self.intervals.push(interval);
+        self.intervals.sort_by_key(|interval| interval.from);
+        let mut merged: Vec<Interval> = Vec::with_capacity(self.intervals.len());
+        for interval in self.intervals.drain(..) {
+            match merged.last_mut() {
+                Some(last) if interval.from <= last.until => {
+                    if interval.until > last.until {
+                        last.until = interval.until;
+                        last.end = interval.end;
+                    }
+                }
+                _ => merged.push(interval),
+            }
+        }
+        self.intervals = merged;
+    }
+
+    fn contains(&self, as_of: i64) -> bool {
+        let after = self.intervals.partition_point(|interval| interval.from <= as_of);
+        after > 0 && as_of < self.intervals[after - 1].until
+    }
+}
+
+/// A member joining or leaving the index
+#[derive(Debug, Clone, Copy)]
+struct Change {
+    time: i64,
+    member: usize,
+    joins: bool,
+}
+
+/// Every change in time order, with snapshots of the members between them
+struct Timeline {
+    changes: Vec<Change>,
+    /// Members after the first `i * SNAPSHOT_EVERY` changes, at index `i`
+    snapshots: Vec<Vec<usize>>,
+}
+
+impl Timeline {
+    fn new(members: &[Member]) -> Self {
+        let mut changes: Vec<Change> = members
+            .iter()
+            .enumerate()
+            .flat_map(|(member, entry)| {
+                entry.intervals.iter().flat_map(move |interval| {
+                    let joins = Change {
+                        time: interval.from,
+                        member,
+                        joins: true,
+                    };
+                    let leaves = (interval.until != i64::MAX).then_some(Change {
+                        time: interval.until,
+                        member,
+                        joins: false,
+                    });
+                    std::iter::once(joins).chain(leaves)
+                })
+            })
+            .collect();
+        // Departures first, so a replacement never briefly overlaps the
+        // member it replaces
+        changes.sort_by_key(|change| (change.time, change.joins, change.member));
+
+        let mut snapshots = vec![Vec::new()];
+        let mut current = BTreeSet::new();
+        for (index, change) in changes.iter().enumerate() {
+            apply(&mut current, change);
+            if (index + 1) % SNAPSHOT_EVERY == 0 {
+                snapshots.push(current.iter().copied().collect());
+            }
+        }
+        Timeline { changes, snapshots }
+    }
+
+    /// Members at `as_of`, in the order they were first added
+    fn members(&self, as_of: i64) -> Vec<usize> {
+        let applied = self.changes.partition_point(|change| change.time <= as_of);
+        let snapshot = applied / SNAPSHOT_EVERY;
+        let mut current: BTreeSet<usize> = self.snapshots[snapshot].iter().copied().collect();
+        for change in &self.changes[snapshot * SNAPSHOT_EVERY..applied] {
+            apply(&mut current, change);
+        }
+        current.into_iter().collect()
+    }
+}
+
+fn apply(members: &mut BTreeSet<usize>, change: &Change) {
+    if change.joins {
+        members.insert(change.member);
+    } else {
+        members.remove(&change.member);
+    }
+}
+
+/// Members by instrument, with a timeline built on first query
+#[derive(Default)]
+struct Membership {
+    members: Vec<Member>,
+    by_instrument: HashMap<InstrumentId, usize>,
+    timeline: Option<Timeline>,
+}
+
+impl Membership {
+    fn add(&mut self, id: InstrumentId, instrument: &Bound<'_, PyAny>, interval: Interval) {
+        let members = &mut self.members;
+        let index = *self.by_instrument.entry(id).or_insert_with(|| {
+            members.push(Member {
+                instrument: instrument.clone().unbind(),
+                intervals: Vec::new(),
+            });
+            members.len() - 1
+        });
+        self.members[index].insert(interval);
+        self.timeline = None;
+    }
+
+    fn timeline(&mut self) -> &Timeline {
+        let members = &self.members;
+        self.timeline.get_or_insert_with(|| Timeline::new(members))
+    }
+}
+
+/// A row of a membership file
+struct Row {
+    symbol: String,
+    start: NaiveDate,
+    end: Option<NaiveDate>,
+}
+
+fn open(path: &Path) -> Result<File, DataError> {
+    File::open(path).map_err(|source| DataError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()
+}
+
+/// Read intervals from a CSV file with `symbol`, `start` and `end` columns
+fn read_csv(path: &Path) -> Result<Vec<Row>, DataError> {
+    let malformed = |message: String| DataError::Malformed {
+        path: path.to_path_buf(),
+        message,
+    };
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(open(path)?);
+    let headers = reader.headers().map_err(|err| malformed(err.to_string()))?.clone();
+    let position = |column: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim() == column)
+            .ok_or_else(|| DataError::MissingColumn {
+                format: "CSV",
+                path: path.to_path_buf(),
+                column: column.to_owned(),
+            })
+    };
+    let columns = [position(SYMBOL_COLUMN)?, position(START_COLUMN)?, position(END_COLUMN)?];
+
+    let mut rows = Vec::new();
+    for (row, record) in reader.records().enumerate() {
+        let record = record.map_err(|err| malformed(err.to_string()))?;
+        let line = row + 2;
+        let [symbol, start, end] = columns.map(|column| record.get(column).unwrap_or("").trim());
+        let invalid = |what: &str, text: &str| malformed(format!("line {line}: invalid {what} '{text}'"));
+        if symbol.is_empty() {
+            return Err(invalid("symbol", symbol));
+        }
+        let start_date = parse_date(start).ok_or_else(|| invalid("start date", start))?;
+        let end_date = match end {
+            "" => None,
+            _ => Some(parse_date(end).ok_or_else(|| invalid("end date", end))?),
+        };
+        rows.push(Row {
+            symbol: symbol.to_owned(),
+            start: start_date,
+            end: end_date,
+        });
+    }
+    Ok(rows)
+}
+
+/// Date at `row` of a date, timestamp or ISO date string column; `Some(None)`
+/// for a null, `None` if the value cannot be read as a date
+fn date_at(array: &dyn Array, row: usize, parser: &TimestampParser) -> Option<Option<NaiveDate>> {
+    if array.is_null(row) {
+        return Some(None);
+    }
+    let date = match array.data_type() {
+        DataType::Date32 => NaiveDate::from_num_days_from_ce_opt(
+            array.as_primitive::<Date32Type>().value(row).checked_add(EPOCH_DAYS_FROM_CE)?,
+        )?,
+        DataType::Date64 => {
+            DateTime::from_timestamp_millis(array.as_primitive::<Date64Type>().value(row))?.date_naive()
+        }
+        DataType::Timestamp(unit, tz) => {
+            let (value, scale) = match unit {
+                TimeUnit::Second => (array.as_primitive::<TimestampSecondType>().value(row), 1_000_000_000),
+                TimeUnit::Millisecond => (array.as_primitive::<TimestampMillisecondType>().value(row), 1_000_000),
+                TimeUnit::Microsecond => (array.as_primitive::<TimestampMicrosecondType>().value(row), 1_000),
+                TimeUnit::Nanosecond => (array.as_primitive::<TimestampNanosecondType>().value(row), 1),
+            };
+            let nanos = value.checked_mul(scale)?;
+            match tz {
+                // An instant: the date it falls on in the store's timezone
+                Some(_) => parser.to_local(nanos).date(),
+                None => DateTime::from_timestamp(nanos.div_euclid(1_000_000_000), 0)?.date_naive(),
+            }
+        }
+        _ => parse_date(Strings::new(array)?.value(row).trim())?,
+    };
+    Some(Some(date))
+}
+
+/// Read intervals from a Parquet file with `symbol`, `start` and `end`
+/// columns
+fn read_parquet(path: &Path, parser: &TimestampParser) -> Result<Vec<Row>, DataError> {
+    let malformed = |message: String| DataError::Malformed {
+        path: path.to_path_buf(),
+        message,
+    };
+    let builder = ParquetRecordBatchReaderBuilder::try_new(open(path)?).map_err(|err| malformed(err.to_string()))?;
+    let columns =
+        [SYMBOL_COLUMN, START_COLUMN, END_COLUMN].map(|column| builder.schema().index_of(column).map_err(|_| column));
+    let mut indices = Vec::with_capacity(columns.len());
+    for column in columns {
+        indices.push(column.map_err(|column| DataError::MissingColumn {
+            format: "Parquet",
+            path: path.to_path_buf(),
+            column: column.to_owned(),
+        })?);
+    }
+    let projection = ProjectionMask::roots(builder.parquet_schema(), indices);
+    let reader = builder.with_projection(projection).build().map_err(|err| malformed(err.to_string()))?;
+
+    let mut rows = Vec::new();
+    for batch in reader {
+        let batch = batch.map_err(|err| malformed(err.to_string()))?;
+        let [symbols, starts, ends] = [SYMBOL_COLUMN, START_COLUMN, END_COLUMN]
+            .map(|column| batch.column_by_name(column).expect("projected column"));
+        let strings = Strings::new(symbols.as_ref())
+            .ok_or_else(|| malformed(format!("column '{SYMBOL_COLUMN}' is not a string")))?;
+        for row in 0..batch.num_rows() {
+            let line = rows.len() + 1;
+            let invalid = |what: &str| malformed(format!("row {line}: invalid {what}"));
+            let symbol = strings.value(row).trim();
+            if symbols.is_null(row) || symbol.is_empty() {
+                return Err(invalid("symbol"));
+            }
+            let start = date_at(starts.as_ref(), row, parser).flatten().ok_or_else(|| invalid("start date"))?;
+            let end = date_at(ends.as_ref(), row, parser).ok_or_else(|| invalid("end date"))?;
+            rows.push(Row {
+                symbol: symbol.to_owned(),
+                start,
+                end,
+            });
+        }
+    }
+    Ok(rows)
+}
+
+/// Index constituents over time, answering which instruments were members
+/// at a given time
+///
+/// Load intervals from a file with `from_csv()` or `from_parquet()`, or
+/// pass `(instrument, start, end)` tuples, with `end` `None` for a current
+/// member. `start` is the date the instrument was added, `end` the date it
+/// was removed. `timezone` sets the midnight each date starts at. Query
+/// times accept aware datetimes, or naive ones in UTC.
+///
+/// A query replays at most a few dozen changes after a binary search, so
+/// asking at every event of a backtest stays cheap.
+#[pyclass(module = "_simulor_rust", frozen)]
+pub struct MembershipStore {
+    parser: TimestampParser,
+    timezone_info: Py<PyAny>,
+    membership: Mutex<Membership>,
+}
+
+impl MembershipStore {
+    fn interval(&self, start: NaiveDate, end: Option<NaiveDate>) -> PyResult<Interval> {
+        let midnight = |date: NaiveDate| {
+            date.and_hms_opt(0, 0, 0)
+                .and_then(|midnight| self.parser.localize(&midnight))
+                .ok_or_else(|| PyValueError::new_err(format!("Date out of range: {date}")))
+        };
+        if end.is_some_and(|end| end <= start) {
+            return Err(PyValueError::new_err(format!(
+                "Membership must end after it starts: {start} to {}",
+                end.expect("checked above")
+            )));
+        }
+        Ok(Interval {
+            start,
+            end,
+            from: midnight(start)?,
+            until: end.map(midnight).transpose()?.unwrap_or(i64::MAX),
+        })
+    }
+
+    fn insert(&self, instrument: &Bound<'_, PyAny>, start: NaiveDate, end: Option<NaiveDate>) -> PyResult<()> {
+        let instrument = extract_instrument(instrument)?;
+        let id = default_registry(instrument.py())?.get().intern_instrument(&instrument)?;
+        let interval = self.interval(start, end)?;
+        self.membership.lock().unwrap().add(id, &instrument, interval);
+        Ok(())
+    }
+
+    /// Add rows read from a file; symbols are taken as stocks
+    fn insert_rows(&self, py: Python<'_>, rows: Vec<Row>) -> PyResult<()> {
+        let mut instruments: HashMap<String, Bound<'_, PyAny>> = HashMap::new();
+        for row in rows {
+            if !instruments.contains_key(&row.symbol) {
+                let instrument = extract_instrument(PyString::new(py, &row.symbol).as_any())?;
+                instruments.insert(row.symbol.clone(), instrument);
+            }
+            self.insert(&instruments[&row.symbol], row.start, row.end)?;
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl MembershipStore {
+    #[new]
+    #[pyo3(signature = (intervals=None, timezone="UTC"))]
+    fn py_new(py: Python<'_>, intervals: Option<&Bound<'_, PyAny>>, timezone: &str) -> PyResult<Self> {
+        let store = MembershipStore {
+            parser: TimestampParser::new(timezone)?,
+            timezone_info: zoneinfo_type(py)?.call1((timezone,))?.unbind(),
+            membership: Mutex::new(Membership::default()),
+        };
+        for interval in intervals.map(|intervals| intervals.try_iter()).transpose()?.into_iter().flatten() {
+            let (instrument, start, end): (Bound<'_, PyAny>, Bound<'_, PyAny>, Option<Bound<'_, PyAny>>) =
+                interval?.extract()?;
+            store.add(&instrument, &start, end.as_ref())?;
+        }
+        Ok(store)
+    }
+
+    /// Load intervals from a CSV file
+    ///
+    /// Columns: `symbol`, `start` (the date added, YYYY-MM-DD) and `end`
+    /// (the date removed, empty for a current member). Symbols are taken
+    /// as stocks.
+    #[staticmethod]
+    #[pyo3(signature = (path, timezone="UTC"))]
+    fn from_csv(py: Python<'_>, path: PathBuf, timezone: &str) -> PyResult<Self> {
+        let store = MembershipStore::py_new(py, None, timezone)?;
+        store.insert_rows(py, read_csv(&path)?)?;
+        Ok(store)
+    }
+
+    /// Load intervals from a Parquet file
+    ///
+    /// Columns as for `from_csv()`; dates may be stored as dates,
+    /// timestamps or YYYY-MM-DD strings, with null `end` for a current
+    /// member. Timestamps with a timezone fall on their date in `timezone`.
+    #[staticmethod]
+    #[pyo3(signature = (path, timezone="UTC"))]
+    fn from_parquet(py: Python<'_>, path: PathBuf, timezone: &str) -> PyResult<Self> {
+        let store = MembershipStore::py_new(py, None, timezone)?;
+        let rows = read_parquet(&path, &store.parser)?;
+        store.insert_rows(py, rows)?;
+        Ok(store)
+    }
+
+    /// Timezone dates start in
+    #[getter]
+    fn timezone_info(&self, py: Python<'_>) -> Py<PyAny> {
+        self.timezone_info.clone_ref(py)
+    }
+
+    /// Add a membership interval of `instrument`, an `Instrument` or a
+    /// stock symbol; overlapping intervals are merged
+    #[pyo3(signature = (instrument, start, end=None))]
+    fn add(
+        &self,
+        instrument: &Bound<'_, PyAny>,
+        start: &Bound<'_, PyAny>,
+        end: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<()> {
+        let end = end.filter(|end| !end.is_none()).map(extract_date).transpose()?;
+        self.insert(instrument, extract_date(start)?, end)
+    }
+
+    /// Members at `as_of`, in the order they were first added
+    fn members<'py>(&self, py: Python<'py>, as_of: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyList>> {
+        let as_of = datetime_to_nanos(as_of)?;
+        let mut membership = self.membership.lock().unwrap();
+        let members = membership.timeline().members(as_of);
+        let instruments: Vec<Py<PyAny>> = members
+            .into_iter()
+            .map(|member| membership.members[member].instrument.clone_ref(py))
+            .collect();
+        PyList::new(py, instruments)
+    }
+
+    /// Whether `instrument` was a member at `as_of`
+    fn is_member(&self, instrument: &Bound<'_, PyAny>, as_of: &Bound<'_, PyAny>) -> PyResult<bool> {
+        let instrument = extract_instrument(instrument)?;
+        let Some(id) = default_registry(instrument.py())?.get().lookup_instrument(&instrument)? else {
+            return Ok(false);
+        };
+        let as_of = datetime_to_nanos(as_of)?;
+        let membership = self.membership.lock().unwrap();
+        Ok(membership
+            .by_instrument
+            .get(&id)
+            .is_some_and(|&member| membership.members[member].contains(as_of)))
+    }
+
+    /// Every instrument that was ever a member, including those since
+    /// removed
+    fn instruments<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let membership = self.membership.lock().unwrap();
+        let instruments: Vec<Py<PyAny>> =
+            membership.members.iter().map(|member| member.instrument.clone_ref(py)).collect();
+        PyList::new(py, instruments)
+    }
+
+    /// `(instrument, start, end)` of every interval, or those of
+    /// `instrument`, after merging
+    #[pyo3(signature = (instrument=None))]
+    fn intervals<'py>(&self, py: Python<'py>, instrument: Option<&Bound<'py, PyAny>>) -> PyResult<Bound<'py, PyList>> {
+        let id = match instrument {
+            Some(instrument) => {
+                match default_registry(py)?.get().lookup_instrument(&extract_instrument(instrument)?)? {
+                    Some(id) => Some(id),
+                    None => return Ok(PyList::empty(py)),
+                }
+            }
+            None => None,
+        };
+        let membership = self.membership.lock().unwrap();
+        let selected: Vec<&Member> = match id {
+            Some(id) => membership
+                .by_instrument
+                .get(&id)
+                .map(|&member| &membership.members[member])
+                .into_iter()
+                .collect(),
+            None => membership.members.iter().collect(),
+        };
+        let mut out = Vec::new();
+        for member in selected {
+            for interval in &member.intervals {
+                let end = interval.end.map(|end| date_to_py(py, end)).transpose()?;
+                out.push(PyTuple::new(
+                    py,
+                    [
+                        member.instrument.bind(py).clone(),
+                        date_to_py(py, interval.start)?,
+                        end.unwrap_or_else(|| py.None().into_bound(py)),
+                    ],
+                )?);
+            }
+        }
+        PyList::new(py, out)
+    }
+
+    fn __len__(&self) -> usize {
+        self.membership.lock().unwrap().members.len()
+    }
+
+    fn __repr__(&self) -> String {
+        let membership = self.membership.lock().unwrap();
+        let intervals: usize = membership.members.iter().map(|member| member.intervals.len()).sum();
+        format!("MembershipStore(instruments={}, intervals={})", membership.members.len(), intervals)
+    }
+}
@@ -0,0 +1,17 @@
+//! Universe membership
+//!
+//! Index constituents loaded once and queried point in time, so a
+//! backtest trades the members of the day, including those later removed
+//! or delisted, rather than today's survivors.
+
+pub mod membership;
+
+use pyo3::prelude::*;
+
+pub use membership::MembershipStore;
+
+/// Register the universe membership classes on the extension module
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<MembershipStore>()?;
+    Ok(())
+}
@@ -31,6 +31,7 @@ from simulor.analytics.returns import (
 )
 
 if TYPE_CHECKING:
+    from simulor.data.quality import DataQualityReport
     from simulor.portfolio.recorder import TimeSeriesRecorder
     from simulor.types import Fill
 
@@ -75,6 +76,7 @@ class BacktestResult:
         - start_date: Backtest start timestamp
         - end_date: Backtest end timestamp
         - benchmark_returns: Optional benchmark time series
+        - data_quality: Optional per-instrument report of the data quality checks
 
         Computed metrics (set in __post_init__):
         - total_return, annualized_return, cagr
@@ -97,6 +99,7 @@ class BacktestResult:
     # Optional inputs
     strategy_recorders: dict[str, TimeSeriesRecorder] = field(default_factory=dict)
     benchmark_returns: list[tuple[datetime, float]] | None = None
+    data_quality: DataQualityReport | None = None
 
     # Computed metrics (initialized in __post_init__)
     # Returns
@@ -432,6 +435,18 @@ class BacktestResult:
                 ]
             )
 
+        if self.data_quality is not None:
+            lines.extend(
+                [
+                    "",
+                    "DATA QUALITY",
+                    f"  Records Checked:    {self.data_quality.records:>8}",
+                    f"  Dropped:            {self.data_quality.dropped:>8}",
+                    f"  Repaired:           {self.data_quality.repaired:>8}",
+                    f"  Flagged:            {self.data_quality.flagged:>8}",
+                ]
+            )
+
         lines.append("=" * 60)
 
         return "\n".join(lines)
@@ -494,6 +509,18 @@ class BacktestResult:
                 "tracking_error": float(self.tracking_error) if self.tracking_error is not None else None,
             }
 
+        if self.data_quality is not None:
+            result["data_quality"] = {
+                str(quality.instrument.symbol): {
+                    "records": quality.records,
+                    "dropped": quality.dropped,
+                    "repaired": quality.repaired,
+                    "flagged": quality.flagged,
+                    "issues": {issue.name.lower(): count for issue, count in quality.issues.items()},
+                }
+                for quality in self.data_quality
+            }
+
         return result
 
     def plot_equity_curve(self, title: str = "Portfolio Equity Curve") -> go.Figure:
@@ -12,10 +12,10 @@ T = TypeVar("T")
 
 
 class ReadOnlySequence(Sequence[T]):
-    """Read-only view of a list.
+    """Read-only view of a list or other sequence.
 
     Provides sequence protocol without allowing modification of underlying data.
-    All operations are zero-copy views of the internal list.
+    All operations are zero-copy views of the internal sequence.
 
     Examples:
         >>> data = [1, 2, 3, 4, 5]
@@ -34,7 +34,7 @@ class ReadOnlySequence(Sequence[T]):
 
     __slots__ = ("_data",)
 
-    def __init__(self, data: list[T]) -> None:
+    def __init__(self, data: Sequence[T]) -> None:
         self._data = data
 
     @overload
@@ -5,18 +5,28 @@ Event system for the Simulor trading simulation framework.
 
 from __future__ import annotations
 
+import contextlib
 import queue
 import threading
+from abc import ABCMeta
 from collections import defaultdict
 from collections.abc import Callable
 from dataclasses import dataclass, field
 from datetime import datetime
 from enum import Enum, auto
-from typing import Any, Literal
+from typing import TYPE_CHECKING, Any, Literal
 
 from simulor.logging import get_logger
 from simulor.types import Instrument, MarketData, QuoteBar, QuoteTick, Resolution, TradeBar, TradeTick
 
+if TYPE_CHECKING:
+    from _simulor_rust import BookUpdate
+else:
+    # Book updates are only implemented natively, in the Rust extension
+    BookUpdate = None
+    with contextlib.suppress(ImportError):
+        from _simulor_rust import BookUpdate
+
 # Create module logger for event-related logging
 logger = get_logger(__name__)
 
@@ -32,13 +42,18 @@ class EventType(Enum):
     END_OF_STREAM = auto()
     MARKET = auto()
     FILL = auto()
+    PARTIAL_FILL = auto()
+    ORDER_TRIGGERED = auto()
 
 
 @dataclass(slots=True)
-class Event:
+class Event(metaclass=ABCMeta):
     """
     Base class for all events in the simulation.
 
+    Events implemented outside this module (such as the native `MarketEvent`)
+    join the hierarchy by registering with the matching base class.
+
     Attributes:
         type (EventType): The type of the event.
         time (datetime): The timestamp when the event occurred.
@@ -84,6 +99,7 @@ class MarketEvent(DataEvent):
     _quote_ticks: dict[Instrument, list[QuoteTick]] = field(default_factory=dict, repr=False)
     _trade_bars: dict[Instrument, dict[Resolution, TradeBar]] = field(default_factory=dict, repr=False)
     _quote_bars: dict[Instrument, dict[Resolution, QuoteBar]] = field(default_factory=dict, repr=False)
+    _book_updates: dict[Instrument, list[BookUpdate]] = field(default_factory=dict, repr=False)
 
     @property
     def count(self) -> int:
@@ -101,6 +117,7 @@ class MarketEvent(DataEvent):
         instruments.update(self._quote_ticks.keys())
         instruments.update(self._trade_bars.keys())
         instruments.update(self._quote_bars.keys())
+        instruments.update(self._book_updates.keys())
         return instruments
 
     def filter_by_instrument(self, instruments: set[Instrument]) -> MarketEvent:
@@ -147,6 +164,13 @@ class MarketEvent(DataEvent):
                 filtered_event._quote_bars[inst] = quote_bar
                 count += len(quote_bar)
 
+        # 5. Book Updates
+        for inst in instruments:
+            book_updates = self._book_updates.get(inst)
+            if book_updates:
+                filtered_event._book_updates[inst] = book_updates
+                count += len(book_updates)
+
         filtered_event._count = count
         return filtered_event
 
@@ -170,6 +194,9 @@ class MarketEvent(DataEvent):
         for quote_bars in self._quote_bars.values():
             data_points.extend(quote_bars.values())
 
+        for book_updates in self._book_updates.values():
+            data_points.extend(book_updates)
+
         return data_points
 
     def add(self, market_data: MarketData) -> None:
@@ -188,6 +215,8 @@ class MarketEvent(DataEvent):
             self._trade_bars.setdefault(market_data.instrument, {})[market_data.resolution] = market_data
         elif isinstance(market_data, QuoteBar):
             self._quote_bars.setdefault(market_data.instrument, {})[market_data.resolution] = market_data
+        elif BookUpdate is not None and isinstance(market_data, BookUpdate):
+            self._book_updates.setdefault(market_data.instrument, []).append(market_data)
         else:
             raise TypeError(f"Unknown data type: {type(market_data)}")
 
@@ -213,6 +242,21 @@ class MarketEvent(DataEvent):
         """Get all quote bars grouped by instrument."""
         return self._quote_bars
 
+    @property
+    def book_updates(self) -> dict[Instrument, list[BookUpdate]]:
+        """Get all book updates grouped by instrument."""
+        return self._book_updates
+
+    def get_book_updates(self, instrument: Instrument) -> list[BookUpdate]:
+        """Get the book updates for a given instrument, in arrival order.
+
+        Args:
+            instrument: The instrument to retrieve book updates for.
+        Returns:
+            The BookUpdates for the instrument, or an empty list if none are available.
+        """
+        return self._book_updates.get(instrument, [])
+
     def get_last_trade_tick(self, instrument: Instrument) -> TradeTick | None:
         """Get the last trade tick for a given instrument.
 
@@ -423,3 +467,14 @@ class EventBus:
             self._data_queue.task_done()
         elif queue_type == "system":
             self._system_queue.task_done()
+
+
+# Prefer the native bus and market event from the Rust extension when it is installed.
+# The bus keeps the same interface and backpressure semantics, but waits with the GIL
+# released. The event keeps the same interface, so every market event built through
+# this module, by providers, feeds, the engine or strategies, is indexed natively.
+if not TYPE_CHECKING:
+    with contextlib.suppress(ImportError):
+        from _simulor_rust import EventBus, MarketEvent  # noqa: F811
+
+        DataEvent.register(MarketEvent)
@@ -16,11 +16,13 @@ from collections.abc import Iterable
 from typing import TYPE_CHECKING
 
 if TYPE_CHECKING:
+    from datetime import datetime
     from decimal import Decimal
 
     from simulor.alpha.signal import Signal
     from simulor.core.events import DataEvent, EventBus, MarketEvent
     from simulor.data.market_store import MarketStore
+    from simulor.data.quality import DataQualityReport
     from simulor.portfolio.manager import Portfolio
     from simulor.types import Instrument, OrderSpec
 
@@ -29,12 +31,15 @@ class Context:
     """Execution context for all component models.
 
     Provides access to engine state for strategy components.
+    `time` is the time of the event being processed, or None before the
+    first one.
     """
 
     def __init__(self, market_store: MarketStore, portfolio: Portfolio, event_bus: EventBus) -> None:
         self.market_store = market_store
         self.portfolio = portfolio
         self.event_bus = event_bus
+        self.time: datetime | None = None
 
 
 class Model:
@@ -59,6 +64,11 @@ class Model:
         """Get the event bus from the context."""
         return self._context.event_bus
 
+    @property
+    def time(self) -> datetime | None:
+        """Get the time of the event being processed from the context."""
+        return self._context.time
+
 
 class Feed(ABC):
     _event_bus: EventBus
@@ -79,6 +89,11 @@ class Feed(ABC):
         """Publish a data event to the event bus."""
         self._event_bus.publish(event)
 
+    @property
+    def data_quality(self) -> DataQualityReport | None:
+        """Quality report of the data published so far, if the feed checks its data."""
+        return None
+
 
 class UniverseSelectionModel(Model, ABC):
     """Protocol for universe selection.
@@ -1,3 +1,101 @@
-"""Data caching layer for performance optimization."""
+"""Data caching layer for performance optimization.
+
+Market data is cached in the native tick store format: a single versioned,
+columnar file that is memory-mapped on read, so repeated backtests over the
+same history skip re-parsing the source files. Requires the `_simulor_rust`
+extension.
+"""
 
 from __future__ import annotations
+
+import contextlib
+from collections.abc import Iterator
+from pathlib import Path
+from typing import TYPE_CHECKING
+
+from simulor.core.events import MarketEvent
+from simulor.data.providers.base import DataProvider
+from simulor.logging import get_logger
+
+if TYPE_CHECKING:
+    from _simulor_rust import TickStoreDataProvider, TickStoreWriter
+else:
+    # The tick store is only implemented natively, in the Rust extension
+    TickStoreDataProvider = TickStoreWriter = None
+    with contextlib.suppress(ImportError):
+        from _simulor_rust import TickStoreDataProvider, TickStoreWriter
+
+__all__ = [
+    "CachedDataProvider",
+]
+
+logger = get_logger(__name__)
+
+# Whether the tick store could be imported
+_NATIVE = TickStoreDataProvider is not None
+
+if _NATIVE:
+    DataProvider.register(TickStoreDataProvider)
+    __all__ += ["TickStoreDataProvider", "TickStoreWriter"]
+
+
+class CachedDataProvider(DataProvider):
+    """Serve another provider's data from a tick store file.
+
+    The store is written from the source provider on first iteration (or
+    whenever `rebuild` is set) and read back memory-mapped afterwards.
+
+    Example:
+        >>> source = CSVDataProvider(path='data/', resolution=Resolution.TICK)
+        >>> provider = CachedDataProvider(source, cache_path='cache/ticks.simtick')
+        >>> for event in provider:  # first pass writes the cache
+        ...     handle(event)
+    """
+
+    def __init__(
+        self,
+        source: DataProvider,
+        cache_path: str | Path,
+        timezone: str = "UTC",
+        rebuild: bool = False,
+    ) -> None:
+        """Initialize cached provider.
+
+        Args:
+            source: Provider the cache is built from
+            cache_path: Tick store file to read, or to create if missing
+            timezone: Timezone of the datetimes the cached events carry
+            rebuild: Rewrite the store from `source` on next iteration
+
+        Raises:
+            RuntimeError: If the `_simulor_rust` extension is not installed
+        """
+        if not _NATIVE:
+            raise RuntimeError("CachedDataProvider requires the _simulor_rust extension, which is not installed.")
+        self.source = source
+        self.cache_path = Path(cache_path)
+        self.timezone = timezone
+        self.rebuild = rebuild
+
+    def build(self) -> int:
+        """Write the store from the source provider.
+
+        Returns:
+            Number of records written
+        """
+        self.cache_path.parent.mkdir(parents=True, exist_ok=True)
+        with TickStoreWriter(self.cache_path) as writer:
+            writer.write(self.source)
+        logger.info("Built tick store cache %s", self.cache_path)
+        return writer.rows_written
+
+    def store(self) -> TickStoreDataProvider:
+        """Open the store, building it first if needed."""
+        if self.rebuild or not self.cache_path.exists():
+            self.build()
+            self.rebuild = False
+        return TickStoreDataProvider(self.cache_path, timezone=self.timezone)
+
+    def __iter__(self) -> Iterator[MarketEvent]:
+        """Return a new iterator over the cached events."""
+        return iter(self.store())
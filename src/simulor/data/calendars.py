@@ -0,0 +1,24 @@
+"""Exchange trading calendars.
+
+Rule-based calendars for NYSE, NASDAQ, CME Globex, LSE, HKEX and round-the-clock
+crypto venues. They know each exchange's regular hours, pre-market and
+after-hours sessions, midday breaks, holidays and early closes, and answer
+questions such as whether the market is open at a given time, when it next
+opens or last closed, and which trade date a timestamp belongs to. Requires
+the `_simulor_rust` extension.
+
+Example:
+    >>> from simulor.data.calendars import ExchangeCalendar
+    >>> nyse = ExchangeCalendar("NYSE")
+    >>> nyse.is_early_close("2024-07-03")
+    True
+"""
+
+from __future__ import annotations
+
+from _simulor_rust import ExchangeCalendar, TradingSession
+
+__all__ = [
+    "ExchangeCalendar",
+    "TradingSession",
+]
@@ -0,0 +1,37 @@
+"""Streaming bar consolidation.
+
+Consolidators aggregate ticks into bars, or fine bars into coarser ones, as
+records stream past. Besides time bars there are information-driven bars for
+research: tick, volume and dollar bars, and imbalance and run bars, all
+emitted as `TradeBar`s. `ConsolidatingDataProvider` wraps any data provider and
+adds the bars to its `MarketEvent` stream as they close. Requires the
+`_simulor_rust` extension.
+"""
+
+from __future__ import annotations
+
+from _simulor_rust import (
+    BarConsolidator,
+    ConsolidatingDataProvider,
+    DollarBarConsolidator,
+    ImbalanceBarConsolidator,
+    RunBarConsolidator,
+    TickBarConsolidator,
+    TimeBarConsolidator,
+    VolumeBarConsolidator,
+)
+
+from simulor.data.providers.base import DataProvider
+
+__all__ = [
+    "BarConsolidator",
+    "ConsolidatingDataProvider",
+    "DollarBarConsolidator",
+    "ImbalanceBarConsolidator",
+    "RunBarConsolidator",
+    "TickBarConsolidator",
+    "TimeBarConsolidator",
+    "VolumeBarConsolidator",
+]
+
+DataProvider.register(ConsolidatingDataProvider)
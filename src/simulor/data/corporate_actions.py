@@ -0,0 +1,27 @@
+"""Corporate actions: splits, cash dividends and symbol changes.
+
+`CorporateActionStore` holds the actions of a backtest, loaded from a CSV file
+or built from `CorporateAction`s. Given to `Engine`, each action adjusts
+positions and cash when its ex-date arrives, and `MarketStore` lookbacks are
+restated for the actions in effect so far, back-adjusted or as total return,
+while fills keep using the raw prices. Requires the `_simulor_rust` extension.
+
+Example:
+    >>> from simulor.data.corporate_actions import CorporateAction, CorporateActionStore
+    >>> actions = CorporateActionStore([
+    ...     CorporateAction.split("AAPL", "2020-08-31", "4:1"),
+    ...     CorporateAction.dividend("AAPL", "2020-11-06", "0.205"),
+    ...     CorporateAction.symbol_change("FB", "2022-06-09", "META"),
+    ... ], timezone="America/New_York")
+"""
+
+from __future__ import annotations
+
+from _simulor_rust import CorporateAction, CorporateActionStore, CorporateActionType, PriceAdjustment
+
+__all__ = [
+    "CorporateAction",
+    "CorporateActionStore",
+    "CorporateActionType",
+    "PriceAdjustment",
+]
@@ -1,5 +1,9 @@
+from __future__ import annotations
+
+from collections.abc import Iterable, Sequence
 from datetime import datetime
 from pathlib import Path
+from typing import TYPE_CHECKING
 from zoneinfo import ZoneInfo
 
 from simulor.core.events import EndOfStreamEvent
@@ -8,6 +12,12 @@ from simulor.data.providers.csv import CSVDataProvider
 from simulor.logging import get_logger
 from simulor.types import Resolution
 
+if TYPE_CHECKING:
+    from simulor.core.events import MarketEvent
+    from simulor.data.futures import ContinuousFuture
+    from simulor.data.providers.base import DataProvider
+    from simulor.data.quality import DataQualityPolicy, DataQualityReport
+
 logger = get_logger(__name__)
 
 
@@ -26,6 +36,8 @@ class CsvFeed(Feed):
         symbol_column: str = "symbol",
         instrument_type_column: str = "instrument_type",
         timezone: str = "UTC",
+        quality: DataQualityPolicy | None = None,
+        continuous_futures: Sequence[ContinuousFuture] | None = None,
     ) -> None:
         """Initialize the CSV feed.
 
@@ -39,18 +51,68 @@ class CsvFeed(Feed):
             instrument_type_column: Name of the column containing instrument types.
                 Defaults to "instrument_type".
             timezone: Timezone of the timestamps in the CSV. Defaults to "UTC".
+            quality: Optional data quality policy. Rows are then checked, and dropped, repaired
+                or flagged, instead of invalid ones being skipped silently.
+            continuous_futures: Optional continuous futures to stitch from the contracts in the files.
+                Their records are added to each event, after any quality checks.
         """
-        self._provider = CSVDataProvider(
-            path=path,
-            resolution=resolution,
-            date_column=date_column,
-            symbol_column=symbol_column,
-            instrument_type_column=instrument_type_column,
-            timezone=timezone,
-        )
+        self._provider: DataProvider
+        if quality is None and not continuous_futures:
+            self._provider = CSVDataProvider(
+                path=path,
+                resolution=resolution,
+                date_column=date_column,
+                symbol_column=symbol_column,
+                instrument_type_column=instrument_type_column,
+                timezone=timezone,
+            )
+        else:
+            try:
+                from _simulor_rust import CSVDataProvider as NativeCSVDataProvider
+            except ImportError as exc:
+                options = [
+                    name
+                    for name, value in (("quality", quality), ("continuous_futures", continuous_futures))
+                    if value
+                ]
+                verb = "require" if len(options) > 1 else "requires"
+                raise RuntimeError(
+                    f"CsvFeed {' and '.join(options)} {verb} the _simulor_rust extension, which is not installed."
+                ) from exc
+
+            self._provider = NativeCSVDataProvider(
+                path=path,
+                resolution=resolution,
+                date_column=date_column,
+                symbol_column=symbol_column,
+                instrument_type_column=instrument_type_column,
+                timezone=timezone,
+                validate=quality is None,
+            )
+            if quality is not None:
+                from simulor.data.quality import DataQualityProvider
+
+                self._provider = DataQualityProvider(self._provider, quality)
+            if continuous_futures:
+                from simulor.data.futures import ContinuousFuturesProvider
+
+                self._provider = ContinuousFuturesProvider(self._provider, list(continuous_futures))
         self._running = False
         self._last_timestamp: datetime | None = None
 
+    @property
+    def data_quality(self) -> DataQualityReport | None:
+        """Quality report of the rows published so far, when a quality policy is set."""
+        from simulor.data.futures import ContinuousFuturesProvider
+        from simulor.data.quality import DataQualityProvider
+
+        provider: Iterable[MarketEvent] = self._provider
+        if isinstance(provider, ContinuousFuturesProvider):
+            provider = provider.source
+        if isinstance(provider, DataQualityProvider):
+            return provider.report
+        return None
+
     def start(self) -> None:
         """Start the feed.
 
@@ -0,0 +1,88 @@
+"""Feed that publishes market events from Databento DBN files.
+
+Requires the `_simulor_rust` extension.
+"""
+
+from __future__ import annotations
+
+from collections.abc import Iterable
+from datetime import datetime
+from pathlib import Path
+from zoneinfo import ZoneInfo
+
+from _simulor_rust import DbnDataProvider
+
+from simulor.core.events import EndOfStreamEvent
+from simulor.core.protocols import Feed
+from simulor.logging import get_logger
+
+logger = get_logger(__name__)
+
+
+class DbnFeed(Feed):
+    """Feed that publishes market events from DBN files.
+
+    Uses `DbnDataProvider` to read plain or zstd-compressed DBN file(s) and
+    publishes each `MarketEvent` to the configured event bus via `publish_event()`.
+    """
+
+    def __init__(
+        self,
+        path: Path | str,
+        timezone: str = "UTC",
+        start: datetime | None = None,
+        end: datetime | None = None,
+        symbols: Iterable[str] | None = None,
+    ) -> None:
+        """Initialize the DBN feed.
+
+        Args:
+            path: Path to a `.dbn` or `.dbn.zst` file, or a directory of them.
+            timezone: Timezone event times are expressed in. Defaults to "UTC".
+            start: Optional inclusive start of the events published.
+            end: Optional inclusive end of the events published.
+            symbols: Optional raw symbols to restrict the events to.
+        """
+        self._provider = DbnDataProvider(path, timezone=timezone, start=start, end=end, symbols=symbols)
+        self._running = False
+        self._last_timestamp: datetime | None = None
+
+    def start(self) -> None:
+        """Start the feed."""
+        logger.debug("Starting DbnFeed, publishing market events from DBN files")
+        return super().start()
+
+    def run(self) -> None:
+        """Run the feed loop.
+
+        Publishes the provider's `MarketEvent`s until it is exhausted or `stop()`
+        is called, then publishes an `EndOfStreamEvent`.
+        """
+        self._running = True
+        try:
+            for market_event in self._provider:
+                if not self._running:
+                    break
+                try:
+                    self.publish_event(market_event)
+                    self._last_timestamp = market_event.time
+                except Exception:
+                    logger.exception(
+                        "Failed to publish market event at %s",
+                        market_event.time,
+                    )
+        except Exception:
+            logger.exception("DbnFeed run loop terminated with exception")
+        finally:
+            self._running = False
+
+        self.publish_event(
+            EndOfStreamEvent(
+                time=self._last_timestamp or datetime.now(tz=ZoneInfo("UTC")),
+                reason="End of DBN data stream",
+            )
+        )
+
+    def stop(self) -> None:
+        """Stop the feed run loop."""
+        self._running = False
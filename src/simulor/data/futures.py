@@ -0,0 +1,30 @@
+"""Continuous futures: contracts of one root stitched into a front-month series.
+
+`ContinuousFuture` follows one contract at a time and rolls into the next
+under a calendar, volume or open interest rule, deciding each roll only from
+data already seen. `ContinuousFuturesProvider` wraps any data provider and adds
+the continuous instrument's records, copied from the contract followed, to its
+stream. Given to `Engine`, each roll closes positions in the continuous
+instrument at the old contract's price and reopens them at the new one's, and
+`MarketStore` lookbacks are back-adjusted by difference or ratio for the rolls
+made so far, while fills keep using the raw prices. Requires the
+`_simulor_rust` extension.
+
+Example:
+    >>> from simulor.data.futures import BackAdjustment, ContinuousFuture, RollRule
+    >>> es = ContinuousFuture("ES", rule=RollRule.VOLUME, adjustment=BackAdjustment.RATIO)
+    >>> feed = CsvFeed("data/es_contracts.csv", Resolution.DAILY, continuous_futures=[es])
+    >>> engine = Engine(data=feed, fund=fund, broker=SimulatedBroker(), continuous_futures=[es])
+"""
+
+from __future__ import annotations
+
+from _simulor_rust import BackAdjustment, ContinuousFuture, ContinuousFuturesProvider, RollEvent, RollRule
+
+__all__ = [
+    "BackAdjustment",
+    "ContinuousFuture",
+    "ContinuousFuturesProvider",
+    "RollEvent",
+    "RollRule",
+]
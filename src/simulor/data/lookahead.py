@@ -0,0 +1,27 @@
+"""Look-ahead bias guard for market data queries.
+
+A `LookAheadGuard` fences `MarketStore` at the engine's current time: bars
+become available at their end rather than the start they are stamped with,
+after a publication delay per resolution, and lookbacks only see records
+available by then. A strict guard raises `LookAheadError` when a lookback
+reaches for records not yet available, so a feed delivering data early fails
+loudly instead of leaking it. Requires the `_simulor_rust` extension.
+
+Example:
+    >>> from simulor.data.lookahead import LookAheadGuard
+    >>> guard = LookAheadGuard(delays={Resolution.DAILY: timedelta(minutes=30)}, strict=True)
+    >>> guard.lag(Resolution.DAILY)
+    datetime.timedelta(days=1, seconds=1800)
+    >>> engine = Engine(data=feed, fund=fund, broker=broker, lookahead=guard)
+"""
+
+from __future__ import annotations
+
+from _simulor_rust import LookAheadError, LookAheadGuard, SeriesView, TimeFencedStore
+
+__all__ = [
+    "LookAheadError",
+    "LookAheadGuard",
+    "SeriesView",
+    "TimeFencedStore",
+]
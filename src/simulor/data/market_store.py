@@ -1,19 +1,31 @@
 """Historical market data storage and retrieval API.
 
 Provides type-safe access to historical market data for strategy components.
-All data is retained in memory with typed lists for efficient queries.
+All data is retained in memory, in the time-fenced store of the `_simulor_rust`
+extension when it is installed. With a `LookAheadGuard`, lookbacks only see
+records available as of the latest update: bars from their end rather than the
+start they are stamped with, after any publication delay. With corporate
+actions attached, lookbacks are restated for the splits and dividends in effect
+so far, and those of continuous futures are back-adjusted for the rolls made so
+far, while the raw series stays available.
 """
 
 from __future__ import annotations
 
+import contextlib
 from collections.abc import Sequence
+from datetime import datetime
 from decimal import Decimal
-from typing import TYPE_CHECKING
+from typing import TYPE_CHECKING, TypeVar, cast
 
 from simulor.base.collections import ReadOnlySequence
 
 if TYPE_CHECKING:
+    from _simulor_rust import BookUpdate, LookAheadGuard, OrderBookL2
+
     from simulor.core.events import MarketEvent
+    from simulor.data.corporate_actions import CorporateActionStore, PriceAdjustment
+    from simulor.data.futures import ContinuousFuture
 
 from simulor.types import (
     Instrument,
@@ -27,6 +39,101 @@ from simulor.types import (
 
 __all__ = ["MarketStore"]
 
+T = TypeVar("T", bound=MarketData)
+
+
+def _carry_over(series: dict[Instrument, list[T]], old: Instrument, new: Instrument) -> None:
+    """Move `old`'s records to `new`, ahead of any `new` already has."""
+    if old in series:
+        series[new] = series.pop(old) + series.get(new, [])
+
+
+def _carry_over_bars(series: dict[Instrument, dict[Resolution, list[T]]], old: Instrument, new: Instrument) -> None:
+    """Move `old`'s bars to `new` resolution by resolution."""
+    for resolution, records in series.pop(old, {}).items():
+        by_resolution = series.setdefault(new, {})
+        by_resolution[resolution] = records + by_resolution.get(resolution, [])
+
+
+class _ListStore:
+    """Unfenced storage in plain lists, used without the `_simulor_rust` extension.
+
+    Mirrors the native `TimeFencedStore` without a guard: every record is
+    available as soon as it is added.
+    """
+
+    def __init__(self, guard: LookAheadGuard | None = None) -> None:  # noqa: ARG002
+        # Guards only exist with the extension, so `guard` is always None here
+
+        # Separate storage for each data type
+        self._trade_ticks: dict[Instrument, list[TradeTick]] = {}
+        self._quote_ticks: dict[Instrument, list[QuoteTick]] = {}
+        self._trade_bars: dict[Instrument, dict[Resolution, list[TradeBar]]] = {}
+        self._quote_bars: dict[Instrument, dict[Resolution, list[QuoteBar]]] = {}
+        # Cache for latest market data per instrument
+        self._latest: dict[Instrument, MarketData] = {}
+
+    def trade_ticks(self, instrument: Instrument) -> list[TradeTick] | None:
+        return self._trade_ticks.get(instrument)
+
+    def quote_ticks(self, instrument: Instrument) -> list[QuoteTick] | None:
+        return self._quote_ticks.get(instrument)
+
+    def trade_bars(self, instrument: Instrument, resolution: Resolution) -> list[TradeBar] | None:
+        return self._trade_bars.get(instrument, {}).get(resolution)
+
+    def quote_bars(self, instrument: Instrument, resolution: Resolution) -> list[QuoteBar] | None:
+        return self._quote_bars.get(instrument, {}).get(resolution)
+
+    def book_updates(self, instrument: Instrument) -> list[BookUpdate] | None:  # noqa: ARG002
+        # Book updates only exist with the extension
+        return None
+
+    def order_book(self, instrument: Instrument) -> OrderBookL2 | None:  # noqa: ARG002
+        return None
+
+    def latest(self, instrument: Instrument) -> MarketData | None:
+        return self._latest.get(instrument)
+
+    def instruments(self) -> list[Instrument]:
+        return [*self._latest.keys()]
+
+    def rename(self, old: Instrument, new: Instrument) -> None:
+        _carry_over(self._trade_ticks, old, new)
+        _carry_over(self._quote_ticks, old, new)
+        _carry_over_bars(self._trade_bars, old, new)
+        _carry_over_bars(self._quote_bars, old, new)
+        if old in self._latest:
+            latest = self._latest.pop(old)
+            self._latest.setdefault(new, latest)
+
+    def update(self, market_event: MarketEvent) -> None:
+        for market_data in market_event.flatten():
+            if isinstance(market_data, TradeTick):
+                self._trade_ticks.setdefault(market_data.instrument, []).append(market_data)
+            elif isinstance(market_data, QuoteTick):
+                self._quote_ticks.setdefault(market_data.instrument, []).append(market_data)
+            elif isinstance(market_data, TradeBar):
+                self._trade_bars.setdefault(market_data.instrument, {}).setdefault(market_data.resolution, []).append(
+                    market_data
+                )
+            elif isinstance(market_data, QuoteBar):
+                self._quote_bars.setdefault(market_data.instrument, {}).setdefault(market_data.resolution, []).append(
+                    market_data
+                )
+            else:
+                raise TypeError(f"Unknown data type: {type(market_data)}")
+            latest = self._latest.get(market_data.instrument)
+            if latest is None or market_data.timestamp > latest.timestamp:
+                self._latest[market_data.instrument] = market_data
+
+
+# Prefer the native time-fenced store when the extension is installed
+_Store = _ListStore
+if not TYPE_CHECKING:
+    with contextlib.suppress(ImportError):
+        from _simulor_rust import TimeFencedStore as _Store
+
 
 class MarketStore:
     """Historical market data storage and retrieval.
@@ -36,10 +143,17 @@ class MarketStore:
     components to access historical prices, bars, and ticks.
 
     Design decisions:
-    - Type-specific storage: Separate lists for TradeTick, QuoteTick, TradeBar, QuoteBar
+    - Type-specific storage: Separate series for TradeTick, QuoteTick, TradeBar, QuoteBar,
+      and BookUpdate, replayed into an order book per instrument
     - All data retained in memory
     - Returns read-only sequence views (zero-copy, immutable)
     - O(1) access by instrument and data type
+    - Time fencing: with a `LookAheadGuard`, queries only see records available as of
+      the latest update; a strict guard raises `LookAheadError` when a lookback reaches
+      for records not yet available
+    - Point-in-time adjustment: lookbacks are restated only for corporate actions
+      whose ex-date has passed, and continuous futures back-adjusted only for rolls made,
+      as of the latest update; pass `raw=True` for prices as traded
 
     Examples:
         >>> store = MarketStore()
@@ -48,72 +162,190 @@ class MarketStore:
         >>> bars = store.get_trade_bars(instrument, Resolution.MINUTE)
         >>> latest = ticks[-1] if ticks else None
         >>> last_10 = ticks[-10:]  # Slice returns immutable view
+        >>> # Daily bars are only seen once the day is over and published
+        >>> guard = LookAheadGuard(delays={Resolution.DAILY: timedelta(minutes=30)}, strict=True)
+        >>> fenced = MarketStore(lookahead=guard)
     """
 
-    def __init__(self) -> None:
-        """Initialize empty market data storage."""
-        # Separate storage for each data type
-        self._trade_ticks: dict[Instrument, list[TradeTick]] = {}
-        self._quote_ticks: dict[Instrument, list[QuoteTick]] = {}
-        self._trade_bars: dict[Instrument, dict[Resolution, list[TradeBar]]] = {}
-        self._quote_bars: dict[Instrument, dict[Resolution, list[QuoteBar]]] = {}
-        # Cache for latest market data per instrument
-        self._latest_market_data_cache: dict[Instrument, MarketData] = {}
+    def __init__(
+        self,
+        corporate_actions: CorporateActionStore | None = None,
+        adjustment: PriceAdjustment | None = None,
+        lookahead: LookAheadGuard | None = None,
+        continuous_futures: Sequence[ContinuousFuture] = (),
+    ) -> None:
+        """Initialize empty market data storage.
 
-    def get_trade_ticks(self, instrument: Instrument) -> Sequence[TradeTick]:
+        Args:
+            corporate_actions: Optional corporate actions to restate lookbacks for
+            adjustment: How lookbacks are restated (default PriceAdjustment.BACK_ADJUSTED)
+            lookahead: Optional availability rules; records not yet available are hidden
+            continuous_futures: Continuous futures whose lookbacks are back-adjusted for their rolls
+        """
+        # A guard comes from the extension, so the native store is there to take it
+        self._store = _Store(lookahead)
+        self._lookahead = lookahead
+
+        # Corporate action adjustment
+        self._corporate_actions = corporate_actions
+        self._adjustment = adjustment
+        self._as_of: datetime | None = None
+        # Continuous futures, by continuous instrument
+        self._continuous_futures = {future.instrument: future for future in continuous_futures}
+        # Restated lookbacks, with the number of actions or rolls in effect they reflect
+        self._adjusted_cache: dict[tuple[object, ...], tuple[int, list[MarketData]]] = {}
+
+    @property
+    def lookahead(self) -> LookAheadGuard | None:
+        """Availability rules queries are fenced with, if any."""
+        return self._lookahead
+
+    def get_trade_ticks(self, instrument: Instrument, raw: bool = False) -> Sequence[TradeTick]:
         """Get trade tick data for an instrument.
 
         Args:
             instrument: The instrument to get data for
+            raw: Return prices as traded, ignoring corporate actions
 
         Returns:
             Read-only sequence of trade ticks, empty sequence if no data exists
+
+        Raises:
+            LookAheadError: Under a strict guard, if ticks not yet available were stored
         """
-        data = self._trade_ticks.get(instrument)
-        return ReadOnlySequence(data if data else [])
+        data = self._store.trade_ticks(instrument)
+        return ReadOnlySequence(self._adjusted(("trade_ticks", instrument), instrument, data, raw) if data else [])
 
-    def get_quote_ticks(self, instrument: Instrument) -> Sequence[QuoteTick]:
+    def get_quote_ticks(self, instrument: Instrument, raw: bool = False) -> Sequence[QuoteTick]:
         """Get quote tick data for an instrument.
 
         Args:
             instrument: The instrument to get data for
+            raw: Return prices as quoted, ignoring corporate actions
 
         Returns:
             Read-only sequence of quote ticks, empty sequence if no data exists
+
+        Raises:
+            LookAheadError: Under a strict guard, if quotes not yet available were stored
         """
-        data = self._quote_ticks.get(instrument)
-        return ReadOnlySequence(data if data else [])
+        data = self._store.quote_ticks(instrument)
+        return ReadOnlySequence(self._adjusted(("quote_ticks", instrument), instrument, data, raw) if data else [])
 
-    def get_trade_bars(self, instrument: Instrument, resolution: Resolution) -> Sequence[TradeBar]:
+    def get_trade_bars(self, instrument: Instrument, resolution: Resolution, raw: bool = False) -> Sequence[TradeBar]:
         """Get trade bar data for an instrument.
 
         Args:
             instrument: The instrument to get data for
             resolution: The resolution to get data for (SECOND, MINUTE, HOUR, DAILY)
+            raw: Return prices as traded, ignoring corporate actions
 
         Returns:
             Read-only sequence of trade bars, empty sequence if no data exists
+
+        Raises:
+            LookAheadError: Under a strict guard, if bars not yet available were stored
         """
-        data = self._trade_bars.get(instrument, {}).get(resolution)
-        return ReadOnlySequence(data if data else [])
+        data = self._store.trade_bars(instrument, resolution)
+        key = ("trade_bars", instrument, resolution)
+        return ReadOnlySequence(self._adjusted(key, instrument, data, raw) if data else [])
 
-    def get_quote_bars(self, instrument: Instrument, resolution: Resolution) -> Sequence[QuoteBar]:
+    def get_quote_bars(self, instrument: Instrument, resolution: Resolution, raw: bool = False) -> Sequence[QuoteBar]:
         """Get quote bar data for an instrument.
 
         Args:
             instrument: The instrument to get data for
             resolution: The resolution to get data for (SECOND, MINUTE, HOUR, DAILY)
+            raw: Return prices as quoted, ignoring corporate actions
 
         Returns:
             Read-only sequence of quote bars, empty sequence if no data exists
+
+        Raises:
+            LookAheadError: Under a strict guard, if bars not yet available were stored
+        """
+        data = self._store.quote_bars(instrument, resolution)
+        key = ("quote_bars", instrument, resolution)
+        return ReadOnlySequence(self._adjusted(key, instrument, data, raw) if data else [])
+
+    def get_book_updates(self, instrument: Instrument) -> Sequence[BookUpdate]:
+        """Get Level 2 book updates for an instrument.
+
+        Book updates are stored as received and never restated for corporate actions.
+
+        Args:
+            instrument: The instrument to get data for
+
+        Returns:
+            Read-only sequence of book updates, empty sequence if no data exists
+
+        Raises:
+            LookAheadError: Under a strict guard, if updates not yet available were stored
         """
-        data = self._quote_bars.get(instrument, {}).get(resolution)
+        data = self._store.book_updates(instrument)
         return ReadOnlySequence(data if data else [])
 
+    def get_order_book(self, instrument: Instrument) -> OrderBookL2 | None:
+        """Get an instrument's Level 2 book as of the latest update.
+
+        The book is replayed from the available book updates; never raises
+        LookAheadError. The returned book is a copy, so applying updates to it
+        leaves the store's unchanged.
+
+        Args:
+            instrument: The instrument to get the book for
+
+        Returns:
+            The order book, or None if no book updates exist
+        """
+        return self._store.order_book(instrument)
+
+    def _adjusted(self, key: tuple[object, ...], instrument: Instrument, data: Sequence[T], raw: bool) -> Sequence[T]:
+        """Restate a stored series for the corporate actions, or a continuous future's rolls, in effect.
+
+        The restated series is cached until another action or roll takes effect;
+        records added meanwhile are restated on their own and appended.
+        """
+        if raw:
+            return data
+        future = self._continuous_futures.get(instrument)
+        if future is not None:
+            count = future.count(self._as_of)
+        elif self._corporate_actions is not None:
+            count = self._corporate_actions.count(instrument, self._as_of)
+        else:
+            return data
+        if count == 0:
+            return data
+
+        cached = self._adjusted_cache.get(key)
+        if cached is not None and cached[0] == count:
+            adjusted = cached[1]
+            start = len(adjusted)
+        else:
+            adjusted = []
+            start = 0
+        if start < len(data):
+            adjusted.extend(self._restate(instrument, data[start:]))
+            self._adjusted_cache[key] = (count, adjusted)
+        return cast(list[T], adjusted)
+
+    def _restate(self, instrument: Instrument, data: Sequence[T]) -> list[T]:
+        future = self._continuous_futures.get(instrument)
+        if future is not None:
+            return future.adjust(data, self._as_of)
+        assert self._corporate_actions is not None
+        if self._adjustment is None:
+            return self._corporate_actions.adjust(data, self._as_of, instrument=instrument)
+        return self._corporate_actions.adjust(data, self._as_of, self._adjustment, instrument)
+
     def get_latest_price(self, instrument: Instrument) -> Decimal:
         """Get the most recent price for an instrument.
 
-        Searches across all resolutions and data types for the latest price.
+        Searches across all resolutions and data types for the latest price
+        available; never raises LookAheadError. With corporate actions attached,
+        the price is restated for splits that took effect after it, but never
+        for dividends.
 
         Args:
             instrument: The instrument to get price for
@@ -126,10 +358,13 @@ class MarketStore:
         """
 
         latest_price: Decimal
-        latest_data = self._latest_market_data_cache.get(instrument)
+        latest_data = self._store.latest(instrument)
 
         if latest_data is None:
             raise ValueError(f"No price data available for instrument: {instrument}")
+        # Express the price in today's share count, even before the first record after a split
+        if self._corporate_actions is not None:
+            latest_data = self._corporate_actions.adjust([latest_data], self._as_of, instrument=instrument)[0]
         if isinstance(latest_data, TradeTick):
             latest_price = latest_data.price
         elif isinstance(latest_data, QuoteTick):
@@ -149,7 +384,7 @@ class MarketStore:
         Returns:
             Set of all instruments in the store
         """
-        return {*self._latest_market_data_cache.keys()}
+        return {*self._store.instruments()}
 
     def get_latest_prices(self, instruments: Sequence[Instrument]) -> dict[Instrument, Decimal]:
         """Get the most recent prices for multiple instruments.
@@ -167,47 +402,28 @@ class MarketStore:
                 continue
         return latest_prices
 
-    def _update_latest_market_data_cache(self, market_data: MarketData) -> None:
-        """Update the latest market data cache with new data.
+    def rename_instrument(self, old: Instrument, new: Instrument) -> None:
+        """Carry an instrument's history over to its new listing after a symbol change.
+
+        Records keep their original instrument. Any history already stored for
+        `new` follows that of `old`.
 
         Args:
-            market_data: MarketData instance (TradeTick, QuoteTick, TradeBar, or QuoteBar)
+            old: Instrument before the symbol change
+            new: Instrument after the symbol change
         """
-        instrument = market_data.instrument
-        timestamp = market_data.timestamp
-
-        if (
-            instrument not in self._latest_market_data_cache
-            or timestamp > self._latest_market_data_cache[instrument].timestamp
-        ):
-            self._latest_market_data_cache[instrument] = market_data
+        self._store.rename(old, new)
+        self._adjusted_cache.clear()
 
     def update(self, market_event: MarketEvent) -> None:
         """Update store with new market data.
 
-        Dispatches to appropriate storage based on data type.
-        Data is appended to maintain chronological order.
+        Dispatches to appropriate storage based on data type and moves the
+        store's current time to the event's. Data is appended to maintain
+        chronological order.
 
         Args:
             market_event: MarketEvent instance containing new market data
         """
-
-        for market_data in market_event.flatten():
-            if isinstance(market_data, TradeTick):
-                self._trade_ticks.setdefault(market_data.instrument, []).append(market_data)
-                self._update_latest_market_data_cache(market_data)
-            elif isinstance(market_data, QuoteTick):
-                self._quote_ticks.setdefault(market_data.instrument, []).append(market_data)
-                self._update_latest_market_data_cache(market_data)
-            elif isinstance(market_data, TradeBar):
-                self._trade_bars.setdefault(market_data.instrument, {}).setdefault(market_data.resolution, []).append(
-                    market_data
-                )
-                self._update_latest_market_data_cache(market_data)
-            elif isinstance(market_data, QuoteBar):
-                self._quote_bars.setdefault(market_data.instrument, {}).setdefault(market_data.resolution, []).append(
-                    market_data
-                )
-                self._update_latest_market_data_cache(market_data)
-            else:
-                raise TypeError(f"Unknown data type: {type(market_data)}")
+        self._as_of = market_event.time
+        self._store.update(market_event)
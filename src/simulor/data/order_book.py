@@ -0,0 +1,32 @@
+"""Level 2 and Level 3 order books.
+
+`BookUpdate` records carry price-level changes of an instrument's book, as
+incremental updates or full snapshots. An `OrderBookL2` applies them in order,
+detects sequence gaps, and derives depth, microprice, imbalance and a top of
+book `QuoteTick`. Book updates flow through `MarketEvent` to alpha models and
+fill models, and `MarketStore.get_order_book` replays them as of the current
+time. An `OrderBookL3` tracks individual orders and their queue positions, as
+replayed by `ItchDataProvider`. Requires the `_simulor_rust` extension.
+
+Example:
+    >>> from simulor.data.order_book import BookUpdate, OrderBookL2
+    >>> book = OrderBookL2(instrument)
+    >>> book.apply(BookUpdate(time, instrument, Resolution.TICK,
+    ...     bids=[("150.24", 500), ("150.23", 1200)], asks=[("150.26", 300)], sequence=1, snapshot=True))
+    True
+    >>> book.microprice
+    Decimal('150.2525')
+    >>> book.cumulative_bids(2)
+    [(Decimal('150.24'), Decimal('500')), (Decimal('150.23'), Decimal('1700'))]
+"""
+
+from __future__ import annotations
+
+from _simulor_rust import BookUpdate, OrderBookL2, OrderBookL3, SequenceGapError
+
+__all__ = [
+    "BookUpdate",
+    "OrderBookL2",
+    "OrderBookL3",
+    "SequenceGapError",
+]
@@ -1,5 +1,7 @@
 """Data provider package for loading market data from various sources."""
 
+import contextlib
+
 from simulor.data.providers.base import DataIterator, DataProvider
 from simulor.data.providers.csv import CSVDataProvider
 
@@ -8,3 +10,25 @@ __all__ = [
     "DataProvider",
     "CSVDataProvider",
 ]
+
+# Columnar providers are only implemented natively, in the Rust extension
+with contextlib.suppress(ImportError):
+    from _simulor_rust import ArrowIpcDataProvider, ParquetDataProvider
+
+    DataProvider.register(ArrowIpcDataProvider)
+    DataProvider.register(ParquetDataProvider)
+    __all__ += ["ArrowIpcDataProvider", "ParquetDataProvider"]
+
+# As is the NASDAQ TotalView-ITCH 5.0 reader
+with contextlib.suppress(ImportError):
+    from _simulor_rust import ItchDataProvider
+
+    DataProvider.register(ItchDataProvider)
+    __all__ += ["ItchDataProvider"]
+
+# And the Databento DBN reader
+with contextlib.suppress(ImportError):
+    from _simulor_rust import DbnDataProvider
+
+    DataProvider.register(DbnDataProvider)
+    __all__ += ["DbnDataProvider"]
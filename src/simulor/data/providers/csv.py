@@ -6,6 +6,7 @@ chronological ordering using heap-based merge-sort algorithm.
 
 from __future__ import annotations
 
+import contextlib
 import csv
 import heapq
 import warnings
@@ -13,7 +14,7 @@ from collections.abc import Iterator
 from datetime import datetime
 from decimal import Decimal
 from pathlib import Path
-from typing import Any
+from typing import TYPE_CHECKING, Any
 from zoneinfo import ZoneInfo
 
 from simulor.core.events import MarketEvent
@@ -471,3 +472,12 @@ class CSVDataIterator:
         """Close file handles on cleanup."""
         for f in self._file_handles:
             f.close()
+
+
+# Prefer the native provider from the Rust extension when it is installed. It takes
+# the same arguments and yields the same events, parsing files in parallel.
+if not TYPE_CHECKING:
+    with contextlib.suppress(ImportError):
+        from _simulor_rust import CSVDataProvider  # noqa: F811
+
+        DataProvider.register(CSVDataProvider)
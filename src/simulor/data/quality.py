@@ -0,0 +1,45 @@
+"""Market data quality checks.
+
+`DataQualityProvider` wraps any data provider and checks each record before it
+reaches the engine: crossed or locked quotes, zero or negative prices,
+inconsistent bars, duplicate and out-of-order timestamps, stale quotes and
+price spikes. A `DataQualityPolicy` drops, repairs or flags each issue, and the
+provider's `report` counts them per instrument. Requires the `_simulor_rust`
+extension.
+
+Example:
+    >>> from simulor.data.quality import DataQualityPolicy, DataQualityProvider, QualityAction, QualityIssue
+    >>> policy = DataQualityPolicy(
+    ...     default=QualityAction.DROP,
+    ...     actions={QualityIssue.OHLC_INCONSISTENT: QualityAction.REPAIR},
+    ...     stale_after=timedelta(minutes=5),
+    ... )
+    >>> provider = DataQualityProvider(ParquetDataProvider("data/", Resolution.MINUTE, validate=False), policy)
+    >>> feed = CsvFeed("data/bars.csv", Resolution.DAILY, quality=policy)
+"""
+
+from __future__ import annotations
+
+from _simulor_rust import (
+    DataQualityPolicy,
+    DataQualityProvider,
+    DataQualityReport,
+    InstrumentQuality,
+    QualityAction,
+    QualityIssue,
+    SpikeMethod,
+)
+
+from simulor.data.providers.base import DataProvider
+
+__all__ = [
+    "DataQualityPolicy",
+    "DataQualityProvider",
+    "DataQualityReport",
+    "InstrumentQuality",
+    "QualityAction",
+    "QualityIssue",
+    "SpikeMethod",
+]
+
+DataProvider.register(DataQualityProvider)
@@ -7,18 +7,23 @@ all strategy components. It handles:
 - Component lifecycle: initialize and shutdown all models
 - Strategy pipeline: Universe → Alpha → Portfolio → Risk → Execution
 - Order routing: forward orders to Broker and route fills back
+- Market hours: with an exchange calendar, drop off-session data and close sessions
+- Corporate actions: apply splits, dividends and symbol changes at their ex-dates
+- Continuous futures: roll positions into the next contract as each series rolls
+- Look-ahead guard: optionally hide market data from strategies until it is available
 """
 
 from __future__ import annotations
 
+from collections.abc import Sequence
 from datetime import datetime
 from decimal import Decimal
-from typing import Literal
+from typing import TYPE_CHECKING, Literal, cast
 from zoneinfo import ZoneInfo
 
 from simulor.analytics import BacktestResult
 from simulor.core.connectors import Broker
-from simulor.core.events import DataEvent, EndOfStreamEvent, EventBus, MarketEvent, SystemEvent
+from simulor.core.events import DataEvent, EndOfStreamEvent, EventBus, EventType, MarketEvent, SystemEvent
 from simulor.core.protocols import Context, Feed
 from simulor.data import MarketStore
 from simulor.execution.simulation.broker import SimulatedBroker
@@ -26,6 +31,12 @@ from simulor.logging import get_logger
 from simulor.portfolio import Fund, Portfolio
 from simulor.strategy import Strategy
 
+if TYPE_CHECKING:
+    from simulor.data.calendars import ExchangeCalendar, TradingSession
+    from simulor.data.corporate_actions import CorporateAction, CorporateActionStore, PriceAdjustment
+    from simulor.data.futures import ContinuousFuture, RollEvent
+    from simulor.data.lookahead import LookAheadGuard
+
 __all__ = ["Engine"]
 
 # Create module logger
@@ -82,6 +93,12 @@ class Engine:
         data: Feed,
         fund: Fund,
         broker: Broker,
+        calendar: ExchangeCalendar | str | None = None,
+        extended_hours: bool = False,
+        corporate_actions: CorporateActionStore | None = None,
+        price_adjustment: PriceAdjustment | None = None,
+        lookahead: LookAheadGuard | None = None,
+        continuous_futures: Sequence[ContinuousFuture] | None = None,
     ):
         """Initialize engine with data provider and portfolio configuration.
 
@@ -89,6 +106,17 @@ class Engine:
             data: DataProvider yielding MarketEvents in chronological order
             fund: Fund containing strategies and capital allocation
             broker: Optional broker instance (created automatically if not provided)
+            calendar: Optional exchange calendar, or its name (e.g. "NYSE"). Market data
+                outside trading hours is dropped, and each session close expires DAY orders.
+            extended_hours: Whether pre-market and after-hours trading count as in session
+            corporate_actions: Optional splits, dividends and symbol changes. Each adjusts positions
+                and cash at its ex-date, and strategy lookbacks are restated for those in effect.
+            price_adjustment: How lookbacks are restated (default PriceAdjustment.BACK_ADJUSTED)
+            lookahead: Optional availability rules for strategy lookbacks. Records are hidden until
+                available, e.g. daily bars until the day they cover is over; a strict guard raises on access.
+            continuous_futures: Optional continuous futures the data feed rolls. Each roll moves positions in
+                the continuous instrument into the next contract, and strategy lookbacks are back-adjusted
+                for the rolls made so far.
         """
         logger.debug("Initializing engine with %d strategies", len(fund.strategies))
 
@@ -103,9 +131,34 @@ class Engine:
         self._current_timestamp: datetime | None = None
         self._is_running = False
 
+        # Market hours
+        if isinstance(calendar, str):
+            from simulor.data.calendars import ExchangeCalendar
+
+            calendar = ExchangeCalendar(calendar)
+        self._calendar = calendar
+        self._extended_hours = extended_hours
+        # First session not yet closed as of the latest event
+        self._session: TradingSession | None = None
+
+        # Corporate actions
+        self._corporate_actions = corporate_actions
+        self._price_adjustment = price_adjustment
+        # Time up to which actions have been applied
+        self._corporate_actions_applied: datetime | None = None
+
+        # Continuous futures
+        self._continuous_futures = list(continuous_futures or ())
+        # Time up to which rolls have been applied
+        self._rolls_applied: datetime | None = None
+
+        # Availability rules of strategy market stores
+        self._lookahead = lookahead
+
         # Strategy management
         self._strategies: dict[str, Strategy] = {}
         self._strategy_market_stores: dict[str, MarketStore] = {}
+        self._strategy_contexts: dict[str, Context] = {}
 
         # Event bus for internal event handling
         self._event_bus = EventBus()
@@ -185,7 +238,9 @@ class Engine:
         self._strategies[strategy.name] = strategy
 
         # Create isolated market_store for this strategy
-        self._strategy_market_stores[strategy.name] = MarketStore()
+        self._strategy_market_stores[strategy.name] = MarketStore(
+            self._corporate_actions, self._price_adjustment, self._lookahead, self._continuous_futures
+        )
 
         logger.info(
             "Registered strategy '%s' with capital=$%s",
@@ -262,10 +317,10 @@ class Engine:
 
                 self._current_timestamp = event.time
 
-                if isinstance(event, DataEvent):
-                    self._handle_data_event(event)
-                elif isinstance(event, SystemEvent):
+                if isinstance(event, SystemEvent):
                     self._handle_system_event(event)
+                else:
+                    self._handle_data_event(event)
 
             logger.info(
                 "Event loop completed: processed %d events from %s to %s",
@@ -289,14 +344,31 @@ class Engine:
             logger.debug("Engine stopped")
 
     def _handle_data_event(self, event: DataEvent) -> None:
-        if isinstance(event, MarketEvent):
-            self._handle_market_event(event)
+        # Native market events from the Rust providers are registered data events
+        # sharing MarketEvent's interface, not MarketEvent instances, so dispatch
+        # on the event type
+        if event.type == EventType.MARKET:
+            self._handle_market_event(cast(MarketEvent, event))
         elif isinstance(event, EndOfStreamEvent):
             self._handle_end_of_stream_event(event)
 
         self._event_bus.task_done(queue_type="data")
 
     def _handle_market_event(self, market_event: MarketEvent) -> None:
+        if self._corporate_actions is not None:
+            self._apply_corporate_actions(market_event.time)
+
+        if self._continuous_futures:
+            self._apply_rolls(market_event.time)
+
+        if self._calendar is not None:
+            self._close_sessions(market_event.time)
+            in_session = self._calendar.filter_event(market_event, extended=self._extended_hours)
+            if in_session is None:
+                logger.debug("Dropped off-session event at %s", market_event.time)
+                return
+            market_event = in_session
+
         self._process_event(market_event)
         logger.debug(
             "Processed event #%d at %s with %d data points",
@@ -310,6 +382,58 @@ class Engine:
         self._current_timestamp = event.time
         self._is_running = False
 
+    def _upcoming_session(self, time: datetime) -> TradingSession | None:
+        """Session in progress at `time`, or else the next one."""
+        assert self._calendar is not None
+        extended = self._extended_hours
+        return self._calendar.session_at(time, extended=extended) or self._calendar.next_session(
+            time, extended=extended
+        )
+
+    def _close_sessions(self, time: datetime) -> None:
+        """Run end-of-session handling for every session that closed by `time`."""
+        if self._session is None:
+            self._session = self._upcoming_session(time)
+
+        while self._session is not None:
+            close = self._session.post_close if self._extended_hours else self._session.close
+            if time < close:
+                break
+            self._handle_session_close(self._session, close)
+            self._session = self._upcoming_session(close)
+
+    def _handle_session_close(self, session: TradingSession, close: datetime) -> None:
+        logger.debug("Session %s closed at %s", session.date, close)
+        if isinstance(self._broker, SimulatedBroker):
+            self._broker.on_session_close(close)
+
+    def _apply_corporate_actions(self, time: datetime) -> None:
+        """Apply the corporate actions that took effect since the previous event."""
+        assert self._corporate_actions is not None
+        for action in self._corporate_actions.between(self._corporate_actions_applied, time):
+            self._handle_corporate_action(action)
+        self._corporate_actions_applied = time
+
+    def _handle_corporate_action(self, action: CorporateAction) -> None:
+        logger.info("Applying %r", action)
+        if isinstance(self._broker, SimulatedBroker):
+            self._broker.on_corporate_action(action)
+        if action.new_instrument is not None:
+            for market_store in self._strategy_market_stores.values():
+                market_store.rename_instrument(action.instrument, action.new_instrument)
+
+    def _apply_rolls(self, time: datetime) -> None:
+        """Apply the rolls of continuous futures made since the previous event."""
+        for future in self._continuous_futures:
+            for roll in future.between(self._rolls_applied, time):
+                self._handle_roll(roll)
+        self._rolls_applied = time
+
+    def _handle_roll(self, roll: RollEvent) -> None:
+        logger.info("Applying %r", roll)
+        if isinstance(self._broker, SimulatedBroker):
+            self._broker.on_roll(roll)
+
     def _handle_system_event(self, _event: SystemEvent) -> None:
         self._event_bus.task_done(queue_type="system")
 
@@ -326,6 +450,7 @@ class Engine:
 
             # Inject context into all components
             context = Context(market_store=market_store, portfolio=portfolio, event_bus=self._event_bus)
+            self._strategy_contexts[strategy_name] = context
             strategy.universe.set_context(context)
             strategy.alpha.set_context(context)
             strategy.construction.set_context(context)
@@ -362,6 +487,7 @@ class Engine:
         for strategy_name, strategy in sorted(self._strategies.items()):
             market_store = self._strategy_market_stores[strategy_name]
             portfolio = self._broker.strategy_portfolios[strategy_name]
+            self._strategy_contexts[strategy_name].time = market_event.time
 
             # Execute strategy pipeline
             self._execute_strategy_pipeline(
@@ -516,4 +642,5 @@ class Engine:
             start_date=actual_start,
             end_date=actual_end,
             benchmark_returns=None,  # TODO: Add benchmark support
+            data_quality=self._data_feed.data_quality,
         )
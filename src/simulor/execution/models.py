@@ -108,7 +108,10 @@ class Immediate(ExecutionModel):
 
             # Filter 2: Minimum notional value
             if self.min_notional is not None:
-                current_price = self.market_store.get_latest_price(instrument)
+                current_price = self.market_store.get_latest_prices([instrument]).get(instrument)
+                if current_price is None:
+                    logger.debug("Immediate: skipping %s, no price available", instrument)
+                    continue
                 notional = abs_delta * current_price
                 if notional < self.min_notional:
                     logger.debug(
@@ -1,9 +1,7 @@
-import heapq
 import uuid
-from collections import defaultdict
-from dataclasses import dataclass, field
 from datetime import datetime
 from decimal import Decimal
+from typing import TYPE_CHECKING
 from zoneinfo import ZoneInfo
 
 from simulor.core.connectors import Broker, SubmitOrderResult
@@ -11,22 +9,17 @@ from simulor.core.events import EventType, MarketEvent, SystemEvent
 from simulor.execution.simulation.cost_models import CostModel
 from simulor.execution.simulation.fill_models import FillModel, InstantFillModel
 from simulor.execution.simulation.latency_model import ConstantLatencyModel, LatencyModel
+from simulor.execution.simulation.matching import MatchingEngine, OrderFill, OrderTrigger
 from simulor.logging import get_logger
 from simulor.types import Fill, Instrument, OrderSide, OrderSpec
 
-logger = get_logger(__name__)
-
-
-@dataclass(order=True)
-class _DelayedOrder:
-    """Internal wrapper to sort orders by arrival time (simulating latency)."""
+if TYPE_CHECKING:
+    from _simulor_rust import TrailingStopTracker
 
-    release_time: datetime
+    from simulor.data.corporate_actions import CorporateAction
+    from simulor.data.futures import RollEvent
 
-    # Fields below are excluded from sorting comparison
-    strategy_name: str = field(compare=False)
-    order_spec: OrderSpec = field(compare=False)
-    order_id: str = field(compare=False)
+logger = get_logger(__name__)
 
 
 class SimulatedBroker(Broker):
@@ -49,19 +42,9 @@ class SimulatedBroker(Broker):
         self._cost_model = cost_model or CostModel()
         self._latency_model = latency_model or ConstantLatencyModel(latency=0)
 
-        # State: Network Simulation
-        # Priority Queue for orders "in flight"
-        self._latency_buffer: list[_DelayedOrder] = []
-
-        # State: Exchange Matching Engine (The Order Book)
-        # Orders that have arrived but waiting for price (Limit/Stop)
-        self._open_orders: dict[str, OrderSpec] = {}
-
-        # Order ID -> StrategyName (Routing Table)
-        self._order_owners: dict[str, str] = {}
-
-        # Instrument -> Set[Order ID] (Matching Optimization)
-        self._orders_by_instrument: dict[Instrument, set[str]] = defaultdict(set)
+        # State: Network Simulation and Exchange Matching Engine (The Order Book)
+        # Orders "in flight" and those that have arrived but wait for price (Limit/Stop)
+        self._engine = MatchingEngine(self._fill_model, self._cost_model)
 
         # State: Connection (Trivial in simulation)
         self._is_connected = False
@@ -103,10 +86,7 @@ class SimulatedBroker(Broker):
         # The order effectively "arrives" at the exchange in the future
         release_time = self._current_time + self._latency_model.sample()
 
-        delayed_order = _DelayedOrder(release_time, strategy_name, order_spec, order_id)
-
-        # Push to Priority Queue
-        heapq.heappush(self._latency_buffer, delayed_order)
+        self._engine.submit(order_id, strategy_name, order_spec, release_time)
 
         logger.info(
             f"Order {order_id} submitted by strategy '{strategy_name}' at {self._current_time}, arrives {release_time}"
@@ -119,7 +99,7 @@ class SimulatedBroker(Broker):
         Attempt to cancel an order.
         """
         # Check Ownership
-        owner = self._order_owners.get(order_id)
+        owner = self._engine.owner(order_id)
         if not owner:
             logger.warning(f"Order {order_id} not found for cancellation.")
             return
@@ -128,19 +108,14 @@ class SimulatedBroker(Broker):
             return
 
         # Remove from Book
-        if order_id in self._open_orders:
-            order_spec = self._open_orders[order_id]
-            self._remove_from_book(order_id, order_spec.instrument)
+        if self._engine.cancel(order_id) is not None:
             logger.info(f"Order {order_id} canceled by strategy '{strategy_name}'")
         else:
             logger.warning(f"Order {order_id} not found in open orders for cancellation.")
 
-    def _remove_from_book(self, order_id: str, instrument: Instrument) -> None:
-        del self._open_orders[order_id]
-        del self._order_owners[order_id]
-        self._orders_by_instrument[instrument].remove(order_id)
-        if not self._orders_by_instrument[instrument]:
-            del self._orders_by_instrument[instrument]
+    def get_trailing_stop(self, order_id: str) -> "TrailingStopTracker | None":
+        """Stop tracker of a trailing order resting at the exchange, to inspect its current stop level."""
+        return self._engine.trailing_stop(order_id)
 
     def on_market_event(self, event: MarketEvent) -> None:
         """
@@ -149,72 +124,152 @@ class SimulatedBroker(Broker):
         """
         self._current_time = event.time
 
-        # Process Latency Buffer (Network Layer)
-        # Move orders from "In Flight" to "At Exchange"
-        self._process_latency_buffer()
+        # Fills are checked against their strategy's portfolio and applied to it before the engine makes them.
+        # The first fill a strategy cannot afford stops the event's fills, leaving that order and the later ones
+        # resting, and raises once the fills made before it are published
+        error: str | None = None
+
+        def accept(order_fill: OrderFill) -> bool:
+            nonlocal error
+            if error is None:
+                error = self._check_fill(order_fill)
+            if error is not None:
+                return False
+            self._apply_fill(order_fill, event.time)
+            return True
+
+        # Release orders from "In Flight" to "At Exchange" and match the book against new data
+        for matched in self._engine.on_market_event(event, accept):
+            if isinstance(matched, OrderTrigger):
+                self._publish_trigger(matched, event.time)
+            else:
+                self._publish_fill(matched, event.time)
+        if error is not None:
+            raise ValueError(error)
 
-        # Match Orders (Exchange Layer)
-        # Check if any open orders can fill against new data
-        self._match_orders(event)
+    def on_session_close(self, time: datetime) -> None:
+        """
+        Hook called by Engine when the exchange session closes.
+        DAY orders expire, both those resting in the book and those still in flight.
+        """
+        expired, expired_in_flight = self._engine.expire_day_orders()
+        for order_id in expired:
+            logger.info(f"Order {order_id} expired at session close {time}")
+        for order_id in expired_in_flight:
+            logger.info(f"Order {order_id} expired in flight at session close {time}")
 
-    def _process_latency_buffer(self) -> None:
-        """Releases orders that have completed their network travel time."""
-        while self._latency_buffer:
-            # Peek at the earliest arriving order
-            if self._latency_buffer[0].release_time <= self._current_time:
-                delayed_order = heapq.heappop(self._latency_buffer)
+    def on_corporate_action(self, action: "CorporateAction") -> None:
+        """
+        Hook called by Engine when a corporate action takes effect.
+        Strategy positions and cash are adjusted. Orders for a split or renamed instrument are canceled,
+        since their prices and quantities no longer match the listing.
+        """
+        from simulor.data.corporate_actions import CorporateActionType
+
+        instrument = action.instrument
+        for portfolio in self._strategy_portfolios.values():
+            if action.kind == CorporateActionType.SPLIT:
+                portfolio.apply_split(instrument, action.ratio)
+            elif action.kind == CorporateActionType.DIVIDEND:
+                portfolio.apply_dividend(instrument, action.amount)
+            elif action.kind == CorporateActionType.SYMBOL_CHANGE:
+                portfolio.rename_instrument(instrument, action.new_instrument)
+
+        if action.kind == CorporateActionType.DIVIDEND:
+            return
 
-                # Move to Open Orders (The Exchange has received it)
-                self._add_to_book(delayed_order)
-                logger.debug(f"Order {delayed_order.order_id} arrived at exchange at {self._current_time}")
-            else:
-                # Next order arrives in the future
-                break
-
-    def _add_to_book(self, delayed_order: _DelayedOrder) -> None:
-        """Register order in matching engine."""
-        order_id = delayed_order.order_id
-        spec = delayed_order.order_spec
-
-        self._open_orders[order_id] = spec
-        self._order_owners[order_id] = delayed_order.strategy_name
-        self._orders_by_instrument[spec.instrument].add(order_id)
-
-    def _match_orders(self, event: MarketEvent) -> None:
-        active_instruments = event.instruments()
-
-        for inst in active_instruments:
-            if inst in self._orders_by_instrument:
-                # Copy list to allow modification during iteration
-                order_ids = list(self._orders_by_instrument[inst])
-                for order_id in order_ids:
-                    self._check_and_fill(order_id, event)
-
-    def _check_and_fill(self, order_id: str, event: MarketEvent) -> None:
-        """Check if an order can be filled and process the fill."""
-
-        # Retrieve order spec and owner
-        order_spec = self._open_orders[order_id]
-        strategy_name = self._order_owners[order_id]
-        strategy_portfolio = self.strategy_portfolios[strategy_name]
+        self._cancel_orders_for(instrument, "corporate action")
 
-        # Determine fill price
-        fill_price = self._fill_model.get_fill_price(order_spec, event)
-        if fill_price is None:
-            logger.warning(
-                f"Order {order_id} for {order_spec.instrument.display_name} cannot be filled at {event.time}"
+    def on_roll(self, roll: "RollEvent") -> None:
+        """
+        Hook called by Engine when a continuous future rolls into its next contract.
+        Strategy positions in the continuous instrument are closed at the old contract's price and reopened
+        at the new one's, paying costs on both legs. Its orders are canceled, since their prices were set
+        against the old contract.
+        """
+        instrument = roll.instrument
+        for strategy_name, portfolio in self._strategy_portfolios.items():
+            position = portfolio.positions.get(instrument)
+            if position is None or position.quantity == 0:
+                continue
+            quantity = position.quantity
+            for signed_quantity, price in ((-quantity, roll.from_price), (quantity, roll.to_price)):
+                fill = Fill(
+                    instrument=instrument,
+                    quantity=signed_quantity,
+                    price=price,
+                    commission=self._cost_model.calculate_total_cost(quantity=abs(quantity), price=price),
+                )
+                portfolio.update_position(fill)
+                self.event_bus.publish(
+                    event=SystemEvent(
+                        type=EventType.FILL,
+                        time=roll.time,
+                        payload={
+                            "strategy_name": strategy_name,
+                            "fill": fill,
+                        },
+                    ),
+                )
+            portfolio.record_state(timestamp=roll.time)
+            logger.info(
+                "Rolled %s %s from %s @ $%s to %s @ $%s (strategy=%s)",
+                quantity,
+                instrument.display_name,
+                roll.from_contract.display_name,
+                roll.from_price,
+                roll.to_contract.display_name,
+                roll.to_price,
+                strategy_name,
             )
-            return
 
-        # Calculate total commission
-        commission = self._cost_model.calculate_total_cost(
-            quantity=order_spec.quantity,
-            price=fill_price,
+        self._cancel_orders_for(instrument, "roll")
+
+    def _cancel_orders_for(self, instrument: Instrument, reason: str) -> None:
+        """Cancel an instrument's orders, both those resting in the book and those still in flight."""
+        canceled, canceled_in_flight = self._engine.cancel_instrument(instrument)
+        for order_id in canceled:
+            logger.info(f"Order {order_id} canceled by {reason} on {instrument.display_name}")
+        for order_id in canceled_in_flight:
+            logger.info(f"Order {order_id} canceled in flight by {reason}")
+
+    def _publish_trigger(self, trigger: OrderTrigger, time: datetime) -> None:
+        """Publish the trigger of a conditional order, now working as a market or limit order."""
+        self.event_bus.publish(
+            event=SystemEvent(
+                type=EventType.ORDER_TRIGGERED,
+                time=time,
+                payload={
+                    "strategy_name": trigger.strategy_name,
+                    "trigger": trigger,
+                },
+            ),
+        )
+        logger.info(
+            "Order %s triggered: %s %s reached $%s at $%s (strategy=%s)",
+            trigger.order_id,
+            trigger.order.order_type.name,
+            trigger.order.instrument.display_name,
+            trigger.trigger_price,
+            trigger.market_price,
+            trigger.strategy_name,
         )
 
+    def _check_fill(self, order_fill: OrderFill) -> str | None:
+        """Check that a strategy has the cash for a buy fill, or the position for a sell fill.
+
+        Returns:
+            Why the fill cannot be made, or None if it can
+        """
+        order_spec = order_fill.order
+        fill = order_fill.fill
+        fill_quantity = abs(fill.quantity)
+        strategy_name = order_fill.strategy_name
+        strategy_portfolio = self.strategy_portfolios[strategy_name]
+
         # Check if there's enough cash for buy orders (prevent negative cash)
         if order_spec.side == OrderSide.BUY:
-            cost = order_spec.quantity * fill_price + commission
+            cost = fill_quantity * fill.price + fill.commission
             if cost > strategy_portfolio.cash:
                 logger.warning(
                     "Insufficient cash for %s: need $%s, have $%s (strategy=%s)",
@@ -223,7 +278,7 @@ class SimulatedBroker(Broker):
                     strategy_portfolio.cash,
                     strategy_name,
                 )
-                raise ValueError(
+                return (
                     f"Insufficient cash for {order_spec.instrument.display_name}: "
                     f"need {cost}, have {strategy_portfolio.cash}"
                 )
@@ -231,45 +286,45 @@ class SimulatedBroker(Broker):
             # Check if trying to sell more than owned
             current_position = strategy_portfolio.positions.get(order_spec.instrument)
             current_qty = current_position.quantity if current_position else Decimal("0")
-            if order_spec.quantity > current_qty:
+            if fill_quantity > current_qty:
                 logger.warning(
                     "Insufficient shares to sell %s: trying to sell %s, have %s (strategy=%s)",
                     order_spec.instrument.display_name,
-                    order_spec.quantity,
+                    fill_quantity,
                     current_qty,
                     strategy_name,
                 )
-                raise ValueError(
+                return (
                     f"Insufficient shares to sell {order_spec.instrument.display_name}: "
-                    f"trying to sell {order_spec.quantity}, have {current_qty}"
+                    f"trying to sell {fill_quantity}, have {current_qty}"
                 )
+        return None
 
-        # Create signed quantity (positive for buy, negative for sell)
-        signed_quantity = order_spec.quantity if order_spec.side == OrderSide.BUY else -order_spec.quantity
+    def _apply_fill(self, order_fill: OrderFill, time: datetime) -> None:
+        """Apply a fill from the matching engine to its strategy's portfolio."""
+        strategy_portfolio = self.strategy_portfolios[order_fill.strategy_name]
+        strategy_portfolio.update_position(order_fill.fill)
+        strategy_portfolio.record_state(timestamp=time)
 
-        # Create fill
-        fill = Fill(
-            instrument=order_spec.instrument,
-            quantity=signed_quantity,
-            price=fill_price,
-            commission=commission,
-        )
-
-        # Update strategy portfolio and record state
-        strategy_portfolio.update_position(fill)
-        strategy_portfolio.record_state(timestamp=event.time)
+    def _publish_fill(self, order_fill: OrderFill, time: datetime) -> None:
+        """Publish a fill applied to its strategy's portfolio.
 
-        # Cleanup Book
-        self._remove_from_book(order_id, order_spec.instrument)
+        A fill leaving part of the order in the book is published as `PARTIAL_FILL`, the one completing it as `FILL`.
+        """
+        order_spec = order_fill.order
+        fill = order_fill.fill
+        strategy_name = order_fill.strategy_name
 
         # Publish Event
+        partial = order_fill.remaining_quantity > 0
         self.event_bus.publish(
             event=SystemEvent(
-                type=EventType.FILL,
-                time=event.time,
+                type=EventType.PARTIAL_FILL if partial else EventType.FILL,
+                time=time,
                 payload={
                     "strategy_name": strategy_name,
                     "fill": fill,
+                    "order_fill": order_fill,
                 },
             ),
         )
@@ -278,12 +333,22 @@ class SimulatedBroker(Broker):
         logger.info(
             "Executed trade: %s %s %s @ $%s, commission=$%s (strategy=%s)",
             order_spec.side.name,
-            abs(signed_quantity),
+            abs(fill.quantity),
             order_spec.instrument.display_name,
-            fill_price,
-            commission,
+            fill.price,
+            fill.commission,
             strategy_name,
         )
+        if partial:
+            logger.info(
+                "Order %s partially filled: %s of %s at average $%s, %s remaining (strategy=%s)",
+                order_fill.order_id,
+                order_fill.filled_quantity,
+                order_spec.quantity,
+                order_fill.average_price,
+                order_fill.remaining_quantity,
+                strategy_name,
+            )
 
     # TODO: Make it ana abstract method for Broker
     def get_cash_balance(self) -> Decimal:
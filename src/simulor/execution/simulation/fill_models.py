@@ -2,6 +2,7 @@
 
 from __future__ import annotations
 
+import contextlib
 from abc import ABC, abstractmethod
 from decimal import Decimal
 from typing import TYPE_CHECKING
@@ -9,8 +10,18 @@ from typing import TYPE_CHECKING
 from simulor.types import Instrument, OrderSide, OrderType
 
 if TYPE_CHECKING:
+    from _simulor_rust import OrderBookL2
+
     from simulor.core.events import MarketEvent
     from simulor.types import OrderSpec
+else:
+    # Level 2 books are only implemented natively, in the Rust extension
+    OrderBookL2 = None
+    with contextlib.suppress(ImportError):
+        from _simulor_rust import OrderBookL2
+
+# Whether Level 2 books could be imported
+_NATIVE = OrderBookL2 is not None
 
 __all__ = [
     "FillModel",
@@ -26,6 +37,16 @@ class FillModel(ABC):
     cannot be filled yet.
     """
 
+    def on_market_event(self, market_event: MarketEvent) -> None:  # noqa: B027
+        """Observe a market event before orders are matched against it.
+
+        Lets a model keep state across events, such as order books. The
+        default does nothing.
+
+        Args:
+            market_event: Market data of the new time slice
+        """
+
     @abstractmethod
     def get_fill_price(self, order_spec: OrderSpec, market_event: MarketEvent) -> Decimal | None:
         """Determine fill price for an order given market data.
@@ -45,7 +66,11 @@ class InstantFillModel(FillModel):
 
     Simulates instantaneous execution with realistic spread-aware pricing.
     Uses a data priority waterfall to select the best available price data:
-    Quote Tick > Quote Bar > Trade Tick > Trade Bar
+    Book Update > Quote Tick > Quote Bar > Trade Tick > Trade Bar
+
+    Book updates price orders only with `use_book=True`. They are then applied
+    to a Level 2 book per instrument, whose top of book prices orders when the
+    event updated it; otherwise they are ignored.
 
     Pricing logic:
     - BUY orders: Fill at ask price (or trade price if no quotes available)
@@ -57,6 +82,10 @@ class InstantFillModel(FillModel):
     - STOP: Fill when stop price is triggered
     - STOP_LIMIT: Fill when stop triggers and limit is favorable
 
+    Trailing and if-touched orders need a trigger carried across events,
+    which only the native `MatchingEngine` of the `_simulor_rust` extension
+    tracks; it prices them with this model's waterfall.
+
     Characteristics:
     - Spread-aware (uses bid/ask when available)
     - Deterministic (same data produces same fills)
@@ -68,8 +97,43 @@ class InstantFillModel(FillModel):
         # BUY market orders fill at ask price
         # SELL market orders fill at bid price
         # Limit/stop orders respect trigger conditions
+
+        book_fill_model = InstantFillModel(use_book=True)
+        # Orders fill at the top of the Level 2 book when it was updated
     """
 
+    def __init__(self, use_book: bool = False) -> None:
+        """Initialize instant fill model.
+
+        Args:
+            use_book: Whether the top of the Level 2 book prices orders, ahead of
+                quotes and trades. Requires the `_simulor_rust` extension.
+
+        Raises:
+            RuntimeError: If `use_book` is set without the `_simulor_rust` extension
+        """
+        if use_book and not _NATIVE:
+            raise RuntimeError(
+                "InstantFillModel use_book requires the _simulor_rust extension, which is not installed."
+            )
+        self.use_book = use_book
+        self._books: dict[Instrument, OrderBookL2] = {}
+
+    def on_market_event(self, market_event: MarketEvent) -> None:
+        """Apply the event's book updates to the per-instrument books, with `use_book`.
+
+        Args:
+            market_event: Market data of the new time slice
+        """
+        if not self.use_book:
+            return
+        for instrument, updates in market_event.book_updates.items():
+            book = self._books.get(instrument)
+            if book is None:
+                book = self._books[instrument] = OrderBookL2(instrument)
+            for update in updates:
+                book.apply(update)
+
     def get_fill_price(self, order_spec: OrderSpec, market_event: MarketEvent) -> Decimal | None:
         """Determine fill price based on order type and market conditions.
 
@@ -79,6 +143,9 @@ class InstantFillModel(FillModel):
 
         Returns:
             Fill price if order can be filled, None otherwise
+
+        Raises:
+            NotImplementedError: For trailing and if-touched orders, which need a trigger carried across events
         """
         # Resolve the current market price using waterfall logic
         market_price = self._resolve_market_price(order_spec.instrument, order_spec.side, market_event)
@@ -132,7 +199,17 @@ class InstantFillModel(FillModel):
                 if market_price <= order_spec.stop_price and market_price >= order_spec.limit_price:
                     return market_price
 
-        # TODO: Support other order types
+        # TRAILING AND IF-TOUCHED ORDERS: Their trigger is carried across events, by the native MatchingEngine
+        elif order_spec.order_type in (
+            OrderType.TRAILING_STOP,
+            OrderType.TRAILING_STOP_LIMIT,
+            OrderType.MARKET_IF_TOUCHED,
+            OrderType.LIMIT_IF_TOUCHED,
+        ):
+            raise NotImplementedError(
+                f"{order_spec.order_type.name} orders are matched by the native MatchingEngine, "
+                "which tracks their trigger across events; this model cannot price them alone"
+            )
 
         return None
 
@@ -141,7 +218,7 @@ class InstantFillModel(FillModel):
     ) -> Decimal | None:
         """Calculate execution price using data priority waterfall.
 
-        Priority: Quote Tick > Quote Bar > Trade Tick > Trade Bar
+        Priority: Book Update > Quote Tick > Quote Bar > Trade Tick > Trade Bar
 
         Args:
             instrument: Instrument to get price for
@@ -151,7 +228,14 @@ class InstantFillModel(FillModel):
         Returns:
             Execution price or None if no data available
         """
-        # PRIORITY 1: QUOTE TICK (Highest fidelity)
+        # PRIORITY 1: BOOK UPDATE (Top of the Level 2 book, when updated now and opted in)
+        book = self._books.get(instrument)
+        if book is not None and market_event.get_book_updates(instrument):
+            top = book.to_quote_tick()
+            if top is not None:
+                return top.ask_price if order_side == OrderSide.BUY else top.bid_price
+
+        # PRIORITY 2: QUOTE TICK (Highest fidelity)
         quote_tick = market_event.get_last_quote_tick(instrument)
         if quote_tick:
             # Use ask for BUY, bid for SELL
@@ -160,7 +244,7 @@ class InstantFillModel(FillModel):
             elif order_side == OrderSide.SELL and quote_tick.bid_price and quote_tick.bid_price > 0:
                 return quote_tick.bid_price
 
-        # PRIORITY 2: QUOTE BAR (Aggregated spread)
+        # PRIORITY 3: QUOTE BAR (Aggregated spread)
         quote_bar = market_event.get_min_res_quote_bar(instrument)
         if quote_bar:
             if order_side == OrderSide.BUY and quote_bar.ask_close and quote_bar.ask_close > 0:
@@ -168,14 +252,23 @@ class InstantFillModel(FillModel):
             elif order_side == OrderSide.SELL and quote_bar.bid_close and quote_bar.bid_close > 0:
                 return quote_bar.bid_close
 
-        # PRIORITY 3: TRADE TICK (Last traded price)
+        # PRIORITY 4: TRADE TICK (Last traded price)
         trade_tick = market_event.get_last_trade_tick(instrument)
         if trade_tick and trade_tick.price and trade_tick.price > 0:
             return trade_tick.price
 
-        # PRIORITY 4: TRADE BAR (Close price)
+        # PRIORITY 5: TRADE BAR (Close price)
         trade_bar = market_event.get_min_res_trade_bar(instrument)
         if trade_bar and trade_bar and trade_bar.close and trade_bar.close > 0:
             return trade_bar.close
 
         return None
+
+
+# Intrabar and partial fills are only implemented natively, in the Rust extension
+if not TYPE_CHECKING:
+    with contextlib.suppress(ImportError):
+        from _simulor_rust import IntrabarFillModel, IntrabarPath, PartialFillModel  # noqa: F401
+
+        FillModel.register(IntrabarFillModel)
+        FillModel.register(PartialFillModel)
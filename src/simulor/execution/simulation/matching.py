@@ -0,0 +1,278 @@
+"""Matching engine: the exchange side of the simulated broker.
+
+Holds orders in flight to the exchange and those resting in its book, and
+matches them against each market event. Cash and positions are left to
+the broker, which applies the resulting fills.
+"""
+
+from __future__ import annotations
+
+import contextlib
+import heapq
+from collections import defaultdict
+from dataclasses import dataclass, field
+from datetime import datetime
+from decimal import Decimal
+from typing import TYPE_CHECKING
+
+from simulor.logging import get_logger
+from simulor.types import Fill, Instrument, OrderSide, OrderSpec, OrderType, TimeInForce
+
+if TYPE_CHECKING:
+    from collections.abc import Callable
+
+    from _simulor_rust import TrailingStopTracker
+
+    from simulor.core.events import MarketEvent
+    from simulor.execution.simulation.cost_models import CostModel
+    from simulor.execution.simulation.fill_models import FillModel
+
+__all__ = [
+    "MatchingEngine",
+    "OrderFill",
+    "OrderTrigger",
+]
+
+logger = get_logger(__name__)
+
+# Order types whose trigger is carried across events, which only the native engine tracks
+_NATIVE_ORDER_TYPES = frozenset(
+    {
+        OrderType.TRAILING_STOP,
+        OrderType.TRAILING_STOP_LIMIT,
+        OrderType.MARKET_IF_TOUCHED,
+        OrderType.LIMIT_IF_TOUCHED,
+    }
+)
+
+
+@dataclass(order=True)
+class _DelayedOrder:
+    """Internal wrapper to sort orders by arrival time (simulating latency)."""
+
+    release_time: datetime
+    sequence: int
+
+    # Fields below are excluded from sorting comparison
+    strategy_name: str = field(compare=False)
+    order_spec: OrderSpec = field(compare=False)
+    order_id: str = field(compare=False)
+
+
+@dataclass(frozen=True)
+class OrderFill:
+    """An order filled by the matching engine, in full or in part.
+
+    `fill` is this fill alone; `filled_quantity` and `average_price` cover the order's fills so far,
+    `remaining_quantity` what is left resting in the book.
+    """
+
+    order_id: str
+    strategy_name: str
+    order: OrderSpec
+    fill: Fill
+    filled_quantity: Decimal
+    remaining_quantity: Decimal
+    average_price: Decimal
+
+
+@dataclass(frozen=True)
+class OrderTrigger:
+    """A conditional order whose trigger fired, making it a market or limit order."""
+
+    order_id: str
+    strategy_name: str
+    order: OrderSpec
+    trigger_price: Decimal
+    market_price: Decimal
+
+
+class MatchingEngine:
+    """Orders in flight to the exchange and resting in its book.
+
+    Orders are matched against each market event in the order they arrived
+    at the exchange, priced by the fill model; commissions come from the
+    cost model.
+    """
+
+    def __init__(self, fill_model: FillModel, cost_model: CostModel) -> None:
+        self._fill_model = fill_model
+        self._cost_model = cost_model
+
+        # Priority Queue for orders "in flight"
+        self._latency_buffer: list[_DelayedOrder] = []
+        self._sequence = 0
+
+        # Orders that have arrived but waiting for price (Limit/Stop)
+        self._open_orders: dict[str, OrderSpec] = {}
+
+        # Order ID -> StrategyName (Routing Table)
+        self._order_owners: dict[str, str] = {}
+
+        # Instrument -> Order IDs in arrival order (Matching Optimization)
+        self._orders_by_instrument: dict[Instrument, dict[str, None]] = defaultdict(dict)
+
+    def submit(self, order_id: str, strategy_name: str, order_spec: OrderSpec, release_time: datetime) -> None:
+        """Send an order to the exchange, where it arrives at `release_time`.
+
+        Raises:
+            NotImplementedError: For trailing and if-touched orders, which only the native engine matches
+        """
+        if order_spec.order_type in _NATIVE_ORDER_TYPES:
+            raise NotImplementedError(
+                f"{order_spec.order_type.name} orders require the _simulor_rust extension, which is not installed."
+            )
+        self._sequence += 1
+        heapq.heappush(
+            self._latency_buffer, _DelayedOrder(release_time, self._sequence, strategy_name, order_spec, order_id)
+        )
+
+    def owner(self, order_id: str) -> str | None:
+        """Strategy owning a resting order, if it is in the book."""
+        return self._order_owners.get(order_id)
+
+    def trailing_stop(self, order_id: str) -> TrailingStopTracker | None:  # noqa: ARG002
+        """Stop tracker of a resting trailing order.
+
+        Trailing orders are only accepted by the native engine, so this is always None.
+        """
+        return None
+
+    def cancel(self, order_id: str) -> OrderSpec | None:
+        """Remove a resting order from the book, returning its `OrderSpec`."""
+        order_spec = self._open_orders.get(order_id)
+        if order_spec is not None:
+            self._remove_from_book(order_id, order_spec.instrument)
+        return order_spec
+
+    def expire_day_orders(self) -> tuple[list[str], list[str]]:
+        """Expire DAY orders at the session close.
+
+        Returns:
+            IDs of the expired orders resting in the book and of those still in flight
+        """
+        resting = [order_id for order_id, spec in self._open_orders.items() if spec.time_in_force == TimeInForce.DAY]
+        for order_id in resting:
+            self._remove_from_book(order_id, self._open_orders[order_id].instrument)
+        in_flight = self._remove_in_flight(lambda spec: spec.time_in_force == TimeInForce.DAY)
+        return resting, in_flight
+
+    def cancel_instrument(self, instrument: Instrument) -> tuple[list[str], list[str]]:
+        """Cancel an instrument's orders.
+
+        Returns:
+            IDs of the canceled orders resting in the book and of those still in flight
+        """
+        resting = list(self._orders_by_instrument.get(instrument, ()))
+        for order_id in resting:
+            self._remove_from_book(order_id, instrument)
+        in_flight = self._remove_in_flight(lambda spec: spec.instrument == instrument)
+        return resting, in_flight
+
+    @property
+    def open_orders(self) -> dict[str, OrderSpec]:
+        """Resting orders by ID, in arrival order."""
+        return dict(self._open_orders)
+
+    @property
+    def in_flight_count(self) -> int:
+        """Number of orders still in flight."""
+        return len(self._latency_buffer)
+
+    def __len__(self) -> int:
+        return len(self._open_orders)
+
+    def on_market_event(
+        self, event: MarketEvent, accept: Callable[[OrderFill], bool] | None = None
+    ) -> list[OrderTrigger | OrderFill]:
+        """Release the orders arriving by the event's time, then match the book against the event.
+
+        Args:
+            event: Market event to match against
+            accept: Called with each `OrderFill` before it is made; a fill it declines is left out,
+                its order resting as before.
+
+        Returns:
+            Triggers of conditional orders and fills of the matched orders, in the order they happened.
+            Orders leave the book once filled in full. Orders only fill in part in the native engine.
+        """
+        # Move orders from "In Flight" to "At Exchange"
+        while self._latency_buffer and self._latency_buffer[0].release_time <= event.time:
+            delayed_order = heapq.heappop(self._latency_buffer)
+            self._add_to_book(delayed_order)
+            logger.debug(f"Order {delayed_order.order_id} arrived at exchange at {event.time}")
+
+        # Let the fill model track state such as order books
+        self._fill_model.on_market_event(event)
+
+        fills: list[OrderTrigger | OrderFill] = []
+        for instrument in event.instruments():
+            # Copy to allow modification during iteration
+            for order_id in list(self._orders_by_instrument.get(instrument, ())):
+                order_fill = self._check_and_fill(order_id, event, accept)
+                if order_fill is not None:
+                    fills.append(order_fill)
+        return fills
+
+    def _check_and_fill(
+        self, order_id: str, event: MarketEvent, accept: Callable[[OrderFill], bool] | None
+    ) -> OrderFill | None:
+        """Fill an order if the market reaches it and `accept` takes the fill."""
+        order_spec = self._open_orders[order_id]
+        fill_price = self._fill_model.get_fill_price(order_spec, event)
+        if fill_price is None:
+            logger.warning(
+                f"Order {order_id} for {order_spec.instrument.display_name} cannot be filled at {event.time}"
+            )
+            return None
+
+        commission = self._cost_model.calculate_total_cost(quantity=order_spec.quantity, price=fill_price)
+        # Create signed quantity (positive for buy, negative for sell)
+        signed_quantity = order_spec.quantity if order_spec.side == OrderSide.BUY else -order_spec.quantity
+        fill = Fill(
+            instrument=order_spec.instrument,
+            quantity=signed_quantity,
+            price=fill_price,
+            commission=commission,
+        )
+        order_fill = OrderFill(
+            order_id=order_id,
+            strategy_name=self._order_owners[order_id],
+            order=order_spec,
+            fill=fill,
+            filled_quantity=order_spec.quantity,
+            remaining_quantity=Decimal("0"),
+            average_price=fill_price,
+        )
+        if accept is not None and not accept(order_fill):
+            return None
+        self._remove_from_book(order_id, order_spec.instrument)
+        return order_fill
+
+    def _add_to_book(self, delayed_order: _DelayedOrder) -> None:
+        order_id = delayed_order.order_id
+        spec = delayed_order.order_spec
+        self._open_orders[order_id] = spec
+        self._order_owners[order_id] = delayed_order.strategy_name
+        self._orders_by_instrument[spec.instrument][order_id] = None
+
+    def _remove_from_book(self, order_id: str, instrument: Instrument) -> None:
+        del self._open_orders[order_id]
+        del self._order_owners[order_id]
+        del self._orders_by_instrument[instrument][order_id]
+        if not self._orders_by_instrument[instrument]:
+            del self._orders_by_instrument[instrument]
+
+    def _remove_in_flight(self, remove: Callable[[OrderSpec], bool]) -> list[str]:
+        """Take out the in-flight orders matching `remove`, returning their IDs earliest first."""
+        removed = sorted(delayed for delayed in self._latency_buffer if remove(delayed.order_spec))
+        if removed:
+            self._latency_buffer = [delayed for delayed in self._latency_buffer if not remove(delayed.order_spec)]
+            heapq.heapify(self._latency_buffer)
+        return [delayed.order_id for delayed in removed]
+
+
+# Prefer the native engine from the Rust extension when installed
+if not TYPE_CHECKING:
+    with contextlib.suppress(ImportError):
+        from _simulor_rust import MatchingEngine, OrderFill, OrderTrigger  # noqa: F811
@@ -0,0 +1,36 @@
+"""Option pricing, implied volatility and Greeks.
+
+`BlackScholesMerton` prices European options on a spot paying a continuous
+dividend yield, `Black76` European options on futures and `BinomialTree`
+American (or European) options on a Cox-Ross-Rubinstein tree. Each model has
+`price()`, `greeks()` returning `Greeks` (delta, gamma, vega, theta, rho,
+vanna, vomma and charm alongside the price) and `implied_volatility()`, solved
+with Brent's method and NaN where no volatility reproduces the price. Every
+numeric argument is a number or a NumPy array; arrays are evaluated in
+parallel and results come back as arrays of their shape. Time is in years
+and rates, yields and volatilities are annualized. Requires the
+`_simulor_rust` extension.
+
+Example:
+    >>> import numpy as np
+    >>> from simulor.options import BinomialTree, BlackScholesMerton
+    >>> from simulor.types import OptionType
+    >>> bsm = BlackScholesMerton()
+    >>> bsm.price(OptionType.CALL, spot=100.0, strike=100.0, time=1.0, rate=0.05, volatility=0.2)
+    10.45058357218555
+    >>> strikes = np.array([90.0, 100.0, 110.0])
+    >>> ivs = bsm.implied_volatility("put", np.array([2.1, 5.6, 11.2]), 100.0, strikes, 1.0, 0.05)
+    >>> BinomialTree(steps=500).greeks("put", 42.0, 40.0, 0.5, 0.1, 0.2).delta
+    -0.2579...
+"""
+
+from __future__ import annotations
+
+from _simulor_rust import BinomialTree, Black76, BlackScholesMerton, Greeks
+
+__all__ = [
+    "BinomialTree",
+    "Black76",
+    "BlackScholesMerton",
+    "Greeks",
+]
@@ -124,6 +124,46 @@ class Portfolio:
             len(self._positions),
         )
 
+    def apply_split(self, instrument: Instrument, ratio: Decimal) -> None:
+        """Restate a held position after a split of `ratio` new shares per old share."""
+        pos = self._positions.get(instrument)
+        if pos is None:
+            return
+        old_qty = pos.quantity
+        pos.apply_split(ratio)
+        logger.info(
+            "Split %s x%s: quantity %s -> %s, avg_cost=$%s",
+            instrument.display_name,
+            ratio,
+            old_qty,
+            pos.quantity,
+            pos.average_cost,
+        )
+
+    def apply_dividend(self, instrument: Instrument, amount: Decimal) -> None:
+        """Credit a cash dividend of `amount` per share; short positions pay it."""
+        pos = self._positions.get(instrument)
+        if pos is None:
+            return
+        cash_delta = pos.quantity * amount
+        self.update_cash(cash_delta)
+        logger.info(
+            "Dividend %s $%s/share on %s shares: cash delta=$%s",
+            instrument.display_name,
+            amount,
+            pos.quantity,
+            cash_delta,
+        )
+
+    def rename_instrument(self, old: Instrument, new: Instrument) -> None:
+        """Move a held position to the instrument's new listing after a symbol change."""
+        pos = self._positions.pop(old, None)
+        if pos is None:
+            return
+        pos.instrument = new
+        self._positions[new] = pos
+        logger.info("Symbol change: position in %s moved to %s", old.display_name, new.display_name)
+
     def mark_to_market(self, prices: Mapping[Instrument, Decimal]) -> None:
         """Update `current_price` for positions using provided price map.
 
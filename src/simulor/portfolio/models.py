@@ -133,7 +133,11 @@ class EqualWeight(PortfolioConstructionModel):
                 targets[instrument] = Decimal("0")
                 continue
 
-            current_price = self.market_store.get_latest_price(instrument)
+            current_price = self.market_store.get_latest_prices([instrument]).get(instrument)
+            if current_price is None:
+                # No price available yet, e.g. behind a look-ahead guard
+                logger.debug("EqualWeight: no price available for %s, skipping", instrument)
+                continue
 
             # Sanity check for bad data
             if current_price <= 0:
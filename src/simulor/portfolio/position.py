@@ -73,3 +73,12 @@ class Position:
             # If just reducing (Long 10 -> Long 5), average_cost stays the same.
 
         self.quantity = new_qty
+
+    def apply_split(self, ratio: Decimal) -> None:
+        """Restate the position after a split of `ratio` new shares per old share.
+
+        Quantity scales up and prices scale down, leaving market value and cost unchanged.
+        """
+        self.quantity *= ratio
+        self.average_cost /= ratio
+        self.current_price /= ratio
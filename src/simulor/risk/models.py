@@ -71,7 +71,7 @@ class PositionLimit(RiskModel):
 
         for instrument, target_quantity in targets.items():
             # Get current price to calculate position value
-            current_price = self.market_store.get_latest_price(instrument)
+            current_price = self.market_store.get_latest_prices([instrument]).get(instrument)
 
             if current_price is None or current_price <= Decimal("0"):
                 # Can't get price, skip this instrument
@@ -47,22 +47,40 @@ class Instrument:
     # Contract specifications
     contract_size: Decimal | None = None
 
+    @property
+    def key(self) -> tuple[object, ...]:
+        """Composite identity: every field that distinguishes one listing from another.
+
+        Two instruments are equal exactly when their keys are equal, so AAPL on NASDAQ
+        and AAPL on LSE, or a future and a stock sharing a ticker, stay distinct.
+        Contract specifications (tick size, contract size) are not part of the identity.
+        """
+        return (
+            self.symbol,
+            self.asset_type,
+            self.exchange,
+            self.currency,
+            self.expiry,
+            self.strike,
+            self.option_type,
+        )
+
     def __hash__(self) -> int:
-        """Compute hash based on symbol only."""
-        return hash(self.symbol)
+        """Compute hash from the composite identity key."""
+        return hash(self.key)
 
     def __eq__(self, other: object) -> bool:
-        """Compare instruments based on symbol only."""
+        """Compare instruments by their composite identity key."""
         if not isinstance(other, Instrument):
             return NotImplemented
-        return self.symbol == other.symbol
+        return self.key == other.key
 
     def __post_init__(self) -> None:
         """Validate instrument data."""
         if not self.symbol or not self.symbol.strip():
             raise ValueError("Symbol cannot be empty")
 
-        if self.asset_type != AssetType.STOCK:
+        if self.asset_type not in (AssetType.STOCK, AssetType.FUTURE):
             raise NotImplementedError(f"Asset type {self.asset_type.value} is not yet supported.")
 
         # Validate non-derivative fields
@@ -109,3 +127,24 @@ class Instrument:
             currency=currency,
             tick_size=tick_size,
         )
+
+    @classmethod
+    def future(
+        cls,
+        symbol: str,
+        expiry: datetime | None = None,
+        exchange: str | None = None,
+        currency: str = "USD",
+        tick_size: Decimal | None = None,
+        contract_size: Decimal | None = None,
+    ) -> Instrument:
+        """Create a futures instrument: a contract, or a continuous series when `expiry` is None."""
+        return cls(
+            symbol=symbol,
+            asset_type=AssetType.FUTURE,
+            exchange=exchange,
+            currency=currency,
+            tick_size=tick_size,
+            expiry=expiry,
+            contract_size=contract_size,
+        )
@@ -5,9 +5,11 @@ This module defines MarketData and subclasses with NO imports from simulor packa
 
 from __future__ import annotations
 
+import contextlib
 from dataclasses import dataclass
 from datetime import datetime
 from decimal import Decimal
+from typing import TYPE_CHECKING
 
 from simulor.types.common import Resolution, TickDirection
 from simulor.types.instruments import Instrument
@@ -159,3 +161,11 @@ class QuoteBar(MarketData):
     def mid_close(self) -> Decimal:
         """Calculate mid-point close price."""
         return (self.bid_close + self.ask_close) / 2
+
+
+# Prefer the native records from the Rust extension when it is installed. They
+# keep the same fields, constructor signature and validation as the dataclasses
+# above, while holding prices as fixed-point values internally.
+if not TYPE_CHECKING:
+    with contextlib.suppress(ImportError):
+        from _simulor_rust import MarketData, QuoteBar, QuoteTick, TradeBar, TradeTick  # noqa: F811
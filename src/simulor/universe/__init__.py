@@ -3,6 +3,7 @@
 Provides the protocol and reference implementations for universe selection:
 - UniverseSelectionModel: Protocol interface
 - Static: Fixed list of instruments
+- HistoricalIndexUniverse: Point-in-time index members (requires the extension)
 - Top: Top N by metric with rebalancing
 - Liquid: Volume/price filters
 - Fundamental: Metric-based filters
@@ -10,8 +11,16 @@ Provides the protocol and reference implementations for universe selection:
 
 from __future__ import annotations
 
+import contextlib
+
 from simulor.universe.models import Static
 
 __all__ = [
     "Static",
 ]
+
+# Point-in-time membership is only implemented natively, in the Rust extension
+with contextlib.suppress(ImportError):
+    from simulor.universe.historical import HistoricalIndexUniverse, MembershipStore
+
+    __all__ += ["HistoricalIndexUniverse", "MembershipStore"]
@@ -0,0 +1,147 @@
+"""Point-in-time index universe.
+
+`HistoricalIndexUniverse` trades the constituents of an index as they were
+at each point of the backtest, read from a `MembershipStore` of add/remove
+intervals. Names later removed or delisted are included while they were
+members, so results are free of survivorship bias. Requires the
+`_simulor_rust` extension.
+
+Example:
+    >>> from simulor.universe.historical import HistoricalIndexUniverse, MembershipStore
+    >>> store = MembershipStore.from_csv("data/sp500_membership.csv", timezone="America/New_York")
+    >>> universe = HistoricalIndexUniverse(store, rebalance="quarterly")
+"""
+
+from __future__ import annotations
+
+import logging
+from collections.abc import Callable, Hashable, Iterable
+from datetime import date, datetime
+from typing import Literal
+
+from _simulor_rust import MembershipStore
+
+from simulor.core.protocols import UniverseSelectionModel
+from simulor.logging import get_logger
+from simulor.types import Instrument
+
+__all__ = [
+    "HistoricalIndexUniverse",
+    "MembershipStore",
+]
+
+# Create module logger
+logger = get_logger(__name__)
+
+Rebalance = Literal["daily", "weekly", "monthly", "quarterly"]
+
+_PERIODS: dict[str, Callable[[date], Hashable]] = {
+    "daily": lambda day: day,
+    "weekly": lambda day: day.isocalendar()[:2],
+    "monthly": lambda day: (day.year, day.month),
+    "quarterly": lambda day: (day.year, (day.month - 1) // 3),
+}
+
+
+class HistoricalIndexUniverse(UniverseSelectionModel):
+    """Historical index universe: Members of an index as of each rebalance.
+
+    Membership is read from the store at the first event and again at the
+    first event of each rebalance period, or on or after each of a list of
+    rebalance dates. Between rebalances the universe stays fixed, as an
+    index-tracking strategy's would.
+
+    With `include_held`, instruments the portfolio still holds stay in the
+    universe after leaving the index, so their positions can be closed.
+
+    Example:
+        >>> store = MembershipStore([
+        ...     ("AAPL", date(1982, 11, 30), None),
+        ...     ("LEH", date(1994, 5, 31), date(2008, 9, 16)),
+        ... ])
+        >>> universe = HistoricalIndexUniverse(store, rebalance="monthly")
+    """
+
+    def __init__(
+        self,
+        store: MembershipStore,
+        rebalance: Rebalance | Iterable[date] = "monthly",
+        include_held: bool = True,
+    ) -> None:
+        """Initialize historical index universe.
+
+        Args:
+            store: Membership intervals of the index
+            rebalance: Period to refresh membership at ("daily", "weekly",
+                "monthly" or "quarterly"), or the dates to refresh it on
+            include_held: Keep instruments with open positions in the universe
+        """
+        self._period: Callable[[date], Hashable] | None = None
+        self._dates: list[date] = []
+        if isinstance(rebalance, str):
+            if rebalance not in _PERIODS:
+                raise ValueError(f"Unknown rebalance period: {rebalance!r}")
+            self._period = _PERIODS[rebalance]
+        else:
+            self._dates = sorted(rebalance)
+        self._store = store
+        self._include_held = include_held
+        self._members: list[Instrument] = []
+        self._last_refresh: date | None = None
+        self._next_date = 0
+
+    @property
+    def store(self) -> MembershipStore:
+        """Membership intervals of the index."""
+        return self._store
+
+    @property
+    def members(self) -> list[Instrument]:
+        """Index members as of the last rebalance."""
+        return list(self._members)
+
+    def select_universe(self) -> list[Instrument]:
+        """Return the index members, refreshed on rebalance.
+
+        Returns:
+            Members as of the last rebalance, followed by any held
+            instruments no longer in the index when `include_held` is set
+        """
+        now = self.time
+        if now is not None and self._due(now):
+            self._refresh(now)
+        if not self._include_held:
+            return self._members
+        members = set(self._members)
+        held = [instrument for instrument in self.portfolio.positions if instrument not in members]
+        return [*self._members, *held]
+
+    def _due(self, now: datetime) -> bool:
+        """Whether membership should be refreshed at `now`."""
+        day = now.astimezone(self._store.timezone_info).date() if now.tzinfo else now.date()
+        if self._last_refresh is None:
+            due = True
+        elif self._period is not None:
+            due = self._period(day) != self._period(self._last_refresh)
+        else:
+            due = self._next_date < len(self._dates) and day >= self._dates[self._next_date]
+        if due:
+            self._last_refresh = day
+            while self._next_date < len(self._dates) and self._dates[self._next_date] <= day:
+                self._next_date += 1
+        return due
+
+    def _refresh(self, now: datetime) -> None:
+        """Read the index members at `now`."""
+        previous = set(self._members)
+        self._members = self._store.members(now)
+        current = set(self._members)
+        logger.info(
+            "Historical index universe at %s: %d members (%d added, %d removed)",
+            now,
+            len(self._members),
+            len(current - previous),
+            len(previous - current),
+        )
+        if logger.isEnabledFor(logging.DEBUG):
+            logger.debug("Historical index universe members: %s", self._members)
@@ -0,0 +1,27 @@
+"""Shared test fixtures."""
+
+from __future__ import annotations
+
+import importlib.util
+import sys
+from collections.abc import Callable
+from types import ModuleType
+from unittest import mock
+
+import pytest
+
+
+@pytest.fixture
+def python_fallback() -> Callable[[str], ModuleType]:
+    """Load a fresh copy of a module as it runs without the native extension."""
+
+    def load(name: str) -> ModuleType:
+        spec = importlib.util.find_spec(name)
+        assert spec is not None and spec.loader is not None
+        module = importlib.util.module_from_spec(spec)
+        # Registered while it runs, as dataclasses look their module up
+        with mock.patch.dict(sys.modules, {"_simulor_rust": None, name: module}):
+            spec.loader.exec_module(module)
+        return module
+
+    return load
@@ -0,0 +1,108 @@
+"""Test the native exchange calendars."""
+
+from __future__ import annotations
+
+from datetime import UTC, date, datetime
+from decimal import Decimal
+from typing import Any
+from zoneinfo import ZoneInfo
+
+import pytest
+
+from simulor.types import Resolution
+from simulor.types.instruments import Instrument
+
+native = pytest.importorskip("_simulor_rust")
+
+NY = ZoneInfo("America/New_York")
+AAPL = Instrument.stock("AAPL")
+
+
+def trade(time: datetime) -> Any:
+    return native.TradeTick(time, AAPL, Resolution.TICK, Decimal("100"), Decimal("1"))
+
+
+def test_names_and_errors() -> None:
+    assert {"NYSE", "NASDAQ", "CME", "LSE", "HKEX", "CRYPTO"} <= set(native.ExchangeCalendar.names())
+    nyse = native.ExchangeCalendar("nyse")
+    assert nyse.name == "NYSE"
+    assert nyse.timezone_info == NY
+    assert repr(nyse) == "ExchangeCalendar('NYSE')"
+    with pytest.raises(ValueError, match="Unknown exchange calendar: MOON"):
+        native.ExchangeCalendar("MOON")
+
+
+def test_holidays_and_sessions() -> None:
+    nyse = native.ExchangeCalendar("NYSE")
+    holidays = nyse.holidays(2024)
+    assert list(holidays)[:3] == [date(2024, 1, 1), date(2024, 1, 15), date(2024, 2, 19)]
+    assert len(holidays) == 10
+    assert not nyse.is_session(date(2024, 7, 4))
+    assert nyse.is_early_close(date(2024, 7, 3))
+
+    session = nyse.session(date(2024, 7, 3))
+    assert session.open == datetime(2024, 7, 3, 9, 30, tzinfo=NY)
+    assert session.close == datetime(2024, 7, 3, 13, tzinfo=NY)
+    assert session.early_close
+    assert session.break_start is None
+    assert nyse.session(date(2024, 7, 4)) is None
+    assert [s.date for s in nyse.sessions(date(2024, 7, 3), date(2024, 7, 8))] == [
+        date(2024, 7, 3),
+        date(2024, 7, 5),
+        date(2024, 7, 8),
+    ]
+
+
+def test_time_queries_accept_any_timezone() -> None:
+    nyse = native.ExchangeCalendar("NYSE")
+    # 12:59 and 13:00 New York time on the early close
+    assert nyse.is_open(datetime(2024, 7, 3, 16, 59, tzinfo=UTC))
+    assert not nyse.is_open(datetime(2024, 7, 3, 17, tzinfo=UTC))
+    assert nyse.is_open(datetime(2024, 7, 3, 17, tzinfo=UTC), extended=True)
+
+    after = datetime(2024, 7, 3, 13, tzinfo=NY)
+    assert nyse.next_open(after) == datetime(2024, 7, 5, 9, 30, tzinfo=NY)
+    assert nyse.previous_close(after) == after
+    assert nyse.next_session(after).date == date(2024, 7, 5)
+    assert nyse.previous_session(after).date == date(2024, 7, 3)
+    assert nyse.session_at(after) is None
+    assert nyse.session_at(after, extended=True).date == date(2024, 7, 3)
+    assert nyse.session_date(datetime(2024, 7, 4, 12, tzinfo=NY)) == date(2024, 7, 5)
+
+
+def test_lunch_breaks() -> None:
+    hkex = native.ExchangeCalendar("HKEX")
+    session = hkex.session(date(2024, 3, 4))
+    hk = hkex.timezone_info
+    assert session.break_start == datetime(2024, 3, 4, 12, tzinfo=hk)
+    assert session.break_end == datetime(2024, 3, 4, 13, tzinfo=hk)
+    lunch = datetime(2024, 3, 4, 12, 30, tzinfo=hk)
+    assert not session.is_open(lunch)
+    # The break is still part of the session
+    assert hkex.session_at(lunch).date == session.date
+    assert hkex.next_open(lunch) == session.break_end
+
+
+def test_filter_event() -> None:
+    nyse = native.ExchangeCalendar("NYSE")
+    inside, outside = trade(datetime(2024, 7, 3, 12, tzinfo=NY)), trade(datetime(2024, 7, 3, 14, tzinfo=NY))
+    event = native.MarketEvent(inside.timestamp)
+    event.add(inside)
+    assert nyse.filter_event(event) is event
+
+    event.add(outside)
+    (kept,) = nyse.filter_event(event).flatten()
+    assert kept == inside
+    assert len(nyse.filter_event(event, extended=True).flatten()) == 2
+
+    late = native.MarketEvent(outside.timestamp)
+    late.add(outside)
+    assert nyse.filter_event(late) is None
+
+    # Intraday bars overlapping the session are kept; daily bars go by date
+    bar = native.TradeBar(datetime(2024, 7, 3, 12, 30, tzinfo=NY), AAPL, Resolution.HOUR, *[Decimal(1)] * 5)
+    holiday = native.TradeBar(datetime(2024, 7, 4, tzinfo=NY), AAPL, Resolution.DAILY, *[Decimal(1)] * 5)
+    bars = native.MarketEvent(bar.timestamp)
+    bars.add(bar)
+    bars.add(holiday)
+    assert nyse.filter_event(bars).flatten() == [bar]
@@ -0,0 +1,157 @@
+"""Test the native Parquet and Arrow IPC providers against the Python CSV provider."""
+
+from __future__ import annotations
+
+import csv
+import io
+import warnings
+from collections.abc import Callable
+from datetime import UTC, datetime
+from decimal import Decimal
+from pathlib import Path
+from types import ModuleType
+from typing import Any
+
+import pytest
+
+from simulor.types import Resolution
+
+native = pytest.importorskip("_simulor_rust")
+pa = pytest.importorskip("pyarrow")
+pq = pytest.importorskip("pyarrow.parquet")
+feather = pytest.importorskip("pyarrow.feather")
+
+BARS = """timestamp,symbol,instrument_type,open,high,low,close,volume
+2024-01-02 09:30:00,AAPL,stock,185.00,186.10,184.20,185.64,1000
+2024-01-02 09:30:00,MSFT,stock,370.00,371.00,369.50,370.50,800
+2024-01-03 09:30:00,AAPL,stock,184.00,185.00,183.00,184.25,900
+2024-01-03 09:30:00,MSFT,stock,372.00,371.00,369.50,370.50,800
+2024-01-04 09:30:00,MSFT,stock,370.00,371.00,369.50,370.50,-5
+2024-01-05 09:30:00,AAPL,stock,186.00,187.00,185.50,186.75,1200
+"""
+
+TICKS = """timestamp,symbol,price,size
+2024-01-02T14:30:00Z,AAPL,185.01,10
+2024-01-02T14:30:00.25Z,MSFT,370.1,1
+2024-01-02T14:30:01Z,AAPL,185.02,5
+"""
+
+
+def rows(provider: Any, keep: Callable[[Any], bool] = lambda record: True) -> list[tuple[datetime, list[str]]]:
+    """Event times with the reprs of their kept records."""
+    with warnings.catch_warnings():
+        warnings.simplefilter("ignore")
+        events = [(event.time, sorted(repr(r) for r in event.flatten() if keep(r))) for event in provider]
+    return [(time, records) for time, records in events if records]
+
+
+def text_table(contents: str) -> Any:
+    """The CSV as a table of string columns, so values keep their scale."""
+    header, *lines = list(csv.reader(io.StringIO(contents)))
+    return pa.table({name: pa.array([line[i] for line in lines], pa.string()) for i, name in enumerate(header)})
+
+
+def write(table: Any, path: Path) -> Path:
+    if path.suffix == ".parquet":
+        pq.write_table(table, path, row_group_size=2)
+    elif path.suffix == ".arrows":
+        with pa.ipc.new_stream(str(path), table.schema) as writer:
+            writer.write_table(table, max_chunksize=2)
+    else:
+        feather.write_feather(table, path, chunksize=2)
+    return path
+
+
+def provider_class(path: Path) -> Any:
+    return native.ParquetDataProvider if path.suffix == ".parquet" else native.ArrowIpcDataProvider
+
+
+@pytest.fixture
+def fallback(python_fallback: Callable[[str], ModuleType]) -> ModuleType:
+    return python_fallback("simulor.data.providers.csv")
+
+
+@pytest.fixture(params=[".parquet", ".arrow", ".arrows"])
+def suffix(request: Any) -> str:
+    return request.param
+
+
+@pytest.mark.parametrize("timezone", ["UTC", "America/New_York"])
+def test_text_columns_match_csv(tmp_path: Path, fallback: ModuleType, suffix: str, timezone: str) -> None:
+    for name, contents in {"bars": BARS, "ticks": TICKS}.items():
+        csv_path = tmp_path / f"{name}.csv"
+        csv_path.write_text(contents)
+        path = write(text_table(contents), tmp_path / f"{name}{suffix}")
+
+        expected = rows(fallback.CSVDataProvider(csv_path, Resolution.DAILY, timezone=timezone))
+        assert expected
+        assert rows(provider_class(path)(path, Resolution.DAILY, timezone=timezone)) == expected
+
+
+def test_typed_columns_match_csv(tmp_path: Path, fallback: ModuleType, suffix: str) -> None:
+    csv_path = tmp_path / "ticks.csv"
+    csv_path.write_text(TICKS)
+    table = pa.table(
+        {
+            "timestamp": pa.array(
+                [
+                    datetime(2024, 1, 2, 14, 30, tzinfo=UTC),
+                    datetime(2024, 1, 2, 14, 30, 0, 250000, tzinfo=UTC),
+                    datetime(2024, 1, 2, 14, 30, 1, tzinfo=UTC),
+                ],
+                pa.timestamp("us", tz="UTC"),
+            ),
+            "symbol": pa.array(["AAPL", "MSFT", "AAPL"]).dictionary_encode(),
+            "price": pa.array([Decimal("185.01"), Decimal("370.1"), Decimal("185.02")], pa.decimal128(10, 2)),
+            "size": pa.array([10, 1, 5], pa.int64()),
+        }
+    )
+    path = write(table, tmp_path / f"ticks{suffix}")
+    native_rows = rows(provider_class(path)(path, Resolution.TICK))
+
+    # Decimal columns carry their own scale: 370.1 reads as 370.10
+    expected = rows(fallback.CSVDataProvider(csv_path, Resolution.TICK))
+    assert native_rows == [(t, [r.replace("'370.1'", "'370.10'") for r in records]) for t, records in expected]
+
+
+def test_filters_match_filtered_csv(tmp_path: Path, fallback: ModuleType, suffix: str) -> None:
+    csv_path = tmp_path / "bars.csv"
+    csv_path.write_text(BARS)
+    path = write(text_table(BARS), tmp_path / f"bars{suffix}")
+    start, end = datetime(2024, 1, 3, 9, 30), datetime(2024, 1, 5, 9, 30)
+
+    filtered = provider_class(path)(path, Resolution.DAILY, start=start, end=end, symbols=["AAPL"])
+    expected = rows(
+        fallback.CSVDataProvider(csv_path, Resolution.DAILY),
+        lambda record: start <= record.timestamp.replace(tzinfo=None) <= end and record.instrument.symbol == "AAPL",
+    )
+    assert [time.day for time, _ in expected] == [3, 5]
+    assert rows(filtered) == expected
+
+
+def test_validate_false_keeps_invalid_rows(tmp_path: Path, suffix: str) -> None:
+    path = write(text_table(BARS), tmp_path / f"bars{suffix}")
+    validated = list(provider_class(path)(path, Resolution.DAILY))
+    unvalidated = list(provider_class(path)(path, Resolution.DAILY, validate=False))
+
+    # The open above the high on Jan 3 and the negative volume on Jan 4 are invalid
+    assert sum(event.count for event in validated) == 4
+    assert sum(event.count for event in unvalidated) == 6
+
+
+def test_errors(tmp_path: Path, suffix: str) -> None:
+    cls = provider_class(tmp_path / f"x{suffix}")
+    with pytest.raises(FileNotFoundError):
+        cls(tmp_path / f"missing{suffix}", Resolution.DAILY)
+
+    table = text_table(BARS).select(["timestamp", "open", "high", "low", "close", "volume"])
+    path = write(table, tmp_path / f"nosymbol{suffix}")
+    with pytest.raises(ValueError, match="missing required column 'symbol'"):
+        list(cls(path, Resolution.DAILY))
+
+
+def test_are_data_providers() -> None:
+    from simulor.data.providers import ArrowIpcDataProvider, DataProvider, ParquetDataProvider
+
+    assert issubclass(ParquetDataProvider, DataProvider)
+    assert issubclass(ArrowIpcDataProvider, DataProvider)
@@ -0,0 +1,213 @@
+"""Test continuous futures: roll rules, back-adjustment, and rolls in the store, feed and broker."""
+
+from __future__ import annotations
+
+import sys
+from datetime import UTC, date, datetime
+from decimal import Decimal
+from pathlib import Path
+from typing import Any
+from unittest import mock
+
+import pytest
+
+from simulor.types import Instrument, Resolution
+from simulor.types.orders import Fill
+
+native = pytest.importorskip("_simulor_rust")
+
+ES = Instrument.future("ES")
+H24, M24 = Instrument.stock("ESH24"), Instrument.stock("ESM24")
+
+
+def D(value: Any) -> Decimal:
+    return Decimal(str(value))
+
+
+def at(day: int) -> datetime:
+    return datetime(2024, 3, day, 21, tzinfo=UTC)
+
+
+def bar(day: int, instrument: Instrument, close: Any, volume: Any = 100) -> Any:
+    close = D(close)
+    return native.TradeBar(at(day), instrument, Resolution.DAILY, close, close + 1, close - 1, close, D(volume))
+
+
+def events(days: dict[int, list[Any]]) -> list[Any]:
+    out = []
+    for day, records in days.items():
+        event = native.MarketEvent(at(day))
+        for record in records:
+            event.add(record)
+        out.append(event)
+    return out
+
+
+# Both contracts trade daily from Monday 11 March; ESH24 expires on Friday 15 March
+WEEK = events({day: [bar(day, H24, 90 + day), bar(day, M24, 100 + day)] for day in range(11, 15)})
+
+
+def stitched(future: Any, source: list[Any] = WEEK) -> list[Any]:
+    provider = native.ContinuousFuturesProvider(source, [future])
+    return [record for event in provider for record in event.flatten() if record.instrument == ES]
+
+
+def closes(records: Any) -> list[Decimal]:
+    return [record.close for record in records]
+
+
+def test_calendar_rolls_before_expiry() -> None:
+    es = native.ContinuousFuture("ES", days_before_expiry=2)
+    assert (es.instrument, es.rule, es.adjustment) == (ES, native.RollRule.CALENDAR, native.BackAdjustment.DIFFERENCE)
+    series = stitched(es)
+
+    # The front contract until two weekdays before its expiry, then the next
+    assert closes(series) == [D(101), D(102), D(113), D(114)]
+    assert {record.instrument for record in series} == {ES}
+    (roll,) = es.rolls
+    assert (roll.time, roll.instrument, roll.from_contract, roll.to_contract) == (at(13), ES, H24, M24)
+    # Last prices seen before the roll
+    assert (roll.from_price, roll.to_price, roll.amount) == (D(102), D(112), D(10))
+    assert es.active_contract == M24 and es.contracts == [H24, M24]
+    assert repr(es) == "ContinuousFuture(root='ES', rule=CALENDAR, adjustment=DIFFERENCE, 2 contracts, 1 rolls)"
+
+    # Every pass rolls afresh
+    assert closes(stitched(es)) == closes(series) and len(es.rolls) == 1
+
+
+def test_volume_and_open_interest_crossovers_roll_the_next_day(tmp_path: Path) -> None:
+    source = events(
+        {
+            day: [bar(day, H24, 90 + day, volume=100), bar(day, M24, 100 + day, volume=50 + 50 * (day - 4))]
+            for day in range(4, 8)
+        }
+    )
+    # ESM24 trades 150 against 100 on the 6th, so the series rolls on the 7th
+    volume = native.ContinuousFuture("ES", rule=native.RollRule.VOLUME)
+    assert closes(stitched(volume, source)) == [D(94), D(95), D(96), D(107)]
+
+    open_interest = native.ContinuousFuture("ES", rule=native.RollRule.OPEN_INTEREST)
+    (tmp_path / "oi.csv").write_text("date,symbol,open_interest\n2024-03-05,ESH24,900\n2024-03-05,ESM24,800\n")
+    open_interest.load_open_interest(tmp_path / "oi.csv")
+    open_interest.add_open_interest(H24, date(2024, 3, 6), 700)
+    open_interest.add_open_interest("ESM24", date(2024, 3, 6), D(1000))
+    assert closes(stitched(open_interest, source)) == [D(94), D(95), D(96), D(107)]
+    with pytest.raises(ValueError, match="cannot be negative"):
+        open_interest.add_open_interest(H24, date(2024, 3, 6), -1)
+
+    # Without a crossover, the calendar rule still applies
+    calendar = native.ContinuousFuture("ES", rule=native.RollRule.OPEN_INTEREST, days_before_expiry=2)
+    assert closes(stitched(calendar))[2:] == [D(113), D(114)]
+
+
+def test_expiries_can_be_given() -> None:
+    es = native.ContinuousFuture("ES", days_before_expiry=0, expiries={"ESH24": date(2024, 3, 12)})
+    assert closes(stitched(es)) == [D(101), D(112), D(113), D(114)]
+    with pytest.raises(ValueError, match="cannot be empty"):
+        native.ContinuousFuture(" ")
+
+
+@pytest.mark.parametrize(
+    ("adjustment", "amount", "before"),
+    [
+        ("DIFFERENCE", D(10), [D(111), D(112)]),
+        # 112 / 102, to 10 places, scaling earlier prices
+        ("RATIO", D("1.0980392157"), [D("110.9019607857"), D("112.0000000014")]),
+        ("NONE", None, [D(101), D(102)]),
+    ],
+)
+def test_back_adjustment_is_point_in_time(adjustment: str, amount: Decimal | None, before: list[Decimal]) -> None:
+    es = native.ContinuousFuture("ES", days_before_expiry=2, adjustment=getattr(native.BackAdjustment, adjustment))
+    series = stitched(es)
+    assert es.rolls[0].amount == amount
+
+    assert closes(es.adjust(series)) == before + [D(113), D(114)]
+    # Volumes are left alone
+    assert [record.volume for record in es.adjust(series)] == [D(100)] * 4
+    # Only rolls made by `as_of` adjust
+    assert closes(es.adjust(series[:2], as_of=at(12))) == [D(101), D(102)]
+    assert (es.count(at(12)), es.count()) == (0, 0 if amount is None else 1)
+    assert es.between(at(12), at(13)) == es.rolls and es.between(start=at(13)) == []
+
+
+def test_market_store_lookbacks_follow_the_rolls() -> None:
+    from simulor.data.market_store import MarketStore
+
+    es = native.ContinuousFuture("ES", days_before_expiry=2)
+    store = MarketStore(continuous_futures=[es])
+    provider = native.ContinuousFuturesProvider(WEEK, [es])
+    lookbacks = []
+    for event in provider:
+        store.update(event)
+        lookbacks.append(closes(store.get_trade_bars(ES, Resolution.DAILY)))
+
+    assert lookbacks[1] == [D(101), D(102)]
+    assert lookbacks[2] == [D(111), D(112), D(113)]
+    assert closes(store.get_trade_bars(ES, Resolution.DAILY, raw=True)) == [D(101), D(102), D(113), D(114)]
+    # Contracts are stored as traded
+    assert closes(store.get_trade_bars(H24, Resolution.DAILY)) == [D(101), D(102), D(103), D(104)]
+
+
+def test_csv_feed_stitches_contracts(tmp_path: Path) -> None:
+    from simulor.data.csv_feed import CsvFeed
+
+    rows = [
+        f"2024-03-{day},{symbol},stock,1,{close},1,{close},10\n"
+        for day in range(11, 15)
+        for symbol, close in [("ESH24", day), ("ESM24", day + 1)]
+    ]
+    path = tmp_path / "es.csv"
+    path.write_text("timestamp,symbol,instrument_type,open,high,low,close,volume\n" + "".join(rows))
+    es = native.ContinuousFuture("ES", days_before_expiry=2)
+    feed = CsvFeed(path, Resolution.DAILY, continuous_futures=[es])
+    assert feed.data_quality is None
+    series = [record for event in feed._provider for record in event.flatten() if record.instrument == ES]
+    assert closes(series) == [D(11), D(12), D(14), D(15)]
+
+    # The stitching is native, unlike plain CSV reading
+    with (
+        mock.patch.dict(sys.modules, {"_simulor_rust": None}),
+        pytest.raises(RuntimeError, match="CsvFeed continuous_futures requires the _simulor_rust extension"),
+    ):
+        CsvFeed(path, Resolution.DAILY, continuous_futures=[es])
+    with (
+        mock.patch.dict(sys.modules, {"_simulor_rust": None}),
+        pytest.raises(RuntimeError, match="CsvFeed quality and continuous_futures require the _simulor_rust"),
+    ):
+        CsvFeed(path, Resolution.DAILY, quality=native.DataQualityPolicy(), continuous_futures=[es])
+
+
+class Bus:
+    def __init__(self) -> None:
+        self.events: list[Any] = []
+
+    def publish(self, event: Any) -> None:
+        self.events.append(event)
+
+
+def test_broker_rolls_positions_and_cancels_orders() -> None:
+    from simulor.execution.simulation.broker import SimulatedBroker
+    from simulor.portfolio.manager import Portfolio
+    from simulor.types import OrderSide, OrderSpec, OrderType
+
+    broker, bus = SimulatedBroker(), Bus()
+    portfolio = Portfolio(starting_cash=D(10_000))
+    broker.initialize(bus, Portfolio(starting_cash=D(0)), {"s": portfolio})  # type: ignore[arg-type]
+    broker.connect()
+    portfolio.update_position(Fill(instrument=ES, quantity=D(2), price=D(100), commission=D(0)))
+    spec = OrderSpec(instrument=ES, side=OrderSide.SELL, quantity=D(2), order_type=OrderType.LIMIT, limit_price=D(150))
+    order_id = broker.submit_order("s", spec).order_id
+
+    es = native.ContinuousFuture("ES", days_before_expiry=2)
+    stitched(es)
+    broker.on_roll(es.rolls[0])
+
+    # Closed at the old contract's price and reopened at the new one's
+    assert [(event.payload["fill"].quantity, event.payload["fill"].price) for event in bus.events] == [
+        (D(-2), D(102)),
+        (D(2), D(112)),
+    ]
+    assert portfolio.positions[ES].quantity == D(2)
+    # Bought at 100, sold at 102 and bought back at 112
+    assert portfolio.cash == D(10_000) - 2 * D(100) + 2 * D(102) - 2 * D(112)
+    assert broker._engine.owner(order_id) is None
@@ -0,0 +1,205 @@
+"""Test the corporate action store, adjusted lookbacks and position adjustments."""
+
+from __future__ import annotations
+
+from datetime import date, datetime
+from decimal import Decimal
+from pathlib import Path
+from typing import Any
+from zoneinfo import ZoneInfo
+
+import pytest
+
+from simulor.types import Resolution
+from simulor.types.instruments import Instrument
+from simulor.types.orders import Fill
+
+native = pytest.importorskip("_simulor_rust")
+
+NY = ZoneInfo("America/New_York")
+AAPL = Instrument.stock("AAPL")
+FB = Instrument.stock("FB")
+META = Instrument.stock("META")
+
+
+def D(value: Any) -> Decimal:
+    return Decimal(str(value))
+
+
+def bar(day: int, close: Any, volume: Any = 100, instrument: Instrument = AAPL) -> Any:
+    close = D(close)
+    time = datetime(2024, 1, day, 9, 30, tzinfo=NY)
+    return native.TradeBar(time, instrument, Resolution.DAILY, close, close, close, close, D(volume))
+
+
+def closes(records: list[Any]) -> list[Decimal]:
+    return [record.close for record in records]
+
+
+@pytest.fixture
+def store() -> Any:
+    return native.CorporateActionStore(
+        [
+            native.CorporateAction.split("AAPL", date(2024, 1, 3), "4:1"),
+            native.CorporateAction.dividend(AAPL, "2024-01-05", "1"),
+        ],
+        timezone="America/New_York",
+    )
+
+
+def test_actions() -> None:
+    split = native.CorporateAction.split("AAPL", "2024-01-03", "1:10")
+    assert split.kind == native.CorporateActionType.SPLIT
+    assert split.instrument == AAPL
+    assert split.ex_date == date(2024, 1, 3)
+    assert (split.ratio, split.amount, split.new_instrument) == (D("0.1"), None, None)
+    assert native.CorporateAction.split("AAPL", "2024-01-03", 4).ratio == 4
+
+    rename = native.CorporateAction.symbol_change("FB", "2022-06-09", "META")
+    assert (rename.ratio, rename.new_instrument) == (None, META)
+    assert "SYMBOL_CHANGE" in repr(rename)
+
+    with pytest.raises(ValueError, match="Split ratio must be positive"):
+        native.CorporateAction.split("AAPL", "2024-01-03", "0:1")
+    with pytest.raises(ValueError, match="Dividend amount must be positive"):
+        native.CorporateAction.dividend("AAPL", "2024-01-03", "-1")
+    with pytest.raises(ValueError, match="different instrument"):
+        native.CorporateAction.symbol_change("FB", "2024-01-03", FB)
+
+
+def test_loads_csv(tmp_path: Path) -> None:
+    path = tmp_path / "actions.csv"
+    path.write_text(
+        "date,symbol,action,value\n"
+        "2022-06-09,FB,symbol_change,META\n"
+        "2020-08-31,AAPL,split,4:1\n"
+        "2020-11-06,AAPL,Dividend,0.205\n"
+    )
+    store = native.CorporateActionStore.from_csv(path, timezone="America/New_York")
+    Kind = native.CorporateActionType
+    assert len(store) == 3
+    assert store.timezone_info == NY
+    # Sorted by ex-date
+    assert [a.kind for a in store.actions()] == [Kind.SPLIT, Kind.DIVIDEND, Kind.SYMBOL_CHANGE]
+    assert [a.amount for a in store.actions("AAPL")] == [None, D("0.205")]
+
+    path.write_text("date,symbol,action,value\n2020-08-31,AAPL,split,4:1\n2020-08-31,AAPL,merger,1\n")
+    with pytest.raises(ValueError, match="line 3: invalid action 'merger'"):
+        native.CorporateActionStore.from_csv(path)
+    path.write_text("date,symbol,action\n")
+    with pytest.raises(ValueError, match="missing required column 'value'"):
+        native.CorporateActionStore.from_csv(path)
+
+
+def test_queries_are_point_in_time(store: Any) -> None:
+    # Actions take effect at midnight starting the ex-date, in the store's zone
+    assert store.count("AAPL", datetime(2024, 1, 2, 23, 59, tzinfo=NY)) == 0
+    assert store.count("AAPL", datetime(2024, 1, 3, tzinfo=NY)) == 1
+    assert store.count(AAPL) == 2
+    assert store.count("MSFT") == 0
+
+    midnight = datetime(2024, 1, 3, tzinfo=NY)
+    assert [a.kind for a in store.between(midnight, None)] == [native.CorporateActionType.DIVIDEND]
+    assert [a.kind for a in store.between(None, midnight)] == [native.CorporateActionType.SPLIT]
+    assert store.between(midnight, midnight) == []
+
+
+def test_back_adjusts_splits(store: Any) -> None:
+    history = [bar(2, 400, 25), bar(3, 100), bar(4, 100), bar(5, 99)]
+    # Before the split takes effect, nothing is restated
+    assert store.adjust(history, datetime(2024, 1, 2, 16, tzinfo=NY)) == history
+
+    adjusted = store.adjust(history, datetime(2024, 1, 5, 12, tzinfo=NY))
+    assert closes(adjusted) == [D(100), D(100), D(100), D(99)]
+    assert adjusted[0].volume == D(100)
+    assert adjusted[0].timestamp == history[0].timestamp
+    # Dividends only affect total return
+    assert adjusted[1:] == history[1:]
+    assert store.adjust(history, adjustment=native.PriceAdjustment.RAW) == history
+    assert store.adjust([]) == []
+
+
+def test_total_return_reinvests_dividends(store: Any) -> None:
+    history = [bar(2, 400), bar(3, 100), bar(4, 100), bar(5, 99)]
+    adjusted = store.adjust(history, adjustment=native.PriceAdjustment.TOTAL_RETURN)
+    # The 1.00 dividend is 1% of the last close before its ex-date
+    assert closes(adjusted) == [D(99), D(99), D(99), D(99)]
+
+    quotes = [
+        native.QuoteTick(datetime(2024, 1, 4, 15, tzinfo=NY), AAPL, Resolution.TICK, D(99), D(5), D(101), D(5)),
+        native.QuoteTick(datetime(2024, 1, 5, 10, tzinfo=NY), AAPL, Resolution.TICK, D(98), D(5), D(100), D(5)),
+    ]
+    # Quotes are measured at the mid
+    first, _ = store.adjust(quotes, adjustment=native.PriceAdjustment.TOTAL_RETURN)
+    assert (first.bid_price, first.ask_price, first.bid_size) == (D("98.01"), D("99.99"), D(5))
+
+
+def test_history_follows_symbol_changes() -> None:
+    store = native.CorporateActionStore(
+        [
+            native.CorporateAction.split("FB", "2024-01-03", 2),
+            native.CorporateAction.symbol_change("FB", "2024-01-04", "META"),
+            native.CorporateAction.split("META", "2024-01-05", 3),
+        ]
+    )
+    history = [bar(2, 600, instrument=FB), bar(3, 300, instrument=FB), bar(4, 300, instrument=META)]
+    assert store.count("META") == 3
+    assert store.count("FB") == 2
+    adjusted = store.adjust(history, instrument=META)
+    assert closes(adjusted) == [D(100), D(100), D(100)]
+    # Records keep their instrument
+    assert adjusted[0].instrument == FB
+
+
+def test_market_store_restates_lookbacks(store: Any) -> None:
+    from simulor.data.market_store import MarketStore
+
+    market_store = MarketStore(store)
+    for record in [bar(2, 400, 25), bar(3, 100)]:
+        event = native.MarketEvent(record.timestamp)
+        event.add(record)
+        market_store.update(event)
+
+    assert closes(market_store.get_trade_bars(AAPL, Resolution.DAILY)) == [D(100), D(100)]
+    assert closes(market_store.get_trade_bars(AAPL, Resolution.DAILY, raw=True)) == [D(400), D(100)]
+    assert market_store.get_latest_price(AAPL) == D(100)
+
+    total_return = MarketStore(store, native.PriceAdjustment.TOTAL_RETURN)
+    for day, close in [(4, 100), (5, 99)]:
+        event = native.MarketEvent(bar(day, close).timestamp)
+        event.add(bar(day, close))
+        total_return.update(event)
+    assert closes(total_return.get_trade_bars(AAPL, Resolution.DAILY)) == [D(99), D(99)]
+    assert closes(total_return.get_trade_bars(AAPL, Resolution.DAILY, raw=True)) == [D(100), D(99)]
+
+    market_store.rename_instrument(AAPL, META)
+    assert market_store.get_trade_bars(AAPL, Resolution.DAILY) == []
+    assert closes(market_store.get_trade_bars(META, Resolution.DAILY, raw=True)) == [D(400), D(100)]
+
+
+def test_portfolio_applies_actions() -> None:
+    from simulor.portfolio.manager import Portfolio
+
+    portfolio = Portfolio(D(10_000))
+    portfolio.update_position(Fill(instrument=AAPL, quantity=D(10), price=D(400)))
+    portfolio.update_position(Fill(instrument=FB, quantity=D(-5), price=D(300)))
+    portfolio.mark_to_market({AAPL: D(400), FB: D(300)})
+    value = portfolio.total_value
+
+    portfolio.apply_split(AAPL, D(4))
+    position = portfolio.positions[AAPL]
+    assert (position.quantity, position.average_cost, position.current_price) == (D(40), D(100), D(100))
+    assert portfolio.total_value == value
+
+    # Short positions pay the dividend
+    portfolio.apply_dividend(AAPL, D("0.5"))
+    portfolio.apply_dividend(FB, D(1))
+    assert portfolio.cash == D(10_000) - D(4000) + D(1500) + D(20) - D(5)
+
+    portfolio.rename_instrument(FB, META)
+    assert FB not in portfolio.positions
+    assert portfolio.positions[META].quantity == D(-5)
+    assert portfolio.positions[META].instrument == META
+    # Instruments without a position are ignored
+    portfolio.apply_split(Instrument.stock("MSFT"), D(2))
+    portfolio.apply_dividend(Instrument.stock("MSFT"), D(2))
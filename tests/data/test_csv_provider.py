@@ -0,0 +1,124 @@
+"""Test the native CSV provider against the Python implementation."""
+
+from __future__ import annotations
+
+import warnings
+from collections.abc import Callable
+from datetime import datetime
+from pathlib import Path
+from types import ModuleType
+from typing import Any
+
+import pytest
+
+from simulor.types import Resolution
+
+native = pytest.importorskip("_simulor_rust")
+
+BARS = """timestamp,symbol,instrument_type,open,high,low,close,volume
+2024-01-02 09:30:00,AAPL,stock,185.00,186.10,184.20,185.64,1000
+2024-01-02 09:30:00,MSFT,stock,370.00,371.00,369.50,370.50,800
+2024-01-03 09:30:00,AAPL,stock,184.00,185.00,183.00,184.25,900
+2024-01-04 09:30:00,,stock,1,1,1,1,1
+2024-01-04 09:30:00,MSFT,stock,372.00,371.00,369.50,370.50,800
+2024-01-05T09:30:00-05:00,MSFT,stock,370.00,371.00,369.50,370.50,0
+"""
+
+TICKS = """timestamp,symbol,price,size
+1704205800,AAPL,185.01,10
+1704205800000,MSFT,370.1,1
+1704205800.25,AAPL,185.02,5
+2024-01-02T14:30:01Z,AAPL,185.03,7
+2024-01-02 14:30:02.5,AAPL,0,7
+"""
+
+QUOTES = """timestamp,symbol,bid_price,bid_size,ask_price,ask_size
+2024-01-02 14:30:00,AAPL,185.00,100,185.02,200
+2024-01-02 14:30:00.5,AAPL,185.01,100,185.02,0
+2024-01-02 14:30:01,AAPL,185.05,100,185.02,200
+"""
+
+QUOTE_BARS = """timestamp,symbol,bid_open,bid_high,bid_low,bid_close,ask_open,ask_high,ask_low,ask_close
+2024-01-02,EURUSD,1.10,1.11,1.09,1.105,1.101,1.111,1.091,1.106
+"""
+
+
+def rows(provider: Any) -> list[tuple[datetime, list[str]]]:
+    """Event times with their records, in a form both implementations share."""
+    with warnings.catch_warnings():
+        warnings.simplefilter("ignore")
+        return [(event.time, sorted(map(repr, event.flatten()))) for event in provider]
+
+
+@pytest.fixture
+def fallback(python_fallback: Callable[[str], ModuleType]) -> ModuleType:
+    return python_fallback("simulor.data.providers.csv")
+
+
+@pytest.fixture
+def data_dir(tmp_path: Path) -> Path:
+    for name, contents in {"bars": BARS, "ticks": TICKS, "quotes": QUOTES, "quote_bars": QUOTE_BARS}.items():
+        (tmp_path / f"{name}.csv").write_text(contents)
+    (tmp_path / "notes.txt").write_text("not data")
+    return tmp_path
+
+
+@pytest.mark.parametrize("name", ["bars", "ticks", "quotes", "quote_bars"])
+@pytest.mark.parametrize("timezone", ["UTC", "America/New_York"])
+def test_single_file_matches_python(data_dir: Path, fallback: ModuleType, name: str, timezone: str) -> None:
+    path = data_dir / f"{name}.csv"
+    expected = rows(fallback.CSVDataProvider(path, Resolution.DAILY, timezone=timezone))
+    assert expected
+    assert rows(native.CSVDataProvider(path, Resolution.DAILY, timezone=timezone)) == expected
+
+
+def test_directory_merge_matches_python(data_dir: Path, fallback: ModuleType) -> None:
+    expected = rows(fallback.CSVDataProvider(data_dir, Resolution.MINUTE, timezone="America/New_York"))
+    merged = rows(native.CSVDataProvider(data_dir, Resolution.MINUTE, timezone="America/New_York"))
+
+    assert merged == expected
+    assert [time for time, _ in merged] == sorted(time for time, _ in merged)
+
+
+def test_same_timestamp_rows_share_an_event(data_dir: Path) -> None:
+    events = list(native.CSVDataProvider(data_dir / "bars.csv", Resolution.DAILY))
+    assert [event.count for event in events] == [2, 1, 1]
+    assert {i.symbol for i in events[0].instruments()} == {"AAPL", "MSFT"}
+
+
+def test_iterating_twice_restarts(data_dir: Path) -> None:
+    provider = native.CSVDataProvider(data_dir, Resolution.DAILY)
+    assert rows(provider) == rows(provider)
+
+
+def test_warns_without_instrument_type_column(data_dir: Path) -> None:
+    with pytest.warns(UserWarning, match="missing 'instrument_type' column"):
+        list(native.CSVDataProvider(data_dir / "ticks.csv", Resolution.TICK))
+
+
+def test_custom_column_names(tmp_path: Path, fallback: ModuleType) -> None:
+    path = tmp_path / "renamed.csv"
+    path.write_text("time,ticker,price,size\n2024-01-02 10:00:00,AAPL,1,2\n")
+    expected = rows(fallback.CSVDataProvider(path, Resolution.TICK, date_column="time", symbol_column="ticker"))
+    assert rows(native.CSVDataProvider(path, Resolution.TICK, date_column="time", symbol_column="ticker")) == expected
+
+
+def test_errors_match_python(tmp_path: Path, fallback: ModuleType) -> None:
+    with pytest.raises(FileNotFoundError):
+        native.CSVDataProvider(tmp_path / "missing.csv", Resolution.DAILY)
+    with pytest.raises(ValueError, match="No CSV files found"):
+        native.CSVDataProvider(tmp_path, Resolution.DAILY)
+
+    path = tmp_path / "nosymbol.csv"
+    path.write_text("timestamp,price,size\n2024-01-02,1,1\n")
+    with pytest.raises(ValueError, match="missing required column 'symbol'"):
+        list(fallback.CSVDataProvider(path, Resolution.TICK))
+    with pytest.raises(ValueError, match="missing required column 'symbol'"):
+        list(native.CSVDataProvider(path, Resolution.TICK))
+
+
+def test_is_a_data_provider() -> None:
+    from simulor.data.providers import CSVDataProvider, DataProvider
+
+    assert CSVDataProvider is native.CSVDataProvider
+    assert issubclass(CSVDataProvider, DataProvider)
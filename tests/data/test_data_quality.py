@@ -0,0 +1,170 @@
+"""Test the native data quality stage and its report."""
+
+from __future__ import annotations
+
+import sys
+from datetime import UTC, datetime, timedelta
+from decimal import Decimal
+from pathlib import Path
+from typing import Any
+from unittest import mock
+
+import pytest
+
+from simulor.types import Resolution
+from simulor.types.instruments import Instrument
+
+native = pytest.importorskip("_simulor_rust")
+
+Issue = native.QualityIssue
+Action = native.QualityAction
+
+AAPL = Instrument.stock("AAPL")
+MSFT = Instrument.stock("MSFT")
+
+BARS = """timestamp,symbol,instrument_type,open,high,low,close,volume
+2024-01-02 09:30:00,AAPL,stock,185.00,186.10,184.20,185.64,1000
+2024-01-02 09:30:00,MSFT,stock,370.00,371.00,369.50,370.50,800
+2024-01-03 09:30:00,AAPL,stock,184.00,183.00,182.00,182.50,900
+2024-01-03 09:30:00,MSFT,stock,-1,371.00,369.50,370.50,800
+2024-01-04 09:30:00,AAPL,stock,183.00,184.00,182.00,183.50,900
+2024-01-05 09:30:00,MSFT,stock,370.00,371.00,369.50,370.50,800
+"""
+
+QUOTES = """timestamp,symbol,bid_price,bid_size,ask_price,ask_size
+2024-01-02 09:30:00,AAPL,185.00,10,185.02,12
+2024-01-02 09:30:01,AAPL,185.03,10,185.01,12
+2024-01-02 09:30:02,AAPL,185.00,10,185.00,12
+"""
+
+
+@pytest.fixture
+def bars(tmp_path: Path) -> Path:
+    path = tmp_path / "bars.csv"
+    path.write_text(BARS)
+    return path
+
+
+def provider(path: Path, policy: Any = None) -> Any:
+    source = native.CSVDataProvider(path, Resolution.DAILY, validate=False)
+    return native.DataQualityProvider(source, policy)
+
+
+def test_drops_bad_records_and_reports_them(bars: Path) -> None:
+    checked = provider(bars)
+    events = list(checked)
+    kept = [(event.time.day, record.instrument.symbol) for event in events for record in event.flatten()]
+    assert kept == [(2, "AAPL"), (2, "MSFT"), (4, "AAPL"), (5, "MSFT")]
+
+    report = checked.report
+    assert (report.records, report.dropped, report.repaired, report.flagged) == (6, 2, 0, 0)
+    assert report.issues == {Issue.OHLC_INCONSISTENT: 1, Issue.NON_POSITIVE_PRICE: 1}
+    assert len(report) == 2
+    assert [quality.instrument for quality in report] == [AAPL, MSFT]
+    assert AAPL in report and Instrument.stock("IBM") not in report
+    aapl = report[AAPL]
+    assert (aapl.records, aapl.dropped, aapl.repaired, aapl.flagged) == (3, 1, 0, 0)
+    assert aapl.issues == {Issue.OHLC_INCONSISTENT: 1}
+    with pytest.raises(KeyError):
+        report[Instrument.stock("IBM")]
+
+    # Each pass starts a fresh report
+    list(checked)
+    assert report.records == 6
+
+
+def test_repairs_or_flags_records(bars: Path) -> None:
+    repair = native.DataQualityPolicy(default=Action.FLAG, actions={Issue.OHLC_INCONSISTENT: Action.REPAIR})
+    checked = provider(bars, repair)
+    records = [record for event in checked for record in event.flatten()]
+    assert len(records) == 6
+    repaired = records[2]
+    assert (repaired.open, repaired.high, repaired.low) == (Decimal("184.00"), Decimal("184.00"), Decimal("182.00"))
+    # The negative open is flagged, but its bar is repaired too, and that outranks the flag
+    assert records[3].low == Decimal(-1)
+    report = checked.report
+    assert (report.dropped, report.repaired, report.flagged) == (0, 2, 0)
+    assert report.issues == {Issue.OHLC_INCONSISTENT: 2, Issue.NON_POSITIVE_PRICE: 1}
+
+    # Issues that cannot be repaired are dropped under REPAIR
+    checked = provider(bars, native.DataQualityPolicy(default=Action.REPAIR))
+    assert sum(event.count for event in checked) == 5
+    assert (checked.report.dropped, checked.report.repaired) == (1, 1)
+
+
+def test_quote_checks(tmp_path: Path) -> None:
+    path = tmp_path / "quotes.csv"
+    path.write_text(QUOTES)
+    checked = provider(path, native.DataQualityPolicy(default=Action.REPAIR))
+    quotes = [record for event in checked for record in event.flatten()]
+    # The crossed quote has its sides swapped; the locked one cannot be repaired
+    assert [(q.bid_price, q.ask_price) for q in quotes] == [
+        (Decimal("185.00"), Decimal("185.02")),
+        (Decimal("185.01"), Decimal("185.03")),
+    ]
+    assert quotes[1].bid_size == Decimal(12)
+    assert checked.report.issues == {Issue.CROSSED_QUOTE: 1, Issue.LOCKED_QUOTE: 1}
+
+
+def events(*groups: list[Any]) -> list[Any]:
+    out = []
+    for records in groups:
+        event = native.MarketEvent(records[0].timestamp)
+        for record in records:
+            event.add(record)
+        out.append(event)
+    return out
+
+
+def test_events_pass_through_unless_changed() -> None:
+    time = datetime(2024, 1, 2, 14, 30, tzinfo=UTC)
+    bar = native.TradeBar(time, AAPL, Resolution.MINUTE, *[Decimal(1)] * 5)
+    other = native.TradeBar(time, MSFT, Resolution.MINUTE, *[Decimal(1)] * 5)
+    later = native.TradeBar(time + timedelta(minutes=1), AAPL, Resolution.MINUTE, *[Decimal(1)] * 5)
+    source = events([bar, other], [bar], [later, other])
+    checked = list(native.DataQualityProvider(source))
+
+    # Clean events are passed on as they are; emptied events are skipped
+    assert len(checked) == 2
+    assert checked[0] is source[0]
+    assert checked[1].flatten() == [later]
+
+
+def test_policy() -> None:
+    policy = native.DataQualityPolicy(
+        actions={Issue.STALE_QUOTE: Action.FLAG}, spike_method=native.SpikeMethod.Z_SCORE, stale_after=timedelta(1)
+    )
+    assert policy.default == Action.DROP
+    assert policy.action(Issue.STALE_QUOTE) == Action.FLAG
+    assert policy.action(Issue.PRICE_SPIKE) == Action.DROP
+    assert policy.actions == {Issue.STALE_QUOTE: Action.FLAG}
+    assert (policy.spike_threshold, policy.spike_window) == (10.0, 50)
+    assert policy.stale_after == timedelta(1)
+    assert native.DataQualityPolicy(spike_threshold=None).spike_threshold is None
+
+    with pytest.raises(ValueError, match="spike_threshold must be positive"):
+        native.DataQualityPolicy(spike_threshold=0)
+    with pytest.raises(ValueError, match="spike_window must be at least 2"):
+        native.DataQualityPolicy(spike_window=1)
+    with pytest.raises(ValueError, match="stale_after must be positive"):
+        native.DataQualityPolicy(stale_after=timedelta(0))
+
+
+def test_csv_feed_reports_quality(bars: Path) -> None:
+    from simulor.data.csv_feed import CsvFeed
+
+    assert CsvFeed(bars, Resolution.DAILY).data_quality is None
+    feed = CsvFeed(bars, Resolution.DAILY, quality=native.DataQualityPolicy())
+    assert feed.data_quality is not None
+    assert sum(event.count for event in feed._provider) == 4
+    assert feed.data_quality.dropped == 2
+
+
+def test_csv_feed_quality_requires_the_extension(bars: Path) -> None:
+    from simulor.data.csv_feed import CsvFeed
+
+    with (
+        mock.patch.dict(sys.modules, {"_simulor_rust": None}),
+        pytest.raises(RuntimeError, match="CsvFeed quality requires the _simulor_rust extension"),
+    ):
+        CsvFeed(bars, Resolution.DAILY, quality=native.DataQualityPolicy())
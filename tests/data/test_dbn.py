@@ -0,0 +1,254 @@
+"""Test the native Databento DBN provider and feed."""
+
+from __future__ import annotations
+
+import logging
+import struct
+from datetime import UTC, datetime, timedelta
+from decimal import Decimal
+from pathlib import Path
+from typing import Any
+from zoneinfo import ZoneInfo
+
+import pytest
+
+from simulor.types import Instrument, OrderSide, Resolution
+from simulor.types.common import TickDirection
+
+native = pytest.importorskip("_simulor_rust")
+
+OPEN = datetime(2024, 1, 2, 14, 30, tzinfo=UTC)
+SYMBOL_LEN = 71
+UNDEF = 2**63 - 1
+SCHEMAS = {"mbo": 0, "mbp-1": 1, "mbp-10": 2, "trades": 4, "ohlcv-1m": 6}
+AAPL, MSFT = 42, 43
+
+
+def nanos(time: datetime) -> int:
+    return int((time - datetime(1970, 1, 1, tzinfo=UTC)).total_seconds()) * 10**9 + time.microsecond * 1000
+
+
+def symbol(text: str) -> bytes:
+    return text.encode().ljust(SYMBOL_LEN, b"\0")
+
+
+def dbn(schema: str | None, records: list[bytes], mappings: dict[str, int] | None = None) -> bytes:
+    """A version 2 file starting at `OPEN`, mapping raw symbols to instrument IDs for January"""
+    mappings = {"AAPL": AAPL, "MSFT": MSFT} if mappings is None else mappings
+    body = b"XNAS.ITCH".ljust(16, b"\0")
+    body += struct.pack("<HQQQ3BH", SCHEMAS.get(schema, 0xFFFF), nanos(OPEN), 2**64 - 1, 0, 1, 1, 0, SYMBOL_LEN)
+    body += bytes(53) + struct.pack("<I", 0)
+    body += struct.pack("<I", len(mappings)) + b"".join(map(symbol, mappings)) + struct.pack("<II", 0, 0)
+    body += struct.pack("<I", len(mappings))
+    for raw, instrument_id in mappings.items():
+        body += symbol(raw) + struct.pack("<III", 1, 20240101, 20240201) + symbol(str(instrument_id))
+    return b"DBN\x02" + struct.pack("<I", len(body)) + body + b"".join(records)
+
+
+def header(rtype: int, length: int, instrument_id: int, time: datetime) -> bytes:
+    return struct.pack("<BBHIQ", length // 4, rtype, 1, instrument_id, nanos(time))
+
+
+def price(value: str | None) -> int:
+    return UNDEF if value is None else int(Decimal(value) * 10**9)
+
+
+def market(rtype: int, length: int, time: datetime, action: bytes, side: bytes, at: str, size: int = 0) -> bytes:
+    """Trades and MBP records up to their levels, received when their event happened"""
+    fields = struct.pack("<qIccBBQiI", price(at), size, action, side, 0, 0, nanos(time), 0, 0)
+    return header(rtype, length, AAPL, time) + fields
+
+
+def trade(time: datetime, at: str, size: int, side: bytes = b"B") -> bytes:
+    return market(0x00, 48, time, b"T", side, at, size)
+
+
+def level(bid: tuple[str, int] | None = None, ask: tuple[str, int] | None = None) -> bytes:
+    (bid_price, bid_size), (ask_price, ask_size) = bid or (None, 0), ask or (None, 0)
+    return struct.pack("<qqIIII", price(bid_price), price(ask_price), bid_size, ask_size, 1, 1)
+
+
+def bar(time: datetime, instrument_id: int, *values: str) -> bytes:
+    *prices, volume = values
+    return header(0x21, 56, instrument_id, time) + struct.pack("<4qQ", *map(price, prices), int(volume))
+
+
+def mbo(time: datetime, action: bytes, order: int, side: bytes = b"N", at: str | None = None, size: int = 0) -> bytes:
+    fields = struct.pack("<QqIBBccQiI", order, price(at), size, 0, 0, action, side, nanos(time), 0, 0)
+    return header(0xA0, 56, AAPL, time) + fields
+
+
+def records(provider: Any) -> list[tuple[datetime, list[Any]]]:
+    return [(event.time, event.flatten()) for event in provider]
+
+
+def at(seconds: int) -> datetime:
+    return OPEN + timedelta(seconds=seconds)
+
+
+def test_merges_trades_and_bars_from_a_directory(tmp_path: Path) -> None:
+    (tmp_path / "trades.dbn").write_bytes(dbn("trades", [trade(at(1), "185.05", 100), trade(at(90), "185.1", 5)]))
+    (tmp_path / "bars.dbn").write_bytes(dbn("ohlcv-1m", [bar(OPEN, MSFT, "370", "371.5", "369", "371", "1200")]))
+    (tmp_path / "notes.zst").write_bytes(b"not read")
+    provider = native.DbnDataProvider(tmp_path, timezone="America/New_York")
+    assert [path.name for path in provider.files] == ["bars.dbn", "trades.dbn"]
+    assert [(m["version"], m["dataset"], m["schema"]) for m in provider.metadata] == [
+        (2, "XNAS.ITCH", "ohlcv-1m"),
+        (2, "XNAS.ITCH", "trades"),
+    ]
+    assert provider.metadata[0]["start"] == OPEN and provider.metadata[0]["end"] is None
+
+    stream = records(provider)
+    # Bars are placed at their open, trades at their receipt
+    assert [time for time, _ in stream] == [OPEN, at(1), at(90)]
+    assert stream[0][0].tzinfo == ZoneInfo("America/New_York")
+    (minute,) = stream[0][1]
+    assert isinstance(minute, native.TradeBar) and minute.resolution == Resolution.MINUTE
+    assert (minute.instrument, minute.open, minute.high, minute.close, minute.volume) == (
+        Instrument.stock("MSFT"),
+        *map(Decimal, "370 371.5 371 1200".split()),
+    )
+    (first,) = stream[1][1]
+    assert isinstance(first, native.TradeTick)
+    assert (first.instrument.symbol, first.price, first.size) == ("AAPL", Decimal("185.05"), Decimal(100))
+    assert first.direction == TickDirection.BUY
+
+
+def test_mbp_records_become_quotes_and_book_snapshots(tmp_path: Path) -> None:
+    top = market(0x01, 80, at(1), b"T", b"A", "185.04", 10) + level(("185.04", 300), ("185.06", 200))
+    one_sided = market(0x01, 80, at(2), b"A", b"B", "185.05", 10) + level(("185.05", 10))
+    depth = [level(("185.04", 300), ("185.06", 200)), level(ask=("185.08", 50))] + [level()] * 8
+    ten = market(0x0A, 368, at(3), b"C", b"A", "185.07", 10) + b"".join(depth)
+    (tmp_path / "mbp-1.dbn").write_bytes(dbn("mbp-1", [top, one_sided]))
+    (tmp_path / "mbp-10.dbn").write_bytes(dbn("mbp-10", [ten]))
+
+    stream = records(native.DbnDataProvider(tmp_path))
+    assert [[type(record).__name__ for record in event] for _, event in stream] == [
+        ["TradeTick", "QuoteTick"],
+        # No quote while a side is empty
+        [],
+        ["BookUpdate"],
+    ]
+    print_, quote = stream[0][1]
+    assert (quote.bid_price, quote.bid_size, quote.ask_price, quote.ask_size) == tuple(
+        map(Decimal, "185.04 300 185.06 200".split())
+    )
+    assert (print_.price, print_.direction) == (Decimal("185.04"), TickDirection.SELL)
+    (snapshot,) = stream[-1][1]
+    assert snapshot.snapshot and snapshot.sequence == 1
+    assert snapshot.bids == ((Decimal("185.04"), Decimal(300)),)
+    assert snapshot.asks == ((Decimal("185.06"), Decimal(200)), (Decimal("185.08"), Decimal(50)))
+
+
+def test_mbo_records_replay_into_the_book(tmp_path: Path) -> None:
+    path = tmp_path / "mbo.dbn"
+    path.write_bytes(
+        dbn(
+            "mbo",
+            [
+                mbo(at(1), b"A", 1, b"B", "185", 100),
+                mbo(at(1), b"A", 2, b"B", "185", 50),
+                mbo(at(2), b"A", 3, b"A", "185.05", 20),
+                # Shrinking in place keeps priority, fills leave the book to the cancel that follows
+                mbo(at(3), b"M", 1, b"B", "185", 60),
+                mbo(at(4), b"T", 0, b"A", "185", 30),
+                mbo(at(4), b"F", 1, b"B", "185", 30),
+                mbo(at(4), b"C", 1, b"B", "185", 30),
+                # Moving an order loses priority
+                mbo(at(5), b"M", 2, b"B", "184.99", 50),
+            ],
+        )
+    )
+    stream = iter(native.DbnDataProvider(path))
+    events = [
+        [(type(record).__name__, getattr(record, "bids", None), getattr(record, "asks", None)) for record in event]
+        for _, event in records(stream)
+    ]
+
+    d = Decimal
+    # A snapshot of the empty book announces it
+    assert events[0] == [
+        ("BookUpdate", (), ()),
+        ("BookUpdate", ((d(185), d(100)),), ()),
+        ("BookUpdate", ((d(185), d(150)),), ()),
+    ]
+    assert events[2] == [("BookUpdate", ((d(185), d(110)),), ())]
+    assert [kind for kind, _, _ in events[3]] == ["TradeTick", "BookUpdate"]
+    assert events[3][1][1] == ((d(185), d(80)),)
+    assert events[4] == [("BookUpdate", ((d(185), d(30)), (d("184.99"), d(50))), ())]
+
+    book = stream.order_book(Instrument.stock("AAPL"))
+    assert book.timestamp == at(5)
+    assert book.bids() == [(d(185), d(30)), (d("184.99"), d(50))]
+    assert book.asks() == [(d("185.05"), d(20))]
+    assert book.order(1) == (OrderSide.BUY, d(185), d(30))
+    assert stream.order_book("MSFT") is None
+
+
+def test_clears_and_unknown_orders(tmp_path: Path, caplog: pytest.LogCaptureFixture) -> None:
+    path = tmp_path / "mbo.dbn"
+    path.write_bytes(
+        dbn("mbo", [mbo(at(1), b"C", 9, b"B", "185", 10), mbo(at(2), b"A", 1, b"B", "185", 5), mbo(at(3), b"R", 0)])
+    )
+    with caplog.at_level(logging.WARNING, logger="simulor.data.providers.dbn"):
+        events = records(native.DbnDataProvider(path))
+    assert sum("does not match the book" in record.getMessage() for record in caplog.records) == 1
+    cleared = events[-1][1][-1]
+    assert cleared.snapshot and (cleared.bids, cleared.asks) == ((), ())
+
+
+def test_symbology_filters_and_windows(tmp_path: Path, caplog: pytest.LogCaptureFixture) -> None:
+    mapping = header(0x16, 176, 7, at(1)) + struct.pack("<B", 0) + symbol("NVDA") + b"\0" + symbol("7")
+    mapping += struct.pack("<QQ", nanos(OPEN), nanos(OPEN + timedelta(days=1)))
+    unmapped = header(0x00, 48, 8, at(3)) + trade(at(3), "1", 1)[16:]
+    nvda = header(0x00, 48, 7, at(2)) + trade(at(2), "480", 1)[16:]
+    path = tmp_path / "live.dbn"
+    path.write_bytes(dbn("trades", [mapping, nvda, unmapped, trade(at(4), "185", 1)], mappings={}))
+
+    with caplog.at_level(logging.WARNING, logger="simulor.data.providers.dbn"):
+        symbols = [[r.instrument.symbol for r in event] for _, event in records(native.DbnDataProvider(path))]
+    # Symbol mapping records of live captures name instruments; unknown IDs stand for themselves
+    assert symbols == [["NVDA"], ["8"], ["42"]]
+    assert "instrument ID 8 has no symbology mapping" in caplog.text
+
+    provider = native.DbnDataProvider(path, start=at(3), end=at(4), symbols=["42", "NVDA"])
+    assert [time for time, _ in records(provider)] == [at(4)]
+
+
+def test_errors(tmp_path: Path) -> None:
+    with pytest.raises(FileNotFoundError):
+        native.DbnDataProvider(tmp_path / "missing.dbn")
+    with pytest.raises(ValueError, match="No DBN files"):
+        native.DbnDataProvider(tmp_path)
+
+    path = tmp_path / "data.dbn"
+    path.write_bytes(b"PAR1" + bytes(16))
+    with pytest.raises(ValueError, match="missing the DBN signature"):
+        native.DbnDataProvider(path)
+    path.write_bytes(dbn("trades", [trade(at(1), "1", 1)[:40]]))
+    with pytest.raises(ValueError, match="truncated DBN record"):
+        list(native.DbnDataProvider(path))
+
+
+def test_feed_publishes_events_then_the_end_of_stream(tmp_path: Path) -> None:
+    from simulor.core.events import EndOfStreamEvent
+    from simulor.data.dbn_feed import DbnFeed
+    from simulor.data.providers import DataProvider
+
+    assert issubclass(native.DbnDataProvider, DataProvider)
+    path = tmp_path / "trades.dbn"
+    path.write_bytes(dbn("trades", [trade(at(1), "185", 1), trade(at(2), "186", 1)]))
+
+    class Bus:
+        def __init__(self) -> None:
+            self.events: list[Any] = []
+
+        def publish(self, event: Any) -> None:
+            self.events.append(event)
+
+    bus = Bus()
+    feed = DbnFeed(path, symbols=["AAPL"])
+    feed.set_event_bus(bus)  # type: ignore[arg-type]
+    feed.run()
+    assert [event.time for event in bus.events] == [at(1), at(2), at(2)]
+    assert isinstance(bus.events[-1], EndOfStreamEvent)
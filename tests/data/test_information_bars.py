@@ -0,0 +1,145 @@
+"""Test the native information-driven bar consolidators."""
+
+from __future__ import annotations
+
+from datetime import UTC, datetime, timedelta
+from decimal import Decimal
+from typing import Any
+
+import pytest
+
+from simulor.types import Resolution
+from simulor.types.common import TickDirection
+from simulor.types.instruments import Instrument
+
+native = pytest.importorskip("_simulor_rust")
+
+T0 = datetime(2024, 1, 2, 14, 30, tzinfo=UTC)
+AAPL = Instrument.stock("AAPL")
+MSFT = Instrument.stock("MSFT")
+
+
+def trades(
+    prices: list[Any],
+    sizes: list[Any] | None = None,
+    directions: list[TickDirection | None] | None = None,
+    instrument: Instrument = AAPL,
+) -> list[Any]:
+    sizes = sizes or [1] * len(prices)
+    directions = directions or [None] * len(prices)
+    return [
+        native.TradeTick(T0 + timedelta(seconds=i), instrument, Resolution.TICK, Decimal(str(p)), Decimal(str(s)), d)
+        for i, (p, s, d) in enumerate(zip(prices, sizes, directions, strict=True))
+    ]
+
+
+def bar_lengths(consolidator: Any, ticks: list[Any]) -> list[int]:
+    """Number of trades in each bar, by the position of the trade closing it."""
+    lengths, start = [], 0
+    for i, tick in enumerate(ticks):
+        if consolidator.update(tick):
+            lengths.append(i + 1 - start)
+            start = i + 1
+    return lengths
+
+
+def test_tick_bars() -> None:
+    consolidator = native.TickBarConsolidator(3)
+    ticks = trades([100, 102, 99, 101, 101, 103, 104], sizes=[1, 2, 3, 4, 5, 6, 7])
+    bars = [bar for tick in ticks for bar in consolidator.update(tick)]
+
+    assert len(bars) == 2
+    first = bars[0]
+    assert isinstance(first, native.TradeBar)
+    assert first.resolution == Resolution.TICK
+    # Timestamped at the first trade
+    assert first.timestamp == T0
+    ohlcv = (first.open, first.high, first.low, first.close, first.volume)
+    assert ohlcv == tuple(map(Decimal, "100 102 99 99 6".split()))
+    assert bars[1].timestamp == T0 + timedelta(seconds=3)
+    (rest,) = consolidator.flush()
+    assert (rest.open, rest.volume) == (Decimal(104), Decimal(7))
+    assert consolidator.threshold == 3
+
+
+def test_volume_and_dollar_bars_close_on_reaching_the_threshold() -> None:
+    volume = native.VolumeBarConsolidator(Decimal("10"))
+    assert bar_lengths(volume, trades([100] * 6, sizes=[4, 4, 4, 10, 1, 9])) == [3, 1, 2]
+    assert volume.threshold == Decimal("10")
+
+    dollar = native.DollarBarConsolidator(1000)
+    # 500, then 500 + 600 crosses
+    assert bar_lengths(dollar, trades([100, 100, "999.98", "0.01"], sizes=[5, 6, 1, 1])) == [2]
+    (rest,) = dollar.flush()
+    assert rest.volume == Decimal(2)
+
+
+def test_instruments_are_sampled_separately() -> None:
+    consolidator = native.TickBarConsolidator(2)
+    ticks = [tick for pair in zip(trades([1, 2]), trades([3, 4], instrument=MSFT), strict=True) for tick in pair]
+    bars = [bar for tick in ticks for bar in consolidator.update(tick)]
+    assert [(bar.instrument.symbol, bar.close) for bar in bars] == [("AAPL", Decimal(2)), ("MSFT", Decimal(4))]
+
+
+def test_only_trade_ticks_are_consumed() -> None:
+    consolidator = native.TickBarConsolidator(1)
+    quote = native.QuoteTick(T0, AAPL, Resolution.TICK, Decimal(1), Decimal(1), Decimal(2), Decimal(1))
+    assert consolidator.update(quote) == []
+    assert consolidator.flush() == []
+
+    event = native.MarketEvent(T0)
+    event.add(quote)
+    event.add(trades([5])[0])
+    provider = native.ConsolidatingDataProvider([event], consolidator, include_source=False)
+    (out,) = list(provider)
+    # The bar closes on its trade, so it joins that trade's event
+    assert out.time == T0
+    assert sorted(type(r).__name__ for r in out.flatten()) == ["QuoteTick", "TradeBar"]
+
+
+@pytest.mark.parametrize("cls", ["ImbalanceBarConsolidator", "RunBarConsolidator"])
+def test_signed_bars_use_directions_then_the_tick_rule(cls: str) -> None:
+    def make() -> Any:
+        return getattr(native, cls)(metric="tick", expected_ticks=3, adaptive=False)
+
+    buys = trades([100] * 6, directions=[TickDirection.BUY] * 6)
+    assert bar_lengths(make(), buys) == [3, 3]
+
+    # Without directions the first trade is unsigned, then up-ticks and
+    # unchanged prices count as buys
+    assert bar_lengths(make(), trades([100, 101, 101, 101, 102, 102])) == [4]
+    # The tick rule is skipped for trades whose direction is known
+    mixed = trades([100, 99, 98, 97], directions=[TickDirection.BUY] * 4)
+    assert bar_lengths(make(), mixed) == [3]
+
+
+def test_imbalance_bars_wait_for_a_net_imbalance() -> None:
+    consolidator = native.ImbalanceBarConsolidator(metric="volume", expected_ticks=2, tick_span=1, adaptive=False)
+    sides = [TickDirection.BUY, TickDirection.SELL] * 3 + [TickDirection.BUY] * 2
+    # With tick_span=1 the expected imbalance is the last signed volume: 2×1
+    assert bar_lengths(consolidator, trades([100] * 8, directions=sides)) == [8]
+
+
+def test_adaptive_expectations_follow_bar_lengths() -> None:
+    fixed = native.ImbalanceBarConsolidator(metric="volume", expected_ticks=4, adaptive=False)
+    adaptive = native.ImbalanceBarConsolidator(metric="volume", expected_ticks=4, bar_span=1)
+    ticks = trades([100] * 12, sizes=[1, 1, 10] + [1] * 9, directions=[TickDirection.BUY] * 12)
+    # The block trade closes the first bar early; with bar_span=1 the
+    # expected length becomes 3, so the next bar is shorter too
+    assert bar_lengths(fixed, ticks) == [3, 5]
+    assert bar_lengths(adaptive, ticks) == [3, 4, 5]
+
+
+def test_rejects_invalid_settings() -> None:
+    with pytest.raises(ValueError, match="positive"):
+        native.TickBarConsolidator(0)
+    with pytest.raises(ValueError, match="positive"):
+        native.VolumeBarConsolidator(Decimal("-1"))
+    with pytest.raises(ValueError, match="Unknown bar metric"):
+        native.RunBarConsolidator(metric="notional")
+    with pytest.raises(ValueError, match="must be positive"):
+        native.ImbalanceBarConsolidator(expected_ticks=0)
+
+    run = native.RunBarConsolidator(metric="dollar", expected_ticks=50)
+    assert (run.metric, run.expected_ticks) == ("dollar", 50)
+    assert isinstance(run, native.BarConsolidator)
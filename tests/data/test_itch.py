@@ -0,0 +1,211 @@
+"""Test the native ITCH 5.0 provider and the Level 3 order book."""
+
+from __future__ import annotations
+
+import logging
+import struct
+from collections import Counter
+from datetime import date, datetime
+from decimal import Decimal
+from pathlib import Path
+from typing import Any
+from zoneinfo import ZoneInfo
+
+import pytest
+
+from simulor.types import Instrument, OrderSide
+from simulor.types.common import TickDirection
+
+native = pytest.importorskip("_simulor_rust")
+
+NY = ZoneInfo("America/New_York")
+SAMPLE = Path(__file__).parent / "itch" / "01022024.NASDAQ_ITCH50"
+AAPL = Instrument.stock("AAPL")
+OPEN = datetime(2024, 1, 2, 9, 30, tzinfo=NY)
+
+
+def at(microseconds: int) -> datetime:
+    return OPEN.replace(microsecond=microseconds)
+
+
+def kinds(event: Any) -> list[str]:
+    return [type(record).__name__ for record in event.flatten()]
+
+
+def trades(events: list[Any]) -> list[tuple[datetime, Decimal, Decimal, TickDirection]]:
+    return [
+        (record.timestamp, record.price, record.size, record.direction)
+        for event in events
+        for record in event.flatten()
+        if isinstance(record, native.TradeTick)
+    ]
+
+
+def test_replays_the_sample_file() -> None:
+    provider = native.ItchDataProvider(SAMPLE)
+    assert provider.files == [SAMPLE]
+    assert provider.timezone_info == NY
+    events = list(provider)
+
+    # One event per timestamp of the 14 messages after the directory
+    assert [event.time for event in events] == [at(micros) for micros in range(11)]
+    counts = Counter(kind for event in events for kind in kinds(event))
+    assert counts == {"BookUpdate": 13, "TradeTick": 4}
+    # Every pass replays the file afresh
+    assert [event.count for event in provider] == [event.count for event in events]
+
+
+def test_messages_update_levels_and_print_trades() -> None:
+    events = list(native.ItchDataProvider(SAMPLE, symbols=["AAPL"]))
+
+    def levels(event: Any) -> list[tuple[Any, Any, int]]:
+        return [(u.bids, u.asks, u.sequence) for u in event.flatten() if isinstance(u, native.BookUpdate)]
+
+    d = Decimal
+    # Adds: a snapshot of the empty book, then the size of each level changed
+    first = [u for u in events[0].flatten() if isinstance(u, native.BookUpdate)]
+    assert first[0].snapshot and (first[0].bids, first[0].asks) == ((), ())
+    assert levels(events[0])[1:] == [(((d(185), d(100)),), (), 2), (((d(185), d(300)),), (), 3)]
+    # An execution takes 40 off the first order at 185, a cancel 50 more
+    assert levels(events[2]) == [(((d(185), d(260)),), (), 6)]
+    assert levels(events[3]) == [(((d(185), d(210)),), (), 7)]
+    # A replace moves an order from 185.05 to 100 at 185.04
+    assert levels(events[4]) == [((), ((d("185.05"), d(50)), (d("185.04"), d(100))), 8)]
+    # Executing the last order at 185.05 empties the level
+    assert levels(events[5]) == [((), ((d("185.05"), d(0)),), 9)]
+    assert levels(events[7]) == [((), ((d("185.04"), d(0)),), 10)]
+
+    # Executions at the order's price or their own, non-displayed trades and
+    # crosses; the aggressor is opposite the resting order
+    assert trades(events) == [
+        (at(3), d(185), d(40), TickDirection.SELL),
+        (at(6), d("185.06"), d(50), TickDirection.BUY),
+        (at(7), d("185.02"), d(25), TickDirection.SELL),
+        (at(9), d("185.03"), d(1000), TickDirection.NEUTRAL),
+    ]
+
+
+def test_l3_book_after_replay() -> None:
+    stream = iter(native.ItchDataProvider(SAMPLE))
+    assert stream.order_book("AAPL") is None
+    l2: dict[str, Any] = {}
+    for event in stream:
+        for update in (r for r in event.flatten() if isinstance(r, native.BookUpdate)):
+            symbol = update.instrument.symbol
+            l2.setdefault(symbol, native.OrderBookL2(update.instrument, strict=True)).apply(update)
+        # Book updates rebuild the aggregated depth of the L3 books
+        for symbol, book in l2.items():
+            l3 = stream.order_book(symbol)
+            assert (l3.bids(), l3.asks()) == (book.bids(), book.asks())
+
+    book = stream.order_book(AAPL)
+    assert book.timestamp == at(9)
+    assert book.bids() == [(Decimal(185), Decimal(210))]
+    assert book.asks() == [] and book.best_ask is None
+    # Order 1 keeps its priority after being partly executed
+    assert book.queue(OrderSide.BUY, Decimal(185)) == [(1, Decimal(60)), (2, Decimal(150))]
+    assert book.queue_position(2) == (1, Decimal(60))
+    assert book.order(1) == (OrderSide.BUY, Decimal(185), Decimal(60))
+    # Replaced, deleted and fully executed orders are gone
+    assert [book.order(order_id) for order_id in (3, 4, 6)] == [None, None, None]
+    msft = stream.order_book("MSFT")
+    assert (msft.bids(), msft.asks()) == ([], [])
+    assert {instrument.symbol for instrument in stream.instruments} == {"AAPL", "MSFT"}
+
+
+def test_window_keeps_books_complete() -> None:
+    provider = native.ItchDataProvider(SAMPLE, start=at(5), end=at(7))
+    stream = iter(provider)
+    events = list(stream)
+    assert [event.time for event in events] == [at(5), at(6), at(7)]
+    # Messages before the window still built the book, announced by a snapshot
+    (snapshot, *_) = events[0].flatten()
+    assert snapshot.snapshot and snapshot.bids == ((Decimal(185), Decimal(210)),)
+    assert stream.order_book("AAPL").asks() == [(Decimal("185.04"), Decimal(100))]
+
+
+def message(kind: bytes, nanos: int, locate: int, body: bytes) -> bytes:
+    payload = kind + struct.pack(">HH", locate, 0) + nanos.to_bytes(6, "big") + body
+    return struct.pack(">H", len(payload)) + payload
+
+
+def directory(locate: int, symbol: str) -> bytes:
+    return message(b"R", 0, locate, symbol.ljust(8).encode() + b" " * 20)
+
+
+def add(nanos: int, order: int, side: bytes, shares: int, price: int) -> bytes:
+    body = struct.pack(">Q", order) + side + struct.pack(">I", shares) + b"AAPL    " + struct.pack(">I", price)
+    return message(b"A", nanos, 1, body)
+
+
+def delete(nanos: int, order: int) -> bytes:
+    return message(b"D", nanos, 1, struct.pack(">Q", order))
+
+
+def test_reads_directories_of_days(tmp_path: Path) -> None:
+    nine = 9 * 3600 * 10**9
+    (tmp_path / "01032024.NASDAQ_ITCH50").write_bytes(directory(1, "AAPL") + add(nine, 7, b"S", 10, 1_900_000))
+    (tmp_path / "01022024.NASDAQ_ITCH50").write_bytes(directory(1, "AAPL") + add(nine, 7, b"B", 5, 1_850_000))
+    provider = native.ItchDataProvider(tmp_path)
+    assert [path.name for path in provider.files] == ["01022024.NASDAQ_ITCH50", "01032024.NASDAQ_ITCH50"]
+
+    stream = iter(provider)
+    days = [(event.time, [(u.snapshot, u.bids, u.asks) for u in event.flatten()]) for event in stream]
+    # Order references only hold for their day, so each day starts from an empty book
+    assert days == [
+        (datetime(2024, 1, 2, 9, tzinfo=NY), [(True, (), ()), (False, ((Decimal(185), Decimal(5)),), ())]),
+        (datetime(2024, 1, 3, 9, tzinfo=NY), [(True, (), ()), (False, (), ((Decimal(190), Decimal(10)),))]),
+    ]
+    assert stream.order_book("AAPL").bids() == []
+
+
+def test_unknown_orders_warn_once(tmp_path: Path, caplog: pytest.LogCaptureFixture) -> None:
+    path = tmp_path / "feed.itch"
+    path.write_bytes(directory(1, "AAPL") + delete(1, 99) + add(2, 1, b"B", 5, 10_000) + delete(3, 98))
+    with caplog.at_level(logging.WARNING, logger="simulor.data.order_book"):
+        events = list(native.ItchDataProvider(path, date=date(2024, 1, 2)))
+    assert sum("does not match the book" in record.getMessage() for record in caplog.records) == 1
+    assert [len(kinds(event)) for event in events] == [1, 1, 0]
+
+
+def test_errors(tmp_path: Path) -> None:
+    with pytest.raises(FileNotFoundError):
+        native.ItchDataProvider(tmp_path / "missing.itch")
+    with pytest.raises(ValueError, match="No ITCH files"):
+        native.ItchDataProvider(tmp_path)
+
+    path = tmp_path / "feed.itch"
+    path.write_bytes(directory(1, "AAPL") + add(1, 1, b"B", 5, 10_000)[:-3])
+    with pytest.raises(ValueError, match="trading date"):
+        native.ItchDataProvider(path)
+    with pytest.raises(ValueError, match="truncated ITCH message"):
+        list(native.ItchDataProvider(path, date=date(2024, 1, 2)))
+
+
+def test_l3_book_operations() -> None:
+    book = native.OrderBookL3(AAPL)
+    book.add(1, OrderSide.BUY, Decimal("10.00"), Decimal(100))
+    book.add(2, OrderSide.BUY, Decimal("10.00"), Decimal(50))
+    book.add(3, OrderSide.BUY, Decimal("9.99"), Decimal(10))
+    book.add(4, OrderSide.SELL, Decimal("10.02"), Decimal(30))
+    assert book.bids() == [(Decimal(10), Decimal(150)), (Decimal("9.99"), Decimal(10))]
+    assert book.best_ask == (Decimal("10.02"), Decimal(30))
+    assert book.queue_position(2) == (1, Decimal(100))
+
+    # Executions and cancels keep priority, and never take more than rests
+    assert book.execute(1, Decimal(40)) == Decimal(40)
+    assert book.cancel(1, Decimal(100)) == Decimal(60)
+    assert book.queue_position(2) == (0, Decimal(0))
+    # A replace loses priority, even at the same price
+    book.add(5, OrderSide.BUY, Decimal("10.00"), Decimal(20))
+    book.replace(2, 6, Decimal("10.00"), Decimal(50))
+    assert book.queue(OrderSide.BUY, Decimal(10)) == [(5, Decimal(20)), (6, Decimal(50))]
+    book.delete(4)
+    assert book.asks() == []
+
+    l2 = book.to_order_book_l2()
+    assert l2.bids() == book.bids()
+    with pytest.raises(KeyError):
+        book.delete(4)
+    with pytest.raises(ValueError):
+        book.add(5, OrderSide.SELL, Decimal(11), Decimal(1))
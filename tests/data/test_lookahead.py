@@ -0,0 +1,180 @@
+"""Test the look-ahead guard and the time-fenced market store."""
+
+from __future__ import annotations
+
+from collections.abc import Callable
+from datetime import UTC, datetime, timedelta
+from decimal import Decimal
+from types import ModuleType
+from typing import Any
+
+import pytest
+
+from simulor.types import Resolution
+from simulor.types.instruments import Instrument
+
+native = pytest.importorskip("_simulor_rust")
+
+T0 = datetime(2024, 1, 2, tzinfo=UTC)
+AAPL = Instrument.stock("AAPL")
+MSFT = Instrument.stock("MSFT")
+
+
+def daily(day: int, close: Any, instrument: Instrument = AAPL) -> Any:
+    price = Decimal(str(close))
+    time = datetime(2024, 1, day, tzinfo=UTC)
+    return native.TradeBar(time, instrument, Resolution.DAILY, price, price, price, price, Decimal(100))
+
+
+def trade(time: datetime, price: Any, instrument: Instrument = AAPL) -> Any:
+    return native.TradeTick(time, instrument, Resolution.TICK, Decimal(str(price)), Decimal(1))
+
+
+def event(time: datetime, *records: Any) -> Any:
+    out = native.MarketEvent(time)
+    for record in records:
+        out.add(record)
+    return out
+
+
+def market_store(**kwargs: Any) -> Any:
+    from simulor.data.market_store import MarketStore
+
+    return MarketStore(**kwargs)
+
+
+def closes(records: Any) -> list[Decimal]:
+    return [record.close for record in records]
+
+
+def test_guard_lags() -> None:
+    guard = native.LookAheadGuard(delays={Resolution.DAILY: timedelta(minutes=30)})
+    assert guard.lag(Resolution.DAILY) == timedelta(days=1, minutes=30)
+    assert guard.lag(Resolution.MINUTE) == timedelta(minutes=1)
+    assert guard.lag(Resolution.TICK) == timedelta(0)
+    assert guard.delays == {Resolution.DAILY: timedelta(minutes=30)}
+    assert (guard.bar_end, guard.strict) == (True, False)
+
+    at_start = native.LookAheadGuard(delays={Resolution.TICK: timedelta(milliseconds=5)}, bar_end=False)
+    assert at_start.lag(Resolution.DAILY) == timedelta(0)
+    assert at_start.lag(Resolution.TICK) == timedelta(milliseconds=5)
+    assert "bar_end=False" in repr(at_start)
+
+    with pytest.raises(ValueError, match="must not be negative"):
+        native.LookAheadGuard(delays={Resolution.DAILY: timedelta(minutes=-1)})
+
+
+def test_bars_are_hidden_until_their_end() -> None:
+    guard = native.LookAheadGuard(delays={Resolution.DAILY: timedelta(minutes=30)})
+    store = market_store(lookahead=guard)
+    assert store.lookahead is guard
+
+    # A daily bar stamped at midnight is the whole day's data
+    store.update(event(datetime(2024, 1, 2, 14, 30, tzinfo=UTC), daily(2, 100)))
+    assert store.get_trade_bars(AAPL, Resolution.DAILY) == []
+    with pytest.raises(ValueError, match="No price data"):
+        store.get_latest_price(AAPL)
+    assert store.all_instruments() == set()
+
+    store.update(event(datetime(2024, 1, 3, 0, 29, tzinfo=UTC)))
+    assert store.get_trade_bars(AAPL, Resolution.DAILY) == []
+    store.update(event(datetime(2024, 1, 3, 0, 30, tzinfo=UTC), daily(3, 101)))
+    assert closes(store.get_trade_bars(AAPL, Resolution.DAILY)) == [Decimal(100)]
+    assert store.get_latest_price(AAPL) == Decimal(100)
+    assert store.all_instruments() == {AAPL}
+
+
+def test_strict_guard_raises() -> None:
+    from simulor.data.lookahead import LookAheadError
+
+    store = market_store(lookahead=native.LookAheadGuard(strict=True))
+    tick_time = datetime(2024, 1, 2, 14, 30, tzinfo=UTC)
+    store.update(event(tick_time, daily(2, 100), trade(tick_time, 99)))
+    # Ticks are available at once
+    assert len(store.get_trade_ticks(AAPL)) == 1
+    with pytest.raises(LookAheadError, match="TradeBar of AAPL at 2024-01-02 00:00:00\\+00:00 is not available until"):
+        store.get_trade_bars(AAPL, Resolution.DAILY)
+    assert issubclass(LookAheadError, RuntimeError)
+    # The latest price only looks at available records
+    assert store.get_latest_price(AAPL) == Decimal(99)
+
+
+def test_records_never_overtake_earlier_arrivals() -> None:
+    guard = native.LookAheadGuard(delays={Resolution.TICK: timedelta(seconds=10)})
+    store = native.TimeFencedStore(guard)
+    first, second = trade(T0 + timedelta(seconds=5), 1), trade(T0, 2)
+    store.update(event(T0 + timedelta(seconds=5), first))
+    store.update(event(T0 + timedelta(seconds=6), second))
+    # The second tick is due at 10s, but views are a prefix of arrival order
+    store.advance(T0 + timedelta(seconds=14))
+    assert len(store.trade_ticks(AAPL)) == 0
+    store.advance(T0 + timedelta(seconds=15))
+    assert list(store.trade_ticks(AAPL)) == [first, second]
+
+    with pytest.raises(ValueError, match="cannot move backwards"):
+        store.advance(T0)
+    assert store.now == T0 + timedelta(seconds=15)
+
+
+def test_views() -> None:
+    store = native.TimeFencedStore()
+    assert store.now is None and store.guard is None
+    assert store.trade_bars(AAPL, Resolution.DAILY) is None
+    store.update(event(T0, daily(2, 1), daily(2, 1, MSFT)))
+    store.update(event(T0 + timedelta(days=1), daily(3, 2)))
+    view = store.trade_bars(AAPL, Resolution.DAILY)
+
+    store.update(event(T0 + timedelta(days=2), daily(4, 3)))
+    # A view keeps the length it was taken with
+    assert len(view) == 2
+    assert closes(view) == [Decimal(1), Decimal(2)]
+    assert view[-1] == daily(3, 2)
+    assert closes(view[::-1]) == [Decimal(2), Decimal(1)]
+    assert view.index(daily(3, 2)) == 1 and view.count(daily(2, 1)) == 1
+    assert view == [daily(2, 1), daily(3, 2)]
+    assert view != store.trade_bars(AAPL, Resolution.DAILY)
+    with pytest.raises(IndexError):
+        view[2]
+    assert repr(view).startswith("SeriesView([TradeBar(")
+    assert store.latest(AAPL) == daily(4, 3)
+    assert {i.symbol for i in store.instruments()} == {"AAPL", "MSFT"}
+    with pytest.raises(TypeError, match="Unknown data type"):
+        store.update(event(T0 + timedelta(days=3), object()))
+
+
+def test_rename_carries_history() -> None:
+    meta = Instrument.stock("META")
+    store = market_store()
+    store.update(event(T0, daily(2, 1)))
+    store.update(event(T0 + timedelta(days=1), daily(3, 2, meta)))
+    store.rename_instrument(AAPL, meta)
+    assert closes(store.get_trade_bars(meta, Resolution.DAILY)) == [Decimal(1), Decimal(2)]
+    assert store.get_trade_bars(AAPL, Resolution.DAILY) == []
+    assert store.get_latest_price(meta) == Decimal(2)
+
+
+def test_native_store_matches_python_store(python_fallback: Callable[[str], ModuleType]) -> None:
+    fallback = python_fallback("simulor.data.market_store")
+    stores = [fallback.MarketStore(), market_store()]
+    quote = native.QuoteTick(T0, MSFT, Resolution.TICK, Decimal(10), Decimal(1), Decimal(11), Decimal(1))
+    events = [
+        event(T0, daily(2, 1), trade(T0, 5), quote),
+        event(T0 + timedelta(days=1), daily(3, 2), trade(T0 + timedelta(days=1), 6)),
+    ]
+    for store in stores:
+        for market_event in events:
+            store.update(market_event)
+
+    def snapshot(store: Any) -> tuple[Any, ...]:
+        return (
+            list(store.get_trade_bars(AAPL, Resolution.DAILY)),
+            list(store.get_trade_ticks(AAPL)),
+            list(store.get_quote_ticks(MSFT)),
+            list(store.get_quote_bars(AAPL, Resolution.DAILY)),
+            store.get_latest_prices([AAPL, MSFT, Instrument.stock("IBM")]),
+            store.all_instruments(),
+        )
+
+    python, rust = map(snapshot, stores)
+    assert python == rust
+    assert python[4] == {AAPL: Decimal(6), MSFT: Decimal("10.5")}
@@ -0,0 +1,256 @@
+"""Test the native Level 2 order book, book updates in events, and book-aware fill pricing."""
+
+from __future__ import annotations
+
+import logging
+from collections.abc import Callable
+from datetime import UTC, datetime, timedelta
+from decimal import Decimal
+from types import ModuleType
+from typing import Any
+
+import pytest
+
+from simulor.types import Instrument, OrderSide, OrderSpec, OrderType, Resolution
+
+native = pytest.importorskip("_simulor_rust")
+
+T0 = datetime(2024, 1, 2, 14, 30, tzinfo=UTC)
+AAPL = Instrument.stock("AAPL")
+MSFT = Instrument.stock("MSFT")
+
+
+def update(
+    sequence: int | None,
+    bids: Any = None,
+    asks: Any = None,
+    snapshot: bool = False,
+    instrument: Instrument = AAPL,
+) -> Any:
+    time = T0 + timedelta(seconds=sequence or 0)
+    return native.BookUpdate(
+        time, instrument, Resolution.TICK, bids=bids, asks=asks, sequence=sequence, snapshot=snapshot
+    )
+
+
+def levels(*pairs: tuple[str, str]) -> list[tuple[Decimal, Decimal]]:
+    return [(Decimal(price), Decimal(size)) for price, size in pairs]
+
+
+SNAPSHOT = update(1, bids=[("150.24", 500), ("150.23", 1200)], asks=[("150.26", 300), ("150.27", 900)], snapshot=True)
+
+
+def test_applies_snapshots_and_incremental_updates() -> None:
+    book = native.OrderBookL2(AAPL)
+    assert book.apply(SNAPSHOT)
+    assert book.bids() == levels(("150.24", "500"), ("150.23", "1200"))
+    assert book.asks(depth=1) == levels(("150.26", "300"))
+
+    # A zero size removes the level, others set it
+    assert book.apply(update(2, bids=[("150.24", 0), ("150.22", 100)], asks=[("150.25", 50)]))
+    assert book.bids() == levels(("150.23", "1200"), ("150.22", "100"))
+    assert book.best_ask == (Decimal("150.25"), Decimal("50"))
+    assert (book.sequence, book.timestamp) == (2, T0 + timedelta(seconds=2))
+
+    # A snapshot replaces every level
+    book.apply(update(3, bids=[("149", 1)], asks=[("151", 1)], snapshot=True))
+    assert (book.bids(), book.asks()) == (levels(("149", "1")), levels(("151", "1")))
+    book.clear()
+    assert (book.bids(), book.sequence, book.best_bid) == ([], None, None)
+
+
+def test_derives_depth_and_features() -> None:
+    book = native.OrderBookL2(AAPL)
+    book.apply(SNAPSHOT)
+
+    assert book.cumulative_bids() == levels(("150.24", "500"), ("150.23", "1700"))
+    assert book.cumulative_asks(1) == levels(("150.26", "300"))
+    assert book.spread == Decimal("0.02")
+    assert book.mid_price == Decimal("150.25")
+    # (150.24 × 300 + 150.26 × 500) / 800
+    assert book.microprice == Decimal("150.2525")
+    assert book.imbalance() == Decimal("0.25")
+    assert book.imbalance(depth=2) == Decimal(1700 - 1200) / Decimal(2900)
+    with pytest.raises(ValueError, match="at least 1"):
+        book.imbalance(0)
+
+    quote = book.to_quote_tick()
+    assert isinstance(quote, native.QuoteTick)
+    assert (quote.timestamp, quote.instrument) == (SNAPSHOT.timestamp, AAPL)
+    assert (quote.bid_price, quote.bid_size, quote.ask_price, quote.ask_size) == tuple(
+        map(Decimal, ["150.24", "500", "150.26", "300"])
+    )
+
+
+def test_one_sided_or_crossed_books_have_no_top() -> None:
+    book = native.OrderBookL2(AAPL)
+    assert book.to_quote_tick() is None and book.imbalance() is None
+
+    book.apply(update(None, bids=[("100", 5)]))
+    assert (book.to_quote_tick(), book.spread, book.microprice) == (None, None, None)
+    assert book.imbalance() == Decimal(1)
+
+    book.apply(update(None, asks=[("99", 5)]))
+    assert (book.to_quote_tick(), book.mid_price) == (None, None)
+
+
+def test_sequence_gaps_desync_until_the_next_snapshot(caplog: pytest.LogCaptureFixture) -> None:
+    book = native.OrderBookL2(AAPL)
+    book.apply(SNAPSHOT)
+    with caplog.at_level(logging.WARNING, logger="simulor.data.order_book"):
+        assert book.apply(update(4, bids=[("150.20", 10)]))
+    assert "expected 2, got 4" in caplog.text
+    assert (book.synced, book.gaps, book.sequence) == (False, 1, 4)
+
+    # Repeated and older numbers are ignored
+    assert not book.apply(update(4, bids=[("150.21", 10)]))
+    assert not book.apply(update(2, bids=[("150.21", 10)]))
+    assert Decimal("150.21") not in dict(book.bids())
+
+    book.apply(update(9, bids=[("150", 1)], asks=[("151", 1)], snapshot=True))
+    assert (book.synced, book.gaps, book.sequence) == (True, 1, 9)
+
+
+def test_strict_books_raise_on_gaps() -> None:
+    book = native.OrderBookL2(AAPL, strict=True)
+    book.apply(SNAPSHOT)
+    with pytest.raises(native.SequenceGapError, match="expected sequence 2, got 3"):
+        book.apply(update(3, bids=[("150.20", 10)]))
+    assert book.synced and book.sequence == 1
+
+    with pytest.raises(ValueError, match="MSFT applied to the book of AAPL"):
+        book.apply(update(2, bids=[("1", 1)], instrument=MSFT))
+
+
+def test_copies_are_independent() -> None:
+    book = native.OrderBookL2(AAPL)
+    book.apply(SNAPSHOT)
+    copy = book.copy()
+    copy.apply(update(2, bids=[("150.24", 0)]))
+    assert book.best_bid == (Decimal("150.24"), Decimal("500"))
+    assert copy.best_bid == (Decimal("150.23"), Decimal("1200"))
+
+
+def test_updates_are_validated() -> None:
+    with pytest.raises(ValueError, match="resolution must be TICK"):
+        native.BookUpdate(T0, AAPL, Resolution.MINUTE, bids=[("1", 1)])
+    with pytest.raises(ValueError, match="Sizes cannot be negative"):
+        native.BookUpdate(T0, AAPL, Resolution.TICK, bids=[("1", -1)])
+    with pytest.raises(ValueError, match="Prices must be positive"):
+        native.BookUpdate(T0, AAPL, Resolution.TICK, asks=[("0", 1)])
+    assert SNAPSHOT == update(1, bids=SNAPSHOT.bids, asks=SNAPSHOT.asks, snapshot=True)
+    assert (SNAPSHOT.sequence, SNAPSHOT.snapshot) == (1, True)
+
+
+@pytest.mark.parametrize("kind", ["native", "python"])
+def test_events_carry_book_updates(kind: str) -> None:
+    from simulor.core.events import MarketEvent
+
+    event = native.MarketEvent(T0) if kind == "native" else MarketEvent(time=T0)
+    second = update(2, bids=[("150.24", 0)])
+    for record in (SNAPSHOT, update(None, bids=[("1", 1)], instrument=MSFT), second):
+        event.add(record)
+
+    assert event.get_book_updates(AAPL) == [SNAPSHOT, second]
+    assert set(event.book_updates) == {AAPL, MSFT}
+    assert event.instruments() == {AAPL, MSFT}
+    assert event.count == 3 and len(event.flatten()) == 3
+    filtered = event.filter_by_instrument({MSFT})
+    assert (filtered.get_book_updates(AAPL), filtered.count) == ([], 1)
+
+
+def test_market_store_replays_the_book() -> None:
+    from simulor.data.market_store import MarketStore
+
+    store = MarketStore()
+    assert store.get_order_book(AAPL) is None
+    for record in (SNAPSHOT, update(2, bids=[("150.24", 0)])):
+        event = native.MarketEvent(record.timestamp)
+        event.add(record)
+        store.update(event)
+
+    assert len(store.get_book_updates(AAPL)) == 2
+    book = store.get_order_book(AAPL)
+    assert book.best_bid == (Decimal("150.23"), Decimal("1200"))
+    # A copy: applying to it leaves the store's book unchanged
+    book.apply(update(3, bids=[("150.23", 0)]))
+    assert store.get_order_book(AAPL).best_bid == (Decimal("150.23"), Decimal("1200"))
+
+
+def book_event(*records: Any) -> Any:
+    event = native.MarketEvent(records[0].timestamp)
+    for record in records:
+        event.add(record)
+    return event
+
+
+def order(side: OrderSide, limit: str | None = None) -> OrderSpec:
+    kind = OrderType.MARKET if limit is None else OrderType.LIMIT
+    limit_price = None if limit is None else Decimal(limit)
+    return OrderSpec(instrument=AAPL, side=side, quantity=Decimal(10), order_type=kind, limit_price=limit_price)
+
+
+def test_instant_fills_use_the_book_only_when_opted_in() -> None:
+    from simulor.execution.simulation.fill_models import InstantFillModel
+
+    quote = native.QuoteTick(T0 + timedelta(seconds=1), AAPL, Resolution.TICK, *map(Decimal, "150 5 150.5 5".split()))
+    first, later = book_event(SNAPSHOT, quote), book_event(quote)
+
+    default, book = InstantFillModel(), InstantFillModel(use_book=True)
+    for model in (default, book):
+        model.on_market_event(first)
+    assert default.get_fill_price(order(OrderSide.BUY), first) == Decimal("150.5")
+    assert book.get_fill_price(order(OrderSide.BUY), first) == Decimal("150.26")
+    assert book.get_fill_price(order(OrderSide.SELL), first) == Decimal("150.24")
+    # Only when the event updated the book
+    book.on_market_event(later)
+    assert book.get_fill_price(order(OrderSide.SELL), later) == Decimal("150")
+
+
+@pytest.mark.parametrize("use_book", [False, True])
+def test_native_engine_matches_python_engine(python_fallback: Callable[[str], ModuleType], use_book: bool) -> None:
+    from simulor.execution.simulation.cost_models import CostModel
+    from simulor.execution.simulation.fill_models import InstantFillModel
+
+    python = python_fallback("simulor.execution.simulation.matching")
+    quote = native.QuoteTick(T0, AAPL, Resolution.TICK, *map(Decimal, "150.20 5 150.30 5".split()))
+    events = [
+        book_event(SNAPSHOT, quote),
+        book_event(update(2, asks=[("150.26", 0)]), quote),
+        book_event(update(3, asks=[("150.25", 40)])),
+    ]
+
+    def fills(engine: Any) -> list[tuple[str, Decimal]]:
+        for i, spec in enumerate([order(OrderSide.BUY), order(OrderSide.BUY, limit="150.25")]):
+            engine.submit(str(i), "s", spec, T0 - timedelta(seconds=1))
+        return [(fill.order_id, fill.fill.price) for event in events for fill in engine.on_market_event(event)]
+
+    expected = fills(python.MatchingEngine(InstantFillModel(use_book=use_book), CostModel()))
+    assert fills(native.MatchingEngine(InstantFillModel(use_book=use_book), CostModel())) == expected
+    if use_book:
+        # The best ask, then the limit once an ask joins at it
+        assert expected == [("0", Decimal("150.26")), ("1", Decimal("150.25"))]
+    else:
+        assert expected == [("0", Decimal("150.30"))]
+
+
+def test_native_fill_models_take_use_book() -> None:
+    assert not native.PartialFillModel().use_book
+    partial = native.PartialFillModel(Decimal("0.5"), use_book=True)
+    assert partial.use_book and repr(partial) == "PartialFillModel(participation=0.5, use_book=True)"
+    intrabar = native.IntrabarFillModel(use_book=True)
+    assert intrabar.use_book and repr(intrabar).endswith("use_book=True)")
+
+    event = book_event(SNAPSHOT)
+    for model in (partial, native.PartialFillModel(Decimal("0.5"))):
+        model.on_market_event(event)
+    # Within half the 300 shown at the best ask, or nothing to price against without the book
+    assert partial.get_fill_quantity(order(OrderSide.BUY), event) == Decimal(10)
+    assert native.PartialFillModel(Decimal("0.5")).get_fill_price(order(OrderSide.BUY), event) is None
+
+
+def test_use_book_requires_the_extension(python_fallback: Callable[[str], ModuleType]) -> None:
+    fill_models = python_fallback("simulor.execution.simulation.fill_models")
+    assert not fill_models.InstantFillModel().use_book
+    with pytest.raises(RuntimeError, match="_simulor_rust extension"):
+        fill_models.InstantFillModel(use_book=True)
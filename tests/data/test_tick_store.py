@@ -0,0 +1,167 @@
+"""Test writing and reading the native tick store."""
+
+from __future__ import annotations
+
+from collections.abc import Callable
+from datetime import UTC, datetime, timedelta
+from decimal import Decimal
+from pathlib import Path
+from types import ModuleType
+from typing import Any
+
+import pytest
+
+from simulor.types import Resolution
+from simulor.types.common import TickDirection
+from simulor.types.instruments import Instrument
+
+native = pytest.importorskip("_simulor_rust")
+
+T0 = datetime(2024, 1, 2, 14, 30, tzinfo=UTC)
+AAPL = Instrument.stock("AAPL", exchange="NASDAQ", tick_size=Decimal("0.01"))
+ESH4 = Instrument.future("ES", expiry=datetime(2024, 3, 15), exchange="CME", contract_size=Decimal("50"))
+
+BARS = """timestamp,symbol,instrument_type,open,high,low,close,volume
+2024-01-02 09:30:00,AAPL,stock,185.00,186.10,184.20,185.64,1000
+2024-01-02 09:30:00,MSFT,stock,370.00,371.00,369.50,370.50,800
+2024-01-03 09:30:00,AAPL,stock,184.00,185.00,183.00,184.25,900
+2024-01-04 09:30:00,MSFT,stock,372.00,373.00,369.50,370.50,800
+"""
+
+
+def records(provider: Any) -> list[tuple[datetime, list[Any]]]:
+    return [(event.time, sorted(event.flatten(), key=repr)) for event in provider]
+
+
+def trade(instrument: Instrument, seconds: float, price: str, direction: TickDirection | None = None) -> Any:
+    return native.TradeTick(
+        T0 + timedelta(seconds=seconds), instrument, Resolution.TICK, Decimal(price), Decimal("1"), direction
+    )
+
+
+@pytest.fixture
+def bars(tmp_path: Path) -> Any:
+    path = tmp_path / "bars.csv"
+    path.write_text(BARS)
+    return native.CSVDataProvider(path, Resolution.DAILY, timezone="America/New_York")
+
+
+def test_round_trips_a_provider(tmp_path: Path, bars: Any) -> None:
+    path = tmp_path / "bars.simtick"
+    with native.TickStoreWriter(path) as writer:
+        assert writer.write(bars) == 4
+    store = native.TickStoreDataProvider(path, timezone="America/New_York")
+
+    assert records(store) == records(bars)
+    assert [event.time for event in store] == [event.time for event in bars]
+    assert store.row_count == 4
+    # One chunk per instrument and UTC day
+    assert store.chunk_count == 4
+    assert store.start == datetime(2024, 1, 2, 14, 30, tzinfo=UTC)
+    assert store.end == datetime(2024, 1, 4, 14, 30, tzinfo=UTC)
+    assert {i.symbol for i in store.instruments} == {"AAPL", "MSFT"}
+
+
+def test_keeps_instrument_fields_and_directions(tmp_path: Path) -> None:
+    path = tmp_path / "ticks.simtick"
+    ticks = [
+        trade(AAPL, 0, "185.01", TickDirection.BUY),
+        trade(ESH4, 0, "4700.25", TickDirection.SELL),
+        trade(AAPL, 0.5, "185.015"),
+        trade(AAPL, 86_400, "186", TickDirection.NEUTRAL),
+    ]
+    with native.TickStoreWriter(path) as writer:
+        for tick in ticks:
+            writer.add(tick)
+    store = native.TickStoreDataProvider(path)
+
+    read = [record for event in store for record in event.flatten()]
+    assert sorted(read, key=repr) == sorted(ticks, key=repr)
+    assert [tick.direction for tick in read if tick.instrument == AAPL] == [
+        TickDirection.BUY,
+        None,
+        TickDirection.NEUTRAL,
+    ]
+    (future,) = (i for i in store.instruments if i.symbol == "ES")
+    assert future == ESH4
+    assert future.contract_size == Decimal("50")
+
+
+def test_filters_by_window_and_symbol(tmp_path: Path, bars: Any) -> None:
+    path = tmp_path / "bars.simtick"
+    with native.TickStoreWriter(path) as writer:
+        writer.write(bars)
+
+    window = native.TickStoreDataProvider(
+        path, timezone="America/New_York", start=datetime(2024, 1, 3, 9, 30), end=datetime(2024, 1, 4, 9, 30)
+    )
+    assert [event.time.day for event in window] == [3, 4]
+    only_msft = native.TickStoreDataProvider(path, symbols=["MSFT"])
+    assert [r.instrument.symbol for event in only_msft for r in event.flatten()] == ["MSFT", "MSFT"]
+
+
+def test_requires_chronological_order(tmp_path: Path) -> None:
+    with native.TickStoreWriter(tmp_path / "ticks.simtick") as writer:
+        writer.add(trade(AAPL, 1, "185"))
+        # Series are ordered independently
+        writer.add(trade(ESH4, 0, "4700"))
+        with pytest.raises(ValueError, match="chronological order"):
+            writer.add(trade(AAPL, 0, "185"))
+        with pytest.raises(TypeError, match="Unknown data type"):
+            writer.add(object())
+
+
+def test_store_only_appears_once_closed(tmp_path: Path) -> None:
+    path = tmp_path / "ticks.simtick"
+    writer = native.TickStoreWriter(path)
+    writer.add(trade(AAPL, 0, "185"))
+    assert not path.exists()
+    writer.close()
+    assert path.exists() and writer.closed and writer.rows_written == 1
+    with pytest.raises(ValueError, match="closed TickStoreWriter"):
+        writer.add(trade(AAPL, 1, "185"))
+
+    with pytest.raises(RuntimeError), native.TickStoreWriter(tmp_path / "aborted.simtick") as aborted:
+        aborted.add(trade(AAPL, 0, "185"))
+        raise RuntimeError
+    assert sorted(p.name for p in tmp_path.iterdir()) == ["ticks.simtick"]
+
+
+def test_rejects_corrupt_files(tmp_path: Path) -> None:
+    path = tmp_path / "bad.simtick"
+    path.write_bytes(b"not a tick store at all, just some bytes")
+    with pytest.raises(ValueError, match="not a tick store file"):
+        native.TickStoreDataProvider(path)
+    with pytest.raises(FileNotFoundError):
+        native.TickStoreDataProvider(tmp_path / "missing.simtick")
+
+
+def test_cached_provider_builds_once(tmp_path: Path, bars: Any) -> None:
+    from simulor.data.cache import CachedDataProvider
+    from simulor.data.providers import DataProvider
+
+    class Counting:
+        def __init__(self) -> None:
+            self.passes = 0
+
+        def __iter__(self) -> Any:
+            self.passes += 1
+            return iter(bars)
+
+    source = Counting()
+    cached = CachedDataProvider(source, tmp_path / "cache" / "bars.simtick", timezone="America/New_York")
+    assert records(cached) == records(bars)
+    assert records(cached) == records(bars)
+    assert source.passes == 1
+
+    cached.rebuild = True
+    list(cached)
+    assert source.passes == 2
+    assert isinstance(cached.store(), DataProvider)
+
+
+def test_cache_needs_the_extension(tmp_path: Path, python_fallback: Callable[[str], ModuleType]) -> None:
+    cache = python_fallback("simulor.data.cache")
+    assert cache.__all__ == ["CachedDataProvider"]
+    with pytest.raises(RuntimeError, match="CachedDataProvider requires the _simulor_rust extension"):
+        cache.CachedDataProvider([], tmp_path / "bars.simtick")
@@ -0,0 +1,241 @@
+"""Test the native time bar consolidators and the consolidating provider."""
+
+from __future__ import annotations
+
+from datetime import UTC, datetime, timedelta
+from decimal import Decimal
+from typing import Any
+from zoneinfo import ZoneInfo
+
+import pytest
+
+from simulor.types import Resolution
+from simulor.types.instruments import Instrument
+
+native = pytest.importorskip("_simulor_rust")
+
+NY = ZoneInfo("America/New_York")
+T0 = datetime(2024, 1, 2, 14, 30, tzinfo=UTC)
+AAPL = Instrument.stock("AAPL")
+MSFT = Instrument.stock("MSFT")
+
+
+def D(value: Any) -> Decimal:
+    return Decimal(str(value))
+
+
+def trade(time: datetime, price: Any, size: Any = 1, instrument: Instrument = AAPL) -> Any:
+    return native.TradeTick(time, instrument, Resolution.TICK, D(price), D(size))
+
+
+def quote(time: datetime, bid: Any, ask: Any) -> Any:
+    return native.QuoteTick(time, AAPL, Resolution.TICK, D(bid), D(1), D(ask), D(1))
+
+
+def minute_bar(time: datetime, close: Any, volume: Any = 10) -> Any:
+    close = D(close)
+    return native.TradeBar(time, AAPL, Resolution.MINUTE, close, close + 1, close - 1, close, D(volume))
+
+
+def ohlcv(bar: Any) -> tuple[Decimal, ...]:
+    return (bar.open, bar.high, bar.low, bar.close, bar.volume)
+
+
+def feed(consolidator: Any, records: list[Any]) -> list[Any]:
+    return [bar for record in records for bar in consolidator.update(record)]
+
+
+def test_ticks_roll_into_minute_bars() -> None:
+    consolidator = native.TimeBarConsolidator(Resolution.MINUTE)
+    ticks = [
+        trade(T0 + timedelta(seconds=5), "100", 10),
+        trade(T0 + timedelta(seconds=20), "101", 5),
+        trade(T0 + timedelta(seconds=50), "99.5", 1),
+    ]
+    assert feed(consolidator, ticks) == []
+
+    (bar,) = consolidator.update(trade(T0 + timedelta(seconds=70), "100.5", 2))
+    assert isinstance(bar, native.TradeBar)
+    assert bar.timestamp == T0
+    assert bar.resolution == Resolution.MINUTE
+    assert bar.instrument == AAPL
+    assert ohlcv(bar) == (D(100), D(101), D("99.5"), D("99.5"), D(16))
+
+    (last,) = consolidator.flush()
+    assert last.timestamp == T0 + timedelta(minutes=1)
+    assert ohlcv(last) == (D("100.5"),) * 4 + (D(2),)
+    assert consolidator.flush() == []
+
+
+def test_quote_ticks_roll_into_quote_bars() -> None:
+    consolidator = native.TimeBarConsolidator(Resolution.MINUTE)
+    feed(consolidator, [quote(T0, "10.0", "10.2"), quote(T0 + timedelta(seconds=1), "10.1", "10.3")])
+    feed(consolidator, [quote(T0 + timedelta(seconds=2), "9.9", "10.1")])
+
+    (bar,) = consolidator.advance(T0 + timedelta(minutes=1))
+    assert isinstance(bar, native.QuoteBar)
+    assert (bar.bid_open, bar.bid_high, bar.bid_low, bar.bid_close) == (D("10.0"), D("10.1"), D("9.9"), D("9.9"))
+    assert (bar.ask_open, bar.ask_high, bar.ask_low, bar.ask_close) == (D("10.2"), D("10.3"), D("10.1"), D("10.1"))
+
+
+def test_instruments_are_consolidated_separately() -> None:
+    consolidator = native.TimeBarConsolidator(Resolution.MINUTE)
+    feed(consolidator, [trade(T0, 1), trade(T0, 2, instrument=MSFT), trade(T0 + timedelta(seconds=1), 3)])
+
+    bars = consolidator.advance(T0 + timedelta(minutes=1))
+    assert {bar.instrument.symbol: bar.close for bar in bars} == {"AAPL": D(3), "MSFT": D(2)}
+
+
+def test_bars_align_to_session_hours() -> None:
+    consolidator = native.TimeBarConsolidator(
+        Resolution.HOUR, timezone="America/New_York", session_start="09:30", session_end="16:00"
+    )
+    day = datetime(2024, 1, 2, tzinfo=NY)
+    bars = feed(
+        consolidator,
+        [
+            # Ends at the open, so before the session
+            minute_bar(day.replace(hour=9, minute=29), 50),
+            minute_bar(day.replace(hour=9, minute=30), 100),
+            minute_bar(day.replace(hour=10, minute=29), 102),
+            minute_bar(day.replace(hour=10, minute=30), 104),
+            minute_bar(day.replace(hour=15, minute=59), 106),
+            minute_bar(day.replace(hour=16, minute=0), 200),
+        ],
+    )
+    bars += consolidator.flush()
+
+    assert [bar.timestamp for bar in bars] == [
+        day.replace(hour=9, minute=30),
+        day.replace(hour=10, minute=30),
+        day.replace(hour=15, minute=30),
+    ]
+    assert bars[0].timestamp.tzinfo == NY
+    assert ohlcv(bars[0]) == (D(100), D(103), D(99), D(102), D(20))
+    assert ohlcv(bars[2]) == (D(106), D(107), D(105), D(106), D(10))
+    # Bars at or above the target resolution pass through untouched
+    hourly = native.TradeBar(day.replace(hour=11), AAPL, Resolution.HOUR, D(1), D(1), D(1), D(1), D(1))
+    assert consolidator.update(hourly) == []
+
+
+def test_daily_bars_cover_the_session() -> None:
+    consolidator = native.TimeBarConsolidator(
+        Resolution.DAILY, timezone="America/New_York", session_start="09:30", session_end="16:00"
+    )
+    day = datetime(2024, 1, 2, tzinfo=NY)
+    feed(consolidator, [minute_bar(day.replace(hour=9, minute=30), 100), minute_bar(day.replace(hour=15), 110)])
+
+    assert consolidator.advance(day.replace(hour=15, minute=59)) == []
+    (bar,) = consolidator.advance(day.replace(hour=16))
+    assert bar.timestamp == day.replace(hour=9, minute=30)
+    assert ohlcv(bar) == (D(100), D(111), D(99), D(110), D(20))
+
+
+@pytest.mark.parametrize("fill_forward", [False, True])
+def test_empty_periods_skip_or_fill_forward(fill_forward: bool) -> None:
+    consolidator = native.TimeBarConsolidator(Resolution.MINUTE, fill_forward=fill_forward)
+    consolidator.update(trade(T0 + timedelta(seconds=10), "100", 3))
+
+    bars = consolidator.update(trade(T0 + timedelta(minutes=3, seconds=5), "101"))
+    expected = [T0, T0 + timedelta(minutes=1), T0 + timedelta(minutes=2)] if fill_forward else [T0]
+    assert [bar.timestamp for bar in bars] == expected
+    for flat in bars[1:]:
+        assert ohlcv(flat) == (D(100),) * 4 + (D(0),)
+
+
+def test_fill_forward_stops_at_the_session_close() -> None:
+    consolidator = native.TimeBarConsolidator(
+        Resolution.HOUR, timezone="America/New_York", session_start="09:30", session_end="16:00", fill_forward=True
+    )
+    day = datetime(2024, 1, 2, tzinfo=NY)
+    consolidator.update(trade(day.replace(hour=14), 100))
+
+    bars = consolidator.update(trade(day.replace(day=3, hour=9, minute=45), 101))
+    assert [bar.timestamp.hour for bar in bars] == [13, 14, 15]
+    assert bars[-1].timestamp == day.replace(hour=15, minute=30)
+
+
+def test_calendar_sessions_skip_holidays_and_honour_early_closes() -> None:
+    consolidator = native.TimeBarConsolidator(Resolution.HOUR, calendar="NYSE")
+    assert consolidator.timezone_info == NY
+    assert consolidator.calendar.name == "NYSE"
+
+    early_close = datetime(2024, 7, 3, tzinfo=NY)
+    feed(consolidator, [trade(early_close.replace(hour=12, minute=45), 100)])
+    # The last bar is cut short by the 13:00 early close
+    (bar,) = consolidator.advance(early_close.replace(hour=13))
+    assert bar.timestamp == early_close.replace(hour=12, minute=30)
+    # Nothing trades after the close, nor on Independence Day
+    assert consolidator.update(trade(early_close.replace(hour=13, minute=30), 101)) == []
+    assert consolidator.update(trade(early_close.replace(day=4, hour=11), 102)) == []
+    assert consolidator.flush() == []
+
+
+def test_reset_drops_open_bars() -> None:
+    consolidator = native.TimeBarConsolidator(Resolution.MINUTE)
+    consolidator.update(trade(T0, 100))
+    consolidator.reset()
+    assert consolidator.flush() == []
+
+
+def test_rejects_invalid_settings() -> None:
+    with pytest.raises(ValueError, match="TICK"):
+        native.TimeBarConsolidator(Resolution.TICK)
+    with pytest.raises(ValueError, match="given together"):
+        native.TimeBarConsolidator(Resolution.HOUR, session_start="09:30")
+    with pytest.raises(ValueError, match="must differ"):
+        native.TimeBarConsolidator(Resolution.HOUR, session_start="09:30", session_end="09:30")
+    with pytest.raises(ValueError, match="cannot be combined"):
+        native.TimeBarConsolidator(Resolution.HOUR, session_start="09:30", session_end="16:00", calendar="NYSE")
+
+
+def events(*groups: tuple[datetime, list[Any]]) -> list[Any]:
+    out = []
+    for time, records in groups:
+        event = native.MarketEvent(time)
+        for record in records:
+            event.add(record)
+        out.append(event)
+    return out
+
+
+def test_provider_inserts_bars_as_they_close() -> None:
+    source = events(
+        (T0, [trade(T0, 100)]),
+        (T0 + timedelta(seconds=30), [trade(T0 + timedelta(seconds=30), 101)]),
+        (T0 + timedelta(minutes=1), [trade(T0 + timedelta(minutes=1), 102)]),
+        (T0 + timedelta(minutes=3, seconds=30), [trade(T0 + timedelta(minutes=3, seconds=30), 103)]),
+    )
+    provider = native.ConsolidatingDataProvider(source, native.TimeBarConsolidator(Resolution.MINUTE))
+    stream = [(event.time, sorted(type(r).__name__ for r in event.flatten())) for event in provider]
+
+    assert stream == [
+        (T0, ["TradeTick"]),
+        (T0 + timedelta(seconds=30), ["TradeTick"]),
+        # The bar ending exactly at an event joins it
+        (T0 + timedelta(minutes=1), ["TradeBar", "TradeTick"]),
+        # A bar closing between events gets its own event at its end
+        (T0 + timedelta(minutes=2), ["TradeBar"]),
+        (T0 + timedelta(minutes=3, seconds=30), ["TradeTick"]),
+        # Bars still open at the end are flushed
+        (T0 + timedelta(minutes=4), ["TradeBar"]),
+    ]
+    # Every pass starts from fresh consolidators
+    assert [(event.time, event.count) for event in provider] == [(time, len(kinds)) for time, kinds in stream]
+
+
+def test_provider_can_drop_consumed_records() -> None:
+    source = events((T0, [trade(T0, 100), quote(T0, 99, 101)]), (T0 + timedelta(minutes=1), []))
+    provider = native.ConsolidatingDataProvider(
+        source, [native.TimeBarConsolidator(Resolution.MINUTE)], include_source=False
+    )
+    stream = [(event.time, sorted(type(r).__name__ for r in event.flatten())) for event in provider]
+    # Quotes are consumed too, into quote bars; the empty source event stays empty
+    assert stream == [(T0 + timedelta(minutes=1), ["QuoteBar", "TradeBar"])]
+
+
+def test_provider_is_a_data_provider() -> None:
+    from simulor.data.consolidators import ConsolidatingDataProvider
+    from simulor.data.providers import DataProvider
+
+    assert issubclass(ConsolidatingDataProvider, DataProvider)
@@ -0,0 +1,162 @@
+"""Test the native EventBus against the Python implementation."""
+
+from __future__ import annotations
+
+import logging
+import threading
+import time
+from collections.abc import Callable
+from datetime import datetime, timezone
+from types import ModuleType
+from typing import Any
+
+import pytest
+
+from simulor.core import events as core_events
+
+TS = datetime(2024, 1, 2, tzinfo=timezone.utc)
+
+
+@pytest.fixture(params=["python", "native"])
+def events(request: Any, python_fallback: Callable[[str], ModuleType]) -> ModuleType:
+    """The events module whose `EventBus` is under test, with matching event classes."""
+    if request.param == "python":
+        return python_fallback("simulor.core.events")
+    pytest.importorskip("_simulor_rust")
+    return core_events
+
+
+def data(events: ModuleType, reason: str = "data") -> Any:
+    return events.EndOfStreamEvent(time=TS, reason=reason)
+
+
+def system(events: ModuleType, name: str = "fill") -> Any:
+    return events.SystemEvent(type=events.EventType.FILL, time=TS, payload={"name": name})
+
+
+def test_system_events_come_first(events: ModuleType) -> None:
+    bus = events.EventBus()
+    first, second, urgent = data(events, "1"), data(events, "2"), system(events)
+    for event in (first, second, urgent):
+        assert bus.publish(event)
+
+    assert [bus.next(timeout=0) for _ in range(4)] == [urgent, first, second, None]
+    assert bus.consumed_event_count == 3
+
+
+def test_drop_discards_and_warns_when_full(events: ModuleType, caplog: Any) -> None:
+    bus = events.EventBus(data_qsize=1)
+    assert bus.publish(data(events, "kept"), backpressure="drop")
+    with caplog.at_level(logging.WARNING):
+        assert not bus.publish(data(events, "dropped"), backpressure="drop")
+
+    assert any("Event queue full, dropping event" in message for message in caplog.messages)
+    assert bus.publish(system(events), backpressure="drop")
+    assert bus.next(timeout=0).type == events.EventType.FILL
+    assert bus.next(timeout=0).reason == "kept"
+    assert bus.next(timeout=0) is None
+
+
+def test_block_waits_for_a_consumer(events: ModuleType) -> None:
+    bus = events.EventBus(data_qsize=1)
+    bus.publish(data(events, "1"))
+    published = threading.Event()
+
+    def producer() -> None:
+        bus.publish(data(events, "2"))
+        published.set()
+
+    thread = threading.Thread(target=producer)
+    thread.start()
+    assert not published.wait(0.05)
+    assert bus.next(timeout=1).reason == "1"
+    assert published.wait(1)
+    thread.join()
+    assert bus.next(timeout=1).reason == "2"
+
+
+@pytest.mark.parametrize("size", [0, -1])
+def test_non_positive_size_is_unbounded(events: ModuleType, size: int) -> None:
+    bus = events.EventBus(data_qsize=size)
+    for i in range(1000):
+        assert bus.publish(data(events, str(i)))
+    assert bus.next(timeout=0).reason == "0"
+
+
+def test_subscribers_see_their_event_type(events: ModuleType, caplog: Any) -> None:
+    bus = events.EventBus()
+    seen: list[Any] = []
+
+    def failing(event: Any) -> None:
+        raise RuntimeError("handler failed")
+
+    bus.subscribe(events.EventType.FILL, failing)
+    bus.subscribe(events.EventType.FILL, seen.append)
+    bus.subscribe(events.EventType.END_OF_STREAM, seen.append)
+    bus.publish(system(events))
+    bus.publish(data(events))
+    with caplog.at_level(logging.ERROR):
+        bus.next(timeout=0)
+        bus.next(timeout=0)
+
+    assert [event.type for event in seen] == [events.EventType.FILL, events.EventType.END_OF_STREAM]
+    assert any("Error in event handler" in message for message in caplog.messages)
+
+    bus.unsubscribe(events.EventType.FILL, seen.append)
+    bus.unsubscribe(events.EventType.FILL, seen.append)
+    bus.publish(system(events))
+    bus.next(timeout=0)
+    assert len(seen) == 2
+
+
+def test_rejects_unknown_events(events: ModuleType) -> None:
+    bus = events.EventBus()
+    assert not bus.publish(object())
+    assert bus.next(timeout=0) is None
+
+
+def test_task_done_is_checked(events: ModuleType) -> None:
+    bus = events.EventBus()
+    bus.publish(data(events))
+    bus.next(timeout=0)
+    bus.task_done("data")
+    with pytest.raises(ValueError):
+        bus.task_done("data")
+
+
+def test_native_counts_per_event_type() -> None:
+    pytest.importorskip("_simulor_rust")
+    bus = core_events.EventBus(data_qsize=1)
+    bus.publish(data(core_events))
+    bus.publish(data(core_events), backpressure="drop")
+    bus.publish(system(core_events))
+    bus.next(timeout=0)
+
+    assert bus.stats() == {
+        "END_OF_STREAM": {"published": 1, "consumed": 0, "dropped": 1},
+        "FILL": {"published": 1, "consumed": 1, "dropped": 0},
+    }
+    assert (bus.data_qsize, bus.system_qsize) == (1, 0)
+
+
+def test_native_waits_without_holding_the_gil() -> None:
+    pytest.importorskip("_simulor_rust")
+    bus = core_events.EventBus()
+    ticks = 0
+    stop = threading.Event()
+
+    def spin() -> None:
+        nonlocal ticks
+        while not stop.is_set():
+            ticks += 1
+            time.sleep(0)
+
+    thread = threading.Thread(target=spin)
+    thread.start()
+    try:
+        start = ticks
+        assert bus.next(timeout=0.2) is None
+        assert ticks - start > 10
+    finally:
+        stop.set()
+        thread.join()
@@ -0,0 +1,162 @@
+"""Test the native MarketEvent against the Python implementation."""
+
+from __future__ import annotations
+
+from collections.abc import Callable
+from datetime import datetime, timezone
+from decimal import Decimal
+from types import ModuleType
+from typing import Any
+
+import pytest
+
+from simulor.core.events import DataEvent, Event, EventType
+from simulor.types import Instrument, QuoteBar, QuoteTick, Resolution, TradeBar, TradeTick
+
+native = pytest.importorskip("_simulor_rust")
+
+TS = datetime(2024, 1, 2, 14, 30, tzinfo=timezone.utc)
+AAPL = Instrument.stock("AAPL")
+MSFT = Instrument.stock("MSFT")
+SPY = Instrument.stock("SPY")
+
+
+def trade(instrument: Instrument, price: str) -> TradeTick:
+    return TradeTick(TS, instrument, Resolution.TICK, Decimal(price), Decimal("10"))
+
+
+def quote(instrument: Instrument, bid: str) -> QuoteTick:
+    return QuoteTick(TS, instrument, Resolution.TICK, Decimal(bid), Decimal("1"), Decimal(bid) + 1, Decimal("1"))
+
+
+def bar(instrument: Instrument, resolution: Resolution, close: str) -> TradeBar:
+    price = Decimal(close)
+    return TradeBar(TS, instrument, resolution, price, price, price, price, Decimal("100"))
+
+
+def quote_bar(instrument: Instrument, resolution: Resolution) -> QuoteBar:
+    one, two = Decimal("1"), Decimal("2")
+    return QuoteBar(TS, instrument, resolution, one, one, one, one, two, two, two, two)
+
+
+RECORDS = [
+    trade(AAPL, "10"),
+    trade(MSFT, "20"),
+    trade(AAPL, "11"),
+    quote(AAPL, "10"),
+    quote(SPY, "400"),
+    bar(MSFT, Resolution.DAILY, "20"),
+    bar(MSFT, Resolution.MINUTE, "21"),
+    bar(MSFT, Resolution.DAILY, "22"),
+    quote_bar(SPY, Resolution.HOUR),
+    quote_bar(SPY, Resolution.MINUTE),
+]
+
+
+def snapshot(event: Any) -> dict[str, Any]:
+    """Everything observable through the shared MarketEvent interface."""
+    return {
+        "type": event.type.name,
+        "time": event.time,
+        "count": event.count,
+        "instruments": event.instruments(),
+        "trade_ticks": event.trade_ticks,
+        "quote_ticks": event.quote_ticks,
+        "trade_bars": event.trade_bars,
+        "quote_bars": event.quote_bars,
+        "flatten": sorted(map(repr, event.flatten())),
+        "per_instrument": {
+            instrument: (
+                event.get_last_trade_tick(instrument),
+                event.get_last_quote_tick(instrument),
+                event.get_min_res_trade_bar(instrument),
+                event.get_min_res_quote_bar(instrument),
+            )
+            for instrument in (AAPL, MSFT, SPY, Instrument.stock("NONE"))
+        },
+    }
+
+
+@pytest.fixture
+def events(python_fallback: Callable[[str], ModuleType]) -> tuple[Any, Any]:
+    fallback = python_fallback("simulor.core.events")
+    python_event = fallback.MarketEvent(time=TS)
+    native_event = native.MarketEvent(TS)
+    for record in RECORDS:
+        python_event.add(record)
+        native_event.add(record)
+    return python_event, native_event
+
+
+def test_matches_the_python_event(events: tuple[Any, Any]) -> None:
+    python_event, native_event = events
+    assert snapshot(native_event) == snapshot(python_event)
+
+
+@pytest.mark.parametrize("selection", [{AAPL}, {MSFT, SPY}, {Instrument.stock("NONE")}, set()])
+def test_filtering_matches_the_python_event(events: tuple[Any, Any], selection: set[Instrument]) -> None:
+    python_event, native_event = events
+    assert snapshot(native_event.filter_by_instrument(selection)) == snapshot(
+        python_event.filter_by_instrument(selection)
+    )
+
+
+def test_lookups_keep_arrival_order_and_finest_bar(events: tuple[Any, Any]) -> None:
+    _, event = events
+    assert event.get_last_trade_tick(AAPL).price == Decimal("11")
+    assert [t.price for t in event.trade_ticks[AAPL]] == [Decimal("10"), Decimal("11")]
+    assert event.get_min_res_trade_bar(MSFT).resolution == Resolution.MINUTE
+    assert event.trade_bars[MSFT][Resolution.DAILY].close == Decimal("22")
+    assert event.count == len(RECORDS)
+
+
+def test_filtered_event_does_not_see_later_additions_to_the_source(events: tuple[Any, Any]) -> None:
+    _, event = events
+    filtered = event.filter_by_instrument({AAPL})
+    event.add(trade(AAPL, "12"))
+    filtered.add(trade(AAPL, "13"))
+
+    assert [t.price for t in filtered.trade_ticks[AAPL]] == [Decimal("10"), Decimal("11"), Decimal("13")]
+    assert event.get_last_trade_tick(AAPL).price == Decimal("12")
+    assert MSFT not in filtered.instruments()
+
+
+def test_getters_return_copies(events: tuple[Any, Any]) -> None:
+    _, event = events
+    event.trade_ticks[AAPL].clear()
+    event.trade_ticks.clear()
+    event.get_book_updates(AAPL).append(None)
+
+    assert len(event.trade_ticks[AAPL]) == 2
+    assert event.get_book_updates(AAPL) == []
+
+
+def test_rejects_unknown_records() -> None:
+    with pytest.raises(TypeError):
+        native.MarketEvent(TS).add(object())
+
+
+def test_is_a_data_event() -> None:
+    event = native.MarketEvent(TS)
+    assert isinstance(event, DataEvent)
+    assert isinstance(event, Event)
+    assert event.type is EventType.MARKET
+
+
+def test_simulor_builds_native_events() -> None:
+    from simulor.core.events import MarketEvent
+
+    # Events built by providers, feeds and the engine's strategy pipeline go through this name
+    assert MarketEvent is native.MarketEvent
+    event = MarketEvent(time=TS)
+    event.add(trade(AAPL, "100"))
+    assert isinstance(event.filter_by_instrument({AAPL}), native.MarketEvent)
+
+
+def test_event_bus_accepts_native_events() -> None:
+    from simulor.core.events import EventBus
+
+    bus = EventBus()
+    event = native.MarketEvent(TS)
+    assert bus.publish(event)
+    assert bus.next(timeout=0) is event
@@ -0,0 +1,60 @@
+"""Market data shared by the execution tests, built from the native records."""
+
+from __future__ import annotations
+
+from datetime import UTC, datetime, timedelta
+from decimal import Decimal
+from typing import Any
+
+import pytest
+
+from simulor.types import Instrument, Resolution
+
+native = pytest.importorskip("_simulor_rust")
+
+T0 = datetime(2024, 1, 2, 14, 30, tzinfo=UTC)
+AAPL = Instrument.stock("AAPL")
+
+
+def D(value: Any) -> Decimal:
+    return Decimal(str(value))
+
+
+def at(minute: int) -> datetime:
+    return T0 + timedelta(minutes=minute)
+
+
+def trade_bar(minute: int, close: Any, volume: Any = 1000, instrument: Instrument = AAPL) -> Any:
+    close = D(close)
+    return native.TradeBar(at(minute), instrument, Resolution.MINUTE, close, close, close, close, D(volume))
+
+
+def quote_bar(minute: int, bid: Any, ask: Any) -> Any:
+    bid, ask = D(bid), D(ask)
+    return native.QuoteBar(at(minute), AAPL, Resolution.MINUTE, bid, bid, bid, bid, ask, ask, ask, ask)
+
+
+def trade_tick(minute: int, price: Any, size: Any = 10) -> Any:
+    return native.TradeTick(at(minute), AAPL, Resolution.TICK, D(price), D(size))
+
+
+def quote_tick(minute: int, bid: Any, ask: Any, bid_size: Any = 10, ask_size: Any = 10) -> Any:
+    return native.QuoteTick(at(minute), AAPL, Resolution.TICK, D(bid), D(bid_size), D(ask), D(ask_size))
+
+
+def event(minute: int, *records: Any) -> Any:
+    market_event = native.MarketEvent(at(minute))
+    for record in records:
+        market_event.add(record)
+    return market_event
+
+
+def bar_event(minute: int, close: Any) -> Any:
+    return event(minute, trade_bar(minute, close))
+
+
+def engine(fill_model: Any = None) -> Any:
+    from simulor.execution.simulation.cost_models import CostModel
+    from simulor.execution.simulation.fill_models import InstantFillModel
+
+    return native.MatchingEngine(InstantFillModel() if fill_model is None else fill_model, CostModel())
@@ -0,0 +1,90 @@
+"""Test the matching of market-if-touched and limit-if-touched orders."""
+
+from __future__ import annotations
+
+from collections.abc import Callable
+from decimal import Decimal
+from types import ModuleType
+from typing import Any
+
+import pytest
+from helpers import AAPL, T0, D, bar_event, engine
+
+from simulor.types import OrderSide, OrderSpec, OrderType
+
+native = pytest.importorskip("_simulor_rust")
+
+
+def touched(side: OrderSide, kind: OrderType, stop: Any, limit: Any = None) -> OrderSpec:
+    limit_price = None if limit is None else D(limit)
+    return OrderSpec(
+        instrument=AAPL, side=side, quantity=D(10), order_type=kind, stop_price=D(stop), limit_price=limit_price
+    )
+
+
+def matched(engine: Any, minute: int, close: Any) -> list[tuple[str, str, Decimal]]:
+    return [
+        (type(m).__name__, m.order_id, m.market_price if hasattr(m, "market_price") else m.fill.price)
+        for m in engine.on_market_event(bar_event(minute, close))
+    ]
+
+
+def test_market_if_touched_orders_trigger_from_the_far_side() -> None:
+    matching = engine()
+    matching.submit("buy", "s", touched(OrderSide.BUY, OrderType.MARKET_IF_TOUCHED, 95), T0)
+    matching.submit("sell", "s", touched(OrderSide.SELL, OrderType.MARKET_IF_TOUCHED, 105), T0)
+
+    # Unlike stops, a buy waits for the market to fall to its level and a sell for it to rise
+    assert matched(matching, 0, 100) == []
+    assert matched(matching, 1, 94) == [("OrderTrigger", "buy", D(94)), ("OrderFill", "buy", D(94))]
+    assert matched(matching, 2, 106) == [("OrderTrigger", "sell", D(106)), ("OrderFill", "sell", D(106))]
+    assert len(matching) == 0
+
+
+def test_limit_if_touched_orders_rest_as_limit_orders() -> None:
+    matching = engine()
+    matching.submit("sell", "s", touched(OrderSide.SELL, OrderType.LIMIT_IF_TOUCHED, 105, limit=107), T0)
+
+    trigger, *fills = matching.on_market_event(bar_event(0, 105))
+    assert (trigger.order_id, trigger.trigger_price, trigger.market_price, fills) == ("sell", D(105), D(105), [])
+    # Triggered once, then waits for its limit, even after the market falls back
+    assert matched(matching, 1, 100) == []
+    assert matched(matching, 2, 108) == [("OrderFill", "sell", D(108))]
+
+
+def test_broker_publishes_the_trigger() -> None:
+    from simulor.core.events import EventType
+    from simulor.execution.simulation.broker import SimulatedBroker
+    from simulor.portfolio.manager import Portfolio
+
+    class Bus:
+        def __init__(self) -> None:
+            self.events: list[Any] = []
+
+        def publish(self, event: Any) -> None:
+            self.events.append(event)
+
+    broker, bus = SimulatedBroker(), Bus()
+    portfolio = Portfolio(starting_cash=D(10_000))
+    broker.initialize(bus, Portfolio(starting_cash=D(0)), {"s": portfolio})  # type: ignore[arg-type]
+    broker.connect()
+    broker.submit_order("s", touched(OrderSide.BUY, OrderType.LIMIT_IF_TOUCHED, 95, limit=96))
+    broker.on_market_event(bar_event(0, 95))
+    # Triggered, and within its limit at once
+    assert [published.type for published in bus.events] == [EventType.ORDER_TRIGGERED, EventType.FILL]
+    trigger = bus.events[0].payload["trigger"]
+    assert (bus.events[0].payload["strategy_name"], trigger.order.order_type) == ("s", OrderType.LIMIT_IF_TOUCHED)
+    assert portfolio.positions[AAPL].quantity == D(10)
+
+
+@pytest.mark.parametrize("kind", [OrderType.MARKET_IF_TOUCHED, OrderType.LIMIT_IF_TOUCHED])
+def test_if_touched_orders_need_native_matching(python_fallback: Callable[[str], ModuleType], kind: OrderType) -> None:
+    from simulor.execution.simulation.cost_models import CostModel
+    from simulor.execution.simulation.fill_models import InstantFillModel
+
+    spec = touched(OrderSide.BUY, kind, 95, limit=96)
+    python = python_fallback("simulor.execution.simulation.matching")
+    with pytest.raises(NotImplementedError, match=f"{kind.name} orders require the _simulor_rust extension"):
+        python.MatchingEngine(InstantFillModel(), CostModel()).submit("buy", "s", spec, T0)
+    with pytest.raises(NotImplementedError, match="matched by the native MatchingEngine"):
+        InstantFillModel().get_fill_price(spec, bar_event(0, 95))
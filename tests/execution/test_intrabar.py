@@ -0,0 +1,100 @@
+"""Test intrabar fills: the inferred price path through bars, and stops and targets reached in one bar."""
+
+from __future__ import annotations
+
+from decimal import Decimal
+from typing import Any
+
+import pytest
+from helpers import AAPL, T0, D, at, engine, event
+
+from simulor.types import OrderSide, OrderSpec, OrderType, Resolution
+
+native = pytest.importorskip("_simulor_rust")
+
+
+def bar(minute: int, open_: Any, high: Any, low: Any, close: Any) -> Any:
+    prices = (D(open_), D(high), D(low), D(close))
+    return event(minute, native.TradeBar(at(minute), AAPL, Resolution.MINUTE, *prices, D(1000)))
+
+
+def order(side: OrderSide, kind: OrderType, quantity: Any = 10, **prices: Any) -> OrderSpec:
+    prices = {name: D(price) for name, price in prices.items()}
+    return OrderSpec(instrument=AAPL, side=side, quantity=D(quantity), order_type=kind, **prices)
+
+
+def fills(matching: Any, market_event: Any, accept: Any = None) -> list[tuple[str, Decimal]]:
+    return [(m.order_id, m.fill.price) for m in matching.on_market_event(market_event, accept) if hasattr(m, "fill")]
+
+
+def test_limits_and_stops_fill_at_their_level_or_the_gap() -> None:
+    matching = engine(native.IntrabarFillModel())
+    matching.submit("limit", "a", order(OrderSide.BUY, OrderType.LIMIT, limit_price=97), T0)
+    matching.submit("stop", "b", order(OrderSide.BUY, OrderType.STOP, stop_price=103), T0)
+    matching.submit("gap", "c", order(OrderSide.SELL, OrderType.LIMIT, limit_price=90), T0)
+    matching.submit("out", "d", order(OrderSide.BUY, OrderType.LIMIT, limit_price=95), T0)
+
+    # The close alone, 101, would fill none of these; the sell limit is gapped through at the open
+    assert fills(matching, bar(0, 100, 104, 96, 101)) == [("gap", D(100)), ("limit", D(97)), ("stop", D(103))]
+    assert list(matching.open_orders) == ["out"]
+    # A buy limit below a gap down fills at the open
+    assert fills(matching, bar(1, 94, 95, 93, 94)) == [("out", D(94))]
+
+
+@pytest.mark.parametrize(
+    ("path", "first"),
+    [
+        # A bar closing up is assumed to make its low first
+        (None, "target"),
+        ("OPEN_LOW_HIGH_CLOSE", "target"),
+        ("OPEN_HIGH_LOW_CLOSE", "stop"),
+    ],
+)
+def test_a_stop_and_target_in_one_bar_leave_the_first(path: str | None, first: str) -> None:
+    model = native.IntrabarFillModel() if path is None else native.IntrabarFillModel(getattr(native.IntrabarPath, path))
+    matching = engine(model)
+    # Short 10: a buy stop above and a buy target below
+    matching.submit("stop", "s", order(OrderSide.BUY, OrderType.STOP, stop_price=103), T0)
+    matching.submit("target", "s", order(OrderSide.BUY, OrderType.LIMIT, limit_price=97), T0)
+    # Another strategy's orders are matched apart
+    matching.submit("other", "t", order(OrderSide.BUY, OrderType.LIMIT, limit_price=98), T0)
+
+    price = {"stop": D(103), "target": D(97)}[first]
+    assert sorted(fills(matching, bar(0, 100, 104, 96, 101))) == sorted([(first, price), ("other", D(98))])
+    # The other one of the pair stays in the book, for the strategy to cancel
+    (loser,) = {"stop", "target"} - {first}
+    assert list(matching.open_orders) == [loser]
+    matching.cancel(loser)
+    assert fills(matching, bar(1, 100, 110, 90, 100)) == []
+
+
+def test_the_loser_rests_as_it_was_before_the_bar() -> None:
+    matching = engine(native.IntrabarFillModel())
+    matching.submit("stop", "s", order(OrderSide.SELL, OrderType.TRAILING_STOP, trailing_amount=2), T0)
+    matching.submit("target", "s", order(OrderSide.SELL, OrderType.LIMIT, limit_price=103), T0)
+    matching.on_market_event(bar(0, 100, 100, 100, 100))
+    assert matching.trailing_stop("stop").stop_price == D(98)
+
+    # High first: the target fills at 103 before the stop, ratcheted to 102, fires; but its fill is declined
+    assert matching.on_market_event(bar(1, 100, 104, 97, 99), lambda order_fill: False) == []
+    assert list(matching.open_orders) == ["stop", "target"]
+    # So the stop rests as it was before the bar
+    tracker = matching.trailing_stop("stop")
+    assert (tracker.stop_price, tracker.triggered) == (D(98), False)
+
+    # Low first, then up through the target and back down to the ratcheted stop: the target fills,
+    # and the stop is left as it was before the bar
+    assert fills(matching, bar(2, 101, 104, 100, 102)) == [("target", D(103))]
+    assert list(matching.open_orders) == ["stop"]
+    tracker = matching.trailing_stop("stop")
+    assert (tracker.stop_price, tracker.triggered) == (D(98), False)
+
+
+def test_fill_model_prices_one_event() -> None:
+    model = native.IntrabarFillModel(native.IntrabarPath.OPEN_HIGH_LOW_CLOSE)
+    assert repr(model) == "IntrabarFillModel(path=IntrabarPath.OPEN_HIGH_LOW_CLOSE)"
+    assert model.path == native.IntrabarPath.OPEN_HIGH_LOW_CLOSE and not model.use_book
+    market_event = bar(0, 100, 104, 96, 101)
+    assert model.get_fill_price(order(OrderSide.SELL, OrderType.STOP, stop_price=98), market_event) == D(98)
+    assert model.get_fill_price(order(OrderSide.SELL, OrderType.LIMIT, limit_price=105), market_event) is None
+    assert model.get_fill_price(order(OrderSide.BUY, OrderType.MARKET), market_event) == D(100)
@@ -0,0 +1,178 @@
+"""Test the native matching engine against the Python one, and how the simulated broker applies its fills."""
+
+from __future__ import annotations
+
+import logging
+from collections.abc import Callable
+from decimal import Decimal
+from types import ModuleType
+from typing import Any
+
+import pytest
+from helpers import AAPL, D, at, event, quote_bar, quote_tick, trade_bar, trade_tick
+
+from simulor.types import Instrument, OrderSide, OrderSpec, OrderType
+
+native = pytest.importorskip("_simulor_rust")
+
+MSFT = Instrument.stock("MSFT")
+
+
+def order(
+    side: OrderSide,
+    kind: OrderType = OrderType.MARKET,
+    quantity: Any = 10,
+    instrument: Instrument = AAPL,
+    **prices: Any,
+) -> OrderSpec:
+    prices = {name: D(price) for name, price in prices.items()}
+    return OrderSpec(instrument=instrument, side=side, quantity=D(quantity), order_type=kind, **prices)
+
+
+@pytest.fixture(params=["native", "python"])
+def engine(request: pytest.FixtureRequest, python_fallback: Callable[[str], ModuleType]) -> Any:
+    from simulor.execution.simulation.cost_models import CostModel
+    from simulor.execution.simulation.fill_models import InstantFillModel
+
+    module = native if request.param == "native" else python_fallback("simulor.execution.simulation.matching")
+    return module.MatchingEngine(InstantFillModel(), CostModel())
+
+
+def prices(matched: list[Any]) -> list[tuple[str, Decimal, Decimal]]:
+    return [(fill.order_id, fill.fill.quantity, fill.fill.price) for fill in matched]
+
+
+@pytest.mark.parametrize(
+    ("records", "buy", "sell"),
+    [
+        ([trade_bar(0, 100)], 100, 100),
+        ([trade_bar(0, 100), trade_tick(0, 101)], 101, 101),
+        ([trade_bar(0, 100), trade_tick(0, 101), quote_bar(0, 99, 102)], 102, 99),
+        ([trade_bar(0, 100), trade_tick(0, 101), quote_bar(0, 99, 102), quote_tick(0, 98, 103)], 103, 98),
+    ],
+)
+def test_prices_by_the_waterfall(engine: Any, records: list[Any], buy: Any, sell: Any) -> None:
+    engine.submit("buy", "s", order(OrderSide.BUY), at(-1))
+    engine.submit("sell", "s", order(OrderSide.SELL), at(-1))
+    # Quote ticks, then quote bars, trade ticks and trade bars; buys at the ask and sells at the bid
+    assert prices(engine.on_market_event(event(0, *records))) == [("buy", D(10), D(buy)), ("sell", D(-10), D(sell))]
+    assert len(engine) == 0
+
+
+def test_orders_arrive_after_their_latency_and_rest_until_reached(engine: Any) -> None:
+    engine.submit("limit", "s", order(OrderSide.BUY, OrderType.LIMIT, limit_price=98), at(0))
+    engine.submit("stop", "s", order(OrderSide.SELL, OrderType.STOP, stop_price=97), at(0))
+    engine.submit("stop_limit", "s", order(OrderSide.BUY, OrderType.STOP_LIMIT, stop_price=101, limit_price=102), at(0))
+    engine.submit("late", "t", order(OrderSide.BUY), at(2))
+
+    assert engine.on_market_event(event(-1, trade_bar(-1, 95))) == []
+    assert (engine.in_flight_count, len(engine)) == (4, 0)
+    assert engine.on_market_event(event(0, trade_bar(0, 100))) == []
+    assert list(engine.open_orders) == ["limit", "stop", "stop_limit"] and engine.owner("limit") == "s"
+    assert prices(engine.on_market_event(event(1, trade_bar(1, 101.5)))) == [("stop_limit", D(10), D("101.5"))]
+    assert prices(engine.on_market_event(event(2, trade_bar(2, 97)))) == [
+        ("limit", D(10), D(97)),
+        ("stop", D(-10), D(97)),
+        ("late", D(10), D(97)),
+    ]
+    # Orders of other instruments are left alone
+    engine.submit("msft", "s", order(OrderSide.BUY, instrument=MSFT), at(2))
+    assert engine.on_market_event(event(3, trade_bar(3, 97))) == [] and engine.owner("msft") == "s"
+    assert engine.cancel("msft").side == OrderSide.BUY and engine.cancel("msft") is None
+
+
+def test_native_engine_matches_python_engine(python_fallback: Callable[[str], ModuleType]) -> None:
+    from simulor.execution.simulation.cost_models import CostModel, PercentageFee, PerShareCommission
+    from simulor.execution.simulation.fill_models import InstantFillModel
+
+    python = python_fallback("simulor.execution.simulation.matching")
+    specs = [
+        order(OrderSide.BUY, OrderType.LIMIT, limit_price=price) for price in range(90, 110, 2)
+    ] + [order(OrderSide.SELL, OrderType.STOP, stop_price=price) for price in range(90, 110, 3)]
+    events = [event(minute, trade_bar(minute, close)) for minute, close in enumerate([105, 99, 101, 93, 108, 89])]
+
+    def fills(engine: Any) -> list[tuple[str, Decimal, Decimal, Decimal]]:
+        for i, spec in enumerate(specs):
+            engine.submit(str(i), "s", spec, at(0))
+        matched = [fill for market_event in events for fill in engine.on_market_event(market_event)]
+        return [(fill.order_id, fill.fill.quantity, fill.fill.price, fill.fill.commission) for fill in matched]
+
+    costs = CostModel([PerShareCommission(D("0.005"), minimum=D(1)), PercentageFee(D("0.0001"))])
+    expected = fills(python.MatchingEngine(InstantFillModel(), costs))
+    assert fills(native.MatchingEngine(InstantFillModel(), costs)) == expected
+    # The minimum per-share commission, and 1 bp of 1050
+    assert len(expected) == len(specs) and expected[0][3] == D("1.105")
+
+
+def test_orders_that_cannot_fill_warn(engine: Any, caplog: pytest.LogCaptureFixture) -> None:
+    engine.submit("limit", "s", order(OrderSide.BUY, OrderType.LIMIT, limit_price=90), at(0))
+    with caplog.at_level(logging.WARNING, logger="simulor.execution.simulation.matching"):
+        engine.on_market_event(event(0, trade_bar(0, 100)))
+        # Only for orders of instruments in the event
+        engine.on_market_event(event(1, trade_bar(1, 100, instrument=MSFT)))
+    assert [record.getMessage() for record in caplog.records] == [
+        f"Order limit for {AAPL.display_name} cannot be filled at {at(0)}"
+    ]
+
+
+def test_declined_fills_leave_orders_resting(engine: Any) -> None:
+    engine.submit("first", "s", order(OrderSide.BUY), at(0))
+    engine.submit("second", "s", order(OrderSide.BUY), at(0))
+    offered = []
+
+    def accept(order_fill: Any) -> bool:
+        offered.append(order_fill.order_id)
+        return order_fill.order_id == "second"
+
+    assert prices(engine.on_market_event(event(0, trade_bar(0, 100)), accept)) == [("second", D(10), D(100))]
+    assert offered == ["first", "second"]
+    assert list(engine.open_orders) == ["first"]
+    assert prices(engine.on_market_event(event(1, trade_bar(1, 101)))) == [("first", D(10), D(101))]
+
+
+class Bus:
+    def __init__(self) -> None:
+        self.events: list[Any] = []
+
+    def publish(self, event: Any) -> None:
+        self.events.append(event)
+
+
+@pytest.mark.parametrize("kind", ["native", "python"])
+def test_broker_keeps_orders_a_strategy_cannot_afford(
+    kind: str, python_fallback: Callable[[str], ModuleType], caplog: pytest.LogCaptureFixture
+) -> None:
+    from simulor.core.events import EventType
+    from simulor.execution.simulation.broker import SimulatedBroker
+    from simulor.portfolio.manager import Portfolio
+
+    broker, bus = SimulatedBroker(), Bus()
+    if kind == "python":
+        python = python_fallback("simulor.execution.simulation.matching")
+        broker._engine = python.MatchingEngine(broker._fill_model, broker._cost_model)
+    portfolio = Portfolio(starting_cash=D(1000))
+    broker.initialize(bus, Portfolio(starting_cash=D(0)), {"s": portfolio})  # type: ignore[arg-type]
+    broker.connect()
+
+    first = broker.submit_order("s", order(OrderSide.BUY, quantity=2)).order_id
+    big = broker.submit_order("s", order(OrderSide.BUY, quantity=10)).order_id
+    small = broker.submit_order("s", order(OrderSide.BUY, quantity=1)).order_id
+    # The first buy fills; the second is not affordable, which stops the event's fills there
+    with (
+        caplog.at_level(logging.WARNING),
+        pytest.raises(ValueError, match="Insufficient cash for AAPL: need 1500, have 700"),
+    ):
+        broker.on_market_event(event(0, trade_bar(0, 150)))
+    assert [record.getMessage().split(":")[0] for record in caplog.records] == ["Insufficient cash for AAPL"]
+
+    # The fill made before it is still published, and the orders from the one declined on rest in the book
+    assert [(e.type, e.payload["fill"].quantity) for e in bus.events] == [(EventType.FILL, D(2))]
+    assert portfolio.cash == D(700) and portfolio.positions[AAPL].quantity == D(2)
+    assert list(broker._engine.open_orders) == [big, small]
+    assert broker._engine.owner(first) is None
+
+    broker.cancel_order("s", big)
+    broker.submit_order("s", order(OrderSide.SELL, quantity=3))
+    broker.on_market_event(event(1, trade_bar(1, 100)))
+    assert [e.payload["fill"].quantity for e in bus.events[1:]] == [D(1), D(-3)]
+    assert portfolio.cash == D(700) - D(100) + D(300) and len(broker._engine) == 0
@@ -0,0 +1,143 @@
+"""Test partial fills: participation in the market's quantity, and the remainders left resting."""
+
+from __future__ import annotations
+
+from decimal import Decimal
+from typing import Any
+
+import pytest
+from helpers import AAPL, T0, D, engine, event, quote_bar, quote_tick, trade_bar, trade_tick
+
+from simulor.types import OrderSide, OrderSpec, OrderType
+
+native = pytest.importorskip("_simulor_rust")
+
+
+def order(side: OrderSide, quantity: Any) -> OrderSpec:
+    return OrderSpec(instrument=AAPL, side=side, quantity=D(quantity), order_type=OrderType.MARKET)
+
+
+def fills(matched: list[Any]) -> list[tuple[str, Decimal, Decimal]]:
+    return [(m.order_id, m.fill.quantity, m.fill.price) for m in matched if hasattr(m, "fill")]
+
+
+@pytest.mark.parametrize(
+    ("records", "buy", "sell"),
+    [
+        # A bar's volume and a trade's size are shared by both sides
+        ([trade_bar(0, 100, 300)], D(30), D(0)),
+        ([trade_tick(0, 100, 300)], D(30), D(0)),
+        # A quote tick's size on the order's side: the ask for a buy, the bid for a sell
+        ([quote_tick(0, 99, 101, bid_size=200, ask_size=300)], D(30), D(-20)),
+    ],
+)
+def test_fills_a_share_of_the_market_quantity(records: list[Any], buy: Decimal, sell: Decimal) -> None:
+    matching = engine(native.PartialFillModel())
+    matching.submit("buy", "s", order(OrderSide.BUY, 100), T0)
+    matching.submit("sell", "s", order(OrderSide.SELL, 100), T0)
+
+    filled = {order_id: quantity for order_id, quantity, _ in fills(matching.on_market_event(event(0, *records)))}
+    assert filled.get("buy", D(0)) == buy
+    assert filled.get("sell", D(0)) == sell
+
+
+def test_orders_share_the_event_in_arrival_order_and_rest_the_remainder() -> None:
+    matching = engine(native.PartialFillModel())
+    matching.submit("first", "s", order(OrderSide.BUY, 25), T0)
+    matching.submit("second", "s", order(OrderSide.BUY, 25), T0)
+
+    # 10% of 300 is 30: the first order takes 25, the second what is left
+    matched = matching.on_market_event(event(0, trade_bar(0, 100, 300)))
+    assert fills(matched) == [("first", D(25), D(100)), ("second", D(5), D(100))]
+    assert [(m.filled_quantity, m.remaining_quantity) for m in matched] == [(D(25), D(0)), (D(5), D(20))]
+    assert list(matching.open_orders) == ["second"]
+
+    # The remainder fills against later events, averaging its prices
+    matched = matching.on_market_event(event(1, trade_bar(1, 110, 100)))
+    assert fills(matched) == [("second", D(10), D(110))]
+    matched = matching.on_market_event(event(2, trade_bar(2, 120, 1000)))
+    assert fills(matched) == [("second", D(10), D(120))]
+    (last,) = matched
+    assert (last.filled_quantity, last.remaining_quantity) == (D(25), D(0))
+    assert last.average_price == (D(5) * 100 + D(10) * 110 + D(10) * 120) / D(25)
+    assert len(matching) == 0
+
+
+def test_fills_round_down_within_the_share() -> None:
+    matching = engine(native.PartialFillModel())
+    matching.submit("order", "s", order(OrderSide.BUY, 2), T0)
+
+    # 10% of 15 shares is 1.5: one whole share fills per event
+    filled = [fills(matching.on_market_event(event(minute, trade_bar(minute, 100, 15)))) for minute in range(2)]
+    assert filled == [[("order", D(1), D(100))], [("order", D(1), D(100))]]
+
+    # 10% of 4 shares is 0.4, under a whole one, so nothing fills
+    matching.submit("small", "s", order(OrderSide.BUY, 1), T0)
+    assert fills(matching.on_market_event(event(2, trade_bar(2, 100, 4)))) == []
+    assert list(matching.open_orders) == ["small"]
+
+
+def test_declined_fills_leave_the_share_to_later_orders() -> None:
+    matching = engine(native.PartialFillModel())
+    matching.submit("declined", "s", order(OrderSide.BUY, 25), T0)
+    matching.submit("accepted", "s", order(OrderSide.BUY, 25), T0)
+
+    # 10% of 300 is 30, none of it taken by the declined fill
+    matched = matching.on_market_event(event(0, trade_bar(0, 100, 300)), lambda fill: fill.order_id == "accepted")
+    assert fills(matched) == [("accepted", D(25), D(100))]
+    assert list(matching.open_orders) == ["declined"]
+
+
+def test_quote_bars_are_capped_by_trade_bar_volume() -> None:
+    matching = engine(native.PartialFillModel())
+    matching.submit("order", "s", order(OrderSide.BUY, 100), T0)
+
+    # Priced at the quote bar's ask, sized by the trade bar's volume
+    matched = matching.on_market_event(event(0, quote_bar(0, 99, 101), trade_bar(0, 100, 200)))
+    assert fills(matched) == [("order", D(20), D(101))]
+    # Without a trade bar a quote bar shows no quantity, and the rest fills in full
+    matched = matching.on_market_event(event(1, quote_bar(1, 99, 101)))
+    assert fills(matched) == [("order", D(80), D(101))]
+
+
+def test_broker_publishes_partial_fills() -> None:
+    from simulor.core.events import EventType
+    from simulor.execution.simulation.broker import SimulatedBroker
+    from simulor.portfolio.manager import Portfolio
+
+    class Bus:
+        def __init__(self) -> None:
+            self.events: list[Any] = []
+
+        def publish(self, event: Any) -> None:
+            self.events.append(event)
+
+    broker, bus = SimulatedBroker(fill_model=native.PartialFillModel(D("0.5"))), Bus()
+    portfolio = Portfolio(starting_cash=D(10000))
+    broker.initialize(bus, Portfolio(starting_cash=D(0)), {"s": portfolio})  # type: ignore[arg-type]
+    broker.connect()
+
+    broker.submit_order("s", order(OrderSide.BUY, 30))
+    broker.on_market_event(event(0, trade_bar(0, 100, 40)))
+    broker.on_market_event(event(1, trade_bar(1, 100, 40)))
+    assert [(e.type, e.payload["order_fill"].filled_quantity) for e in bus.events] == [
+        (EventType.PARTIAL_FILL, D(20)),
+        (EventType.FILL, D(30)),
+    ]
+    assert portfolio.positions[AAPL].quantity == D(30)
+
+
+def test_partial_fill_model() -> None:
+    model = native.PartialFillModel()
+    assert (model.participation, model.use_book) == (D("0.1"), False)
+    assert repr(model) == "PartialFillModel(participation=0.1)"
+    assert repr(native.PartialFillModel(D(1), use_book=True)) == "PartialFillModel(participation=1, use_book=True)"
+    for participation in (D(0), D("1.5")):
+        with pytest.raises(ValueError, match="Participation must be greater than 0 and at most 1"):
+            native.PartialFillModel(participation)
+
+    # Asked alone, a model prices and sizes an order against the event
+    market_event = event(0, trade_bar(0, 100, 300))
+    assert model.get_fill_price(order(OrderSide.BUY, 100), market_event) == D(100)
+    assert model.get_fill_quantity(order(OrderSide.BUY, 100), market_event) == D(30)
+    assert model.get_fill_quantity(order(OrderSide.BUY, 100), event(0, trade_bar(0, 100, 5))) is None
@@ -0,0 +1,127 @@
+"""Test trailing stop tracking and the matching of trailing stop and trailing stop-limit orders."""
+
+from __future__ import annotations
+
+from collections.abc import Callable
+from decimal import Decimal
+from types import ModuleType
+from typing import Any
+
+import pytest
+from helpers import AAPL, T0, D, bar_event, engine
+
+from simulor.types import OrderSide, OrderSpec, OrderType
+
+native = pytest.importorskip("_simulor_rust")
+
+
+def trailing(side: OrderSide, kind: OrderType = OrderType.TRAILING_STOP, **prices: Any) -> OrderSpec:
+    prices = {name: D(price) for name, price in prices.items()}
+    return OrderSpec(instrument=AAPL, side=side, quantity=D(10), order_type=kind, **prices)
+
+
+def test_tracker_ratchets_and_triggers() -> None:
+    sell = native.TrailingStopTracker(OrderSide.SELL, trailing_amount=D(2))
+    assert sell.stop_price is None
+    # Up with the market, never down, and triggered where it stood
+    assert [sell.update(D(price)) for price in (100, 103, 102, 101.5)] == [False] * 4
+    assert sell.stop_price == D(101) and not sell.triggered
+    assert sell.update(D(101)) and sell.triggered and sell.update(D(110))
+    assert sell.stop_price == D(101)
+    assert repr(sell) == "TrailingStopTracker(side=SELL, trailing_amount=2, stop_price=101, triggered=True)"
+
+    buy = native.TrailingStopTracker(OrderSide.BUY, trailing_percent=D(5), stop_price=D(110))
+    # From the given stop, down to 5% above the lowest price
+    assert not buy.update(D(108)) and buy.stop_price == D(110)
+    assert not buy.update(D(90)) and buy.stop_price == D("94.5")
+    assert (buy.trailing_amount, buy.trailing_percent, buy.side) == (None, D(5), OrderSide.BUY)
+
+    spec = trailing(OrderSide.SELL, trailing_percent=10, stop_price=95)
+    assert native.TrailingStopTracker.for_order(spec).stop_price == D(95)
+    with pytest.raises(ValueError, match="Cannot specify both"):
+        native.TrailingStopTracker(OrderSide.SELL, trailing_amount=D(1), trailing_percent=D(1))
+    with pytest.raises(ValueError, match="cannot be negative"):
+        native.TrailingStopTracker(OrderSide.SELL, trailing_amount=D(-1))
+
+
+def test_trailing_stops_trigger_then_fill_at_the_market() -> None:
+    matching = engine()
+    matching.submit("sell", "s", trailing(OrderSide.SELL, trailing_amount=2), T0)
+    matching.submit("buy", "s", trailing(OrderSide.BUY, trailing_percent=10), T0)
+
+    outcomes = [[type(m).__name__ for m in matching.on_market_event(bar_event(i, p))] for i, p in enumerate([100, 104])]
+    assert outcomes == [[], []]
+    assert matching.trailing_stop("sell").stop_price == D(102)
+    assert matching.trailing_stop("buy").stop_price == D(110)
+
+    # The sell stop fires at 102 and fills at the market, in one event
+    trigger, fill = matching.on_market_event(bar_event(2, 101))
+    assert (trigger.order_id, trigger.trigger_price, trigger.market_price) == ("sell", D(102), D(101))
+    assert (fill.order_id, fill.fill.price, fill.fill.quantity) == ("sell", D(101), D(-10))
+    assert matching.trailing_stop("sell") is None and list(matching.open_orders) == ["buy"]
+
+
+def test_trailing_stop_limits_rest_as_limit_orders() -> None:
+    matching = engine()
+    # Without a limit price, at the stop level once triggered
+    matching.submit("sell", "s", trailing(OrderSide.SELL, OrderType.TRAILING_STOP_LIMIT, trailing_amount=2), T0)
+    matching.submit(
+        "limited", "s", trailing(OrderSide.SELL, OrderType.TRAILING_STOP_LIMIT, trailing_amount=2, limit_price=99), T0
+    )
+    matching.on_market_event(bar_event(0, 100))
+    matched = matching.on_market_event(bar_event(1, 97))
+    # Both trigger at 98; the market is already through both limits
+    assert [(type(m).__name__, m.order_id) for m in matched] == [
+        ("OrderTrigger", "sell"),
+        ("OrderTrigger", "limited"),
+    ]
+    assert [m.order_id for m in matching.on_market_event(bar_event(2, 98.5))] == ["sell"]
+    assert [m.fill.price for m in matching.on_market_event(bar_event(3, 99))] == [D(99)]
+
+
+def test_broker_publishes_triggers_and_exposes_the_stop() -> None:
+    from simulor.core.events import EventType
+    from simulor.execution.simulation.broker import SimulatedBroker
+    from simulor.portfolio.manager import Portfolio
+    from simulor.types import Fill
+
+    class Bus:
+        def __init__(self) -> None:
+            self.events: list[Any] = []
+
+        def publish(self, event: Any) -> None:
+            self.events.append(event)
+
+    broker, bus = SimulatedBroker(), Bus()
+    portfolio = Portfolio(starting_cash=D(10_000))
+    broker.initialize(bus, Portfolio(starting_cash=D(0)), {"s": portfolio})  # type: ignore[arg-type]
+    broker.connect()
+    portfolio.update_position(Fill(instrument=AAPL, quantity=D(10), price=D(100), commission=D(0)))
+    order_id = broker.submit_order("s", trailing(OrderSide.SELL, trailing_amount=2)).order_id
+
+    broker.on_market_event(bar_event(0, 100))
+    assert broker.get_trailing_stop(order_id).stop_price == D(98)
+    broker.on_market_event(bar_event(1, 97))
+    assert [e.type for e in bus.events] == [EventType.ORDER_TRIGGERED, EventType.FILL]
+    assert bus.events[0].payload["trigger"].trigger_price == D(98)
+    assert AAPL not in portfolio.positions and broker.get_trailing_stop(order_id) is None
+
+
+def test_trailing_orders_need_native_matching(python_fallback: Callable[[str], ModuleType]) -> None:
+    from simulor.execution.simulation.cost_models import CostModel
+    from simulor.execution.simulation.fill_models import FillModel, InstantFillModel
+
+    spec = trailing(OrderSide.SELL, trailing_amount=2)
+    python = python_fallback("simulor.execution.simulation.matching")
+    with pytest.raises(NotImplementedError, match="TRAILING_STOP orders require the _simulor_rust extension"):
+        python.MatchingEngine(InstantFillModel(), CostModel()).submit("sell", "s", spec, T0)
+
+    class NoFillModel(FillModel):
+        def get_fill_price(self, order_spec: OrderSpec, market_event: Any) -> Decimal | None:
+            return None
+
+    # Other fill models price one event at a time, with no stop carried across
+    with pytest.raises(NotImplementedError, match="PartialFillModel, not a NoFillModel"):
+        native.MatchingEngine(NoFillModel(), CostModel()).submit("sell", "s", spec, T0)
+    with pytest.raises(NotImplementedError, match="TRAILING_STOP orders are matched by the native MatchingEngine"):
+        InstantFillModel().get_fill_price(spec, bar_event(0, 100))
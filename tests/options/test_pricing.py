@@ -0,0 +1,125 @@
+"""Test the native option pricing models, implied volatility and Greeks."""
+
+from __future__ import annotations
+
+import math
+from array import array
+
+import pytest
+
+from simulor.types import OptionType
+
+native = pytest.importorskip("_simulor_rust")
+
+# At the money for a year, as in Hull's examples
+ATM = {"spot": 100.0, "strike": 100.0, "time": 1.0, "rate": 0.05, "volatility": 0.2}
+
+
+def test_black_scholes_merton_prices_and_greeks() -> None:
+    from simulor.options import BlackScholesMerton
+
+    bsm = BlackScholesMerton()
+    assert bsm.price(OptionType.CALL, **ATM) == pytest.approx(10.450583572185565, abs=1e-12)
+    assert bsm.price("put", **ATM) == pytest.approx(5.573526022256971, abs=1e-12)
+    # A dividend yield lowers calls and raises puts
+    assert bsm.price("call", **ATM, dividend_yield=0.02) < bsm.price("call", **ATM)
+    assert bsm.price("P", **ATM, dividend_yield=0.02) > bsm.price("put", **ATM)
+
+    greeks = bsm.greeks(OptionType.CALL, **ATM)
+    assert isinstance(greeks, native.Greeks)
+    assert greeks.price == bsm.price(OptionType.CALL, **ATM)
+    assert greeks.delta == pytest.approx(0.6368306511756191)
+    assert greeks.gamma == pytest.approx(0.018762017345846895)
+    assert greeks.vega == pytest.approx(37.52403469169379)
+    # Per year of time passing, so negative for a long call
+    assert greeks.theta == pytest.approx(-6.414027546438197)
+    assert greeks.rho == pytest.approx(53.232481545376345)
+    put = bsm.greeks(OptionType.PUT, **ATM)
+    # Calls and puts share gamma and vega, and their deltas differ by one
+    assert (put.gamma, put.vega) == pytest.approx((greeks.gamma, greeks.vega))
+    assert greeks.delta - put.delta == pytest.approx(1.0)
+    assert repr(bsm) == "BlackScholesMerton()"
+
+
+def test_black_76_prices_futures_options() -> None:
+    from simulor.options import Black76, BlackScholesMerton
+
+    black = Black76()
+    args = {"strike": 100.0, "time": 1.0, "rate": 0.05, "volatility": 0.2}
+    call, put = black.price("call", 100.0, **args), black.price("put", 100.0, **args)
+    # At the money forward, calls and puts are worth the same
+    assert call == pytest.approx(put)
+    # A futures option is a spot option whose dividend yield equals the rate
+    assert call == pytest.approx(BlackScholesMerton().price("call", 100.0, **args, dividend_yield=0.05))
+    greeks = black.greeks("call", 100.0, **args)
+    # With the futures price fixed, rho only discounts
+    assert greeks.rho == pytest.approx(-call)
+    assert black.implied_volatility("put", put, 100.0, 100.0, 1.0, 0.05) == pytest.approx(0.2)
+
+
+def test_binomial_tree_prices_early_exercise() -> None:
+    from simulor.options import BinomialTree, BlackScholesMerton
+
+    tree = BinomialTree(steps=500)
+    assert (tree.steps, tree.american) == (500, True)
+    assert repr(BinomialTree(american=False)) == "BinomialTree(steps=200, american=False)"
+
+    # Hull's American put: S = K = 50, five months, r = 10%, σ = 40%
+    put = ("put", 50.0, 50.0, 5 / 12, 0.1, 0.4)
+    american, european = tree.price(*put), BinomialTree(steps=500, american=False).price(*put)
+    assert american == pytest.approx(4.28, abs=0.01)
+    assert european == pytest.approx(BlackScholesMerton().price(*put), abs=0.01)
+    assert american > european
+    greeks = tree.greeks(*put)
+    assert greeks.price == american and -1 < greeks.delta < 0 and greeks.gamma > 0
+    assert tree.implied_volatility("put", american, 50.0, 50.0, 5 / 12, 0.1) == pytest.approx(0.4, abs=1e-6)
+
+    # Below the volatility the tree supports, |r - q| × sqrt(T / steps)
+    assert math.isnan(BinomialTree(steps=4).price("call", 100.0, 100.0, 1.0, 0.5, 0.2))
+    with pytest.raises(ValueError, match="at least 2"):
+        BinomialTree(steps=1)
+
+
+def test_implied_volatility_round_trips() -> None:
+    from simulor.options import BlackScholesMerton
+
+    bsm = BlackScholesMerton()
+    for option_type, strike, volatility in [("call", 100.0, 0.2), ("put", 70.0, 0.85), ("call", 180.0, 0.3)]:
+        price = bsm.price(option_type, 100.0, strike, 0.5, 0.03, volatility, dividend_yield=0.01)
+        solved = bsm.implied_volatility(option_type, price, 100.0, strike, 0.5, 0.03, dividend_yield=0.01)
+        assert solved == pytest.approx(volatility, abs=1e-8)
+    # No volatility prices a call below its discounted intrinsic value
+    assert math.isnan(bsm.implied_volatility("call", 1.0, 120.0, 100.0, 1.0, 0.05))
+
+
+def test_invalid_inputs() -> None:
+    from simulor.options import BlackScholesMerton
+
+    bsm = BlackScholesMerton()
+    assert math.isnan(bsm.price("call", 100.0, -1.0, 1.0, 0.05, 0.2))
+    assert math.isnan(bsm.greeks("put", 100.0, 100.0, 1.0, 0.05, -0.2).vega)
+    with pytest.raises(ValueError, match="Unknown option type: straddle"):
+        bsm.price("straddle", **ATM)
+    with pytest.raises(ValueError, match="option_type must be"):
+        bsm.price(1, **ATM)
+    with pytest.raises(ValueError, match="spot must be a number"):
+        bsm.price("call", "100", 100.0, 1.0, 0.05, 0.2)
+    # Arrays must share a shape
+    with pytest.raises(ValueError, match=r"strike has shape \[3\] but spot has shape \[2\]"):
+        bsm.price("call", array("d", [90, 100]), array("d", [90, 100, 110]), 1.0, 0.05, 0.2)
+
+
+def test_arrays_are_priced_element_wise() -> None:
+    np = pytest.importorskip("numpy")
+    from simulor.options import BlackScholesMerton
+
+    bsm = BlackScholesMerton()
+    strikes = np.linspace(50.0, 150.0, 1000).reshape(10, 100)
+    prices = bsm.price("put", 100.0, strikes, 1.0, 0.05, 0.2)
+    assert prices.shape == (10, 100)
+    assert prices[3, 7] == bsm.price("put", 100.0, float(strikes[3, 7]), 1.0, 0.05, 0.2)
+
+    greeks = bsm.greeks(["call", "put"], np.array([100.0, 100.0]), 100.0, 1.0, 0.05, np.array([0.2, 0.3]))
+    assert greeks.delta.shape == (2,) and greeks.delta[0] > 0 > greeks.delta[1]
+    ivs = bsm.implied_volatility("put", prices, 100.0, strikes, 1.0, 0.05)
+    np.testing.assert_allclose(ivs, 0.2, atol=1e-8)
@@ -0,0 +1,96 @@
+"""Test composite instrument identity and the native instrument interner."""
+
+from __future__ import annotations
+
+from datetime import datetime, timezone
+from decimal import Decimal
+from types import ModuleType
+
+import pytest
+
+from simulor.types.instruments import Instrument
+
+EXPIRY = datetime(2024, 3, 15)
+
+
+def test_identity_covers_listing_fields() -> None:
+    assert Instrument.stock("AAPL", exchange="NASDAQ") == Instrument.stock("AAPL", exchange="NASDAQ")
+    assert Instrument.stock("AAPL", exchange="NASDAQ") != Instrument.stock("AAPL", exchange="LSE")
+    assert Instrument.stock("AAPL", currency="USD") != Instrument.stock("AAPL", currency="EUR")
+    assert Instrument.stock("ES") != Instrument.future("ES", expiry=EXPIRY)
+    assert Instrument.future("ES", expiry=EXPIRY) != Instrument.future("ES", expiry=datetime(2024, 6, 21))
+    assert Instrument.future("ES") != Instrument.future("ES", expiry=EXPIRY)
+
+
+def test_contract_specifications_are_not_identity() -> None:
+    a = Instrument.stock("AAPL", tick_size=Decimal("0.01"))
+    b = Instrument.stock("AAPL", tick_size=Decimal("0.0001"))
+    assert a == b
+    assert hash(a) == hash(b)
+    assert len({a, b, Instrument.stock("MSFT")}) == 2
+
+
+def test_hash_follows_key() -> None:
+    future = Instrument.future("ES", expiry=EXPIRY, exchange="CME")
+    assert hash(future) == hash(future.key)
+    assert future.key == ("ES", future.asset_type, "CME", "USD", EXPIRY, None, None)
+
+
+@pytest.fixture
+def rust() -> ModuleType:
+    return pytest.importorskip("_simulor_rust")
+
+
+def test_ids_are_dense_and_stable(rust: ModuleType) -> None:
+    registry = rust.InstrumentRegistry()
+    aapl = registry.intern(Instrument.stock("AAPL"))
+    msft = registry.intern(Instrument.stock("MSFT"))
+
+    assert (int(aapl), int(msft)) == (0, 1)
+    assert registry.intern(Instrument.stock("AAPL")) == aapl
+    assert registry.lookup(Instrument.stock("AAPL")) == aapl
+    assert len(registry) == 2
+
+
+def test_equal_instruments_share_an_id(rust: ModuleType) -> None:
+    registry = rust.InstrumentRegistry()
+    first = Instrument.stock("AAPL", tick_size=Decimal("0.01"))
+    same = registry.intern(Instrument.stock("AAPL", tick_size=Decimal("0.0001")))
+
+    assert registry.intern(first) == same
+    assert registry.resolve(same) is not first
+    assert registry.resolve(same) == first
+
+
+def test_distinct_listings_get_distinct_ids(rust: ModuleType) -> None:
+    registry = rust.InstrumentRegistry()
+    instruments = [
+        Instrument.stock("AAPL", exchange="NASDAQ"),
+        Instrument.stock("AAPL", exchange="LSE"),
+        Instrument.stock("ES"),
+        Instrument.future("ES"),
+        Instrument.future("ES", expiry=EXPIRY),
+        Instrument.future("ES", expiry=EXPIRY.replace(tzinfo=timezone.utc)),
+    ]
+    ids = [registry.intern(instrument) for instrument in instruments]
+
+    assert len(set(ids)) == len(instruments)
+    assert registry.instruments() == instruments
+
+
+def test_resolve_and_membership(rust: ModuleType) -> None:
+    registry = rust.InstrumentRegistry()
+    aapl = Instrument.stock("AAPL")
+    instrument_id = registry.intern(aapl)
+
+    assert registry.resolve(instrument_id) is aapl
+    assert registry.resolve(0) is aapl
+    assert aapl in registry
+    assert Instrument.stock("MSFT") not in registry
+    assert registry.lookup(Instrument.stock("MSFT")) is None
+    with pytest.raises(KeyError):
+        registry.resolve(5)
+
+
+def test_default_registry_is_shared(rust: ModuleType) -> None:
+    assert rust.InstrumentRegistry.default() is rust.InstrumentRegistry.default()
@@ -0,0 +1,114 @@
+"""Test the native market data records against the dataclass fallback."""
+
+from __future__ import annotations
+
+import pickle
+from collections.abc import Callable
+from datetime import datetime, timezone
+from decimal import Decimal
+from types import ModuleType
+from typing import Any
+
+import pytest
+
+from simulor.types.common import Resolution, TickDirection
+from simulor.types.instruments import Instrument
+
+native = pytest.importorskip("_simulor_rust")
+
+TS = datetime(2024, 1, 2, 14, 30, tzinfo=timezone.utc)
+AAPL = Instrument.stock("AAPL", exchange="NASDAQ")
+
+VALID: dict[str, tuple[Any, ...]] = {
+    "TradeTick": (Resolution.TICK, Decimal("187.50"), Decimal("100"), TickDirection.BUY),
+    "QuoteTick": (Resolution.TICK, Decimal("187.49"), Decimal("300"), Decimal("187.51"), Decimal("0")),
+    "TradeBar": (
+        Resolution.MINUTE,
+        Decimal("187.00"),
+        Decimal("188.25"),
+        Decimal("186.5"),
+        Decimal("188"),
+        Decimal("12500"),
+    ),
+    "QuoteBar": (
+        Resolution.MINUTE,
+        *(Decimal(v) for v in ("10.0", "10.2", "9.9", "10.1")),
+        *(Decimal(v) for v in ("10.1", "10.3", "10.0", "10.2")),
+    ),
+}
+
+INVALID: list[tuple[str, tuple[Any, ...]]] = [
+    ("TradeTick", (Resolution.TICK, Decimal("0"), Decimal("1"))),
+    ("TradeTick", (Resolution.TICK, Decimal("1"), Decimal("-1"))),
+    ("TradeTick", (Resolution.MINUTE, Decimal("1"), Decimal("1"))),
+    ("QuoteTick", (Resolution.TICK, Decimal("2"), Decimal("1"), Decimal("2"), Decimal("1"))),
+    ("QuoteTick", (Resolution.TICK, Decimal("1"), Decimal("-1"), Decimal("2"), Decimal("1"))),
+    ("QuoteTick", (Resolution.SECOND, Decimal("1"), Decimal("1"), Decimal("2"), Decimal("1"))),
+    ("TradeBar", (Resolution.DAILY, Decimal("3"), Decimal("2"), Decimal("1"), Decimal("2"), Decimal("1"))),
+    ("TradeBar", (Resolution.DAILY, Decimal("1"), Decimal("2"), Decimal("1"), Decimal("0.5"), Decimal("1"))),
+    ("TradeBar", (Resolution.DAILY, Decimal("1"), Decimal("2"), Decimal("1"), Decimal("2"), Decimal("-1"))),
+    ("QuoteBar", (Resolution.DAILY, *(Decimal(v) for v in ("1", "2", "1", "3", "1", "2", "1", "2")))),
+    ("QuoteBar", (Resolution.DAILY, *(Decimal(v) for v in ("1", "2", "1", "2", "0", "2", "1", "2")))),
+]
+
+
+@pytest.fixture
+def fallback(python_fallback: Callable[[str], ModuleType]) -> ModuleType:
+    return python_fallback("simulor.types.market_data")
+
+
+@pytest.mark.parametrize("name", list(VALID))
+def test_records_match_the_dataclasses(name: str, fallback: ModuleType) -> None:
+    args = (TS, AAPL, *VALID[name])
+    record = getattr(native, name)(*args)
+    expected = getattr(fallback, name)(*args)
+
+    assert isinstance(record, native.MarketData)
+    assert repr(record) == repr(expected)
+    for field in expected.__dataclass_fields__:
+        value = getattr(record, field)
+        assert value == getattr(expected, field)
+        assert str(value) == str(getattr(expected, field))
+
+
+@pytest.mark.parametrize(("name", "args"), INVALID)
+def test_validation_matches_the_dataclasses(name: str, args: tuple[Any, ...], fallback: ModuleType) -> None:
+    with pytest.raises(ValueError) as expected:
+        getattr(fallback, name)(TS, AAPL, *args)
+    with pytest.raises(ValueError, match=str(expected.value)):
+        getattr(native, name)(TS, AAPL, *args)
+
+
+def test_derived_quote_values() -> None:
+    quote = native.QuoteTick(TS, AAPL, *VALID["QuoteTick"])
+    assert quote.spread == Decimal("0.02")
+    assert quote.mid_price == Decimal("187.50")
+    bar = native.QuoteBar(TS, AAPL, *VALID["QuoteBar"])
+    assert bar.mid_close == Decimal("10.15")
+
+
+def test_keyword_construction_and_defaults() -> None:
+    tick = native.TradeTick(
+        timestamp=TS, instrument=AAPL, resolution=Resolution.TICK, price=Decimal("1"), size=Decimal("2")
+    )
+    assert tick.direction is None
+
+
+@pytest.mark.parametrize("name", list(VALID))
+def test_records_are_frozen_hashable_and_picklable(name: str) -> None:
+    cls = getattr(native, name)
+    record = cls(TS, AAPL, *VALID[name])
+    same = cls(TS, AAPL, *VALID[name])
+
+    assert record == same
+    assert hash(record) == hash(same)
+    assert pickle.loads(pickle.dumps(record)) == record
+    with pytest.raises(AttributeError):
+        record.timestamp = TS  # type: ignore[misc]
+
+
+def test_records_differ_by_type_and_value() -> None:
+    tick = native.TradeTick(TS, AAPL, Resolution.TICK, Decimal("1"), Decimal("1"))
+    assert tick != native.TradeTick(TS, AAPL, Resolution.TICK, Decimal("1"), Decimal("2"))
+    assert tick != native.TradeTick(TS, Instrument.stock("AAPL", exchange="LSE"), Resolution.TICK, 1, 1)
+    assert tick.price == native.TradeTick(TS, AAPL, Resolution.TICK, Decimal("1.00"), Decimal("1")).price
@@ -0,0 +1,123 @@
+"""Test the native fixed-point Price and Quantity types."""
+
+from __future__ import annotations
+
+import pickle
+from decimal import Decimal
+
+import pytest
+
+native = pytest.importorskip("_simulor_rust")
+Price = native.Price
+Quantity = native.Quantity
+RoundingMode = native.RoundingMode
+
+
+def test_construction_preserves_scale() -> None:
+    assert str(Price("1.50")) == "1.50"
+    assert Price(Decimal("1.50")).scale == 2
+    assert Price(0.1) == Price("0.1")
+    assert Quantity(3).raw == 3
+    assert Price.from_raw(12345, 2) == Price("123.45")
+
+
+def test_construction_rescales_only_exactly_without_rounding() -> None:
+    assert str(Price("1.5", scale=3)) == "1.500"
+    with pytest.raises(ValueError):
+        Price("1.55", scale=1)
+    assert Price("1.55", scale=1, rounding=RoundingMode.HALF_EVEN) == Price("1.6")
+    assert Price("1.25", scale=1, rounding=RoundingMode.HALF_EVEN) == Price("1.2")
+    assert Price("1.25", scale=1, rounding=RoundingMode.HALF_UP) == Price("1.3")
+
+
+def test_rejects_non_finite_and_unsupported_values() -> None:
+    with pytest.raises(ValueError):
+        Price(float("nan"))
+    with pytest.raises(TypeError):
+        Price([1])
+
+
+def test_same_kind_arithmetic_keeps_the_kind() -> None:
+    total = Price("1.10") + Price("2.2")
+    assert isinstance(total, Price)
+    assert str(total) == "3.30"
+    assert isinstance(Quantity(5) - Quantity(2), Quantity)
+    assert Price("1.5") * 2 == Price("3.0")
+    assert 2 * Quantity("1.5") == Quantity("3")
+    assert Price("10") / 4 == Price("2.5")
+    assert -Price("1.5") == Price("-1.5")
+    assert abs(Quantity(-2)) == Quantity(2)
+
+
+def test_notional_and_average_price() -> None:
+    notional = Price("101.25") * Quantity(4)
+    assert isinstance(notional, Price)
+    assert notional == Price("405")
+    assert isinstance(Quantity(4) * Price("101.25"), Price)
+    assert notional / Quantity(4) == Price("101.25")
+
+
+def test_ratios_of_the_same_kind_are_decimals() -> None:
+    ratio = Price("3") / Price("2")
+    assert type(ratio) is Decimal
+    assert ratio == Decimal("1.5")
+    assert Quantity(1) / Quantity(4) == Decimal("0.25")
+
+
+@pytest.mark.parametrize(
+    "op",
+    [
+        lambda: Price(1) + Quantity(1),
+        lambda: Quantity(1) - Price(1),
+        lambda: Price(2) * Price(3),
+        lambda: Quantity(2) * Quantity(3),
+        lambda: Quantity(2) / Price(3),
+        lambda: Price(1) + 1.5,
+    ],
+)
+def test_meaningless_combinations_raise(op: object) -> None:
+    with pytest.raises(TypeError):
+        op()  # type: ignore[operator]
+
+
+def test_division_by_zero_and_overflow_raise() -> None:
+    with pytest.raises(ZeroDivisionError):
+        Price(1) / 0
+    with pytest.raises(OverflowError):
+        Price.from_raw(2**126, 0) * 4
+
+
+def test_comparison_across_scales_and_kinds() -> None:
+    assert Price("1.5") == Price("1.500")
+    assert Price("1.5") == Decimal("1.5")
+    assert Price("1.49") < Price("1.5") <= 2
+    assert Price(1) != Quantity(1)
+    with pytest.raises(TypeError):
+        _ = Price(1) < Quantity(2)
+
+
+def test_hash_matches_decimal() -> None:
+    assert hash(Price("2.50")) == hash(Price("2.5")) == hash(Decimal("2.5"))
+    assert len({Quantity("1.0"), Quantity(1)}) == 1
+
+
+def test_ticks_and_rescaling() -> None:
+    assert Price("10.037").round_to_tick(Decimal("0.05")) == Price("10.05")
+    assert Price("10.037").round_to_tick("0.05", RoundingMode.FLOOR) == Price("10.00")
+    assert Price("10.10").is_on_tick("0.05")
+    assert not Price("10.11").is_on_tick("0.05")
+    assert str(Price("2.675").rescale(2)) == "2.68"
+    assert str(Price("2.665").rescale(2)) == "2.66"
+    assert str(Price("1.500").normalize()) == "1.5"
+
+
+def test_conversions_and_pickle() -> None:
+    price = Price("-7.90")
+    assert price.to_decimal() == Decimal("-7.90")
+    assert str(price.to_decimal()) == "-7.90"
+    assert int(price) == -7
+    assert float(price) == -7.9
+    assert not Quantity(0)
+    restored = pickle.loads(pickle.dumps(price))
+    assert restored == price
+    assert restored.scale == 2
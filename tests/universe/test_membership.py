@@ -0,0 +1,172 @@
+"""Test the point-in-time membership store and the historical index universe."""
+
+from __future__ import annotations
+
+import random
+from datetime import UTC, date, datetime, timedelta
+from decimal import Decimal
+from pathlib import Path
+from typing import Any
+from zoneinfo import ZoneInfo
+
+import pytest
+
+from simulor.types.instruments import Instrument
+from simulor.types.orders import Fill
+
+native = pytest.importorskip("_simulor_rust")
+
+NY = ZoneInfo("America/New_York")
+AAPL = Instrument.stock("AAPL")
+LEH = Instrument.stock("LEH")
+META = Instrument.stock("META")
+
+
+def at(day: date, hour: int = 12) -> datetime:
+    return datetime(day.year, day.month, day.day, hour, tzinfo=NY)
+
+
+def symbols(instruments: list[Instrument]) -> list[str]:
+    return [instrument.symbol for instrument in instruments]
+
+
+@pytest.fixture
+def store() -> Any:
+    return native.MembershipStore(
+        [
+            ("AAPL", date(1982, 11, 30), None),
+            (LEH, date(1994, 5, 31), date(2008, 9, 16)),
+            ("FB", date(2013, 12, 23), date(2022, 6, 9)),
+            ("META", date(2022, 6, 9), None),
+        ],
+        timezone="America/New_York",
+    )
+
+
+def test_members_as_of(store: Any) -> None:
+    assert store.timezone_info == NY
+    assert symbols(store.members(at(date(2008, 9, 15)))) == ["AAPL", "LEH"]
+    # Membership ends at midnight starting the removal date, in the store's zone
+    assert symbols(store.members(datetime(2008, 9, 16, 3, 59, tzinfo=UTC))) == ["AAPL", "LEH"]
+    assert symbols(store.members(datetime(2008, 9, 16, 4, tzinfo=UTC))) == ["AAPL"]
+    # A replacement takes over on the same day, never alongside
+    assert symbols(store.members(at(date(2022, 6, 8)))) == ["AAPL", "FB"]
+    assert symbols(store.members(at(date(2022, 6, 9), 0))) == ["AAPL", "META"]
+    assert store.members(at(date(1980, 1, 1))) == []
+
+    assert store.is_member(LEH, at(date(2000, 1, 3)))
+    assert not store.is_member("LEH", at(date(2010, 1, 4)))
+    assert not store.is_member("IBM", at(date(2010, 1, 4)))
+    # Removed and delisted names are kept
+    assert len(store) == 4
+    assert LEH in store.instruments()
+
+
+def test_overlapping_intervals_merge() -> None:
+    store = native.MembershipStore()
+    store.add(AAPL, date(2000, 1, 1), date(2001, 1, 1))
+    store.add("AAPL", "2000-06-01", "2002-01-01")
+    store.add(AAPL, date(2002, 1, 1), date(2003, 1, 1))
+    store.add(AAPL, date(2005, 1, 1))
+    assert store.intervals(AAPL) == [
+        (AAPL, date(2000, 1, 1), date(2003, 1, 1)),
+        (AAPL, date(2005, 1, 1), None),
+    ]
+    assert not store.is_member(AAPL, datetime(2004, 1, 1, tzinfo=UTC))
+    assert store.intervals("IBM") == []
+    assert repr(store) == "MembershipStore(instruments=1, intervals=2)"
+
+    with pytest.raises(ValueError, match="must end after it starts"):
+        store.add(AAPL, date(2000, 1, 1), date(2000, 1, 1))
+
+
+def test_many_changes_match_interval_checks() -> None:
+    rng = random.Random(7)
+    store = native.MembershipStore()
+    instruments = [Instrument.stock(f"S{i:03}") for i in range(150)]
+    start = date(2000, 1, 1)
+    for instrument in instruments:
+        first = start + timedelta(days=rng.randrange(3000))
+        end = None if rng.random() < 0.3 else first + timedelta(days=rng.randrange(1, 2000))
+        store.add(instrument, first, end)
+
+    for _ in range(60):
+        when = datetime(2000, 1, 1, tzinfo=UTC) + timedelta(days=rng.randrange(5500), hours=rng.randrange(24))
+        expected = {instrument for instrument in instruments if store.is_member(instrument, when)}
+        members = store.members(when)
+        assert set(members) == expected
+        assert len(members) == len(expected)
+
+
+def test_loads_csv(tmp_path: Path) -> None:
+    path = tmp_path / "members.csv"
+    path.write_text("symbol,start,end\nAAPL,1982-11-30,\nLEH,1994-05-31,2008-09-16\n")
+    store = native.MembershipStore.from_csv(path, timezone="America/New_York")
+    assert store.intervals() == [(AAPL, date(1982, 11, 30), None), (LEH, date(1994, 5, 31), date(2008, 9, 16))]
+
+    path.write_text("symbol,start,end\nAAPL,1982-11-30,\nLEH,1994-05-31,16/09/2008\n")
+    with pytest.raises(ValueError, match="line 3: invalid end date '16/09/2008'"):
+        native.MembershipStore.from_csv(path)
+    path.write_text("symbol,start\n")
+    with pytest.raises(ValueError, match="missing required column 'end'"):
+        native.MembershipStore.from_csv(path)
+
+
+def test_loads_parquet(tmp_path: Path) -> None:
+    pa = pytest.importorskip("pyarrow")
+    pq = pytest.importorskip("pyarrow.parquet")
+    path = tmp_path / "members.parquet"
+    table = pa.table(
+        {
+            "symbol": ["AAPL", "LEH", "FB"],
+            "start": pa.array([date(1982, 11, 30), date(1994, 5, 31), date(2013, 12, 23)], pa.date32()),
+            "end": pa.array(
+                [None, datetime(2008, 9, 16, 4, tzinfo=UTC), datetime(2022, 6, 9, 13, tzinfo=UTC)],
+                pa.timestamp("us", tz="UTC"),
+            ),
+        }
+    )
+    pq.write_table(table, path)
+    store = native.MembershipStore.from_parquet(path, timezone="America/New_York")
+    assert [end for _, _, end in store.intervals()] == [None, date(2008, 9, 16), date(2022, 6, 9)]
+
+
+def universe(store: Any, **kwargs: Any) -> tuple[Any, Any]:
+    from simulor.core.protocols import Context
+    from simulor.portfolio.manager import Portfolio
+    from simulor.universe.historical import HistoricalIndexUniverse
+
+    portfolio = Portfolio(Decimal(100_000))
+    context = Context(market_store=None, portfolio=portfolio, event_bus=None)  # type: ignore[arg-type]
+    model = HistoricalIndexUniverse(store, **kwargs)
+    model.set_context(context)
+    return model, context
+
+
+def test_universe_refreshes_on_rebalance(store: Any) -> None:
+    model, context = universe(store, rebalance="monthly", include_held=False)
+    context.time = at(date(2022, 6, 1))
+    assert symbols(model.select_universe()) == ["AAPL", "FB"]
+    # The symbol change on June 9 is only seen at the next rebalance
+    context.time = at(date(2022, 6, 30))
+    assert symbols(model.select_universe()) == ["AAPL", "FB"]
+    context.time = at(date(2022, 7, 1))
+    assert symbols(model.select_universe()) == ["AAPL", "META"]
+    assert symbols(model.members) == ["AAPL", "META"]
+
+    dated, context = universe(store, rebalance=[date(2022, 6, 10)], include_held=False)
+    context.time = at(date(2022, 6, 1))
+    assert symbols(dated.select_universe()) == ["AAPL", "FB"]
+    context.time = at(date(2022, 6, 10))
+    assert symbols(dated.select_universe()) == ["AAPL", "META"]
+
+    with pytest.raises(ValueError, match="Unknown rebalance period"):
+        universe(store, rebalance="yearly")
+
+
+def test_universe_keeps_held_instruments(store: Any) -> None:
+    model, context = universe(store, rebalance="daily")
+    context.portfolio.update_position(Fill(instrument=LEH, quantity=Decimal(10), price=Decimal(20)))
+    context.time = at(date(2008, 9, 17))
+    assert symbols(model.select_universe()) == ["AAPL", "LEH"]
+    assert symbols(model.members) == ["AAPL"]